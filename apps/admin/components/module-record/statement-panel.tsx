@@ -171,7 +171,17 @@ export function StatementPanel({
                     </div>
                     <p className="text-foreground text-xs">{dateLabel}</p>
                     <p className="text-right text-sm tabular-nums">
-                      {formatCurrency(line.amount_pyg, currency, locale)}
+                      {formatCurrency(line.amount, currency, locale)}
+                      {line.original_currency &&
+                      line.original_amount !== undefined ? (
+                        <span className="block text-muted-foreground text-xs">
+                          {formatCurrency(
+                            line.original_amount,
+                            line.original_currency,
+                            locale
+                          )}
+                        </span>
+                      ) : null}
                     </p>
                   </div>
                 );
@@ -20,9 +20,9 @@ export function toStatementLineItems(value: unknown): StatementLineItem[] {
     const source_id =
       typeof record.source_id === "string" ? record.source_id : "";
     const kind = typeof record.kind === "string" ? record.kind : "";
-    const amount_pyg = toNumber(record.amount_pyg);
+    const amount = toNumber(record.amount ?? record.amount_pyg);
 
-    if (!(bucket && source_table && source_id && kind) || amount_pyg === null) {
+    if (!(bucket && source_table && source_id && kind) || amount === null) {
       continue;
     }
 
@@ -31,7 +31,12 @@ export function toStatementLineItems(value: unknown): StatementLineItem[] {
       source_table,
       source_id,
       kind,
-      amount_pyg,
+      amount,
+      original_amount: toNumber(record.original_amount) ?? undefined,
+      original_currency:
+        typeof record.original_currency === "string"
+          ? record.original_currency
+          : undefined,
       date: typeof record.date === "string" ? record.date : undefined,
       from: typeof record.from === "string" ? record.from : undefined,
       to: typeof record.to === "string" ? record.to : undefined,
@@ -32,7 +32,9 @@ export type StatementLineItem = {
   source_table: string;
   source_id: string;
   kind: string;
-  amount_pyg: number;
+  amount: number;
+  original_amount?: number;
+  original_currency?: string;
   date?: string;
   from?: string;
   to?: string;
@@ -6,7 +6,7 @@ use serde::Deserialize;
 use serde_json::{json, Value};
 use sqlx::Row;
 
-use crate::{error::AppError, state::AppState};
+use crate::{error::AppError, services::api_keys, state::AppState};
 
 /// Compatibility auth user payload used across handlers/tenancy.
 /// `id` remains the canonical app user UUID in Casaora.
@@ -117,7 +117,11 @@ fn header_string(headers: &HeaderMap, key: &str) -> Option<String> {
 }
 
 /// Validate Clerk JWTs via JWKS and map them to internal app users.
+/// Organization API keys resolve to the key's service user.
 async fn resolve_user(state: &AppState, token: &str) -> Option<AuthenticatedUser> {
+    if api_keys::is_api_key_token(token) {
+        return api_keys::resolve_token(state.db_pool.as_ref()?, token).await;
+    }
     validate_clerk_jwt_with_jwks(state, token).await
 }
 
@@ -111,11 +111,13 @@ pub struct AppConfig {
     pub whatsapp_phone_number_id: Option<String>,
     pub whatsapp_access_token: Option<String>,
     pub whatsapp_verify_token: Option<String>,
+    pub whatsapp_app_secret: Option<String>,
     pub resend_api_key: Option<String>,
     pub email_from_address: String,
     pub stripe_secret_key: Option<String>,
     pub stripe_webhook_secret: Option<String>,
     pub stripe_trial_days: i32,
+    pub sifen_key_encryption_key: Option<String>,
     pub twilio_account_sid: Option<String>,
     pub twilio_auth_token: Option<String>,
     pub twilio_phone_number: Option<String>,
@@ -128,8 +130,10 @@ pub struct AppConfig {
     pub workflow_engine_mode: WorkflowEngineMode,
     pub scheduler_enabled: bool,
     pub ical_sync_interval_minutes: u64,
+    pub ical_sync_concurrency: usize,
     pub workflow_poll_interval_seconds: u64,
     pub message_poll_interval_seconds: u64,
+    pub scheduler_run_retention_days: i64,
 }
 
 impl AppConfig {
@@ -261,11 +265,13 @@ impl AppConfig {
             whatsapp_phone_number_id: env_opt("WHATSAPP_PHONE_NUMBER_ID"),
             whatsapp_access_token: env_opt("WHATSAPP_ACCESS_TOKEN"),
             whatsapp_verify_token: env_opt("WHATSAPP_VERIFY_TOKEN"),
+            whatsapp_app_secret: env_opt("WHATSAPP_APP_SECRET"),
             resend_api_key: env_opt("RESEND_API_KEY"),
             email_from_address: env_or("EMAIL_FROM_ADDRESS", "noreply@casaora.co"),
             stripe_secret_key: env_opt("STRIPE_SECRET_KEY"),
             stripe_webhook_secret: env_opt("STRIPE_WEBHOOK_SECRET"),
             stripe_trial_days: env_parse_or("STRIPE_TRIAL_DAYS", 14),
+            sifen_key_encryption_key: env_opt("SIFEN_KEY_ENCRYPTION_KEY"),
             twilio_account_sid: env_opt("TWILIO_ACCOUNT_SID"),
             twilio_auth_token: env_opt("TWILIO_AUTH_TOKEN"),
             twilio_phone_number: env_opt("TWILIO_PHONE_NUMBER"),
@@ -278,8 +284,10 @@ impl AppConfig {
             workflow_engine_mode: WorkflowEngineMode::from_env(env_opt("WORKFLOW_ENGINE_MODE")),
             scheduler_enabled: env_parse_bool_or("SCHEDULER_ENABLED", false),
             ical_sync_interval_minutes: env_parse_or("ICAL_SYNC_INTERVAL_MINUTES", 30),
+            ical_sync_concurrency: env_parse_or("ICAL_SYNC_CONCURRENCY", 4),
             workflow_poll_interval_seconds: env_parse_or("WORKFLOW_POLL_INTERVAL_SECONDS", 300),
             message_poll_interval_seconds: env_parse_or("MESSAGE_POLL_INTERVAL_SECONDS", 30),
+            scheduler_run_retention_days: env_parse_or("SCHEDULER_RUN_RETENTION_DAYS", 30),
         }
     }
 
@@ -224,6 +224,18 @@ fn classify_database_error(message: &str) -> Option<ClassifiedDbError> {
         });
     }
 
+    if normalized.contains("unit_occupancy_no_overlap")
+        || normalized.contains("reservations_no_overlap")
+        || normalized.contains("calendar_blocks_no_overlap")
+    {
+        return Some(ClassifiedDbError {
+            code: "availability_conflict",
+            status: StatusCode::CONFLICT,
+            retryable: false,
+            detail: "Selected dates overlap with an existing reservation or calendar block.",
+        });
+    }
+
     if normalized.contains("timed out")
         || normalized.contains("timeout")
         || normalized.contains("connection refused")
@@ -14,6 +14,8 @@ mod schemas;
 mod services;
 mod state;
 mod tenancy;
+#[cfg(test)]
+mod test_support;
 
 use std::net::SocketAddr;
 use std::time::Duration;
@@ -65,6 +67,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         );
     }
 
+    if state.config.whatsapp_phone_number_id.is_some()
+        && state
+            .config
+            .whatsapp_app_secret
+            .as_deref()
+            .is_none_or(str::is_empty)
+    {
+        tracing::warn!(
+            "WhatsApp is configured without WHATSAPP_APP_SECRET; incoming WhatsApp webhooks will be rejected"
+        );
+    }
+
+    if state.config.stripe_secret_key.is_some()
+        && state
+            .config
+            .stripe_webhook_secret
+            .as_deref()
+            .is_none_or(str::is_empty)
+    {
+        tracing::warn!(
+            "Stripe is configured without STRIPE_WEBHOOK_SECRET; incoming Stripe webhooks will be rejected"
+        );
+    }
+
     let mut app = Router::new()
         .nest(&state.config.api_prefix, routes::v1_router())
         .layer(DefaultBodyLimit::max(2 * 1024 * 1024)) // 2 MB
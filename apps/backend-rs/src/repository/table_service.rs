@@ -14,33 +14,55 @@ const ALLOWED_TABLES: &[&str] = &[
     "application_events",
     "application_submissions",
     "audit_logs",
+    "bank_import_profiles",
+    "bank_statement_imports",
     "calendar_blocks",
     "collection_records",
+    "electronic_documents",
     "expenses",
+    "gl_accounts",
+    "gl_journal_entries",
+    "gl_journal_lines",
     "guests",
     "integration_events",
+    "late_fee_policies",
     "lease_charges",
+    "lease_deposit_deductions",
+    "lease_deposits",
+    "lease_payments",
     "leases",
     "integrations",
     "listing_fee_lines",
     "listings",
     "maintenance_requests",
+    "management_fee_schedules",
     "message_logs",
     "message_templates",
     "notification_rules",
     "notification_events",
     "user_notifications",
     "notification_rule_dispatches",
+    "organization_api_keys",
     "organization_invites",
     "organization_members",
+    "organization_roles",
     "organizations",
+    "owner_contributions",
+    "owner_payout_batches",
+    "owner_payout_items",
+    "owner_reserve_entries",
     "owner_statements",
+    "payment_allocations",
     "payment_instructions",
+    "payout_bank_layouts",
     "pricing_template_lines",
     "pricing_templates",
     "properties",
+    "property_group_members",
+    "property_groups",
     "property_floors",
     "reservations",
+    "sifen_settings",
     "unit_beds",
     "unit_condition_events",
     "unit_spaces",
@@ -48,6 +70,9 @@ const ALLOWED_TABLES: &[&str] = &[
     "tasks",
     "tenant_access_tokens",
     "units",
+    "webhook_deliveries",
+    "webhook_signing_secrets",
+    "webhook_subscriptions",
     "documents",
     "knowledge_documents",
     "knowledge_chunks",
@@ -61,6 +86,7 @@ const ALLOWED_TABLES: &[&str] = &[
     "communication_sequences",
     "sequence_steps",
     "sequence_enrollments",
+    "stay_restrictions",
 ];
 
 pub async fn list_rows(
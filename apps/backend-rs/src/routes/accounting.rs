@@ -0,0 +1,578 @@
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+        HeaderMap, HeaderValue, Response, StatusCode,
+    },
+    response::IntoResponse,
+    Json,
+};
+use chrono::{NaiveDate, Utc};
+use serde_json::{json, Map, Value};
+use sqlx::Row;
+
+use crate::{
+    auth::require_user_id,
+    error::{AppError, AppResult},
+    repository::table_service::{create_row, get_row},
+    schemas::{
+        clamp_limit, AccountingExportPath, AccountingExportQuery, CreateGlAccountInput,
+        CreateJournalEntryInput, GlAccountsQuery, JournalEntriesQuery, JournalEntryPath,
+        LedgerReportQuery, OwnerLedgerQuery,
+    },
+    services::{
+        accounting_exports::{self, ExportFormat},
+        audit::write_audit_log,
+        fx,
+        general_ledger::{self, AccountRef, AccountType, JournalLine, NewEntry},
+        permissions,
+    },
+    state::AppState,
+    tenancy::{assert_org_member, assert_permission},
+};
+
+pub fn router() -> axum::Router<AppState> {
+    axum::Router::new()
+        .route(
+            "/accounting/accounts",
+            axum::routing::get(list_accounts).post(create_account),
+        )
+        .route(
+            "/accounting/journal",
+            axum::routing::get(list_journal_entries).post(create_journal_entry),
+        )
+        .route(
+            "/accounting/journal/{entry_id}/reverse",
+            axum::routing::post(reverse_journal_entry),
+        )
+        .route(
+            "/accounting/trial-balance",
+            axum::routing::get(get_trial_balance),
+        )
+        .route(
+            "/accounting/balance-sheet",
+            axum::routing::get(get_balance_sheet),
+        )
+        .route(
+            "/accounting/owner-ledger",
+            axum::routing::get(get_owner_ledger),
+        )
+        .route(
+            "/accounting/exports/{format}",
+            axum::routing::get(export_accounting_file),
+        )
+}
+
+/// Chart of accounts, seeding the system accounts on first access.
+async fn list_accounts(
+    State(state): State<AppState>,
+    Query(query): Query<GlAccountsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let mut conn = pool
+        .acquire()
+        .await
+        .map_err(|e| AppError::Dependency(format!("db acquire: {e}")))?;
+    general_ledger::ensure_chart_of_accounts(&mut conn, &query.org_id).await?;
+
+    let account_type = match non_empty_opt(query.account_type.as_deref()) {
+        Some(value) => Some(parse_account_type(&value)?.as_str()),
+        None => None,
+    };
+    let rows = sqlx::query(
+        "SELECT row_to_json(a) AS row
+         FROM gl_accounts a
+         WHERE a.organization_id = $1::uuid
+           AND ($2::text IS NULL OR a.account_type = $2)
+         ORDER BY a.code",
+    )
+    .bind(&query.org_id)
+    .bind(account_type)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load accounts."))?;
+
+    let data: Vec<Value> = rows
+        .iter()
+        .filter_map(|row| row.try_get::<Option<Value>, _>("row").ok().flatten())
+        .collect();
+    Ok(Json(json!({ "data": data })))
+}
+
+async fn create_account(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateGlAccountInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::LEDGER_EDIT,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let code = payload.code.trim();
+    let name = payload.name.trim();
+    if code.is_empty() || name.is_empty() {
+        return Err(AppError::BadRequest(
+            "code and name are required.".to_string(),
+        ));
+    }
+    let account_type = parse_account_type(&payload.account_type)?;
+
+    let mut record = Map::new();
+    record.insert(
+        "organization_id".to_string(),
+        Value::String(payload.organization_id.clone()),
+    );
+    record.insert("code".to_string(), Value::String(code.to_string()));
+    record.insert("name".to_string(), Value::String(name.to_string()));
+    record.insert(
+        "account_type".to_string(),
+        Value::String(account_type.as_str().to_string()),
+    );
+    let created = create_row(pool, "gl_accounts", &record).await?;
+
+    write_audit_log(
+        Some(pool),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        "create",
+        "gl_accounts",
+        created.get("id").and_then(Value::as_str),
+        None,
+        Some(created.clone()),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+async fn list_journal_entries(
+    State(state): State<AppState>,
+    Query(query): Query<JournalEntriesQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let from = parse_date(query.from.as_deref(), "from")?;
+    let to = parse_date(query.to.as_deref(), "to")?;
+    let entries = sqlx::query(
+        "SELECT row_to_json(e) AS row, e.id::text AS id
+         FROM gl_journal_entries e
+         WHERE e.organization_id = $1::uuid
+           AND ($2::date IS NULL OR e.entry_date >= $2::date)
+           AND ($3::date IS NULL OR e.entry_date <= $3::date)
+           AND ($4::text IS NULL OR e.source_type = $4)
+           AND ($5::text IS NULL OR e.source_id::text = $5)
+         ORDER BY e.entry_date DESC, e.created_at DESC
+         LIMIT $6",
+    )
+    .bind(&query.org_id)
+    .bind(from)
+    .bind(to)
+    .bind(non_empty_opt(query.source_type.as_deref()))
+    .bind(non_empty_opt(query.source_id.as_deref()))
+    .bind(clamp_limit(query.limit))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load journal entries."))?;
+
+    let entry_ids: Vec<String> = entries
+        .iter()
+        .map(|row| row.try_get("id").unwrap_or_default())
+        .collect();
+    let lines = sqlx::query(
+        "SELECT l.entry_id::text AS entry_id,
+                json_build_object(
+                  'id', l.id, 'account_id', l.account_id, 'account_code', a.code,
+                  'account_name', a.name, 'debit', l.debit, 'credit', l.credit,
+                  'property_id', l.property_id, 'lease_id', l.lease_id, 'memo', l.memo
+                ) AS line
+         FROM gl_journal_lines l
+         JOIN gl_accounts a ON a.id = l.account_id
+         WHERE l.entry_id::text = ANY($1)
+         ORDER BY l.created_at, l.debit DESC",
+    )
+    .bind(&entry_ids)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load journal lines."))?;
+
+    let mut lines_by_entry: Map<String, Value> = Map::new();
+    for row in &lines {
+        let entry_id: String = row.try_get("entry_id").unwrap_or_default();
+        let line: Value = row.try_get("line").unwrap_or(Value::Null);
+        if let Some(list) = lines_by_entry
+            .entry(entry_id)
+            .or_insert_with(|| Value::Array(Vec::new()))
+            .as_array_mut()
+        {
+            list.push(line);
+        }
+    }
+
+    let data: Vec<Value> = entries
+        .iter()
+        .filter_map(|row| {
+            let id: String = row.try_get("id").unwrap_or_default();
+            let mut entry = row.try_get::<Option<Value>, _>("row").ok().flatten()?;
+            if let Some(obj) = entry.as_object_mut() {
+                obj.insert(
+                    "lines".to_string(),
+                    lines_by_entry
+                        .remove(&id)
+                        .unwrap_or_else(|| Value::Array(Vec::new())),
+                );
+            }
+            Some(entry)
+        })
+        .collect();
+    Ok(Json(json!({ "data": data })))
+}
+
+/// Post a manual balanced entry, e.g. an opening balance or a bank fee.
+async fn create_journal_entry(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateJournalEntryInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::LEDGER_EDIT,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let description = payload.description.trim();
+    if description.is_empty() {
+        return Err(AppError::BadRequest("description is required.".to_string()));
+    }
+    let entry_date = parse_date(Some(&payload.entry_date), "entry_date")?
+        .ok_or_else(|| AppError::BadRequest("entry_date is required.".to_string()))?;
+    let currency = match non_empty_opt(payload.currency.as_deref()) {
+        Some(currency) => fx::normalize_currency(&currency).ok_or_else(|| {
+            AppError::BadRequest("currency must be a 3-letter ISO 4217 code.".to_string())
+        })?,
+        None => "PYG".to_string(),
+    };
+
+    let lines: Vec<JournalLine> = payload
+        .lines
+        .iter()
+        .map(|line| {
+            let mut journal_line = JournalLine::new(
+                AccountRef::Id(line.account_id.clone()),
+                line.debit,
+                line.credit,
+            )
+            .property(line.property_id.as_deref())
+            .lease(line.lease_id.as_deref());
+            journal_line.memo = non_empty_opt(line.memo.as_deref());
+            journal_line
+        })
+        .collect();
+    general_ledger::validate_lines(&lines)?;
+
+    let entry_id = general_ledger::post_entry(
+        pool,
+        &NewEntry {
+            organization_id: payload.organization_id.clone(),
+            entry_date,
+            description: description.to_string(),
+            source_type: "manual".to_string(),
+            source_id: None,
+            source_key: None,
+            currency,
+            created_by_user_id: Some(user_id.clone()),
+            lines,
+        },
+    )
+    .await?
+    .ok_or_else(|| AppError::Internal("Journal entry was not posted.".to_string()))?;
+    let entry = get_row(pool, "gl_journal_entries", &entry_id, "id").await?;
+
+    write_audit_log(
+        Some(pool),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        "create",
+        "gl_journal_entries",
+        Some(&entry_id),
+        None,
+        Some(json!({ "entry": entry, "lines": payload.lines })),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+async fn reverse_journal_entry(
+    State(state): State<AppState>,
+    Path(path): Path<JournalEntryPath>,
+    headers: HeaderMap,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let entry = get_row(pool, "gl_journal_entries", &path.entry_id, "id").await?;
+    let org_id = value_str(&entry, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::LEDGER_EDIT).await?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Dependency(format!("txn begin: {e}")))?;
+    let reversal_id = general_ledger::reverse_entry_in(
+        &mut tx,
+        &org_id,
+        &path.entry_id,
+        Utc::now().date_naive(),
+        Some(&user_id),
+    )
+    .await?;
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Dependency(format!("txn commit: {e}")))?;
+    let reversal = get_row(pool, "gl_journal_entries", &reversal_id, "id").await?;
+
+    write_audit_log(
+        Some(pool),
+        Some(&org_id),
+        Some(&user_id),
+        "reverse",
+        "gl_journal_entries",
+        Some(&path.entry_id),
+        Some(entry),
+        Some(reversal.clone()),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(reversal)))
+}
+
+async fn get_trial_balance(
+    State(state): State<AppState>,
+    Query(query): Query<LedgerReportQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let as_of =
+        parse_date(query.as_of.as_deref(), "as_of")?.unwrap_or_else(|| Utc::now().date_naive());
+    let totals = general_ledger::account_totals(pool, &query.org_id, Some(as_of)).await?;
+    Ok(Json(json!({
+        "organization_id": query.org_id,
+        "as_of": as_of.to_string(),
+        "currencies": general_ledger::trial_balance(&totals),
+    })))
+}
+
+async fn get_balance_sheet(
+    State(state): State<AppState>,
+    Query(query): Query<LedgerReportQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let as_of =
+        parse_date(query.as_of.as_deref(), "as_of")?.unwrap_or_else(|| Utc::now().date_naive());
+    let totals = general_ledger::account_totals(pool, &query.org_id, Some(as_of)).await?;
+    Ok(Json(json!({
+        "organization_id": query.org_id,
+        "as_of": as_of.to_string(),
+        "currencies": general_ledger::balance_sheet(&totals),
+    })))
+}
+
+/// Owner-payable activity for one property or every property of an owner.
+async fn get_owner_ledger(
+    State(state): State<AppState>,
+    Query(query): Query<OwnerLedgerQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let from = parse_date(query.from.as_deref(), "from")?;
+    let to = parse_date(query.to.as_deref(), "to")?;
+    let property_id = non_empty_opt(query.property_id.as_deref());
+    let owner_name = non_empty_opt(query.owner_name.as_deref());
+    if property_id.is_none() && owner_name.is_none() {
+        return Err(AppError::BadRequest(
+            "property_id or owner_name is required.".to_string(),
+        ));
+    }
+
+    let property_ids: Vec<String> = sqlx::query_scalar(
+        "SELECT id::text FROM properties
+         WHERE organization_id = $1::uuid
+           AND ($2::text IS NULL OR id::text = $2)
+           AND ($3::text IS NULL OR lower(asset_owner_name) = lower($3))
+         ORDER BY name",
+    )
+    .bind(&query.org_id)
+    .bind(property_id.as_deref())
+    .bind(owner_name.as_deref())
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load properties."))?;
+    if property_ids.is_empty() {
+        return Err(AppError::NotFound(
+            "No properties match this owner.".to_string(),
+        ));
+    }
+
+    let mut ledger =
+        general_ledger::owner_ledger(pool, &query.org_id, &property_ids, from, to).await?;
+    if let Some(obj) = ledger.as_object_mut() {
+        obj.insert("owner_name".to_string(), json!(owner_name));
+    }
+    Ok(Json(ledger))
+}
+
+/// Download a period as a QuickBooks or Xero journal import, or as the
+/// SET Libro de Ventas / Libro de Compras.
+async fn export_accounting_file(
+    State(state): State<AppState>,
+    Path(path): Path<AccountingExportPath>,
+    Query(query): Query<AccountingExportQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response<Body>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(&state, &user_id, &query.org_id, permissions::LEDGER_EDIT).await?;
+    let pool = db_pool(&state)?;
+
+    let format = ExportFormat::parse(&path.format).ok_or_else(|| {
+        AppError::BadRequest(
+            "format must be one of quickbooks_iif, quickbooks_csv, xero_csv, set_sales, set_purchases."
+                .to_string(),
+        )
+    })?;
+    let from = parse_date(Some(&query.from), "from")?
+        .ok_or_else(|| AppError::BadRequest("from is required.".to_string()))?;
+    let to = parse_date(Some(&query.to), "to")?
+        .ok_or_else(|| AppError::BadRequest("to is required.".to_string()))?;
+    if to < from {
+        return Err(AppError::BadRequest(
+            "to must be on or after from.".to_string(),
+        ));
+    }
+
+    let issuer = accounting_exports::load_issuer(pool, &query.org_id).await?;
+    let body = match format {
+        ExportFormat::QuickbooksIif | ExportFormat::QuickbooksCsv | ExportFormat::XeroCsv => {
+            let currency =
+                fx::reporting_currency(pool, &query.org_id, None, None, query.currency.as_deref())
+                    .await?;
+            let entries =
+                accounting_exports::load_journal(pool, &query.org_id, &currency, from, to).await?;
+            match format {
+                ExportFormat::QuickbooksIif => accounting_exports::render_iif(&entries),
+                ExportFormat::QuickbooksCsv => accounting_exports::render_quickbooks_csv(&entries),
+                _ => accounting_exports::render_xero_csv(&entries),
+            }
+        }
+        ExportFormat::SetSales => {
+            let rows =
+                accounting_exports::load_sales_book(pool, &query.org_id, &issuer, from, to).await?;
+            accounting_exports::render_set_book("Libro de Ventas", &issuer, from, to, &rows)
+        }
+        ExportFormat::SetPurchases => {
+            let rows =
+                accounting_exports::load_purchases_book(pool, &query.org_id, from, to).await?;
+            accounting_exports::render_set_book("Libro de Compras", &issuer, from, to, &rows)
+        }
+    };
+
+    write_audit_log(
+        Some(pool),
+        Some(&query.org_id),
+        Some(&user_id),
+        "export",
+        "gl_journal_entries",
+        None,
+        None,
+        Some(json!({
+            "format": format.as_str(),
+            "from": from.to_string(),
+            "to": to.to_string(),
+        })),
+    )
+    .await;
+
+    let file_name = format.file_name(&issuer.ruc, from, to);
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .body(Body::from(body))
+        .map_err(|error| {
+            tracing::error!(error = %error, "Could not build export response");
+            AppError::Internal("Could not build export response.".to_string())
+        })?;
+    let headers = response.headers_mut();
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static(format.content_type()),
+    );
+    if let Ok(value) = HeaderValue::from_str(&format!("attachment; filename=\"{file_name}\"")) {
+        headers.insert(CONTENT_DISPOSITION, value);
+    }
+    Ok(response)
+}
+
+fn parse_account_type(value: &str) -> AppResult<AccountType> {
+    AccountType::parse(value).ok_or_else(|| {
+        AppError::BadRequest(
+            "account_type must be one of asset, liability, equity, revenue, expense.".to_string(),
+        )
+    })
+}
+
+fn parse_date(value: Option<&str>, field: &str) -> AppResult<Option<NaiveDate>> {
+    match non_empty_opt(value) {
+        Some(value) => NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| AppError::BadRequest(format!("{field} must be a YYYY-MM-DD date."))),
+        None => Ok(None),
+    }
+}
+
+fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
+    state
+        .db_pool
+        .as_ref()
+        .ok_or_else(|| AppError::Dependency("Database is not configured.".to_string()))
+}
+
+fn value_str(row: &Value, key: &str) -> String {
+    row.as_object()
+        .and_then(|obj| obj.get(key))
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+fn non_empty_opt(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+}
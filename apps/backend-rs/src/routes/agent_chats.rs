@@ -21,9 +21,10 @@ use crate::{
         agent_runs::{self, AgentRunMode, CreateAgentRunParams},
         agent_runtime_v2::{inject_runtime_metadata, wrap_stream_event},
         audit::write_audit_log,
+        permissions,
     },
     state::AppState,
-    tenancy::assert_org_member,
+    tenancy::assert_org_scope,
 };
 
 #[derive(Debug, Clone, Deserialize)]
@@ -160,7 +161,7 @@ async fn get_agent_definitions(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let data = agent_chats::list_agents(&state, &query.org_id).await?;
     Ok(Json(serde_json::json!({
@@ -175,7 +176,7 @@ async fn get_agent_chats(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let data =
         agent_chats::list_chats(&state, &query.org_id, &user_id, query.archived, query.limit)
@@ -194,7 +195,7 @@ async fn get_agent_models(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let data = agent_chats::list_models(&state);
     Ok(Json(serde_json::json!({
@@ -209,7 +210,7 @@ async fn create_agent_chat(
     Json(payload): Json<CreateAgentChatInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &payload.org_id).await?;
+    assert_org_scope(&state, &user_id, &payload.org_id, "agents").await?;
 
     let chat = agent_chats::create_chat(
         &state,
@@ -248,7 +249,7 @@ async fn get_agent_chat(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let chat = agent_chats::get_chat(&state, &path.chat_id, &query.org_id, &user_id).await?;
     Ok(Json(chat))
@@ -261,7 +262,7 @@ async fn get_agent_chat_messages(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let data = agent_chats::list_chat_messages(
         &state,
@@ -287,7 +288,7 @@ async fn update_chat_preferences(
     Json(payload): Json<UpdateChatPreferencesInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let chat = agent_chats::update_chat_preferences(
         &state,
@@ -313,15 +314,8 @@ async fn post_agent_chat_message(
     Json(payload): Json<SendAgentMessageInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    let membership = assert_org_member(&state, &user_id, &query.org_id).await?;
-    let role = membership
-        .as_object()
-        .and_then(|obj| obj.get("role"))
-        .and_then(Value::as_str)
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-        .unwrap_or("viewer")
-        .to_string();
+    let membership = assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
+    let role = permissions::agent_role(&membership);
 
     let chat = agent_chats::get_chat(&state, &path.chat_id, &query.org_id, &user_id).await?;
     let agent_slug = value_str(&chat, "agent_slug").unwrap_or_else(|| "supervisor".to_string());
@@ -430,15 +424,8 @@ async fn post_agent_chat_message_stream(
     Json(payload): Json<SendAgentMessageInput>,
 ) -> AppResult<Sse<impl futures_core::Stream<Item = Result<Event, Infallible>>>> {
     let user_id = require_user_id(&state, &headers).await?;
-    let membership = assert_org_member(&state, &user_id, &query.org_id).await?;
-    let role = membership
-        .as_object()
-        .and_then(|obj| obj.get("role"))
-        .and_then(Value::as_str)
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-        .unwrap_or("viewer")
-        .to_string();
+    let membership = assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
+    let role = permissions::agent_role(&membership);
 
     let chat = agent_chats::get_chat(&state, &path.chat_id, &query.org_id, &user_id).await?;
     let agent_slug = value_str(&chat, "agent_slug").unwrap_or_else(|| "supervisor".to_string());
@@ -548,7 +535,7 @@ async fn archive_agent_chat(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let chat = agent_chats::archive_chat(&state, &path.chat_id, &query.org_id, &user_id).await?;
 
@@ -583,7 +570,7 @@ async fn restore_agent_chat(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let chat = agent_chats::restore_chat(&state, &path.chat_id, &query.org_id, &user_id).await?;
 
@@ -624,7 +611,7 @@ async fn delete_agent_chat(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let deleted = agent_chats::delete_chat(&state, &path.chat_id, &query.org_id, &user_id).await?;
 
@@ -711,7 +698,7 @@ async fn get_agent_traces(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let pool = state
         .db_pool
@@ -776,7 +763,7 @@ async fn get_evaluations_summary(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let pool = state
         .db_pool
@@ -844,7 +831,7 @@ async fn post_message_feedback(
     Json(payload): Json<MessageFeedbackInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let rating = payload.rating.trim().to_string();
     if !matches!(rating.as_str(), "positive" | "negative") {
@@ -941,7 +928,7 @@ async fn get_contextual_prompts(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let pool = state
         .db_pool
@@ -1043,7 +1030,7 @@ async fn list_agent_memory(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let pool = state
         .db_pool
@@ -1087,7 +1074,7 @@ async fn delete_agent_memory(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let pool = state
         .db_pool
@@ -1115,7 +1102,7 @@ async fn list_pii_intercepts(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let pool = state
         .db_pool
@@ -1153,7 +1140,7 @@ async fn list_boundary_rules(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let pool = state
         .db_pool
@@ -1202,7 +1189,7 @@ async fn update_boundary_rule(
     Json(payload): Json<UpdateBoundaryRuleInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let pool = state
         .db_pool
@@ -1237,7 +1224,7 @@ async fn get_security_audit(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     let pool = state
         .db_pool
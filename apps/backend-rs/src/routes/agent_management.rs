@@ -12,12 +12,11 @@ use crate::{
     cache::org_key,
     error::{AppError, AppResult},
     services::agent_specs::default_max_steps_for_slug,
+    services::permissions,
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
-const AGENT_ADMIN_ROLES: &[&str] = &["owner_admin", "operator"];
-
 pub fn router() -> axum::Router<AppState> {
     axum::Router::new()
         .route("/ai-agents", axum::routing::get(list_agents))
@@ -219,7 +218,13 @@ async fn update_agent(
     Json(payload): Json<UpdateAgentInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &payload.org_id, AGENT_ADMIN_ROLES).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.org_id,
+        permissions::AGENTS_MANAGE,
+    )
+    .await?;
     let pool = db_pool(&state)?;
     let exists =
         sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM ai_agents WHERE slug = $1)")
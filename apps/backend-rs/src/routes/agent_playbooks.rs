@@ -1,18 +1,29 @@
-use axum::{extract::State, http::HeaderMap, Json};
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use chrono::Utc;
 use serde::Deserialize;
 use serde_json::{json, Map, Value};
 use sqlx::Row;
 
 use crate::{
+    auth::require_user_id,
     error::{AppError, AppResult},
     services::{
         agent_specs::{allowed_tools_for_slug, get_agent_spec},
         ai_agent::{run_ai_agent_chat, RunAiAgentChatParams},
+        cron::CronSchedule,
         notification_center::{emit_event, EmitNotificationEventInput},
     },
     state::AppState,
+    tenancy::assert_org_member,
 };
 
+const DEFAULT_PREVIEW_COUNT: usize = 10;
+const MAX_PREVIEW_COUNT: usize = 50;
+
 #[derive(Debug, Clone, Deserialize)]
 struct RunAgentPlaybookInput {
     org_id: String,
@@ -25,11 +36,187 @@ struct RunAgentPlaybookInput {
     allow_mutations: Option<bool>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+struct SchedulePreviewQuery {
+    org_id: String,
+    expression: String,
+    #[serde(default)]
+    timezone: Option<String>,
+    #[serde(default)]
+    count: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PlaybookSchedulePreviewQuery {
+    org_id: String,
+    #[serde(default)]
+    count: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PlaybookPath {
+    playbook_id: String,
+}
+
 pub fn router() -> axum::Router<AppState> {
-    axum::Router::new().route(
-        "/internal/agent-playbooks/run",
-        axum::routing::post(run_agent_playbook),
+    axum::Router::new()
+        .route(
+            "/internal/agent-playbooks/run",
+            axum::routing::post(run_agent_playbook),
+        )
+        .route(
+            "/agent-playbooks/schedule-preview",
+            axum::routing::get(preview_schedule),
+        )
+        .route(
+            "/agent-playbooks/{playbook_id}/schedule-preview",
+            axum::routing::get(preview_playbook_schedule),
+        )
+}
+
+/// Preview the next fire times for an arbitrary cron expression, so the UI can
+/// validate a schedule before saving it on a playbook.
+async fn preview_schedule(
+    State(state): State<AppState>,
+    Query(query): Query<SchedulePreviewQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let org_id = query.org_id.trim().to_string();
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &org_id).await?;
+
+    let timezone = match query
+        .timezone
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        Some(timezone) => timezone.to_string(),
+        None => organization_timezone(&state, &org_id).await?,
+    };
+
+    let preview = build_schedule_preview(query.expression.trim(), &timezone, query.count)?;
+    Ok(Json(preview))
+}
+
+/// Preview the next fire times of a saved schedule playbook, using its
+/// `trigger_conditions.cron` and `trigger_conditions.timezone` (falling back
+/// to the organization timezone).
+async fn preview_playbook_schedule(
+    State(state): State<AppState>,
+    Path(path): Path<PlaybookPath>,
+    Query(query): Query<PlaybookSchedulePreviewQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let org_id = query.org_id.trim().to_string();
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let row = sqlx::query(
+        "SELECT p.name,
+                p.trigger_type,
+                p.trigger_conditions->>'cron' AS cron_expression,
+                COALESCE(NULLIF(p.trigger_conditions->>'timezone', ''), o.timezone, 'UTC') AS timezone
+         FROM agent_playbooks p
+         JOIN organizations o ON o.id = p.organization_id
+         WHERE p.id = $1::uuid
+           AND p.organization_id = $2::uuid
+         LIMIT 1",
     )
+    .bind(&path.playbook_id)
+    .bind(&org_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|error| AppError::from_database_error(&error, "Database request failed."))?
+    .ok_or_else(|| AppError::NotFound("Playbook not found.".to_string()))?;
+
+    let expression = row
+        .try_get::<Option<String>, _>("cron_expression")
+        .ok()
+        .flatten()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| {
+            AppError::UnprocessableEntity(
+                "Playbook has no cron expression in trigger_conditions.cron.".to_string(),
+            )
+        })?;
+    let timezone = row
+        .try_get::<String, _>("timezone")
+        .unwrap_or_else(|_| "UTC".to_string());
+
+    let mut preview = build_schedule_preview(&expression, &timezone, query.count)?;
+    if let Some(obj) = preview.as_object_mut() {
+        obj.insert("playbook_id".to_string(), Value::String(path.playbook_id));
+        obj.insert(
+            "playbook_name".to_string(),
+            row.try_get::<String, _>("name")
+                .map(Value::String)
+                .unwrap_or(Value::Null),
+        );
+        obj.insert(
+            "trigger_type".to_string(),
+            row.try_get::<String, _>("trigger_type")
+                .map(Value::String)
+                .unwrap_or(Value::Null),
+        );
+    }
+    Ok(Json(preview))
+}
+
+fn build_schedule_preview(
+    expression: &str,
+    timezone: &str,
+    count: Option<usize>,
+) -> AppResult<Value> {
+    if expression.is_empty() {
+        return Err(AppError::BadRequest("expression is required.".to_string()));
+    }
+    let schedule = CronSchedule::parse_in_timezone(expression, timezone)
+        .map_err(AppError::UnprocessableEntity)?;
+    let count = count
+        .unwrap_or(DEFAULT_PREVIEW_COUNT)
+        .clamp(1, MAX_PREVIEW_COUNT);
+    let tz = schedule.timezone();
+
+    let fire_times = schedule
+        .upcoming(&Utc::now(), count)
+        .into_iter()
+        .map(|at| {
+            json!({
+                "utc": at.to_rfc3339(),
+                "local": at.with_timezone(&tz).to_rfc3339(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(json!({
+        "expression": expression,
+        "timezone": tz.name(),
+        "next_fire_times": fire_times,
+    }))
+}
+
+async fn organization_timezone(state: &AppState, org_id: &str) -> AppResult<String> {
+    let pool = db_pool(state)?;
+    let timezone = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT timezone FROM organizations WHERE id = $1::uuid LIMIT 1",
+    )
+    .bind(org_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|error| AppError::from_database_error(&error, "Database request failed."))?
+    .flatten()
+    .filter(|value| !value.trim().is_empty())
+    .unwrap_or_else(|| "UTC".to_string());
+    Ok(timezone)
+}
+
+fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
+    state.db_pool.as_ref().ok_or_else(|| {
+        AppError::Dependency("Database is not configured. Set DATABASE_URL.".to_string())
+    })
 }
 
 async fn run_agent_playbook(
@@ -10,12 +10,11 @@ use crate::{
     auth::require_user_id,
     error::AppResult,
     services::agent_runs::{self, AgentRunMode, CreateAgentRunParams, ListAgentRunsParams},
+    services::permissions,
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
-const AUTONOMY_ROLES: &[&str] = &["owner_admin", "operator"];
-
 #[derive(Debug, Deserialize)]
 struct OrgQuery {
     org_id: String,
@@ -126,7 +125,13 @@ async fn create_run(
 
     let mode = AgentRunMode::parse(&payload.mode)?;
     if mode == AgentRunMode::Autonomous {
-        assert_org_role(&state, &user_id, &payload.org_id, AUTONOMY_ROLES).await?;
+        assert_permission(
+            &state,
+            &user_id,
+            &payload.org_id,
+            permissions::AGENT_RUNS_MANAGE,
+        )
+        .await?;
     }
 
     let run = agent_runs::create_run(
@@ -197,7 +202,13 @@ async fn cancel_run(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &query.org_id, AUTONOMY_ROLES).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &query.org_id,
+        permissions::AGENT_RUNS_MANAGE,
+    )
+    .await?;
 
     let run = agent_runs::cancel_run(&state, &query.org_id, &path.run_id, &user_id).await?;
     Ok(Json(run))
@@ -211,7 +222,13 @@ async fn approve_run(
     Json(payload): Json<ApproveRunInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &query.org_id, AUTONOMY_ROLES).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &query.org_id,
+        permissions::AGENT_RUNS_MANAGE,
+    )
+    .await?;
 
     let run = agent_runs::approve_run(
         &state,
@@ -14,10 +14,11 @@ use crate::{
         agent_specs::{allowed_tools_for_slug, get_agent_spec},
         ai_agent::{execute_tool, tool_definitions, ToolContext, TOOL_REGISTRY_VERSION},
         audit::write_audit_log,
+        permissions,
         tool_validator::{normalize_tool_result, normalized_tool_error},
     },
     state::AppState,
-    tenancy::assert_org_member,
+    tenancy::assert_org_scope,
 };
 
 #[allow(dead_code)]
@@ -70,7 +71,7 @@ async fn get_tool_definitions(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
 
     // If agent_slug provided, look up its allowed_tools from the database
     let allowed_tools = if let Some(slug) = &query.agent_slug {
@@ -144,15 +145,8 @@ async fn post_execute_tool(
     Json(payload): Json<ExecuteToolInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    let membership = assert_org_member(&state, &user_id, &payload.org_id).await?;
-    let role = membership
-        .as_object()
-        .and_then(|obj| obj.get("role"))
-        .and_then(Value::as_str)
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-        .unwrap_or("viewer")
-        .to_string();
+    let membership = assert_org_scope(&state, &user_id, &payload.org_id, "agents").await?;
+    let role = permissions::agent_role(&membership);
 
     let allow_mutations = payload.allow_mutations.unwrap_or(true);
     let confirm_write = payload.confirm_write.unwrap_or(true);
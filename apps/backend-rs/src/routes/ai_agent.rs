@@ -17,9 +17,10 @@ use crate::{
             RuntimeExecutionContext,
         },
         audit::write_audit_log,
+        permissions,
     },
     state::AppState,
-    tenancy::assert_org_member,
+    tenancy::assert_org_scope,
 };
 
 #[derive(Debug, Clone, Deserialize)]
@@ -58,15 +59,8 @@ async fn get_agent_capabilities(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    let membership = assert_org_member(&state, &user_id, &query.org_id).await?;
-    let role = membership
-        .as_object()
-        .and_then(|obj| obj.get("role"))
-        .and_then(Value::as_str)
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-        .unwrap_or("viewer")
-        .to_string();
+    let membership = assert_org_scope(&state, &user_id, &query.org_id, "agents").await?;
+    let role = permissions::agent_role(&membership);
 
     let capabilities = agent_capabilities(&role, false);
     let mut payload = Map::new();
@@ -107,15 +101,8 @@ async fn ai_agent_chat(
     }
 
     let user_id = require_user_id(&state, &headers).await?;
-    let membership = assert_org_member(&state, &user_id, &payload.org_id).await?;
-    let role = membership
-        .as_object()
-        .and_then(|obj| obj.get("role"))
-        .and_then(Value::as_str)
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-        .unwrap_or("viewer")
-        .to_string();
+    let membership = assert_org_scope(&state, &user_id, &payload.org_id, "agents").await?;
+    let role = permissions::agent_role(&membership);
 
     let conversation = payload
         .conversation
@@ -0,0 +1,388 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{Duration, Utc};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    auth::require_user_id,
+    cache::org_key,
+    error::{AppError, AppResult},
+    repository::table_service::{create_row, get_row, list_rows, update_row},
+    schemas::{ApiKeyPath, ApiKeysQuery, CreateApiKeyInput, RotateApiKeyInput, UpdateApiKeyInput},
+    services::{api_keys, audit::write_audit_log, permissions, token_hash::hash_token},
+    state::AppState,
+    tenancy::assert_permission,
+};
+
+const DEFAULT_ROTATION_GRACE_HOURS: i64 = 24;
+
+pub fn router() -> axum::Router<AppState> {
+    axum::Router::new()
+        .route(
+            "/api-keys",
+            axum::routing::get(list_api_keys).post(create_api_key),
+        )
+        .route(
+            "/api-keys/{key_id}",
+            axum::routing::get(get_api_key).patch(update_api_key),
+        )
+        .route(
+            "/api-keys/{key_id}/rotate",
+            axum::routing::post(rotate_api_key),
+        )
+        .route(
+            "/api-keys/{key_id}/revoke",
+            axum::routing::post(revoke_api_key),
+        )
+}
+
+// ── API keys ────────────────────────────────────────────────────────
+
+async fn list_api_keys(
+    State(state): State<AppState>,
+    Query(query): Query<ApiKeysQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &query.org_id,
+        permissions::API_KEYS_MANAGE,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(query.org_id.clone()),
+    );
+    let rows = list_rows(
+        pool,
+        "organization_api_keys",
+        Some(&filters),
+        200,
+        0,
+        "created_at",
+        false,
+    )
+    .await?;
+
+    let data = rows
+        .into_iter()
+        .filter(|row| query.include_revoked || row.get("revoked_at").is_none_or(Value::is_null))
+        .map(masked)
+        .collect::<Vec<_>>();
+    Ok(Json(json!({ "data": data })))
+}
+
+/// The raw token is returned only here and on rotation.
+async fn create_api_key(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateApiKeyInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::API_KEYS_MANAGE,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let name = non_empty_opt(Some(&payload.name))
+        .ok_or_else(|| AppError::BadRequest("name is required.".to_string()))?;
+    let scopes = api_keys::normalize_scopes(&payload.scopes).map_err(AppError::BadRequest)?;
+    let expires_at = parse_expiry(payload.expires_at.as_deref())?;
+
+    let service_user_id = api_keys::create_service_user(pool, &name).await?;
+    let token = api_keys::generate_token();
+
+    let mut record = Map::new();
+    record.insert(
+        "organization_id".to_string(),
+        Value::String(payload.organization_id.clone()),
+    );
+    record.insert("name".to_string(), Value::String(name));
+    record.insert(
+        "key_prefix".to_string(),
+        Value::String(api_keys::display_prefix(&token)),
+    );
+    record.insert("token_hash".to_string(), Value::String(hash_token(&token)));
+    record.insert("scopes".to_string(), json!(scopes));
+    record.insert(
+        "service_user_id".to_string(),
+        Value::String(service_user_id),
+    );
+    record.insert("expires_at".to_string(), expires_at);
+    record.insert(
+        "created_by_user_id".to_string(),
+        Value::String(user_id.clone()),
+    );
+    let created = masked(create_row(pool, "organization_api_keys", &record).await?);
+    let entity_id = value_str(&created, "id");
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        "create",
+        "organization_api_keys",
+        Some(&entity_id),
+        None,
+        Some(created.clone()),
+    )
+    .await;
+
+    Ok((
+        axum::http::StatusCode::CREATED,
+        Json(with_token(created, &token)),
+    ))
+}
+
+async fn get_api_key(
+    State(state): State<AppState>,
+    Path(path): Path<ApiKeyPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let (_, record) = load_api_key(&state, &headers, &path.key_id).await?;
+    Ok(Json(masked(record)))
+}
+
+async fn update_api_key(
+    State(state): State<AppState>,
+    Path(path): Path<ApiKeyPath>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateApiKeyInput>,
+) -> AppResult<Json<Value>> {
+    let (user_id, record) = load_api_key(&state, &headers, &path.key_id).await?;
+    ensure_not_revoked(&record)?;
+    let pool = db_pool(&state)?;
+    let org_id = value_str(&record, "organization_id");
+
+    let mut patch = Map::new();
+    if let Some(name) = payload.name.as_deref() {
+        let name = non_empty_opt(Some(name))
+            .ok_or_else(|| AppError::BadRequest("name cannot be empty.".to_string()))?;
+        patch.insert("name".to_string(), Value::String(name));
+    }
+    if let Some(scopes) = payload.scopes.as_deref() {
+        let scopes = api_keys::normalize_scopes(scopes).map_err(AppError::BadRequest)?;
+        patch.insert("scopes".to_string(), json!(scopes));
+    }
+    if let Some(expires_at) = payload.expires_at.as_deref() {
+        patch.insert("expires_at".to_string(), parse_expiry(Some(expires_at))?);
+    }
+    if patch.is_empty() {
+        return Ok(Json(masked(record)));
+    }
+
+    let updated =
+        masked(update_row(pool, "organization_api_keys", &path.key_id, &patch, "id").await?);
+    invalidate_membership(&state, &record).await;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "update",
+        "organization_api_keys",
+        Some(&path.key_id),
+        Some(masked(record)),
+        Some(updated.clone()),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+/// Issue a new token. The previous one keeps working for the grace period
+/// so deployed integrations can be switched over without downtime.
+async fn rotate_api_key(
+    State(state): State<AppState>,
+    Path(path): Path<ApiKeyPath>,
+    headers: HeaderMap,
+    payload: Option<Json<RotateApiKeyInput>>,
+) -> AppResult<Json<Value>> {
+    let (user_id, record) = load_api_key(&state, &headers, &path.key_id).await?;
+    ensure_not_revoked(&record)?;
+    let pool = db_pool(&state)?;
+    let org_id = value_str(&record, "organization_id");
+
+    let grace_hours = payload
+        .and_then(|Json(payload)| payload.grace_hours)
+        .unwrap_or(DEFAULT_ROTATION_GRACE_HOURS);
+    if !(0..=api_keys::MAX_ROTATION_GRACE_HOURS).contains(&grace_hours) {
+        return Err(AppError::BadRequest(format!(
+            "grace_hours must be between 0 and {}.",
+            api_keys::MAX_ROTATION_GRACE_HOURS
+        )));
+    }
+
+    let token = api_keys::generate_token();
+    let mut patch = Map::new();
+    patch.insert(
+        "previous_token_hash".to_string(),
+        record.get("token_hash").cloned().unwrap_or(Value::Null),
+    );
+    patch.insert(
+        "previous_token_expires_at".to_string(),
+        Value::String((Utc::now() + Duration::hours(grace_hours)).to_rfc3339()),
+    );
+    patch.insert("token_hash".to_string(), Value::String(hash_token(&token)));
+    patch.insert(
+        "key_prefix".to_string(),
+        Value::String(api_keys::display_prefix(&token)),
+    );
+    let updated =
+        masked(update_row(pool, "organization_api_keys", &path.key_id, &patch, "id").await?);
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "rotate",
+        "organization_api_keys",
+        Some(&path.key_id),
+        Some(masked(record)),
+        Some(updated.clone()),
+    )
+    .await;
+
+    Ok(Json(with_token(updated, &token)))
+}
+
+/// Revocation takes effect immediately, including for a rotated-out token.
+async fn revoke_api_key(
+    State(state): State<AppState>,
+    Path(path): Path<ApiKeyPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let (user_id, record) = load_api_key(&state, &headers, &path.key_id).await?;
+    ensure_not_revoked(&record)?;
+    let pool = db_pool(&state)?;
+    let org_id = value_str(&record, "organization_id");
+
+    let mut patch = Map::new();
+    patch.insert(
+        "revoked_at".to_string(),
+        Value::String(Utc::now().to_rfc3339()),
+    );
+    patch.insert("previous_token_hash".to_string(), Value::Null);
+    patch.insert("previous_token_expires_at".to_string(), Value::Null);
+    let updated =
+        masked(update_row(pool, "organization_api_keys", &path.key_id, &patch, "id").await?);
+    invalidate_membership(&state, &record).await;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "revoke",
+        "organization_api_keys",
+        Some(&path.key_id),
+        Some(masked(record)),
+        Some(updated.clone()),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────
+
+async fn load_api_key(
+    state: &AppState,
+    headers: &HeaderMap,
+    key_id: &str,
+) -> AppResult<(String, Value)> {
+    let user_id = require_user_id(state, headers).await?;
+    let pool = db_pool(state)?;
+
+    let record = get_row(pool, "organization_api_keys", key_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(state, &user_id, &org_id, permissions::API_KEYS_MANAGE).await?;
+    Ok((user_id, record))
+}
+
+fn ensure_not_revoked(record: &Value) -> AppResult<()> {
+    if record
+        .get("revoked_at")
+        .is_some_and(|value| !value.is_null())
+    {
+        return Err(AppError::Gone("This API key has been revoked.".to_string()));
+    }
+    Ok(())
+}
+
+/// Scope and expiry changes must not wait for the membership cache TTL.
+async fn invalidate_membership(state: &AppState, record: &Value) {
+    state
+        .org_membership_cache
+        .invalidate(&org_key(
+            &value_str(record, "organization_id"),
+            &value_str(record, "service_user_id"),
+        ))
+        .await;
+}
+
+fn parse_expiry(value: Option<&str>) -> AppResult<Value> {
+    let Some(value) = non_empty_opt(value) else {
+        return Ok(Value::Null);
+    };
+    let parsed = chrono::DateTime::parse_from_rfc3339(&value).map_err(|_| {
+        AppError::BadRequest("expires_at must be an RFC 3339 timestamp.".to_string())
+    })?;
+    if parsed <= Utc::now() {
+        return Err(AppError::BadRequest(
+            "expires_at must be in the future.".to_string(),
+        ));
+    }
+    Ok(Value::String(parsed.to_rfc3339()))
+}
+
+/// Keys are shown without their token hashes.
+fn masked(mut row: Value) -> Value {
+    if let Some(obj) = row.as_object_mut() {
+        obj.remove("token_hash");
+        obj.remove("previous_token_hash");
+    }
+    row
+}
+
+fn with_token(mut row: Value, token: &str) -> Value {
+    if let Some(obj) = row.as_object_mut() {
+        obj.insert("token".to_string(), Value::String(token.to_string()));
+    }
+    row
+}
+
+fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
+    state.db_pool.as_ref().ok_or_else(|| {
+        AppError::Dependency("Database is not configured. Set DATABASE_URL.".to_string())
+    })
+}
+
+fn value_str(row: &Value, key: &str) -> String {
+    row.as_object()
+        .and_then(|obj| obj.get(key))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+fn non_empty_opt(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+}
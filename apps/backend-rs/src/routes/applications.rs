@@ -19,14 +19,14 @@ use crate::{
         audit::write_audit_log,
         lease_schedule::ensure_monthly_lease_schedule,
         notification_center::{emit_event, EmitNotificationEventInput},
+        permissions,
         pricing::lease_financials_from_lines,
         workflows::fire_trigger,
     },
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
-const APPLICATION_EDIT_ROLES: &[&str] = &["owner_admin", "operator"];
 const RESPONSE_SLA_MINUTES: i64 = 120;
 const RESPONSE_SLA_WARNING_MINUTES: f64 = 30.0;
 const QUALIFICATION_STRONG_THRESHOLD: i64 = 75;
@@ -345,7 +345,7 @@ async fn update_application_status(
 
     let record = get_row(pool, "application_submissions", &path.application_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, APPLICATION_EDIT_ROLES).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::APPLICATIONS_EDIT).await?;
 
     let current = value_str(&record, "status");
     let current_status = if current.is_empty() {
@@ -532,7 +532,7 @@ async fn convert_application_to_lease(
 
     let application = get_row(pool, "application_submissions", &path.application_id, "id").await?;
     let org_id = value_str(&application, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, APPLICATION_EDIT_ROLES).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::APPLICATIONS_EDIT).await?;
 
     let current_status = value_str(&application, "status");
     if matches!(current_status.as_str(), "rejected" | "lost") {
@@ -11,11 +11,11 @@ use uuid::Uuid;
 use crate::{
     auth::require_user_id,
     error::{AppError, AppResult},
+    services::permissions,
     state::AppState,
-    tenancy::assert_org_member,
+    tenancy::{assert_org_member, assert_permission},
 };
 
-const APPROVER_ROLES: &[&str] = &["owner_admin", "operator", "accountant"];
 const MUTATION_TOOLS: &[&str] = &["create_row", "update_row", "delete_row"];
 
 #[derive(Debug, Clone, Deserialize)]
@@ -99,8 +99,13 @@ async fn list_approvals(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    let membership = assert_org_member(&state, &user_id, &query.org_id).await?;
-    assert_approver_role(&membership)?;
+    assert_permission(
+        &state,
+        &user_id,
+        &query.org_id,
+        permissions::APPROVALS_REVIEW,
+    )
+    .await?;
 
     let pool = db_pool(&state)?;
     let status_filter = match query.status.as_deref().map(str::trim) {
@@ -190,8 +195,13 @@ async fn batch_review_approvals(
     Json(payload): Json<BatchReviewInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    let membership = assert_org_member(&state, &user_id, &query.org_id).await?;
-    assert_approver_role(&membership)?;
+    assert_permission(
+        &state,
+        &user_id,
+        &query.org_id,
+        permissions::APPROVALS_REVIEW,
+    )
+    .await?;
 
     if payload.ids.is_empty() {
         return Err(AppError::BadRequest("ids must not be empty.".to_string()));
@@ -323,8 +333,13 @@ async fn approve_approval(
     Json(payload): Json<ReviewApprovalInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    let membership = assert_org_member(&state, &user_id, &query.org_id).await?;
-    assert_approver_role(&membership)?;
+    assert_permission(
+        &state,
+        &user_id,
+        &query.org_id,
+        permissions::APPROVALS_REVIEW,
+    )
+    .await?;
 
     let pool = db_pool(&state)?;
 
@@ -482,8 +497,13 @@ async fn reject_approval(
     Json(payload): Json<ReviewApprovalInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    let membership = assert_org_member(&state, &user_id, &query.org_id).await?;
-    assert_approver_role(&membership)?;
+    assert_permission(
+        &state,
+        &user_id,
+        &query.org_id,
+        permissions::APPROVALS_REVIEW,
+    )
+    .await?;
 
     let pool = db_pool(&state)?;
 
@@ -640,8 +660,13 @@ async fn update_approval_policy(
     Json(payload): Json<UpdateApprovalPolicyInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    let membership = assert_org_member(&state, &user_id, &query.org_id).await?;
-    assert_approver_role(&membership)?;
+    assert_permission(
+        &state,
+        &user_id,
+        &query.org_id,
+        permissions::APPROVALS_REVIEW,
+    )
+    .await?;
 
     let tool_name = path.tool_name.trim();
     if !MUTATION_TOOLS.contains(&tool_name) {
@@ -732,25 +757,6 @@ async fn update_approval_policy(
     })))
 }
 
-fn assert_approver_role(membership: &Value) -> AppResult<()> {
-    let role = membership
-        .as_object()
-        .and_then(|obj| obj.get("role"))
-        .and_then(Value::as_str)
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-        .unwrap_or("viewer")
-        .to_ascii_lowercase();
-
-    if APPROVER_ROLES.contains(&role.as_str()) {
-        return Ok(());
-    }
-
-    Err(AppError::Forbidden(
-        "This action requires approver permissions.".to_string(),
-    ))
-}
-
 fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
     state.db_pool.as_ref().ok_or_else(|| {
         AppError::Dependency("Database is not configured. Set DATABASE_URL.".to_string())
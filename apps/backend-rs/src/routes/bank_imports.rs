@@ -0,0 +1,744 @@
+use axum::{
+    extract::{Multipart, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    auth::require_user_id,
+    error::{AppError, AppResult},
+    repository::table_service::{create_row, delete_row, get_row, list_rows, update_row},
+    schemas::{
+        clamp_limit, remove_nulls, serialize_to_map, BankImportPath, BankImportProfilePath,
+        BankImportProfilesQuery, BankImportsQuery, CommitBankImportInput,
+        CreateBankImportProfileInput, UpdateBankImportProfileInput,
+    },
+    services::{
+        audit::write_audit_log,
+        bank_statements::{self, CsvProfile, StatementFormat, StatementLine},
+        fx, permissions,
+        reconciliation::{self, BankCredit},
+    },
+    state::AppState,
+    tenancy::{assert_org_member, assert_permission},
+};
+
+/// Statement exports larger than this are rejected before parsing.
+const MAX_STATEMENT_BYTES: usize = 10 * 1024 * 1024;
+
+pub fn router() -> axum::Router<AppState> {
+    axum::Router::new()
+        .route(
+            "/bank-import-profiles",
+            axum::routing::get(list_profiles).post(create_profile),
+        )
+        .route(
+            "/bank-import-profiles/{profile_id}",
+            axum::routing::patch(update_profile).delete(delete_profile),
+        )
+        .route("/bank-imports", axum::routing::get(list_imports))
+        .route("/bank-imports/preview", axum::routing::post(preview_import))
+        .route("/bank-imports/{import_id}", axum::routing::get(get_import))
+        .route(
+            "/bank-imports/{import_id}/commit",
+            axum::routing::post(commit_import),
+        )
+        .route(
+            "/bank-imports/{import_id}/discard",
+            axum::routing::post(discard_import),
+        )
+}
+
+// ── Column mapping profiles ────────────────────────────────────────────
+
+async fn list_profiles(
+    State(state): State<AppState>,
+    Query(query): Query<BankImportProfilesQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(query.org_id.clone()),
+    );
+    let rows = list_rows(
+        pool,
+        "bank_import_profiles",
+        Some(&filters),
+        200,
+        0,
+        "name",
+        true,
+    )
+    .await?;
+    Ok(Json(json!({ "data": rows })))
+}
+
+async fn create_profile(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateBankImportProfileInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::BANK_IMPORTS_EDIT,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let mut record = remove_nulls(serialize_to_map(&payload));
+    record.insert(
+        "created_by_user_id".to_string(),
+        Value::String(user_id.clone()),
+    );
+    validate_profile(&mut record)?;
+
+    let created = create_row(pool, "bank_import_profiles", &record).await?;
+    write_audit_log(
+        Some(pool),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        "create",
+        "bank_import_profiles",
+        created.get("id").and_then(Value::as_str),
+        None,
+        Some(created.clone()),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+async fn update_profile(
+    State(state): State<AppState>,
+    Path(path): Path<BankImportProfilePath>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateBankImportProfileInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let existing = get_row(pool, "bank_import_profiles", &path.profile_id, "id").await?;
+    let org_id = value_str(&existing, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::BANK_IMPORTS_EDIT).await?;
+
+    let patch = remove_nulls(serialize_to_map(&payload));
+    if patch.is_empty() {
+        return Ok(Json(existing));
+    }
+    let mut merged = existing.as_object().cloned().unwrap_or_default();
+    merged.extend(patch.clone());
+    validate_profile(&mut merged)?;
+    let patch: Map<String, Value> = merged
+        .into_iter()
+        .filter(|(key, _)| patch.contains_key(key))
+        .collect();
+
+    let updated = update_row(pool, "bank_import_profiles", &path.profile_id, &patch, "id").await?;
+    write_audit_log(
+        Some(pool),
+        Some(&org_id),
+        Some(&user_id),
+        "update",
+        "bank_import_profiles",
+        Some(&path.profile_id),
+        Some(existing),
+        Some(updated.clone()),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+async fn delete_profile(
+    State(state): State<AppState>,
+    Path(path): Path<BankImportProfilePath>,
+    headers: HeaderMap,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let existing = get_row(pool, "bank_import_profiles", &path.profile_id, "id").await?;
+    let org_id = value_str(&existing, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::BANK_IMPORTS_EDIT).await?;
+
+    delete_row(pool, "bank_import_profiles", &path.profile_id, "id").await?;
+    write_audit_log(
+        Some(pool),
+        Some(&org_id),
+        Some(&user_id),
+        "delete",
+        "bank_import_profiles",
+        Some(&path.profile_id),
+        Some(existing),
+        None,
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Check the mapping fields the parser relies on and normalize the
+/// currency. Works on a full record so partial updates are checked against
+/// the stored profile.
+fn validate_profile(record: &mut Map<String, Value>) -> AppResult<()> {
+    let text = |key: &str| {
+        record
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+    if text("name").is_none() || text("date_column").is_none() {
+        return Err(AppError::BadRequest(
+            "name and date_column are required.".to_string(),
+        ));
+    }
+    if ["amount_column", "credit_column", "debit_column"]
+        .iter()
+        .all(|key| text(key).is_none())
+    {
+        return Err(AppError::BadRequest(
+            "Map amount_column, or credit_column and/or debit_column.".to_string(),
+        ));
+    }
+    if record
+        .get("delimiter")
+        .and_then(Value::as_str)
+        .is_some_and(|delimiter| delimiter.chars().count() != 1)
+    {
+        return Err(AppError::BadRequest(
+            "delimiter must be a single character.".to_string(),
+        ));
+    }
+    if text("decimal_separator").is_some_and(|separator| separator != "," && separator != ".") {
+        return Err(AppError::BadRequest(
+            "decimal_separator must be ',' or '.'.".to_string(),
+        ));
+    }
+    if let Some(format) = text("date_format") {
+        let invalid = chrono::format::StrftimeItems::new(format)
+            .any(|item| matches!(item, chrono::format::Item::Error));
+        if invalid || !format.contains('%') {
+            return Err(AppError::BadRequest(format!(
+                "date_format '{format}' is not a valid strftime pattern."
+            )));
+        }
+    }
+    if let Some(currency) = text("default_currency") {
+        let currency = fx::normalize_currency(currency).ok_or_else(|| {
+            AppError::BadRequest("default_currency must be a 3-letter ISO 4217 code.".to_string())
+        })?;
+        record.insert("default_currency".to_string(), Value::String(currency));
+    }
+    Ok(())
+}
+
+// ── Statement imports ──────────────────────────────────────────────────
+
+async fn list_imports(
+    State(state): State<AppState>,
+    Query(query): Query<BankImportsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(query.org_id.clone()),
+    );
+    if let Some(status) = non_empty_opt(query.status.as_deref()) {
+        filters.insert("status".to_string(), Value::String(status));
+    }
+    let rows = list_rows(
+        pool,
+        "bank_statement_imports",
+        Some(&filters),
+        clamp_limit(query.limit),
+        0,
+        "created_at",
+        false,
+    )
+    .await?;
+
+    // Parsed rows can run to thousands of lines; fetch one import for them.
+    let data: Vec<Value> = rows
+        .into_iter()
+        .map(|mut row| {
+            if let Some(obj) = row.as_object_mut() {
+                obj.remove("rows");
+                obj.remove("rejected_rows");
+            }
+            row
+        })
+        .collect();
+    Ok(Json(json!({ "data": data })))
+}
+
+async fn get_import(
+    State(state): State<AppState>,
+    Path(path): Path<BankImportPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let record = get_row(pool, "bank_statement_imports", &path.import_id, "id").await?;
+    assert_org_member(&state, &user_id, &value_str(&record, "organization_id")).await?;
+    Ok(Json(record))
+}
+
+/// Parse an uploaded statement and store it as a preview: every line with
+/// its dedupe status and, for new credits, the collection auto-reconciliation
+/// would match it to. Nothing reaches `bank_transactions` until commit.
+///
+/// Multipart fields: `organization_id`, `file`, and optionally `format`
+/// (`csv`, `ofx`, `camt053`; detected when omitted), `profile_id` (required
+/// for CSV) and `bank_name`.
+async fn preview_import(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    mut multipart: Multipart,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let mut org_id: Option<String> = None;
+    let mut profile_id: Option<String> = None;
+    let mut format: Option<String> = None;
+    let mut bank_name: Option<String> = None;
+    let mut file_name: Option<String> = None;
+    let mut file: Option<Vec<u8>> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|error| AppError::BadRequest(format!("Invalid multipart body: {error}")))?
+    {
+        let field_name = field.name().unwrap_or_default().to_string();
+        if field_name == "file" {
+            file_name = field.file_name().map(ToOwned::to_owned);
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|error| AppError::BadRequest(format!("Failed to read file: {error}")))?;
+            if bytes.len() > MAX_STATEMENT_BYTES {
+                return Err(AppError::BadRequest(
+                    "Statement files are limited to 10 MB.".to_string(),
+                ));
+            }
+            file = Some(bytes.to_vec());
+            continue;
+        }
+        let value = field
+            .text()
+            .await
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+        match field_name.as_str() {
+            "organization_id" | "org_id" => org_id = value,
+            "profile_id" => profile_id = value,
+            "format" => format = value,
+            "bank_name" => bank_name = value,
+            _ => {}
+        }
+    }
+
+    let org_id =
+        org_id.ok_or_else(|| AppError::BadRequest("organization_id is required.".to_string()))?;
+    assert_permission(&state, &user_id, &org_id, permissions::BANK_IMPORTS_EDIT).await?;
+    let bytes = file
+        .filter(|bytes| !bytes.is_empty())
+        .ok_or_else(|| AppError::BadRequest("A statement file is required.".to_string()))?;
+    let text = bank_statements::decode_text(&bytes);
+
+    let profile = match profile_id.as_deref() {
+        Some(profile_id) => {
+            let row = get_row(pool, "bank_import_profiles", profile_id, "id").await?;
+            if value_str(&row, "organization_id") != org_id {
+                return Err(AppError::NotFound("Import profile not found.".to_string()));
+            }
+            Some(row)
+        }
+        None => None,
+    };
+    let format = match format.as_deref() {
+        Some(value) => StatementFormat::parse(value).ok_or_else(|| {
+            AppError::BadRequest("format must be csv, ofx or camt053.".to_string())
+        })?,
+        None if profile.is_some() => {
+            StatementFormat::detect(file_name.as_deref(), &text).unwrap_or(StatementFormat::Csv)
+        }
+        None => StatementFormat::detect(file_name.as_deref(), &text).ok_or_else(|| {
+            AppError::BadRequest(
+                "Could not detect the file format; pass format or a CSV profile_id.".to_string(),
+            )
+        })?,
+    };
+
+    let csv_profile = profile.as_ref().map(CsvProfile::from_row);
+    let statement = bank_statements::parse(format, &text, csv_profile.as_ref())
+        .map_err(|message| AppError::BadRequest(format!("Could not read statement: {message}")))?;
+    let hashes =
+        bank_statements::dedupe_hashes(&statement.lines, statement.account_number.as_deref());
+    let existing = existing_hashes(pool, &org_id, &statement.lines, &hashes).await?;
+
+    // Dry-run the matcher over the new credits so the preview shows what a
+    // commit would reconcile.
+    let credits: Vec<BankCredit> = statement
+        .lines
+        .iter()
+        .zip(&hashes)
+        .filter(|(line, hash)| line.amount > 0.0 && !existing.contains(hash.as_str()))
+        .map(|(line, hash)| BankCredit {
+            id: hash.clone(),
+            date: Some(line.date),
+            amount: line.amount,
+            reference: line.reference.clone().unwrap_or_default(),
+            description: line.description.clone(),
+            counterparty: line.counterparty.clone().unwrap_or_default(),
+        })
+        .collect();
+    let collections = reconciliation::load_open_collections(pool, &org_id, "").await;
+    let planned = reconciliation::plan_matches(&credits, &collections);
+
+    let mut new_rows = 0;
+    let rows: Vec<Value> = statement
+        .lines
+        .iter()
+        .zip(&hashes)
+        .map(|(line, hash)| {
+            let duplicate = existing.contains(hash.as_str());
+            if !duplicate {
+                new_rows += 1;
+            }
+            let mut row = line.to_json();
+            if let Some(obj) = row.as_object_mut() {
+                obj.insert("dedupe_hash".to_string(), Value::String(hash.clone()));
+                obj.insert(
+                    "status".to_string(),
+                    Value::String(if duplicate { "duplicate" } else { "new" }.to_string()),
+                );
+                if let Some(planned) = planned.iter().find(|m| m.transaction_id == *hash) {
+                    obj.insert(
+                        "suggested_match".to_string(),
+                        json!({
+                            "collection_id": planned.collection_id,
+                            "method": planned.method,
+                            "confidence": planned.confidence,
+                        }),
+                    );
+                }
+            }
+            row
+        })
+        .collect();
+    let rejected: Vec<Value> = statement
+        .rejected
+        .iter()
+        .map(|(line, reason)| json!({ "line": line, "reason": reason }))
+        .collect();
+
+    let bank_name = bank_name
+        .or_else(|| {
+            profile
+                .as_ref()
+                .and_then(|row| non_empty_opt(row.get("bank_name").and_then(Value::as_str)))
+        })
+        .or(statement.bank_name.clone());
+    let mut record = Map::new();
+    record.insert("organization_id".to_string(), Value::String(org_id.clone()));
+    if let Some(profile_id) = profile_id {
+        record.insert("profile_id".to_string(), Value::String(profile_id));
+    }
+    if let Some(file_name) = file_name {
+        record.insert("file_name".to_string(), Value::String(file_name));
+    }
+    record.insert(
+        "file_format".to_string(),
+        Value::String(format.as_str().to_string()),
+    );
+    if let Some(bank_name) = bank_name {
+        record.insert("bank_name".to_string(), Value::String(bank_name));
+    }
+    if let Some(account_number) = statement.account_number.clone() {
+        record.insert("account_number".to_string(), Value::String(account_number));
+    }
+    record.insert("status".to_string(), Value::String("preview".to_string()));
+    record.insert("total_rows".to_string(), json!(rows.len()));
+    record.insert("new_rows".to_string(), json!(new_rows));
+    record.insert("duplicate_rows".to_string(), json!(rows.len() - new_rows));
+    record.insert("rows".to_string(), Value::Array(rows));
+    record.insert("rejected_rows".to_string(), Value::Array(rejected));
+    record.insert(
+        "created_by_user_id".to_string(),
+        Value::String(user_id.clone()),
+    );
+
+    let created = create_row(pool, "bank_statement_imports", &record).await?;
+    write_audit_log(
+        Some(pool),
+        Some(&org_id),
+        Some(&user_id),
+        "preview",
+        "bank_statement_imports",
+        created.get("id").and_then(Value::as_str),
+        None,
+        Some(json!({
+            "file_format": format.as_str(),
+            "total_rows": record.get("total_rows"),
+            "new_rows": new_rows,
+            "suggested_matches": planned.len(),
+        })),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Write the preview's new rows to `bank_transactions` and, unless
+/// `reconcile` is false, run auto-reconciliation over them.
+async fn commit_import(
+    State(state): State<AppState>,
+    Path(path): Path<BankImportPath>,
+    headers: HeaderMap,
+    payload: Option<Json<CommitBankImportInput>>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+    let payload = payload.map(|Json(payload)| payload).unwrap_or_default();
+
+    let record = get_row(pool, "bank_statement_imports", &path.import_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::BANK_IMPORTS_EDIT).await?;
+
+    // Claim the preview so two concurrent commits cannot both insert.
+    let claimed = sqlx::query(
+        "UPDATE bank_statement_imports
+            SET status = 'committed', committed_at = now()
+          WHERE id = $1::uuid AND status = 'preview'",
+    )
+    .bind(&path.import_id)
+    .execute(pool)
+    .await
+    .map_err(|error| AppError::from_database_error(&error, "Failed to commit import."))?;
+    if claimed.rows_affected() == 0 {
+        return Err(AppError::Conflict(format!(
+            "Import is already {}.",
+            value_str(&record, "status")
+        )));
+    }
+
+    let mut lines = Vec::new();
+    let mut hashes = Vec::new();
+    let mut raw_rows = Vec::new();
+    for row in record
+        .get("rows")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|row| row.get("status").and_then(Value::as_str) == Some("new"))
+    {
+        let Some(line) = StatementLine::from_json(row) else {
+            continue;
+        };
+        lines.push(line);
+        hashes.push(value_str(row, "dedupe_hash"));
+        raw_rows.push(row.clone());
+    }
+    let bank_name = value_str(&record, "bank_name");
+    let account_number = non_empty_opt(record.get("account_number").and_then(Value::as_str));
+    let (imported, late_duplicates) = reconciliation::insert_bank_transactions(
+        pool,
+        &org_id,
+        Some(&path.import_id),
+        &bank_name,
+        account_number.as_deref(),
+        &lines,
+        &hashes,
+        &raw_rows,
+    )
+    .await;
+
+    let reconciliation = if payload.reconcile.unwrap_or(true) && imported > 0 {
+        let mut args = Map::new();
+        args.insert(
+            "import_id".to_string(),
+            Value::String(path.import_id.clone()),
+        );
+        if let Some(period_month) = non_empty_opt(payload.period_month.as_deref()) {
+            args.insert("period_month".to_string(), Value::String(period_month));
+        }
+        Some(reconciliation::tool_auto_reconcile_batch(&state, &org_id, &args).await?)
+    } else {
+        None
+    };
+
+    let mut patch = Map::new();
+    patch.insert("imported_rows".to_string(), json!(imported));
+    patch.insert(
+        "duplicate_rows".to_string(),
+        json!(number(&record, "duplicate_rows") + i64::from(late_duplicates)),
+    );
+    if let Some(run_id) = reconciliation
+        .as_ref()
+        .and_then(|result| result.get("run_id"))
+        .and_then(Value::as_str)
+        .filter(|run_id| !run_id.is_empty())
+    {
+        patch.insert(
+            "reconciliation_run_id".to_string(),
+            Value::String(run_id.to_string()),
+        );
+    }
+    let updated = update_row(
+        pool,
+        "bank_statement_imports",
+        &path.import_id,
+        &patch,
+        "id",
+    )
+    .await?;
+
+    write_audit_log(
+        Some(pool),
+        Some(&org_id),
+        Some(&user_id),
+        "commit",
+        "bank_statement_imports",
+        Some(&path.import_id),
+        None,
+        Some(json!({
+            "imported_rows": imported,
+            "late_duplicates": late_duplicates,
+            "reconciliation": reconciliation,
+        })),
+    )
+    .await;
+
+    Ok(Json(json!({
+        "import": updated,
+        "reconciliation": reconciliation,
+    })))
+}
+
+async fn discard_import(
+    State(state): State<AppState>,
+    Path(path): Path<BankImportPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let record = get_row(pool, "bank_statement_imports", &path.import_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::BANK_IMPORTS_EDIT).await?;
+    if value_str(&record, "status") != "preview" {
+        return Err(AppError::Conflict(
+            "Only previews can be discarded.".to_string(),
+        ));
+    }
+
+    let mut patch = Map::new();
+    patch.insert("status".to_string(), Value::String("discarded".to_string()));
+    let updated = update_row(
+        pool,
+        "bank_statement_imports",
+        &path.import_id,
+        &patch,
+        "id",
+    )
+    .await?;
+    write_audit_log(
+        Some(pool),
+        Some(&org_id),
+        Some(&user_id),
+        "discard",
+        "bank_statement_imports",
+        Some(&path.import_id),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+/// Hashes (and bank-assigned ids) of these lines that are already stored.
+async fn existing_hashes(
+    pool: &sqlx::PgPool,
+    org_id: &str,
+    lines: &[StatementLine],
+    hashes: &[String],
+) -> AppResult<std::collections::HashSet<String>> {
+    let external_ids: Vec<String> = lines
+        .iter()
+        .filter_map(|line| line.external_id.clone())
+        .collect();
+    let rows = sqlx::query_as::<_, (Option<String>, Option<String>)>(
+        "SELECT dedupe_hash, external_id
+           FROM bank_transactions
+          WHERE organization_id = $1::uuid
+            AND (dedupe_hash = ANY($2) OR external_id = ANY($3))",
+    )
+    .bind(org_id)
+    .bind(hashes)
+    .bind(&external_ids)
+    .fetch_all(pool)
+    .await
+    .map_err(|error| AppError::from_database_error(&error, "Failed to check for duplicates."))?;
+
+    let stored_ids: std::collections::HashSet<String> =
+        rows.iter().filter_map(|(_, id)| id.clone()).collect();
+    let mut existing: std::collections::HashSet<String> =
+        rows.into_iter().filter_map(|(hash, _)| hash).collect();
+    for (line, hash) in lines.iter().zip(hashes) {
+        if line
+            .external_id
+            .as_ref()
+            .is_some_and(|id| stored_ids.contains(id))
+        {
+            existing.insert(hash.clone());
+        }
+    }
+    Ok(existing)
+}
+
+fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
+    state
+        .db_pool
+        .as_ref()
+        .ok_or_else(|| AppError::Dependency("Database is not configured.".to_string()))
+}
+
+fn value_str(row: &Value, key: &str) -> String {
+    row.as_object()
+        .and_then(|obj| obj.get(key))
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+fn number(row: &Value, key: &str) -> i64 {
+    row.get(key).and_then(Value::as_i64).unwrap_or(0)
+}
+
+fn non_empty_opt(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+}
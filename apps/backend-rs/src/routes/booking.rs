@@ -9,6 +9,10 @@ use serde_json::{json, Map, Value};
 use crate::{
     error::{AppError, AppResult},
     repository::table_service::{create_row, get_row, list_rows},
+    services::{
+        availability::{self, Occupancy, OccupancyKind},
+        stay_restrictions::{self, StayRestriction},
+    },
     state::AppState,
 };
 
@@ -124,50 +128,36 @@ async fn check_availability(
         ));
     }
 
-    // Get reservations in the date range
-    let mut res_filters = Map::new();
-    res_filters.insert("organization_id".to_string(), Value::String(org_id.clone()));
-    if let Some(unit_id) = &query.unit_id {
-        res_filters.insert("unit_id".to_string(), Value::String(unit_id.clone()));
-    }
-    let reservations = list_rows(
+    // Reservations and calendar blocks holding or bordering the range; the
+    // bordering ones decide whether the stay fills an orphan gap.
+    let occupancy = availability::occupied_ranges(
         pool,
-        "reservations",
-        Some(&res_filters),
-        500,
-        0,
-        "check_in_date",
-        true,
+        &org_id,
+        query.unit_id.as_deref(),
+        start - chrono::Duration::days(1),
+        end + chrono::Duration::days(1),
     )
-    .await
-    .unwrap_or_default();
-
-    // Filter to active reservations that overlap with the requested range
-    let active_statuses = ["pending", "confirmed", "checked_in"];
-    let blocked_ranges: Vec<Value> = reservations
+    .await?;
+    let blocked_ranges: Vec<Value> = occupancy
         .iter()
-        .filter_map(|r| {
-            let obj = r.as_object()?;
-            let status = obj.get("status")?.as_str()?;
-            if !active_statuses.contains(&status) {
-                return None;
-            }
-            let ci =
-                NaiveDate::parse_from_str(obj.get("check_in_date")?.as_str()?, "%Y-%m-%d").ok()?;
-            let co =
-                NaiveDate::parse_from_str(obj.get("check_out_date")?.as_str()?, "%Y-%m-%d").ok()?;
-
-            // Check overlap
-            if co <= start || ci >= end {
-                return None;
-            }
-            Some(json!({
-                "unit_id": obj.get("unit_id"),
-                "check_in_date": ci.to_string(),
-                "check_out_date": co.to_string(),
-            }))
+        .filter(|item| item.overlaps(start, end))
+        .map(|item| {
+            json!({
+                    "unit_id": item.unit_id,
+                    "kind": item.kind.as_str(),
+                    "check_in_date": item.start.to_string(),
+                "check_out_date": item.end.to_string(),
+            })
         })
         .collect();
+    let restrictions = stay_restrictions::load_restrictions(
+        pool,
+        Some(&org_id),
+        query.unit_id.as_deref(),
+        start,
+        end,
+    )
+    .await?;
 
     // Determine which units are available for the full range
     let mut unit_filters = Map::new();
@@ -185,23 +175,46 @@ async fn check_availability(
         })
         .collect();
 
-    let available_units: Vec<&Value> = all_units
-        .iter()
-        .filter(|u| {
-            let uid = u
-                .as_object()
-                .and_then(|o| o.get("id"))
-                .and_then(Value::as_str)
-                .unwrap_or_default();
-            !blocked_unit_ids.contains(uid)
-        })
-        .collect();
+    // Free units can still be closed by stay restrictions
+    let mut available_units: Vec<&Value> = Vec::new();
+    let mut restricted_units: Vec<Value> = Vec::new();
+    for unit in &all_units {
+        let uid = val_str(unit, "id");
+        if blocked_unit_ids.contains(&uid) {
+            continue;
+        }
+        let unit_rules: Vec<StayRestriction> = restrictions
+            .iter()
+            .filter(|rule| rule.unit_id == uid)
+            .cloned()
+            .collect();
+        let unit_occupancy: Vec<Occupancy> = occupancy
+            .iter()
+            .filter(|item| item.unit_id == uid)
+            .cloned()
+            .collect();
+        let violations = stay_restrictions::evaluate_stay(
+            &unit_rules,
+            start,
+            end,
+            stay_restrictions::fills_gap(&unit_occupancy, start, end),
+        );
+        if violations.is_empty() {
+            available_units.push(unit);
+        } else {
+            restricted_units.push(json!({
+                "unit_id": uid,
+                "violations": violations.iter().map(|v| v.to_json()).collect::<Vec<_>>(),
+            }));
+        }
+    }
 
     Ok(Json(json!({
         "start": query.start,
         "end": query.end,
         "available_units": available_units,
         "blocked_ranges": blocked_ranges,
+        "restricted_units": restricted_units,
     })))
 }
 
@@ -238,56 +251,8 @@ async fn create_booking(
         ));
     }
 
-    // Check for overlapping active reservations
-    let mut res_filters = Map::new();
-    res_filters.insert("organization_id".to_string(), Value::String(org_id.clone()));
-    res_filters.insert(
-        "unit_id".to_string(),
-        Value::String(payload.unit_id.clone()),
-    );
-    let existing = list_rows(
-        pool,
-        "reservations",
-        Some(&res_filters),
-        500,
-        0,
-        "check_in_date",
-        true,
-    )
-    .await
-    .unwrap_or_default();
-
-    let has_overlap = existing.iter().any(|r| {
-        let obj = match r.as_object() {
-            Some(o) => o,
-            None => return false,
-        };
-        let status = obj
-            .get("status")
-            .and_then(Value::as_str)
-            .unwrap_or_default();
-        if !["pending", "confirmed", "checked_in"].contains(&status) {
-            return false;
-        }
-        let ci = obj
-            .get("check_in_date")
-            .and_then(Value::as_str)
-            .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok());
-        let co = obj
-            .get("check_out_date")
-            .and_then(Value::as_str)
-            .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok());
-        match (ci, co) {
-            (Some(ci), Some(co)) => !(check_out <= ci || check_in >= co),
-            _ => false,
-        }
-    });
-
-    if has_overlap {
-        return Err(AppError::Conflict(
-            "Selected dates overlap with an existing reservation.".to_string(),
-        ));
-    }
+    availability::ensure_available(pool, &payload.unit_id, check_in, check_out, None).await?;
+    stay_restrictions::ensure_stay_allowed(pool, &payload.unit_id, check_in, check_out).await?;
 
     // Create or find guest
     let mut guest_payload = Map::new();
@@ -457,75 +422,29 @@ async fn calendar_grid(
     let grid_start = month_first - chrono::Duration::days(weekday as i64);
     let grid_end = grid_start + chrono::Duration::days(42);
 
-    // Fetch reservations for this unit in the range
-    let mut res_filters = Map::new();
-    res_filters.insert("organization_id".to_string(), Value::String(org_id.clone()));
-    res_filters.insert("unit_id".to_string(), Value::String(query.unit_id.clone()));
-    let reservations = list_rows(
-        pool,
-        "reservations",
-        Some(&res_filters),
-        500,
-        0,
-        "check_in_date",
-        true,
-    )
-    .await
-    .unwrap_or_default();
-
-    let active_statuses = ["pending", "confirmed", "checked_in"];
-
-    // Collect booked date ranges
-    let booked_ranges: Vec<(NaiveDate, NaiveDate)> = reservations
+    let occupancy =
+        availability::occupied_ranges(pool, &org_id, Some(&query.unit_id), grid_start, grid_end)
+            .await?;
+    let booked_ranges: Vec<(NaiveDate, NaiveDate)> = occupancy
         .iter()
-        .filter_map(|r| {
-            let obj = r.as_object()?;
-            let status = obj.get("status")?.as_str()?;
-            if !active_statuses.contains(&status) {
-                return None;
-            }
-            let ci =
-                NaiveDate::parse_from_str(obj.get("check_in_date")?.as_str()?, "%Y-%m-%d").ok()?;
-            let co =
-                NaiveDate::parse_from_str(obj.get("check_out_date")?.as_str()?, "%Y-%m-%d").ok()?;
-            if co <= grid_start || ci >= grid_end {
-                return None;
-            }
-            Some((ci, co))
-        })
+        .filter(|item| item.kind == OccupancyKind::Reservation)
+        .map(|item| (item.start, item.end))
         .collect();
-
-    // Fetch calendar_blocks for this unit
-    let mut block_filters = Map::new();
-    block_filters.insert("organization_id".to_string(), Value::String(org_id));
-    block_filters.insert("unit_id".to_string(), Value::String(query.unit_id));
-    let blocks = list_rows(
-        pool,
-        "calendar_blocks",
-        Some(&block_filters),
-        500,
-        0,
-        "start_date",
-        true,
-    )
-    .await
-    .unwrap_or_default();
-
-    let blocked_ranges: Vec<(NaiveDate, NaiveDate)> = blocks
+    let blocked_ranges: Vec<(NaiveDate, NaiveDate)> = occupancy
         .iter()
-        .filter_map(|b| {
-            let obj = b.as_object()?;
-            let sd =
-                NaiveDate::parse_from_str(obj.get("start_date")?.as_str()?, "%Y-%m-%d").ok()?;
-            let ed = NaiveDate::parse_from_str(obj.get("end_date")?.as_str()?, "%Y-%m-%d").ok()?;
-            if ed <= grid_start || sd >= grid_end {
-                return None;
-            }
-            Some((sd, ed))
-        })
+        .filter(|item| item.kind == OccupancyKind::Block)
+        .map(|item| (item.start, item.end))
         .collect();
+    let restrictions = stay_restrictions::load_restrictions(
+        pool,
+        Some(&org_id),
+        Some(&query.unit_id),
+        grid_start,
+        grid_end,
+    )
+    .await?;
 
-    // Build per-day status array
+    // Build per-day status array with the arrival/departure rules of each day
     let today = chrono::Utc::now().date_naive();
     let mut days = Vec::new();
     let mut current = grid_start;
@@ -545,18 +464,35 @@ async fn calendar_grid(
         } else {
             "available"
         };
+        let rules = stay_restrictions::rules_for(&restrictions, current);
         days.push(json!({
             "date": current.to_string(),
             "status": status,
+            "min_nights": rules.min_nights,
+            "max_nights": rules.max_nights,
+            "closed_to_arrival": rules.closed_to_arrival,
+            "closed_to_departure": rules.closed_to_departure,
         }));
         current += chrono::Duration::days(1);
     }
 
+    let gap_nights: Vec<Value> = stay_restrictions::find_gap_nights(
+        &occupancy,
+        &restrictions,
+        today.max(grid_start),
+        grid_end,
+    )
+    .iter()
+    .filter(|gap| gap.allow_gap_fill)
+    .map(|gap| gap.to_json())
+    .collect();
+
     Ok(Json(json!({
         "month": query.month,
         "grid_start": grid_start.to_string(),
         "grid_end": grid_end.to_string(),
         "days": days,
+        "gap_nights": gap_nights,
     })))
 }
 
@@ -13,15 +13,18 @@ use crate::{
     repository::table_service::{create_row, delete_row, get_row, list_rows, update_row},
     schemas::{
         clamp_limit_in_range, remove_nulls, serialize_to_map, BlockPath, CalendarAvailabilityQuery,
-        CalendarBlocksQuery, CreateCalendarBlockInput, UpdateCalendarBlockInput,
+        CalendarBlocksQuery, CalendarConflictsQuery, CreateCalendarBlockInput,
+        CreateStayRestrictionInput, DismissCalendarConflictInput, StayRestrictionPath,
+        StayRestrictionsQuery, UpdateCalendarBlockInput, UpdateStayRestrictionInput,
+    },
+    services::{
+        audit::write_audit_log, availability, enrichment::enrich_calendar_blocks, permissions,
+        stay_restrictions,
     },
-    services::{audit::write_audit_log, enrichment::enrich_calendar_blocks},
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
-const ACTIVE_BOOKING_STATUSES: &[&str] = &["pending", "confirmed", "checked_in"];
-
 pub fn router() -> axum::Router<AppState> {
     axum::Router::new()
         .route(
@@ -38,6 +41,22 @@ pub fn router() -> axum::Router<AppState> {
                 .patch(update_calendar_block)
                 .delete(delete_calendar_block),
         )
+        .route(
+            "/calendar/conflicts",
+            axum::routing::get(list_calendar_conflicts),
+        )
+        .route(
+            "/calendar/conflicts/dismiss",
+            axum::routing::post(dismiss_calendar_conflict),
+        )
+        .route(
+            "/calendar/restrictions",
+            axum::routing::get(list_stay_restrictions).post(create_stay_restriction),
+        )
+        .route(
+            "/calendar/restrictions/{restriction_id}",
+            axum::routing::patch(update_stay_restriction).delete(delete_stay_restriction),
+        )
 }
 
 async fn calendar_availability(
@@ -52,85 +71,18 @@ async fn calendar_availability(
     let window_start = parse_date(&query.from_date)?;
     let window_end = parse_date(&query.to_date)?;
 
-    let mut unavailable: Vec<(String, String)> = Vec::new();
-
-    // Fetch reservations and calendar blocks in parallel
-    let res_filters = json_map(&[
-        ("organization_id", Value::String(query.org_id.clone())),
-        ("unit_id", Value::String(query.unit_id.clone())),
-    ]);
-    let block_filters = json_map(&[
-        ("organization_id", Value::String(query.org_id.clone())),
-        ("unit_id", Value::String(query.unit_id.clone())),
-    ]);
-    let (reservations, blocks) = tokio::try_join!(
-        list_rows(
-            pool,
-            "reservations",
-            Some(&res_filters),
-            1000,
-            0,
-            "created_at",
-            false
-        ),
-        list_rows(
-            pool,
-            "calendar_blocks",
-            Some(&block_filters),
-            1000,
-            0,
-            "created_at",
-            false
-        ),
-    )?;
-
-    for reservation in reservations {
-        let Some(obj) = reservation.as_object() else {
-            continue;
-        };
-        let status = value_string(obj.get("status")).unwrap_or_default();
-        if !ACTIVE_BOOKING_STATUSES.contains(&status.as_str()) {
-            continue;
-        }
-        let Some(check_in_raw) = value_string(obj.get("check_in_date")) else {
-            continue;
-        };
-        let Some(check_out_raw) = value_string(obj.get("check_out_date")) else {
-            continue;
-        };
-        let Ok(start) = parse_date(&check_in_raw) else {
-            continue;
-        };
-        let Ok(end) = parse_date(&check_out_raw) else {
-            continue;
-        };
-
-        if overlaps(window_start, window_end, start, end) {
-            unavailable.push((check_in_raw, check_out_raw));
-        }
-    }
-
-    for block in blocks {
-        let Some(obj) = block.as_object() else {
-            continue;
-        };
-        let Some(starts_on_raw) = value_string(obj.get("starts_on")) else {
-            continue;
-        };
-        let Some(ends_on_raw) = value_string(obj.get("ends_on")) else {
-            continue;
-        };
-        let Ok(start) = parse_date(&starts_on_raw) else {
-            continue;
-        };
-        let Ok(end) = parse_date(&ends_on_raw) else {
-            continue;
-        };
-
-        if overlaps(window_start, window_end, start, end) {
-            unavailable.push((starts_on_raw, ends_on_raw));
-        }
-    }
+    let occupancy = availability::occupied_ranges(
+        pool,
+        &query.org_id,
+        Some(&query.unit_id),
+        window_start,
+        window_end,
+    )
+    .await?;
+    let mut unavailable: Vec<(String, String)> = occupancy
+        .iter()
+        .map(|item| (item.start.to_string(), item.end.to_string()))
+        .collect();
 
     unavailable.sort_unstable();
     let periods = unavailable
@@ -138,14 +90,100 @@ async fn calendar_availability(
         .map(|(from, to)| json!({ "from": from, "to": to }))
         .collect::<Vec<_>>();
 
+    let restrictions = stay_restrictions::load_restrictions(
+        pool,
+        Some(&query.org_id),
+        Some(&query.unit_id),
+        window_start,
+        window_end,
+    )
+    .await?;
+    let gap_nights =
+        stay_restrictions::find_gap_nights(&occupancy, &restrictions, window_start, window_end)
+            .iter()
+            .map(|gap| gap.to_json())
+            .collect::<Vec<_>>();
+
     Ok(Json(json!({
         "unit_id": query.unit_id,
         "from": query.from_date,
         "to": query.to_date,
-        "unavailable_periods": periods
+        "unavailable_periods": periods,
+        "restricted_days": stay_restrictions::restricted_days(&restrictions, window_start, window_end),
+        "gap_nights": gap_nights,
     })))
 }
 
+/// Conflicts inbox: overlapping reservations and blocks on the org's units,
+/// with a suggested resolution for each pair.
+async fn list_calendar_conflicts(
+    State(state): State<AppState>,
+    Query(query): Query<CalendarConflictsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let unit_id = non_empty_opt(query.unit_id.as_deref());
+    let conflicts = availability::list_conflicts(
+        pool,
+        &query.org_id,
+        &availability::ConflictFilters {
+            unit_id: unit_id.as_deref(),
+            include_dismissed: query.include_dismissed,
+            from: None,
+            limit: clamp_limit_in_range(query.limit, 1, 500),
+        },
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "data": conflicts,
+        "count": conflicts.len(),
+    })))
+}
+
+async fn dismiss_calendar_conflict(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<DismissCalendarConflictInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::CALENDAR_EDIT,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let dismissal = availability::dismiss_conflict(
+        pool,
+        &payload.organization_id,
+        payload.first_id.trim(),
+        payload.second_id.trim(),
+        non_empty_opt(payload.note.as_deref()).as_deref(),
+        &user_id,
+    )
+    .await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        "dismiss_conflict",
+        "availability_conflict_dismissals",
+        Some(&value_str(&dismissal, "id")),
+        None,
+        Some(dismissal.clone()),
+    )
+    .await;
+
+    Ok(Json(dismissal))
+}
+
 async fn list_calendar_blocks(
     State(state): State<AppState>,
     Query(query): Query<CalendarBlocksQuery>,
@@ -184,11 +222,11 @@ async fn create_calendar_block(
     Json(payload): Json<CreateCalendarBlockInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
+    assert_permission(
         &state,
         &user_id,
         &payload.organization_id,
-        &["owner_admin", "operator"],
+        permissions::CALENDAR_EDIT,
     )
     .await?;
     let pool = db_pool(&state)?;
@@ -201,45 +239,7 @@ async fn create_calendar_block(
         ));
     }
 
-    let existing_blocks = list_rows(
-        pool,
-        "calendar_blocks",
-        Some(&json_map(&[
-            (
-                "organization_id",
-                Value::String(payload.organization_id.clone()),
-            ),
-            ("unit_id", Value::String(payload.unit_id.clone())),
-        ])),
-        1000,
-        0,
-        "created_at",
-        false,
-    )
-    .await?;
-
-    for block in existing_blocks {
-        let Some(obj) = block.as_object() else {
-            continue;
-        };
-        let Some(existing_starts) = value_string(obj.get("starts_on")) else {
-            continue;
-        };
-        let Some(existing_ends) = value_string(obj.get("ends_on")) else {
-            continue;
-        };
-        let Ok(start) = parse_date(&existing_starts) else {
-            continue;
-        };
-        let Ok(end) = parse_date(&existing_ends) else {
-            continue;
-        };
-        if overlaps(starts_on, ends_on, start, end) {
-            return Err(AppError::Conflict(
-                "Calendar block overlaps an existing block.".to_string(),
-            ));
-        }
-    }
+    availability::ensure_available(pool, &payload.unit_id, starts_on, ends_on, None).await?;
 
     let record = remove_nulls(serialize_to_map(&payload));
     let created = create_row(pool, "calendar_blocks", &record).await?;
@@ -289,7 +289,7 @@ async fn update_calendar_block(
 
     let record = get_row(pool, "calendar_blocks", &path.block_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::CALENDAR_EDIT).await?;
 
     let patch = remove_nulls(serialize_to_map(&payload));
     if patch.is_empty() {
@@ -321,6 +321,14 @@ async fn update_calendar_block(
             "ends_on must be later than starts_on.".to_string(),
         ));
     }
+    availability::ensure_available(
+        pool,
+        &value_str(&record, "unit_id"),
+        next_starts,
+        next_ends,
+        Some(&path.block_id),
+    )
+    .await?;
 
     let updated = update_row(pool, "calendar_blocks", &path.block_id, &patch, "id").await?;
 
@@ -352,7 +360,7 @@ async fn delete_calendar_block(
 
     let record = get_row(pool, "calendar_blocks", &path.block_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::CALENDAR_EDIT).await?;
 
     let deleted = delete_row(pool, "calendar_blocks", &path.block_id, "id").await?;
 
@@ -371,8 +379,199 @@ async fn delete_calendar_block(
     Ok(Json(deleted))
 }
 
-fn overlaps(start_a: NaiveDate, end_a: NaiveDate, start_b: NaiveDate, end_b: NaiveDate) -> bool {
-    !(end_a <= start_b || start_a >= end_b)
+async fn list_stay_restrictions(
+    State(state): State<AppState>,
+    Query(query): Query<StayRestrictionsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(query.org_id.clone()),
+    );
+    if let Some(unit_id) = non_empty_opt(query.unit_id.as_deref()) {
+        filters.insert("unit_id".to_string(), Value::String(unit_id));
+    }
+    if !query.include_inactive {
+        filters.insert("is_active".to_string(), Value::Bool(true));
+    }
+
+    let rows = list_rows(
+        pool,
+        "stay_restrictions",
+        Some(&filters),
+        clamp_limit_in_range(query.limit, 1, 1000),
+        0,
+        "created_at",
+        true,
+    )
+    .await?;
+    Ok(Json(json!({ "data": rows })))
+}
+
+async fn create_stay_restriction(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateStayRestrictionInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::CALENDAR_EDIT,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let mut record = remove_nulls(serialize_to_map(&payload));
+    validate_stay_restriction(&record)?;
+    record.insert(
+        "created_by_user_id".to_string(),
+        Value::String(user_id.clone()),
+    );
+    let created = create_row(pool, "stay_restrictions", &record).await?;
+    let entity_id = value_str(&created, "id");
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        "create",
+        "stay_restrictions",
+        Some(&entity_id),
+        None,
+        Some(created.clone()),
+    )
+    .await;
+
+    Ok((axum::http::StatusCode::CREATED, Json(created)))
+}
+
+async fn update_stay_restriction(
+    State(state): State<AppState>,
+    Path(path): Path<StayRestrictionPath>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateStayRestrictionInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let record = get_row(pool, "stay_restrictions", &path.restriction_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::CALENDAR_EDIT).await?;
+
+    let patch = remove_nulls(serialize_to_map(&payload));
+    if patch.is_empty() {
+        return Ok(Json(record));
+    }
+    let mut next = record.as_object().cloned().unwrap_or_default();
+    next.extend(patch.clone());
+    validate_stay_restriction(&next)?;
+
+    let updated = update_row(
+        pool,
+        "stay_restrictions",
+        &path.restriction_id,
+        &patch,
+        "id",
+    )
+    .await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "update",
+        "stay_restrictions",
+        Some(&path.restriction_id),
+        Some(record),
+        Some(updated.clone()),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+async fn delete_stay_restriction(
+    State(state): State<AppState>,
+    Path(path): Path<StayRestrictionPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let record = get_row(pool, "stay_restrictions", &path.restriction_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::CALENDAR_EDIT).await?;
+
+    let deleted = delete_row(pool, "stay_restrictions", &path.restriction_id, "id").await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "delete",
+        "stay_restrictions",
+        Some(&path.restriction_id),
+        Some(deleted.clone()),
+        None,
+    )
+    .await;
+
+    Ok(Json(deleted))
+}
+
+/// Readable errors for what the table's CHECK constraints would reject.
+fn validate_stay_restriction(record: &Map<String, Value>) -> AppResult<()> {
+    let date = |key: &str| {
+        record
+            .get(key)
+            .and_then(Value::as_str)
+            .map(parse_date)
+            .transpose()
+    };
+    if let (Some(starts_on), Some(ends_on)) = (date("starts_on")?, date("ends_on")?) {
+        if ends_on < starts_on {
+            return Err(AppError::BadRequest(
+                "ends_on must be on or after starts_on.".to_string(),
+            ));
+        }
+    }
+
+    let days = record
+        .get("days_of_week")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    if days
+        .iter()
+        .any(|day| !day.as_i64().is_some_and(|day| (1..=7).contains(&day)))
+    {
+        return Err(AppError::BadRequest(
+            "days_of_week must hold ISO weekdays (1 = Monday … 7 = Sunday).".to_string(),
+        ));
+    }
+
+    let nights = |key: &str| record.get(key).and_then(Value::as_i64);
+    let (min_nights, max_nights) = (nights("min_nights"), nights("max_nights"));
+    if min_nights.is_some_and(|value| value < 1) || max_nights.is_some_and(|value| value < 1) {
+        return Err(AppError::BadRequest(
+            "min_nights and max_nights must be at least 1.".to_string(),
+        ));
+    }
+    if let (Some(min_nights), Some(max_nights)) = (min_nights, max_nights) {
+        if max_nights < min_nights {
+            return Err(AppError::BadRequest(
+                "max_nights must be greater than or equal to min_nights.".to_string(),
+            ));
+        }
+    }
+    Ok(())
 }
 
 fn parse_date(value: &str) -> AppResult<NaiveDate> {
@@ -396,25 +595,9 @@ fn value_str(row: &Value, key: &str) -> String {
         .unwrap_or_default()
 }
 
-fn value_string(value: Option<&Value>) -> Option<String> {
-    value
-        .and_then(Value::as_str)
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-        .map(ToOwned::to_owned)
-}
-
 fn non_empty_opt(value: Option<&str>) -> Option<String> {
     value
         .map(str::trim)
         .filter(|item| !item.is_empty())
         .map(ToOwned::to_owned)
 }
-
-fn json_map(entries: &[(&str, Value)]) -> Map<String, Value> {
-    let mut map = Map::new();
-    for (key, value) in entries {
-        map.insert((*key).to_string(), value.clone());
-    }
-    map
-}
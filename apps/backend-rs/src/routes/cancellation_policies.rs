@@ -15,8 +15,9 @@ use crate::{
         CancellationPolicyPath, CreateCancellationPolicyInput, UpdateCancellationPolicyInput,
     },
     services::audit::write_audit_log,
+    services::permissions,
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
 pub fn router() -> axum::Router<AppState> {
@@ -66,11 +67,11 @@ async fn create_cancellation_policy(
     Json(payload): Json<CreateCancellationPolicyInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
+    assert_permission(
         &state,
         &user_id,
         &payload.organization_id,
-        &["owner_admin", "operator"],
+        permissions::CANCELLATION_POLICIES_EDIT,
     )
     .await?;
     let pool = db_pool(&state)?;
@@ -120,7 +121,13 @@ async fn update_cancellation_policy(
 
     let record = get_row(pool, "cancellation_policies", &path.policy_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &org_id,
+        permissions::CANCELLATION_POLICIES_EDIT,
+    )
+    .await?;
 
     let patch = remove_nulls(serialize_to_map(&payload));
     if patch.is_empty() {
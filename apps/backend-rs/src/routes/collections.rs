@@ -13,15 +13,19 @@ use crate::{
     repository::table_service::{create_row, get_row, list_rows, update_row},
     schemas::{
         clamp_limit_in_range, remove_nulls, serialize_to_map, CollectionPath, CollectionsQuery,
-        CreateCollectionInput, MarkCollectionPaidInput,
+        CreateCollectionInput, MarkCollectionPaidInput, RenderPdfInput,
+    },
+    services::{
+        analytics::write_analytics_event,
+        audit::write_audit_log,
+        payment_ledger::{self, NewPayment, PaymentSource},
+        pdf_documents, permissions,
+        workflows::fire_trigger,
     },
-    services::{analytics::write_analytics_event, audit::write_audit_log, workflows::fire_trigger},
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
-const COLLECTION_EDIT_ROLES: &[&str] = &["owner_admin", "operator", "accountant"];
-
 pub fn router() -> axum::Router<AppState> {
     axum::Router::new()
         .route(
@@ -36,6 +40,10 @@ pub fn router() -> axum::Router<AppState> {
             "/collections/{collection_id}/mark-paid",
             axum::routing::post(mark_collection_paid),
         )
+        .route(
+            "/collections/{collection_id}/receipt",
+            axum::routing::post(render_collection_receipt),
+        )
 }
 
 async fn list_collections(
@@ -101,11 +109,11 @@ async fn create_collection(
     ensure_lease_collections_enabled(&state)?;
 
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
+    assert_permission(
         &state,
         &user_id,
         &payload.organization_id,
-        COLLECTION_EDIT_ROLES,
+        permissions::COLLECTIONS_EDIT,
     )
     .await?;
     let pool = db_pool(&state)?;
@@ -138,7 +146,15 @@ async fn create_collection(
     )
     .await;
 
-    refresh_lease_status(pool, &payload.lease_id).await?;
+    // Credit left by earlier overpayments covers a collection that is
+    // already due.
+    let applied = payment_ledger::apply_lease_credit(pool, &payload.lease_id).await?;
+    payment_ledger::refresh_lease_delinquency(pool, &payload.lease_id).await?;
+    let created = if applied.is_empty() {
+        created
+    } else {
+        get_row(pool, "collection_records", &entity_id, "id").await?
+    };
 
     let mut enriched = enrich_collection_rows(pool, vec![created]).await?;
     Ok((
@@ -180,60 +196,100 @@ async fn mark_collection_paid(
 
     let record = get_row(pool, "collection_records", &path.collection_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, COLLECTION_EDIT_ROLES).await?;
-
-    let now_iso = Utc::now().to_rfc3339();
+    assert_permission(&state, &user_id, &org_id, permissions::COLLECTIONS_EDIT).await?;
 
-    let mut patch = Map::new();
-    patch.insert("status".to_string(), Value::String("paid".to_string()));
-    patch.insert(
-        "paid_at".to_string(),
-        Value::String(payload.paid_at.unwrap_or(now_iso)),
-    );
-    if let Some(payment_method) = payload.payment_method {
-        patch.insert("payment_method".to_string(), Value::String(payment_method));
+    if value_str(&record, "status") == "waived" {
+        return Err(AppError::Conflict(
+            "A waived collection cannot be paid.".to_string(),
+        ));
     }
-    if let Some(payment_reference) = payload.payment_reference {
+
+    // Payments go through the allocation ledger so partial payments leave
+    // a balance and overpayments become credit for the next collection.
+    let balance = number(&record, "amount") - number(&record, "amount_paid");
+    let amount = payload.amount.unwrap_or(balance);
+    let mut recorded = None;
+    let updated = if balance > 0.005 && value_str(&record, "status") != "paid" {
+        let payment = payment_ledger::record_payment(
+            pool,
+            &NewPayment {
+                organization_id: org_id.clone(),
+                lease_id: value_str(&record, "lease_id"),
+                source: PaymentSource::Manual,
+                amount,
+                currency: value_str(&record, "currency"),
+                payment_method: payload.payment_method.clone(),
+                received_at: payload.paid_at.clone(),
+                reference: payload.payment_reference.clone(),
+                external_id: None,
+                bank_transaction_id: None,
+                notes: payload.notes.clone(),
+                created_by_user_id: Some(user_id.clone()),
+            },
+            std::slice::from_ref(&path.collection_id),
+        )
+        .await?;
+        recorded = Some(payment);
+        get_row(pool, "collection_records", &path.collection_id, "id").await?
+    } else {
+        let now_iso = Utc::now().to_rfc3339();
+        let mut patch = Map::new();
+        patch.insert("status".to_string(), Value::String("paid".to_string()));
         patch.insert(
-            "payment_reference".to_string(),
-            Value::String(payment_reference),
+            "paid_at".to_string(),
+            Value::String(payload.paid_at.clone().unwrap_or(now_iso)),
         );
-    }
-    if let Some(notes) = payload.notes {
-        patch.insert("notes".to_string(), Value::String(notes));
-    }
+        if let Some(payment_method) = payload.payment_method.clone() {
+            patch.insert("payment_method".to_string(), Value::String(payment_method));
+        }
+        if let Some(payment_reference) = payload.payment_reference.clone() {
+            patch.insert(
+                "payment_reference".to_string(),
+                Value::String(payment_reference),
+            );
+        }
+        if let Some(notes) = payload.notes.clone() {
+            patch.insert("notes".to_string(), Value::String(notes));
+        }
 
-    let updated = update_row(
-        pool,
-        "collection_records",
-        &path.collection_id,
-        &patch,
-        "id",
-    )
-    .await?;
+        let updated = update_row(
+            pool,
+            "collection_records",
+            &path.collection_id,
+            &patch,
+            "id",
+        )
+        .await?;
 
-    if let Some(lease_charge_id) = updated
-        .as_object()
-        .and_then(|obj| obj.get("lease_charge_id"))
-        .and_then(Value::as_str)
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-    {
-        let mut lease_patch = Map::new();
-        lease_patch.insert("status".to_string(), Value::String("paid".to_string()));
-        let _ = update_row(pool, "lease_charges", lease_charge_id, &lease_patch, "id").await;
-    }
+        if let Some(lease_charge_id) = updated
+            .as_object()
+            .and_then(|obj| obj.get("lease_charge_id"))
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+        {
+            let mut lease_patch = Map::new();
+            lease_patch.insert("status".to_string(), Value::String("paid".to_string()));
+            let _ = update_row(pool, "lease_charges", lease_charge_id, &lease_patch, "id").await;
+        }
 
-    let lease_id = value_str(&updated, "lease_id");
-    if !lease_id.is_empty() {
-        refresh_lease_status(pool, &lease_id).await?;
-    }
+        let lease_id = value_str(&updated, "lease_id");
+        if !lease_id.is_empty() {
+            payment_ledger::refresh_lease_delinquency(pool, &lease_id).await?;
+        }
+        updated
+    };
+    let fully_paid = value_str(&updated, "status") == "paid";
 
     write_audit_log(
         state.db_pool.as_ref(),
         Some(&org_id),
         Some(&user_id),
-        "status_transition",
+        if fully_paid {
+            "status_transition"
+        } else {
+            "partial_payment"
+        },
         "collection_records",
         Some(&path.collection_id),
         Some(record),
@@ -274,7 +330,11 @@ async fn mark_collection_paid(
     write_analytics_event(
         state.db_pool.as_ref(),
         Some(&org_id),
-        "collection_paid",
+        if fully_paid {
+            "collection_paid"
+        } else {
+            "collection_partially_paid"
+        },
         Some(Value::Object(analytics_payload)),
     )
     .await;
@@ -361,59 +421,53 @@ async fn mark_collection_paid(
     .await;
 
     let mut enriched = enrich_collection_rows(pool, vec![updated]).await?;
-    Ok(Json(
-        enriched.pop().unwrap_or_else(|| Value::Object(Map::new())),
-    ))
+    let mut item = enriched.pop().unwrap_or_else(|| Value::Object(Map::new()));
+    if let (Some(obj), Some(recorded)) = (item.as_object_mut(), recorded) {
+        obj.insert("payment".to_string(), recorded.to_json());
+    }
+    Ok(Json(item))
 }
 
-async fn refresh_lease_status(pool: &sqlx::PgPool, lease_id: &str) -> AppResult<()> {
-    let lease = get_row(pool, "leases", lease_id, "id").await?;
-    let status = value_str(&lease, "lease_status");
-    if status != "active" && status != "delinquent" {
-        return Ok(());
-    }
+/// Render the payment receipt PDF for a paid collection and link it from
+/// the collection record.
+async fn render_collection_receipt(
+    State(state): State<AppState>,
+    Path(path): Path<CollectionPath>,
+    headers: HeaderMap,
+    payload: Option<Json<RenderPdfInput>>,
+) -> AppResult<Json<Value>> {
+    ensure_lease_collections_enabled(&state)?;
 
-    let today_iso = Utc::now().date_naive().to_string();
-    let unpaid = list_rows(
-        pool,
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+    let payload = payload.map(|Json(payload)| payload).unwrap_or_default();
+
+    let record = get_row(pool, "collection_records", &path.collection_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::COLLECTIONS_EDIT).await?;
+
+    let (updated, stored) =
+        pdf_documents::generate_collection_receipt(&state, pool, &record, payload.lang.as_deref())
+            .await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "render_receipt",
         "collection_records",
-        Some(&json_map(&[
-            ("lease_id", Value::String(lease_id.to_string())),
-            (
-                "status",
-                Value::Array(
-                    ["scheduled", "pending", "late"]
-                        .iter()
-                        .map(|value| Value::String((*value).to_string()))
-                        .collect(),
-                ),
-            ),
-        ])),
-        500,
-        0,
-        "created_at",
-        false,
+        Some(&path.collection_id),
+        Some(record),
+        Some(updated.clone()),
     )
-    .await?;
-
-    let has_overdue = unpaid.iter().any(|row| {
-        row.as_object()
-            .and_then(|obj| obj.get("due_date"))
-            .and_then(Value::as_str)
-            .is_some_and(|due_date| !due_date.is_empty() && due_date < today_iso.as_str())
-    });
+    .await;
 
-    let next_status = if has_overdue { "delinquent" } else { "active" };
-    if next_status != status {
-        let mut patch = Map::new();
-        patch.insert(
-            "lease_status".to_string(),
-            Value::String(next_status.to_string()),
-        );
-        update_row(pool, "leases", lease_id, &patch, "id").await?;
+    let mut enriched = enrich_collection_rows(pool, vec![updated]).await?;
+    let mut item = enriched.pop().unwrap_or_else(|| Value::Object(Map::new()));
+    if let Some(obj) = item.as_object_mut() {
+        obj.insert("object_key".to_string(), Value::String(stored.object_key));
     }
-
-    Ok(())
+    Ok(Json(item))
 }
 
 async fn enrich_collection_rows(pool: &sqlx::PgPool, rows: Vec<Value>) -> AppResult<Vec<Value>> {
@@ -525,6 +579,14 @@ fn value_str(row: &Value, key: &str) -> String {
         .unwrap_or_default()
 }
 
+fn number(row: &Value, key: &str) -> f64 {
+    match row.as_object().and_then(|obj| obj.get(key)) {
+        Some(Value::Number(value)) => value.as_f64().unwrap_or(0.0),
+        Some(Value::String(value)) => value.trim().parse().unwrap_or(0.0),
+        _ => 0.0,
+    }
+}
+
 fn non_empty_opt(value: Option<&str>) -> Option<String> {
     value
         .map(str::trim)
@@ -14,9 +14,14 @@ use crate::{
         clamp_limit, remove_nulls, serialize_to_map, ContractTemplatePath, ContractTemplatesQuery,
         CreateContractTemplateInput, RenderContractInput, UpdateContractTemplateInput,
     },
-    services::audit::write_audit_log,
+    services::{
+        audit::write_audit_log,
+        pdf_documents::{self, Lang},
+        permissions,
+        storage::StorageNamespace,
+    },
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
 pub fn router() -> axum::Router<AppState> {
@@ -79,7 +84,13 @@ async fn create_template(
     Json(payload): Json<CreateContractTemplateInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &payload.organization_id, &["owner_admin"]).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::CONTRACT_TEMPLATES_EDIT,
+    )
+    .await?;
     let pool = db_pool(&state)?;
 
     let mut record = Map::new();
@@ -151,7 +162,13 @@ async fn update_template(
         .and_then(Value::as_str)
         .unwrap_or("")
         .to_string();
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin"]).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &org_id,
+        permissions::CONTRACT_TEMPLATES_EDIT,
+    )
+    .await?;
 
     let mut patch = remove_nulls(serialize_to_map(&payload));
 
@@ -198,7 +215,13 @@ async fn delete_template(
         .and_then(Value::as_str)
         .unwrap_or("")
         .to_string();
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin"]).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &org_id,
+        permissions::CONTRACT_TEMPLATES_EDIT,
+    )
+    .await?;
 
     delete_row(pool, "contract_templates", &path.template_id, "id").await?;
 
@@ -356,8 +379,132 @@ async fn render_template(
         rendered = rendered.replace(&format!("{{{{{key}}}}}"), value);
     }
 
-    Ok(Json(json!({
-        "rendered": rendered,
-        "variables_used": vars,
-    })))
+    let format = payload
+        .format
+        .as_deref()
+        .map(|value| value.trim().to_ascii_lowercase())
+        .unwrap_or_else(|| "text".to_string());
+    match format.as_str() {
+        "text" => Ok(Json(json!({
+            "rendered": rendered,
+            "variables_used": vars,
+        }))),
+        "pdf" => {
+            let document = store_contract_pdf(
+                &state, pool, &user_id, &org_id, &template, &lease_obj, &payload, &rendered,
+            )
+            .await?;
+            Ok(Json(json!({
+                "rendered": rendered,
+                "variables_used": vars,
+                "document": document,
+            })))
+        }
+        _ => Err(AppError::BadRequest(
+            "format must be 'text' or 'pdf'.".to_string(),
+        )),
+    }
+}
+
+/// Render the contract as a branded PDF and attach it to the lease as a
+/// `lease_contract` document. The language is the request's, else the
+/// template's, else the organization's.
+#[allow(clippy::too_many_arguments)]
+async fn store_contract_pdf(
+    state: &AppState,
+    pool: &sqlx::PgPool,
+    user_id: &str,
+    org_id: &str,
+    template: &Value,
+    lease: &Map<String, Value>,
+    payload: &RenderContractInput,
+    rendered: &str,
+) -> AppResult<Value> {
+    assert_permission(state, user_id, org_id, permissions::CONTRACTS_GENERATE).await?;
+    let text = |obj: Option<&Map<String, Value>>, key: &str| {
+        obj.and_then(|o| o.get(key))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+    if text(Some(lease), "organization_id") != org_id {
+        return Err(AppError::BadRequest(
+            "Lease does not belong to the template's organization.".to_string(),
+        ));
+    }
+
+    let branding = pdf_documents::load_branding(pool, &state.http_client, org_id).await?;
+    let template_language = text(template.as_object(), "language");
+    let requested = payload.lang.as_deref().or_else(|| {
+        Lang::parse(&template_language)
+            .is_some()
+            .then_some(template_language.as_str())
+    });
+    let lang = branding.lang_for(requested)?;
+
+    let title = text(template.as_object(), "name");
+    let tenant_name = text(Some(lease), "tenant_full_name");
+    let bytes = pdf_documents::render_contract(&branding, lang, &title, rendered, &tenant_name);
+    let stored = pdf_documents::store_pdf(
+        &state.config,
+        StorageNamespace::Documents,
+        org_id,
+        "contracts",
+        &payload.lease_id,
+        bytes,
+    )
+    .await?;
+
+    let file_stem: String = format!("{title} {tenant_name}")
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    let mut record = Map::new();
+    record.insert(
+        "organization_id".to_string(),
+        Value::String(org_id.to_string()),
+    );
+    record.insert(
+        "entity_type".to_string(),
+        Value::String("lease".to_string()),
+    );
+    record.insert(
+        "entity_id".to_string(),
+        Value::String(payload.lease_id.clone()),
+    );
+    record.insert(
+        "file_name".to_string(),
+        Value::String(format!("{}.pdf", file_stem.trim_matches('-'))),
+    );
+    record.insert("file_url".to_string(), Value::String(stored.public_url));
+    record.insert("file_size_bytes".to_string(), json!(stored.size_bytes));
+    record.insert(
+        "mime_type".to_string(),
+        Value::String("application/pdf".to_string()),
+    );
+    record.insert(
+        "category".to_string(),
+        Value::String("lease_contract".to_string()),
+    );
+    record.insert(
+        "uploaded_by_user_id".to_string(),
+        Value::String(user_id.to_string()),
+    );
+    let created = create_row(pool, "documents", &record).await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(org_id),
+        Some(user_id),
+        "render_pdf",
+        "documents",
+        created.get("id").and_then(Value::as_str),
+        None,
+        Some(created.clone()),
+    )
+    .await;
+
+    Ok(created)
 }
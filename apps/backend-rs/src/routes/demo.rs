@@ -12,8 +12,9 @@ use crate::{
     auth::require_user_id,
     error::{AppError, AppResult},
     repository::table_service::{create_row, list_rows},
+    services::permissions,
     state::AppState,
-    tenancy::assert_org_role,
+    tenancy::assert_permission,
 };
 
 pub fn router() -> axum::Router<AppState> {
@@ -63,7 +64,7 @@ async fn seed_demo(
         AppError::BadRequest("Invalid organization_id (expected UUID).".to_string())
     })?;
 
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::ORGANIZATION_MANAGE).await?;
 
     // Only seed into an empty org.
     let existing = list_rows(
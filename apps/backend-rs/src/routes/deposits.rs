@@ -11,9 +11,9 @@ use crate::{
     auth::require_user_id,
     error::{AppError, AppResult},
     repository::table_service::{create_row, get_row, update_row},
-    services::audit::write_audit_log,
+    services::{audit::write_audit_log, general_ledger, permissions},
     state::AppState,
-    tenancy::assert_org_role,
+    tenancy::assert_permission,
 };
 
 #[derive(Deserialize)]
@@ -68,11 +68,11 @@ async fn get_deposit_status(
 
     let reservation = get_row(pool, "reservations", &path.reservation_id, "id").await?;
     let org_id = value_str(&reservation, "organization_id");
-    assert_org_role(
+    assert_permission(
         &state,
         &user_id,
         &org_id,
-        &["owner_admin", "operator", "accountant"],
+        permissions::RESERVATION_DEPOSITS_VIEW,
     )
     .await?;
 
@@ -109,7 +109,13 @@ async fn collect_deposit(
 
     let reservation = get_row(pool, "reservations", &payload.reservation_id, "id").await?;
     let org_id = value_str(&reservation, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &org_id,
+        permissions::RESERVATION_DEPOSITS_EDIT,
+    )
+    .await?;
 
     let current_status = value_str(&reservation, "deposit_status");
     if current_status != "none" && current_status != "pending" {
@@ -142,6 +148,7 @@ async fn collect_deposit(
         &user_id,
     )
     .await?;
+    general_ledger::record_deposit_event(pool, &updated, "collected", Some(&user_id)).await;
 
     write_audit_log(
         state.db_pool.as_ref(),
@@ -171,7 +178,13 @@ async fn hold_deposit(
 
     let reservation = get_row(pool, "reservations", &payload.reservation_id, "id").await?;
     let org_id = value_str(&reservation, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &org_id,
+        permissions::RESERVATION_DEPOSITS_EDIT,
+    )
+    .await?;
 
     let current_status = value_str(&reservation, "deposit_status");
     if current_status != "collected" {
@@ -234,7 +247,13 @@ async fn release_deposit(
 
     let reservation = get_row(pool, "reservations", &path.reservation_id, "id").await?;
     let org_id = value_str(&reservation, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &org_id,
+        permissions::RESERVATION_DEPOSITS_EDIT,
+    )
+    .await?;
 
     let current_status = value_str(&reservation, "deposit_status");
     if current_status != "held" && current_status != "collected" {
@@ -268,6 +287,7 @@ async fn release_deposit(
         &user_id,
     )
     .await?;
+    general_ledger::record_deposit_event(pool, &updated, "released", Some(&user_id)).await;
 
     write_audit_log(
         state.db_pool.as_ref(),
@@ -297,7 +317,13 @@ async fn forfeit_deposit(
 
     let reservation = get_row(pool, "reservations", &path.reservation_id, "id").await?;
     let org_id = value_str(&reservation, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &org_id,
+        permissions::RESERVATION_DEPOSITS_EDIT,
+    )
+    .await?;
 
     let current_status = value_str(&reservation, "deposit_status");
     if current_status != "held" && current_status != "collected" {
@@ -331,6 +357,7 @@ async fn forfeit_deposit(
         &user_id,
     )
     .await?;
+    general_ledger::record_deposit_event(pool, &updated, "forfeited", Some(&user_id)).await;
 
     write_audit_log(
         state.db_pool.as_ref(),
@@ -458,6 +485,7 @@ pub async fn auto_release_deposit_on_checkout(pool: &sqlx::PgPool, reservation:
         Value::String(Utc::now().to_rfc3339()),
     );
     let _ = create_row(pool, "escrow_events", &event).await;
+    general_ledger::record_deposit_event(pool, reservation, "released", None).await;
 }
 
 fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
@@ -13,13 +13,11 @@ use crate::{
     error::{AppError, AppResult},
     repository::table_service::{create_row, delete_row, get_row, list_rows},
     schemas::clamp_limit_in_range,
-    services::{audit::write_audit_log, embeddings},
+    services::{audit::write_audit_log, embeddings, permissions},
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
-const DOC_EDIT_ROLES: &[&str] = &["owner_admin", "operator", "accountant"];
-
 pub fn router() -> axum::Router<AppState> {
     axum::Router::new()
         .route(
@@ -153,7 +151,13 @@ async fn create_document(
     Json(payload): Json<CreateDocumentInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &payload.organization_id, DOC_EDIT_ROLES).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::DOCUMENTS_EDIT,
+    )
+    .await?;
     let pool = db_pool(&state)?;
 
     let mut record = Map::new();
@@ -210,7 +214,7 @@ async fn delete_document(
 
     let record = get_row(pool, "documents", &path.document_id, "id").await?;
     let org_id = val_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, DOC_EDIT_ROLES).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::DOCUMENTS_EDIT).await?;
 
     delete_row(pool, "documents", &path.document_id, "id").await?;
 
@@ -271,7 +275,13 @@ async fn process_document(
     Json(payload): Json<ProcessDocumentInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &payload.organization_id, DOC_EDIT_ROLES).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::DOCUMENTS_EDIT,
+    )
+    .await?;
     let pool = db_pool(&state)?;
 
     // Verify document exists and belongs to the org
@@ -480,7 +490,13 @@ async fn create_knowledge_document(
     Json(payload): Json<CreateKnowledgeDocInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &payload.organization_id, DOC_EDIT_ROLES).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::DOCUMENTS_EDIT,
+    )
+    .await?;
     let pool = db_pool(&state)?;
 
     let mut record = Map::new();
@@ -539,7 +555,7 @@ async fn delete_knowledge_document(
 
     let record = get_row(pool, "knowledge_documents", &path.document_id, "id").await?;
     let org_id = val_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, DOC_EDIT_ROLES).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::DOCUMENTS_EDIT).await?;
 
     // Chunks cascade-delete via FK
     delete_row(pool, "knowledge_documents", &path.document_id, "id").await?;
@@ -776,7 +792,13 @@ async fn seed_knowledge_documents(
     Json(payload): Json<SeedInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &payload.organization_id, DOC_EDIT_ROLES).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::DOCUMENTS_EDIT,
+    )
+    .await?;
     let pool = db_pool(&state)?;
 
     let mut seeded = 0u32;
@@ -902,7 +924,7 @@ async fn upload_knowledge_document(
 
     let org_id =
         org_id.ok_or_else(|| AppError::BadRequest("organization_id is required".to_string()))?;
-    assert_org_role(&state, &user_id, &org_id, DOC_EDIT_ROLES).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::DOCUMENTS_EDIT).await?;
 
     let content = file_content.filter(|c| !c.trim().is_empty()).ok_or_else(|| {
         AppError::BadRequest("No content could be extracted from the file. Supported formats: PDF, DOCX, TXT, MD.".to_string())
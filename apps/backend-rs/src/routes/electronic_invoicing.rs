@@ -0,0 +1,1120 @@
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header::CONTENT_TYPE, HeaderMap, HeaderValue, Response, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use serde_json::{json, Map, Value};
+use sqlx::Row;
+
+use crate::{
+    auth::require_user_id,
+    error::{AppError, AppResult},
+    repository::table_service::{create_row, get_row, list_rows, update_row},
+    schemas::{
+        clamp_limit, remove_nulls, serialize_to_map, CollectionPath, ElectronicDocumentPath,
+        ElectronicDocumentsQuery, OwnerStatementPath, SifenSettingsQuery, UpdateSifenSettingsInput,
+        UploadSifenCertificateInput,
+    },
+    services::{
+        accounting_exports::infer_iva,
+        audit::write_audit_log,
+        fx, permissions,
+        sifen::{
+            self, Emitter, Environment, Invoice, InvoiceItem, PaymentTerms, QrSecret, Receiver,
+            SifenClient, SigningKey, Submitter,
+        },
+    },
+    state::AppState,
+    tenancy::{assert_org_member, assert_permission},
+};
+
+pub fn router() -> axum::Router<AppState> {
+    axum::Router::new()
+        .route(
+            "/sifen/settings",
+            axum::routing::get(get_settings).put(update_settings),
+        )
+        .route(
+            "/sifen/certificates",
+            axum::routing::get(list_certificates).post(upload_certificate),
+        )
+        .route(
+            "/collections/{collection_id}/electronic-invoice",
+            axum::routing::post(invoice_collection),
+        )
+        .route(
+            "/owner-statements/{statement_id}/electronic-invoice",
+            axum::routing::post(invoice_owner_statement),
+        )
+        .route("/electronic-documents", axum::routing::get(list_documents))
+        .route(
+            "/electronic-documents/{document_id}",
+            axum::routing::get(get_document),
+        )
+        .route(
+            "/electronic-documents/{document_id}/xml",
+            axum::routing::get(document_xml),
+        )
+        .route(
+            "/electronic-documents/{document_id}/submit",
+            axum::routing::post(resubmit_document),
+        )
+}
+
+// ── Settings and certificates ──────────────────────────────────────────
+
+async fn get_settings(
+    State(state): State<AppState>,
+    Query(query): Query<SifenSettingsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let settings = load_settings(pool, &query.org_id).await?;
+    Ok(Json(match settings {
+        Some(settings) => public_settings(settings),
+        None => {
+            json!({ "organization_id": query.org_id, "environment": "mock", "configured": false })
+        }
+    }))
+}
+
+async fn update_settings(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateSifenSettingsInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::INVOICING_EDIT,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let mut patch = remove_nulls(serialize_to_map(&payload));
+    patch.remove("organization_id");
+    validate_settings(&mut patch)?;
+
+    let existing = load_settings(pool, &payload.organization_id).await?;
+    let saved = match &existing {
+        Some(_) if patch.is_empty() => existing.clone().unwrap_or(Value::Null),
+        Some(_) => {
+            update_row(
+                pool,
+                "sifen_settings",
+                &payload.organization_id,
+                &patch,
+                "organization_id",
+            )
+            .await?
+        }
+        None => {
+            patch.insert(
+                "organization_id".to_string(),
+                Value::String(payload.organization_id.clone()),
+            );
+            create_row(pool, "sifen_settings", &patch).await?
+        }
+    };
+
+    write_audit_log(
+        Some(pool),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        if existing.is_some() {
+            "update"
+        } else {
+            "create"
+        },
+        "sifen_settings",
+        Some(&payload.organization_id),
+        existing.map(public_settings),
+        Some(public_settings(saved.clone())),
+    )
+    .await;
+
+    Ok(Json(public_settings(saved)))
+}
+
+fn validate_settings(patch: &mut Map<String, Value>) -> AppResult<()> {
+    if let Some(environment) = patch.get("environment").and_then(Value::as_str) {
+        let environment = Environment::parse(environment).ok_or_else(|| {
+            AppError::BadRequest("environment must be one of mock, test, production.".to_string())
+        })?;
+        patch.insert(
+            "environment".to_string(),
+            Value::String(environment.as_str().to_string()),
+        );
+    }
+    for key in ["establishment", "expedition_point"] {
+        if let Some(value) = patch.get(key).and_then(Value::as_str) {
+            let value = value.trim();
+            if value.is_empty() || value.len() > 3 || !value.chars().all(|c| c.is_ascii_digit()) {
+                return Err(AppError::BadRequest(format!(
+                    "{key} must be a number of up to three digits."
+                )));
+            }
+            patch.insert(key.to_string(), Value::String(format!("{value:0>3}")));
+        }
+    }
+    if patch
+        .get("next_document_number")
+        .and_then(Value::as_i64)
+        .is_some_and(|number| !(1..=9_999_999).contains(&number))
+    {
+        return Err(AppError::BadRequest(
+            "next_document_number must be between 1 and 9999999.".to_string(),
+        ));
+    }
+    if patch
+        .get("taxpayer_type")
+        .and_then(Value::as_i64)
+        .is_some_and(|kind| kind != 1 && kind != 2)
+    {
+        return Err(AppError::BadRequest(
+            "taxpayer_type must be 1 (persona física) or 2 (persona jurídica).".to_string(),
+        ));
+    }
+    if let Some(value) = patch.get("timbrado_valid_from").and_then(Value::as_str) {
+        NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| {
+            AppError::BadRequest("timbrado_valid_from must be a YYYY-MM-DD date.".to_string())
+        })?;
+    }
+    Ok(())
+}
+
+/// Settings as returned to clients: the CSC is a secret and only its
+/// presence is reported.
+fn public_settings(settings: Value) -> Value {
+    let mut settings = settings.as_object().cloned().unwrap_or_default();
+    let has_csc = settings
+        .remove("csc")
+        .and_then(|value| value.as_str().map(|csc| !csc.trim().is_empty()))
+        .unwrap_or(false);
+    settings.insert("csc_configured".to_string(), Value::Bool(has_csc));
+    settings.insert("configured".to_string(), Value::Bool(true));
+    Value::Object(settings)
+}
+
+async fn list_certificates(
+    State(state): State<AppState>,
+    Query(query): Query<SifenSettingsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let rows = sqlx::query(
+        "SELECT row_to_json(c) AS row FROM (
+           SELECT id, organization_id, subject, issuer, serial_number, valid_from,
+                  valid_until, is_active, uploaded_by_user_id, created_at
+           FROM sifen_certificates
+           WHERE organization_id = $1::uuid
+           ORDER BY created_at DESC
+         ) c",
+    )
+    .bind(&query.org_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load certificates."))?;
+    let data: Vec<Value> = rows
+        .iter()
+        .filter_map(|row| row.try_get::<Value, _>("row").ok())
+        .collect();
+    Ok(Json(json!({ "data": data })))
+}
+
+async fn upload_certificate(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<UploadSifenCertificateInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::INVOICING_EDIT,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+    let encryption_key = key_encryption_key(&state)?;
+
+    let key =
+        match (
+            non_empty_opt(payload.pkcs12_base64.as_deref()),
+            non_empty_opt(payload.certificate_pem.as_deref()),
+            non_empty_opt(payload.private_key_pem.as_deref()),
+        ) {
+            (Some(pkcs12), _, _) => {
+                let der = BASE64
+                    .decode(pkcs12.split_whitespace().collect::<String>())
+                    .map_err(|_| {
+                        AppError::BadRequest("pkcs12_base64 is not valid base64.".to_string())
+                    })?;
+                SigningKey::from_pkcs12(&der, payload.password.as_deref().unwrap_or_default())
+            }
+            (None, Some(certificate), Some(private_key)) => {
+                SigningKey::from_pem(&certificate, &private_key)
+            }
+            _ => return Err(AppError::BadRequest(
+                "Provide pkcs12_base64 with its password, or certificate_pem and private_key_pem."
+                    .to_string(),
+            )),
+        }
+        .map_err(AppError::BadRequest)?;
+
+    let info = key.info();
+    if info.valid_until.is_some_and(|until| until < Utc::now()) {
+        return Err(AppError::UnprocessableEntity(
+            "The certificate has expired.".to_string(),
+        ));
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Could not start transaction."))?;
+    sqlx::query(
+        "UPDATE sifen_certificates SET is_active = false
+         WHERE organization_id = $1::uuid AND is_active",
+    )
+    .bind(&payload.organization_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not retire the old certificate."))?;
+    let certificate_id: String = sqlx::query_scalar(
+        "INSERT INTO sifen_certificates (
+           organization_id, subject, issuer, serial_number, valid_from, valid_until,
+           certificate_pem, private_key_encrypted, uploaded_by_user_id
+         ) VALUES ($1::uuid, $2, $3, $4, $5, $6, $7, $8, $9::uuid)
+         RETURNING id::text",
+    )
+    .bind(&payload.organization_id)
+    .bind(&info.subject)
+    .bind(&info.issuer)
+    .bind(&info.serial_number)
+    .bind(info.valid_from)
+    .bind(info.valid_until)
+    .bind(key.certificate_pem().map_err(AppError::Internal)?)
+    .bind(
+        key.sealed_private_key(encryption_key, &payload.organization_id)
+            .map_err(AppError::Internal)?,
+    )
+    .bind(&user_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not store the certificate."))?;
+    tx.commit()
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Could not store the certificate."))?;
+
+    let created = json!({
+        "id": certificate_id,
+        "organization_id": payload.organization_id,
+        "subject": info.subject,
+        "issuer": info.issuer,
+        "serial_number": info.serial_number,
+        "valid_from": info.valid_from,
+        "valid_until": info.valid_until,
+        "is_active": true,
+    });
+    write_audit_log(
+        Some(pool),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        "upload_certificate",
+        "sifen_certificates",
+        created.get("id").and_then(Value::as_str),
+        None,
+        Some(created.clone()),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+// ── Issuing ────────────────────────────────────────────────────────────
+
+/// What the organization needs to issue documents: its emitter data, the
+/// active certificate and where documents go.
+struct IssuingSetup {
+    emitter: Emitter,
+    environment: Environment,
+    qr_secret: QrSecret,
+    key: SigningKey,
+    certificate_id: String,
+}
+
+async fn invoice_collection(
+    State(state): State<AppState>,
+    Path(path): Path<CollectionPath>,
+    headers: HeaderMap,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let collection = get_row(pool, "collection_records", &path.collection_id, "id").await?;
+    let org_id = value_str(&collection, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::INVOICING_EDIT).await?;
+    if value_str(&collection, "status") == "waived" {
+        return Err(AppError::Conflict(
+            "Waived collections are not invoiced.".to_string(),
+        ));
+    }
+
+    let existing = existing_document(pool, "collection_record_id", &path.collection_id).await?;
+    if let Some(document) = reusable_document(&existing) {
+        return Ok((StatusCode::OK, Json(document)));
+    }
+
+    let row = sqlx::query(
+        "SELECT l.tenant_full_name, COALESCE(l.tenant_ruc, '') AS tenant_ruc,
+                (l.monthly_rent + l.service_fee_flat)::float8 AS taxable_base,
+                l.tax_iva::float8 AS tax_iva,
+                lc.charge_type::text AS charge_type,
+                COALESCE(lc.description, '') AS charge_description
+         FROM collection_records cr
+         JOIN leases l ON l.id = cr.lease_id
+         LEFT JOIN lease_charges lc ON lc.id = cr.lease_charge_id
+         WHERE cr.id = $1::uuid",
+    )
+    .bind(&path.collection_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load the collection's lease."))?;
+
+    let charge_type: Option<String> = row.try_get("charge_type").ok().flatten();
+    if charge_type.as_deref() == Some("security_deposit") {
+        return Err(AppError::UnprocessableEntity(
+            "Security deposits are not a sale and are not invoiced.".to_string(),
+        ));
+    }
+    let due_date = NaiveDate::parse_from_str(&value_str(&collection, "due_date"), "%Y-%m-%d")
+        .unwrap_or_else(|_| Utc::now().date_naive());
+    let (lease_rate, _) = infer_iva(
+        row.try_get("taxable_base").unwrap_or(0.0),
+        row.try_get("tax_iva").unwrap_or(0.0),
+    );
+    let iva_rate = match charge_type.as_deref() {
+        Some("late_fee" | "admin_fee") => 10,
+        _ => lease_rate,
+    };
+    let description = non_empty_opt(
+        row.try_get::<String, _>("charge_description")
+            .ok()
+            .as_deref(),
+    )
+    .unwrap_or_else(|| charge_label(charge_type.as_deref(), due_date));
+
+    let receiver = Receiver::from_tax_id(
+        &row.try_get::<String, _>("tenant_ruc").unwrap_or_default(),
+        &row.try_get::<String, _>("tenant_full_name")
+            .unwrap_or_default(),
+    )
+    .map_err(AppError::UnprocessableEntity)?;
+    let payment = if value_str(&collection, "status") == "paid" {
+        PaymentTerms::cash(&value_str(&collection, "payment_method"))
+    } else {
+        PaymentTerms::Credit {
+            days: (due_date - Utc::now().date_naive()).num_days().max(0) as u32,
+        }
+    };
+    let items = vec![InvoiceItem {
+        code: charge_type.unwrap_or_else(|| "monthly_rent".to_string()),
+        description,
+        amount: value_f64(&collection, "amount"),
+        iva_rate,
+    }];
+
+    let document = issue_and_submit(
+        &state,
+        pool,
+        &org_id,
+        &user_id,
+        existing,
+        Source::Collection(&path.collection_id),
+        &value_str(&collection, "currency"),
+        receiver,
+        payment,
+        items,
+    )
+    .await?;
+    Ok((StatusCode::CREATED, Json(document)))
+}
+
+async fn invoice_owner_statement(
+    State(state): State<AppState>,
+    Path(path): Path<OwnerStatementPath>,
+    headers: HeaderMap,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let statement = get_row(pool, "owner_statements", &path.statement_id, "id").await?;
+    let org_id = value_str(&statement, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::INVOICING_EDIT).await?;
+    if !matches!(
+        value_str(&statement, "status").as_str(),
+        "finalized" | "sent" | "paid"
+    ) {
+        return Err(AppError::Conflict(
+            "Only finalized statements can be invoiced.".to_string(),
+        ));
+    }
+
+    let existing = existing_document(pool, "owner_statement_id", &path.statement_id).await?;
+    if let Some(document) = reusable_document(&existing) {
+        return Ok((StatusCode::OK, Json(document)));
+    }
+
+    let period = format!(
+        "{} – {}",
+        value_str(&statement, "period_start"),
+        value_str(&statement, "period_end")
+    );
+    let items: Vec<InvoiceItem> = [
+        ("service_fees", "MGMT_FEE", "Comisión de administración"),
+        ("collection_fees", "COLLECTION_FEE", "Comisión de cobranza"),
+        (
+            "management_fees",
+            "MGMT_SCHEDULE_FEE",
+            "Honorarios de administración según contrato",
+        ),
+    ]
+    .into_iter()
+    .filter(|(column, _, _)| value_f64(&statement, column) > 0.0)
+    .map(|(column, code, label)| InvoiceItem {
+        code: code.to_string(),
+        description: format!("{label} {period}"),
+        amount: value_f64(&statement, column),
+        iva_rate: 10,
+    })
+    .collect();
+    if items.is_empty() {
+        return Err(AppError::UnprocessableEntity(
+            "This statement has no management fees to invoice.".to_string(),
+        ));
+    }
+
+    let owner = sqlx::query(
+        "SELECT COALESCE(p.asset_owner_ruc, '') AS owner_ruc,
+                COALESCE(p.asset_owner_name, '') AS owner_name
+         FROM owner_statements os
+         LEFT JOIN units u ON u.id = os.unit_id
+         LEFT JOIN properties p ON p.id = COALESCE(os.property_id, u.property_id)
+         WHERE os.id = $1::uuid",
+    )
+    .bind(&path.statement_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load the property owner."))?;
+    let receiver = Receiver::from_tax_id(
+        &owner.try_get::<String, _>("owner_ruc").unwrap_or_default(),
+        &owner.try_get::<String, _>("owner_name").unwrap_or_default(),
+    )
+    .map_err(AppError::UnprocessableEntity)?;
+
+    // Fees are withheld from the payout, so they are settled on issue.
+    let document = issue_and_submit(
+        &state,
+        pool,
+        &org_id,
+        &user_id,
+        existing,
+        Source::OwnerStatement(&path.statement_id),
+        &value_str(&statement, "currency"),
+        receiver,
+        PaymentTerms::cash("other"),
+        items,
+    )
+    .await?;
+    Ok((StatusCode::CREATED, Json(document)))
+}
+
+fn charge_label(charge_type: Option<&str>, due_date: NaiveDate) -> String {
+    let month = due_date.format("%m/%Y");
+    match charge_type {
+        Some("advance_rent") => format!("Alquiler adelantado {month}"),
+        Some("service_fee_flat") => format!("Gastos de servicio {month}"),
+        Some("guarantee_option_fee") => "Opción de garantía".to_string(),
+        Some("admin_fee") => "Gastos administrativos".to_string(),
+        Some("late_fee") => format!("Recargo por mora {month}"),
+        Some("tax_iva" | "other") => format!("Cargo {month}"),
+        _ => format!("Alquiler {month}"),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Source<'a> {
+    Collection(&'a str),
+    OwnerStatement(&'a str),
+}
+
+async fn existing_document(
+    pool: &sqlx::PgPool,
+    column: &str,
+    id: &str,
+) -> AppResult<Option<Value>> {
+    let mut filters = Map::new();
+    filters.insert(column.to_string(), Value::String(id.to_string()));
+    let rows = list_rows(
+        pool,
+        "electronic_documents",
+        Some(&filters),
+        1,
+        0,
+        "created_at",
+        false,
+    )
+    .await?;
+    Ok(rows.into_iter().next())
+}
+
+/// A document that was already issued and not rejected is returned as is;
+/// rejected ones are rebuilt under the same number.
+fn reusable_document(existing: &Option<Value>) -> Option<Value> {
+    existing
+        .as_ref()
+        .filter(|document| value_str(document, "status") != "rejected")
+        .map(document_summary)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn issue_and_submit(
+    state: &AppState,
+    pool: &sqlx::PgPool,
+    org_id: &str,
+    user_id: &str,
+    rejected: Option<Value>,
+    source: Source<'_>,
+    currency: &str,
+    receiver: Receiver,
+    payment: PaymentTerms,
+    items: Vec<InvoiceItem>,
+) -> AppResult<Value> {
+    let setup = load_issuing_setup(state, pool, org_id).await?;
+    let issued_at: NaiveDateTime = Utc::now()
+        .with_timezone(&chrono_tz::America::Asuncion)
+        .naive_local();
+    let currency = if currency.is_empty() { "PYG" } else { currency };
+    let exchange_rate = if currency == "PYG" {
+        None
+    } else {
+        let rate = fx::cached_rate_on(state, currency, "PYG", issued_at.date())
+            .await?
+            .ok_or_else(|| {
+                AppError::UnprocessableEntity(format!(
+                    "No {currency}→PYG exchange rate is available for {}.",
+                    issued_at.date()
+                ))
+            })?;
+        Some(rate.rate)
+    };
+
+    let document_number = match &rejected {
+        Some(document) => value_f64(document, "document_number") as u32,
+        None => allocate_document_number(pool, org_id).await?,
+    };
+    let invoice = Invoice {
+        emitter: setup.emitter,
+        receiver,
+        document_number,
+        issued_at,
+        security_code: sifen::security_code(),
+        currency: currency.to_string(),
+        exchange_rate,
+        payment,
+        items,
+    };
+    let issued = sifen::issue(&invoice, &setup.key, setup.environment, &setup.qr_secret)
+        .map_err(AppError::UnprocessableEntity)?;
+
+    let mut record = Map::new();
+    record.insert("cdc".to_string(), Value::String(issued.cdc.clone()));
+    record.insert(
+        "issued_at".to_string(),
+        Value::String(issued_at.format("%Y-%m-%dT%H:%M:%S").to_string()),
+    );
+    record.insert("currency".to_string(), Value::String(currency.to_string()));
+    record.insert("total_amount".to_string(), json!(issued.totals.total));
+    record.insert("total_iva".to_string(), json!(issued.totals.total_iva()));
+    record.insert(
+        "receiver_name".to_string(),
+        Value::String(invoice.receiver.name().to_string()),
+    );
+    record.insert(
+        "receiver_tax_id".to_string(),
+        invoice.receiver.tax_id().map_or(Value::Null, Value::String),
+    );
+    record.insert("signed_xml".to_string(), Value::String(issued.xml.clone()));
+    record.insert(
+        "digest_value".to_string(),
+        Value::String(issued.digest_value.clone()),
+    );
+    record.insert("qr_url".to_string(), Value::String(issued.qr_url.clone()));
+    record.insert(
+        "environment".to_string(),
+        Value::String(setup.environment.as_str().to_string()),
+    );
+    record.insert("status".to_string(), Value::String("signed".to_string()));
+    record.insert(
+        "certificate_id".to_string(),
+        Value::String(setup.certificate_id.clone()),
+    );
+    for key in [
+        "submitted_at",
+        "response_code",
+        "response_message",
+        "protocol_number",
+        "response",
+    ] {
+        record.insert(key.to_string(), Value::Null);
+    }
+
+    let document = match &rejected {
+        Some(document) => {
+            let id = value_str(document, "id");
+            update_row(pool, "electronic_documents", &id, &record, "id").await?
+        }
+        None => {
+            record.insert(
+                "organization_id".to_string(),
+                Value::String(org_id.to_string()),
+            );
+            let (column, id) = match source {
+                Source::Collection(id) => ("collection_record_id", id),
+                Source::OwnerStatement(id) => ("owner_statement_id", id),
+            };
+            record.insert(column.to_string(), Value::String(id.to_string()));
+            record.insert("document_type".to_string(), json!(1));
+            record.insert(
+                "establishment".to_string(),
+                Value::String(invoice.emitter.establishment.clone()),
+            );
+            record.insert(
+                "expedition_point".to_string(),
+                Value::String(invoice.emitter.expedition_point.clone()),
+            );
+            record.insert("document_number".to_string(), json!(document_number));
+            record.insert(
+                "created_by_user_id".to_string(),
+                Value::String(user_id.to_string()),
+            );
+            create_row(pool, "electronic_documents", &record).await?
+        }
+    };
+
+    if let Source::Collection(collection_id) = source {
+        let mut patch = Map::new();
+        patch.insert("sifen_cdc".to_string(), Value::String(issued.cdc.clone()));
+        patch.insert(
+            "sifen_qr_url".to_string(),
+            Value::String(issued.qr_url.clone()),
+        );
+        update_row(pool, "collection_records", collection_id, &patch, "id").await?;
+    }
+
+    write_audit_log(
+        Some(pool),
+        Some(org_id),
+        Some(user_id),
+        "issue_electronic_document",
+        "electronic_documents",
+        document.get("id").and_then(Value::as_str),
+        rejected.as_ref().map(document_summary),
+        Some(document_summary(&document)),
+    )
+    .await;
+
+    submit_document(pool, document, &setup.key, setup.environment).await
+}
+
+async fn load_settings(pool: &sqlx::PgPool, org_id: &str) -> AppResult<Option<Value>> {
+    match get_row(pool, "sifen_settings", org_id, "organization_id").await {
+        Ok(settings) => Ok(Some(settings)),
+        Err(AppError::NotFound(_)) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// The app-held key that seals stored certificate private keys.
+fn key_encryption_key(state: &AppState) -> AppResult<&str> {
+    state
+        .config
+        .sifen_key_encryption_key
+        .as_deref()
+        .ok_or_else(|| {
+            AppError::ServiceUnavailable(
+                "Certificate key encryption is not configured (SIFEN_KEY_ENCRYPTION_KEY)."
+                    .to_string(),
+            )
+        })
+}
+
+async fn load_issuing_setup(
+    state: &AppState,
+    pool: &sqlx::PgPool,
+    org_id: &str,
+) -> AppResult<IssuingSetup> {
+    let settings = load_settings(pool, org_id).await?.ok_or_else(|| {
+        AppError::UnprocessableEntity(
+            "Configure electronic invoicing for this organization first.".to_string(),
+        )
+    })?;
+    let organization = get_row(pool, "organizations", org_id, "id").await?;
+
+    let mut missing = Vec::new();
+    let mut required = |row: &Value, key: &'static str| {
+        let value = value_str(row, key);
+        if value.trim().is_empty() {
+            missing.push(key);
+        }
+        value.trim().to_string()
+    };
+    let ruc = required(&organization, "ruc");
+    let timbrado = required(&organization, "timbrado_number");
+    let timbrado_valid_from = required(&settings, "timbrado_valid_from");
+    let address = required(&settings, "fiscal_address");
+    let department_name = required(&settings, "department_name");
+    let city_name = required(&settings, "city_name");
+    let activity_code = required(&settings, "activity_code");
+    let activity_description = required(&settings, "activity_description");
+    let csc_id = required(&settings, "csc_id");
+    let csc = required(&settings, "csc");
+    for key in ["department_code", "city_code"] {
+        if settings.get(key).and_then(Value::as_i64).is_none() {
+            missing.push(key);
+        }
+    }
+    if !missing.is_empty() {
+        return Err(AppError::UnprocessableEntity(format!(
+            "Electronic invoicing is missing: {}.",
+            missing.join(", ")
+        )));
+    }
+
+    let (ruc, check_digit) = sifen::split_ruc(&ruc).map_err(AppError::UnprocessableEntity)?;
+    let timbrado_valid_from =
+        NaiveDate::parse_from_str(&timbrado_valid_from, "%Y-%m-%d").map_err(|_| {
+            AppError::UnprocessableEntity("timbrado_valid_from is not a valid date.".to_string())
+        })?;
+    let environment =
+        Environment::parse(&value_str(&settings, "environment")).unwrap_or(Environment::Mock);
+
+    let certificate = sqlx::query(
+        "SELECT id::text AS id, certificate_pem, private_key_encrypted
+         FROM sifen_certificates
+         WHERE organization_id = $1::uuid AND is_active
+           AND (valid_until IS NULL OR valid_until > now())",
+    )
+    .bind(org_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load the certificate."))?
+    .ok_or_else(|| {
+        AppError::UnprocessableEntity(
+            "Upload a valid signing certificate before issuing documents.".to_string(),
+        )
+    })?;
+    let key = SigningKey::from_sealed(
+        &certificate
+            .try_get::<String, _>("certificate_pem")
+            .unwrap_or_default(),
+        &certificate
+            .try_get::<String, _>("private_key_encrypted")
+            .unwrap_or_default(),
+        key_encryption_key(state)?,
+        org_id,
+    )
+    .map_err(AppError::Internal)?;
+
+    let name = non_empty_opt(Some(&value_str(&organization, "legal_name")))
+        .unwrap_or_else(|| value_str(&organization, "name"));
+    let emitter = Emitter {
+        ruc,
+        check_digit,
+        name,
+        taxpayer_type: settings
+            .get("taxpayer_type")
+            .and_then(Value::as_u64)
+            .unwrap_or(2) as u8,
+        timbrado,
+        timbrado_valid_from,
+        establishment: value_str(&settings, "establishment"),
+        expedition_point: value_str(&settings, "expedition_point"),
+        address,
+        house_number: non_empty_opt(Some(&value_str(&settings, "house_number")))
+            .unwrap_or_else(|| "0".to_string()),
+        department_code: settings
+            .get("department_code")
+            .and_then(Value::as_i64)
+            .unwrap_or_default() as i32,
+        department_name,
+        city_code: settings
+            .get("city_code")
+            .and_then(Value::as_i64)
+            .unwrap_or_default() as i32,
+        city_name,
+        phone: value_str(&settings, "phone"),
+        email: value_str(&settings, "email"),
+        activity_code,
+        activity_description,
+    };
+
+    Ok(IssuingSetup {
+        emitter,
+        environment,
+        qr_secret: QrSecret {
+            id: csc_id,
+            code: csc,
+        },
+        key,
+        certificate_id: certificate.try_get("id").unwrap_or_default(),
+    })
+}
+
+/// Take the next number of the organization's expedition point.
+async fn allocate_document_number(pool: &sqlx::PgPool, org_id: &str) -> AppResult<u32> {
+    let number: i32 = sqlx::query_scalar(
+        "UPDATE sifen_settings
+         SET next_document_number = next_document_number + 1
+         WHERE organization_id = $1::uuid
+         RETURNING next_document_number - 1",
+    )
+    .bind(org_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not allocate a document number."))?;
+    Ok(number.max(1) as u32)
+}
+
+/// Send a signed document and record SET's answer. Transport failures
+/// leave it `signed` so it can be resubmitted.
+async fn submit_document(
+    pool: &sqlx::PgPool,
+    document: Value,
+    key: &SigningKey,
+    environment: Environment,
+) -> AppResult<Value> {
+    let id = value_str(&document, "id");
+    let client = Submitter::for_environment(environment, key).map_err(AppError::Dependency)?;
+    let request_id = Utc::now().timestamp_millis().unsigned_abs();
+
+    let mut patch = Map::new();
+    patch.insert(
+        "submitted_at".to_string(),
+        Value::String(Utc::now().to_rfc3339()),
+    );
+    match client
+        .submit(request_id, &value_str(&document, "signed_xml"))
+        .await
+    {
+        Ok(result) => {
+            let status = if result.approved {
+                "approved"
+            } else {
+                "rejected"
+            };
+            patch.insert("status".to_string(), Value::String(status.to_string()));
+            patch.insert("response_code".to_string(), Value::String(result.code));
+            patch.insert(
+                "response_message".to_string(),
+                Value::String(result.message),
+            );
+            patch.insert(
+                "protocol_number".to_string(),
+                result.protocol_number.map_or(Value::Null, Value::String),
+            );
+            patch.insert(
+                "response".to_string(),
+                json!({ "environment": client.environment().as_str(), "raw": result.raw_response }),
+            );
+        }
+        Err(message) => {
+            tracing::warn!(document_id = %id, error = %message, "SIFEN submission failed");
+            patch.insert("response_message".to_string(), Value::String(message));
+        }
+    }
+    let updated = update_row(pool, "electronic_documents", &id, &patch, "id").await?;
+    Ok(document_summary(&updated))
+}
+
+// ── Documents ──────────────────────────────────────────────────────────
+
+async fn list_documents(
+    State(state): State<AppState>,
+    Query(query): Query<ElectronicDocumentsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(query.org_id.clone()),
+    );
+    if let Some(status) = non_empty_opt(query.status.as_deref()) {
+        filters.insert("status".to_string(), Value::String(status));
+    }
+    if let Some(collection_id) = non_empty_opt(query.collection_id.as_deref()) {
+        filters.insert(
+            "collection_record_id".to_string(),
+            Value::String(collection_id),
+        );
+    }
+    if let Some(statement_id) = non_empty_opt(query.owner_statement_id.as_deref()) {
+        filters.insert(
+            "owner_statement_id".to_string(),
+            Value::String(statement_id),
+        );
+    }
+    let rows = list_rows(
+        pool,
+        "electronic_documents",
+        Some(&filters),
+        clamp_limit(query.limit),
+        0,
+        "issued_at",
+        false,
+    )
+    .await?;
+    let data: Vec<Value> = rows.iter().map(document_summary).collect();
+    Ok(Json(json!({ "data": data })))
+}
+
+async fn get_document(
+    State(state): State<AppState>,
+    Path(path): Path<ElectronicDocumentPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let document = get_row(pool, "electronic_documents", &path.document_id, "id").await?;
+    assert_org_member(&state, &user_id, &value_str(&document, "organization_id")).await?;
+    Ok(Json(document_summary(&document)))
+}
+
+async fn document_xml(
+    State(state): State<AppState>,
+    Path(path): Path<ElectronicDocumentPath>,
+    headers: HeaderMap,
+) -> AppResult<Response<Body>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let document = get_row(pool, "electronic_documents", &path.document_id, "id").await?;
+    assert_org_member(&state, &user_id, &value_str(&document, "organization_id")).await?;
+
+    let mut response = Response::new(Body::from(value_str(&document, "signed_xml")));
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/xml; charset=utf-8"),
+    );
+    Ok(response)
+}
+
+async fn resubmit_document(
+    State(state): State<AppState>,
+    Path(path): Path<ElectronicDocumentPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let document = get_row(pool, "electronic_documents", &path.document_id, "id").await?;
+    let org_id = value_str(&document, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::INVOICING_EDIT).await?;
+    match value_str(&document, "status").as_str() {
+        "approved" => return Ok(Json(document_summary(&document))),
+        "rejected" => {
+            return Err(AppError::Conflict(
+                "Rejected documents are reissued from their collection or statement.".to_string(),
+            ))
+        }
+        _ => {}
+    }
+
+    let setup = load_issuing_setup(&state, pool, &org_id).await?;
+    let environment =
+        Environment::parse(&value_str(&document, "environment")).unwrap_or(setup.environment);
+    let updated = submit_document(pool, document, &setup.key, environment).await?;
+    write_audit_log(
+        Some(pool),
+        Some(&org_id),
+        Some(&user_id),
+        "submit_electronic_document",
+        "electronic_documents",
+        Some(&path.document_id),
+        None,
+        Some(updated.clone()),
+    )
+    .await;
+    Ok(Json(updated))
+}
+
+/// A document without its XML, which is served separately.
+fn document_summary(document: &Value) -> Value {
+    let mut document = document.as_object().cloned().unwrap_or_default();
+    document.remove("signed_xml");
+    let label = format!(
+        "{}-{}-{:07}",
+        document
+            .get("establishment")
+            .and_then(Value::as_str)
+            .unwrap_or_default(),
+        document
+            .get("expedition_point")
+            .and_then(Value::as_str)
+            .unwrap_or_default(),
+        document
+            .get("document_number")
+            .and_then(Value::as_i64)
+            .unwrap_or_default(),
+    );
+    document.insert("number_label".to_string(), Value::String(label));
+    Value::Object(document)
+}
+
+fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
+    state
+        .db_pool
+        .as_ref()
+        .ok_or_else(|| AppError::Dependency("Database is not configured.".to_string()))
+}
+
+fn value_str(row: &Value, key: &str) -> String {
+    row.as_object()
+        .and_then(|obj| obj.get(key))
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+fn value_f64(row: &Value, key: &str) -> f64 {
+    match row.as_object().and_then(|obj| obj.get(key)) {
+        Some(Value::Number(value)) => value.as_f64().unwrap_or(0.0),
+        Some(Value::String(value)) => value.trim().parse().unwrap_or(0.0),
+        _ => 0.0,
+    }
+}
+
+fn non_empty_opt(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+}
@@ -15,9 +15,11 @@ use crate::{
         clamp_limit_in_range, remove_nulls, serialize_to_map, CreateExpenseInput,
         ExpenseApprovalInput, ExpensePath, ExpensesQuery, UpdateExpenseInput,
     },
-    services::{audit::write_audit_log, enrichment::enrich_expenses, fx::get_usd_to_pyg_rate},
+    services::{
+        audit::write_audit_log, enrichment::enrich_expenses, fx, general_ledger, permissions,
+    },
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
 pub fn router() -> axum::Router<AppState> {
@@ -62,11 +64,11 @@ async fn create_expense(
     Json(payload): Json<CreateExpenseInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
+    assert_permission(
         &state,
         &user_id,
         &payload.organization_id,
-        &["owner_admin", "accountant"],
+        permissions::EXPENSES_EDIT,
     )
     .await?;
     let pool = db_pool(&state)?;
@@ -96,21 +98,16 @@ async fn create_expense(
 
     let currency = string_from_map(&record, "currency")
         .filter(|value| !value.is_empty())
-        .unwrap_or_else(|| "PYG".to_string())
-        .to_uppercase();
+        .unwrap_or_else(|| "PYG".to_string());
+    let currency = fx::normalize_currency(&currency).ok_or_else(|| {
+        AppError::BadRequest("currency must be a 3-letter ISO 4217 code.".to_string())
+    })?;
     record.insert("currency".to_string(), Value::String(currency.clone()));
 
-    if currency == "USD" {
+    if currency != "PYG" {
         if !record.contains_key("fx_rate_to_pyg") {
-            let fetched =
-                get_usd_to_pyg_rate(&state.http_client, payload.expense_date.trim()).await;
-            if let Some(rate) = fetched {
-                record.insert("fx_rate_to_pyg".to_string(), json!(rate));
-            } else {
-                return Err(AppError::BadRequest(
-                    "fx_rate_to_pyg is required for USD expenses (auto-fetch failed).".to_string(),
-                ));
-            }
+            let rate = rate_to_pyg(&state, pool, &currency, &payload.expense_date).await?;
+            record.insert("fx_rate_to_pyg".to_string(), json!(rate));
         }
     } else {
         record.remove("fx_rate_to_pyg");
@@ -193,7 +190,7 @@ async fn update_expense(
 
     let record = get_row(pool, "expenses", &path.expense_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "accountant"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::EXPENSES_EDIT).await?;
 
     let mut patch = remove_nulls(serialize_to_map(&payload));
 
@@ -216,14 +213,15 @@ async fn update_expense(
 
     let mut currency: Option<String> = None;
     if patch.contains_key("currency") {
-        let next_currency = string_from_map(&patch, "currency")
-            .unwrap_or_default()
-            .to_uppercase();
+        let next_currency = string_from_map(&patch, "currency").unwrap_or_default();
         if next_currency.is_empty() {
             return Err(AppError::BadRequest(
                 "currency cannot be empty.".to_string(),
             ));
         }
+        let next_currency = fx::normalize_currency(&next_currency).ok_or_else(|| {
+            AppError::BadRequest("currency must be a 3-letter ISO 4217 code.".to_string())
+        })?;
         patch.insert("currency".to_string(), Value::String(next_currency.clone()));
         currency = Some(next_currency);
     }
@@ -237,22 +235,21 @@ async fn update_expense(
         .or_else(|| string_from_value(record.get("expense_date")))
         .unwrap_or_default();
 
-    if effective_currency == "USD" {
-        if !patch.contains_key("fx_rate_to_pyg") && record.get("fx_rate_to_pyg").is_none() {
-            let fetched = get_usd_to_pyg_rate(&state.http_client, effective_date.trim()).await;
-            if let Some(rate) = fetched {
-                patch.insert("fx_rate_to_pyg".to_string(), json!(rate));
-            } else {
-                return Err(AppError::BadRequest(
-                    "fx_rate_to_pyg is required for USD expenses (auto-fetch failed).".to_string(),
-                ));
-            }
+    if effective_currency != "PYG" {
+        // A currency or date change invalidates the previously fetched rate.
+        let rate_is_stale = currency.is_some() || patch.contains_key("expense_date");
+        if !patch.contains_key("fx_rate_to_pyg")
+            && (rate_is_stale || record.get("fx_rate_to_pyg").is_none_or(Value::is_null))
+        {
+            let rate = rate_to_pyg(&state, pool, &effective_currency, &effective_date).await?;
+            patch.insert("fx_rate_to_pyg".to_string(), json!(rate));
         }
     } else {
         patch.insert("fx_rate_to_pyg".to_string(), Value::Null);
     }
 
     let updated = update_row(pool, "expenses", &path.expense_id, &patch, "id").await?;
+    general_ledger::sync_expense(pool, Some(&record), Some(&updated), Some(&user_id)).await;
 
     write_audit_log(
         state.db_pool.as_ref(),
@@ -282,9 +279,10 @@ async fn delete_expense(
 
     let record = get_row(pool, "expenses", &path.expense_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "accountant"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::EXPENSES_EDIT).await?;
 
     let deleted = delete_row(pool, "expenses", &path.expense_id, "id").await?;
+    general_ledger::sync_expense(pool, Some(&deleted), None, Some(&user_id)).await;
 
     write_audit_log(
         state.db_pool.as_ref(),
@@ -312,7 +310,7 @@ async fn approve_expense(
 
     let record = get_row(pool, "expenses", &path.expense_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::EXPENSES_APPROVE).await?;
 
     let mut patch = Map::new();
     patch.insert(
@@ -326,6 +324,7 @@ async fn approve_expense(
     );
 
     let updated = update_row(pool, "expenses", &path.expense_id, &patch, "id").await?;
+    general_ledger::sync_expense(pool, Some(&record), Some(&updated), Some(&user_id)).await;
 
     write_audit_log(
         state.db_pool.as_ref(),
@@ -356,7 +355,7 @@ async fn reject_expense(
 
     let record = get_row(pool, "expenses", &path.expense_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::EXPENSES_APPROVE).await?;
 
     let mut patch = Map::new();
     patch.insert(
@@ -370,6 +369,7 @@ async fn reject_expense(
     );
 
     let updated = update_row(pool, "expenses", &path.expense_id, &patch, "id").await?;
+    general_ledger::sync_expense(pool, Some(&record), Some(&updated), Some(&user_id)).await;
 
     write_audit_log(
         state.db_pool.as_ref(),
@@ -519,3 +519,23 @@ fn non_empty_opt(value: Option<&str>) -> Option<String> {
         .filter(|item| !item.is_empty())
         .map(ToOwned::to_owned)
 }
+
+/// Rate from `currency` to PYG on the expense date, read from (or fetched
+/// into) the stored daily rates.
+async fn rate_to_pyg(
+    state: &AppState,
+    pool: &sqlx::PgPool,
+    currency: &str,
+    expense_date: &str,
+) -> AppResult<f64> {
+    let date = chrono::NaiveDate::parse_from_str(expense_date.trim(), "%Y-%m-%d")
+        .map_err(|_| AppError::BadRequest("Invalid expense_date.".to_string()))?;
+    fx::rate_on(pool, &state.http_client, currency, "PYG", date)
+        .await?
+        .map(|rate| rate.rate)
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "fx_rate_to_pyg is required for {currency} expenses (no rate available for {date})."
+            ))
+        })
+}
@@ -0,0 +1,109 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use chrono::{NaiveDate, Utc};
+use serde_json::{json, Value};
+
+use crate::{
+    auth::require_user_id,
+    error::{AppError, AppResult},
+    schemas::{FxBackfillInput, FxPairPath, FxRateQuery},
+    services::fx,
+    services::permissions,
+    state::AppState,
+    tenancy::assert_permission,
+};
+
+pub fn router() -> axum::Router<AppState> {
+    axum::Router::new()
+        .route("/public/fx/usd-pyg", axum::routing::get(public_usd_pyg))
+        .route("/public/fx/{pair}", axum::routing::get(public_fx_rate))
+        .route("/fx/backfill", axum::routing::post(backfill_fx_rates))
+}
+
+/// Legacy shape kept for the web and admin clients.
+async fn public_usd_pyg(State(state): State<AppState>) -> AppResult<Json<Value>> {
+    let today = Utc::now().date_naive();
+    let rate = fx::cached_rate_on(&state, "USD", "PYG", today)
+        .await?
+        .ok_or_else(|| AppError::ServiceUnavailable("USD→PYG rate is unavailable.".to_string()))?;
+    Ok(Json(
+        json!({ "usd_pyg": rate.rate, "rate_date": rate.rate_date.to_string() }),
+    ))
+}
+
+async fn public_fx_rate(
+    State(state): State<AppState>,
+    Path(path): Path<FxPairPath>,
+    Query(query): Query<FxRateQuery>,
+) -> AppResult<Json<Value>> {
+    let (base, quote) = fx::parse_pair(&path.pair)
+        .ok_or_else(|| AppError::BadRequest("Currency pair must look like usd-pyg.".to_string()))?;
+    let date = match query
+        .date
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+    {
+        Some(value) => parse_date(value)?,
+        None => Utc::now().date_naive(),
+    };
+
+    let rate = fx::cached_rate_on(&state, &base, &quote, date)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(format!("No {base}→{quote} rate is available for {date}."))
+        })?;
+    Ok(Json(rate.to_json(date)))
+}
+
+async fn backfill_fx_rates(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<FxBackfillInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::FX_MANAGE,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let base = fx::normalize_currency(&payload.base_currency).ok_or_else(|| {
+        AppError::BadRequest("base_currency must be a 3-letter ISO 4217 code.".to_string())
+    })?;
+    let quote = fx::normalize_currency(&payload.quote_currency).ok_or_else(|| {
+        AppError::BadRequest("quote_currency must be a 3-letter ISO 4217 code.".to_string())
+    })?;
+    if base == quote {
+        return Err(AppError::BadRequest(
+            "base_currency and quote_currency must differ.".to_string(),
+        ));
+    }
+    let from = parse_date(&payload.from)?;
+    let to = parse_date(&payload.to)?;
+    if to < from {
+        return Err(AppError::BadRequest(
+            "to must be on or after from.".to_string(),
+        ));
+    }
+
+    let summary = fx::backfill(pool, &state.http_client, &base, &[quote], from, to).await?;
+    Ok(Json(summary))
+}
+
+fn parse_date(value: &str) -> AppResult<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| AppError::BadRequest("Invalid ISO date.".to_string()))
+}
+
+fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
+    state.db_pool.as_ref().ok_or_else(|| {
+        AppError::Dependency("Database is not configured. Set DATABASE_URL.".to_string())
+    })
+}
@@ -15,8 +15,9 @@ use crate::{
         GuestsQuery, UpdateBackgroundCheckInput, UpdateGuestInput,
     },
     services::audit::write_audit_log,
+    services::permissions,
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
 pub fn router() -> axum::Router<AppState> {
@@ -79,11 +80,11 @@ async fn create_guest(
 ) -> AppResult<impl IntoResponse> {
     validate_input(&payload)?;
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
+    assert_permission(
         &state,
         &user_id,
         &payload.organization_id,
-        &["owner_admin", "operator"],
+        permissions::GUESTS_EDIT,
     )
     .await?;
     let pool = db_pool(&state)?;
@@ -127,7 +128,7 @@ async fn update_guest(
     let pool = db_pool(&state)?;
     let record = get_row(pool, "guests", &path.guest_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::GUESTS_EDIT).await?;
     let patch = remove_nulls(serialize_to_map(&payload));
     let updated = update_row(pool, "guests", &path.guest_id, &patch, "id").await?;
     write_audit_log(
@@ -153,7 +154,7 @@ async fn delete_guest(
     let pool = db_pool(&state)?;
     let record = get_row(pool, "guests", &path.guest_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::GUESTS_EDIT).await?;
     let deleted = delete_row(pool, "guests", &path.guest_id, "id").await?;
     write_audit_log(
         state.db_pool.as_ref(),
@@ -185,7 +186,7 @@ async fn update_background_check(
 
     let record = get_row(pool, "guests", &path.guest_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::GUESTS_VERIFY).await?;
 
     if !ALLOWED_BG_STATUSES.contains(&payload.background_check_status.as_str()) {
         return Err(AppError::BadRequest(format!(
@@ -239,7 +240,7 @@ async fn submit_verification(
 
     let record = get_row(pool, "guests", &path.guest_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::GUESTS_EDIT).await?;
 
     let mut patch = serde_json::Map::new();
     patch.insert(
@@ -283,7 +284,7 @@ async fn review_verification(
 
     let record = get_row(pool, "guests", &path.guest_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::GUESTS_VERIFY).await?;
 
     if !matches!(
         payload.verification_status.as_str(),
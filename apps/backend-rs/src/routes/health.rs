@@ -1,8 +1,9 @@
 use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
 use chrono::Utc;
 use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
 
-use crate::state::AppState;
+use crate::{error::AppResult, services::scheduler_leases, state::AppState};
 
 pub async fn live() -> Json<Value> {
     Json(json!({
@@ -31,6 +32,41 @@ pub async fn cache_stats(State(state): State<AppState>) -> Json<Value> {
     }))
 }
 
+/// Scheduler lease state and last-run times, one entry per background job.
+/// Holder ids identify backend instances, so each lease shows only a short
+/// hash of its holder: enough to tell whether two jobs run on the same
+/// instance. Raw ids are listed on the platform admin
+/// `/platform/scheduler/jobs` route.
+pub async fn scheduler(State(state): State<AppState>) -> AppResult<Json<Value>> {
+    let leases = match state.db_pool.as_ref() {
+        Some(pool) => scheduler_leases::list_leases(pool).await.map_err(|error| {
+            crate::error::AppError::from_database_error(&error, "Database request failed.")
+        })?,
+        None => Vec::new(),
+    };
+
+    Ok(Json(json!({
+        "scheduler_enabled": state.config.scheduler_enabled,
+        "leases": leases.into_iter().map(public_lease).collect::<Vec<_>>(),
+        "now": Utc::now().to_rfc3339(),
+    })))
+}
+
+fn public_lease(mut lease: Value) -> Value {
+    if let Some(obj) = lease.as_object_mut() {
+        let holder = obj
+            .remove("holder_id")
+            .and_then(|holder_id| holder_id.as_str().map(holder_hash));
+        obj.insert("holder".to_string(), json!(holder));
+    }
+    lease
+}
+
+fn holder_hash(holder_id: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(holder_id.as_bytes()));
+    digest[..12].to_string()
+}
+
 async fn readiness_response(state: &AppState) -> (StatusCode, Json<Value>) {
     let report = state.api_readiness_report().await;
     let status = if report.ready {
@@ -54,3 +90,26 @@ async fn readiness_response(state: &AppState) -> (StatusCode, Json<Value>) {
         })),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn public_lease_replaces_the_holder_id_with_a_hash() {
+        let lease = public_lease(json!({
+            "job_name": "late_fees",
+            "holder_id": "api-7f9c:4412",
+            "is_held": true,
+        }));
+        assert!(lease.get("holder_id").is_none());
+        let holder = lease["holder"].as_str().unwrap();
+        assert_eq!(holder.len(), 12);
+        assert_eq!(holder, holder_hash("api-7f9c:4412"));
+        assert_ne!(holder, holder_hash("api-7f9c:4413"));
+        assert_eq!(lease["is_held"], json!(true));
+
+        let unheld = public_lease(json!({ "job_name": "late_fees", "holder_id": null }));
+        assert_eq!(unheld["holder"], Value::Null);
+    }
+}
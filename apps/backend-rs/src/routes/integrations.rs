@@ -1,4 +1,7 @@
+use std::collections::HashMap;
+
 use axum::{
+    body::Bytes,
     extract::{Path, Query, State},
     http::{HeaderMap, StatusCode},
     response::IntoResponse,
@@ -15,14 +18,23 @@ use crate::{
     schemas::{
         clamp_limit, clamp_limit_in_range, remove_nulls, serialize_to_map, AuditLogPath,
         AuditLogsQuery, CreateIntegrationInput, IntegrationEventPath, IntegrationEventsQuery,
-        IntegrationPath, IntegrationsQuery, UpdateIntegrationInput,
+        IntegrationPath, IntegrationSyncHistoryQuery, IntegrationsQuery,
+        RotateWebhookSigningSecretInput, UpdateIntegrationInput, WebhookSigningSecretPath,
+        WebhookSigningSecretsQuery,
     },
     services::{
-        audit::write_audit_log, enrichment::enrich_integrations,
-        ical::sync_listing_ical_reservations,
+        audit::write_audit_log,
+        channel_connector::{
+            load_channel_integrations, push_integration_ari, sync_channel_reservations,
+            ChannelConnector, ChannelIntegration, Connector,
+        },
+        enrichment::enrich_integrations,
+        ical::{sync_listing_ical_reservations, IcalSyncMode},
+        permissions,
+        webhook_verification::{self, HmacVerifier, WebhookRequest},
     },
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
 pub fn router() -> axum::Router<AppState> {
@@ -42,9 +54,25 @@ pub fn router() -> axum::Router<AppState> {
             "/integrations/{integration_id}/sync-ical",
             axum::routing::post(sync_integration_ical),
         )
+        .route(
+            "/integrations/{integration_id}/sync-history",
+            axum::routing::get(list_integration_sync_history),
+        )
         .route(
             "/integrations/{integration_id}/sync-airbnb",
-            axum::routing::post(sync_integration_airbnb),
+            axum::routing::post(sync_integration_channel),
+        )
+        .route(
+            "/integrations/{integration_id}/sync-channel",
+            axum::routing::post(sync_integration_channel),
+        )
+        .route(
+            "/integrations/{integration_id}/push-ari",
+            axum::routing::post(push_integration_channel_ari),
+        )
+        .route(
+            "/integrations/bookingcom/connect",
+            axum::routing::post(bookingcom_connect),
         )
         .route(
             "/integrations/airbnb/auth-url",
@@ -67,6 +95,14 @@ pub fn router() -> axum::Router<AppState> {
             "/integrations/webhooks/{provider}",
             axum::routing::post(ingest_integration_webhook),
         )
+        .route(
+            "/integrations/webhook-secrets",
+            axum::routing::get(list_webhook_signing_secrets).post(rotate_webhook_signing_secret),
+        )
+        .route(
+            "/integrations/webhook-secrets/{secret_id}",
+            axum::routing::delete(delete_webhook_signing_secret),
+        )
         // --- Audit logs ---
         .route("/audit-logs", axum::routing::get(list_audit_logs))
         .route("/audit-logs/{log_id}", axum::routing::get(get_audit_log))
@@ -115,7 +151,13 @@ async fn create_integration(
     Json(payload): Json<CreateIntegrationInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &payload.organization_id, &["owner_admin"]).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::INTEGRATIONS_MANAGE,
+    )
+    .await?;
     let pool = db_pool(&state)?;
     let record = remove_nulls(serialize_to_map(&payload));
     let created = create_row(pool, "integrations", &record).await?;
@@ -159,7 +201,7 @@ async fn update_integration(
     let pool = db_pool(&state)?;
     let record = get_row(pool, "integrations", &path.integration_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::INTEGRATIONS_MANAGE).await?;
     let patch = remove_nulls(serialize_to_map(&payload));
     let updated = update_row(pool, "integrations", &path.integration_id, &patch, "id").await?;
     write_audit_log(
@@ -187,7 +229,7 @@ async fn delete_integration(
     let pool = db_pool(&state)?;
     let record = get_row(pool, "integrations", &path.integration_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::INTEGRATIONS_MANAGE).await?;
     let deleted = delete_row(pool, "integrations", &path.integration_id, "id").await?;
     write_audit_log(
         state.db_pool.as_ref(),
@@ -212,7 +254,7 @@ async fn sync_integration_ical(
     let pool = db_pool(&state)?;
     let record = get_row(pool, "integrations", &path.integration_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::INTEGRATIONS_SYNC).await?;
 
     let integration_event = create_row(
         pool,
@@ -243,7 +285,15 @@ async fn sync_integration_ical(
 
     let now_iso = chrono::Utc::now().to_rfc3339();
 
-    match sync_listing_ical_reservations(pool, &state.http_client, &record, &user_id).await {
+    match sync_listing_ical_reservations(
+        pool,
+        &state.http_client,
+        &record,
+        &user_id,
+        IcalSyncMode::Full,
+    )
+    .await
+    {
         Ok(result) => {
             let processed_at = result
                 .get("processed_at")
@@ -344,6 +394,52 @@ async fn sync_integration_ical(
     }
 }
 
+/// Per-sync diff records (`ical_sync_diff` events) for one integration,
+/// newest first.
+async fn list_integration_sync_history(
+    State(state): State<AppState>,
+    Path(path): Path<IntegrationPath>,
+    Query(query): Query<IntegrationSyncHistoryQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+    let record = get_row(pool, "integrations", &path.integration_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_org_member(&state, &user_id, &org_id).await?;
+
+    let rows = sqlx::query(
+        "SELECT row_to_json(t) AS row
+         FROM (
+           SELECT *
+           FROM integration_events
+           WHERE organization_id = $1::uuid
+             AND event_type = 'ical_sync_diff'
+             AND payload->>'integration_id' = $2
+           ORDER BY received_at DESC
+           LIMIT $3
+         ) t",
+    )
+    .bind(&org_id)
+    .bind(&path.integration_id)
+    .bind(clamp_limit_in_range(query.limit, 1, 500))
+    .fetch_all(pool)
+    .await
+    .map_err(|error| AppError::from_database_error(&error, "Database request failed."))?;
+
+    let data = rows
+        .into_iter()
+        .filter_map(|row| row.try_get::<Option<Value>, _>("row").ok().flatten())
+        .collect::<Vec<_>>();
+
+    Ok(Json(json!({
+        "integration_id": path.integration_id,
+        "last_ical_sync_at": record.get("last_ical_sync_at").cloned().unwrap_or(Value::Null),
+        "ical_sync_error": record.get("ical_sync_error").cloned().unwrap_or(Value::Null),
+        "data": data,
+    })))
+}
+
 // ========== Integration events ==========
 
 async fn list_integration_events(
@@ -428,11 +524,11 @@ async fn create_integration_event(
         .cloned()
         .ok_or_else(|| AppError::BadRequest("payload is required.".to_string()))?;
 
-    assert_org_role(
+    assert_permission(
         &state,
         &user_id,
         &organization_id,
-        &["owner_admin", "operator"],
+        permissions::INTEGRATIONS_SYNC,
     )
     .await?;
 
@@ -487,23 +583,64 @@ struct IngestWebhookQuery {
     external_event_id: Option<String>,
 }
 
+/// Ingest an event from an external system. Calls signed with the
+/// organization's signing secret for the provider need no session; unsigned
+/// calls still require an owner_admin or operator.
 async fn ingest_integration_webhook(
     State(state): State<AppState>,
     Path(provider): Path<String>,
     Query(query): Query<IngestWebhookQuery>,
+    Query(params): Query<HashMap<String, String>>,
     headers: HeaderMap,
-    Json(payload): Json<Value>,
+    body: Bytes,
 ) -> AppResult<impl IntoResponse> {
-    let user_id = require_user_id(&state, &headers).await?;
     let pool = db_pool(&state)?;
-
-    assert_org_role(
-        &state,
-        &user_id,
-        &query.org_id,
-        &["owner_admin", "operator"],
-    )
-    .await?;
+    let payload: Value = serde_json::from_slice(&body)
+        .map_err(|_| AppError::BadRequest("Invalid webhook JSON.".to_string()))?;
+
+    let mut external_event_id = non_empty_opt(query.external_event_id.as_deref());
+    let receipt_provider = format!("integration:{provider}");
+    let mut signed_event_id = None;
+    if headers.contains_key(webhook_verification::SIGNATURE_HEADER) {
+        let Some((secrets, tolerance_secs)) =
+            webhook_verification::org_signing_secrets(pool, &query.org_id, &provider).await?
+        else {
+            return Err(AppError::Unauthorized(
+                "No webhook signing secret is configured for this provider.".to_string(),
+            ));
+        };
+        let request = WebhookRequest {
+            headers: &headers,
+            query: &params,
+            body: &body,
+        };
+        let verifier = HmacVerifier {
+            provider: provider.clone(),
+            tolerance_secs,
+        };
+        let (verified, _) = webhook_verification::verify_with_any(&verifier, &request, &secrets)
+            .map_err(|error| webhook_verification::rejected(&provider, &error))?;
+        sqlx::query(
+            "UPDATE webhook_signing_secrets SET last_used_at = now()
+             WHERE organization_id = $1::uuid AND provider = $2",
+        )
+        .bind(&query.org_id)
+        .bind(&provider)
+        .execute(pool)
+        .await
+        .ok();
+        external_event_id = Some(verified.event_id.clone());
+        signed_event_id = Some(verified.event_id);
+    } else {
+        let user_id = require_user_id(&state, &headers).await?;
+        assert_permission(
+            &state,
+            &user_id,
+            &query.org_id,
+            permissions::INTEGRATIONS_SYNC,
+        )
+        .await?;
+    }
 
     let mut record = Map::new();
     record.insert(
@@ -515,7 +652,7 @@ async fn ingest_integration_webhook(
         "event_type".to_string(),
         Value::String(query.event_type.clone()),
     );
-    if let Some(external_event_id) = non_empty_opt(query.external_event_id.as_deref()) {
+    if let Some(external_event_id) = external_event_id {
         record.insert(
             "external_event_id".to_string(),
             Value::String(external_event_id),
@@ -524,8 +661,243 @@ async fn ingest_integration_webhook(
     record.insert("payload".to_string(), payload);
     record.insert("status".to_string(), Value::String("received".to_string()));
 
-    let created = create_row(pool, "integration_events", &record).await?;
-    Ok((StatusCode::CREATED, Json(created)))
+    let Some(event_id) = signed_event_id else {
+        let created = create_row(pool, "integration_events", &record).await?;
+        return Ok((StatusCode::CREATED, Json(created)));
+    };
+    // Event ids are only unique within the sending organization.
+    match webhook_verification::process_once(
+        pool,
+        &receipt_provider,
+        &format!("{}:{}", query.org_id, event_id),
+        Some(&query.org_id),
+        create_row(pool, "integration_events", &record),
+    )
+    .await?
+    {
+        Some(created) => Ok((StatusCode::CREATED, Json(created))),
+        None => Ok((
+            StatusCode::OK,
+            Json(json!({ "status": "duplicate", "external_event_id": event_id })),
+        )),
+    }
+}
+
+// ========== Webhook signing secrets ==========
+
+async fn list_webhook_signing_secrets(
+    State(state): State<AppState>,
+    Query(query): Query<WebhookSigningSecretsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &query.org_id,
+        permissions::WEBHOOKS_MANAGE,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(query.org_id.clone()),
+    );
+    let rows = list_rows(
+        pool,
+        "webhook_signing_secrets",
+        Some(&filters),
+        100,
+        0,
+        "provider",
+        true,
+    )
+    .await?;
+
+    Ok(Json(
+        json!({ "data": rows.into_iter().map(masked_signing_secret).collect::<Vec<_>>() }),
+    ))
+}
+
+/// Create the provider's signing secret or rotate the existing one. The new
+/// secret is only ever returned here; the previous one keeps verifying for
+/// a grace period so senders can switch over.
+async fn rotate_webhook_signing_secret(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<RotateWebhookSigningSecretInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::WEBHOOKS_MANAGE,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let provider = payload.provider.trim().to_lowercase();
+    if provider.is_empty()
+        || !provider
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(AppError::BadRequest(
+            "provider must be letters, digits, '_' or '-'.".to_string(),
+        ));
+    }
+    if payload
+        .tolerance_seconds
+        .is_some_and(|secs| !(30..=3600).contains(&secs))
+    {
+        return Err(AppError::BadRequest(
+            "tolerance_seconds must be between 30 and 3600.".to_string(),
+        ));
+    }
+
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(payload.organization_id.clone()),
+    );
+    filters.insert("provider".to_string(), Value::String(provider.clone()));
+    let existing = list_rows(
+        pool,
+        "webhook_signing_secrets",
+        Some(&filters),
+        1,
+        0,
+        "created_at",
+        false,
+    )
+    .await?
+    .into_iter()
+    .next();
+
+    let secret = webhook_verification::generate_secret();
+    let mut record = Map::new();
+    record.insert("secret".to_string(), Value::String(secret));
+    record.insert("is_active".to_string(), Value::Bool(true));
+    if let Some(tolerance_seconds) = payload.tolerance_seconds {
+        record.insert("tolerance_seconds".to_string(), json!(tolerance_seconds));
+    }
+
+    let mut saved = match existing.as_ref() {
+        Some(current) => {
+            record.insert(
+                "previous_secret".to_string(),
+                Value::String(value_str(current, "secret")),
+            );
+            record.insert(
+                "previous_secret_expires_at".to_string(),
+                Value::String(
+                    (chrono::Utc::now()
+                        + chrono::Duration::hours(webhook_verification::ROTATION_GRACE_HOURS))
+                    .to_rfc3339(),
+                ),
+            );
+            update_row(
+                pool,
+                "webhook_signing_secrets",
+                &value_str(current, "id"),
+                &record,
+                "id",
+            )
+            .await?
+        }
+        None => {
+            record.insert(
+                "organization_id".to_string(),
+                Value::String(payload.organization_id.clone()),
+            );
+            record.insert("provider".to_string(), Value::String(provider));
+            record.insert(
+                "created_by_user_id".to_string(),
+                Value::String(user_id.clone()),
+            );
+            create_row(pool, "webhook_signing_secrets", &record).await?
+        }
+    };
+
+    let entity_id = value_str(&saved, "id");
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        if existing.is_some() {
+            "rotate"
+        } else {
+            "create"
+        },
+        "webhook_signing_secrets",
+        Some(&entity_id),
+        existing.map(masked_signing_secret),
+        Some(masked_signing_secret(saved.clone())),
+    )
+    .await;
+
+    // Only the new secret is shown, once; the rotated-out one stays server-side.
+    if let Some(obj) = saved.as_object_mut() {
+        obj.remove("previous_secret");
+    }
+    Ok((StatusCode::CREATED, Json(saved)))
+}
+
+async fn delete_webhook_signing_secret(
+    State(state): State<AppState>,
+    Path(path): Path<WebhookSigningSecretPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let record = get_row(pool, "webhook_signing_secrets", &path.secret_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::WEBHOOKS_MANAGE).await?;
+
+    delete_row(pool, "webhook_signing_secrets", &path.secret_id, "id").await?;
+    let masked = masked_signing_secret(record);
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "delete",
+        "webhook_signing_secrets",
+        Some(&path.secret_id),
+        Some(masked.clone()),
+        None,
+    )
+    .await;
+
+    Ok(Json(masked))
+}
+
+/// Never echo stored secrets back; keep the last characters as a hint.
+fn masked_signing_secret(mut row: Value) -> Value {
+    if let Some(obj) = row.as_object_mut() {
+        let hint = obj
+            .get("secret")
+            .and_then(Value::as_str)
+            .map(|secret| {
+                let tail: String = secret
+                    .chars()
+                    .rev()
+                    .take(4)
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .rev()
+                    .collect();
+                format!("…{tail}")
+            })
+            .unwrap_or_default();
+        obj.insert("secret".to_string(), Value::String(hint));
+        obj.remove("previous_secret");
+    }
+    row
 }
 
 // ========== Audit logs ==========
@@ -642,11 +1014,11 @@ async fn airbnb_auth_url(
     Json(payload): Json<AirbnbAuthUrlInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
+    assert_permission(
         &state,
         &user_id,
         &payload.org_id,
-        &["owner_admin", "operator"],
+        permissions::INTEGRATIONS_SYNC,
     )
     .await?;
 
@@ -666,11 +1038,11 @@ async fn airbnb_callback(
     Json(payload): Json<AirbnbCallbackInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
+    assert_permission(
         &state,
         &user_id,
         &payload.org_id,
-        &["owner_admin", "operator"],
+        permissions::INTEGRATIONS_SYNC,
     )
     .await?;
     let pool = db_pool(&state)?;
@@ -726,7 +1098,24 @@ async fn airbnb_callback(
     })))
 }
 
-async fn sync_integration_airbnb(
+/// Load an API-connected integration and its connector for a route.
+async fn channel_integration_for_route(
+    pool: &sqlx::PgPool,
+    integration_id: &str,
+) -> AppResult<(ChannelIntegration, Connector)> {
+    let integration = load_channel_integrations(pool, Some(integration_id))
+        .await
+        .map_err(AppError::Dependency)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            AppError::BadRequest("This integration has no API channel connector.".to_string())
+        })?;
+    let connector = Connector::for_integration(&integration).map_err(AppError::Dependency)?;
+    Ok((integration, connector))
+}
+
+async fn sync_integration_channel(
     State(state): State<AppState>,
     Path(path): Path<IntegrationPath>,
     headers: HeaderMap,
@@ -735,14 +1124,10 @@ async fn sync_integration_airbnb(
     let pool = db_pool(&state)?;
     let record = get_row(pool, "integrations", &path.integration_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    let unit_id = value_str(&record, "unit_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::INTEGRATIONS_SYNC).await?;
 
-    // Get stored tokens
-    let (access_token, listing_id) =
-        crate::services::airbnb::get_integration_airbnb_token(pool, &path.integration_id)
-            .await
-            .map_err(AppError::Dependency)?;
+    let (integration, connector) =
+        channel_integration_for_route(pool, &path.integration_id).await?;
 
     // Log sync event
     let integration_event = create_row(
@@ -750,7 +1135,7 @@ async fn sync_integration_airbnb(
         "integration_events",
         &serde_json::from_value::<Map<String, Value>>(json!({
             "organization_id": org_id,
-            "provider": "airbnb",
+            "provider": connector.kind(),
             "event_type": "listing_sync_requested",
             "payload": json!({"integration_id": path.integration_id, "requested_by_user_id": user_id}).to_string(),
             "status": "received",
@@ -760,17 +1145,7 @@ async fn sync_integration_airbnb(
     .await?;
     let event_id = value_str(&integration_event, "id");
 
-    match crate::services::airbnb::sync_airbnb_integration(
-        pool,
-        &state.http_client,
-        &path.integration_id,
-        &access_token,
-        &listing_id,
-        &org_id,
-        &unit_id,
-    )
-    .await
-    {
+    match sync_channel_reservations(pool, &state.http_client, &connector, &integration).await {
         Ok(report) => {
             // Mark event as processed
             let mut patch = Map::new();
@@ -807,3 +1182,110 @@ async fn sync_integration_airbnb(
         }
     }
 }
+
+/// Push availability, rates and restrictions to the channel right away
+/// instead of waiting for the daily outbound sync.
+async fn push_integration_channel_ari(
+    State(state): State<AppState>,
+    Path(path): Path<IntegrationPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+    let record = get_row(pool, "integrations", &path.integration_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::INTEGRATIONS_SYNC).await?;
+
+    let (integration, _) = channel_integration_for_route(pool, &path.integration_id).await?;
+    let report = push_integration_ari(pool, &state.http_client, &integration)
+        .await
+        .map_err(AppError::Dependency)?;
+
+    Ok(Json(json!({
+        "ok": true,
+        "integration_id": path.integration_id,
+        "push_report": report,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+struct BookingComConnectInput {
+    org_id: String,
+    integration_id: String,
+    hotel_id: String,
+    room_id: String,
+    rate_id: Option<String>,
+    min_stay: Option<u32>,
+}
+
+/// Map a Booking.com hotel/room/rate to a `bookingcom` integration.
+async fn bookingcom_connect(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<BookingComConnectInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.org_id,
+        permissions::INTEGRATIONS_SYNC,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let record = get_row(pool, "integrations", &payload.integration_id, "id").await?;
+    if value_str(&record, "organization_id") != payload.org_id {
+        return Err(AppError::NotFound("Integration not found.".to_string()));
+    }
+    if value_str(&record, "kind") != "bookingcom" {
+        return Err(AppError::BadRequest(
+            "Only Booking.com integrations can be mapped here.".to_string(),
+        ));
+    }
+    let hotel_id = payload.hotel_id.trim();
+    let room_id = payload.room_id.trim();
+    if hotel_id.is_empty() || room_id.is_empty() {
+        return Err(AppError::BadRequest(
+            "hotel_id and room_id are required.".to_string(),
+        ));
+    }
+
+    let mapping = json!({
+        "bookingcom_hotel_id": hotel_id,
+        "bookingcom_room_id": room_id,
+        "bookingcom_rate_id": payload.rate_id.as_deref().map(str::trim).filter(|v| !v.is_empty()),
+        "min_stay": payload.min_stay.filter(|value| *value > 0),
+    });
+    sqlx::query(
+        "UPDATE integrations SET
+           metadata = COALESCE(metadata, '{}'::jsonb) || jsonb_strip_nulls($2::jsonb),
+           external_account_ref = COALESCE(external_account_ref, $3),
+           updated_at = now()
+         WHERE id = $1::uuid",
+    )
+    .bind(&payload.integration_id)
+    .bind(&mapping)
+    .bind(hotel_id)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Failed to save Booking.com mapping."))?;
+
+    write_audit_log(
+        Some(pool),
+        Some(&payload.org_id),
+        Some(&user_id),
+        "bookingcom_connected",
+        "integrations",
+        Some(&payload.integration_id),
+        None,
+        Some(mapping.clone()),
+    )
+    .await;
+
+    Ok(Json(json!({
+        "ok": true,
+        "integration_id": payload.integration_id,
+        "mapping": mapping,
+    })))
+}
@@ -0,0 +1,486 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    auth::require_user_id,
+    error::{AppError, AppResult},
+    repository::table_service::{create_row, delete_row, get_row, list_rows, update_row},
+    schemas::{
+        clamp_limit, remove_nulls, serialize_to_map, AdjustLateFeeInput, CreateLateFeePolicyInput,
+        LateFeePath, LateFeePoliciesQuery, LateFeePolicyPath, LateFeesQuery,
+        UpdateLateFeePolicyInput, WaiveLateFeeInput,
+    },
+    services::{
+        audit::write_audit_log,
+        late_fees::{round_currency, LateFeeType},
+        permissions,
+    },
+    state::AppState,
+    tenancy::{assert_org_member, assert_permission},
+};
+
+pub fn router() -> axum::Router<AppState> {
+    axum::Router::new()
+        .route(
+            "/late-fee-policies",
+            axum::routing::get(list_policies).post(create_policy),
+        )
+        .route(
+            "/late-fee-policies/{policy_id}",
+            axum::routing::patch(update_policy).delete(delete_policy),
+        )
+        .route("/late-fees", axum::routing::get(list_late_fees))
+        .route(
+            "/late-fees/{charge_id}/waive",
+            axum::routing::post(waive_late_fee),
+        )
+        .route(
+            "/late-fees/{charge_id}/adjust",
+            axum::routing::post(adjust_late_fee),
+        )
+}
+
+// ── Policies ───────────────────────────────────────────────────────────
+
+async fn list_policies(
+    State(state): State<AppState>,
+    Query(query): Query<LateFeePoliciesQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(query.org_id.clone()),
+    );
+    if let Some(lease_id) = non_empty_opt(query.lease_id.as_deref()) {
+        filters.insert("lease_id".to_string(), Value::String(lease_id));
+    }
+    let rows = list_rows(
+        pool,
+        "late_fee_policies",
+        Some(&filters),
+        500,
+        0,
+        "created_at",
+        true,
+    )
+    .await?;
+    Ok(Json(json!({ "data": rows })))
+}
+
+async fn create_policy(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateLateFeePolicyInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::LATE_FEES_EDIT,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    if let Some(lease_id) = non_empty_opt(payload.lease_id.as_deref()) {
+        let lease = get_row(pool, "leases", &lease_id, "id").await?;
+        if value_str(&lease, "organization_id") != payload.organization_id {
+            return Err(AppError::BadRequest(
+                "lease_id does not belong to this organization.".to_string(),
+            ));
+        }
+    }
+
+    let mut record = remove_nulls(serialize_to_map(&payload));
+    validate_policy(&mut record)?;
+    record.insert("source".to_string(), Value::String("manual".to_string()));
+    record.insert(
+        "created_by_user_id".to_string(),
+        Value::String(user_id.clone()),
+    );
+
+    let created = create_row(pool, "late_fee_policies", &record).await?;
+    write_audit_log(
+        Some(pool),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        "create",
+        "late_fee_policies",
+        created.get("id").and_then(Value::as_str),
+        None,
+        Some(created.clone()),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+async fn update_policy(
+    State(state): State<AppState>,
+    Path(path): Path<LateFeePolicyPath>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateLateFeePolicyInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let existing = get_row(pool, "late_fee_policies", &path.policy_id, "id").await?;
+    let org_id = value_str(&existing, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::LATE_FEES_EDIT).await?;
+
+    let patch = remove_nulls(serialize_to_map(&payload));
+    if patch.is_empty() {
+        return Ok(Json(existing));
+    }
+    let mut merged = existing.as_object().cloned().unwrap_or_default();
+    merged.extend(patch.clone());
+    validate_policy(&mut merged)?;
+    let patch: Map<String, Value> = merged
+        .into_iter()
+        .filter(|(key, _)| patch.contains_key(key))
+        .collect();
+
+    let updated = update_row(pool, "late_fee_policies", &path.policy_id, &patch, "id").await?;
+    write_audit_log(
+        Some(pool),
+        Some(&org_id),
+        Some(&user_id),
+        "update",
+        "late_fee_policies",
+        Some(&path.policy_id),
+        Some(existing),
+        Some(updated.clone()),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+async fn delete_policy(
+    State(state): State<AppState>,
+    Path(path): Path<LateFeePolicyPath>,
+    headers: HeaderMap,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let existing = get_row(pool, "late_fee_policies", &path.policy_id, "id").await?;
+    let org_id = value_str(&existing, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::LATE_FEES_EDIT).await?;
+
+    delete_row(pool, "late_fee_policies", &path.policy_id, "id").await?;
+    write_audit_log(
+        Some(pool),
+        Some(&org_id),
+        Some(&user_id),
+        "delete",
+        "late_fee_policies",
+        Some(&path.policy_id),
+        Some(existing),
+        None,
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Check the pricing fields on a full record so partial updates are
+/// validated against the stored policy.
+fn validate_policy(record: &mut Map<String, Value>) -> AppResult<()> {
+    let fee_type = record
+        .get("fee_type")
+        .and_then(Value::as_str)
+        .and_then(LateFeeType::parse)
+        .ok_or_else(|| {
+            AppError::BadRequest(
+                "fee_type must be one of fixed, percentage, daily_interest.".to_string(),
+            )
+        })?;
+    record.insert(
+        "fee_type".to_string(),
+        Value::String(fee_type.as_str().to_string()),
+    );
+
+    let number = |key: &str| match record.get(key) {
+        Some(Value::Number(value)) => value.as_f64(),
+        Some(Value::String(value)) => value.trim().parse::<f64>().ok(),
+        _ => None,
+    };
+    let amount = number("amount").unwrap_or(0.0);
+    if amount <= 0.0 {
+        return Err(AppError::BadRequest(
+            "amount must be greater than zero.".to_string(),
+        ));
+    }
+    if fee_type != LateFeeType::Fixed && amount > 100.0 {
+        return Err(AppError::BadRequest(
+            "Percentage and daily interest rates cannot exceed 100.".to_string(),
+        ));
+    }
+    if number("grace_days").is_some_and(|days| !(0.0..=90.0).contains(&days)) {
+        return Err(AppError::BadRequest(
+            "grace_days must be between 0 and 90.".to_string(),
+        ));
+    }
+    if ["max_fee_amount", "max_fee_percent"]
+        .iter()
+        .any(|key| number(key).is_some_and(|cap| cap <= 0.0))
+    {
+        return Err(AppError::BadRequest(
+            "Fee caps must be greater than zero.".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// ── Assessed fees ──────────────────────────────────────────────────────
+
+async fn list_late_fees(
+    State(state): State<AppState>,
+    Query(query): Query<LateFeesQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(query.org_id.clone()),
+    );
+    filters.insert(
+        "charge_type".to_string(),
+        Value::String("late_fee".to_string()),
+    );
+    if let Some(lease_id) = non_empty_opt(query.lease_id.as_deref()) {
+        filters.insert("lease_id".to_string(), Value::String(lease_id));
+    }
+    if let Some(status) = non_empty_opt(query.status.as_deref()) {
+        filters.insert("status".to_string(), Value::String(status));
+    }
+    let rows = list_rows(
+        pool,
+        "lease_charges",
+        Some(&filters),
+        clamp_limit(query.limit),
+        0,
+        "charge_date",
+        false,
+    )
+    .await?;
+    Ok(Json(json!({ "data": rows })))
+}
+
+async fn waive_late_fee(
+    State(state): State<AppState>,
+    Path(path): Path<LateFeePath>,
+    headers: HeaderMap,
+    Json(payload): Json<WaiveLateFeeInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+    let reason = required_reason(&payload.reason)?;
+
+    let (charge, org_id) = load_late_fee(&state, pool, &path.charge_id, &user_id).await?;
+    match value_str(&charge, "status").as_str() {
+        "waived" => return Ok(Json(charge)),
+        "paid" => {
+            return Err(AppError::Conflict(
+                "This late fee has already been paid.".to_string(),
+            ))
+        }
+        _ => {}
+    }
+    let collections = open_fee_collections(pool, &path.charge_id).await?;
+
+    let now = Utc::now().to_rfc3339();
+    let mut collection_patch = Map::new();
+    collection_patch.insert("status".to_string(), Value::String("waived".to_string()));
+    for collection_id in &collections {
+        update_row(
+            pool,
+            "collection_records",
+            collection_id,
+            &collection_patch,
+            "id",
+        )
+        .await?;
+    }
+
+    let mut patch = Map::new();
+    patch.insert("status".to_string(), Value::String("waived".to_string()));
+    patch.insert("waived_at".to_string(), Value::String(now));
+    patch.insert(
+        "waived_by_user_id".to_string(),
+        Value::String(user_id.clone()),
+    );
+    patch.insert("waive_reason".to_string(), Value::String(reason));
+    let updated = update_row(pool, "lease_charges", &path.charge_id, &patch, "id").await?;
+
+    write_audit_log(
+        Some(pool),
+        Some(&org_id),
+        Some(&user_id),
+        "waive_late_fee",
+        "lease_charges",
+        Some(&path.charge_id),
+        Some(charge),
+        Some(updated.clone()),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+async fn adjust_late_fee(
+    State(state): State<AppState>,
+    Path(path): Path<LateFeePath>,
+    headers: HeaderMap,
+    Json(payload): Json<AdjustLateFeeInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+    let reason = required_reason(&payload.reason)?;
+    if !payload.amount.is_finite() || payload.amount <= 0.0 {
+        return Err(AppError::BadRequest(
+            "amount must be greater than zero; waive the fee to cancel it.".to_string(),
+        ));
+    }
+
+    let (charge, org_id) = load_late_fee(&state, pool, &path.charge_id, &user_id).await?;
+    let status = value_str(&charge, "status");
+    if status == "paid" || status == "waived" {
+        return Err(AppError::Conflict(format!(
+            "A {status} late fee cannot be adjusted."
+        )));
+    }
+    let amount = round_currency(payload.amount, &value_str(&charge, "currency"));
+    let collections = open_fee_collections(pool, &path.charge_id).await?;
+
+    let mut collection_patch = Map::new();
+    collection_patch.insert("amount".to_string(), json!(amount));
+    for collection_id in &collections {
+        update_row(
+            pool,
+            "collection_records",
+            collection_id,
+            &collection_patch,
+            "id",
+        )
+        .await?;
+    }
+
+    // adjusted_at also stops the daily cycle from re-accruing interest.
+    let mut patch = Map::new();
+    patch.insert("amount".to_string(), json!(amount));
+    patch.insert(
+        "adjusted_at".to_string(),
+        Value::String(Utc::now().to_rfc3339()),
+    );
+    patch.insert(
+        "adjusted_by_user_id".to_string(),
+        Value::String(user_id.clone()),
+    );
+    patch.insert("adjustment_reason".to_string(), Value::String(reason));
+    let updated = update_row(pool, "lease_charges", &path.charge_id, &patch, "id").await?;
+
+    write_audit_log(
+        Some(pool),
+        Some(&org_id),
+        Some(&user_id),
+        "adjust_late_fee",
+        "lease_charges",
+        Some(&path.charge_id),
+        Some(charge),
+        Some(updated.clone()),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+/// Fetch a late-fee charge and check the caller may manage it.
+async fn load_late_fee(
+    state: &AppState,
+    pool: &sqlx::PgPool,
+    charge_id: &str,
+    user_id: &str,
+) -> AppResult<(Value, String)> {
+    let charge = get_row(pool, "lease_charges", charge_id, "id").await?;
+    if value_str(&charge, "charge_type") != "late_fee" {
+        return Err(AppError::NotFound("Late fee not found.".to_string()));
+    }
+    let org_id = value_str(&charge, "organization_id");
+    assert_permission(state, user_id, &org_id, permissions::LATE_FEES_EDIT).await?;
+    Ok((charge, org_id))
+}
+
+/// Ids of the collection records billing this fee, refusing changes once
+/// any of them has been paid.
+async fn open_fee_collections(pool: &sqlx::PgPool, charge_id: &str) -> AppResult<Vec<String>> {
+    let mut filters = Map::new();
+    filters.insert(
+        "lease_charge_id".to_string(),
+        Value::String(charge_id.to_string()),
+    );
+    let rows = list_rows(
+        pool,
+        "collection_records",
+        Some(&filters),
+        50,
+        0,
+        "created_at",
+        true,
+    )
+    .await?;
+    if rows.iter().any(|row| value_str(row, "status") == "paid") {
+        return Err(AppError::Conflict(
+            "This late fee has already been collected.".to_string(),
+        ));
+    }
+    Ok(rows
+        .iter()
+        .filter(|row| value_str(row, "status") != "waived")
+        .map(|row| value_str(row, "id"))
+        .filter(|id| !id.is_empty())
+        .collect())
+}
+
+fn required_reason(reason: &str) -> AppResult<String> {
+    non_empty_opt(Some(reason))
+        .ok_or_else(|| AppError::BadRequest("reason is required.".to_string()))
+}
+
+fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
+    state
+        .db_pool
+        .as_ref()
+        .ok_or_else(|| AppError::Dependency("Database is not configured.".to_string()))
+}
+
+fn value_str(row: &Value, key: &str) -> String {
+    row.as_object()
+        .and_then(|obj| obj.get(key))
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+fn non_empty_opt(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+}
@@ -0,0 +1,867 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{NaiveDate, Utc};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    auth::require_user_id,
+    error::{AppError, AppResult},
+    repository::table_service::{create_row, delete_row, get_row, list_rows, update_row},
+    schemas::{
+        clamp_limit_in_range, AccrueLeaseDepositInterestInput, CompareLeaseDepositInspectionsInput,
+        CreateLeaseDepositDeductionInput, CreateLeaseDepositInput, LeaseDepositDeductionPath,
+        LeaseDepositPath, LeaseDepositsQuery, ReceiveLeaseDepositInput, RenderPdfInput,
+        SettleLeaseDepositInput,
+    },
+    services::{
+        audit::write_audit_log,
+        general_ledger,
+        lease_deposits::{self, Settlement, DEDUCTION_CATEGORIES},
+        pdf_documents::{self, DepositLetterPdf},
+        permissions,
+        storage::StorageNamespace,
+        vision_ai,
+    },
+    state::AppState,
+    tenancy::{assert_org_member, assert_permission},
+};
+
+/// Settling pays money out of trust.
+pub fn router() -> axum::Router<AppState> {
+    axum::Router::new()
+        .route(
+            "/lease-deposits",
+            axum::routing::get(list_lease_deposits).post(create_lease_deposit),
+        )
+        .route(
+            "/lease-deposits/{deposit_id}",
+            axum::routing::get(get_lease_deposit),
+        )
+        .route(
+            "/lease-deposits/{deposit_id}/receive",
+            axum::routing::post(receive_lease_deposit),
+        )
+        .route(
+            "/lease-deposits/{deposit_id}/accrue-interest",
+            axum::routing::post(accrue_lease_deposit_interest),
+        )
+        .route(
+            "/lease-deposits/{deposit_id}/inspection",
+            axum::routing::post(compare_move_out_inspection),
+        )
+        .route(
+            "/lease-deposits/{deposit_id}/deductions",
+            axum::routing::post(create_deduction),
+        )
+        .route(
+            "/lease-deposits/{deposit_id}/deductions/{deduction_id}",
+            axum::routing::delete(delete_deduction),
+        )
+        .route(
+            "/lease-deposits/{deposit_id}/letter",
+            axum::routing::post(render_deduction_letter),
+        )
+        .route(
+            "/lease-deposits/{deposit_id}/settle",
+            axum::routing::post(settle_lease_deposit),
+        )
+}
+
+// ── Deposits ────────────────────────────────────────────────────────
+
+async fn list_lease_deposits(
+    State(state): State<AppState>,
+    Query(query): Query<LeaseDepositsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = json_map(&[("organization_id", Value::String(query.org_id.clone()))]);
+    if let Some(lease_id) = non_empty_opt(query.lease_id.as_deref()) {
+        filters.insert("lease_id".to_string(), Value::String(lease_id));
+    }
+    if let Some(status) = non_empty_opt(query.status.as_deref()) {
+        filters.insert("status".to_string(), Value::String(status));
+    }
+    let rows = list_rows(
+        pool,
+        "lease_deposits",
+        Some(&filters),
+        clamp_limit_in_range(query.limit, 1, 500),
+        0,
+        "created_at",
+        false,
+    )
+    .await?;
+
+    Ok(Json(json!({ "data": rows })))
+}
+
+async fn create_lease_deposit(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateLeaseDepositInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let lease = get_row(pool, "leases", &payload.lease_id, "id").await?;
+    let org_id = value_str(&lease, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::LEASE_DEPOSITS_EDIT).await?;
+
+    let amount_required = payload
+        .amount_required
+        .unwrap_or_else(|| value_f64(&lease, "security_deposit"));
+    if !amount_required.is_finite() || amount_required < 0.0 {
+        return Err(AppError::BadRequest(
+            "amount_required cannot be negative.".to_string(),
+        ));
+    }
+    let interest_rate = payload.interest_rate.unwrap_or(0.0);
+    if !(0.0..=1.0).contains(&interest_rate) {
+        return Err(AppError::BadRequest(
+            "interest_rate must be a fraction between 0 and 1.".to_string(),
+        ));
+    }
+
+    let mut record = json_map(&[
+        ("organization_id", Value::String(org_id.clone())),
+        ("lease_id", Value::String(payload.lease_id.clone())),
+        ("currency", Value::String(value_str(&lease, "currency"))),
+        ("amount_required", json!(round2(amount_required))),
+        ("interest_rate", json!(interest_rate)),
+        ("created_by_user_id", Value::String(user_id.clone())),
+    ]);
+    if let Some(property_id) = non_empty_opt(lease.get("property_id").and_then(Value::as_str)) {
+        record.insert("property_id".to_string(), Value::String(property_id));
+    }
+    if let Some(notes) = non_empty_opt(payload.notes.as_deref()) {
+        record.insert("notes".to_string(), Value::String(notes));
+    }
+    let created = create_row(pool, "lease_deposits", &record).await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "create",
+        "lease_deposits",
+        Some(&value_str(&created, "id")),
+        None,
+        Some(created.clone()),
+    )
+    .await;
+
+    Ok((axum::http::StatusCode::CREATED, Json(created)))
+}
+
+async fn get_lease_deposit(
+    State(state): State<AppState>,
+    Path(path): Path<LeaseDepositPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let deposit = get_row(pool, "lease_deposits", &path.deposit_id, "id").await?;
+    let org_id = value_str(&deposit, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::LEASE_DEPOSITS_EDIT).await?;
+
+    let deductions = lease_deposits::load_deductions(pool, &path.deposit_id).await?;
+    Ok(Json(with_deductions(deposit, deductions)))
+}
+
+async fn receive_lease_deposit(
+    State(state): State<AppState>,
+    Path(path): Path<LeaseDepositPath>,
+    headers: HeaderMap,
+    Json(payload): Json<ReceiveLeaseDepositInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let deposit = get_row(pool, "lease_deposits", &path.deposit_id, "id").await?;
+    let org_id = value_str(&deposit, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::LEASE_DEPOSITS_EDIT).await?;
+    expect_status(&deposit, &["pending"], "receive")?;
+
+    let mut patch = Map::new();
+    let mut amount = payload.amount;
+    let mut received_at = non_empty_opt(payload.received_at.as_deref());
+    if let Some(payment_id) = non_empty_opt(payload.lease_payment_id.as_deref()) {
+        let payment = get_row(pool, "lease_payments", &payment_id, "id").await?;
+        if value_str(&payment, "lease_id") != value_str(&deposit, "lease_id") {
+            return Err(AppError::BadRequest(
+                "The payment belongs to a different lease.".to_string(),
+            ));
+        }
+        amount = amount.or_else(|| Some(value_f64(&payment, "amount")));
+        received_at = received_at
+            .or_else(|| non_empty_opt(payment.get("received_at").and_then(Value::as_str)));
+        patch.insert("lease_payment_id".to_string(), Value::String(payment_id));
+    }
+    let amount = round2(amount.unwrap_or_else(|| value_f64(&deposit, "amount_required")));
+    if amount <= 0.0 {
+        return Err(AppError::BadRequest(
+            "The deposit amount must be greater than zero.".to_string(),
+        ));
+    }
+
+    patch.insert("status".to_string(), Value::String("held".to_string()));
+    patch.insert("amount_received".to_string(), json!(amount));
+    patch.insert(
+        "received_at".to_string(),
+        Value::String(received_at.unwrap_or_else(|| Utc::now().to_rfc3339())),
+    );
+    if let Some(reference) = non_empty_opt(payload.reference.as_deref()) {
+        patch.insert("receipt_reference".to_string(), Value::String(reference));
+    }
+    let updated = update_row(pool, "lease_deposits", &path.deposit_id, &patch, "id").await?;
+    general_ledger::record_lease_deposit_event(
+        pool,
+        &updated,
+        "received",
+        amount,
+        "received",
+        Some(&user_id),
+    )
+    .await;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "receive_deposit",
+        "lease_deposits",
+        Some(&path.deposit_id),
+        Some(deposit),
+        Some(updated.clone()),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+async fn accrue_lease_deposit_interest(
+    State(state): State<AppState>,
+    Path(path): Path<LeaseDepositPath>,
+    headers: HeaderMap,
+    Json(payload): Json<AccrueLeaseDepositInterestInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let deposit = get_row(pool, "lease_deposits", &path.deposit_id, "id").await?;
+    let org_id = value_str(&deposit, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::LEASE_DEPOSITS_EDIT).await?;
+    expect_status(&deposit, &["held", "move_out"], "accrue interest on")?;
+
+    let as_of = match non_empty_opt(payload.as_of.as_deref()) {
+        Some(value) => parse_date(&value, "as_of")?,
+        None => Utc::now().date_naive(),
+    };
+    let updated = accrue_interest(pool, &deposit, as_of, &user_id).await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "accrue_deposit_interest",
+        "lease_deposits",
+        Some(&path.deposit_id),
+        Some(deposit),
+        Some(updated.clone()),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+// ── Move-out ────────────────────────────────────────────────────────
+
+async fn compare_move_out_inspection(
+    State(state): State<AppState>,
+    Path(path): Path<LeaseDepositPath>,
+    headers: HeaderMap,
+    Json(payload): Json<CompareLeaseDepositInspectionsInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let deposit = get_row(pool, "lease_deposits", &path.deposit_id, "id").await?;
+    let org_id = value_str(&deposit, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::LEASE_DEPOSITS_EDIT).await?;
+    expect_status(&deposit, &["held", "move_out"], "inspect")?;
+
+    let mut args = json_map(&[(
+        "current_report_id",
+        Value::String(payload.move_out_report_id.clone()),
+    )]);
+    if let Some(baseline_id) = non_empty_opt(payload.baseline_report_id.as_deref()) {
+        args.insert("baseline_report_id".to_string(), Value::String(baseline_id));
+    }
+    let comparison = vision_ai::tool_compare_inspections(&state, &org_id, &args).await?;
+    if comparison.get("ok").and_then(Value::as_bool) != Some(true) {
+        return Err(AppError::BadRequest(
+            comparison
+                .get("error")
+                .and_then(Value::as_str)
+                .unwrap_or("Could not compare the inspections.")
+                .to_string(),
+        ));
+    }
+
+    let photos: Value = sqlx::query_scalar(
+        "SELECT photos FROM inspection_reports
+         WHERE id = $1::uuid AND organization_id = $2::uuid",
+    )
+    .bind(&payload.move_out_report_id)
+    .bind(&org_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load the inspection photos."))?
+    .unwrap_or_else(|| json!([]));
+
+    let mut patch = json_map(&[
+        ("status", Value::String("move_out".to_string())),
+        (
+            "move_out_inspection_id",
+            Value::String(payload.move_out_report_id.clone()),
+        ),
+        ("inspection_comparison", comparison.clone()),
+    ]);
+    if let Some(baseline_id) = non_empty_opt(payload.baseline_report_id.as_deref()) {
+        patch.insert(
+            "baseline_inspection_id".to_string(),
+            Value::String(baseline_id),
+        );
+    }
+    let updated = update_row(pool, "lease_deposits", &path.deposit_id, &patch, "id").await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "compare_inspections",
+        "lease_deposits",
+        Some(&path.deposit_id),
+        Some(deposit),
+        Some(updated.clone()),
+    )
+    .await;
+
+    Ok(Json(json!({
+        "deposit": updated,
+        "comparison": comparison,
+        "photos": photos,
+        "suggested_deductions": lease_deposits::suggested_deductions(
+            &comparison,
+            &payload.move_out_report_id,
+        ),
+    })))
+}
+
+async fn create_deduction(
+    State(state): State<AppState>,
+    Path(path): Path<LeaseDepositPath>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateLeaseDepositDeductionInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let deposit = get_row(pool, "lease_deposits", &path.deposit_id, "id").await?;
+    let org_id = value_str(&deposit, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::LEASE_DEPOSITS_EDIT).await?;
+    expect_status(&deposit, &["held", "move_out"], "add deductions to")?;
+
+    let category = payload.category.trim().to_ascii_lowercase();
+    if !DEDUCTION_CATEGORIES.contains(&category.as_str()) {
+        return Err(AppError::BadRequest(format!(
+            "category must be one of: {}.",
+            DEDUCTION_CATEGORIES.join(", ")
+        )));
+    }
+    if payload.description.trim().is_empty() {
+        return Err(AppError::BadRequest("description is required.".to_string()));
+    }
+    if !payload.amount.is_finite() || payload.amount <= 0.0 {
+        return Err(AppError::BadRequest(
+            "amount must be greater than zero.".to_string(),
+        ));
+    }
+
+    let mut photo_urls = payload.photo_urls.clone().unwrap_or_default();
+    let mut record = json_map(&[
+        ("organization_id", Value::String(org_id.clone())),
+        ("deposit_id", Value::String(path.deposit_id.clone())),
+        ("category", Value::String(category)),
+        (
+            "description",
+            Value::String(payload.description.trim().to_string()),
+        ),
+        ("amount", json!(round2(payload.amount))),
+        ("created_by_user_id", Value::String(user_id.clone())),
+    ]);
+    if let Some(request_id) = non_empty_opt(payload.maintenance_request_id.as_deref()) {
+        let request = get_row(pool, "maintenance_requests", &request_id, "id").await?;
+        let lease = get_row(pool, "leases", &value_str(&deposit, "lease_id"), "id").await?;
+        let same_lease = value_str(&request, "lease_id") == value_str(&lease, "id");
+        let same_unit = !value_str(&lease, "unit_id").is_empty()
+            && value_str(&request, "unit_id") == value_str(&lease, "unit_id");
+        if value_str(&request, "organization_id") != org_id || !(same_lease || same_unit) {
+            return Err(AppError::BadRequest(
+                "The maintenance request is not for this lease or unit.".to_string(),
+            ));
+        }
+        if photo_urls.is_empty() {
+            photo_urls = request
+                .get("photo_urls")
+                .and_then(Value::as_array)
+                .map(|urls| {
+                    urls.iter()
+                        .filter_map(Value::as_str)
+                        .map(ToOwned::to_owned)
+                        .collect()
+                })
+                .unwrap_or_default();
+        }
+        record.insert(
+            "maintenance_request_id".to_string(),
+            Value::String(request_id),
+        );
+    }
+    if let Some(report_id) = non_empty_opt(payload.inspection_report_id.as_deref()) {
+        record.insert("inspection_report_id".to_string(), Value::String(report_id));
+    }
+    if let Some(room) = non_empty_opt(payload.room.as_deref()) {
+        record.insert("room".to_string(), Value::String(room));
+    }
+    record.insert("photo_urls".to_string(), json!(photo_urls));
+
+    let created = create_row(pool, "lease_deposit_deductions", &record).await?;
+    refresh_deductions_total(pool, &path.deposit_id).await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "create",
+        "lease_deposit_deductions",
+        Some(&value_str(&created, "id")),
+        None,
+        Some(created.clone()),
+    )
+    .await;
+
+    Ok((axum::http::StatusCode::CREATED, Json(created)))
+}
+
+async fn delete_deduction(
+    State(state): State<AppState>,
+    Path(path): Path<LeaseDepositDeductionPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let deposit = get_row(pool, "lease_deposits", &path.deposit_id, "id").await?;
+    let org_id = value_str(&deposit, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::LEASE_DEPOSITS_EDIT).await?;
+    expect_status(&deposit, &["held", "move_out"], "remove deductions from")?;
+
+    let deduction = get_row(pool, "lease_deposit_deductions", &path.deduction_id, "id").await?;
+    if value_str(&deduction, "deposit_id") != path.deposit_id {
+        return Err(AppError::NotFound(
+            "Deduction not found for this deposit.".to_string(),
+        ));
+    }
+    delete_row(pool, "lease_deposit_deductions", &path.deduction_id, "id").await?;
+    let updated = refresh_deductions_total(pool, &path.deposit_id).await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "delete",
+        "lease_deposit_deductions",
+        Some(&path.deduction_id),
+        Some(deduction),
+        None,
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+async fn render_deduction_letter(
+    State(state): State<AppState>,
+    Path(path): Path<LeaseDepositPath>,
+    headers: HeaderMap,
+    payload: Option<Json<RenderPdfInput>>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+    let payload = payload.map(|Json(payload)| payload).unwrap_or_default();
+
+    let deposit = get_row(pool, "lease_deposits", &path.deposit_id, "id").await?;
+    let org_id = value_str(&deposit, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::LEASE_DEPOSITS_EDIT).await?;
+    expect_status(
+        &deposit,
+        &["move_out", "settled"],
+        "write a deduction letter for",
+    )?;
+
+    let branding = pdf_documents::load_branding(pool, &state.http_client, &org_id).await?;
+    let lang = branding.lang_for(payload.lang.as_deref())?;
+    let lease = get_row(pool, "leases", &value_str(&deposit, "lease_id"), "id").await?;
+    let (property_name, unit_name, inspected_at) =
+        sqlx::query_as::<_, (Option<String>, Option<String>, Option<String>)>(
+            "SELECT (SELECT name FROM properties WHERE id = $1::uuid),
+                    (SELECT name FROM units WHERE id = $2::uuid),
+                    (SELECT inspected_at::text FROM inspection_reports WHERE id = $3::uuid)",
+        )
+        .bind(non_empty_opt(
+            lease.get("property_id").and_then(Value::as_str),
+        ))
+        .bind(non_empty_opt(lease.get("unit_id").and_then(Value::as_str)))
+        .bind(non_empty_opt(
+            deposit
+                .get("move_out_inspection_id")
+                .and_then(Value::as_str),
+        ))
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Failed to load the lease details."))?;
+
+    let deductions = lease_deposits::load_deductions(pool, &path.deposit_id).await?;
+    let settlement = settlement_for(&deposit, &deductions);
+    let bytes = pdf_documents::render_deposit_letter(
+        &branding,
+        lang,
+        &DepositLetterPdf {
+            tenant_name: value_str(&lease, "tenant_full_name"),
+            property_name,
+            unit_name,
+            lease_starts_on: value_str(&lease, "starts_on"),
+            lease_ends_on: non_empty_opt(lease.get("ends_on").and_then(Value::as_str)),
+            currency: value_str(&deposit, "currency"),
+            received: settlement.held,
+            interest: settlement.interest,
+            deductions: &deductions,
+            refund: settlement.refund,
+            balance_due: settlement.balance_due,
+            inspected_at,
+        },
+    );
+    let stored = pdf_documents::store_pdf(
+        &state.config,
+        StorageNamespace::Documents,
+        &org_id,
+        "deposit-letters",
+        &path.deposit_id,
+        bytes,
+    )
+    .await?;
+
+    let updated = update_row(
+        pool,
+        "lease_deposits",
+        &path.deposit_id,
+        &json_map(&[("letter_pdf_url", Value::String(stored.public_url.clone()))]),
+        "id",
+    )
+    .await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "render_pdf",
+        "lease_deposits",
+        Some(&path.deposit_id),
+        Some(deposit),
+        Some(updated),
+    )
+    .await;
+
+    Ok(Json(json!({
+        "deposit_id": path.deposit_id,
+        "pdf_url": stored.public_url,
+        "object_key": stored.object_key,
+        "size_bytes": stored.size_bytes,
+        "lang": lang.code(),
+        "settlement": settlement.to_json(),
+    })))
+}
+
+/// Accrue interest to today, keep the deductions and refund the rest.
+async fn settle_lease_deposit(
+    State(state): State<AppState>,
+    Path(path): Path<LeaseDepositPath>,
+    headers: HeaderMap,
+    Json(payload): Json<SettleLeaseDepositInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let deposit = get_row(pool, "lease_deposits", &path.deposit_id, "id").await?;
+    let org_id = value_str(&deposit, "organization_id");
+    assert_permission(
+        &state,
+        &user_id,
+        &org_id,
+        permissions::LEASE_DEPOSITS_SETTLE,
+    )
+    .await?;
+    expect_status(&deposit, &["held", "move_out"], "settle")?;
+
+    let accrued = accrue_interest(pool, &deposit, Utc::now().date_naive(), &user_id).await?;
+    let deductions = lease_deposits::load_deductions(pool, &path.deposit_id).await?;
+    let settlement = settlement_for(&accrued, &deductions);
+
+    let payment = match non_empty_opt(accrued.get("lease_payment_id").and_then(Value::as_str)) {
+        Some(payment_id) => Some(get_row(pool, "lease_payments", &payment_id, "id").await?),
+        None => None,
+    };
+    let refundable = payment
+        .as_ref()
+        .is_some_and(lease_deposits::provider_refundable);
+    let through_provider = match payload.refund_method.as_deref().map(str::trim) {
+        None | Some("") => refundable,
+        Some("original") if refundable => true,
+        Some("original") => {
+            return Err(AppError::UnprocessableEntity(
+                "The deposit was not paid through a provider that can refund it.".to_string(),
+            ))
+        }
+        Some("bank_transfer") => false,
+        Some(other) => {
+            return Err(AppError::BadRequest(format!(
+                "refund_method must be 'original' or 'bank_transfer', not '{other}'."
+            )))
+        }
+    };
+
+    let mut refund_method = None;
+    let mut refund_reference = non_empty_opt(payload.refund_reference.as_deref());
+    if settlement.refund > 0.0 {
+        if through_provider {
+            let payment_id = payment
+                .as_ref()
+                .map(|payment| value_str(payment, "id"))
+                .unwrap_or_default();
+            let (provider, reference) = lease_deposits::refund_through_provider(
+                pool,
+                &state.http_client,
+                &state.config,
+                &payment_id,
+                settlement.refund,
+                &lease_deposits::refund_idempotency_key(&path.deposit_id),
+            )
+            .await?;
+            refund_method = Some(provider);
+            refund_reference = Some(reference).filter(|value| !value.is_empty());
+        } else {
+            refund_method = Some("bank_transfer".to_string());
+        }
+    }
+
+    let mut patch = json_map(&[
+        ("status", Value::String("settled".to_string())),
+        ("deductions_total", json!(settlement.deductions)),
+        ("refund_amount", json!(settlement.refund)),
+        ("balance_due", json!(settlement.balance_due)),
+        ("settled_at", Value::String(Utc::now().to_rfc3339())),
+    ]);
+    if let Some(method) = refund_method {
+        patch.insert("refund_method".to_string(), Value::String(method));
+    }
+    if let Some(reference) = refund_reference {
+        patch.insert("refund_reference".to_string(), Value::String(reference));
+    }
+    let updated = update_row(pool, "lease_deposits", &path.deposit_id, &patch, "id").await?;
+    general_ledger::record_lease_deposit_event(
+        pool,
+        &updated,
+        "deducted",
+        settlement.applied,
+        "deducted",
+        Some(&user_id),
+    )
+    .await;
+    general_ledger::record_lease_deposit_event(
+        pool,
+        &updated,
+        "refunded",
+        settlement.refund,
+        "refunded",
+        Some(&user_id),
+    )
+    .await;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "settle_deposit",
+        "lease_deposits",
+        Some(&path.deposit_id),
+        Some(deposit),
+        Some(updated.clone()),
+    )
+    .await;
+
+    let mut response = with_deductions(updated, deductions);
+    if let Some(obj) = response.as_object_mut() {
+        obj.insert("settlement".to_string(), settlement.to_json());
+    }
+    Ok(Json(response))
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────
+
+/// Add interest from the last accrual (or receipt) up to `as_of` and post
+/// the increase.
+async fn accrue_interest(
+    pool: &sqlx::PgPool,
+    deposit: &Value,
+    as_of: NaiveDate,
+    user_id: &str,
+) -> AppResult<Value> {
+    let deposit_id = value_str(deposit, "id");
+    let rate = value_f64(deposit, "interest_rate");
+    let from = value_str(deposit, "interest_accrued_through")
+        .parse::<NaiveDate>()
+        .ok()
+        .or_else(|| {
+            value_str(deposit, "received_at")
+                .get(..10)
+                .and_then(|value| value.parse::<NaiveDate>().ok())
+        });
+    let Some(from) = from.filter(|from| rate > 0.0 && *from < as_of) else {
+        return Ok(deposit.clone());
+    };
+
+    let interest =
+        lease_deposits::accrue_interest(value_f64(deposit, "amount_received"), rate, from, as_of);
+    let patch = json_map(&[
+        (
+            "interest_accrued",
+            json!(round2(value_f64(deposit, "interest_accrued") + interest)),
+        ),
+        ("interest_accrued_through", Value::String(as_of.to_string())),
+    ]);
+    let updated = update_row(pool, "lease_deposits", &deposit_id, &patch, "id").await?;
+    general_ledger::record_lease_deposit_event(
+        pool,
+        &updated,
+        "interest",
+        interest,
+        &format!("interest:{as_of}"),
+        Some(user_id),
+    )
+    .await;
+    Ok(updated)
+}
+
+async fn refresh_deductions_total(pool: &sqlx::PgPool, deposit_id: &str) -> AppResult<Value> {
+    let deductions = lease_deposits::load_deductions(pool, deposit_id).await?;
+    update_row(
+        pool,
+        "lease_deposits",
+        deposit_id,
+        &json_map(&[(
+            "deductions_total",
+            json!(lease_deposits::deductions_total(&deductions)),
+        )]),
+        "id",
+    )
+    .await
+}
+
+fn settlement_for(deposit: &Value, deductions: &[Value]) -> Settlement {
+    Settlement::compute(
+        value_f64(deposit, "amount_received"),
+        value_f64(deposit, "interest_accrued"),
+        lease_deposits::deductions_total(deductions),
+    )
+}
+
+fn with_deductions(deposit: Value, deductions: Vec<Value>) -> Value {
+    let settlement = settlement_for(&deposit, &deductions);
+    let mut response = deposit.as_object().cloned().unwrap_or_default();
+    response.insert("deductions".to_string(), Value::Array(deductions));
+    response.insert("settlement".to_string(), settlement.to_json());
+    Value::Object(response)
+}
+
+fn expect_status(deposit: &Value, allowed: &[&str], action: &str) -> AppResult<()> {
+    let status = value_str(deposit, "status");
+    if allowed.contains(&status.as_str()) {
+        return Ok(());
+    }
+    Err(AppError::UnprocessableEntity(format!(
+        "Cannot {action} a deposit in status '{status}'; expected {}.",
+        allowed
+            .iter()
+            .map(|status| format!("'{status}'"))
+            .collect::<Vec<_>>()
+            .join(" or ")
+    )))
+}
+
+fn parse_date(value: &str, field: &str) -> AppResult<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| AppError::BadRequest(format!("{field} must be an ISO date.")))
+}
+
+fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
+    state.db_pool.as_ref().ok_or_else(|| {
+        AppError::Dependency("Database is not configured. Set DATABASE_URL.".to_string())
+    })
+}
+
+fn value_str(row: &Value, key: &str) -> String {
+    row.as_object()
+        .and_then(|obj| obj.get(key))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+fn value_f64(row: &Value, key: &str) -> f64 {
+    match row.as_object().and_then(|obj| obj.get(key)) {
+        Some(Value::Number(value)) => value.as_f64().unwrap_or(0.0),
+        Some(Value::String(text)) => text.trim().parse().unwrap_or(0.0),
+        _ => 0.0,
+    }
+}
+
+fn non_empty_opt(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+fn json_map(entries: &[(&str, Value)]) -> Map<String, Value> {
+    let mut map = Map::new();
+    for (key, value) in entries {
+        map.insert((*key).to_string(), value.clone());
+    }
+    map
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
@@ -0,0 +1,254 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    auth::require_user_id,
+    error::{AppError, AppResult},
+    repository::table_service::{get_row, list_rows},
+    schemas::{
+        clamp_limit, CreateLeasePaymentInput, LeasePath, LeasePaymentPath, LeasePaymentsQuery,
+    },
+    services::{
+        audit::write_audit_log,
+        fx,
+        payment_ledger::{self, Allocation, NewPayment, PaymentSource},
+        permissions,
+    },
+    state::AppState,
+    tenancy::{assert_org_member, assert_permission},
+};
+
+pub fn router() -> axum::Router<AppState> {
+    axum::Router::new()
+        .route(
+            "/lease-payments",
+            axum::routing::get(list_payments).post(create_payment),
+        )
+        .route(
+            "/lease-payments/{payment_id}",
+            axum::routing::get(get_payment),
+        )
+        .route(
+            "/leases/{lease_id}/balance",
+            axum::routing::get(get_lease_balance),
+        )
+        .route(
+            "/leases/{lease_id}/apply-credit",
+            axum::routing::post(apply_credit),
+        )
+}
+
+async fn list_payments(
+    State(state): State<AppState>,
+    Query(query): Query<LeasePaymentsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(query.org_id.clone()),
+    );
+    if let Some(lease_id) = non_empty_opt(query.lease_id.as_deref()) {
+        filters.insert("lease_id".to_string(), Value::String(lease_id));
+    }
+    if let Some(source) = non_empty_opt(query.source.as_deref()) {
+        filters.insert("source".to_string(), Value::String(source));
+    }
+    let rows = list_rows(
+        pool,
+        "lease_payments",
+        Some(&filters),
+        clamp_limit(query.limit),
+        0,
+        "received_at",
+        false,
+    )
+    .await?;
+    Ok(Json(json!({ "data": rows })))
+}
+
+/// Record a payment received outside the online checkout (cash, transfer
+/// confirmed by hand) and allocate it across the lease's balances.
+async fn create_payment(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateLeasePaymentInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::PAYMENTS_EDIT,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let lease = get_row(pool, "leases", &payload.lease_id, "id").await?;
+    if value_str(&lease, "organization_id") != payload.organization_id {
+        return Err(AppError::BadRequest(
+            "lease_id does not belong to this organization.".to_string(),
+        ));
+    }
+    let currency = match non_empty_opt(payload.currency.as_deref()) {
+        Some(currency) => fx::normalize_currency(&currency).ok_or_else(|| {
+            AppError::BadRequest("currency must be a 3-letter ISO 4217 code.".to_string())
+        })?,
+        None => value_str(&lease, "currency"),
+    };
+
+    let recorded = payment_ledger::record_payment(
+        pool,
+        &NewPayment {
+            organization_id: payload.organization_id.clone(),
+            lease_id: payload.lease_id.clone(),
+            source: PaymentSource::Manual,
+            amount: payload.amount,
+            currency,
+            payment_method: non_empty_opt(payload.payment_method.as_deref()),
+            received_at: non_empty_opt(payload.received_at.as_deref()),
+            reference: non_empty_opt(payload.reference.as_deref()),
+            external_id: None,
+            bank_transaction_id: None,
+            notes: non_empty_opt(payload.notes.as_deref()),
+            created_by_user_id: Some(user_id.clone()),
+        },
+        &payload.collection_ids,
+    )
+    .await?;
+
+    write_audit_log(
+        Some(pool),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        "record_payment",
+        "lease_payments",
+        recorded.payment.get("id").and_then(Value::as_str),
+        None,
+        Some(recorded.to_json()),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(recorded.to_json())))
+}
+
+async fn get_payment(
+    State(state): State<AppState>,
+    Path(path): Path<LeasePaymentPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let payment = get_row(pool, "lease_payments", &path.payment_id, "id").await?;
+    assert_org_member(&state, &user_id, &value_str(&payment, "organization_id")).await?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "payment_id".to_string(),
+        Value::String(path.payment_id.clone()),
+    );
+    let allocations = list_rows(
+        pool,
+        "payment_allocations",
+        Some(&filters),
+        500,
+        0,
+        "created_at",
+        true,
+    )
+    .await?;
+
+    let mut item = payment;
+    if let Some(obj) = item.as_object_mut() {
+        obj.insert("allocations".to_string(), Value::Array(allocations));
+    }
+    Ok(Json(item))
+}
+
+async fn get_lease_balance(
+    State(state): State<AppState>,
+    Path(path): Path<LeasePath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let lease = get_row(pool, "leases", &path.lease_id, "id").await?;
+    assert_org_member(&state, &user_id, &value_str(&lease, "organization_id")).await?;
+
+    let mut balance =
+        payment_ledger::lease_balance(pool, &path.lease_id, Utc::now().date_naive()).await?;
+    if let Some(obj) = balance.as_object_mut() {
+        obj.insert(
+            "lease_status".to_string(),
+            lease.get("lease_status").cloned().unwrap_or(Value::Null),
+        );
+    }
+    Ok(Json(balance))
+}
+
+/// Apply unallocated credit to balances that are due now, without waiting
+/// for the daily collection cycle.
+async fn apply_credit(
+    State(state): State<AppState>,
+    Path(path): Path<LeasePath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let lease = get_row(pool, "leases", &path.lease_id, "id").await?;
+    let org_id = value_str(&lease, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::PAYMENTS_EDIT).await?;
+
+    let applied = payment_ledger::apply_lease_credit(pool, &path.lease_id).await?;
+    let applied: Vec<Value> = applied.iter().map(Allocation::to_json).collect();
+    if !applied.is_empty() {
+        write_audit_log(
+            Some(pool),
+            Some(&org_id),
+            Some(&user_id),
+            "apply_credit",
+            "leases",
+            Some(&path.lease_id),
+            None,
+            Some(json!({ "allocations": applied })),
+        )
+        .await;
+    }
+
+    Ok(Json(json!({ "allocations": applied })))
+}
+
+fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
+    state
+        .db_pool
+        .as_ref()
+        .ok_or_else(|| AppError::Dependency("Database is not configured.".to_string()))
+}
+
+fn value_str(row: &Value, key: &str) -> String {
+    row.as_object()
+        .and_then(|obj| obj.get(key))
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+fn non_empty_opt(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+}
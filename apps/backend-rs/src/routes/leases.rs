@@ -20,15 +20,14 @@ use crate::{
         audit::write_audit_log,
         lease_schedule::ensure_monthly_lease_schedule,
         leases::{build_lease_detail_overview, build_leases_overview, enrich_lease_rows},
+        permissions,
         sequences::enroll_in_sequences,
         workflows::fire_trigger,
     },
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
-const LEASE_EDIT_ROLES: &[&str] = &["owner_admin", "operator", "accountant"];
-
 pub fn router() -> axum::Router<AppState> {
     axum::Router::new()
         .route(
@@ -469,7 +468,13 @@ async fn create_lease(
     ensure_lease_collections_enabled(&state)?;
 
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &payload.organization_id, LEASE_EDIT_ROLES).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::LEASES_EDIT,
+    )
+    .await?;
     let pool = db_pool(&state)?;
 
     let mut lease_payload = remove_nulls(serialize_to_map(&payload));
@@ -714,7 +719,7 @@ async fn update_lease(
 
     let record = get_row(pool, "leases", &path.lease_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, LEASE_EDIT_ROLES).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::LEASES_EDIT).await?;
 
     let mut patch = remove_nulls(serialize_to_map(&payload));
     normalize_lease_payload_for_write(&mut patch);
@@ -1424,7 +1429,7 @@ async fn send_renewal_offer(
 
     let lease = get_row(pool, "leases", &path.lease_id, "id").await?;
     let org_id = value_str(&lease, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::LEASES_RENEW).await?;
 
     let app_public_url =
         std::env::var("APP_PUBLIC_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
@@ -1477,7 +1482,7 @@ async fn accept_renewal(
 
     let original = get_row(pool, "leases", &path.lease_id, "id").await?;
     let org_id = value_str(&original, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, LEASE_EDIT_ROLES).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::LEASES_EDIT).await?;
 
     let renewal_status = value_str(&original, "renewal_status");
     if renewal_status != "offered" && renewal_status != "pending" {
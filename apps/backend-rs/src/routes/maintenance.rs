@@ -13,12 +13,13 @@ use crate::{
     schemas::clamp_limit_in_range,
     services::audit::write_audit_log,
     services::notification_center::{emit_event, EmitNotificationEventInput},
+    services::permissions,
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{
+        assert_org_member, assert_permission, assert_property_in_scope, scope_property_filter,
+    },
 };
 
-const MAINTENANCE_EDIT_ROLES: &[&str] = &["owner_admin", "operator"];
-
 pub fn router() -> axum::Router<AppState> {
     axum::Router::new()
         .route(
@@ -79,7 +80,7 @@ async fn list_maintenance_requests(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &query.org_id).await?;
     let pool = db_pool(&state)?;
 
     let mut filters = Map::new();
@@ -93,6 +94,17 @@ async fn list_maintenance_requests(
     if let Some(property_id) = non_empty_opt(query.property_id.as_deref()) {
         filters.insert("property_id".to_string(), Value::String(property_id));
     }
+    if !scope_property_filter(
+        &state,
+        &query.org_id,
+        &membership,
+        &mut filters,
+        "property_id",
+    )
+    .await?
+    {
+        return Ok(Json(json!({ "data": [] })));
+    }
 
     let rows = list_rows(
         pool,
@@ -118,7 +130,14 @@ async fn get_maintenance_request(
 
     let record = get_row(pool, "maintenance_requests", &path.request_id, "id").await?;
     let org_id = val_str(&record, "organization_id");
-    assert_org_member(&state, &user_id, &org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &org_id).await?;
+    assert_property_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&val_str(&record, "property_id")),
+    )
+    .await?;
 
     Ok(Json(record))
 }
@@ -134,7 +153,15 @@ async fn update_maintenance_request(
 
     let existing = get_row(pool, "maintenance_requests", &path.request_id, "id").await?;
     let org_id = val_str(&existing, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, MAINTENANCE_EDIT_ROLES).await?;
+    let membership =
+        assert_permission(&state, &user_id, &org_id, permissions::MAINTENANCE_EDIT).await?;
+    assert_property_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&val_str(&existing, "property_id")),
+    )
+    .await?;
 
     let previous_status = val_str(&existing, "status");
     let mut patch = Map::new();
@@ -0,0 +1,246 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
+};
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+
+use crate::{
+    auth::require_user_id,
+    error::{AppError, AppResult},
+    repository::table_service::{create_row, get_row, list_rows, update_row},
+    schemas::{
+        clamp_limit_in_range, remove_nulls, serialize_to_map, CreateManagementFeeScheduleInput,
+        ManagementFeeSchedulePath, ManagementFeeSchedulesQuery, UpdateManagementFeeScheduleInput,
+    },
+    services::{audit::write_audit_log, fx, management_fees, permissions},
+    state::AppState,
+    tenancy::{assert_org_member, assert_permission},
+};
+
+pub fn router() -> axum::Router<AppState> {
+    axum::Router::new()
+        .route(
+            "/management-fee-schedules",
+            axum::routing::get(list_schedules).post(create_schedule),
+        )
+        .route(
+            "/management-fee-schedules/{schedule_id}",
+            axum::routing::get(get_schedule).patch(update_schedule),
+        )
+}
+
+async fn list_schedules(
+    State(state): State<AppState>,
+    Query(query): Query<ManagementFeeSchedulesQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = serde_json::Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(query.org_id.clone()),
+    );
+    if let Some(property_id) = non_empty_opt(query.property_id.as_deref()) {
+        filters.insert("property_id".to_string(), Value::String(property_id));
+    }
+    if let Some(is_active) = query.is_active {
+        filters.insert("is_active".to_string(), Value::Bool(is_active));
+    }
+
+    let rows = list_rows(
+        pool,
+        "management_fee_schedules",
+        Some(&filters),
+        clamp_limit_in_range(query.limit, 1, 500),
+        0,
+        "effective_from",
+        false,
+    )
+    .await?;
+
+    Ok(Json(json!({ "data": rows })))
+}
+
+async fn create_schedule(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateManagementFeeScheduleInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::MANAGEMENT_FEES_EDIT,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    if payload.name.trim().is_empty() {
+        return Err(AppError::BadRequest("name is required.".to_string()));
+    }
+    let currency = fx::normalize_currency(&payload.currency)
+        .ok_or_else(|| AppError::BadRequest("currency must be an ISO 4217 code.".to_string()))?;
+    validate_period(&payload.effective_from, payload.effective_to.as_deref())?;
+    validate_rules(&payload.rules)?;
+    if let Some(property_id) = non_empty_opt(payload.property_id.as_deref()) {
+        let property = get_row(pool, "properties", &property_id, "id").await?;
+        if value_str(&property, "organization_id") != payload.organization_id {
+            return Err(AppError::BadRequest(
+                "property_id does not belong to this organization.".to_string(),
+            ));
+        }
+    }
+
+    let mut record = remove_nulls(serialize_to_map(&payload));
+    record.insert("currency".to_string(), Value::String(currency));
+    record.insert(
+        "created_by_user_id".to_string(),
+        Value::String(user_id.clone()),
+    );
+    let created = create_row(pool, "management_fee_schedules", &record).await?;
+    let entity_id = value_str(&created, "id");
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        "create",
+        "management_fee_schedules",
+        Some(&entity_id),
+        None,
+        Some(created.clone()),
+    )
+    .await;
+
+    Ok((axum::http::StatusCode::CREATED, Json(created)))
+}
+
+async fn get_schedule(
+    State(state): State<AppState>,
+    Path(path): Path<ManagementFeeSchedulePath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let record = get_row(pool, "management_fee_schedules", &path.schedule_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_org_member(&state, &user_id, &org_id).await?;
+
+    Ok(Json(record))
+}
+
+async fn update_schedule(
+    State(state): State<AppState>,
+    Path(path): Path<ManagementFeeSchedulePath>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateManagementFeeScheduleInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let record = get_row(pool, "management_fee_schedules", &path.schedule_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::MANAGEMENT_FEES_EDIT).await?;
+
+    let patch = remove_nulls(serialize_to_map(&payload));
+    if patch.is_empty() {
+        return Ok(Json(record));
+    }
+    if payload
+        .name
+        .as_deref()
+        .is_some_and(|name| name.trim().is_empty())
+    {
+        return Err(AppError::BadRequest("name cannot be empty.".to_string()));
+    }
+    if payload.effective_from.is_some() || payload.effective_to.is_some() {
+        let effective_from = payload
+            .effective_from
+            .clone()
+            .unwrap_or_else(|| value_str(&record, "effective_from"));
+        let effective_to = payload
+            .effective_to
+            .clone()
+            .or_else(|| non_empty_opt(record.get("effective_to").and_then(Value::as_str)));
+        validate_period(&effective_from, effective_to.as_deref())?;
+    }
+    if let Some(rules) = payload.rules.as_ref() {
+        validate_rules(rules)?;
+    }
+
+    let updated = update_row(
+        pool,
+        "management_fee_schedules",
+        &path.schedule_id,
+        &patch,
+        "id",
+    )
+    .await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "update",
+        "management_fee_schedules",
+        Some(&path.schedule_id),
+        Some(record),
+        Some(updated.clone()),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+fn validate_rules(rules: &Value) -> AppResult<()> {
+    management_fees::parse_rules(rules)
+        .map(|_| ())
+        .map_err(AppError::BadRequest)
+}
+
+fn validate_period(effective_from: &str, effective_to: Option<&str>) -> AppResult<()> {
+    let parse = |value: &str, field: &str| {
+        NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+            .map_err(|_| AppError::BadRequest(format!("{field} must be an ISO date.")))
+    };
+    let from = parse(effective_from, "effective_from")?;
+    if let Some(to) = effective_to {
+        if parse(to, "effective_to")? < from {
+            return Err(AppError::BadRequest(
+                "effective_to cannot be before effective_from.".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
+    state.db_pool.as_ref().ok_or_else(|| {
+        AppError::Dependency("Database is not configured. Set DATABASE_URL.".to_string())
+    })
+}
+
+fn value_str(row: &Value, key: &str) -> String {
+    row.as_object()
+        .and_then(|obj| obj.get(key))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+fn non_empty_opt(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+}
@@ -26,14 +26,14 @@ use crate::{
             build_listings_overview, get_listing_row_with_context, get_public_listing_row_by_slug,
             list_public_listing_rows, public_listing_shape,
         },
+        permissions,
         pricing::{compute_pricing_totals, missing_required_fee_types, normalize_fee_lines},
         readiness::{compute_readiness_report, readiness_summary},
     },
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
-const MARKETPLACE_EDIT_ROLES: &[&str] = &["owner_admin", "operator"];
 const MAX_GALLERY_IMAGES: usize = 8;
 const MAX_SPATIAL_ASSETS: usize = 16;
 const MAX_AMENITIES: usize = 24;
@@ -239,11 +239,11 @@ async fn create_listing(
     Json(payload): Json<CreateListingInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
+    assert_permission(
         &state,
         &user_id,
         &payload.organization_id,
-        MARKETPLACE_EDIT_ROLES,
+        permissions::MARKETPLACE_EDIT,
     )
     .await?;
     let pool = db_pool(&state)?;
@@ -427,7 +427,7 @@ async fn update_listing(
 
     let record = get_row(pool, "listings", &path.listing_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, MARKETPLACE_EDIT_ROLES).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::MARKETPLACE_EDIT).await?;
 
     let mut patch = remove_nulls(serialize_payload(&payload));
     patch.remove("fee_lines");
@@ -564,7 +564,7 @@ async fn publish_listing(
 
     let record = get_row(pool, "listings", &path.listing_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, MARKETPLACE_EDIT_ROLES).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::MARKETPLACE_EDIT).await?;
 
     assert_publishable(&state, pool, &record).await?;
 
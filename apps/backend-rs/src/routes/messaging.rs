@@ -1,4 +1,5 @@
 use axum::{
+    body::Bytes,
     extract::{Path, Query, State},
     http::HeaderMap,
     response::IntoResponse,
@@ -21,9 +22,11 @@ use crate::{
     services::lease_renewal::run_lease_renewal_scan,
     services::messaging::process_queued_messages,
     services::notification_center::{emit_event, EmitNotificationEventInput},
+    services::permissions,
     services::sequences::process_sequences,
+    services::webhook_verification::{self, MetaVerifier, WebhookRequest, WebhookVerifier},
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
 pub fn router() -> axum::Router<AppState> {
@@ -139,11 +142,11 @@ async fn create_template(
     Json(payload): Json<CreateMessageTemplateInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
+    assert_permission(
         &state,
         &user_id,
         &payload.organization_id,
-        &["owner_admin", "operator"],
+        permissions::MESSAGING_SEND,
     )
     .await?;
     let pool = db_pool(&state)?;
@@ -188,11 +191,11 @@ async fn send_message(
     Json(payload): Json<SendMessageInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
+    assert_permission(
         &state,
         &user_id,
         &payload.organization_id,
-        &["owner_admin", "operator"],
+        permissions::MESSAGING_SEND,
     )
     .await?;
     let pool = db_pool(&state)?;
@@ -296,7 +299,8 @@ async fn process_messages(
     }
 
     let pool = db_pool(&state)?;
-    let (sent, failed) = process_queued_messages(pool, &state.http_client, &state.config).await;
+    let (sent, failed) =
+        process_queued_messages(pool, &state.http_client, &state.config, None).await;
 
     Ok(Json(json!({ "sent": sent, "failed": failed })))
 }
@@ -363,7 +367,13 @@ async fn sync_ical(State(state): State<AppState>, headers: HeaderMap) -> AppResu
     }
 
     let pool = db_pool(&state)?;
-    let result = sync_all_ical_integrations(pool, &state.http_client).await;
+    let result = sync_all_ical_integrations(
+        pool,
+        &state.http_client,
+        state.config.ical_sync_concurrency,
+        None,
+    )
+    .await;
 
     Ok(Json(result))
 }
@@ -404,10 +414,55 @@ async fn whatsapp_webhook_verify(
 /// WhatsApp webhook (POST) — receives delivery status updates AND inbound messages.
 async fn whatsapp_webhook(
     State(state): State<AppState>,
-    Json(payload): Json<Value>,
+    headers: HeaderMap,
+    body: Bytes,
 ) -> AppResult<impl IntoResponse> {
+    // Unsigned deliveries are never trusted: without the app secret there is
+    // no way to tell Meta's requests from forged ones.
+    let Some(secret) = state
+        .config
+        .whatsapp_app_secret
+        .as_deref()
+        .filter(|s| !s.is_empty())
+    else {
+        tracing::error!("WHATSAPP_APP_SECRET is not configured; rejecting WhatsApp webhook");
+        return Err(AppError::Unauthorized(
+            "Webhook signature verification is not configured.".to_string(),
+        ));
+    };
+    let query = std::collections::HashMap::new();
+    let request = WebhookRequest {
+        headers: &headers,
+        query: &query,
+        body: &body,
+    };
+    let (verified, _) =
+        webhook_verification::verify_with_any(&MetaVerifier, &request, &[secret.to_string()])
+            .map_err(|error| webhook_verification::rejected(MetaVerifier.provider(), &error))?;
+
     let pool = db_pool(&state)?;
+    let payload: Value = serde_json::from_slice(&body)
+        .map_err(|_| AppError::BadRequest("Invalid webhook JSON.".to_string()))?;
 
+    webhook_verification::process_once(
+        pool,
+        MetaVerifier.provider(),
+        &verified.event_id,
+        None,
+        process_whatsapp_payload(&state, pool, &payload),
+    )
+    .await?;
+
+    Ok(axum::http::StatusCode::OK)
+}
+
+/// Store inbound messages and apply delivery status updates from a verified
+/// WhatsApp Cloud API payload.
+async fn process_whatsapp_payload(
+    state: &AppState,
+    pool: &sqlx::PgPool,
+    payload: &Value,
+) -> AppResult<()> {
     // Parse WhatsApp Cloud API webhook format
     if let Some(entries) = payload.get("entry").and_then(Value::as_array) {
         for entry in entries {
@@ -453,7 +508,7 @@ async fn whatsapp_webhook(
                             // Try to match sender to a guest or tenant
                             let org_id = match_phone_to_org(pool, sender_phone).await;
 
-                            let created = crate::services::messaging::create_inbound_message(
+                            crate::services::messaging::create_inbound_message(
                                 pool,
                                 org_id.as_deref(),
                                 sender_phone,
@@ -461,65 +516,61 @@ async fn whatsapp_webhook(
                                 media_url,
                                 wa_msg_id,
                             )
-                            .await;
+                            .await
+                            .map_err(AppError::Internal)?;
 
                             if let Some(oid) = &org_id {
-                                if created.is_ok() {
-                                    let mut event_payload = serde_json::Map::new();
-                                    event_payload.insert(
-                                        "sender_phone".to_string(),
-                                        Value::String(sender_phone.to_string()),
-                                    );
+                                let mut event_payload = serde_json::Map::new();
+                                event_payload.insert(
+                                    "sender_phone".to_string(),
+                                    Value::String(sender_phone.to_string()),
+                                );
+                                event_payload.insert(
+                                    "recipient_phone".to_string(),
+                                    Value::String(sender_phone.to_string()),
+                                );
+                                event_payload.insert(
+                                    "wa_message_id".to_string(),
+                                    Value::String(wa_msg_id.to_string()),
+                                );
+                                event_payload.insert(
+                                    "message_type".to_string(),
+                                    Value::String(msg_type.to_string()),
+                                );
+                                event_payload.insert(
+                                    "preview".to_string(),
+                                    Value::String(text.chars().take(160).collect::<String>()),
+                                );
+                                if let Some(media_url) = media_url {
                                     event_payload.insert(
-                                        "recipient_phone".to_string(),
-                                        Value::String(sender_phone.to_string()),
+                                        "media_url".to_string(),
+                                        Value::String(media_url.to_string()),
                                     );
-                                    event_payload.insert(
-                                        "wa_message_id".to_string(),
-                                        Value::String(wa_msg_id.to_string()),
-                                    );
-                                    event_payload.insert(
-                                        "message_type".to_string(),
-                                        Value::String(msg_type.to_string()),
-                                    );
-                                    event_payload.insert(
-                                        "preview".to_string(),
-                                        Value::String(text.chars().take(160).collect::<String>()),
-                                    );
-                                    if let Some(media_url) = media_url {
-                                        event_payload.insert(
-                                            "media_url".to_string(),
-                                            Value::String(media_url.to_string()),
-                                        );
-                                    }
-
-                                    let _ = emit_event(
-                                        pool,
-                                        EmitNotificationEventInput {
-                                            organization_id: oid.clone(),
-                                            event_type: "guest_message_received".to_string(),
-                                            category: "messaging".to_string(),
-                                            severity: "warning".to_string(),
-                                            title: "Nuevo mensaje de huésped".to_string(),
-                                            body: format!(
-                                                "Mensaje entrante desde {}",
-                                                sender_phone
-                                            ),
-                                            link_path: Some("/module/messaging".to_string()),
-                                            source_table: Some("message_logs".to_string()),
-                                            source_id: None,
-                                            actor_user_id: None,
-                                            payload: event_payload,
-                                            dedupe_key: Some(format!(
-                                                "guest_message_received:{}",
-                                                wa_msg_id
-                                            )),
-                                            occurred_at: None,
-                                            fallback_roles: vec![],
-                                        },
-                                    )
-                                    .await;
                                 }
+
+                                let _ = emit_event(
+                                    pool,
+                                    EmitNotificationEventInput {
+                                        organization_id: oid.clone(),
+                                        event_type: "guest_message_received".to_string(),
+                                        category: "messaging".to_string(),
+                                        severity: "warning".to_string(),
+                                        title: "Nuevo mensaje de huésped".to_string(),
+                                        body: format!("Mensaje entrante desde {}", sender_phone),
+                                        link_path: Some("/module/messaging".to_string()),
+                                        source_table: Some("message_logs".to_string()),
+                                        source_id: None,
+                                        actor_user_id: None,
+                                        payload: event_payload,
+                                        dedupe_key: Some(format!(
+                                            "guest_message_received:{}",
+                                            wa_msg_id
+                                        )),
+                                        occurred_at: None,
+                                        fallback_roles: vec![],
+                                    },
+                                )
+                                .await;
                             }
 
                             // AI auto-reply for guest messages via guest concierge agent
@@ -601,8 +652,7 @@ async fn whatsapp_webhook(
                                 "sent_at",
                                 false,
                             )
-                            .await
-                            .unwrap_or_default();
+                            .await?;
 
                             for msg in &messages {
                                 let provider_msg_id = msg
@@ -654,7 +704,7 @@ async fn whatsapp_webhook(
         }
     }
 
-    Ok(axum::http::StatusCode::OK)
+    Ok(())
 }
 
 /// Internal cron-compatible endpoint that processes communication sequences.
@@ -720,3 +770,67 @@ async fn match_phone_to_org(pool: &sqlx::PgPool, phone: &str) -> Option<String>
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    use super::*;
+    use crate::test_support::test_state;
+
+    fn webhook_state(app_secret: Option<&str>) -> AppState {
+        let mut state = test_state(None);
+        std::sync::Arc::make_mut(&mut state.config).whatsapp_app_secret =
+            app_secret.map(str::to_string);
+        state
+    }
+
+    fn signed_headers(secret: &str, body: &[u8]) -> HeaderMap {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac key");
+        mac.update(body);
+        let digest = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-hub-signature-256",
+            HeaderValue::from_str(&format!("sha256={digest}")).expect("header"),
+        );
+        headers
+    }
+
+    #[tokio::test]
+    async fn whatsapp_webhook_requires_a_configured_app_secret() {
+        let body = Bytes::from_static(br#"{"entry":[]}"#);
+
+        let unconfigured = whatsapp_webhook(
+            State(webhook_state(None)),
+            signed_headers("app-secret", &body),
+            body.clone(),
+        )
+        .await;
+        assert!(matches!(unconfigured, Err(AppError::Unauthorized(_))));
+
+        let forged = whatsapp_webhook(
+            State(webhook_state(Some("app-secret"))),
+            signed_headers("other-secret", &body),
+            body.clone(),
+        )
+        .await;
+        assert!(matches!(forged, Err(AppError::Unauthorized(_))));
+
+        // A valid signature gets past verification to the (missing) database.
+        let signed = whatsapp_webhook(
+            State(webhook_state(Some("app-secret"))),
+            signed_headers("app-secret", &body),
+            body,
+        )
+        .await;
+        assert!(matches!(signed, Err(AppError::Dependency(_))));
+    }
+}
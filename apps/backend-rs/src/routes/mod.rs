@@ -1,8 +1,8 @@
-use axum::{extract::State, routing::get, Json, Router};
-use serde_json::{json, Value};
+use axum::{routing::get, Router};
 
 use crate::state::AppState;
 
+pub mod accounting;
 pub mod agent_chats;
 pub mod agent_inbox;
 pub mod agent_management;
@@ -10,8 +10,10 @@ pub mod agent_playbooks;
 pub mod agent_runs;
 pub mod agent_tools;
 pub mod ai_agent;
+pub mod api_keys;
 pub mod applications;
 pub mod approvals;
+pub mod bank_imports;
 pub mod booking;
 pub mod calendar;
 pub mod cancellation_policies;
@@ -20,32 +22,42 @@ pub mod contract_templates;
 pub mod demo;
 pub mod deposits;
 pub mod documents;
+pub mod electronic_invoicing;
 pub mod expenses;
+pub mod fx;
 pub mod guest_portal;
 pub mod guests;
 pub mod health;
 pub mod identity;
 pub mod integrations;
+pub mod late_fees;
+pub mod lease_deposits;
+pub mod lease_payments;
 pub mod leases;
 pub mod maintenance;
+pub mod management_fees;
 pub mod marketplace;
 pub mod messaging;
 pub mod notification_center;
 pub mod notifications;
 pub mod operations;
 pub mod organizations;
+pub mod owner_payouts;
 pub mod owner_portal;
+pub mod owner_reserves;
 pub mod owner_statements;
 pub mod payments;
 pub mod platform;
 pub mod portfolio;
 pub mod pricing;
 pub mod properties;
+pub mod property_groups;
 pub mod public_ical;
 pub mod referrals;
 pub mod reports;
 pub mod reservations;
 pub mod reviews;
+pub mod roles;
 pub mod sequences;
 pub mod storage;
 pub mod subscriptions;
@@ -53,21 +65,17 @@ pub mod tasks;
 pub mod tenant;
 pub mod vendor_portal;
 pub mod voice_agent;
+pub mod webhook_subscriptions;
 pub mod workflows;
 
-async fn public_fx_rate(State(state): State<AppState>) -> Json<Value> {
-    let rate = crate::services::fx::get_cached_usd_pyg_rate(&state).await;
-    Json(json!({ "usd_pyg": rate }))
-}
-
 pub fn v1_router() -> Router<AppState> {
     Router::new()
         .route("/live", get(health::live))
         .route("/ready", get(health::ready))
         .route("/health", get(health::health))
         .route("/health/cache-stats", get(health::cache_stats))
+        .route("/health/scheduler", get(health::scheduler))
         .route("/me", get(identity::me))
-        .route("/public/fx/usd-pyg", get(public_fx_rate))
         .merge(agent_chats::router())
         .merge(agent_inbox::router())
         .merge(agent_management::router())
@@ -76,7 +84,10 @@ pub fn v1_router() -> Router<AppState> {
         .merge(agent_tools::router())
         .merge(ai_agent::router())
         .merge(organizations::router())
+        .merge(roles::router())
+        .merge(fx::router())
         .merge(properties::router())
+        .merge(property_groups::router())
         .merge(guests::router())
         .merge(reservations::router())
         .merge(calendar::router())
@@ -84,11 +95,22 @@ pub fn v1_router() -> Router<AppState> {
         .merge(tasks::router())
         .merge(expenses::router())
         .merge(collections::router())
+        .merge(accounting::router())
+        .merge(electronic_invoicing::router())
+        .merge(owner_payouts::router())
+        .merge(owner_reserves::router())
+        .merge(management_fees::router())
+        .merge(bank_imports::router())
+        .merge(late_fees::router())
+        .merge(lease_deposits::router())
+        .merge(lease_payments::router())
         .merge(leases::router())
         .merge(applications::router())
         .merge(pricing::router())
         .merge(messaging::router())
         .merge(notification_center::router())
+        .merge(webhook_subscriptions::router())
+        .merge(api_keys::router())
         .merge(payments::router())
         .merge(notifications::router())
         .merge(operations::router())
@@ -17,12 +17,11 @@ use crate::{
         UpdateNotificationRuleInput,
     },
     services::audit::write_audit_log,
+    services::permissions,
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
-const NOTIFICATION_EDIT_ROLES: &[&str] = &["owner_admin", "operator"];
-
 struct TriggerMetadata {
     value: &'static str,
     label_en: &'static str,
@@ -209,11 +208,11 @@ async fn create_notification_rule(
     Json(payload): Json<CreateNotificationRuleInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
+    assert_permission(
         &state,
         &user_id,
         &payload.organization_id,
-        NOTIFICATION_EDIT_ROLES,
+        permissions::NOTIFICATIONS_EDIT,
     )
     .await?;
     let pool = db_pool(&state)?;
@@ -263,7 +262,7 @@ async fn update_notification_rule(
 
     let existing = get_row(pool, "notification_rules", &path.rule_id, "id").await?;
     let org_id = value_str(&existing, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, NOTIFICATION_EDIT_ROLES).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::NOTIFICATIONS_EDIT).await?;
 
     let patch = remove_nulls(serialize_to_map(&payload));
     if patch.is_empty() {
@@ -11,7 +11,7 @@ use crate::{
     schemas::OperationsOverviewQuery,
     services::operations::build_operations_overview,
     state::AppState,
-    tenancy::assert_org_member,
+    tenancy::{assert_org_member, property_scope},
 };
 
 pub fn router() -> Router<AppState> {
@@ -24,10 +24,13 @@ async fn operations_overview(
     headers: HeaderMap,
 ) -> AppResult<Json<serde_json::Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &query.org_id).await?;
     let pool = db_pool(&state)?;
+    let scope = property_scope(&state, &query.org_id, &membership).await?;
 
-    Ok(Json(build_operations_overview(pool, &query).await?))
+    Ok(Json(
+        build_operations_overview(pool, &query, scope.as_deref()).await?,
+    ))
 }
 
 fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
@@ -10,6 +10,7 @@ use sqlx::{Postgres, QueryBuilder, Row};
 
 use crate::{
     auth::require_authenticated_user,
+    cache::org_key,
     error::{AppError, AppResult},
     repository::table_service::{
         create_row, create_row_tx, delete_row, get_row, list_rows, update_row,
@@ -21,12 +22,14 @@ use crate::{
         UpdateOrganizationMemberInput,
     },
     services::{
+        api_keys,
         audit::write_audit_log,
+        permissions,
         plan_limits::{check_plan_limit, PlanResource},
     },
     state::AppState,
     tenancy::{
-        assert_org_member, assert_org_role, ensure_app_user, ensure_org_membership,
+        assert_org_member, assert_permission, ensure_app_user, ensure_org_membership,
         get_org_membership, list_user_org_ids, list_user_organizations,
     },
 };
@@ -94,6 +97,7 @@ async fn create_organization(
 ) -> AppResult<impl IntoResponse> {
     validate_input(&payload)?;
     let user = require_authenticated_user(&state, &headers).await?;
+    api_keys::reject_api_key_user(&user)?;
     let _app_user = ensure_app_user(&state, &user).await?;
     let pool = db_pool(&state)?;
 
@@ -162,7 +166,13 @@ async fn update_organization(
 ) -> AppResult<Json<Value>> {
     let user = require_authenticated_user(&state, &headers).await?;
     let _app_user = ensure_app_user(&state, &user).await?;
-    assert_org_role(&state, &user.id, &path.org_id, &["owner_admin"]).await?;
+    assert_permission(
+        &state,
+        &user.id,
+        &path.org_id,
+        permissions::ORGANIZATION_MANAGE,
+    )
+    .await?;
     let pool = db_pool(&state)?;
 
     let org = get_row(pool, "organizations", &path.org_id, "id").await?;
@@ -224,7 +234,7 @@ async fn list_invites(
 ) -> AppResult<Json<Value>> {
     let user = require_authenticated_user(&state, &headers).await?;
     let _app_user = ensure_app_user(&state, &user).await?;
-    assert_org_role(&state, &user.id, &path.org_id, &["owner_admin"]).await?;
+    assert_permission(&state, &user.id, &path.org_id, permissions::MEMBERS_MANAGE).await?;
 
     let pool = db_pool(&state)?;
     let mut filters = Map::new();
@@ -254,7 +264,7 @@ async fn create_invite(
     validate_input(&payload)?;
     let user = require_authenticated_user(&state, &headers).await?;
     let _app_user = ensure_app_user(&state, &user).await?;
-    assert_org_role(&state, &user.id, &path.org_id, &["owner_admin"]).await?;
+    assert_permission(&state, &user.id, &path.org_id, permissions::MEMBERS_MANAGE).await?;
     let pool = db_pool(&state)?;
 
     let days = payload.expires_in_days;
@@ -325,7 +335,7 @@ async fn revoke_invite(
 ) -> AppResult<Json<Value>> {
     let user = require_authenticated_user(&state, &headers).await?;
     let _app_user = ensure_app_user(&state, &user).await?;
-    assert_org_role(&state, &user.id, &path.org_id, &["owner_admin"]).await?;
+    assert_permission(&state, &user.id, &path.org_id, permissions::MEMBERS_MANAGE).await?;
     let pool = db_pool(&state)?;
 
     let existing = get_row(pool, "organization_invites", &path.invite_id, "id").await?;
@@ -369,6 +379,7 @@ async fn accept_invite(
     Json(payload): Json<AcceptOrganizationInviteInput>,
 ) -> AppResult<Json<Value>> {
     let user = require_authenticated_user(&state, &headers).await?;
+    api_keys::reject_api_key_user(&user)?;
     let _app_user = ensure_app_user(&state, &user).await?;
     let pool = db_pool(&state)?;
 
@@ -503,7 +514,7 @@ async fn add_member(
 ) -> AppResult<impl IntoResponse> {
     let user = require_authenticated_user(&state, &headers).await?;
     let _app_user = ensure_app_user(&state, &user).await?;
-    assert_org_role(&state, &user.id, &path.org_id, &["owner_admin"]).await?;
+    assert_permission(&state, &user.id, &path.org_id, permissions::MEMBERS_MANAGE).await?;
     let pool = db_pool(&state)?;
 
     check_plan_limit(pool, &path.org_id, PlanResource::User).await?;
@@ -552,7 +563,7 @@ async fn update_member(
 ) -> AppResult<Json<Value>> {
     let user = require_authenticated_user(&state, &headers).await?;
     let _app_user = ensure_app_user(&state, &user).await?;
-    assert_org_role(&state, &user.id, &path.org_id, &["owner_admin"]).await?;
+    assert_permission(&state, &user.id, &path.org_id, permissions::MEMBERS_MANAGE).await?;
     let pool = db_pool(&state)?;
 
     let org = get_row(pool, "organizations", &path.org_id, "id").await?;
@@ -566,11 +577,43 @@ async fn update_member(
         .await?
         .ok_or_else(|| AppError::NotFound("organization_members record not found.".to_string()))?;
 
-    let patch = remove_nulls(serialize_to_map(&payload));
+    let mut patch = remove_nulls(serialize_to_map(&payload));
+    if let Some(custom_role_id) = payload.custom_role_id.as_deref().map(str::trim) {
+        if custom_role_id.is_empty() {
+            patch.insert("custom_role_id".to_string(), Value::Null);
+        } else {
+            let custom_role = get_row(pool, "organization_roles", custom_role_id, "id").await?;
+            if value_str(&custom_role, "organization_id") != path.org_id {
+                return Err(AppError::BadRequest(
+                    "custom_role_id must reference a role of this organization.".to_string(),
+                ));
+            }
+            patch.insert(
+                "custom_role_id".to_string(),
+                Value::String(custom_role_id.to_string()),
+            );
+        }
+    }
     if patch.is_empty() {
         return Ok(Json(existing));
     }
 
+    // Custom roles narrow what a member may do; owner_admin keeps full access
+    // so an organization cannot lock itself out of role management.
+    let role = payload
+        .role
+        .clone()
+        .unwrap_or_else(|| value_str(&existing, "role"));
+    let has_custom_role = match patch.get("custom_role_id") {
+        Some(value) => !value.is_null(),
+        None => !value_str(&existing, "custom_role_id").is_empty(),
+    };
+    if role == "owner_admin" && has_custom_role {
+        return Err(AppError::BadRequest(
+            "owner_admin members cannot hold a custom role.".to_string(),
+        ));
+    }
+
     let mut builder = QueryBuilder::<Postgres>::new("UPDATE organization_members t SET ");
     {
         let mut keys = patch.keys().cloned().collect::<Vec<_>>();
@@ -602,6 +645,10 @@ async fn update_member(
     let updated = updated_row
         .and_then(|row| row.try_get::<Option<Value>, _>("row").ok().flatten())
         .unwrap_or_else(|| existing.clone());
+    state
+        .org_membership_cache
+        .invalidate(&org_key(&path.org_id, &path.member_user_id))
+        .await;
 
     write_audit_log(
         state.db_pool.as_ref(),
@@ -625,7 +672,7 @@ async fn delete_member(
 ) -> AppResult<Json<Value>> {
     let user = require_authenticated_user(&state, &headers).await?;
     let _app_user = ensure_app_user(&state, &user).await?;
-    assert_org_role(&state, &user.id, &path.org_id, &["owner_admin"]).await?;
+    assert_permission(&state, &user.id, &path.org_id, permissions::MEMBERS_MANAGE).await?;
     let pool = db_pool(&state)?;
 
     let org = get_row(pool, "organizations", &path.org_id, "id").await?;
@@ -672,6 +719,10 @@ async fn delete_member(
             tracing::error!(error = %error, "Database query failed");
             AppError::Dependency("External service request failed.".to_string())
         })?;
+    state
+        .org_membership_cache
+        .invalidate(&org_key(&path.org_id, &path.member_user_id))
+        .await;
 
     write_audit_log(
         state.db_pool.as_ref(),
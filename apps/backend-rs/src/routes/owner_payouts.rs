@@ -0,0 +1,999 @@
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+        HeaderMap, HeaderValue, Response, StatusCode,
+    },
+    response::IntoResponse,
+    Json,
+};
+use chrono::{NaiveDate, Utc};
+use serde_json::{json, Map, Value};
+use sqlx::Row;
+
+use crate::{
+    auth::require_user_id,
+    error::{AppError, AppResult},
+    repository::table_service::{
+        create_row, create_row_tx, delete_row, get_row, list_rows, update_row,
+    },
+    schemas::{
+        clamp_limit, remove_nulls, serialize_to_map, CreateOwnerPayoutBatchInput,
+        CreatePayoutBankLayoutInput, FailOwnerPayoutInput, OwnerPayoutBatchPath,
+        OwnerPayoutBatchesQuery, OwnerPayoutFileQuery, PayoutBankLayoutPath,
+        PayoutBankLayoutsQuery, UpdatePayoutBankLayoutInput,
+    },
+    services::{
+        audit::write_audit_log,
+        fx, general_ledger,
+        owner_payouts::{
+            group_transfers, render_generic_csv, BatchFile, FixedWidthLayout, PayoutStatus,
+            StatementPayout, TransferLine,
+        },
+        permissions,
+    },
+    state::AppState,
+    tenancy::{assert_org_member, assert_permission},
+};
+
+pub fn router() -> axum::Router<AppState> {
+    axum::Router::new()
+        .route(
+            "/owner-payout-batches",
+            axum::routing::get(list_batches).post(create_batch),
+        )
+        .route(
+            "/owner-payout-batches/{batch_id}",
+            axum::routing::get(get_batch),
+        )
+        .route(
+            "/owner-payout-batches/{batch_id}/send",
+            axum::routing::post(send_batch),
+        )
+        .route(
+            "/owner-payout-batches/{batch_id}/confirm",
+            axum::routing::post(confirm_batch),
+        )
+        .route(
+            "/owner-payout-batches/{batch_id}/fail",
+            axum::routing::post(fail_batch),
+        )
+        .route(
+            "/owner-payout-batches/{batch_id}/file",
+            axum::routing::get(batch_file),
+        )
+        .route(
+            "/payout-bank-layouts",
+            axum::routing::get(list_layouts).post(create_layout),
+        )
+        .route(
+            "/payout-bank-layouts/{layout_id}",
+            axum::routing::patch(update_layout).delete(delete_layout),
+        )
+}
+
+// ── Batches ────────────────────────────────────────────────────────────
+
+async fn list_batches(
+    State(state): State<AppState>,
+    Query(query): Query<OwnerPayoutBatchesQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(query.org_id.clone()),
+    );
+    if let Some(status) = non_empty_opt(query.status.as_deref()) {
+        filters.insert("status".to_string(), Value::String(status));
+    }
+    let rows = list_rows(
+        pool,
+        "owner_payout_batches",
+        Some(&filters),
+        clamp_limit(query.limit),
+        0,
+        "created_at",
+        false,
+    )
+    .await?;
+    Ok(Json(json!({ "data": rows })))
+}
+
+async fn get_batch(
+    State(state): State<AppState>,
+    Path(path): Path<OwnerPayoutBatchPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let batch = get_row(pool, "owner_payout_batches", &path.batch_id, "id").await?;
+    assert_org_member(&state, &user_id, &value_str(&batch, "organization_id")).await?;
+    let items = batch_items(pool, &path.batch_id).await?;
+
+    let mut batch = batch.as_object().cloned().unwrap_or_default();
+    batch.insert("items".to_string(), Value::Array(items));
+    Ok(Json(Value::Object(batch)))
+}
+
+async fn create_batch(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateOwnerPayoutBatchInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::OWNER_PAYOUTS_EDIT,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let scheduled_for = match non_empty_opt(payload.scheduled_for.as_deref()) {
+        Some(value) => NaiveDate::parse_from_str(&value, "%Y-%m-%d").map_err(|_| {
+            AppError::BadRequest("scheduled_for must be a YYYY-MM-DD date.".to_string())
+        })?,
+        None => Utc::now().date_naive(),
+    };
+    let currency = match non_empty_opt(payload.currency.as_deref()) {
+        Some(currency) => Some(fx::normalize_currency(&currency).ok_or_else(|| {
+            AppError::BadRequest("currency must be a 3-letter ISO 4217 code.".to_string())
+        })?),
+        None => None,
+    };
+    let requested: Option<Vec<String>> = payload
+        .statement_ids
+        .as_ref()
+        .map(|ids| {
+            ids.iter()
+                .filter_map(|id| non_empty_opt(Some(id)))
+                .collect()
+        })
+        .filter(|ids: &Vec<String>| !ids.is_empty());
+
+    let candidates = payable_statements(
+        pool,
+        &payload.organization_id,
+        requested.as_deref(),
+        currency.as_deref(),
+    )
+    .await?;
+
+    let mut skipped = Vec::new();
+    let mut statements = Vec::new();
+    for (statement, property_name) in candidates {
+        let problem = if statement.account_number.is_empty() {
+            Some(format!("{property_name} has no owner bank account"))
+        } else if statement.amount() <= 0.0 {
+            Some("nothing to pay out".to_string())
+        } else {
+            None
+        };
+        match problem {
+            Some(reason) => skipped.push(json!({
+                "owner_statement_id": statement.statement_id,
+                "reason": reason,
+            })),
+            None => statements.push(statement),
+        }
+    }
+
+    if let Some(requested) = &requested {
+        let found = statements.len() + skipped.len();
+        if found < requested.len() {
+            return Err(AppError::Conflict(
+                "Some statements are not finalized, are already in a payout batch or belong to another organization."
+                    .to_string(),
+            ));
+        }
+        if !skipped.is_empty() {
+            return Err(AppError::UnprocessableEntity(format!(
+                "Some statements cannot be paid out: {}.",
+                skipped
+                    .iter()
+                    .map(|item| format!(
+                        "{} ({})",
+                        value_str(item, "owner_statement_id"),
+                        value_str(item, "reason")
+                    ))
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            )));
+        }
+    }
+    if statements.is_empty() {
+        return Err(AppError::UnprocessableEntity(
+            "There are no finalized statements ready to pay out.".to_string(),
+        ));
+    }
+    let currency = match currency {
+        Some(currency) => currency,
+        None => {
+            let first = statements[0].currency.clone();
+            if statements.iter().any(|s| s.currency != first) {
+                return Err(AppError::BadRequest(
+                    "The statements are in several currencies; pass currency to batch one of them."
+                        .to_string(),
+                ));
+            }
+            first
+        }
+    };
+
+    let transfers = group_transfers(&statements);
+    let total_amount: f64 = transfers.iter().map(|line| line.amount).sum();
+    let total_fees: f64 = statements.iter().map(|s| s.management_fees).sum();
+    let total_reserves: f64 = statements.iter().map(|s| s.reserve_amount).sum();
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Could not start transaction."))?;
+
+    let mut record = Map::new();
+    record.insert(
+        "organization_id".to_string(),
+        Value::String(payload.organization_id.clone()),
+    );
+    record.insert(
+        "reference".to_string(),
+        Value::String(batch_reference(scheduled_for)),
+    );
+    record.insert("currency".to_string(), Value::String(currency));
+    record.insert(
+        "scheduled_for".to_string(),
+        Value::String(scheduled_for.to_string()),
+    );
+    record.insert("transfer_count".to_string(), json!(transfers.len()));
+    record.insert("total_amount".to_string(), json!(round2(total_amount)));
+    record.insert(
+        "total_management_fees".to_string(),
+        json!(round2(total_fees)),
+    );
+    record.insert("total_reserves".to_string(), json!(round2(total_reserves)));
+    if let Some(notes) = non_empty_opt(payload.notes.as_deref()) {
+        record.insert("notes".to_string(), Value::String(notes));
+    }
+    record.insert(
+        "created_by_user_id".to_string(),
+        Value::String(user_id.clone()),
+    );
+    let batch = create_row_tx(&mut tx, "owner_payout_batches", &record).await?;
+    let batch_id = value_str(&batch, "id");
+
+    for transfer in &transfers {
+        for statement in statements
+            .iter()
+            .filter(|s| transfer.statement_ids.contains(&s.statement_id))
+        {
+            let mut item = Map::new();
+            item.insert(
+                "organization_id".to_string(),
+                Value::String(payload.organization_id.clone()),
+            );
+            item.insert("batch_id".to_string(), Value::String(batch_id.clone()));
+            item.insert(
+                "owner_statement_id".to_string(),
+                Value::String(statement.statement_id.clone()),
+            );
+            item.insert("transfer_sequence".to_string(), json!(transfer.sequence));
+            item.insert(
+                "payee_name".to_string(),
+                Value::String(transfer.payee_name.clone()),
+            );
+            item.insert(
+                "payee_tax_id".to_string(),
+                Value::String(statement.payee_tax_id.clone()),
+            );
+            item.insert(
+                "bank_name".to_string(),
+                Value::String(statement.bank_name.clone()),
+            );
+            item.insert(
+                "bank_account_number".to_string(),
+                Value::String(transfer.account_number.clone()),
+            );
+            if !statement.account_type.is_empty() {
+                item.insert(
+                    "bank_account_type".to_string(),
+                    Value::String(statement.account_type.clone()),
+                );
+            }
+            item.insert("net_payout".to_string(), json!(statement.net_payout));
+            item.insert(
+                "management_fees".to_string(),
+                json!(statement.management_fees),
+            );
+            item.insert(
+                "reserve_amount".to_string(),
+                json!(statement.reserve_amount),
+            );
+            item.insert("amount".to_string(), json!(statement.amount()));
+            create_row_tx(&mut tx, "owner_payout_items", &item).await?;
+        }
+    }
+    tx.commit()
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Could not create the payout batch."))?;
+
+    write_audit_log(
+        Some(pool),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        "create",
+        "owner_payout_batches",
+        Some(&batch_id),
+        None,
+        Some(batch.clone()),
+    )
+    .await;
+
+    let mut batch = batch.as_object().cloned().unwrap_or_default();
+    batch.insert(
+        "items".to_string(),
+        Value::Array(batch_items(pool, &batch_id).await?),
+    );
+    batch.insert("skipped".to_string(), Value::Array(skipped));
+    Ok((StatusCode::CREATED, Json(Value::Object(batch))))
+}
+
+/// Finalized statements not yet in a live batch, with the owner's bank
+/// account and the fees already netted out of `net_payout`. The payout
+/// shows the statement's reserve top-up as held back from it.
+async fn payable_statements(
+    pool: &sqlx::PgPool,
+    org_id: &str,
+    statement_ids: Option<&[String]>,
+    currency: Option<&str>,
+) -> AppResult<Vec<(StatementPayout, String)>> {
+    let rows = sqlx::query(
+        "SELECT os.id::text AS id, os.currency::text AS currency,
+                (os.net_payout + os.reserve_top_up)::float8 AS net_payout,
+                os.reserve_top_up::float8 AS reserve_amount,
+                (os.service_fees + os.collection_fees + os.management_fees)::float8 AS management_fees,
+                COALESCE(p.name, '') AS property_name,
+                COALESCE(NULLIF(p.asset_owner_name, ''), p.name, '') AS payee_name,
+                COALESCE(p.asset_owner_ruc, '') AS payee_tax_id,
+                COALESCE(p.asset_owner_bank_name, '') AS bank_name,
+                COALESCE(p.asset_owner_bank_account_number, '') AS account_number,
+                COALESCE(p.asset_owner_bank_account_type, '') AS account_type
+         FROM owner_statements os
+         LEFT JOIN units u ON u.id = os.unit_id
+         LEFT JOIN properties p ON p.id = COALESCE(os.property_id, u.property_id)
+         WHERE os.organization_id = $1::uuid
+           AND os.status IN ('finalized', 'sent')
+           AND NOT EXISTS (
+             SELECT 1 FROM owner_payout_items i
+             WHERE i.owner_statement_id = os.id AND i.status <> 'failed'
+           )
+           AND ($2::text[] IS NULL OR os.id::text = ANY($2))
+           AND ($3::text IS NULL OR os.currency = $3)
+         ORDER BY os.period_end, os.id",
+    )
+    .bind(org_id)
+    .bind(statement_ids)
+    .bind(currency)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load owner statements."))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let text = |key: &str| row.try_get::<String, _>(key).unwrap_or_default();
+            (
+                StatementPayout {
+                    statement_id: text("id"),
+                    payee_name: text("payee_name"),
+                    payee_tax_id: text("payee_tax_id"),
+                    bank_name: text("bank_name"),
+                    account_number: text("account_number"),
+                    account_type: text("account_type"),
+                    currency: text("currency"),
+                    net_payout: row.try_get("net_payout").unwrap_or(0.0),
+                    management_fees: row.try_get("management_fees").unwrap_or(0.0),
+                    reserve_amount: row.try_get("reserve_amount").unwrap_or(0.0),
+                },
+                text("property_name"),
+            )
+        })
+        .collect())
+}
+
+async fn send_batch(
+    State(state): State<AppState>,
+    Path(path): Path<OwnerPayoutBatchPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+    let (batch, org_id) = load_batch(&state, pool, &path.batch_id, &user_id).await?;
+    ensure_transition(&batch, PayoutStatus::Sent)?;
+
+    set_item_status(pool, &path.batch_id, None, PayoutStatus::Sent, None).await?;
+    let mut patch = Map::new();
+    patch.insert("status".to_string(), Value::String("sent".to_string()));
+    patch.insert(
+        "sent_at".to_string(),
+        Value::String(Utc::now().to_rfc3339()),
+    );
+    let updated = update_row(pool, "owner_payout_batches", &path.batch_id, &patch, "id").await?;
+
+    audit_transition(pool, &org_id, &user_id, &path.batch_id, batch, &updated).await;
+    Ok(Json(updated))
+}
+
+/// The bank has executed the transfers: mark the batch confirmed and its
+/// statements paid, posting each payout to the ledger.
+async fn confirm_batch(
+    State(state): State<AppState>,
+    Path(path): Path<OwnerPayoutBatchPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+    let (batch, org_id) = load_batch(&state, pool, &path.batch_id, &user_id).await?;
+    ensure_transition(&batch, PayoutStatus::Confirmed)?;
+
+    let statement_ids: Vec<String> =
+        set_item_status(pool, &path.batch_id, None, PayoutStatus::Confirmed, None).await?;
+    let paid_at = Utc::now().to_rfc3339();
+    for statement_id in &statement_ids {
+        let mut patch = Map::new();
+        patch.insert("status".to_string(), Value::String("paid".to_string()));
+        patch.insert("paid_at".to_string(), Value::String(paid_at.clone()));
+        let statement = update_row(pool, "owner_statements", statement_id, &patch, "id").await?;
+        general_ledger::record_statement_payout(pool, &statement, Some(&user_id)).await;
+    }
+
+    let mut patch = Map::new();
+    patch.insert("status".to_string(), Value::String("confirmed".to_string()));
+    patch.insert("confirmed_at".to_string(), Value::String(paid_at));
+    let updated = update_row(pool, "owner_payout_batches", &path.batch_id, &patch, "id").await?;
+
+    audit_transition(pool, &org_id, &user_id, &path.batch_id, batch, &updated).await;
+    Ok(Json(updated))
+}
+
+/// Record that the bank rejected the batch, or only some of its transfers.
+/// Failed statements are released so they can go into a new batch.
+async fn fail_batch(
+    State(state): State<AppState>,
+    Path(path): Path<OwnerPayoutBatchPath>,
+    headers: HeaderMap,
+    Json(payload): Json<FailOwnerPayoutInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+    let reason = non_empty_opt(Some(&payload.reason))
+        .ok_or_else(|| AppError::BadRequest("reason is required.".to_string()))?;
+    let (batch, org_id) = load_batch(&state, pool, &path.batch_id, &user_id).await?;
+    ensure_transition(&batch, PayoutStatus::Failed)?;
+
+    let item_ids: Option<Vec<String>> = payload
+        .item_ids
+        .as_ref()
+        .map(|ids| {
+            ids.iter()
+                .filter_map(|id| non_empty_opt(Some(id)))
+                .collect()
+        })
+        .filter(|ids: &Vec<String>| !ids.is_empty());
+
+    let failed = match &item_ids {
+        Some(item_ids) => {
+            // A transfer fails as a whole, with every statement it pays.
+            let sequences: Vec<i32> = sqlx::query_scalar(
+                "SELECT DISTINCT transfer_sequence FROM owner_payout_items
+                 WHERE batch_id = $1::uuid AND id::text = ANY($2)",
+            )
+            .bind(&path.batch_id)
+            .bind(item_ids)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::from_database_error(&e, "Could not load payout items."))?;
+            if sequences.is_empty() {
+                return Err(AppError::BadRequest(
+                    "item_ids do not belong to this batch.".to_string(),
+                ));
+            }
+            set_item_status(
+                pool,
+                &path.batch_id,
+                Some(&sequences),
+                PayoutStatus::Failed,
+                Some(&reason),
+            )
+            .await?
+        }
+        None => {
+            set_item_status(
+                pool,
+                &path.batch_id,
+                None,
+                PayoutStatus::Failed,
+                Some(&reason),
+            )
+            .await?
+        }
+    };
+
+    let remaining = sqlx::query(
+        "SELECT count(DISTINCT transfer_sequence)::int8 AS transfers,
+                COALESCE(sum(amount), 0)::float8 AS amount,
+                COALESCE(sum(management_fees), 0)::float8 AS fees,
+                COALESCE(sum(reserve_amount), 0)::float8 AS reserves
+         FROM owner_payout_items
+         WHERE batch_id = $1::uuid AND status <> 'failed'",
+    )
+    .bind(&path.batch_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not total the payout batch."))?;
+    let transfers: i64 = remaining.try_get("transfers").unwrap_or(0);
+
+    let mut patch = Map::new();
+    patch.insert("transfer_count".to_string(), json!(transfers));
+    patch.insert(
+        "total_amount".to_string(),
+        json!(round2(remaining.try_get("amount").unwrap_or(0.0))),
+    );
+    patch.insert(
+        "total_management_fees".to_string(),
+        json!(round2(remaining.try_get("fees").unwrap_or(0.0))),
+    );
+    patch.insert(
+        "total_reserves".to_string(),
+        json!(round2(remaining.try_get("reserves").unwrap_or(0.0))),
+    );
+    if transfers == 0 {
+        patch.insert("status".to_string(), Value::String("failed".to_string()));
+        patch.insert(
+            "failed_at".to_string(),
+            Value::String(Utc::now().to_rfc3339()),
+        );
+        patch.insert("failure_reason".to_string(), Value::String(reason));
+    }
+    let updated = update_row(pool, "owner_payout_batches", &path.batch_id, &patch, "id").await?;
+
+    audit_transition(pool, &org_id, &user_id, &path.batch_id, batch, &updated).await;
+    let mut updated = updated.as_object().cloned().unwrap_or_default();
+    updated.insert(
+        "released_statement_ids".to_string(),
+        Value::Array(failed.into_iter().map(Value::String).collect()),
+    );
+    Ok(Json(Value::Object(updated)))
+}
+
+async fn batch_file(
+    State(state): State<AppState>,
+    Path(path): Path<OwnerPayoutBatchPath>,
+    Query(query): Query<OwnerPayoutFileQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response<Body>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+    let (batch, org_id) = load_batch(&state, pool, &path.batch_id, &user_id).await?;
+    if value_str(&batch, "status") == "failed" {
+        return Err(AppError::Conflict(
+            "A failed batch has nothing to transfer.".to_string(),
+        ));
+    }
+
+    let items = batch_items(pool, &path.batch_id).await?;
+    let lines = transfer_lines(&items, &value_str(&batch, "currency"));
+    let organization = get_row(pool, "organizations", &org_id, "id").await?;
+    let reference = value_str(&batch, "reference");
+    let file = BatchFile {
+        reference: reference.clone(),
+        payment_date: NaiveDate::parse_from_str(&value_str(&batch, "scheduled_for"), "%Y-%m-%d")
+            .unwrap_or_else(|_| Utc::now().date_naive()),
+        currency: value_str(&batch, "currency"),
+        origin_name: non_empty_opt(Some(&value_str(&organization, "bank_account_holder")))
+            .or_else(|| non_empty_opt(Some(&value_str(&organization, "legal_name"))))
+            .unwrap_or_else(|| value_str(&organization, "name")),
+        origin_tax_id: value_str(&organization, "ruc"),
+        origin_account: value_str(&organization, "bank_account_number"),
+    };
+
+    let (body, content_type, extension) = match non_empty_opt(query.layout_id.as_deref()) {
+        None => (
+            render_generic_csv(&file, &lines),
+            "text/csv; charset=utf-8",
+            "csv".to_string(),
+        ),
+        Some(layout_id) => {
+            let layout = get_row(pool, "payout_bank_layouts", &layout_id, "id").await?;
+            if value_str(&layout, "organization_id") != org_id {
+                return Err(AppError::NotFound("Bank layout not found.".to_string()));
+            }
+            let body = parse_layout(&layout)?
+                .render(&file, &lines)
+                .map_err(AppError::UnprocessableEntity)?;
+            (
+                body,
+                "text/plain; charset=utf-8",
+                value_str(&layout, "file_extension"),
+            )
+        }
+    };
+
+    write_audit_log(
+        Some(pool),
+        Some(&org_id),
+        Some(&user_id),
+        "export",
+        "owner_payout_batches",
+        Some(&path.batch_id),
+        None,
+        Some(json!({ "layout_id": query.layout_id, "transfers": lines.len() })),
+    )
+    .await;
+
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .body(Body::from(body))
+        .map_err(|error| {
+            tracing::error!(error = %error, "Could not build payout file response");
+            AppError::Internal("Could not build payout file response.".to_string())
+        })?;
+    let headers = response.headers_mut();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    if let Ok(value) =
+        HeaderValue::from_str(&format!("attachment; filename=\"{reference}.{extension}\""))
+    {
+        headers.insert(CONTENT_DISPOSITION, value);
+    }
+    Ok(response)
+}
+
+/// Rebuild the batch's transfers from its live items.
+fn transfer_lines(items: &[Value], currency: &str) -> Vec<TransferLine> {
+    let mut lines: Vec<TransferLine> = Vec::new();
+    for item in items
+        .iter()
+        .filter(|item| value_str(item, "status") != "failed")
+    {
+        let sequence = item
+            .get("transfer_sequence")
+            .and_then(Value::as_u64)
+            .unwrap_or_default() as u32;
+        let amount = value_f64(item, "amount");
+        let statement_id = value_str(item, "owner_statement_id");
+        if let Some(line) = lines.iter_mut().find(|line| line.sequence == sequence) {
+            line.amount = round2(line.amount + amount);
+            line.statement_ids.push(statement_id);
+            continue;
+        }
+        lines.push(TransferLine {
+            sequence,
+            payee_name: value_str(item, "payee_name"),
+            payee_tax_id: value_str(item, "payee_tax_id"),
+            bank_name: value_str(item, "bank_name"),
+            account_number: value_str(item, "bank_account_number"),
+            account_type: value_str(item, "bank_account_type"),
+            currency: currency.to_string(),
+            amount,
+            statement_ids: vec![statement_id],
+        });
+    }
+    lines.sort_by_key(|line| line.sequence);
+    lines
+}
+
+async fn load_batch(
+    state: &AppState,
+    pool: &sqlx::PgPool,
+    batch_id: &str,
+    user_id: &str,
+) -> AppResult<(Value, String)> {
+    let batch = get_row(pool, "owner_payout_batches", batch_id, "id").await?;
+    let org_id = value_str(&batch, "organization_id");
+    assert_permission(state, user_id, &org_id, permissions::OWNER_PAYOUTS_EDIT).await?;
+    Ok((batch, org_id))
+}
+
+fn ensure_transition(batch: &Value, next: PayoutStatus) -> AppResult<()> {
+    let current = PayoutStatus::parse(&value_str(batch, "status")).unwrap_or(PayoutStatus::Failed);
+    if current.can_become(next) {
+        Ok(())
+    } else {
+        Err(AppError::Conflict(format!(
+            "A {} batch cannot be marked {}.",
+            current.as_str(),
+            next.as_str()
+        )))
+    }
+}
+
+/// Move the batch's live items (optionally only some transfers) to
+/// `status`, returning the statements they pay.
+async fn set_item_status(
+    pool: &sqlx::PgPool,
+    batch_id: &str,
+    sequences: Option<&[i32]>,
+    status: PayoutStatus,
+    failure_reason: Option<&str>,
+) -> AppResult<Vec<String>> {
+    sqlx::query_scalar(
+        "UPDATE owner_payout_items
+         SET status = $3, failure_reason = COALESCE($4, failure_reason)
+         WHERE batch_id = $1::uuid
+           AND status NOT IN ('failed', 'confirmed')
+           AND ($2::int4[] IS NULL OR transfer_sequence = ANY($2))
+         RETURNING owner_statement_id::text",
+    )
+    .bind(batch_id)
+    .bind(sequences)
+    .bind(status.as_str())
+    .bind(failure_reason)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not update payout items."))
+}
+
+async fn batch_items(pool: &sqlx::PgPool, batch_id: &str) -> AppResult<Vec<Value>> {
+    let mut filters = Map::new();
+    filters.insert("batch_id".to_string(), Value::String(batch_id.to_string()));
+    list_rows(
+        pool,
+        "owner_payout_items",
+        Some(&filters),
+        1000,
+        0,
+        "transfer_sequence",
+        true,
+    )
+    .await
+}
+
+async fn audit_transition(
+    pool: &sqlx::PgPool,
+    org_id: &str,
+    user_id: &str,
+    batch_id: &str,
+    before: Value,
+    after: &Value,
+) {
+    write_audit_log(
+        Some(pool),
+        Some(org_id),
+        Some(user_id),
+        "status_transition",
+        "owner_payout_batches",
+        Some(batch_id),
+        Some(before),
+        Some(after.clone()),
+    )
+    .await;
+}
+
+/// `PAY-20261017-3F9A1C`: the payment date and a random suffix.
+fn batch_reference(date: NaiveDate) -> String {
+    let suffix = uuid::Uuid::new_v4().simple().to_string()[..6].to_uppercase();
+    format!("PAY-{}-{suffix}", date.format("%Y%m%d"))
+}
+
+// ── Bank layouts ───────────────────────────────────────────────────────
+
+async fn list_layouts(
+    State(state): State<AppState>,
+    Query(query): Query<PayoutBankLayoutsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(query.org_id.clone()),
+    );
+    let rows = list_rows(
+        pool,
+        "payout_bank_layouts",
+        Some(&filters),
+        200,
+        0,
+        "name",
+        true,
+    )
+    .await?;
+    Ok(Json(json!({ "data": rows })))
+}
+
+async fn create_layout(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreatePayoutBankLayoutInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::OWNER_PAYOUTS_EDIT,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let mut record = remove_nulls(serialize_to_map(&payload));
+    validate_layout(&mut record)?;
+    record.insert(
+        "created_by_user_id".to_string(),
+        Value::String(user_id.clone()),
+    );
+    let created = create_row(pool, "payout_bank_layouts", &record).await?;
+
+    write_audit_log(
+        Some(pool),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        "create",
+        "payout_bank_layouts",
+        created.get("id").and_then(Value::as_str),
+        None,
+        Some(created.clone()),
+    )
+    .await;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+async fn update_layout(
+    State(state): State<AppState>,
+    Path(path): Path<PayoutBankLayoutPath>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdatePayoutBankLayoutInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let existing = get_row(pool, "payout_bank_layouts", &path.layout_id, "id").await?;
+    let org_id = value_str(&existing, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::OWNER_PAYOUTS_EDIT).await?;
+
+    let patch = remove_nulls(serialize_to_map(&payload));
+    if patch.is_empty() {
+        return Ok(Json(existing));
+    }
+    let mut merged = existing.as_object().cloned().unwrap_or_default();
+    merged.extend(patch.clone());
+    validate_layout(&mut merged)?;
+    let patch: Map<String, Value> = merged
+        .into_iter()
+        .filter(|(key, _)| patch.contains_key(key))
+        .collect();
+
+    let updated = update_row(pool, "payout_bank_layouts", &path.layout_id, &patch, "id").await?;
+    write_audit_log(
+        Some(pool),
+        Some(&org_id),
+        Some(&user_id),
+        "update",
+        "payout_bank_layouts",
+        Some(&path.layout_id),
+        Some(existing),
+        Some(updated.clone()),
+    )
+    .await;
+    Ok(Json(updated))
+}
+
+async fn delete_layout(
+    State(state): State<AppState>,
+    Path(path): Path<PayoutBankLayoutPath>,
+    headers: HeaderMap,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let existing = get_row(pool, "payout_bank_layouts", &path.layout_id, "id").await?;
+    let org_id = value_str(&existing, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::OWNER_PAYOUTS_EDIT).await?;
+
+    delete_row(pool, "payout_bank_layouts", &path.layout_id, "id").await?;
+    write_audit_log(
+        Some(pool),
+        Some(&org_id),
+        Some(&user_id),
+        "delete",
+        "payout_bank_layouts",
+        Some(&path.layout_id),
+        Some(existing),
+        None,
+    )
+    .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Check a full layout record so partial updates are validated against the
+/// stored one.
+fn validate_layout(record: &mut Map<String, Value>) -> AppResult<()> {
+    let name = record
+        .get("name")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .unwrap_or_default();
+    if name.is_empty() {
+        return Err(AppError::BadRequest("name is required.".to_string()));
+    }
+    if let Some(extension) = record.get("file_extension").and_then(Value::as_str) {
+        let extension = extension
+            .trim()
+            .trim_start_matches('.')
+            .to_ascii_lowercase();
+        if extension.is_empty()
+            || extension.len() > 5
+            || !extension.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(AppError::BadRequest(
+                "file_extension must be up to five letters or digits.".to_string(),
+            ));
+        }
+        record.insert("file_extension".to_string(), Value::String(extension));
+    }
+    if let Some(line_ending) = record.get("line_ending").and_then(Value::as_str) {
+        if line_ending != "crlf" && line_ending != "lf" {
+            return Err(AppError::BadRequest(
+                "line_ending must be crlf or lf.".to_string(),
+            ));
+        }
+    }
+    parse_layout(&Value::Object(record.clone())).map(|_| ())
+}
+
+fn parse_layout(layout: &Value) -> AppResult<FixedWidthLayout> {
+    FixedWidthLayout::from_json(
+        layout.get("header_fields").unwrap_or(&Value::Null),
+        layout.get("detail_fields").unwrap_or(&Value::Null),
+        layout.get("trailer_fields").unwrap_or(&Value::Null),
+        layout
+            .get("line_ending")
+            .and_then(Value::as_str)
+            .unwrap_or("crlf"),
+    )
+    .map_err(AppError::BadRequest)
+}
+
+fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
+    state
+        .db_pool
+        .as_ref()
+        .ok_or_else(|| AppError::Dependency("Database is not configured.".to_string()))
+}
+
+fn value_str(row: &Value, key: &str) -> String {
+    row.as_object()
+        .and_then(|obj| obj.get(key))
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+fn value_f64(row: &Value, key: &str) -> f64 {
+    match row.as_object().and_then(|obj| obj.get(key)) {
+        Some(Value::Number(value)) => value.as_f64().unwrap_or(0.0),
+        Some(Value::String(value)) => value.trim().parse().unwrap_or(0.0),
+        _ => 0.0,
+    }
+}
+
+fn non_empty_opt(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
@@ -2,7 +2,10 @@ use crate::{
     error::{AppError, AppResult},
     repository::table_service::{create_row, get_row, list_rows, update_row},
     schemas::clamp_limit_in_range,
-    services::token_hash::{hash_token, hash_token_sha1},
+    services::{
+        mercado_pago, owner_reserves, payments,
+        token_hash::{hash_token, hash_token_sha1},
+    },
     state::AppState,
 };
 use axum::extract::Path;
@@ -42,6 +45,18 @@ pub fn router() -> axum::Router<AppState> {
             "/owner/property-performance",
             axum::routing::get(owner_property_performance),
         )
+        .route(
+            "/owner/reserves",
+            axum::routing::get(owner_reserve_balances),
+        )
+        .route(
+            "/owner/contributions",
+            axum::routing::get(owner_contributions),
+        )
+        .route(
+            "/owner/contributions/{contribution_id}/pay",
+            axum::routing::post(pay_owner_contribution),
+        )
 }
 
 #[derive(Debug, Deserialize)]
@@ -65,6 +80,18 @@ struct StatementIdPath {
     statement_id: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct ContributionIdPath {
+    contribution_id: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PayContributionInput {
+    /// `stripe` or `mercado_pago`; Mercado Pago when the organization has
+    /// it configured, Stripe otherwise.
+    provider: Option<String>,
+}
+
 fn default_limit() -> i64 {
     200
 }
@@ -607,7 +634,8 @@ async fn owner_reservations(
     Ok(Json(json!({ "data": rows })))
 }
 
-/// List finalized owner statements with payout amounts.
+/// List finalized and paid owner statements with payout amounts and the
+/// status of the bank transfer paying them.
 async fn owner_payout_history(
     State(state): State<AppState>,
     Query(query): Query<OwnerListQuery>,
@@ -616,10 +644,22 @@ async fn owner_payout_history(
     let (pool, org_id) = require_owner(&state, &headers).await?;
 
     let rows: Vec<Value> = sqlx::query_as::<_, (Value,)>(
-        "SELECT row_to_json(os.*)
+        "SELECT to_jsonb(os.*) || jsonb_build_object(
+                  'payout_status', payout.status,
+                  'payout_reference', payout.reference,
+                  'payout_scheduled_for', payout.scheduled_for
+                )
          FROM owner_statements os
+         LEFT JOIN LATERAL (
+           SELECT i.status, b.reference, b.scheduled_for
+           FROM owner_payout_items i
+           JOIN owner_payout_batches b ON b.id = i.batch_id
+           WHERE i.owner_statement_id = os.id
+           ORDER BY i.created_at DESC
+           LIMIT 1
+         ) payout ON true
          WHERE os.organization_id = $1::uuid
-           AND os.status = 'finalized'
+           AND os.status IN ('finalized', 'sent', 'paid')
          ORDER BY os.period_end DESC
          LIMIT $2",
     )
@@ -766,6 +806,167 @@ async fn owner_property_performance(
     Ok(Json(json!({ "data": performance })))
 }
 
+/// Reserve target and balance of each property.
+async fn owner_reserve_balances(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let (pool, org_id) = require_owner(&state, &headers).await?;
+    let rows = owner_reserves::reserve_balances(pool, &org_id, None).await?;
+    Ok(Json(json!({ "data": rows })))
+}
+
+/// Contributions the owner has been asked to pay, open ones first.
+async fn owner_contributions(
+    State(state): State<AppState>,
+    Query(query): Query<OwnerListQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let (pool, org_id) = require_owner(&state, &headers).await?;
+
+    let rows: Vec<Value> = sqlx::query_scalar(
+        "SELECT to_jsonb(oc.*) || jsonb_build_object('property_name', p.name)
+         FROM owner_contributions oc
+         JOIN properties p ON p.id = oc.property_id
+         WHERE oc.organization_id = $1::uuid
+           AND oc.status <> 'cancelled'
+         ORDER BY (oc.status = 'requested') DESC, oc.due_date NULLS LAST, oc.created_at DESC
+         LIMIT $2",
+    )
+    .bind(&org_id)
+    .bind(clamp_limit_in_range(query.limit, 1, 500))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load contributions."))?;
+
+    let outstanding: f64 = rows
+        .iter()
+        .filter(|row| val_str(row, "status") == "requested")
+        .map(|row| val_f64(row, "amount"))
+        .sum();
+
+    Ok(Json(json!({
+        "data": rows,
+        "outstanding": outstanding,
+    })))
+}
+
+/// Create a payment link for a requested contribution. The provider
+/// notifies the payment webhook, which credits the owner's reserve.
+async fn pay_owner_contribution(
+    State(state): State<AppState>,
+    Path(path): Path<ContributionIdPath>,
+    headers: HeaderMap,
+    payload: Option<Json<PayContributionInput>>,
+) -> AppResult<Json<Value>> {
+    let (pool, org_id) = require_owner(&state, &headers).await?;
+    let payload = payload.map(|Json(payload)| payload).unwrap_or_default();
+
+    let contribution = get_row(pool, "owner_contributions", &path.contribution_id, "id").await?;
+    if val_str(&contribution, "organization_id") != org_id {
+        return Err(AppError::Forbidden(
+            "Contribution does not belong to this organization.".to_string(),
+        ));
+    }
+    if val_str(&contribution, "status") != "requested" {
+        return Err(AppError::Gone(
+            "This contribution is no longer awaiting payment.".to_string(),
+        ));
+    }
+
+    let amount = val_f64(&contribution, "amount");
+    let currency = val_str(&contribution, "currency");
+    let reference_code = val_str(&contribution, "reference_code");
+    let org = get_row(pool, "organizations", &org_id, "id").await?;
+    let org_name = val_str(&org, "name");
+    let payer_name: String = sqlx::query_scalar(
+        "SELECT COALESCE(NULLIF(asset_owner_name, ''), name) FROM properties WHERE id = $1::uuid",
+    )
+    .bind(val_str(&contribution, "property_id"))
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load the property."))?
+    .unwrap_or_default();
+    let return_path = format!("/owner/contributions/{reference_code}");
+
+    let mp_access_token = mercado_pago::get_org_mp_access_token(pool, &org_id).await;
+    let provider = match payload.provider.as_deref().map(str::trim) {
+        Some("stripe") => "stripe",
+        Some("mercado_pago") => "mercado_pago",
+        Some(other) if !other.is_empty() => {
+            return Err(AppError::BadRequest(
+                "provider must be stripe or mercado_pago.".to_string(),
+            ));
+        }
+        _ if mp_access_token.is_ok() => "mercado_pago",
+        _ => "stripe",
+    };
+
+    let (checkout_url, session_id) = if provider == "mercado_pago" {
+        let access_token = mp_access_token.map_err(AppError::Dependency)?;
+        let description = format!("Aporte {reference_code} — {org_name}");
+        let result = mercado_pago::create_mp_checkout(
+            &state.http_client,
+            &access_token,
+            amount,
+            &currency,
+            &reference_code,
+            &description,
+            &format!(
+                "{}{return_path}?status=success",
+                state.config.app_public_url
+            ),
+            &format!("{}{return_path}?status=failed", state.config.app_public_url),
+        )
+        .await
+        .map_err(AppError::Dependency)?;
+        (
+            val_str(&result, "checkout_url"),
+            val_str(&result, "preference_id"),
+        )
+    } else {
+        let session = payments::create_stripe_checkout_session(
+            &state.http_client,
+            &state.config,
+            amount,
+            &currency,
+            &reference_code,
+            &payer_name,
+            &org_name,
+            &return_path,
+        )
+        .await
+        .map_err(AppError::Dependency)?;
+        (val_str(&session, "url"), val_str(&session, "id"))
+    };
+
+    let mut patch = Map::new();
+    patch.insert("provider".to_string(), Value::String(provider.to_string()));
+    patch.insert(
+        "checkout_url".to_string(),
+        Value::String(checkout_url.clone()),
+    );
+    patch.insert(
+        "provider_session_id".to_string(),
+        Value::String(session_id.clone()),
+    );
+    update_row(
+        pool,
+        "owner_contributions",
+        &path.contribution_id,
+        &patch,
+        "id",
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "provider": provider,
+        "checkout_url": checkout_url,
+        "session_id": session_id,
+        "reference_code": reference_code,
+    })))
+}
+
 /// Authenticate an owner from the x-owner-token header.
 async fn require_owner<'a>(
     state: &'a AppState,
@@ -0,0 +1,443 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::NaiveDate;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    auth::require_user_id,
+    error::{AppError, AppResult},
+    repository::table_service::{create_row, get_row, list_rows, update_row},
+    schemas::{
+        clamp_limit, CreateOwnerContributionInput, CreateOwnerReserveAdjustmentInput,
+        MarkOwnerContributionPaidInput, OwnerContributionPath, OwnerContributionsQuery,
+        OwnerReserveEntriesQuery, OwnerReservePath, OwnerReservesQuery,
+    },
+    services::{audit::write_audit_log, fx, owner_reserves, permissions},
+    state::AppState,
+    tenancy::{assert_org_member, assert_permission},
+};
+
+pub fn router() -> axum::Router<AppState> {
+    axum::Router::new()
+        .route("/owner-reserves", axum::routing::get(list_reserves))
+        .route(
+            "/owner-reserves/{property_id}/entries",
+            axum::routing::get(list_reserve_entries),
+        )
+        .route(
+            "/owner-reserves/{property_id}/adjustments",
+            axum::routing::post(create_reserve_adjustment),
+        )
+        .route(
+            "/owner-contributions",
+            axum::routing::get(list_contributions).post(create_contribution),
+        )
+        .route(
+            "/owner-contributions/{contribution_id}",
+            axum::routing::get(get_contribution),
+        )
+        .route(
+            "/owner-contributions/{contribution_id}/mark-paid",
+            axum::routing::post(mark_contribution_paid),
+        )
+        .route(
+            "/owner-contributions/{contribution_id}/cancel",
+            axum::routing::post(cancel_contribution),
+        )
+}
+
+// ── Reserves ───────────────────────────────────────────────────────────
+
+async fn list_reserves(
+    State(state): State<AppState>,
+    Query(query): Query<OwnerReservesQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let property_id = non_empty_opt(query.property_id.as_deref());
+    let rows =
+        owner_reserves::reserve_balances(pool, &query.org_id, property_id.as_deref()).await?;
+    Ok(Json(json!({ "data": rows })))
+}
+
+async fn list_reserve_entries(
+    State(state): State<AppState>,
+    Path(path): Path<OwnerReservePath>,
+    Query(query): Query<OwnerReserveEntriesQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let property = get_row(pool, "properties", &path.property_id, "id").await?;
+    assert_org_member(&state, &user_id, &value_str(&property, "organization_id")).await?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "property_id".to_string(),
+        Value::String(path.property_id.clone()),
+    );
+    let rows = list_rows(
+        pool,
+        "owner_reserve_entries",
+        Some(&filters),
+        clamp_limit(query.limit),
+        0,
+        "created_at",
+        false,
+    )
+    .await?;
+    Ok(Json(json!({ "data": rows })))
+}
+
+async fn create_reserve_adjustment(
+    State(state): State<AppState>,
+    Path(path): Path<OwnerReservePath>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateOwnerReserveAdjustmentInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let property = get_row(pool, "properties", &path.property_id, "id").await?;
+    let org_id = value_str(&property, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::OWNER_RESERVES_EDIT).await?;
+
+    if !payload.amount.is_finite() || round2(payload.amount) == 0.0 {
+        return Err(AppError::BadRequest(
+            "amount must be a non-zero number.".to_string(),
+        ));
+    }
+    let currency = fx::reporting_currency(
+        pool,
+        &org_id,
+        Some(&path.property_id),
+        None,
+        payload.currency.as_deref(),
+    )
+    .await?;
+
+    let entry = owner_reserves::record_adjustment(
+        pool,
+        &org_id,
+        &path.property_id,
+        &currency,
+        payload.amount,
+        non_empty_opt(payload.notes.as_deref()).as_deref(),
+        Some(&user_id),
+    )
+    .await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "create",
+        "owner_reserve_entries",
+        Some(&value_str(&entry, "id")),
+        None,
+        Some(entry.clone()),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+// ── Contributions ──────────────────────────────────────────────────────
+
+async fn list_contributions(
+    State(state): State<AppState>,
+    Query(query): Query<OwnerContributionsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(query.org_id.clone()),
+    );
+    if let Some(status) = non_empty_opt(query.status.as_deref()) {
+        filters.insert("status".to_string(), Value::String(status));
+    }
+    if let Some(property_id) = non_empty_opt(query.property_id.as_deref()) {
+        filters.insert("property_id".to_string(), Value::String(property_id));
+    }
+    let rows = list_rows(
+        pool,
+        "owner_contributions",
+        Some(&filters),
+        clamp_limit(query.limit),
+        0,
+        "created_at",
+        false,
+    )
+    .await?;
+    Ok(Json(json!({ "data": rows })))
+}
+
+async fn get_contribution(
+    State(state): State<AppState>,
+    Path(path): Path<OwnerContributionPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let record = get_row(pool, "owner_contributions", &path.contribution_id, "id").await?;
+    assert_org_member(&state, &user_id, &value_str(&record, "organization_id")).await?;
+    Ok(Json(record))
+}
+
+/// Ask the owner to pay money in. Against a statement, the contribution
+/// defaults to the deficit the statement carried forward.
+async fn create_contribution(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateOwnerContributionInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let org_id = payload.organization_id.clone();
+    assert_permission(&state, &user_id, &org_id, permissions::OWNER_RESERVES_EDIT).await?;
+    let pool = db_pool(&state)?;
+
+    let mut property_id = non_empty_opt(payload.property_id.as_deref());
+    let mut amount = payload.amount;
+    let mut currency = payload.currency.clone();
+    let statement_id = non_empty_opt(payload.owner_statement_id.as_deref());
+    if let Some(statement_id) = statement_id.as_deref() {
+        let statement = get_row(pool, "owner_statements", statement_id, "id").await?;
+        if value_str(&statement, "organization_id") != org_id {
+            return Err(AppError::NotFound("Owner statement not found.".to_string()));
+        }
+        let open: bool = sqlx::query_scalar(
+            "SELECT EXISTS (
+               SELECT 1 FROM owner_contributions
+               WHERE owner_statement_id = $1::uuid AND status <> 'cancelled'
+             )",
+        )
+        .bind(statement_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Could not load contributions."))?;
+        if open {
+            return Err(AppError::Conflict(
+                "A contribution has already been requested for this statement.".to_string(),
+            ));
+        }
+
+        if property_id.is_none() {
+            property_id = sqlx::query_scalar::<_, Option<String>>(
+                "SELECT COALESCE(os.property_id, u.property_id)::text
+                 FROM owner_statements os
+                 LEFT JOIN units u ON u.id = os.unit_id
+                 WHERE os.id = $1::uuid",
+            )
+            .bind(statement_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::from_database_error(&e, "Could not load the statement."))?
+            .flatten();
+        }
+        if amount.is_none() {
+            amount = Some(-value_f64(&statement, "balance_carried_forward"));
+        }
+        if currency.is_none() {
+            currency = non_empty_opt(Some(value_str(&statement, "currency").as_str()));
+        }
+    }
+
+    let property_id = property_id.ok_or_else(|| {
+        AppError::BadRequest("property_id or a property statement is required.".to_string())
+    })?;
+    let property = get_row(pool, "properties", &property_id, "id").await?;
+    if value_str(&property, "organization_id") != org_id {
+        return Err(AppError::NotFound("Property not found.".to_string()));
+    }
+    let amount = round2(amount.unwrap_or(0.0));
+    if !amount.is_finite() || amount <= 0.0 {
+        return Err(AppError::BadRequest(
+            "amount must be greater than zero; the statement has no deficit to cover.".to_string(),
+        ));
+    }
+    let currency =
+        fx::reporting_currency(pool, &org_id, Some(&property_id), None, currency.as_deref())
+            .await?;
+
+    let mut record = Map::new();
+    record.insert("organization_id".to_string(), Value::String(org_id.clone()));
+    record.insert("property_id".to_string(), Value::String(property_id));
+    if let Some(statement_id) = statement_id {
+        record.insert(
+            "owner_statement_id".to_string(),
+            Value::String(statement_id),
+        );
+    }
+    record.insert("amount".to_string(), json!(amount));
+    record.insert("currency".to_string(), Value::String(currency));
+    if let Some(reason) = non_empty_opt(payload.reason.as_deref()) {
+        record.insert("reason".to_string(), Value::String(reason));
+    }
+    if let Some(due_date) = non_empty_opt(payload.due_date.as_deref()) {
+        NaiveDate::parse_from_str(&due_date, "%Y-%m-%d")
+            .map_err(|_| AppError::BadRequest("due_date must be a YYYY-MM-DD date.".to_string()))?;
+        record.insert("due_date".to_string(), Value::String(due_date));
+    }
+    record.insert(
+        "created_by_user_id".to_string(),
+        Value::String(user_id.clone()),
+    );
+
+    let created = create_row(pool, "owner_contributions", &record).await?;
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "create",
+        "owner_contributions",
+        Some(&value_str(&created, "id")),
+        None,
+        Some(created.clone()),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Record a contribution paid outside the payment link, such as by bank
+/// transfer.
+async fn mark_contribution_paid(
+    State(state): State<AppState>,
+    Path(path): Path<OwnerContributionPath>,
+    headers: HeaderMap,
+    Json(payload): Json<MarkOwnerContributionPaidInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let record = get_row(pool, "owner_contributions", &path.contribution_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::OWNER_RESERVES_EDIT).await?;
+
+    if payload.amount.is_some_and(|amount| amount <= 0.0) {
+        return Err(AppError::BadRequest(
+            "amount must be greater than zero.".to_string(),
+        ));
+    }
+    let updated = owner_reserves::record_contribution_payment(
+        pool,
+        &path.contribution_id,
+        payload.amount,
+        non_empty_opt(payload.payment_reference.as_deref()).as_deref(),
+        Some(&user_id),
+    )
+    .await?
+    .ok_or_else(|| {
+        AppError::BadRequest("Only requested contributions can be marked paid.".to_string())
+    })?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "status_transition",
+        "owner_contributions",
+        Some(&path.contribution_id),
+        Some(record),
+        Some(updated.clone()),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+async fn cancel_contribution(
+    State(state): State<AppState>,
+    Path(path): Path<OwnerContributionPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let record = get_row(pool, "owner_contributions", &path.contribution_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::OWNER_RESERVES_EDIT).await?;
+
+    if value_str(&record, "status") != "requested" {
+        return Err(AppError::BadRequest(
+            "Only requested contributions can be cancelled.".to_string(),
+        ));
+    }
+
+    let mut patch = Map::new();
+    patch.insert("status".to_string(), Value::String("cancelled".to_string()));
+    patch.insert(
+        "cancelled_at".to_string(),
+        Value::String(chrono::Utc::now().to_rfc3339()),
+    );
+    let updated = update_row(
+        pool,
+        "owner_contributions",
+        &path.contribution_id,
+        &patch,
+        "id",
+    )
+    .await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "status_transition",
+        "owner_contributions",
+        Some(&path.contribution_id),
+        Some(record),
+        Some(updated.clone()),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
+    state
+        .db_pool
+        .as_ref()
+        .ok_or_else(|| AppError::Dependency("Database is not configured.".to_string()))
+}
+
+fn value_str(row: &Value, key: &str) -> String {
+    row.as_object()
+        .and_then(|obj| obj.get(key))
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+fn value_f64(row: &Value, key: &str) -> f64 {
+    match row.as_object().and_then(|obj| obj.get(key)) {
+        Some(Value::Number(value)) => value.as_f64().unwrap_or(0.0),
+        Some(Value::String(value)) => value.trim().parse().unwrap_or(0.0),
+        _ => 0.0,
+    }
+}
+
+fn non_empty_opt(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
@@ -13,10 +13,20 @@ use crate::{
     repository::table_service::{create_row, get_row, list_rows, update_row},
     schemas::{
         clamp_limit_in_range, CreateOwnerStatementInput, OwnerStatementPath, OwnerStatementsQuery,
+        RenderPdfInput,
+    },
+    services::{
+        audit::write_audit_log,
+        enrichment::enrich_owner_statements,
+        fx::{self, FxRate},
+        general_ledger, management_fees,
+        owner_reserves::{self, StatementBalances},
+        pdf_documents::{self, StatementPdf},
+        permissions,
+        storage::StorageNamespace,
     },
-    services::{audit::write_audit_log, enrichment::enrich_owner_statements},
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
 const REPORTABLE_STATUSES: &[&str] = &["confirmed", "checked_in", "checked_out"];
@@ -43,6 +53,14 @@ pub fn router() -> axum::Router<AppState> {
             "/owner-statements/{statement_id}/finalize",
             axum::routing::post(finalize_owner_statement),
         )
+        .route(
+            "/owner-statements/{statement_id}/mark-paid",
+            axum::routing::post(mark_owner_statement_paid),
+        )
+        .route(
+            "/owner-statements/{statement_id}/pdf",
+            axum::routing::post(render_owner_statement_pdf),
+        )
 }
 
 async fn list_owner_statements(
@@ -89,22 +107,42 @@ async fn create_owner_statement(
     Json(payload): Json<CreateOwnerStatementInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
+    assert_permission(
         &state,
         &user_id,
         &payload.organization_id,
-        &["owner_admin", "accountant"],
+        permissions::OWNER_STATEMENTS_EDIT,
     )
     .await?;
     let pool = db_pool(&state)?;
 
+    let currency = fx::reporting_currency(
+        pool,
+        &payload.organization_id,
+        payload.property_id.as_deref(),
+        payload.unit_id.as_deref(),
+        payload.currency.as_deref(),
+    )
+    .await?;
     let breakdown = build_statement_breakdown(
         pool,
+        &state.http_client,
         &payload.organization_id,
         &payload.period_start,
         &payload.period_end,
         payload.property_id.as_deref(),
         payload.unit_id.as_deref(),
+        &currency,
+    )
+    .await?;
+    let balances = owner_reserves::statement_balances(
+        pool,
+        &payload.organization_id,
+        payload.property_id.as_deref(),
+        payload.unit_id.as_deref(),
+        &payload.period_start,
+        &currency,
+        breakdown.net_payout,
     )
     .await?;
 
@@ -112,6 +150,7 @@ async fn create_owner_statement(
         .ok()
         .and_then(|value| value.as_object().cloned())
         .unwrap_or_default();
+    statement.insert("currency".to_string(), Value::String(currency));
     statement.insert("gross_revenue".to_string(), json!(breakdown.gross_revenue));
     statement.insert(
         "lease_collections".to_string(),
@@ -122,6 +161,10 @@ async fn create_owner_statement(
         "collection_fees".to_string(),
         json!(breakdown.collection_fees),
     );
+    statement.insert(
+        "management_fees".to_string(),
+        json!(breakdown.management_fees),
+    );
     statement.insert("platform_fees".to_string(), json!(breakdown.platform_fees));
     statement.insert(
         "taxes_collected".to_string(),
@@ -131,7 +174,7 @@ async fn create_owner_statement(
         "operating_expenses".to_string(),
         json!(breakdown.operating_expenses),
     );
-    statement.insert("net_payout".to_string(), json!(breakdown.net_payout));
+    insert_balances(&mut statement, &balances);
     statement.insert("status".to_string(), Value::String("draft".to_string()));
 
     let created = create_row(pool, "owner_statements", &statement).await?;
@@ -149,12 +192,13 @@ async fn create_owner_statement(
 
     let mut response = created.as_object().cloned().unwrap_or_default();
     response.insert("line_items".to_string(), Value::Array(breakdown.line_items));
+    response.insert("fx_rates".to_string(), Value::Array(breakdown.fx_rates));
     response.insert(
         "reconciliation".to_string(),
         json!({
             "gross_total": breakdown.reconciliation_gross_total,
-            "computed_net_payout": breakdown.reconciliation_computed_net_payout,
-            "stored_net_payout": breakdown.net_payout,
+            "computed_net_payout": balances.payout_for(breakdown.reconciliation_computed_net_payout),
+            "stored_net_payout": balances.net_payout,
             "stored_vs_computed_diff": 0.0,
         }),
     );
@@ -183,26 +227,33 @@ async fn get_owner_statement(
         .pop()
         .unwrap_or_else(|| Value::Object(Map::new()));
 
+    let currency = fx::normalize_currency(&value_str(&record, "currency"))
+        .unwrap_or_else(|| "PYG".to_string());
     let breakdown = build_statement_breakdown(
         pool,
+        &state.http_client,
         &org_id,
         &value_str(&record, "period_start"),
         &value_str(&record, "period_end"),
         non_empty_opt(record.get("property_id").and_then(Value::as_str)).as_deref(),
         non_empty_opt(record.get("unit_id").and_then(Value::as_str)).as_deref(),
+        &currency,
     )
     .await?;
 
     let stored_net = round2(number_from_value(record.get("net_payout")));
+    let computed_net = StatementBalances::from_statement(&record)
+        .payout_for(breakdown.reconciliation_computed_net_payout);
     if let Some(obj) = item.as_object_mut() {
         obj.insert("line_items".to_string(), Value::Array(breakdown.line_items));
+        obj.insert("fx_rates".to_string(), Value::Array(breakdown.fx_rates));
         obj.insert(
             "reconciliation".to_string(),
             json!({
                 "gross_total": breakdown.reconciliation_gross_total,
-                "computed_net_payout": breakdown.reconciliation_computed_net_payout,
+                "computed_net_payout": computed_net,
                 "stored_net_payout": stored_net,
-                "stored_vs_computed_diff": round2(stored_net - breakdown.reconciliation_computed_net_payout),
+                "stored_vs_computed_diff": round2(stored_net - computed_net),
             }),
         );
     }
@@ -219,7 +270,13 @@ async fn request_approval_owner_statement(
 
     let record = get_row(pool, "owner_statements", &path.statement_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "accountant"]).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &org_id,
+        permissions::OWNER_STATEMENTS_EDIT,
+    )
+    .await?;
 
     let current_status = value_str(&record, "status");
     if current_status != "draft" {
@@ -265,7 +322,13 @@ async fn approve_owner_statement(
 
     let record = get_row(pool, "owner_statements", &path.statement_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin"]).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &org_id,
+        permissions::OWNER_STATEMENTS_APPROVE,
+    )
+    .await?;
 
     let approval_status = value_str(&record, "approval_status");
     if approval_status != "pending" {
@@ -312,7 +375,13 @@ async fn finalize_owner_statement(
 
     let record = get_row(pool, "owner_statements", &path.statement_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "accountant"]).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &org_id,
+        permissions::OWNER_STATEMENTS_EDIT,
+    )
+    .await?;
 
     let updated = update_row(
         pool,
@@ -322,6 +391,8 @@ async fn finalize_owner_statement(
         "id",
     )
     .await?;
+    general_ledger::record_statement_fees(pool, &updated, Some(&user_id)).await;
+    owner_reserves::record_statement_reserve(pool, &updated, Some(&user_id)).await?;
 
     write_audit_log(
         state.db_pool.as_ref(),
@@ -338,27 +409,191 @@ async fn finalize_owner_statement(
     Ok(Json(updated))
 }
 
+/// Record that the owner has been paid the statement's net payout.
+async fn mark_owner_statement_paid(
+    State(state): State<AppState>,
+    Path(path): Path<OwnerStatementPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let record = get_row(pool, "owner_statements", &path.statement_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(
+        &state,
+        &user_id,
+        &org_id,
+        permissions::OWNER_STATEMENTS_EDIT,
+    )
+    .await?;
+
+    let status = value_str(&record, "status");
+    if status != "finalized" && status != "sent" {
+        return Err(AppError::BadRequest(
+            "Only finalized or sent statements can be marked paid.".to_string(),
+        ));
+    }
+
+    let updated = update_row(
+        pool,
+        "owner_statements",
+        &path.statement_id,
+        &json_map(&[
+            ("status", Value::String("paid".to_string())),
+            ("paid_at", Value::String(chrono::Utc::now().to_rfc3339())),
+        ]),
+        "id",
+    )
+    .await?;
+    general_ledger::record_statement_payout(pool, &updated, Some(&user_id)).await;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "status_transition",
+        "owner_statements",
+        Some(&path.statement_id),
+        Some(record),
+        Some(updated.clone()),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+/// Render the statement as a branded PDF, store it and link it from
+/// `owner_statements.pdf_url`. Re-rendering replaces the link.
+async fn render_owner_statement_pdf(
+    State(state): State<AppState>,
+    Path(path): Path<OwnerStatementPath>,
+    headers: HeaderMap,
+    payload: Option<Json<RenderPdfInput>>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+    let payload = payload.map(|Json(payload)| payload).unwrap_or_default();
+
+    let record = get_row(pool, "owner_statements", &path.statement_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(
+        &state,
+        &user_id,
+        &org_id,
+        permissions::OWNER_STATEMENTS_EDIT,
+    )
+    .await?;
+
+    let branding = pdf_documents::load_branding(pool, &state.http_client, &org_id).await?;
+    let lang = branding.lang_for(payload.lang.as_deref())?;
+
+    let property_id = non_empty_opt(record.get("property_id").and_then(Value::as_str));
+    let unit_id = non_empty_opt(record.get("unit_id").and_then(Value::as_str));
+    let currency = fx::normalize_currency(&value_str(&record, "currency"))
+        .unwrap_or_else(|| "PYG".to_string());
+    let breakdown = build_statement_breakdown(
+        pool,
+        &state.http_client,
+        &org_id,
+        &value_str(&record, "period_start"),
+        &value_str(&record, "period_end"),
+        property_id.as_deref(),
+        unit_id.as_deref(),
+        &currency,
+    )
+    .await?;
+
+    let (property_name, unit_name) = sqlx::query_as::<_, (Option<String>, Option<String>)>(
+        "SELECT (SELECT name FROM properties WHERE id = $1::uuid),
+                (SELECT name FROM units WHERE id = $2::uuid)",
+    )
+    .bind(property_id.as_deref())
+    .bind(unit_id.as_deref())
+    .fetch_one(pool)
+    .await
+    .map_err(|error| AppError::from_database_error(&error, "Failed to load statement scope."))?;
+
+    let bytes = pdf_documents::render_owner_statement(
+        &branding,
+        lang,
+        &StatementPdf {
+            statement: &record,
+            property_name,
+            unit_name,
+            line_items: &breakdown.line_items,
+            fx_rates: &breakdown.fx_rates,
+        },
+    );
+    let stored = pdf_documents::store_pdf(
+        &state.config,
+        StorageNamespace::Documents,
+        &org_id,
+        "owner-statements",
+        &path.statement_id,
+        bytes,
+    )
+    .await?;
+
+    let updated = update_row(
+        pool,
+        "owner_statements",
+        &path.statement_id,
+        &json_map(&[("pdf_url", Value::String(stored.public_url.clone()))]),
+        "id",
+    )
+    .await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "render_pdf",
+        "owner_statements",
+        Some(&path.statement_id),
+        Some(record),
+        Some(updated),
+    )
+    .await;
+
+    Ok(Json(json!({
+        "statement_id": path.statement_id,
+        "pdf_url": stored.public_url,
+        "object_key": stored.object_key,
+        "size_bytes": stored.size_bytes,
+        "lang": lang.code(),
+    })))
+}
+
 struct StatementBreakdown {
     gross_revenue: f64,
     lease_collections: f64,
     service_fees: f64,
     collection_fees: f64,
+    management_fees: f64,
     platform_fees: f64,
     taxes_collected: f64,
     operating_expenses: f64,
     net_payout: f64,
     line_items: Vec<Value>,
+    fx_rates: Vec<Value>,
     reconciliation_gross_total: f64,
     reconciliation_computed_net_payout: f64,
 }
 
+/// Compute statement totals and line items in `currency`. Each transaction
+/// is converted at the rate in effect on its own date (check-in, expense,
+/// payment or charge date).
+#[allow(clippy::too_many_arguments)]
 async fn build_statement_breakdown(
     pool: &sqlx::PgPool,
+    http: &reqwest::Client,
     organization_id: &str,
     period_start: &str,
     period_end: &str,
     property_id: Option<&str>,
     unit_id: Option<&str>,
+    currency: &str,
 ) -> AppResult<StatementBreakdown> {
     let start = parse_date(period_start)?;
     let end = parse_date(period_end)?;
@@ -662,10 +897,12 @@ async fn build_statement_breakdown(
     let mut line_items: Vec<Value> = Vec::new();
     let mut expense_warnings: std::collections::HashMap<String, Vec<String>> =
         std::collections::HashMap::new();
+    let mut converter = StatementFx::new(pool, http, currency);
 
     let mut gross_revenue = 0.0;
     let mut platform_fees = 0.0;
     let mut taxes_collected = 0.0;
+    let mut booked_nights = 0_i64;
     for reservation in reservations {
         let Some(res_obj) = reservation.as_object() else {
             continue;
@@ -688,47 +925,65 @@ async fn build_statement_breakdown(
             continue;
         }
 
+        booked_nights += (check_out_date.min(end + chrono::Duration::days(1))
+            - check_in_date.max(start))
+        .num_days()
+        .max(0);
+
         let reservation_id = value_str_from_obj(res_obj, "id");
+        let res_currency = record_currency(res_obj);
         let gross_amount = number_from_obj(res_obj, "total_amount");
         let platform_amount = number_from_obj(res_obj, "platform_fee");
         let tax_amount = number_from_obj(res_obj, "tax_amount");
+        if gross_amount == 0.0 && platform_amount == 0.0 && tax_amount == 0.0 {
+            continue;
+        }
 
-        gross_revenue += gross_amount;
-        platform_fees += platform_amount;
-        taxes_collected += tax_amount;
+        let Some(rate) = converter.rate(&res_currency, check_in_date).await? else {
+            expense_warnings
+                .entry(format!("missing_fx_rate:{res_currency}"))
+                .or_default()
+                .push(reservation_id.clone());
+            continue;
+        };
+        let gross = Converted::at(gross_amount, &res_currency, currency, rate.as_ref());
+        let platform = Converted::at(platform_amount, &res_currency, currency, rate.as_ref());
+        let tax = Converted::at(tax_amount, &res_currency, currency, rate.as_ref());
+
+        gross_revenue += gross.amount;
+        platform_fees += platform.amount;
+        taxes_collected += tax.amount;
 
         if gross_amount != 0.0 {
-            line_items.push(json!({
+            line_items.push(gross.line_item(json!({
                 "bucket": "gross_revenue",
                 "source_table": "reservations",
                 "source_id": reservation_id,
                 "kind": "reservation_total",
                 "from": value_str_from_obj(res_obj, "check_in_date"),
                 "to": value_str_from_obj(res_obj, "check_out_date"),
-                "amount_pyg": round2(gross_amount),
-            }));
+            })));
         }
         if platform_amount != 0.0 {
-            line_items.push(json!({
+            line_items.push(platform.line_item(json!({
                 "bucket": "platform_fees",
                 "source_table": "reservations",
                 "source_id": reservation_id,
                 "kind": "reservation_platform_fee",
-                "amount_pyg": round2(platform_amount),
-            }));
+            })));
         }
         if tax_amount != 0.0 {
-            line_items.push(json!({
+            line_items.push(tax.line_item(json!({
                 "bucket": "taxes_collected",
                 "source_table": "reservations",
                 "source_id": reservation_id,
                 "kind": "reservation_tax",
-                "amount_pyg": round2(tax_amount),
-            }));
+            })));
         }
     }
 
     let mut operating_expenses = 0.0;
+    let mut period_expenses: Vec<management_fees::PeriodExpense> = Vec::new();
     for expense in expenses {
         let Some(expense_obj) = expense.as_object() else {
             continue;
@@ -744,23 +999,47 @@ async fn build_statement_breakdown(
             continue;
         }
 
-        let (amount_pyg, warning) = expense_amount_pyg(expense_obj);
-        operating_expenses += amount_pyg;
         let expense_id = value_str_from_obj(expense_obj, "id");
-        if let Some(warning_key) = warning {
-            expense_warnings
-                .entry(warning_key)
-                .or_default()
-                .push(expense_id.clone());
-        }
-        line_items.push(json!({
+        let expense_currency = record_currency(expense_obj);
+        let amount = number_from_obj(expense_obj, "amount");
+        // A rate recorded on the expense itself wins over the market rate.
+        let recorded_fx = number_from_obj(expense_obj, "fx_rate_to_pyg");
+        let converted = if currency == "PYG" && expense_currency != "PYG" && recorded_fx > 0.0 {
+            Converted::at(
+                amount,
+                &expense_currency,
+                currency,
+                Some(&FxRate {
+                    base: expense_currency.clone(),
+                    quote: "PYG".to_string(),
+                    rate_date: expense_date,
+                    rate: recorded_fx,
+                    source: "expense".to_string(),
+                }),
+            )
+        } else {
+            let Some(rate) = converter.rate(&expense_currency, expense_date).await? else {
+                expense_warnings
+                    .entry(format!("missing_fx_rate:{expense_currency}"))
+                    .or_default()
+                    .push(expense_id.clone());
+                continue;
+            };
+            Converted::at(amount, &expense_currency, currency, rate.as_ref())
+        };
+        operating_expenses += converted.amount;
+        period_expenses.push(management_fees::PeriodExpense {
+            expense_id: expense_id.clone(),
+            category: value_str_from_obj(expense_obj, "category").to_ascii_lowercase(),
+            amount: converted.amount,
+        });
+        line_items.push(converted.line_item(json!({
             "bucket": "operating_expenses",
             "source_table": "expenses",
             "source_id": expense_id,
             "kind": fallback_str(expense_obj.get("category"), "expense"),
             "date": value_str_from_obj(expense_obj, "expense_date"),
-            "amount_pyg": round2(amount_pyg),
-        }));
+        })));
     }
 
     let mut lease_collections = 0.0;
@@ -792,29 +1071,35 @@ async fn build_statement_breakdown(
             continue;
         }
 
-        let (amount_pyg, warning) = generic_amount_pyg(collection_obj, "amount");
-        lease_collections += amount_pyg;
         let collection_id = value_str_from_obj(collection_obj, "id");
-        if let Some(warning_key) = warning {
+        let collection_currency = record_currency(collection_obj);
+        let Some(rate) = converter.rate(&collection_currency, paid_on).await? else {
             expense_warnings
-                .entry(warning_key)
+                .entry(format!("missing_fx_rate:{collection_currency}"))
                 .or_default()
                 .push(collection_id.clone());
-        }
+            continue;
+        };
+        let converted = Converted::at(
+            number_from_obj(collection_obj, "amount"),
+            &collection_currency,
+            currency,
+            rate.as_ref(),
+        );
+        lease_collections += converted.amount;
 
         let lease_id = value_str_from_obj(collection_obj, "lease_id");
         if !lease_id.is_empty() {
             paid_lease_ids.insert(lease_id);
         }
 
-        line_items.push(json!({
+        line_items.push(converted.line_item(json!({
             "bucket": "lease_collections",
             "source_table": "collection_records",
             "source_id": collection_id,
             "kind": "collection_paid",
             "date": paid_on.to_string(),
-            "amount_pyg": round2(amount_pyg),
-        }));
+        })));
     }
 
     let mut service_fees = 0.0;
@@ -838,54 +1123,191 @@ async fn build_statement_breakdown(
             continue;
         }
 
-        let (amount_pyg, warning) = generic_amount_pyg(charge_obj, "amount");
-        service_fees += amount_pyg;
         let charge_id = value_str_from_obj(charge_obj, "id");
-        if let Some(warning_key) = warning {
+        let charge_currency = record_currency(charge_obj);
+        let Some(rate) = converter.rate(&charge_currency, charge_date).await? else {
             expense_warnings
-                .entry(warning_key)
+                .entry(format!("missing_fx_rate:{charge_currency}"))
                 .or_default()
                 .push(charge_id.clone());
-        }
+            continue;
+        };
+        let converted = Converted::at(
+            number_from_obj(charge_obj, "amount"),
+            &charge_currency,
+            currency,
+            rate.as_ref(),
+        );
+        service_fees += converted.amount;
 
-        line_items.push(json!({
+        line_items.push(converted.line_item(json!({
             "bucket": "service_fees",
             "source_table": "lease_charges",
             "source_id": charge_id,
             "kind": charge_type,
             "date": value_str_from_obj(charge_obj, "charge_date"),
-            "amount_pyg": round2(amount_pyg),
-        }));
+        })));
     }
 
+    let schedule_property_id = match (property_scope.as_ref(), unit_scope.as_ref()) {
+        (Some(property_id), _) => Some(property_id.clone()),
+        (None, Some(unit_id)) => sqlx::query_scalar::<_, Option<String>>(
+            "SELECT property_id::text FROM units WHERE id = $1::uuid",
+        )
+        .bind(unit_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Could not load the unit."))?
+        .flatten(),
+        (None, None) => None,
+    };
+    let schedule = management_fees::applicable_schedule(
+        pool,
+        organization_id,
+        schedule_property_id.as_deref(),
+        start,
+        end,
+    )
+    .await?;
+
+    // A fee schedule replaces the fixed per-lease collection fee below.
     let mut collection_fees = 0.0;
+    let mut management_fee_total = 0.0;
+    if let Some(schedule) = schedule.as_ref() {
+        let unit_count = match (unit_scope.as_ref(), allowed_unit_ids.as_ref()) {
+            (Some(_), _) => 1,
+            (None, Some(allowed)) => allowed.len(),
+            (None, None) => sqlx::query_scalar::<_, i64>(
+                "SELECT count(*) FROM units WHERE organization_id = $1::uuid",
+            )
+            .bind(organization_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::from_database_error(&e, "Could not count units."))?
+                as usize,
+        };
+        let period_days = (end - start).num_days() + 1;
+        let occupancy = if unit_count == 0 {
+            0.0
+        } else {
+            (booked_nights as f64 / (unit_count as f64 * period_days as f64)).min(1.0)
+        };
+
+        let mut new_leases = Vec::new();
+        for (lease_id, lease) in &lease_index {
+            let Some(lease) = lease.as_object() else {
+                continue;
+            };
+            let starts_on = lease
+                .get("starts_on")
+                .and_then(Value::as_str)
+                .and_then(|value| parse_date(value).ok());
+            if value_str_from_obj(lease, "lease_status") == "draft"
+                || !starts_on.is_some_and(|date| date >= start && date <= end)
+            {
+                continue;
+            }
+            let lease_currency = record_currency(lease);
+            let Some(rate) = converter.rate(&lease_currency, end).await? else {
+                expense_warnings
+                    .entry(format!("missing_fx_rate:{lease_currency}"))
+                    .or_default()
+                    .push(lease_id.clone());
+                continue;
+            };
+            new_leases.push(management_fees::NewLease {
+                lease_id: lease_id.clone(),
+                monthly_rent: Converted::at(
+                    number_from_obj(lease, "monthly_rent"),
+                    &lease_currency,
+                    currency,
+                    rate.as_ref(),
+                )
+                .amount,
+                is_renewal: lease
+                    .get("is_renewal")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+            });
+        }
+        new_leases.sort_by(|a, b| a.lease_id.cmp(&b.lease_id));
+
+        // Flat amounts are in the schedule currency and, like other fees,
+        // use the end-date rate.
+        let flat_amount_rate = match converter.rate(&schedule.currency, end).await? {
+            Some(rate) => rate.map_or(1.0, |rate| rate.rate),
+            None => {
+                expense_warnings
+                    .entry(format!("missing_fx_rate:{}", schedule.currency))
+                    .or_default()
+                    .push(schedule.id.clone());
+                1.0
+            }
+        };
+
+        let inputs = management_fees::FeeInputs {
+            currency: currency.to_string(),
+            reservation_revenue: gross_revenue,
+            lease_collections,
+            platform_fees,
+            operating_expenses,
+            unit_count,
+            months: management_fees::months_in_period(start, end),
+            occupancy,
+            new_leases,
+            expenses: period_expenses,
+            flat_amount_rate,
+        };
+        for fee in management_fees::evaluate(&schedule.rules, &inputs) {
+            management_fee_total += fee.amount;
+            let mut item = fee.line_item(currency, &schedule.id);
+            if let Some(obj) = item.as_object_mut() {
+                obj.insert("schedule_name".to_string(), json!(schedule.name));
+            }
+            line_items.push(item);
+        }
+    }
+
+    if schedule.is_some() {
+        paid_lease_ids.clear();
+    }
     for lease_id in paid_lease_ids {
         let lease = lease_index
             .get(&lease_id)
             .and_then(Value::as_object)
             .cloned()
             .unwrap_or_default();
-        let platform_fee = number_from_obj(&lease, "platform_fee");
-        collection_fees += platform_fee;
-        line_items.push(json!({
+        let lease_currency = record_currency(&lease);
+        // Fees accrue when the period closes, so they use the end-date rate.
+        let Some(rate) = converter.rate(&lease_currency, end).await? else {
+            expense_warnings
+                .entry(format!("missing_fx_rate:{lease_currency}"))
+                .or_default()
+                .push(lease_id.clone());
+            continue;
+        };
+        let converted = Converted::at(
+            number_from_obj(&lease, "platform_fee"),
+            &lease_currency,
+            currency,
+            rate.as_ref(),
+        );
+        collection_fees += converted.amount;
+        line_items.push(converted.line_item(json!({
             "bucket": "collection_fees",
             "source_table": "leases",
             "source_id": lease_id,
             "kind": "platform_fee_per_paid_lease",
-            "amount_pyg": round2(platform_fee),
-        }));
+        })));
     }
 
     if !expense_warnings.is_empty() {
-        let missing = expense_warnings
-            .get("missing_fx_rate_to_pyg")
-            .map(|ids| ids.len())
-            .unwrap_or(0);
-        let unsupported = expense_warnings
-            .iter()
-            .filter(|(key, _)| key.starts_with("unsupported_currency:"))
-            .map(|(_, ids)| ids.len())
-            .sum::<usize>();
+        let missing = expense_warnings.values().map(Vec::len).sum::<usize>();
+        let mut currencies = expense_warnings
+            .keys()
+            .filter_map(|key| key.strip_prefix("missing_fx_rate:"))
+            .collect::<Vec<_>>();
+        currencies.sort_unstable();
 
         let mut samples: Vec<String> = Vec::new();
         for ids in expense_warnings.values() {
@@ -910,57 +1332,140 @@ async fn build_statement_breakdown(
             samples.join(", ")
         };
         return Err(AppError::BadRequest(format!(
-            "Cannot compute operating expenses in PYG for this period. missing_fx_rate_to_pyg={missing}, unsupported_currency={unsupported}. Fix the underlying expenses (sample ids: {sample_ids})."
+            "Cannot convert {missing} transactions into {currency} for this period: no exchange rate is available from {}. Backfill rates with POST /fx/backfill or record fx_rate_to_pyg on the expenses (sample ids: {sample_ids}).",
+            currencies.join(", ")
         )));
     }
 
     let gross_total = gross_revenue + lease_collections;
-    let net_payout =
-        gross_total - platform_fees - service_fees - collection_fees - operating_expenses;
+    let net_payout = gross_total
+        - platform_fees
+        - service_fees
+        - collection_fees
+        - management_fee_total
+        - operating_expenses;
 
     Ok(StatementBreakdown {
         gross_revenue: round2(gross_revenue),
         lease_collections: round2(lease_collections),
         service_fees: round2(service_fees),
         collection_fees: round2(collection_fees),
+        management_fees: round2(management_fee_total),
         platform_fees: round2(platform_fees),
         taxes_collected: round2(taxes_collected),
         operating_expenses: round2(operating_expenses),
         net_payout: round2(net_payout),
         line_items,
+        fx_rates: converter.into_rates_json(),
         reconciliation_gross_total: round2(gross_total),
         reconciliation_computed_net_payout: round2(net_payout),
     })
 }
 
+/// Store how the statement settles with the owner's balance and reserve;
+/// `net_payout` is what is left to pay after them.
+fn insert_balances(statement: &mut Map<String, Value>, balances: &StatementBalances) {
+    if let Value::Object(fields) = balances.to_json() {
+        statement.extend(fields);
+    }
+}
+
 fn parse_date(value: &str) -> AppResult<NaiveDate> {
     NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
         .map_err(|_| AppError::BadRequest("Invalid ISO date.".to_string()))
 }
 
-fn expense_amount_pyg(expense: &Map<String, Value>) -> (f64, Option<String>) {
-    let currency = value_str_from_obj(expense, "currency").to_ascii_uppercase();
-    let amount = number_from_obj(expense, "amount");
-    if currency == "PYG" {
-        return (amount, None);
+/// Per-statement FX lookups, memoized by (currency, day) so a month of
+/// transactions costs at most one lookup per currency and date.
+struct StatementFx<'a> {
+    pool: &'a sqlx::PgPool,
+    http: &'a reqwest::Client,
+    target: String,
+    rates: std::collections::BTreeMap<(String, NaiveDate), Option<FxRate>>,
+}
+
+impl<'a> StatementFx<'a> {
+    fn new(pool: &'a sqlx::PgPool, http: &'a reqwest::Client, target: &str) -> Self {
+        Self {
+            pool,
+            http,
+            target: target.to_string(),
+            rates: std::collections::BTreeMap::new(),
+        }
     }
-    if currency == "USD" {
-        let fx_rate = number_from_obj(expense, "fx_rate_to_pyg");
-        if fx_rate <= 0.0 {
-            return (0.0, Some("missing_fx_rate_to_pyg".to_string()));
+
+    /// `Ok(None)` when no rate exists, `Ok(Some(None))` when no conversion is
+    /// needed, otherwise the rate from `currency` into the target.
+    async fn rate(&mut self, currency: &str, date: NaiveDate) -> AppResult<Option<Option<FxRate>>> {
+        if currency == self.target {
+            return Ok(Some(None));
         }
-        return (amount * fx_rate, None);
+        let key = (currency.to_string(), date);
+        if let Some(cached) = self.rates.get(&key) {
+            return Ok(cached.clone().map(Some));
+        }
+        let rate = fx::rate_on(self.pool, self.http, currency, &self.target, date).await?;
+        self.rates.insert(key, rate.clone());
+        Ok(rate.map(Some))
+    }
+
+    fn into_rates_json(self) -> Vec<Value> {
+        self.rates
+            .into_iter()
+            .filter_map(|((_, date), rate)| rate.map(|rate| rate.to_json(date)))
+            .collect()
     }
-    (0.0, Some(format!("unsupported_currency:{currency}")))
 }
 
-fn generic_amount_pyg(record: &Map<String, Value>, amount_key: &str) -> (f64, Option<String>) {
-    let currency = value_str_from_obj(record, "currency").to_ascii_uppercase();
-    let amount = number_from_obj(record, amount_key);
-    if currency == "PYG" {
-        return (amount, None);
+/// An amount converted into the statement currency, keeping the original
+/// figures for the line item audit trail.
+struct Converted {
+    amount: f64,
+    currency: String,
+    original_amount: f64,
+    original_currency: String,
+    rate: Option<FxRate>,
+}
+
+impl Converted {
+    fn at(amount: f64, from: &str, to: &str, rate: Option<&FxRate>) -> Self {
+        Self {
+            amount: rate.map_or(amount, |rate| amount * rate.rate),
+            currency: to.to_string(),
+            original_amount: amount,
+            original_currency: from.to_string(),
+            rate: rate.cloned(),
+        }
+    }
+
+    fn line_item(&self, mut item: Value) -> Value {
+        if let Some(obj) = item.as_object_mut() {
+            obj.insert("amount".to_string(), json!(round2(self.amount)));
+            obj.insert("currency".to_string(), json!(self.currency));
+            if let Some(rate) = self.rate.as_ref() {
+                obj.insert(
+                    "original_amount".to_string(),
+                    json!(round2(self.original_amount)),
+                );
+                obj.insert(
+                    "original_currency".to_string(),
+                    json!(self.original_currency),
+                );
+                obj.insert("fx_rate".to_string(), json!(rate.rate));
+                obj.insert(
+                    "fx_rate_date".to_string(),
+                    json!(rate.rate_date.to_string()),
+                );
+            }
+        }
+        item
     }
-    (0.0, Some(format!("unsupported_currency:{currency}")))
+}
+
+/// Transaction currency of a row; rows predating multi-currency are PYG.
+fn record_currency(record: &Map<String, Value>) -> String {
+    fx::normalize_currency(&value_str_from_obj(record, "currency"))
+        .unwrap_or_else(|| "PYG".to_string())
 }
 
 fn fallback_str(value: Option<&Value>, default: &str) -> String {
@@ -983,6 +1488,7 @@ fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
 /// already have a statement for the period.
 pub async fn auto_generate_monthly_statements(
     pool: &sqlx::PgPool,
+    http: &reqwest::Client,
     org_id: &str,
     engine_mode: crate::config::WorkflowEngineMode,
 ) -> u32 {
@@ -1037,14 +1543,29 @@ pub async fn auto_generate_monthly_statements(
             continue;
         }
 
+        let currency =
+            match fx::reporting_currency(pool, org_id, Some(property_id), None, None).await {
+                Ok(currency) => currency,
+                Err(e) => {
+                    tracing::warn!(
+                        property_id,
+                        error = %e,
+                        "Failed to resolve statement currency"
+                    );
+                    continue;
+                }
+            };
+
         // Build the statement breakdown
         let breakdown = match build_statement_breakdown(
             pool,
+            http,
             org_id,
             &period_start_str,
             &period_end_str,
             Some(property_id),
             None,
+            &currency,
         )
         .await
         {
@@ -1058,6 +1579,27 @@ pub async fn auto_generate_monthly_statements(
                 continue;
             }
         };
+        let balances = match owner_reserves::statement_balances(
+            pool,
+            org_id,
+            Some(property_id),
+            None,
+            &period_start_str,
+            &currency,
+            breakdown.net_payout,
+        )
+        .await
+        {
+            Ok(balances) => balances,
+            Err(e) => {
+                tracing::warn!(
+                    property_id,
+                    error = %e,
+                    "Failed to settle owner balance"
+                );
+                continue;
+            }
+        };
 
         let mut statement = Map::new();
         statement.insert(
@@ -1072,6 +1614,7 @@ pub async fn auto_generate_monthly_statements(
             "period_start".to_string(),
             Value::String(period_start_str.clone()),
         );
+        statement.insert("currency".to_string(), Value::String(currency));
         statement.insert(
             "period_end".to_string(),
             Value::String(period_end_str.clone()),
@@ -1094,6 +1637,10 @@ pub async fn auto_generate_monthly_statements(
             "collection_fees".to_string(),
             json!(breakdown.collection_fees),
         );
+        statement.insert(
+            "management_fees".to_string(),
+            json!(breakdown.management_fees),
+        );
         statement.insert("platform_fees".to_string(), json!(breakdown.platform_fees));
         statement.insert(
             "taxes_collected".to_string(),
@@ -1103,7 +1650,7 @@ pub async fn auto_generate_monthly_statements(
             "operating_expenses".to_string(),
             json!(breakdown.operating_expenses),
         );
-        statement.insert("net_payout".to_string(), json!(breakdown.net_payout));
+        insert_balances(&mut statement, &balances);
         statement.insert("status".to_string(), Value::String("draft".to_string()));
 
         match create_row(pool, "owner_statements", &statement).await {
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use axum::{
     body::Bytes,
     extract::{Path, Query, State},
@@ -16,13 +18,20 @@ use crate::{
         clamp_limit_in_range, CreatePaymentInstructionInput, PaymentInstructionPath,
         PaymentInstructionsQuery, PaymentReferencePath,
     },
-    services::{audit::write_audit_log, reconciliation},
+    services::{
+        audit::write_audit_log,
+        owner_reserves,
+        payment_ledger::PaymentSource,
+        permissions, reconciliation,
+        webhook_verification::{
+            self, MercadoPagoVerifier, StripeVerifier, VerificationError, WebhookRequest,
+            WebhookVerifier,
+        },
+    },
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
-const PAYMENT_EDIT_ROLES: &[&str] = &["owner_admin", "operator", "accountant"];
-
 pub fn router() -> axum::Router<AppState> {
     axum::Router::new()
         .route(
@@ -67,7 +76,7 @@ async fn create_payment_link(
 
     let collection = get_row(pool, "collection_records", &path.collection_id, "id").await?;
     let org_id = value_str(&collection, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, PAYMENT_EDIT_ROLES).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::PAYMENTS_EDIT).await?;
 
     let lease_id = value_str(&collection, "lease_id");
     let amount = collection
@@ -381,6 +390,7 @@ async fn create_stripe_checkout(
         &reference_code,
         &tenant_name,
         &org_name,
+        &format!("/pay/{reference_code}"),
     )
     .await
     .map_err(AppError::Dependency)?;
@@ -417,91 +427,119 @@ async fn stripe_webhook(
     headers: HeaderMap,
     body: Bytes,
 ) -> AppResult<impl IntoResponse> {
-    let pool = db_pool(&state)?;
-
-    let body_str = String::from_utf8(body.to_vec())
-        .map_err(|_| AppError::BadRequest("Invalid webhook body encoding.".to_string()))?;
-
-    // Verify Stripe signature if webhook secret is configured
-    if let Some(secret) = state
+    // Unsigned events are never trusted: without the webhook secret anyone
+    // could post a completed checkout and mark a payment as paid.
+    let Some(secret) = state
         .config
         .stripe_webhook_secret
         .as_deref()
         .filter(|s| !s.is_empty())
-    {
-        let sig_header = headers
-            .get("stripe-signature")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or_default();
-
-        if !crate::services::payments::verify_stripe_signature(&body_str, sig_header, secret) {
-            tracing::warn!("Stripe webhook signature verification failed");
-            return Err(AppError::Unauthorized(
-                "Invalid webhook signature.".to_string(),
-            ));
-        }
-    }
+    else {
+        tracing::error!("STRIPE_WEBHOOK_SECRET is not configured; rejecting Stripe webhook");
+        return Err(AppError::Unauthorized(
+            "Webhook signature verification is not configured.".to_string(),
+        ));
+    };
+    let query = HashMap::new();
+    let request = WebhookRequest {
+        headers: &headers,
+        query: &query,
+        body: &body,
+    };
+    let verifier = StripeVerifier::default();
+    let (verified, _) =
+        webhook_verification::verify_with_any(&verifier, &request, &[secret.to_string()])
+            .map_err(|error| webhook_verification::rejected(verifier.provider(), &error))?;
 
-    let payload: Value = serde_json::from_str(&body_str)
+    let pool = db_pool(&state)?;
+    let payload: Value = serde_json::from_slice(&body)
         .map_err(|_| AppError::BadRequest("Invalid webhook JSON.".to_string()))?;
 
+    webhook_verification::process_once(
+        pool,
+        verifier.provider(),
+        &verified.event_id,
+        None,
+        process_stripe_event(&state, pool, &payload),
+    )
+    .await?;
+
+    Ok(axum::http::StatusCode::OK)
+}
+
+/// Apply a verified Stripe event. Errors leave the event to Stripe's retry.
+async fn process_stripe_event(
+    state: &AppState,
+    pool: &sqlx::PgPool,
+    payload: &Value,
+) -> AppResult<()> {
     let event_type = payload
         .get("type")
         .and_then(Value::as_str)
         .unwrap_or_default();
 
-    match event_type {
-        "checkout.session.completed" => {
-            let session = payload.get("data").and_then(|d| d.get("object"));
-            if let Some(session) = session {
-                let session_id = session
-                    .get("id")
-                    .and_then(Value::as_str)
-                    .unwrap_or_default();
-                let reference_code = session
-                    .get("metadata")
-                    .and_then(|m| m.get("reference_code"))
-                    .and_then(Value::as_str)
-                    .unwrap_or_default();
-
-                if !reference_code.is_empty() {
-                    if let Ok(instruction) = get_row(
-                        pool,
-                        "payment_instructions",
-                        reference_code,
-                        "reference_code",
-                    )
-                    .await
-                    {
-                        let amount = instruction
-                            .as_object()
-                            .and_then(|o| o.get("amount"))
-                            .and_then(|v| v.as_f64())
-                            .unwrap_or(0.0);
-
-                        // Reconcile payment (handles exact, partial, overpayment)
-                        reconciliation::reconcile_payment(
-                            pool,
-                            &instruction,
-                            amount,
-                            "card",
-                            &format!("stripe:{session_id}"),
-                            state.config.workflow_engine_mode,
-                        )
-                        .await;
-
-                        // Queue WhatsApp receipt
-                        reconciliation::queue_payment_receipt(pool, &instruction, amount).await;
-                    }
-                }
-            }
+    if event_type != "checkout.session.completed" {
+        tracing::debug!("Unhandled Stripe event type: {event_type}");
+        return Ok(());
+    }
+    let Some(session) = payload.get("data").and_then(|d| d.get("object")) else {
+        return Ok(());
+    };
+    let session_id = session
+        .get("id")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let reference_code = session
+        .get("metadata")
+        .and_then(|m| m.get("reference_code"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if reference_code.is_empty() {
+        return Ok(());
+    }
+
+    match get_row(
+        pool,
+        "payment_instructions",
+        reference_code,
+        "reference_code",
+    )
+    .await
+    {
+        Ok(instruction) => {
+            let amount = instruction
+                .as_object()
+                .and_then(|o| o.get("amount"))
+                .and_then(|v| v.as_f64())
+                .unwrap_or(0.0);
+
+            // Reconcile payment (handles exact, partial, overpayment)
+            reconciliation::reconcile_payment(
+                pool,
+                &instruction,
+                amount,
+                PaymentSource::Stripe,
+                &format!("stripe:{session_id}"),
+                state.config.workflow_engine_mode,
+            )
+            .await;
+
+            // Queue WhatsApp receipt
+            reconciliation::queue_payment_receipt(state, pool, &instruction, amount).await;
         }
-        _ => {
-            tracing::debug!("Unhandled Stripe event type: {event_type}");
+        Err(AppError::NotFound(_)) => {
+            owner_reserves::settle_contribution_by_reference(
+                pool,
+                reference_code,
+                None,
+                &format!("stripe:{session_id}"),
+            )
+            .await?;
         }
+        Err(error) => return Err(error),
     }
 
-    Ok(axum::http::StatusCode::OK)
+    Ok(())
 }
 
 /// Create a Mercado Pago checkout for a public payment (PYG).
@@ -594,10 +632,15 @@ async fn create_mercado_pago_checkout(
 /// Mercado Pago webhook handler — processes payment notifications.
 async fn mercado_pago_webhook(
     State(state): State<AppState>,
-    Json(payload): Json<Value>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    body: Bytes,
 ) -> AppResult<impl IntoResponse> {
     let pool = db_pool(&state)?;
 
+    let payload: Value = serde_json::from_slice(&body)
+        .map_err(|_| AppError::BadRequest("Invalid webhook JSON.".to_string()))?;
+
     let topic = payload
         .get("type")
         .or_else(|| payload.get("topic"))
@@ -626,8 +669,8 @@ async fn mercado_pago_webhook(
     // by checking external_reference in our payment_instructions
     // First, fetch the payment from MP API to get the external_reference
     // We'll try all orgs with MP configured (typically just one)
-    let org_rows: Vec<(String, String)> = sqlx::query_as(
-        "SELECT organization_id::text, mercado_pago_access_token
+    let org_rows: Vec<(String, String, Option<String>)> = sqlx::query_as(
+        "SELECT organization_id::text, mercado_pago_access_token, mercado_pago_webhook_secret
          FROM integrations
          WHERE mercado_pago_access_token IS NOT NULL
          LIMIT 10",
@@ -636,7 +679,51 @@ async fn mercado_pago_webhook(
     .await
     .unwrap_or_default();
 
-    for (_org_id, access_token) in &org_rows {
+    let request = WebhookRequest {
+        headers: &headers,
+        query: &query,
+        body: &body,
+    };
+    let verifier = MercadoPagoVerifier::default();
+    match mercado_pago_targets(&request, org_rows) {
+        Ok(MercadoPagoTargets::Verified {
+            org_id,
+            access_token,
+            event_id,
+        }) => {
+            let orgs = [(org_id.clone(), access_token)];
+            webhook_verification::process_once(
+                pool,
+                verifier.provider(),
+                &event_id,
+                Some(&org_id),
+                process_mercado_pago_payment(&state, pool, &orgs, &payment_id_str),
+            )
+            .await?;
+        }
+        Ok(MercadoPagoTargets::Legacy(orgs)) => {
+            tracing::warn!(
+                organizations = orgs.len(),
+                "Processing unsigned Mercado Pago webhook for organizations without a webhook secret; unsigned notifications are deprecated"
+            );
+            process_mercado_pago_payment(&state, pool, &orgs, &payment_id_str).await?;
+        }
+        Err(error) => return Err(webhook_verification::rejected(verifier.provider(), &error)),
+    }
+
+    Ok(axum::http::StatusCode::OK)
+}
+
+/// Fetch the payment from each candidate org's account and apply it to the
+/// payment instruction or owner contribution it references. With a single
+/// (signed) target org a failed lookup is an error so Mercado Pago retries.
+async fn process_mercado_pago_payment(
+    state: &AppState,
+    pool: &sqlx::PgPool,
+    orgs: &[(String, String)],
+    payment_id_str: &str,
+) -> AppResult<()> {
+    for (_org_id, access_token) in orgs {
         if access_token.is_empty() {
             continue;
         }
@@ -644,11 +731,12 @@ async fn mercado_pago_webhook(
         let payment_info = match crate::services::mercado_pago::get_mp_payment(
             &state.http_client,
             access_token,
-            &payment_id_str,
+            payment_id_str,
         )
         .await
         {
             Ok(info) => info,
+            Err(error) if orgs.len() == 1 => return Err(AppError::Dependency(error)),
             Err(_) => continue,
         };
 
@@ -666,39 +754,109 @@ async fn mercado_pago_webhook(
         }
 
         // Find the payment instruction by reference code
-        if let Ok(instruction) =
-            get_row(pool, "payment_instructions", external_ref, "reference_code").await
-        {
-            let mp_amount = payment_info
-                .get("transaction_amount")
-                .and_then(|v| v.as_f64())
-                .or_else(|| {
-                    instruction
-                        .as_object()
-                        .and_then(|o| o.get("amount"))
-                        .and_then(|v| v.as_f64())
-                })
-                .unwrap_or(0.0);
+        match get_row(pool, "payment_instructions", external_ref, "reference_code").await {
+            Ok(instruction) => {
+                let mp_amount = payment_info
+                    .get("transaction_amount")
+                    .and_then(|v| v.as_f64())
+                    .or_else(|| {
+                        instruction
+                            .as_object()
+                            .and_then(|o| o.get("amount"))
+                            .and_then(|v| v.as_f64())
+                    })
+                    .unwrap_or(0.0);
 
-            // Reconcile payment (handles exact, partial, overpayment)
-            reconciliation::reconcile_payment(
-                pool,
-                &instruction,
-                mp_amount,
-                "mercado_pago",
-                &format!("mp:{payment_id_str}"),
-                state.config.workflow_engine_mode,
-            )
-            .await;
+                // Reconcile payment (handles exact, partial, overpayment)
+                reconciliation::reconcile_payment(
+                    pool,
+                    &instruction,
+                    mp_amount,
+                    PaymentSource::MercadoPago,
+                    &format!("mp:{payment_id_str}"),
+                    state.config.workflow_engine_mode,
+                )
+                .await;
 
-            // Queue WhatsApp receipt
-            reconciliation::queue_payment_receipt(pool, &instruction, mp_amount).await;
+                // Queue WhatsApp receipt
+                reconciliation::queue_payment_receipt(state, pool, &instruction, mp_amount).await;
+
+                break;
+            }
+            Err(AppError::NotFound(_)) => {}
+            Err(error) => return Err(error),
+        }
 
+        // Otherwise it may pay an owner contribution
+        if owner_reserves::settle_contribution_by_reference(
+            pool,
+            external_ref,
+            payment_info
+                .get("transaction_amount")
+                .and_then(Value::as_f64),
+            &format!("mp:{payment_id_str}"),
+        )
+        .await?
+        {
             break;
         }
     }
 
-    Ok(axum::http::StatusCode::OK)
+    Ok(())
+}
+
+/// Who a Mercado Pago notification is processed for.
+#[derive(Debug, PartialEq)]
+enum MercadoPagoTargets {
+    /// Signed with this org's webhook secret.
+    Verified {
+        org_id: String,
+        access_token: String,
+        event_id: String,
+    },
+    /// Unsigned; goes to the legacy orgs that have no webhook secret.
+    Legacy(Vec<(String, String)>),
+}
+
+/// Orgs with a webhook secret only accept notifications signed with it, and a
+/// present-but-invalid signature is rejected outright. Unsigned notifications
+/// only reach orgs with no secret configured. `orgs` holds
+/// `(organization_id, access_token, webhook_secret)`.
+fn mercado_pago_targets(
+    request: &WebhookRequest<'_>,
+    orgs: Vec<(String, String, Option<String>)>,
+) -> Result<MercadoPagoTargets, VerificationError> {
+    let (signed, unsigned): (Vec<_>, Vec<_>) = orgs
+        .into_iter()
+        .partition(|(_, _, secret)| secret.as_deref().is_some_and(|s| !s.is_empty()));
+    let secrets: Vec<String> = signed
+        .iter()
+        .map(|(_, _, secret)| secret.clone().unwrap_or_default())
+        .collect();
+    let verification = if signed.is_empty() {
+        Err(VerificationError::MissingSignature)
+    } else {
+        webhook_verification::verify_with_any(&MercadoPagoVerifier::default(), request, &secrets)
+    };
+    match verification {
+        Ok((verified, index)) => {
+            let (org_id, access_token, _) = signed[index].clone();
+            Ok(MercadoPagoTargets::Verified {
+                org_id,
+                access_token,
+                event_id: verified.event_id,
+            })
+        }
+        Err(VerificationError::MissingSignature) if !unsigned.is_empty() => {
+            Ok(MercadoPagoTargets::Legacy(
+                unsigned
+                    .into_iter()
+                    .map(|(org_id, token, _)| (org_id, token))
+                    .collect(),
+            ))
+        }
+        Err(error) => Err(error),
+    }
 }
 
 fn non_empty_opt(value: Option<&str>) -> Option<String> {
@@ -707,3 +865,156 @@ fn non_empty_opt(value: Option<&str>) -> Option<String> {
         .filter(|item| !item.is_empty())
         .map(ToOwned::to_owned)
 }
+
+#[cfg(test)]
+mod tests {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    use super::*;
+
+    fn org(id: &str, secret: Option<&str>) -> (String, String, Option<String>) {
+        (
+            id.to_string(),
+            format!("token-{id}"),
+            secret.map(str::to_string),
+        )
+    }
+
+    fn orgs() -> Vec<(String, String, Option<String>)> {
+        vec![
+            org("signed-a", Some("secret-a")),
+            org("legacy", None),
+            org("signed-b", Some("secret-b")),
+        ]
+    }
+
+    fn hmac_hex(secret: &str, message: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac key");
+        mac.update(message);
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>()
+    }
+
+    fn signature(secret: &str) -> String {
+        let ts = chrono::Utc::now().timestamp();
+        let manifest = format!("id:123;request-id:req-1;ts:{ts};");
+        format!("ts={ts},v1={}", hmac_hex(secret, manifest.as_bytes()))
+    }
+
+    fn headers(signature: Option<String>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-request-id", "req-1".parse().expect("header"));
+        if let Some(signature) = signature {
+            headers.insert("x-signature", signature.parse().expect("header"));
+        }
+        headers
+    }
+
+    fn targets(
+        headers: &HeaderMap,
+        orgs: Vec<(String, String, Option<String>)>,
+    ) -> Result<MercadoPagoTargets, VerificationError> {
+        let query = HashMap::new();
+        let body = br#"{"type":"payment","data":{"id":"123"}}"#;
+        let request = WebhookRequest {
+            headers,
+            query: &query,
+            body,
+        };
+        mercado_pago_targets(&request, orgs)
+    }
+
+    #[test]
+    fn signed_notifications_go_to_the_matching_org() {
+        let result = targets(&headers(Some(signature("secret-b"))), orgs()).unwrap();
+        let MercadoPagoTargets::Verified {
+            org_id,
+            access_token,
+            ..
+        } = result
+        else {
+            panic!("expected a verified target, got {result:?}");
+        };
+        assert_eq!(
+            (org_id.as_str(), access_token.as_str()),
+            ("signed-b", "token-signed-b")
+        );
+    }
+
+    #[test]
+    fn invalid_signatures_are_rejected_even_with_legacy_orgs() {
+        let result = targets(&headers(Some(signature("wrong"))), orgs());
+        assert_eq!(result, Err(VerificationError::Mismatch));
+        let result = targets(&headers(Some("ts=1".to_string())), orgs());
+        assert_eq!(result, Err(VerificationError::Malformed));
+    }
+
+    #[test]
+    fn unsigned_notifications_only_reach_orgs_without_a_secret() {
+        assert_eq!(
+            targets(&headers(None), orgs()),
+            Ok(MercadoPagoTargets::Legacy(vec![(
+                "legacy".to_string(),
+                "token-legacy".to_string()
+            )]))
+        );
+        let signed_only = vec![org("signed-a", Some("secret-a"))];
+        assert_eq!(
+            targets(&headers(None), signed_only),
+            Err(VerificationError::MissingSignature)
+        );
+    }
+
+    fn stripe_state(webhook_secret: Option<&str>) -> AppState {
+        let mut state = crate::test_support::test_state(None);
+        std::sync::Arc::make_mut(&mut state.config).stripe_webhook_secret =
+            webhook_secret.map(str::to_string);
+        state
+    }
+
+    fn stripe_headers(secret: &str, body: &[u8]) -> HeaderMap {
+        let timestamp = chrono::Utc::now().timestamp();
+        let mut message = format!("{timestamp}.").into_bytes();
+        message.extend_from_slice(body);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "stripe-signature",
+            format!("t={timestamp},v1={}", hmac_hex(secret, &message))
+                .parse()
+                .expect("header"),
+        );
+        headers
+    }
+
+    #[tokio::test]
+    async fn stripe_webhook_requires_a_configured_secret() {
+        let body = Bytes::from_static(
+            br#"{"id":"evt_1","type":"checkout.session.completed","data":{"object":{}}}"#,
+        );
+
+        let unconfigured =
+            stripe_webhook(State(stripe_state(None)), HeaderMap::new(), body.clone()).await;
+        assert!(matches!(unconfigured, Err(AppError::Unauthorized(_))));
+
+        let forged = stripe_webhook(
+            State(stripe_state(Some("whsec_test"))),
+            stripe_headers("whsec_other", &body),
+            body.clone(),
+        )
+        .await;
+        assert!(matches!(forged, Err(AppError::Unauthorized(_))));
+
+        // A valid signature gets past verification to the (missing) database.
+        let signed = stripe_webhook(
+            State(stripe_state(Some("whsec_test"))),
+            stripe_headers("whsec_test", &body),
+            body,
+        )
+        .await;
+        assert!(matches!(signed, Err(AppError::Dependency(_))));
+    }
+}
@@ -10,7 +10,12 @@ use crate::{
     error::{AppError, AppResult},
     repository::table_service::{get_row, list_rows, update_row},
     schemas::clamp_limit_in_range,
-    services::audit::write_audit_log,
+    services::{
+        audit::write_audit_log,
+        scheduler::{spawn_manual_run, ORG_SCOPED_JOBS},
+        scheduler_leases::list_leases,
+        scheduler_runs::{get_run, list_runs, RunFilters},
+    },
     state::AppState,
 };
 
@@ -22,6 +27,22 @@ pub fn router() -> axum::Router<AppState> {
             axum::routing::post(suspend_org),
         )
         .route("/platform/stats", axum::routing::get(platform_stats))
+        .route(
+            "/platform/scheduler/jobs",
+            axum::routing::get(list_scheduler_jobs),
+        )
+        .route(
+            "/platform/scheduler/jobs/{job_name}/run",
+            axum::routing::post(run_scheduler_job),
+        )
+        .route(
+            "/platform/scheduler/runs",
+            axum::routing::get(list_scheduler_runs),
+        )
+        .route(
+            "/platform/scheduler/runs/{run_id}",
+            axum::routing::get(get_scheduler_run),
+        )
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -38,6 +59,32 @@ struct PlatformOrgPath {
     org_id: String,
 }
 
+#[derive(Debug, serde::Deserialize)]
+struct SchedulerRunsQuery {
+    job_name: Option<String>,
+    status: Option<String>,
+    organization_id: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: i64,
+    #[serde(default)]
+    offset: i64,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SchedulerJobPath {
+    job_name: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SchedulerRunPath {
+    run_id: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RunSchedulerJobInput {
+    organization_id: String,
+}
+
 /// Verify the caller is a platform admin.
 async fn require_platform_admin(state: &AppState, user_id: &str) -> AppResult<()> {
     let pool = state
@@ -206,6 +253,123 @@ async fn platform_stats(
     })))
 }
 
+/// Known scheduler jobs with their lease state (platform admin only).
+async fn list_scheduler_jobs(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    require_platform_admin(&state, &user_id).await?;
+    let pool = db_pool(&state)?;
+
+    let leases = list_leases(pool)
+        .await
+        .map_err(|error| AppError::from_database_error(&error, "Database request failed."))?;
+
+    let mut jobs: Vec<Value> = leases
+        .into_iter()
+        .map(|lease| {
+            let job_name = val_str(&lease, "job_name");
+            json!({
+                "job_name": job_name,
+                "org_scoped": ORG_SCOPED_JOBS.contains(&job_name.as_str()),
+                "lease": lease,
+            })
+        })
+        .collect();
+    for job_name in ORG_SCOPED_JOBS {
+        if !jobs.iter().any(|job| val_str(job, "job_name") == *job_name) {
+            jobs.push(json!({ "job_name": job_name, "org_scoped": true, "lease": Value::Null }));
+        }
+    }
+
+    Ok(Json(json!({ "data": jobs })))
+}
+
+/// Trigger one org-scoped scheduler job on demand (platform admin only).
+async fn run_scheduler_job(
+    State(state): State<AppState>,
+    Path(path): Path<SchedulerJobPath>,
+    headers: HeaderMap,
+    Json(payload): Json<RunSchedulerJobInput>,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    require_platform_admin(&state, &user_id).await?;
+    let pool = db_pool(&state)?;
+
+    if !ORG_SCOPED_JOBS.contains(&path.job_name.as_str()) {
+        return Err(AppError::BadRequest(format!(
+            "Job '{}' cannot be run on demand. Supported jobs: {}.",
+            path.job_name,
+            ORG_SCOPED_JOBS.join(", ")
+        )));
+    }
+    get_row(pool, "organizations", &payload.organization_id, "id").await?;
+
+    let run = spawn_manual_run(&state, &path.job_name, &payload.organization_id, &user_id)
+        .await
+        .map_err(|error| AppError::from_database_error_message(&error, "Could not start run."))?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        "run_scheduler_job",
+        "scheduler_runs",
+        run.get("id").and_then(Value::as_str),
+        None,
+        Some(run.clone()),
+    )
+    .await;
+
+    Ok(Json(run))
+}
+
+/// Scheduler run history, newest first (platform admin only).
+async fn list_scheduler_runs(
+    State(state): State<AppState>,
+    Query(query): Query<SchedulerRunsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    require_platform_admin(&state, &user_id).await?;
+    let pool = db_pool(&state)?;
+
+    let filters = RunFilters {
+        job_name: non_empty(query.job_name.as_deref()),
+        status: non_empty(query.status.as_deref()),
+        organization_id: non_empty(query.organization_id.as_deref()),
+        limit: clamp_limit_in_range(query.limit, 1, 500),
+        offset: query.offset.max(0),
+    };
+    let runs = list_runs(pool, &filters)
+        .await
+        .map_err(|error| AppError::from_database_error(&error, "Database request failed."))?;
+
+    Ok(Json(json!({ "data": runs })))
+}
+
+/// A single scheduler run (platform admin only).
+async fn get_scheduler_run(
+    State(state): State<AppState>,
+    Path(path): Path<SchedulerRunPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    require_platform_admin(&state, &user_id).await?;
+    let pool = db_pool(&state)?;
+
+    get_run(pool, &path.run_id)
+        .await
+        .map_err(|error| AppError::from_database_error(&error, "Database request failed."))?
+        .map(Json)
+        .ok_or_else(|| AppError::NotFound("Scheduler run not found.".to_string()))
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
 fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
     state.db_pool.as_ref().ok_or_else(|| {
         AppError::Dependency("Database is not configured. Set DATABASE_URL.".to_string())
@@ -18,14 +18,13 @@ use crate::{
     },
     services::{
         audit::write_audit_log,
+        permissions,
         pricing::{compute_pricing_totals, normalize_fee_lines},
     },
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
-const PRICING_EDIT_ROLES: &[&str] = &["owner_admin", "operator", "accountant"];
-
 pub fn router() -> axum::Router<AppState> {
     axum::Router::new()
         .route(
@@ -93,11 +92,11 @@ async fn create_pricing_template(
     Json(payload): Json<CreatePricingTemplateInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
+    assert_permission(
         &state,
         &user_id,
         &payload.organization_id,
-        PRICING_EDIT_ROLES,
+        permissions::PRICING_EDIT,
     )
     .await?;
     let pool = db_pool(&state)?;
@@ -174,7 +173,7 @@ async fn update_pricing_template(
 
     let record = get_row(pool, "pricing_templates", &path.template_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, PRICING_EDIT_ROLES).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::PRICING_EDIT).await?;
 
     let mut patch = remove_nulls(serialize_to_map(&payload));
     patch.remove("lines");
@@ -450,7 +449,7 @@ async fn update_pricing_recommendation(
     Json(payload): Json<UpdateRecommendationInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &payload.org_id, PRICING_EDIT_ROLES).await?;
+    assert_permission(&state, &user_id, &payload.org_id, permissions::PRICING_EDIT).await?;
     let pool = db_pool(&state)?;
 
     let valid_statuses = ["approved", "dismissed", "applied"];
@@ -633,7 +632,7 @@ async fn create_strategy(
     Json(payload): Json<CreateStrategyInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &payload.org_id, PRICING_EDIT_ROLES).await?;
+    assert_permission(&state, &user_id, &payload.org_id, permissions::PRICING_EDIT).await?;
     let pool = db_pool(&state)?;
 
     let presets = strategy_presets(&payload.strategy)?;
@@ -749,7 +748,7 @@ async fn update_strategy(
     Json(payload): Json<UpdateStrategyInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &payload.org_id, PRICING_EDIT_ROLES).await?;
+    assert_permission(&state, &user_id, &payload.org_id, permissions::PRICING_EDIT).await?;
     let pool = db_pool(&state)?;
 
     // Build SET clauses dynamically
@@ -22,10 +22,14 @@ use crate::{
     services::{
         audit::write_audit_log,
         enrichment::enrich_units,
+        permissions,
         plan_limits::{check_plan_limit, PlanResource},
     },
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{
+        assert_org_member, assert_permission, assert_property_in_scope, assert_property_permission,
+        property_scope, scope_property_filter,
+    },
 };
 
 pub fn router() -> axum::Router<AppState> {
@@ -69,10 +73,13 @@ async fn list_properties(
 ) -> AppResult<Json<Value>> {
     let org_id = query.org_id.to_string();
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &org_id).await?;
     let pool = db_pool(&state)?;
 
     let mut filters = Map::new();
+    if !scope_property_filter(&state, &org_id, &membership, &mut filters, "id").await? {
+        return Ok(Json(json!({ "data": [] })));
+    }
     filters.insert("organization_id".to_string(), Value::String(org_id));
     if let Some(status) = query
         .status
@@ -123,7 +130,13 @@ async fn create_property(
     Json(payload): Json<CreatePropertyInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &payload.organization_id, &["owner_admin"]).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::PROPERTIES_MANAGE,
+    )
+    .await?;
     let pool = db_pool(&state)?;
 
     check_plan_limit(pool, &payload.organization_id, PlanResource::Property).await?;
@@ -156,7 +169,8 @@ async fn get_property(
     let pool = db_pool(&state)?;
     let record = get_row(pool, "properties", &path.property_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_member(&state, &user_id, &org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &org_id).await?;
+    assert_property_in_scope(&state, &org_id, &membership, Some(&path.property_id)).await?;
     Ok(Json(record))
 }
 
@@ -170,7 +184,8 @@ async fn get_property_hierarchy(
 
     let property = get_row(pool, "properties", &path.property_id, "id").await?;
     let org_id = value_str(&property, "organization_id");
-    assert_org_member(&state, &user_id, &org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &org_id).await?;
+    assert_property_in_scope(&state, &org_id, &membership, Some(&path.property_id)).await?;
 
     let filters = json_map(&[
         ("organization_id", Value::String(org_id.clone())),
@@ -223,7 +238,14 @@ async fn update_property(
 
     let record = get_row(pool, "properties", &path.property_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin"]).await?;
+    assert_property_permission(
+        &state,
+        &user_id,
+        &org_id,
+        permissions::PROPERTIES_MANAGE,
+        &path.property_id,
+    )
+    .await?;
     let mut patch = remove_nulls(serialize_to_map(&payload));
     normalize_property_payload_for_write(&mut patch);
     let updated = update_row(pool, "properties", &path.property_id, &patch, "id").await?;
@@ -251,7 +273,14 @@ async fn delete_property(
     let pool = db_pool(&state)?;
     let record = get_row(pool, "properties", &path.property_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin"]).await?;
+    assert_property_permission(
+        &state,
+        &user_id,
+        &org_id,
+        permissions::PROPERTIES_MANAGE,
+        &path.property_id,
+    )
+    .await?;
     let deleted = delete_row(pool, "properties", &path.property_id, "id").await?;
     write_audit_log(
         state.db_pool.as_ref(),
@@ -274,7 +303,7 @@ async fn list_units(
 ) -> AppResult<Json<Value>> {
     let org_id = query.org_id.to_string();
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &org_id).await?;
     let pool = db_pool(&state)?;
 
     let mut filters = Map::new();
@@ -315,6 +344,9 @@ async fn list_units(
     if let Some(floor_level) = query.floor_level {
         filters.insert("floor_level".to_string(), json!(floor_level));
     }
+    if !scope_property_filter(&state, &org_id, &membership, &mut filters, "property_id").await? {
+        return Ok(Json(json!({ "data": [] })));
+    }
     let rows = list_rows(
         pool,
         "units",
@@ -339,7 +371,8 @@ async fn get_property_twin(
 
     let property = get_row(pool, "properties", &path.property_id, "id").await?;
     let org_id = value_str(&property, "organization_id");
-    assert_org_member(&state, &user_id, &org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &org_id).await?;
+    assert_property_in_scope(&state, &org_id, &membership, Some(&path.property_id)).await?;
 
     let twin =
         crate::services::digital_twin::get_property_state(pool, &org_id, &path.property_id).await?;
@@ -356,7 +389,21 @@ async fn bulk_update_units(
     Json(payload): Json<BulkUpdateUnitsInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &payload.organization_id, &["owner_admin"]).await?;
+    let membership = assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::PROPERTIES_MANAGE,
+    )
+    .await?;
+    if property_scope(&state, &payload.organization_id, &membership)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::Forbidden(
+            "Forbidden: bulk unit updates require access to every property.".to_string(),
+        ));
+    }
     let pool = db_pool(&state)?;
 
     let filters = build_bulk_unit_filters(&payload.organization_id, &payload.filters)?;
@@ -492,7 +539,14 @@ async fn create_unit(
     Json(payload): Json<CreateUnitInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &payload.organization_id, &["owner_admin"]).await?;
+    assert_property_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::PROPERTIES_MANAGE,
+        &payload.property_id,
+    )
+    .await?;
     let pool = db_pool(&state)?;
 
     check_plan_limit(pool, &payload.organization_id, PlanResource::Unit).await?;
@@ -604,7 +658,14 @@ async fn get_unit(
     let pool = db_pool(&state)?;
     let record = get_row(pool, "units", &path.unit_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_member(&state, &user_id, &org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &org_id).await?;
+    assert_property_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&value_str(&record, "property_id")),
+    )
+    .await?;
     let mut enriched = enrich_units(&state, pool, vec![record], &org_id).await?;
     let first = enriched.pop().unwrap_or_else(|| Value::Object(Map::new()));
     Ok(Json(first))
@@ -621,7 +682,14 @@ async fn update_unit(
 
     let record = get_row(pool, "units", &path.unit_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin"]).await?;
+    assert_property_permission(
+        &state,
+        &user_id,
+        &org_id,
+        permissions::PROPERTIES_MANAGE,
+        &value_str(&record, "property_id"),
+    )
+    .await?;
     let mut patch = remove_nulls(serialize_to_map(&payload));
     normalize_unit_payload_for_write(&mut patch);
     let updated = update_row(pool, "units", &path.unit_id, &patch, "id").await?;
@@ -657,7 +725,14 @@ async fn delete_unit(
 
     let record = get_row(pool, "units", &path.unit_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin"]).await?;
+    assert_property_permission(
+        &state,
+        &user_id,
+        &org_id,
+        permissions::PROPERTIES_MANAGE,
+        &value_str(&record, "property_id"),
+    )
+    .await?;
     let deleted = delete_row(pool, "units", &path.unit_id, "id").await?;
 
     write_audit_log(
@@ -1495,7 +1570,13 @@ async fn bulk_import_properties(
     Json(payload): Json<BulkImportPropertiesInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &payload.organization_id, &["owner_admin"]).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::PROPERTIES_MANAGE,
+    )
+    .await?;
     let pool = db_pool(&state)?;
 
     let mut results: Vec<Value> = Vec::new();
@@ -0,0 +1,655 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
+};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    auth::require_user_id,
+    cache::org_key,
+    error::{AppError, AppResult},
+    repository::table_service::{
+        count_rows, create_row, delete_row, get_row, list_rows, update_row,
+    },
+    schemas::{
+        AddPropertyGroupMemberInput, CreatePropertyGroupInput, PropertyGroupMemberPath,
+        PropertyGroupPath, PropertyGroupsQuery, UpdatePropertyGroupInput,
+    },
+    services::{audit::write_audit_log, permissions},
+    state::AppState,
+    tenancy::{assert_org_member, assert_permission, property_scope},
+};
+
+pub fn router() -> axum::Router<AppState> {
+    axum::Router::new()
+        .route(
+            "/property-groups",
+            axum::routing::get(list_property_groups).post(create_property_group),
+        )
+        .route(
+            "/property-groups/{group_id}",
+            axum::routing::get(get_property_group)
+                .patch(update_property_group)
+                .delete(delete_property_group),
+        )
+        .route(
+            "/property-groups/{group_id}/members",
+            axum::routing::get(list_property_group_members).post(add_property_group_member),
+        )
+        .route(
+            "/property-groups/{group_id}/members/{user_id}",
+            axum::routing::delete(remove_property_group_member),
+        )
+}
+
+// ── Property groups ─────────────────────────────────────────────────
+
+async fn list_property_groups(
+    State(state): State<AppState>,
+    Query(query): Query<PropertyGroupsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(query.org_id.clone()),
+    );
+    let rows = list_rows(
+        pool,
+        "property_groups",
+        Some(&filters),
+        500,
+        0,
+        "name",
+        true,
+    )
+    .await?;
+
+    Ok(Json(json!({ "data": rows })))
+}
+
+async fn create_property_group(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreatePropertyGroupInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_group_editor(&state, &user_id, &payload.organization_id).await?;
+    let pool = db_pool(&state)?;
+
+    let name = non_empty_opt(Some(&payload.name))
+        .ok_or_else(|| AppError::BadRequest("name is required.".to_string()))?;
+    let property_ids =
+        validate_properties(pool, &payload.organization_id, &payload.property_ids).await?;
+
+    let mut record = Map::new();
+    record.insert(
+        "organization_id".to_string(),
+        Value::String(payload.organization_id.clone()),
+    );
+    record.insert("name".to_string(), Value::String(name));
+    if let Some(description) = non_empty_opt(payload.description.as_deref()) {
+        record.insert("description".to_string(), Value::String(description));
+    }
+    record.insert("property_ids".to_string(), json!(property_ids));
+    let created = create_row(pool, "property_groups", &record).await?;
+    let entity_id = value_str(&created, "id");
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        "create",
+        "property_groups",
+        Some(&entity_id),
+        None,
+        Some(created.clone()),
+    )
+    .await;
+
+    Ok((axum::http::StatusCode::CREATED, Json(created)))
+}
+
+async fn get_property_group(
+    State(state): State<AppState>,
+    Path(path): Path<PropertyGroupPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let record = get_row(pool, "property_groups", &path.group_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_org_member(&state, &user_id, &org_id).await?;
+    Ok(Json(record))
+}
+
+async fn update_property_group(
+    State(state): State<AppState>,
+    Path(path): Path<PropertyGroupPath>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdatePropertyGroupInput>,
+) -> AppResult<Json<Value>> {
+    let (user_id, record) = load_property_group(&state, &headers, &path.group_id).await?;
+    let pool = db_pool(&state)?;
+    let org_id = value_str(&record, "organization_id");
+
+    let mut patch = Map::new();
+    if let Some(name) = payload.name.as_deref() {
+        let name = non_empty_opt(Some(name))
+            .ok_or_else(|| AppError::BadRequest("name cannot be empty.".to_string()))?;
+        patch.insert("name".to_string(), Value::String(name));
+    }
+    if let Some(description) = payload.description.as_deref() {
+        patch.insert(
+            "description".to_string(),
+            non_empty_opt(Some(description)).map_or(Value::Null, Value::String),
+        );
+    }
+    if let Some(property_ids) = payload.property_ids.as_deref() {
+        let property_ids = validate_properties(pool, &org_id, property_ids).await?;
+        patch.insert("property_ids".to_string(), json!(property_ids));
+    }
+    if patch.is_empty() {
+        return Ok(Json(record));
+    }
+
+    let updated = update_row(pool, "property_groups", &path.group_id, &patch, "id").await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "update",
+        "property_groups",
+        Some(&path.group_id),
+        Some(record),
+        Some(updated.clone()),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+/// Groups referenced by a custom role or assigned to members cannot be
+/// deleted: dropping someone's last group would lift their property
+/// restriction.
+async fn delete_property_group(
+    State(state): State<AppState>,
+    Path(path): Path<PropertyGroupPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let (user_id, record) = load_property_group(&state, &headers, &path.group_id).await?;
+    let pool = db_pool(&state)?;
+    let org_id = value_str(&record, "organization_id");
+
+    let referenced = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (
+           SELECT 1 FROM organization_roles
+           WHERE organization_id = $1::uuid AND $2::uuid = ANY(property_group_ids)
+         )",
+    )
+    .bind(&org_id)
+    .bind(&path.group_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|error| AppError::from_database_error(&error, "Database request failed."))?;
+    if referenced {
+        return Err(AppError::Conflict(
+            "Remove this group from the roles that use it before deleting it.".to_string(),
+        ));
+    }
+
+    let mut filters = Map::new();
+    filters.insert("group_id".to_string(), Value::String(path.group_id.clone()));
+    if count_rows(pool, "property_group_members", Some(&filters)).await? > 0 {
+        return Err(AppError::Conflict(
+            "Remove the members assigned to this group before deleting it.".to_string(),
+        ));
+    }
+
+    let deleted = delete_row(pool, "property_groups", &path.group_id, "id").await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "delete",
+        "property_groups",
+        Some(&path.group_id),
+        Some(deleted.clone()),
+        None,
+    )
+    .await;
+
+    Ok(Json(deleted))
+}
+
+// ── Group members ───────────────────────────────────────────────────
+
+async fn list_property_group_members(
+    State(state): State<AppState>,
+    Path(path): Path<PropertyGroupPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let (_, record) = load_group_for_members(&state, &headers, &path.group_id).await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(value_str(&record, "organization_id")),
+    );
+    filters.insert("group_id".to_string(), Value::String(path.group_id.clone()));
+    let rows = list_rows(
+        pool,
+        "property_group_members",
+        Some(&filters),
+        500,
+        0,
+        "created_at",
+        true,
+    )
+    .await?;
+
+    Ok(Json(json!({ "data": rows })))
+}
+
+async fn add_property_group_member(
+    State(state): State<AppState>,
+    Path(path): Path<PropertyGroupPath>,
+    headers: HeaderMap,
+    Json(payload): Json<AddPropertyGroupMemberInput>,
+) -> AppResult<impl IntoResponse> {
+    let (user_id, record) = load_group_for_members(&state, &headers, &path.group_id).await?;
+    let pool = db_pool(&state)?;
+    let org_id = value_str(&record, "organization_id");
+
+    let member_user_id = non_empty_opt(Some(&payload.user_id))
+        .ok_or_else(|| AppError::BadRequest("user_id is required.".to_string()))?;
+    let mut filters = Map::new();
+    filters.insert("organization_id".to_string(), Value::String(org_id.clone()));
+    filters.insert("user_id".to_string(), Value::String(member_user_id.clone()));
+    let member = list_rows(
+        pool,
+        "organization_members",
+        Some(&filters),
+        1,
+        0,
+        "user_id",
+        true,
+    )
+    .await?
+    .pop()
+    .ok_or_else(|| {
+        AppError::BadRequest("user_id must be a member of this organization.".to_string())
+    })?;
+    if value_str(&member, "role") == "owner_admin" {
+        return Err(AppError::BadRequest(
+            "Owners and admins always have access to every property.".to_string(),
+        ));
+    }
+
+    let mut payload_map = Map::new();
+    payload_map.insert("organization_id".to_string(), Value::String(org_id.clone()));
+    payload_map.insert("group_id".to_string(), Value::String(path.group_id.clone()));
+    payload_map.insert("user_id".to_string(), Value::String(member_user_id.clone()));
+    payload_map.insert(
+        "created_by_user_id".to_string(),
+        Value::String(user_id.clone()),
+    );
+    let created = create_row(pool, "property_group_members", &payload_map).await?;
+    let entity_id = value_str(&created, "id");
+    state
+        .org_membership_cache
+        .invalidate(&org_key(&org_id, &member_user_id))
+        .await;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "create",
+        "property_group_members",
+        Some(&entity_id),
+        None,
+        Some(created.clone()),
+    )
+    .await;
+
+    Ok((axum::http::StatusCode::CREATED, Json(created)))
+}
+
+async fn remove_property_group_member(
+    State(state): State<AppState>,
+    Path(path): Path<PropertyGroupMemberPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let (user_id, record) = load_group_for_members(&state, &headers, &path.group_id).await?;
+    let pool = db_pool(&state)?;
+    let org_id = value_str(&record, "organization_id");
+
+    let mut filters = Map::new();
+    filters.insert("group_id".to_string(), Value::String(path.group_id.clone()));
+    filters.insert("user_id".to_string(), Value::String(path.user_id.clone()));
+    let assignment = list_rows(
+        pool,
+        "property_group_members",
+        Some(&filters),
+        1,
+        0,
+        "created_at",
+        true,
+    )
+    .await?
+    .pop()
+    .ok_or_else(|| AppError::NotFound("Member is not assigned to this group.".to_string()))?;
+    let entity_id = value_str(&assignment, "id");
+
+    let deleted = delete_row(pool, "property_group_members", &entity_id, "id").await?;
+    state
+        .org_membership_cache
+        .invalidate(&org_key(&org_id, &path.user_id))
+        .await;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "delete",
+        "property_group_members",
+        Some(&entity_id),
+        Some(deleted.clone()),
+        None,
+    )
+    .await;
+
+    Ok(Json(deleted))
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────
+
+async fn load_property_group(
+    state: &AppState,
+    headers: &HeaderMap,
+    group_id: &str,
+) -> AppResult<(String, Value)> {
+    let user_id = require_user_id(state, headers).await?;
+    let pool = db_pool(state)?;
+
+    let record = get_row(pool, "property_groups", group_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_group_editor(state, &user_id, &org_id).await?;
+    Ok((user_id, record))
+}
+
+async fn load_group_for_members(
+    state: &AppState,
+    headers: &HeaderMap,
+    group_id: &str,
+) -> AppResult<(String, Value)> {
+    let user_id = require_user_id(state, headers).await?;
+    let pool = db_pool(state)?;
+
+    let record = get_row(pool, "property_groups", group_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(state, &user_id, &org_id, permissions::MEMBERS_MANAGE).await?;
+    Ok((user_id, record))
+}
+
+/// Members limited to property groups cannot edit groups, or they could
+/// widen their own scope.
+async fn assert_group_editor(state: &AppState, user_id: &str, org_id: &str) -> AppResult<()> {
+    let membership =
+        assert_permission(state, user_id, org_id, permissions::PROPERTIES_MANAGE).await?;
+    if property_scope(state, org_id, &membership).await?.is_some() {
+        return Err(AppError::Forbidden(
+            "Forbidden: property groups can only be edited with access to every property."
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn validate_properties(
+    pool: &sqlx::PgPool,
+    org_id: &str,
+    property_ids: &[String],
+) -> AppResult<Vec<String>> {
+    let mut ids: Vec<String> = Vec::new();
+    for id in property_ids {
+        let id = id.trim();
+        if !id.is_empty() && !ids.iter().any(|existing| existing == id) {
+            ids.push(id.to_string());
+        }
+    }
+    if ids.is_empty() {
+        return Ok(ids);
+    }
+
+    let found = sqlx::query_scalar::<_, i64>(
+        "SELECT count(*) FROM properties
+         WHERE organization_id = $1::uuid AND id::text = ANY($2)",
+    )
+    .bind(org_id)
+    .bind(&ids)
+    .fetch_one(pool)
+    .await
+    .map_err(|error| AppError::from_database_error(&error, "Database request failed."))?;
+    if found != ids.len() as i64 {
+        return Err(AppError::BadRequest(
+            "property_ids must reference properties of this organization.".to_string(),
+        ));
+    }
+    Ok(ids)
+}
+
+fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
+    state.db_pool.as_ref().ok_or_else(|| {
+        AppError::Dependency("Database is not configured. Set DATABASE_URL.".to_string())
+    })
+}
+
+fn value_str(row: &Value, key: &str) -> String {
+    row.as_object()
+        .and_then(|obj| obj.get(key))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+fn non_empty_opt(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+
+    use crate::test_support::{
+        add_member, create_org, create_property, create_unit, create_user, send, test_pool,
+        test_state,
+    };
+
+    async fn insert_id(pool: &PgPool, sql: &str, binds: &[&str]) -> String {
+        let mut query = sqlx::query_scalar::<_, String>(sql);
+        for bind in binds {
+            query = query.bind(*bind);
+        }
+        query.fetch_one(pool).await.expect("insert fixture")
+    }
+
+    fn ids(body: &Value) -> Vec<String> {
+        let mut ids = body["data"]
+            .as_array()
+            .expect("data array")
+            .iter()
+            .map(|row| row["id"].as_str().unwrap_or_default().to_string())
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn group_members_are_limited_to_the_group_properties() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let state = test_state(Some(pool.clone()));
+        let owner = create_user(&pool).await;
+        let member = create_user(&pool).await;
+        let org = create_org(&pool, &owner).await;
+        add_member(&pool, &org, &member, "operator").await;
+
+        let (inside, outside) = (
+            create_property(&pool, &org).await,
+            create_property(&pool, &org).await,
+        );
+        let inside_unit = create_unit(&pool, &org, &inside).await;
+        let outside_unit = create_unit(&pool, &org, &outside).await;
+        let reservation_sql =
+            "INSERT INTO reservations (organization_id, unit_id, check_in_date, check_out_date)
+             VALUES ($1::uuid, $2::uuid, current_date + 30, current_date + 33)
+             RETURNING id::text";
+        let inside_reservation = insert_id(&pool, reservation_sql, &[&org, &inside_unit]).await;
+        let outside_reservation = insert_id(&pool, reservation_sql, &[&org, &outside_unit]).await;
+        let task_sql = "INSERT INTO tasks (organization_id, property_id, title)
+             VALUES ($1::uuid, $2::uuid, 'Inspect') RETURNING id::text";
+        let inside_task = insert_id(&pool, task_sql, &[&org, &inside]).await;
+        let outside_task = insert_id(&pool, task_sql, &[&org, &outside]).await;
+        let maintenance_sql =
+            "INSERT INTO maintenance_requests (organization_id, property_id, title)
+             VALUES ($1::uuid, $2::uuid, 'Leak') RETURNING id::text";
+        let inside_request = insert_id(&pool, maintenance_sql, &[&org, &inside]).await;
+        let outside_request = insert_id(&pool, maintenance_sql, &[&org, &outside]).await;
+
+        let properties_uri = format!("/properties?org_id={org}");
+        let reservations_uri = format!("/reservations?org_id={org}");
+        let mut everything = vec![inside.clone(), outside.clone()];
+        everything.sort();
+
+        // Cached as an unscoped member before the group exists.
+        let (_, body) = send(&state, Method::GET, &properties_uri, &member, None).await;
+        assert_eq!(ids(&body), everything);
+
+        let (status, group) = send(
+            &state,
+            Method::POST,
+            "/property-groups",
+            &owner,
+            Some(json!({ "organization_id": org, "name": "North", "property_ids": [inside] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let group_id = group["id"].as_str().expect("group id").to_string();
+        let (status, _) = send(
+            &state,
+            Method::POST,
+            &format!("/property-groups/{group_id}/members"),
+            &owner,
+            Some(json!({ "user_id": member })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        // Adding the member dropped their cached membership.
+        let (_, body) = send(&state, Method::GET, &properties_uri, &member, None).await;
+        assert_eq!(ids(&body), vec![inside.clone()]);
+        let (status, body) = send(&state, Method::GET, &reservations_uri, &member, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ids(&body), vec![inside_reservation.clone()]);
+        let (status, _) = send(
+            &state,
+            Method::GET,
+            &format!("{reservations_uri}&property_id={outside}"),
+            &member,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (_, body) = send(
+            &state,
+            Method::GET,
+            &format!("/tasks?org_id={org}"),
+            &member,
+            None,
+        )
+        .await;
+        assert_eq!(ids(&body), vec![inside_task.clone()]);
+
+        for (uri, allowed) in [
+            (format!("/properties/{inside}"), true),
+            (format!("/properties/{outside}"), false),
+            (format!("/reservations/{inside_reservation}"), true),
+            (format!("/reservations/{outside_reservation}"), false),
+            (format!("/tasks/{inside_task}"), true),
+            (format!("/tasks/{outside_task}"), false),
+            (format!("/maintenance-requests/{inside_request}"), true),
+            (format!("/maintenance-requests/{outside_request}"), false),
+        ] {
+            let (status, _) = send(&state, Method::GET, &uri, &member, None).await;
+            let expected = if allowed {
+                StatusCode::OK
+            } else {
+                StatusCode::FORBIDDEN
+            };
+            assert_eq!(status, expected, "GET {uri}");
+        }
+        let (status, _) = send(
+            &state,
+            Method::PATCH,
+            &format!("/tasks/{outside_task}"),
+            &member,
+            Some(json!({ "title": "Moved" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // Owners keep the whole portfolio and can filter reservations by property.
+        let (status, body) = send(
+            &state,
+            Method::GET,
+            &format!("{reservations_uri}&property_id={outside}"),
+            &owner,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ids(&body), vec![outside_reservation.clone()]);
+
+        // Removing the member drops the cached scope again.
+        let (status, _) = send(
+            &state,
+            Method::DELETE,
+            &format!("/property-groups/{group_id}/members/{member}"),
+            &owner,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, body) = send(&state, Method::GET, &properties_uri, &member, None).await;
+        assert_eq!(ids(&body), everything);
+        let (status, _) = send(
+            &state,
+            Method::GET,
+            &format!("/reservations/{outside_reservation}"),
+            &member,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -85,7 +85,17 @@ async fn owner_summary_report(
     }
 
     let pool = db_pool(&state)?;
+    let currency = crate::services::fx::reporting_currency(
+        pool,
+        &query.org_id,
+        query.property_id.as_deref(),
+        query.unit_id.as_deref(),
+        query.currency.as_deref(),
+    )
+    .await?;
 
+    // Amounts are converted at the rate in effect on each transaction date;
+    // rows without a usable rate are left out and counted as warnings.
     let owner_metrics_query = sqlx::query(
         "WITH filtered_units AS (
            SELECT u.id
@@ -98,7 +108,7 @@ async fn owner_summary_report(
            SELECT
              r.check_in_date,
              r.check_out_date,
-             r.total_amount
+             r.total_amount * fx_rate_on(r.currency, $6, r.check_in_date) AS total_amount
            FROM reservations r
            JOIN filtered_units u ON u.id = r.unit_id
            WHERE r.organization_id = $1::uuid
@@ -117,7 +127,8 @@ async fn owner_summary_report(
              ),
              0
            )::bigint AS booked_nights,
-           COALESCE(SUM(rs.total_amount), 0)::double precision AS gross_revenue
+           COALESCE(SUM(rs.total_amount), 0)::double precision AS gross_revenue,
+           COUNT(*) FILTER (WHERE rs.total_amount IS NULL)::bigint AS missing_fx_rate
          FROM reservation_scope rs",
     )
     .bind(&query.org_id)
@@ -125,28 +136,18 @@ async fn owner_summary_report(
     .bind(unit_id)
     .bind(period_start)
     .bind(period_end)
+    .bind(&currency)
     .fetch_one(pool);
 
     let expense_metrics_query = sqlx::query(
-        "SELECT
-           COALESCE(
-             SUM(
-               CASE
-                 WHEN e.currency = 'PYG' THEN e.amount
-                 WHEN e.currency = 'USD' AND COALESCE(e.fx_rate_to_pyg, 0) > 0
-                   THEN e.amount * e.fx_rate_to_pyg
-                 ELSE 0
-               END
-             ),
-             0
-           )::double precision AS total_expenses,
-           COUNT(*) FILTER (
-             WHERE e.currency = 'USD'
-               AND (e.fx_rate_to_pyg IS NULL OR e.fx_rate_to_pyg <= 0)
-           )::bigint AS missing_fx_rate_to_pyg,
-           COUNT(*) FILTER (
-             WHERE e.currency NOT IN ('PYG', 'USD')
-           )::bigint AS unsupported_currency
+        "WITH converted AS (
+           SELECT
+             CASE
+               WHEN e.currency = $6 THEN e.amount
+               WHEN $6 = 'PYG' AND COALESCE(e.fx_rate_to_pyg, 0) > 0
+                 THEN e.amount * e.fx_rate_to_pyg
+               ELSE e.amount * fx_rate_on(e.currency, $6, e.expense_date)
+             END AS amount
          FROM expenses e
          WHERE e.organization_id = $1::uuid
            AND e.expense_date >= $2::date
@@ -155,13 +156,19 @@ async fn owner_summary_report(
              ($5::uuid IS NOT NULL AND e.unit_id = $5::uuid)
              OR ($5::uuid IS NULL AND $4::uuid IS NOT NULL AND e.property_id = $4::uuid)
              OR ($5::uuid IS NULL AND $4::uuid IS NULL)
-           )",
+           )
+         )
+         SELECT
+           COALESCE(SUM(c.amount), 0)::double precision AS total_expenses,
+           COUNT(*) FILTER (WHERE c.amount IS NULL)::bigint AS missing_fx_rate
+         FROM converted c",
     )
     .bind(&query.org_id)
     .bind(period_start)
     .bind(period_end)
     .bind(property_id)
     .bind(unit_id)
+    .bind(&currency)
     .fetch_one(pool);
 
     let (owner_metrics, expense_metrics) =
@@ -183,20 +190,14 @@ async fn owner_summary_report(
 
     let mut warnings: std::collections::HashMap<String, i64> = std::collections::HashMap::new();
     let missing_fx_count = expense_metrics
-        .try_get::<i64, _>("missing_fx_rate_to_pyg")
+        .try_get::<i64, _>("missing_fx_rate")
         .unwrap_or(0);
     if missing_fx_count > 0 {
-        warnings.insert("missing_fx_rate_to_pyg".to_string(), missing_fx_count);
+        warnings.insert("missing_fx_rate".to_string(), missing_fx_count);
     }
-    let unsupported_currency_count = expense_metrics
-        .try_get::<i64, _>("unsupported_currency")
+    let missing_revenue_fx_count = owner_metrics
+        .try_get::<i64, _>("missing_fx_rate")
         .unwrap_or(0);
-    if unsupported_currency_count > 0 {
-        warnings.insert(
-            "unsupported_currency".to_string(),
-            unsupported_currency_count,
-        );
-    }
 
     let unit_count = std::cmp::max(raw_unit_count, 1);
     let available_nights = std::cmp::max(total_days * unit_count, 1) as f64;
@@ -208,11 +209,13 @@ async fn owner_summary_report(
         "organization_id": query.org_id,
         "from": query.from_date,
         "to": query.to_date,
+        "currency": currency,
         "occupancy_rate": occupancy_rate,
         "gross_revenue": round2(gross_revenue),
         "expenses": round2(total_expenses),
         "net_payout": net_payout,
         "expense_warnings": warnings,
+        "revenue_warnings": { "missing_fx_rate": missing_revenue_fx_count },
     });
 
     state
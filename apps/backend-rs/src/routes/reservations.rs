@@ -10,14 +10,20 @@ use crate::{
     },
     services::{
         audit::write_audit_log,
+        availability,
         enrichment::enrich_reservations,
+        permissions,
         reservations::{build_reservation_detail_overview, build_reservations_overview},
         sequences::enroll_in_sequences,
+        stay_restrictions,
         token_hash::hash_token,
         workflows::fire_trigger,
     },
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{
+        assert_org_member, assert_permission, assert_unit_in_scope, property_scope,
+        scope_unit_filter,
+    },
 };
 use axum::{
     extract::{Path, Query, State},
@@ -159,7 +165,7 @@ async fn list_reservations(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &query.org_id).await?;
     let pool = db_pool(&state)?;
 
     let mut filters = Map::new();
@@ -167,9 +173,6 @@ async fn list_reservations(
         "organization_id".to_string(),
         Value::String(query.org_id.clone()),
     );
-    if let Some(property_id) = non_empty_opt(query.property_id.as_deref()) {
-        filters.insert("property_id".to_string(), Value::String(property_id));
-    }
     if let Some(unit_id) = non_empty_opt(query.unit_id.as_deref()) {
         filters.insert("unit_id".to_string(), Value::String(unit_id));
     }
@@ -182,6 +185,19 @@ async fn list_reservations(
     if let Some(status) = non_empty_opt(query.status.as_deref()) {
         filters.insert("status".to_string(), Value::String(status));
     }
+    // Reservations reach their property through the unit.
+    let property_id = non_empty_opt(query.property_id.as_deref());
+    if !scope_unit_filter(
+        &state,
+        &query.org_id,
+        &membership,
+        &mut filters,
+        property_id.as_deref(),
+    )
+    .await?
+    {
+        return Ok(Json(json!({ "data": [] })));
+    }
 
     let rows = list_rows(
         pool,
@@ -203,10 +219,13 @@ async fn list_reservations_overview(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &query.org_id).await?;
     let pool = db_pool(&state)?;
+    let scope = property_scope(&state, &query.org_id, &membership).await?;
 
-    Ok(Json(build_reservations_overview(pool, &query).await?))
+    Ok(Json(
+        build_reservations_overview(pool, &query, scope.as_deref()).await?,
+    ))
 }
 
 async fn create_reservation(
@@ -215,27 +234,29 @@ async fn create_reservation(
     Json(payload): Json<CreateReservationInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
+    let membership = assert_permission(
         &state,
         &user_id,
         &payload.organization_id,
-        &["owner_admin", "operator"],
+        permissions::RESERVATIONS_EDIT,
     )
     .await?;
-    let pool = db_pool(&state)?;
-
-    if has_overlap(
-        pool,
-        &payload.unit_id,
-        &payload.check_in_date,
-        &payload.check_out_date,
+    assert_unit_in_scope(
+        &state,
         &payload.organization_id,
+        &membership,
+        Some(&payload.unit_id),
     )
-    .await?
-    {
-        return Err(AppError::Conflict(
-            "Reservation overlaps an existing active reservation.".to_string(),
-        ));
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let check_in = parse_date(&payload.check_in_date)?;
+    let check_out = parse_date(&payload.check_out_date)?;
+    availability::ensure_available(pool, &payload.unit_id, check_in, check_out, None).await?;
+    // Channel bookings were already accepted under the channel's own copy of
+    // the restrictions (pushed with ARI), so only direct entries are checked.
+    if non_empty_opt(payload.integration_id.as_deref()).is_none() {
+        stay_restrictions::ensure_stay_allowed(pool, &payload.unit_id, check_in, check_out).await?;
     }
 
     let record = remove_nulls(serialize_to_map(&payload));
@@ -268,7 +289,14 @@ async fn get_reservation(
     let pool = db_pool(&state)?;
     let record = get_row(pool, "reservations", &path.reservation_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_member(&state, &user_id, &org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &org_id).await?;
+    assert_unit_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&value_str(&record, "unit_id")),
+    )
+    .await?;
 
     let mut enriched = enrich_reservations(&state, pool, vec![record], &org_id).await?;
     Ok(Json(
@@ -285,7 +313,14 @@ async fn get_reservation_overview(
     let pool = db_pool(&state)?;
     let record = get_row(pool, "reservations", &path.reservation_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_member(&state, &user_id, &org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &org_id).await?;
+    assert_unit_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&value_str(&record, "unit_id")),
+    )
+    .await?;
 
     Ok(Json(
         build_reservation_detail_overview(pool, &path.reservation_id).await?,
@@ -303,7 +338,15 @@ async fn update_reservation(
 
     let record = get_row(pool, "reservations", &path.reservation_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    let membership =
+        assert_permission(&state, &user_id, &org_id, permissions::RESERVATIONS_EDIT).await?;
+    assert_unit_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&value_str(&record, "unit_id")),
+    )
+    .await?;
 
     let patch = remove_nulls(serialize_to_map(&payload));
     let updated = update_row(pool, "reservations", &path.reservation_id, &patch, "id").await?;
@@ -334,7 +377,15 @@ async fn transition_status(
 
     let reservation = get_row(pool, "reservations", &path.reservation_id, "id").await?;
     let org_id = value_str(&reservation, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    let membership =
+        assert_permission(&state, &user_id, &org_id, permissions::RESERVATIONS_EDIT).await?;
+    assert_unit_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&value_str(&reservation, "unit_id")),
+    )
+    .await?;
 
     let current_status = value_str(&reservation, "status");
     if payload.status == current_status {
@@ -506,6 +557,7 @@ async fn trigger_ical_resync_for_unit(
             client,
             integration,
             "system",
+            crate::services::ical::IcalSyncMode::Full,
         )
         .await
         {
@@ -528,40 +580,6 @@ async fn trigger_ical_resync_for_unit(
     }
 }
 
-async fn has_overlap(
-    pool: &sqlx::PgPool,
-    unit_id: &str,
-    check_in_date: &str,
-    check_out_date: &str,
-    _org_id: &str,
-) -> AppResult<bool> {
-    // Validate dates parse correctly
-    let _ = parse_date(check_in_date)?;
-    let _ = parse_date(check_out_date)?;
-
-    // Single SQL query using daterange overlap — eliminates TOCTOU race and 1000-row limit.
-    // The DB-level GiST exclusion constraint is the ultimate guard; this gives a friendlier error.
-    let exists: bool = sqlx::query_scalar(
-        "SELECT EXISTS(
-            SELECT 1 FROM reservations
-            WHERE unit_id = $1::uuid
-              AND daterange(check_in_date, check_out_date, '[)') && daterange($2::date, $3::date, '[)')
-              AND status IN ('pending', 'confirmed', 'checked_in')
-        )",
-    )
-    .bind(unit_id)
-    .bind(check_in_date)
-    .bind(check_out_date)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| {
-        tracing::error!(error = %e, "Reservation overlap check failed");
-        AppError::Internal("Failed to check reservation overlap.".to_string())
-    })?;
-
-    Ok(exists)
-}
-
 async fn sync_turnover_tasks_for_status(
     pool: &sqlx::PgPool,
     reservation_row: &Value,
@@ -958,7 +976,15 @@ async fn refund_deposit(
 
     let record = get_row(pool, "reservations", &path.reservation_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    let membership =
+        assert_permission(&state, &user_id, &org_id, permissions::RESERVATIONS_EDIT).await?;
+    assert_unit_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&value_str(&record, "unit_id")),
+    )
+    .await?;
 
     let deposit_status = value_str(&record, "deposit_status");
     if deposit_status != "held" && deposit_status != "collected" {
@@ -1045,7 +1071,14 @@ async fn send_guest_portal_link(
         .filter(|s| !s.is_empty())
         .ok_or_else(|| AppError::BadRequest("Reservation has no organization.".to_string()))?
         .to_string();
-    assert_org_member(&state, &user_id, &org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &org_id).await?;
+    assert_unit_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&value_str(&reservation, "unit_id")),
+    )
+    .await?;
 
     let guest_id = reservation
         .as_object()
@@ -1145,7 +1178,14 @@ async fn list_reservation_guests(
 
     let reservation = get_row(pool, "reservations", &path.reservation_id, "id").await?;
     let org_id = value_str(&reservation, "organization_id");
-    assert_org_member(&state, &user_id, &org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &org_id).await?;
+    assert_unit_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&value_str(&reservation, "unit_id")),
+    )
+    .await?;
 
     let mut filters = Map::new();
     filters.insert(
@@ -1213,7 +1253,15 @@ async fn add_reservation_guest(
 
     let reservation = get_row(pool, "reservations", &path.reservation_id, "id").await?;
     let org_id = value_str(&reservation, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    let membership =
+        assert_permission(&state, &user_id, &org_id, permissions::RESERVATIONS_EDIT).await?;
+    assert_unit_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&value_str(&reservation, "unit_id")),
+    )
+    .await?;
 
     // Validate guest belongs to same org
     let guest = get_row(pool, "guests", &payload.guest_id, "id").await?;
@@ -1282,7 +1330,15 @@ async fn remove_reservation_guest(
 
     let reservation = get_row(pool, "reservations", &path.reservation_id, "id").await?;
     let org_id = value_str(&reservation, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    let membership =
+        assert_permission(&state, &user_id, &org_id, permissions::RESERVATIONS_EDIT).await?;
+    assert_unit_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&value_str(&reservation, "unit_id")),
+    )
+    .await?;
 
     let deleted = delete_row(pool, "reservation_guests", &path.reservation_guest_id, "id").await?;
 
@@ -10,8 +10,9 @@ use sqlx::Row;
 use crate::{
     auth::require_user_id,
     error::{AppError, AppResult},
+    services::permissions,
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
 pub fn router() -> axum::Router<AppState> {
@@ -179,13 +180,7 @@ async fn update_review(
     Json(payload): Json<UpdateReviewInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
-        &state,
-        &user_id,
-        &payload.org_id,
-        &["owner_admin", "operator"],
-    )
-    .await?;
+    assert_permission(&state, &user_id, &payload.org_id, permissions::REVIEWS_EDIT).await?;
     let pool = db_pool(&state)?;
 
     let mut updates = Vec::new();
@@ -246,13 +241,7 @@ async fn publish_response(
     Json(payload): Json<UpdateReviewInput>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
-        &state,
-        &user_id,
-        &payload.org_id,
-        &["owner_admin", "operator"],
-    )
-    .await?;
+    assert_permission(&state, &user_id, &payload.org_id, permissions::REVIEWS_EDIT).await?;
     let pool = db_pool(&state)?;
 
     let result = sqlx::query(
@@ -0,0 +1,320 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
+};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    auth::require_user_id,
+    error::{AppError, AppResult},
+    repository::table_service::{
+        count_rows, create_row, delete_row, get_row, list_rows, update_row,
+    },
+    schemas::{CreateRoleInput, RolePath, RolesQuery, UpdateRoleInput},
+    services::{audit::write_audit_log, permissions},
+    state::AppState,
+    tenancy::{assert_org_member, assert_permission},
+};
+
+pub fn router() -> axum::Router<AppState> {
+    axum::Router::new()
+        .route("/permissions", axum::routing::get(list_permissions))
+        .route("/roles", axum::routing::get(list_roles).post(create_role))
+        .route(
+            "/roles/{role_id}",
+            axum::routing::get(get_role)
+                .patch(update_role)
+                .delete(delete_role),
+        )
+}
+
+// ── Permission catalog ──────────────────────────────────────────────
+
+async fn list_permissions(
+    State(state): State<AppState>,
+    Query(query): Query<RolesQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let membership = assert_org_member(&state, &user_id, &query.org_id).await?;
+
+    let granted = permissions::CATALOG
+        .iter()
+        .filter(|permission| permissions::membership_allows(&membership, **permission))
+        .map(|permission| permission.key())
+        .collect::<Vec<_>>();
+
+    Ok(Json(json!({
+        "data": permissions::catalog_json(),
+        "granted": granted,
+    })))
+}
+
+// ── Custom roles ────────────────────────────────────────────────────
+
+async fn list_roles(
+    State(state): State<AppState>,
+    Query(query): Query<RolesQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(&state, &user_id, &query.org_id, permissions::ROLES_MANAGE).await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(query.org_id.clone()),
+    );
+    let rows = list_rows(
+        pool,
+        "organization_roles",
+        Some(&filters),
+        200,
+        0,
+        "name",
+        true,
+    )
+    .await?;
+
+    Ok(Json(json!({ "data": rows })))
+}
+
+async fn create_role(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateRoleInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::ROLES_MANAGE,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let name = non_empty_opt(Some(&payload.name))
+        .ok_or_else(|| AppError::BadRequest("name is required.".to_string()))?;
+    let grants =
+        permissions::normalize_grants(&payload.permissions).map_err(AppError::BadRequest)?;
+    let group_ids =
+        validate_property_groups(pool, &payload.organization_id, &payload.property_group_ids)
+            .await?;
+
+    let mut record = Map::new();
+    record.insert(
+        "organization_id".to_string(),
+        Value::String(payload.organization_id.clone()),
+    );
+    record.insert("name".to_string(), Value::String(name));
+    if let Some(description) = non_empty_opt(payload.description.as_deref()) {
+        record.insert("description".to_string(), Value::String(description));
+    }
+    record.insert("permissions".to_string(), json!(grants));
+    record.insert("property_group_ids".to_string(), json!(group_ids));
+    record.insert(
+        "created_by_user_id".to_string(),
+        Value::String(user_id.clone()),
+    );
+    let created = create_row(pool, "organization_roles", &record).await?;
+    let entity_id = value_str(&created, "id");
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        "create",
+        "organization_roles",
+        Some(&entity_id),
+        None,
+        Some(created.clone()),
+    )
+    .await;
+
+    Ok((axum::http::StatusCode::CREATED, Json(created)))
+}
+
+async fn get_role(
+    State(state): State<AppState>,
+    Path(path): Path<RolePath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let (_, record) = load_role(&state, &headers, &path.role_id).await?;
+    Ok(Json(record))
+}
+
+async fn update_role(
+    State(state): State<AppState>,
+    Path(path): Path<RolePath>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateRoleInput>,
+) -> AppResult<Json<Value>> {
+    let (user_id, record) = load_role(&state, &headers, &path.role_id).await?;
+    let pool = db_pool(&state)?;
+    let org_id = value_str(&record, "organization_id");
+
+    let mut patch = Map::new();
+    if let Some(name) = payload.name.as_deref() {
+        let name = non_empty_opt(Some(name))
+            .ok_or_else(|| AppError::BadRequest("name cannot be empty.".to_string()))?;
+        patch.insert("name".to_string(), Value::String(name));
+    }
+    if let Some(description) = payload.description.as_deref() {
+        patch.insert(
+            "description".to_string(),
+            non_empty_opt(Some(description)).map_or(Value::Null, Value::String),
+        );
+    }
+    if let Some(grants) = payload.permissions.as_deref() {
+        let grants = permissions::normalize_grants(grants).map_err(AppError::BadRequest)?;
+        patch.insert("permissions".to_string(), json!(grants));
+    }
+    if let Some(group_ids) = payload.property_group_ids.as_deref() {
+        let group_ids = validate_property_groups(pool, &org_id, group_ids).await?;
+        patch.insert("property_group_ids".to_string(), json!(group_ids));
+    }
+    if patch.is_empty() {
+        return Ok(Json(record));
+    }
+
+    let updated = update_row(pool, "organization_roles", &path.role_id, &patch, "id").await?;
+    invalidate_org_memberships(&state, &org_id).await;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "update",
+        "organization_roles",
+        Some(&path.role_id),
+        Some(record),
+        Some(updated.clone()),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+/// Roles still assigned to members cannot be deleted: members would fall
+/// back to their built-in role, which may grant more than the custom one.
+async fn delete_role(
+    State(state): State<AppState>,
+    Path(path): Path<RolePath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let (user_id, record) = load_role(&state, &headers, &path.role_id).await?;
+    let pool = db_pool(&state)?;
+    let org_id = value_str(&record, "organization_id");
+
+    let mut filters = Map::new();
+    filters.insert(
+        "custom_role_id".to_string(),
+        Value::String(path.role_id.clone()),
+    );
+    if count_rows(pool, "organization_members", Some(&filters)).await? > 0 {
+        return Err(AppError::Conflict(
+            "Reassign the members holding this role before deleting it.".to_string(),
+        ));
+    }
+
+    let deleted = delete_row(pool, "organization_roles", &path.role_id, "id").await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "delete",
+        "organization_roles",
+        Some(&path.role_id),
+        Some(deleted.clone()),
+        None,
+    )
+    .await;
+
+    Ok(Json(deleted))
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────
+
+async fn load_role(
+    state: &AppState,
+    headers: &HeaderMap,
+    role_id: &str,
+) -> AppResult<(String, Value)> {
+    let user_id = require_user_id(state, headers).await?;
+    let pool = db_pool(state)?;
+
+    let record = get_row(pool, "organization_roles", role_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(state, &user_id, &org_id, permissions::ROLES_MANAGE).await?;
+    Ok((user_id, record))
+}
+
+async fn validate_property_groups(
+    pool: &sqlx::PgPool,
+    org_id: &str,
+    group_ids: &[String],
+) -> AppResult<Vec<String>> {
+    let mut ids: Vec<String> = Vec::new();
+    for id in group_ids {
+        let id = id.trim();
+        if !id.is_empty() && !ids.iter().any(|existing| existing == id) {
+            ids.push(id.to_string());
+        }
+    }
+    if ids.is_empty() {
+        return Ok(ids);
+    }
+
+    let found = sqlx::query_scalar::<_, i64>(
+        "SELECT count(*) FROM property_groups
+         WHERE organization_id = $1::uuid AND id::text = ANY($2)",
+    )
+    .bind(org_id)
+    .bind(&ids)
+    .fetch_one(pool)
+    .await
+    .map_err(|error| AppError::from_database_error(&error, "Database request failed."))?;
+    if found != ids.len() as i64 {
+        return Err(AppError::BadRequest(
+            "property_group_ids must reference property groups of this organization.".to_string(),
+        ));
+    }
+    Ok(ids)
+}
+
+/// Memberships embed their custom role, so every cached membership of the
+/// organization is dropped when a role changes.
+async fn invalidate_org_memberships(state: &AppState, org_id: &str) {
+    state
+        .org_membership_cache
+        .invalidate_prefix(&format!("{org_id}:"))
+        .await;
+}
+
+fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
+    state.db_pool.as_ref().ok_or_else(|| {
+        AppError::Dependency("Database is not configured. Set DATABASE_URL.".to_string())
+    })
+}
+
+fn value_str(row: &Value, key: &str) -> String {
+    row.as_object()
+        .and_then(|obj| obj.get(key))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+fn non_empty_opt(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+}
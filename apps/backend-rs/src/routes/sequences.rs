@@ -11,8 +11,9 @@ use crate::{
     error::{AppError, AppResult},
     repository::table_service::{create_row, delete_row, get_row, list_rows, update_row},
     schemas::clamp_limit_in_range,
+    services::permissions,
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
 const SEQUENCE_TRIGGER_TYPES: &[&str] = &[
@@ -153,11 +154,11 @@ async fn create_sequence(
     Json(payload): Json<CreateSequenceInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
+    assert_permission(
         &state,
         &user_id,
         &payload.organization_id,
-        &["owner_admin", "operator"],
+        permissions::SEQUENCES_EDIT,
     )
     .await?;
     let pool = db_pool(&state)?;
@@ -232,7 +233,7 @@ async fn update_sequence(
 
     let record = get_row(pool, "communication_sequences", &path.sequence_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::SEQUENCES_EDIT).await?;
 
     let mut patch = Map::new();
     if let Some(name) = payload.name {
@@ -271,7 +272,7 @@ async fn delete_sequence(
 
     let record = get_row(pool, "communication_sequences", &path.sequence_id, "id").await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::SEQUENCES_DELETE).await?;
 
     let deleted = delete_row(pool, "communication_sequences", &path.sequence_id, "id").await?;
     Ok(Json(deleted))
@@ -319,7 +320,7 @@ async fn create_step(
 
     let sequence = get_row(pool, "communication_sequences", &path.sequence_id, "id").await?;
     let org_id = value_str(&sequence, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::SEQUENCES_EDIT).await?;
 
     let mut record = Map::new();
     record.insert("sequence_id".to_string(), Value::String(path.sequence_id));
@@ -363,7 +364,7 @@ async fn update_step(
     let sequence_id = value_str(&step, "sequence_id");
     let sequence = get_row(pool, "communication_sequences", &sequence_id, "id").await?;
     let org_id = value_str(&sequence, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::SEQUENCES_EDIT).await?;
 
     let mut patch = Map::new();
     if let Some(order) = payload.step_order {
@@ -407,7 +408,7 @@ async fn delete_step(
     let sequence_id = value_str(&step, "sequence_id");
     let sequence = get_row(pool, "communication_sequences", &sequence_id, "id").await?;
     let org_id = value_str(&sequence, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::SEQUENCES_EDIT).await?;
 
     let deleted = delete_row(pool, "sequence_steps", &path.step_id, "id").await?;
     Ok(Json(deleted))
@@ -12,12 +12,11 @@ use crate::{
     repository::table_service::{create_row, get_row, list_rows, update_row},
     schemas::clamp_limit_in_range,
     services::audit::write_audit_log,
+    services::permissions,
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
-const BILLING_ROLES: &[&str] = &["owner_admin"];
-
 pub fn router() -> axum::Router<AppState> {
     axum::Router::new()
         .route("/subscription-plans", axum::routing::get(list_plans))
@@ -215,7 +214,13 @@ async fn subscribe(
     Json(payload): Json<SubscribeInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &payload.organization_id, BILLING_ROLES).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::BILLING_MANAGE,
+    )
+    .await?;
     let pool = db_pool(&state)?;
 
     // Verify plan exists
@@ -331,7 +336,13 @@ async fn cancel_subscription(
     Json(payload): Json<BillingOrgQuery>,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(&state, &user_id, &payload.org_id, BILLING_ROLES).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.org_id,
+        permissions::BILLING_MANAGE,
+    )
+    .await?;
     let pool = db_pool(&state)?;
 
     let mut org_filter = Map::new();
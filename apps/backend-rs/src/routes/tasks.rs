@@ -17,14 +17,15 @@ use crate::{
         CreateTaskItemInput, TaskItemPath, TaskItemsQuery, TaskPath, TasksQuery, UpdateTaskInput,
         UpdateTaskItemInput,
     },
-    services::{audit::write_audit_log, enrichment::enrich_tasks, workflows::fire_trigger},
+    services::{
+        audit::write_audit_log, enrichment::enrich_tasks, permissions, workflows::fire_trigger,
+    },
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{
+        assert_org_member, assert_permission, assert_property_in_scope, scope_property_filter,
+    },
 };
 
-const TASK_ITEM_UPDATE_ROLES: &[&str] = &["owner_admin", "operator", "cleaner"];
-const TASK_ITEM_MANAGE_ROLES: &[&str] = &["owner_admin", "operator"];
-
 pub fn router() -> axum::Router<AppState> {
     axum::Router::new()
         .route("/tasks", axum::routing::get(list_tasks).post(create_task))
@@ -52,7 +53,7 @@ async fn list_tasks(
     headers: HeaderMap,
 ) -> AppResult<Json<Value>> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_member(&state, &user_id, &query.org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &query.org_id).await?;
     let pool = db_pool(&state)?;
 
     let mut filters = Map::new();
@@ -78,6 +79,17 @@ async fn list_tasks(
     if let Some(reservation_id) = non_empty_opt(query.reservation_id.as_deref()) {
         filters.insert("reservation_id".to_string(), Value::String(reservation_id));
     }
+    if !scope_property_filter(
+        &state,
+        &query.org_id,
+        &membership,
+        &mut filters,
+        "property_id",
+    )
+    .await?
+    {
+        return Ok(Json(json!({ "data": [] })));
+    }
 
     let rows = list_rows(
         pool,
@@ -106,11 +118,18 @@ async fn create_task(
     Json(payload): Json<CreateTaskInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
+    let membership = assert_permission(
         &state,
         &user_id,
         &payload.organization_id,
-        &["owner_admin", "operator"],
+        permissions::TASKS_EDIT,
+    )
+    .await?;
+    assert_property_in_scope(
+        &state,
+        &payload.organization_id,
+        &membership,
+        payload.property_id.as_deref(),
     )
     .await?;
     let pool = db_pool(&state)?;
@@ -144,7 +163,14 @@ async fn get_task(
 
     let record = get_task_record(pool, &path.task_id).await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_member(&state, &user_id, &org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &org_id).await?;
+    assert_property_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&value_str(&record, "property_id")),
+    )
+    .await?;
 
     let flagged = flag_sla_breach(pool, record, state.config.workflow_engine_mode).await;
     let mut enriched = enrich_tasks(&state, pool, vec![flagged], &org_id).await?;
@@ -164,7 +190,14 @@ async fn update_task(
 
     let record = get_task_record(pool, &path.task_id).await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, &["owner_admin", "operator"]).await?;
+    let membership = assert_permission(&state, &user_id, &org_id, permissions::TASKS_EDIT).await?;
+    assert_property_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&value_str(&record, "property_id")),
+    )
+    .await?;
 
     let patch = remove_nulls(serialize_to_map(&payload));
     let previous_status = value_str(&record, "status");
@@ -209,11 +242,13 @@ async fn complete_task(
 
     let record = get_task_record(pool, &path.task_id).await?;
     let org_id = value_str(&record, "organization_id");
-    assert_org_role(
+    let membership =
+        assert_permission(&state, &user_id, &org_id, permissions::TASKS_COMPLETE).await?;
+    assert_property_in_scope(
         &state,
-        &user_id,
         &org_id,
-        &["owner_admin", "operator", "cleaner"],
+        &membership,
+        Some(&value_str(&record, "property_id")),
     )
     .await?;
 
@@ -323,7 +358,14 @@ async fn list_task_items(
 
     let task = get_task_record(pool, &path.task_id).await?;
     let org_id = value_str(&task, "organization_id");
-    assert_org_member(&state, &user_id, &org_id).await?;
+    let membership = assert_org_member(&state, &user_id, &org_id).await?;
+    assert_property_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&value_str(&task, "property_id")),
+    )
+    .await?;
 
     let rows = list_rows(
         pool,
@@ -353,7 +395,14 @@ async fn create_task_item(
 
     let task = get_task_record(pool, &path.task_id).await?;
     let org_id = value_str(&task, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, TASK_ITEM_MANAGE_ROLES).await?;
+    let membership = assert_permission(&state, &user_id, &org_id, permissions::TASKS_EDIT).await?;
+    assert_property_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&value_str(&task, "property_id")),
+    )
+    .await?;
 
     let label = payload.label.trim().to_string();
     if label.is_empty() {
@@ -407,7 +456,15 @@ async fn update_task_item(
 
     let task = get_task_record(pool, &path.task_id).await?;
     let org_id = value_str(&task, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, TASK_ITEM_UPDATE_ROLES).await?;
+    let membership =
+        assert_permission(&state, &user_id, &org_id, permissions::TASKS_COMPLETE).await?;
+    assert_property_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&value_str(&task, "property_id")),
+    )
+    .await?;
 
     let existing = get_row(pool, "task_items", &path.item_id, "id").await?;
     if value_str(&existing, "task_id") != path.task_id {
@@ -480,7 +537,14 @@ async fn delete_task_item(
 
     let task = get_task_record(pool, &path.task_id).await?;
     let org_id = value_str(&task, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, TASK_ITEM_MANAGE_ROLES).await?;
+    let membership = assert_permission(&state, &user_id, &org_id, permissions::TASKS_EDIT).await?;
+    assert_property_in_scope(
+        &state,
+        &org_id,
+        &membership,
+        Some(&value_str(&task, "property_id")),
+    )
+    .await?;
 
     let existing = get_row(pool, "task_items", &path.item_id, "id").await?;
     if value_str(&existing, "task_id") != path.task_id {
@@ -0,0 +1,513 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
+};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    auth::require_user_id,
+    error::{AppError, AppResult},
+    repository::table_service::{create_row, delete_row, get_row, list_rows, update_row},
+    schemas::{
+        clamp_limit_in_range, CreateWebhookSubscriptionInput, UpdateWebhookSubscriptionInput,
+        WebhookDeliveriesQuery, WebhookDeliveryPath, WebhookSubscriptionPath,
+        WebhookSubscriptionsQuery,
+    },
+    services::{audit::write_audit_log, outbound_webhooks, permissions, webhook_verification},
+    state::AppState,
+    tenancy::assert_permission,
+};
+
+const DELIVERY_STATUSES: &[&str] = &["pending", "sending", "succeeded", "failed", "cancelled"];
+
+pub fn router() -> axum::Router<AppState> {
+    axum::Router::new()
+        .route(
+            "/webhook-subscriptions",
+            axum::routing::get(list_subscriptions).post(create_subscription),
+        )
+        .route(
+            "/webhook-subscriptions/{subscription_id}",
+            axum::routing::get(get_subscription)
+                .patch(update_subscription)
+                .delete(delete_subscription),
+        )
+        .route(
+            "/webhook-subscriptions/{subscription_id}/rotate-secret",
+            axum::routing::post(rotate_subscription_secret),
+        )
+        .route(
+            "/webhook-subscriptions/{subscription_id}/test",
+            axum::routing::post(send_test_event),
+        )
+        .route(
+            "/webhook-subscriptions/{subscription_id}/deliveries",
+            axum::routing::get(list_deliveries),
+        )
+        .route(
+            "/webhook-deliveries/{delivery_id}",
+            axum::routing::get(get_delivery),
+        )
+        .route(
+            "/webhook-deliveries/{delivery_id}/replay",
+            axum::routing::post(replay_delivery),
+        )
+}
+
+// ── Subscriptions ───────────────────────────────────────────────────
+
+async fn list_subscriptions(
+    State(state): State<AppState>,
+    Query(query): Query<WebhookSubscriptionsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &query.org_id,
+        permissions::WEBHOOKS_MANAGE,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(query.org_id.clone()),
+    );
+    if let Some(is_active) = query.is_active {
+        filters.insert("is_active".to_string(), Value::Bool(is_active));
+    }
+    let rows = list_rows(
+        pool,
+        "webhook_subscriptions",
+        Some(&filters),
+        200,
+        0,
+        "created_at",
+        false,
+    )
+    .await?;
+
+    Ok(Json(
+        json!({ "data": rows.into_iter().map(masked).collect::<Vec<_>>() }),
+    ))
+}
+
+/// The signing secret is returned in full only here and on rotation.
+async fn create_subscription(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateWebhookSubscriptionInput>,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    assert_permission(
+        &state,
+        &user_id,
+        &payload.organization_id,
+        permissions::WEBHOOKS_MANAGE,
+    )
+    .await?;
+    let pool = db_pool(&state)?;
+
+    let url = validate_url(&state, &payload.url)?;
+    let mut record = Map::new();
+    record.insert(
+        "organization_id".to_string(),
+        Value::String(payload.organization_id.clone()),
+    );
+    record.insert("url".to_string(), Value::String(url));
+    if let Some(description) = non_empty_opt(payload.description.as_deref()) {
+        record.insert("description".to_string(), Value::String(description));
+    }
+    record.insert(
+        "event_types".to_string(),
+        normalize_event_types(&payload.event_types)?,
+    );
+    record.insert(
+        "secret".to_string(),
+        Value::String(webhook_verification::generate_secret()),
+    );
+    record.insert(
+        "created_by_user_id".to_string(),
+        Value::String(user_id.clone()),
+    );
+    let created = create_row(pool, "webhook_subscriptions", &record).await?;
+    let entity_id = value_str(&created, "id");
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&payload.organization_id),
+        Some(&user_id),
+        "create",
+        "webhook_subscriptions",
+        Some(&entity_id),
+        None,
+        Some(masked(created.clone())),
+    )
+    .await;
+
+    Ok((axum::http::StatusCode::CREATED, Json(created)))
+}
+
+async fn get_subscription(
+    State(state): State<AppState>,
+    Path(path): Path<WebhookSubscriptionPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let (_, record) = load_subscription(&state, &headers, &path.subscription_id).await?;
+    Ok(Json(masked(record)))
+}
+
+async fn update_subscription(
+    State(state): State<AppState>,
+    Path(path): Path<WebhookSubscriptionPath>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateWebhookSubscriptionInput>,
+) -> AppResult<Json<Value>> {
+    let (user_id, record) = load_subscription(&state, &headers, &path.subscription_id).await?;
+    let pool = db_pool(&state)?;
+    let org_id = value_str(&record, "organization_id");
+
+    let mut patch = Map::new();
+    if let Some(url) = payload.url.as_deref() {
+        patch.insert("url".to_string(), Value::String(validate_url(&state, url)?));
+    }
+    if let Some(description) = payload.description.as_deref() {
+        patch.insert(
+            "description".to_string(),
+            non_empty_opt(Some(description)).map_or(Value::Null, Value::String),
+        );
+    }
+    if let Some(event_types) = payload.event_types.as_deref() {
+        patch.insert(
+            "event_types".to_string(),
+            normalize_event_types(event_types)?,
+        );
+    }
+    if let Some(is_active) = payload.is_active {
+        patch.insert("is_active".to_string(), Value::Bool(is_active));
+        if is_active {
+            patch.insert("consecutive_failures".to_string(), json!(0));
+            patch.insert("disabled_at".to_string(), Value::Null);
+            patch.insert("disabled_reason".to_string(), Value::Null);
+        }
+    }
+    if patch.is_empty() {
+        return Ok(Json(masked(record)));
+    }
+
+    let updated = update_row(
+        pool,
+        "webhook_subscriptions",
+        &path.subscription_id,
+        &patch,
+        "id",
+    )
+    .await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "update",
+        "webhook_subscriptions",
+        Some(&path.subscription_id),
+        Some(masked(record)),
+        Some(masked(updated.clone())),
+    )
+    .await;
+
+    Ok(Json(masked(updated)))
+}
+
+async fn delete_subscription(
+    State(state): State<AppState>,
+    Path(path): Path<WebhookSubscriptionPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let (user_id, record) = load_subscription(&state, &headers, &path.subscription_id).await?;
+    let pool = db_pool(&state)?;
+    let org_id = value_str(&record, "organization_id");
+
+    delete_row(pool, "webhook_subscriptions", &path.subscription_id, "id").await?;
+    let record = masked(record);
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "delete",
+        "webhook_subscriptions",
+        Some(&path.subscription_id),
+        Some(record.clone()),
+        None,
+    )
+    .await;
+
+    Ok(Json(record))
+}
+
+/// Replace the signing secret. Receivers must switch at once: deliveries
+/// already queued are signed with the new secret when sent.
+async fn rotate_subscription_secret(
+    State(state): State<AppState>,
+    Path(path): Path<WebhookSubscriptionPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let (user_id, record) = load_subscription(&state, &headers, &path.subscription_id).await?;
+    let pool = db_pool(&state)?;
+    let org_id = value_str(&record, "organization_id");
+
+    let mut patch = Map::new();
+    patch.insert(
+        "secret".to_string(),
+        Value::String(webhook_verification::generate_secret()),
+    );
+    let updated = update_row(
+        pool,
+        "webhook_subscriptions",
+        &path.subscription_id,
+        &patch,
+        "id",
+    )
+    .await?;
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "rotate_secret",
+        "webhook_subscriptions",
+        Some(&path.subscription_id),
+        Some(masked(record)),
+        Some(masked(updated.clone())),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+/// Queue a `webhook.test` delivery to this subscription only.
+async fn send_test_event(
+    State(state): State<AppState>,
+    Path(path): Path<WebhookSubscriptionPath>,
+    headers: HeaderMap,
+) -> AppResult<impl IntoResponse> {
+    let (user_id, record) = load_subscription(&state, &headers, &path.subscription_id).await?;
+    let pool = db_pool(&state)?;
+    let org_id = value_str(&record, "organization_id");
+    if record.get("is_active") != Some(&Value::Bool(true)) {
+        return Err(AppError::Conflict(
+            "Enable the subscription before sending a test event.".to_string(),
+        ));
+    }
+
+    let event_id = uuid::Uuid::new_v4().to_string();
+    let mut delivery = Map::new();
+    delivery.insert("organization_id".to_string(), Value::String(org_id.clone()));
+    delivery.insert(
+        "subscription_id".to_string(),
+        Value::String(path.subscription_id.clone()),
+    );
+    delivery.insert("event_id".to_string(), Value::String(event_id.clone()));
+    delivery.insert("event_type".to_string(), json!("webhook.test"));
+    delivery.insert(
+        "payload".to_string(),
+        outbound_webhooks::envelope(
+            &event_id,
+            &org_id,
+            "webhook.test",
+            &json!({ "requested_by_user_id": user_id }),
+        ),
+    );
+    let created = create_row(pool, "webhook_deliveries", &delivery).await?;
+
+    Ok((axum::http::StatusCode::ACCEPTED, Json(created)))
+}
+
+// ── Deliveries ──────────────────────────────────────────────────────
+
+async fn list_deliveries(
+    State(state): State<AppState>,
+    Path(path): Path<WebhookSubscriptionPath>,
+    Query(query): Query<WebhookDeliveriesQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    load_subscription(&state, &headers, &path.subscription_id).await?;
+    let pool = db_pool(&state)?;
+
+    let mut filters = Map::new();
+    filters.insert(
+        "subscription_id".to_string(),
+        Value::String(path.subscription_id.clone()),
+    );
+    if let Some(status) = non_empty_opt(query.status.as_deref()) {
+        if !DELIVERY_STATUSES.contains(&status.as_str()) {
+            return Err(AppError::BadRequest(format!(
+                "status must be one of: {}.",
+                DELIVERY_STATUSES.join(", ")
+            )));
+        }
+        filters.insert("status".to_string(), Value::String(status));
+    }
+    if let Some(event_type) = non_empty_opt(query.event_type.as_deref()) {
+        filters.insert("event_type".to_string(), Value::String(event_type));
+    }
+
+    let rows = list_rows(
+        pool,
+        "webhook_deliveries",
+        Some(&filters),
+        clamp_limit_in_range(query.limit, 1, 500),
+        0,
+        "created_at",
+        false,
+    )
+    .await?;
+
+    Ok(Json(json!({ "data": rows })))
+}
+
+async fn get_delivery(
+    State(state): State<AppState>,
+    Path(path): Path<WebhookDeliveryPath>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let record = get_row(pool, "webhook_deliveries", &path.delivery_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::WEBHOOKS_MANAGE).await?;
+
+    Ok(Json(record))
+}
+
+/// Send a past delivery again, with the same event id and body.
+async fn replay_delivery(
+    State(state): State<AppState>,
+    Path(path): Path<WebhookDeliveryPath>,
+    headers: HeaderMap,
+) -> AppResult<impl IntoResponse> {
+    let user_id = require_user_id(&state, &headers).await?;
+    let pool = db_pool(&state)?;
+
+    let record = get_row(pool, "webhook_deliveries", &path.delivery_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(&state, &user_id, &org_id, permissions::WEBHOOKS_MANAGE).await?;
+
+    let status = value_str(&record, "status");
+    if matches!(status.as_str(), "pending" | "sending") {
+        return Err(AppError::Conflict(
+            "This delivery is still being attempted.".to_string(),
+        ));
+    }
+    let subscription = get_row(
+        pool,
+        "webhook_subscriptions",
+        &value_str(&record, "subscription_id"),
+        "id",
+    )
+    .await?;
+    if subscription.get("is_active") != Some(&Value::Bool(true)) {
+        return Err(AppError::Conflict(
+            "Enable the subscription before replaying deliveries.".to_string(),
+        ));
+    }
+
+    let created = outbound_webhooks::replay_delivery(pool, &record).await?;
+    let entity_id = value_str(&created, "id");
+
+    write_audit_log(
+        state.db_pool.as_ref(),
+        Some(&org_id),
+        Some(&user_id),
+        "replay",
+        "webhook_deliveries",
+        Some(&entity_id),
+        None,
+        Some(created.clone()),
+    )
+    .await;
+
+    Ok((axum::http::StatusCode::ACCEPTED, Json(created)))
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────
+
+async fn load_subscription(
+    state: &AppState,
+    headers: &HeaderMap,
+    subscription_id: &str,
+) -> AppResult<(String, Value)> {
+    let user_id = require_user_id(state, headers).await?;
+    let pool = db_pool(state)?;
+
+    let record = get_row(pool, "webhook_subscriptions", subscription_id, "id").await?;
+    let org_id = value_str(&record, "organization_id");
+    assert_permission(state, &user_id, &org_id, permissions::WEBHOOKS_MANAGE).await?;
+    Ok((user_id, record))
+}
+
+fn validate_url(state: &AppState, url: &str) -> AppResult<String> {
+    outbound_webhooks::validate_target_url(url, !state.config.is_production())
+        .map_err(AppError::BadRequest)?;
+    Ok(url.trim().to_string())
+}
+
+fn normalize_event_types(event_types: &[String]) -> AppResult<Value> {
+    let mut normalized: Vec<String> = Vec::new();
+    for event_type in event_types {
+        let event_type = event_type.trim();
+        if event_type.is_empty() {
+            continue;
+        }
+        if !event_type
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '*'))
+        {
+            return Err(AppError::BadRequest(format!(
+                "Invalid event type '{event_type}'."
+            )));
+        }
+        if !normalized.iter().any(|existing| existing == event_type) {
+            normalized.push(event_type.to_string());
+        }
+    }
+    Ok(json!(normalized))
+}
+
+/// Subscriptions are shown without their signing secret.
+fn masked(mut row: Value) -> Value {
+    if let Some(obj) = row.as_object_mut() {
+        obj.remove("secret");
+    }
+    row
+}
+
+fn db_pool(state: &AppState) -> AppResult<&sqlx::PgPool> {
+    state.db_pool.as_ref().ok_or_else(|| {
+        AppError::Dependency("Database is not configured. Set DATABASE_URL.".to_string())
+    })
+}
+
+fn value_str(row: &Value, key: &str) -> String {
+    row.as_object()
+        .and_then(|obj| obj.get(key))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+fn non_empty_opt(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+}
@@ -12,13 +12,11 @@ use crate::{
     error::{AppError, AppResult},
     repository::table_service::{create_row, delete_row, get_row, list_rows, update_row},
     schemas::clamp_limit_in_range,
-    services::{audit::write_audit_log, workflows::process_workflow_jobs},
+    services::{audit::write_audit_log, permissions, workflows::process_workflow_jobs},
     state::AppState,
-    tenancy::{assert_org_member, assert_org_role},
+    tenancy::{assert_org_member, assert_permission},
 };
 
-const WORKFLOW_EDIT_ROLES: &[&str] = &["owner_admin", "operator"];
-
 #[derive(Debug, Clone, Copy, Serialize)]
 struct TriggerMetadata {
     value: &'static str,
@@ -401,11 +399,11 @@ async fn create_workflow_rule(
     Json(payload): Json<CreateWorkflowRuleInput>,
 ) -> AppResult<impl IntoResponse> {
     let user_id = require_user_id(&state, &headers).await?;
-    assert_org_role(
+    assert_permission(
         &state,
         &user_id,
         &payload.organization_id,
-        WORKFLOW_EDIT_ROLES,
+        permissions::WORKFLOWS_EDIT,
     )
     .await?;
     let pool = db_pool(&state)?;
@@ -460,7 +458,7 @@ async fn update_workflow_rule(
 
     let existing = get_row(pool, "workflow_rules", &path.rule_id, "id").await?;
     let org_id = val_str(&existing, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, WORKFLOW_EDIT_ROLES).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::WORKFLOWS_EDIT).await?;
 
     let mut patch = Map::new();
     if let Some(name) = payload.name {
@@ -513,7 +511,7 @@ async fn delete_workflow_rule(
 
     let record = get_row(pool, "workflow_rules", &path.rule_id, "id").await?;
     let org_id = val_str(&record, "organization_id");
-    assert_org_role(&state, &user_id, &org_id, WORKFLOW_EDIT_ROLES).await?;
+    assert_permission(&state, &user_id, &org_id, permissions::WORKFLOWS_EDIT).await?;
 
     delete_row(pool, "workflow_rules", &path.rule_id, "id").await?;
 
@@ -78,6 +78,8 @@ pub struct UpdateOrganizationInput {
     pub name: Option<String>,
     pub legal_name: Option<String>,
     pub ruc: Option<String>,
+    pub timbrado_number: Option<String>,
+    pub timbrado_valid_until: Option<String>,
     pub profile_type: Option<String>,
     pub default_currency: Option<String>,
     pub timezone: Option<String>,
@@ -116,6 +118,9 @@ pub struct CreateOrganizationMemberInput {
 pub struct UpdateOrganizationMemberInput {
     pub role: Option<String>,
     pub is_primary: Option<bool>,
+    /// A custom role replaces the built-in role's permissions; an empty
+    /// string removes it.
+    pub custom_role_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, serde::Serialize)]
@@ -146,6 +151,15 @@ pub struct CreatePropertyInput {
     pub shared_wifi_password: Option<String>,
     pub asset_owner_organization_id: Option<String>,
     pub asset_owner_name: Option<String>,
+    pub asset_owner_ruc: Option<String>,
+    pub asset_owner_bank_name: Option<String>,
+    pub asset_owner_bank_account_number: Option<String>,
+    /// `checking` or `savings`.
+    pub asset_owner_bank_account_type: Option<String>,
+    /// Operating reserve statements hold back until it is reached.
+    pub reserve_target_amount: Option<f64>,
+    /// Most a single statement withholds for the reserve.
+    pub reserve_top_up_cap: Option<f64>,
 }
 
 #[derive(Debug, Clone, Deserialize, serde::Serialize)]
@@ -171,6 +185,15 @@ pub struct UpdatePropertyInput {
     pub shared_wifi_password: Option<String>,
     pub asset_owner_organization_id: Option<String>,
     pub asset_owner_name: Option<String>,
+    pub asset_owner_ruc: Option<String>,
+    pub asset_owner_bank_name: Option<String>,
+    pub asset_owner_bank_account_number: Option<String>,
+    /// `checking` or `savings`.
+    pub asset_owner_bank_account_type: Option<String>,
+    /// Operating reserve statements hold back until it is reached.
+    pub reserve_target_amount: Option<f64>,
+    /// Most a single statement withholds for the reserve.
+    pub reserve_top_up_cap: Option<f64>,
 }
 
 #[derive(Debug, Clone, Deserialize, serde::Serialize)]
@@ -731,6 +754,7 @@ pub struct CreateExpenseInput {
     pub vendor_name: Option<String>,
     pub invoice_number: Option<String>,
     pub invoice_ruc: Option<String>,
+    pub invoice_timbrado: Option<String>,
     pub receipt_url: String,
     pub notes: Option<String>,
     #[serde(default = "default_false")]
@@ -752,6 +776,7 @@ pub struct UpdateExpenseInput {
     pub vendor_name: Option<String>,
     pub invoice_number: Option<String>,
     pub invoice_ruc: Option<String>,
+    pub invoice_timbrado: Option<String>,
     pub receipt_url: Option<String>,
     pub notes: Option<String>,
     pub iva_applicable: Option<bool>,
@@ -782,6 +807,7 @@ pub struct CreateLeaseInput {
     pub tenant_full_name: String,
     pub tenant_email: Option<String>,
     pub tenant_phone_e164: Option<String>,
+    pub tenant_ruc: Option<String>,
     #[serde(default = "default_lease_status_draft")]
     pub lease_status: String,
     pub starts_on: String,
@@ -819,6 +845,7 @@ pub struct UpdateLeaseInput {
     pub tenant_full_name: Option<String>,
     pub tenant_email: Option<String>,
     pub tenant_phone_e164: Option<String>,
+    pub tenant_ruc: Option<String>,
     pub lease_status: Option<String>,
     pub starts_on: Option<String>,
     pub ends_on: Option<String>,
@@ -854,6 +881,9 @@ pub struct CreateCollectionInput {
 
 #[derive(Debug, Clone, Deserialize, serde::Serialize)]
 pub struct MarkCollectionPaidInput {
+    /// Amount received; defaults to the remaining balance. Less than the
+    /// balance records a partial payment, more leaves credit on the lease.
+    pub amount: Option<f64>,
     pub payment_method: Option<String>,
     pub payment_reference: Option<String>,
     pub paid_at: Option<String>,
@@ -893,6 +923,24 @@ pub struct ReservationsOverviewQuery {
     pub offset: i64,
 }
 
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CalendarConflictsQuery {
+    pub org_id: String,
+    pub unit_id: Option<String>,
+    #[serde(default = "default_false")]
+    pub include_dismissed: bool,
+    #[serde(default = "default_limit_100")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct DismissCalendarConflictInput {
+    pub organization_id: String,
+    pub first_id: String,
+    pub second_id: String,
+    pub note: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, serde::Serialize)]
 pub struct CalendarAvailabilityQuery {
     pub org_id: String,
@@ -911,6 +959,49 @@ pub struct CalendarBlocksQuery {
     pub limit: i64,
 }
 
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct StayRestrictionsQuery {
+    pub org_id: String,
+    pub unit_id: Option<String>,
+    #[serde(default = "default_false")]
+    pub include_inactive: bool,
+    #[serde(default = "default_limit_200")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CreateStayRestrictionInput {
+    pub organization_id: String,
+    pub unit_id: String,
+    pub starts_on: Option<String>,
+    pub ends_on: Option<String>,
+    #[serde(default)]
+    pub days_of_week: Vec<i16>,
+    pub min_nights: Option<i32>,
+    pub max_nights: Option<i32>,
+    #[serde(default = "default_false")]
+    pub closed_to_arrival: bool,
+    #[serde(default = "default_false")]
+    pub closed_to_departure: bool,
+    #[serde(default = "default_true")]
+    pub allow_gap_fill: bool,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct UpdateStayRestrictionInput {
+    pub starts_on: Option<String>,
+    pub ends_on: Option<String>,
+    pub days_of_week: Option<Vec<i16>>,
+    pub min_nights: Option<i32>,
+    pub max_nights: Option<i32>,
+    pub closed_to_arrival: Option<bool>,
+    pub closed_to_departure: Option<bool>,
+    pub allow_gap_fill: Option<bool>,
+    pub note: Option<String>,
+    pub is_active: Option<bool>,
+}
+
 #[derive(Debug, Clone, Deserialize, serde::Serialize)]
 pub struct TasksQuery {
     pub org_id: String,
@@ -1031,6 +1122,11 @@ pub struct BlockPath {
     pub block_id: String,
 }
 
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct StayRestrictionPath {
+    pub restriction_id: String,
+}
+
 #[derive(Debug, Clone, Deserialize, serde::Serialize)]
 pub struct TaskPath {
     pub task_id: String,
@@ -1156,6 +1252,12 @@ pub struct IntegrationEventsQuery {
     pub limit: i64,
 }
 
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct IntegrationSyncHistoryQuery {
+    #[serde(default = "default_limit_50")]
+    pub limit: i64,
+}
+
 #[derive(Debug, Clone, Deserialize, serde::Serialize)]
 pub struct AuditLogsQuery {
     pub org_id: String,
@@ -1185,6 +1287,26 @@ pub struct IcalPath {
     pub token: String,
 }
 
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct FxPairPath {
+    /// `BASE-QUOTE`, e.g. `usd-pyg` or `brl-ars`.
+    pub pair: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct FxRateQuery {
+    pub date: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct FxBackfillInput {
+    pub organization_id: String,
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub from: String,
+    pub to: String,
+}
+
 #[derive(Debug, Clone, Deserialize, serde::Serialize)]
 pub struct ApplicationsQuery {
     pub org_id: String,
@@ -1268,8 +1390,9 @@ pub struct CreateOwnerStatementInput {
     pub organization_id: String,
     pub period_start: String,
     pub period_end: String,
-    #[serde(default = "default_currency_pyg")]
-    pub currency: String,
+    /// Reporting currency; defaults to the property owner's, then the
+    /// organization's default currency.
+    pub currency: Option<String>,
     pub property_id: Option<String>,
     pub unit_id: Option<String>,
 }
@@ -1279,6 +1402,13 @@ pub struct OwnerStatementPath {
     pub statement_id: String,
 }
 
+/// Body for endpoints that render a stored PDF. `lang` is `es` or `en` and
+/// defaults to the organization's document language.
+#[derive(Debug, Clone, Default, Deserialize, serde::Serialize)]
+pub struct RenderPdfInput {
+    pub lang: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, serde::Serialize)]
 pub struct OwnerSummaryQuery {
     pub org_id: String,
@@ -1288,6 +1418,8 @@ pub struct OwnerSummaryQuery {
     pub to_date: String,
     pub property_id: Option<String>,
     pub unit_id: Option<String>,
+    /// Reporting currency; defaults to the property owner's currency.
+    pub currency: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, serde::Serialize)]
@@ -1695,6 +1827,748 @@ pub struct UpdateContractTemplateInput {
 #[derive(Debug, Clone, Deserialize, serde::Serialize)]
 pub struct RenderContractInput {
     pub lease_id: String,
+    /// `text` (default) returns the rendered body; `pdf` also stores a PDF
+    /// and links it to the lease as a document.
+    pub format: Option<String>,
+    pub lang: Option<String>,
+}
+
+// ===== Bank Statement Imports =====
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct BankImportProfilesQuery {
+    pub org_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct BankImportProfilePath {
+    pub profile_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CreateBankImportProfileInput {
+    pub organization_id: String,
+    pub name: String,
+    pub bank_name: Option<String>,
+    pub delimiter: Option<String>,
+    pub header_row: Option<i32>,
+    pub date_column: String,
+    pub date_format: Option<String>,
+    pub description_column: Option<String>,
+    pub amount_column: Option<String>,
+    pub credit_column: Option<String>,
+    pub debit_column: Option<String>,
+    pub reference_column: Option<String>,
+    pub counterparty_column: Option<String>,
+    pub external_id_column: Option<String>,
+    pub currency_column: Option<String>,
+    pub decimal_separator: Option<String>,
+    pub default_currency: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct UpdateBankImportProfileInput {
+    pub name: Option<String>,
+    pub bank_name: Option<String>,
+    pub delimiter: Option<String>,
+    pub header_row: Option<i32>,
+    pub date_column: Option<String>,
+    pub date_format: Option<String>,
+    pub description_column: Option<String>,
+    pub amount_column: Option<String>,
+    pub credit_column: Option<String>,
+    pub debit_column: Option<String>,
+    pub reference_column: Option<String>,
+    pub counterparty_column: Option<String>,
+    pub external_id_column: Option<String>,
+    pub currency_column: Option<String>,
+    pub decimal_separator: Option<String>,
+    pub default_currency: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct BankImportsQuery {
+    pub org_id: String,
+    pub status: Option<String>,
+    #[serde(default = "default_limit_100")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct BankImportPath {
+    pub import_id: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, serde::Serialize)]
+pub struct CommitBankImportInput {
+    /// Run auto-reconciliation over the committed rows (default true).
+    pub reconcile: Option<bool>,
+    /// Limit matching to collections due in this `YYYY-MM` month.
+    pub period_month: Option<String>,
+}
+
+// ===== Late Fees =====
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct LateFeePoliciesQuery {
+    pub org_id: String,
+    pub lease_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct LateFeePolicyPath {
+    pub policy_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CreateLateFeePolicyInput {
+    pub organization_id: String,
+    /// Omit for the organization-wide default policy.
+    pub lease_id: Option<String>,
+    pub fee_type: String,
+    pub amount: f64,
+    pub grace_days: Option<i32>,
+    pub max_fee_amount: Option<f64>,
+    pub max_fee_percent: Option<f64>,
+    pub is_active: Option<bool>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct UpdateLateFeePolicyInput {
+    pub fee_type: Option<String>,
+    pub amount: Option<f64>,
+    pub grace_days: Option<i32>,
+    pub max_fee_amount: Option<f64>,
+    pub max_fee_percent: Option<f64>,
+    pub is_active: Option<bool>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct LateFeesQuery {
+    pub org_id: String,
+    pub lease_id: Option<String>,
+    pub status: Option<String>,
+    #[serde(default = "default_limit_100")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct LateFeePath {
+    pub charge_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct WaiveLateFeeInput {
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct AdjustLateFeeInput {
+    pub amount: f64,
+    pub reason: String,
+}
+
+// ===== Lease Payments =====
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct LeasePaymentsQuery {
+    pub org_id: String,
+    pub lease_id: Option<String>,
+    pub source: Option<String>,
+    #[serde(default = "default_limit_100")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct LeasePaymentPath {
+    pub payment_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CreateLeasePaymentInput {
+    pub organization_id: String,
+    pub lease_id: String,
+    pub amount: f64,
+    /// Defaults to the lease currency.
+    pub currency: Option<String>,
+    pub payment_method: Option<String>,
+    pub received_at: Option<String>,
+    pub reference: Option<String>,
+    pub notes: Option<String>,
+    /// Collections to settle first; the rest is allocated oldest-first.
+    #[serde(default)]
+    pub collection_ids: Vec<String>,
+}
+
+// ===== General Ledger =====
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct GlAccountsQuery {
+    pub org_id: String,
+    pub account_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CreateGlAccountInput {
+    pub organization_id: String,
+    pub code: String,
+    pub name: String,
+    pub account_type: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct JournalEntriesQuery {
+    pub org_id: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub source_type: Option<String>,
+    pub source_id: Option<String>,
+    #[serde(default = "default_limit_100")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct JournalEntryPath {
+    pub entry_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct JournalLineInput {
+    pub account_id: String,
+    #[serde(default)]
+    pub debit: f64,
+    #[serde(default)]
+    pub credit: f64,
+    pub property_id: Option<String>,
+    pub lease_id: Option<String>,
+    pub memo: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CreateJournalEntryInput {
+    pub organization_id: String,
+    pub entry_date: String,
+    pub description: String,
+    pub currency: Option<String>,
+    pub lines: Vec<JournalLineInput>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct LedgerReportQuery {
+    pub org_id: String,
+    /// Defaults to today.
+    pub as_of: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct OwnerLedgerQuery {
+    pub org_id: String,
+    pub property_id: Option<String>,
+    /// All properties of this owner (`properties.asset_owner_name`).
+    pub owner_name: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct AccountingExportPath {
+    pub format: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct AccountingExportQuery {
+    pub org_id: String,
+    pub from: String,
+    pub to: String,
+    /// Journal exports only; defaults to the organization's currency.
+    pub currency: Option<String>,
+}
+
+// ===== Electronic Invoicing (SIFEN) =====
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct SifenSettingsQuery {
+    pub org_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct UpdateSifenSettingsInput {
+    pub organization_id: String,
+    /// `mock`, `test` or `production`.
+    pub environment: Option<String>,
+    pub establishment: Option<String>,
+    pub expedition_point: Option<String>,
+    pub next_document_number: Option<i32>,
+    pub timbrado_valid_from: Option<String>,
+    pub taxpayer_type: Option<i16>,
+    pub fiscal_address: Option<String>,
+    pub house_number: Option<String>,
+    pub department_code: Option<i16>,
+    pub department_name: Option<String>,
+    pub city_code: Option<i32>,
+    pub city_name: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub activity_code: Option<String>,
+    pub activity_description: Option<String>,
+    pub csc_id: Option<String>,
+    pub csc: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct UploadSifenCertificateInput {
+    pub organization_id: String,
+    /// PKCS#12 (.p12/.pfx) file, base64-encoded, with its password.
+    pub pkcs12_base64: Option<String>,
+    pub password: Option<String>,
+    /// Alternatively, the certificate and private key as PEM.
+    pub certificate_pem: Option<String>,
+    pub private_key_pem: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct ElectronicDocumentsQuery {
+    pub org_id: String,
+    pub status: Option<String>,
+    pub collection_id: Option<String>,
+    pub owner_statement_id: Option<String>,
+    #[serde(default = "default_limit_100")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct ElectronicDocumentPath {
+    pub document_id: String,
+}
+
+// ===== Owner Payouts =====
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct OwnerPayoutBatchesQuery {
+    pub org_id: String,
+    pub status: Option<String>,
+    #[serde(default = "default_limit_100")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CreateOwnerPayoutBatchInput {
+    pub organization_id: String,
+    /// Defaults to every finalized, unpaid statement in `currency`.
+    pub statement_ids: Option<Vec<String>>,
+    pub currency: Option<String>,
+    pub scheduled_for: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct OwnerPayoutBatchPath {
+    pub batch_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct FailOwnerPayoutInput {
+    pub reason: String,
+    /// Fail only these items' transfers; the whole batch when omitted.
+    pub item_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct OwnerPayoutFileQuery {
+    /// Bank layout to render; the generic CSV when omitted.
+    pub layout_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct PayoutBankLayoutsQuery {
+    pub org_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CreatePayoutBankLayoutInput {
+    pub organization_id: String,
+    pub name: String,
+    pub bank_name: Option<String>,
+    pub file_extension: Option<String>,
+    /// `crlf` (default) or `lf`.
+    pub line_ending: Option<String>,
+    pub header_fields: Option<serde_json::Value>,
+    pub detail_fields: serde_json::Value,
+    pub trailer_fields: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct UpdatePayoutBankLayoutInput {
+    pub name: Option<String>,
+    pub bank_name: Option<String>,
+    pub file_extension: Option<String>,
+    pub line_ending: Option<String>,
+    pub header_fields: Option<serde_json::Value>,
+    pub detail_fields: Option<serde_json::Value>,
+    pub trailer_fields: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct PayoutBankLayoutPath {
+    pub layout_id: String,
+}
+
+// ===== Owner Reserves & Contributions =====
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct OwnerReservesQuery {
+    pub org_id: String,
+    pub property_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct OwnerReservePath {
+    pub property_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct OwnerReserveEntriesQuery {
+    #[serde(default = "default_limit_100")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CreateOwnerReserveAdjustmentInput {
+    /// Positive funds the reserve from the owner balance; negative releases
+    /// it back to the owner.
+    pub amount: f64,
+    pub currency: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct OwnerContributionsQuery {
+    pub org_id: String,
+    pub status: Option<String>,
+    pub property_id: Option<String>,
+    #[serde(default = "default_limit_100")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CreateOwnerContributionInput {
+    pub organization_id: String,
+    /// Defaults to the statement's property.
+    pub property_id: Option<String>,
+    /// Statement whose carried-forward deficit the contribution covers.
+    pub owner_statement_id: Option<String>,
+    /// Defaults to the statement's deficit.
+    pub amount: Option<f64>,
+    pub currency: Option<String>,
+    pub reason: Option<String>,
+    pub due_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct OwnerContributionPath {
+    pub contribution_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct MarkOwnerContributionPaidInput {
+    /// Defaults to the requested amount.
+    pub amount: Option<f64>,
+    pub payment_reference: Option<String>,
+}
+
+// ===== Management Fee Schedules =====
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct ManagementFeeSchedulesQuery {
+    pub org_id: String,
+    pub property_id: Option<String>,
+    pub is_active: Option<bool>,
+    #[serde(default = "default_limit_100")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct ManagementFeeSchedulePath {
+    pub schedule_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CreateManagementFeeScheduleInput {
+    pub organization_id: String,
+    /// Omit for the organization default.
+    pub property_id: Option<String>,
+    pub name: String,
+    #[serde(default = "default_currency_pyg")]
+    pub currency: String,
+    pub effective_from: String,
+    pub effective_to: Option<String>,
+    pub rules: serde_json::Value,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct UpdateManagementFeeScheduleInput {
+    pub name: Option<String>,
+    pub effective_from: Option<String>,
+    pub effective_to: Option<String>,
+    pub rules: Option<serde_json::Value>,
+    pub is_active: Option<bool>,
+    pub notes: Option<String>,
+}
+
+// ===== Lease Deposits =====
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct LeaseDepositsQuery {
+    pub org_id: String,
+    pub lease_id: Option<String>,
+    pub status: Option<String>,
+    #[serde(default = "default_limit_100")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct LeaseDepositPath {
+    pub deposit_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct LeaseDepositDeductionPath {
+    pub deposit_id: String,
+    pub deduction_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CreateLeaseDepositInput {
+    pub lease_id: String,
+    /// Defaults to the lease's security deposit.
+    pub amount_required: Option<f64>,
+    /// Annual simple interest owed to the tenant, as a fraction.
+    pub interest_rate: Option<f64>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct ReceiveLeaseDepositInput {
+    /// Defaults to the required amount, or the linked payment's amount.
+    pub amount: Option<f64>,
+    pub received_at: Option<String>,
+    /// The lease payment that paid the deposit, when it was recorded as one.
+    pub lease_payment_id: Option<String>,
+    pub reference: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct AccrueLeaseDepositInterestInput {
+    /// Defaults to today.
+    pub as_of: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CompareLeaseDepositInspectionsInput {
+    pub move_out_report_id: String,
+    /// Defaults to the unit's latest move-in inspection.
+    pub baseline_report_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CreateLeaseDepositDeductionInput {
+    pub category: String,
+    pub description: String,
+    pub amount: f64,
+    pub room: Option<String>,
+    pub maintenance_request_id: Option<String>,
+    pub inspection_report_id: Option<String>,
+    /// Defaults to the maintenance request's photos.
+    pub photo_urls: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct SettleLeaseDepositInput {
+    /// `original` refunds through the provider that took the deposit
+    /// payment; `bank_transfer` records a transfer made outside the app.
+    /// Defaults to `original` when the payment allows it.
+    pub refund_method: Option<String>,
+    pub refund_reference: Option<String>,
+}
+
+// ===== Webhook Signing Secrets =====
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct WebhookSigningSecretsQuery {
+    pub org_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct WebhookSigningSecretPath {
+    pub secret_id: String,
+}
+
+/// Creates the provider's secret, or rotates it when one exists: the old
+/// secret keeps verifying for a grace period.
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct RotateWebhookSigningSecretInput {
+    pub organization_id: String,
+    pub provider: String,
+    /// Allowed clock drift for signed timestamps; defaults to 300.
+    pub tolerance_seconds: Option<i32>,
+}
+
+// ===== Webhook Subscriptions =====
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct WebhookSubscriptionsQuery {
+    pub org_id: String,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct WebhookSubscriptionPath {
+    pub subscription_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct WebhookDeliveryPath {
+    pub delivery_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CreateWebhookSubscriptionInput {
+    pub organization_id: String,
+    pub url: String,
+    pub description: Option<String>,
+    /// Trigger names (`payment_received`), `prefix*` patterns or `*`;
+    /// empty subscribes to every event.
+    #[serde(default)]
+    pub event_types: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct UpdateWebhookSubscriptionInput {
+    pub url: Option<String>,
+    pub description: Option<String>,
+    pub event_types: Option<Vec<String>>,
+    /// Re-enabling resets the failure count.
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct WebhookDeliveriesQuery {
+    pub status: Option<String>,
+    pub event_type: Option<String>,
+    #[serde(default = "default_limit_100")]
+    pub limit: i64,
+}
+
+// ===== API Keys =====
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct ApiKeysQuery {
+    pub org_id: String,
+    #[serde(default)]
+    pub include_revoked: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct ApiKeyPath {
+    pub key_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CreateApiKeyInput {
+    pub organization_id: String,
+    pub name: String,
+    /// Any of `read_only`, `finance`, `operations`, `agents`.
+    pub scopes: Vec<String>,
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct UpdateApiKeyInput {
+    pub name: Option<String>,
+    pub scopes: Option<Vec<String>>,
+    /// An empty string clears the expiry.
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, serde::Serialize)]
+pub struct RotateApiKeyInput {
+    /// Hours the previous token keeps working (default 24, max 168).
+    pub grace_hours: Option<i64>,
+}
+
+// ===== Roles & Permissions =====
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct RolesQuery {
+    pub org_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct RolePath {
+    pub role_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CreateRoleInput {
+    pub organization_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// `resource.action` or `resource.*` entries from `GET /permissions`.
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    /// Limits the role to these property groups; empty means all properties.
+    #[serde(default)]
+    pub property_group_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct UpdateRoleInput {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub permissions: Option<Vec<String>>,
+    pub property_group_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct PropertyGroupsQuery {
+    pub org_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct PropertyGroupPath {
+    pub group_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct CreatePropertyGroupInput {
+    pub organization_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub property_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct UpdatePropertyGroupInput {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub property_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct PropertyGroupMemberPath {
+    pub group_id: String,
+    pub user_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct AddPropertyGroupMemberInput {
+    pub user_id: String,
 }
 
 // ===== Properties Bulk Import =====
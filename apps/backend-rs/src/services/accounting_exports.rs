@@ -0,0 +1,846 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use sqlx::{PgPool, Row};
+
+use crate::{
+    error::{AppError, AppResult},
+    services::fx,
+};
+
+/// SET's generic identifier for sales to an unidentified final consumer.
+const INNOMINADO_RUC: &str = "44444401-7";
+const INNOMINADO_NAME: &str = "SIN NOMBRE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// QuickBooks Desktop IIF general journal.
+    QuickbooksIif,
+    /// QuickBooks Online journal entry import.
+    QuickbooksCsv,
+    /// Xero manual journal import.
+    XeroCsv,
+    /// SET Libro de Ventas.
+    SetSales,
+    /// SET Libro de Compras.
+    SetPurchases,
+}
+
+impl ExportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "quickbooks_iif" | "iif" => Some(Self::QuickbooksIif),
+            "quickbooks_csv" => Some(Self::QuickbooksCsv),
+            "xero_csv" | "xero" => Some(Self::XeroCsv),
+            "set_sales" | "libro_ventas" => Some(Self::SetSales),
+            "set_purchases" | "libro_compras" => Some(Self::SetPurchases),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::QuickbooksIif => "quickbooks_iif",
+            Self::QuickbooksCsv => "quickbooks_csv",
+            Self::XeroCsv => "xero_csv",
+            Self::SetSales => "set_sales",
+            Self::SetPurchases => "set_purchases",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::QuickbooksIif => "text/plain; charset=utf-8",
+            _ => "text/csv; charset=utf-8",
+        }
+    }
+
+    pub fn file_name(self, tax_id: &str, from: NaiveDate, to: NaiveDate) -> String {
+        let (prefix, extension) = match self {
+            Self::QuickbooksIif => ("quickbooks_journal", "iif"),
+            Self::QuickbooksCsv => ("quickbooks_journal", "csv"),
+            Self::XeroCsv => ("xero_manual_journal", "csv"),
+            Self::SetSales => ("libro_ventas", "csv"),
+            Self::SetPurchases => ("libro_compras", "csv"),
+        };
+        let tax_id: String = tax_id
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+            .collect();
+        if tax_id.is_empty() {
+            format!("{prefix}_{from}_{to}.{extension}")
+        } else {
+            format!("{prefix}_{tax_id}_{from}_{to}.{extension}")
+        }
+    }
+}
+
+// ---------- Journal exports (QuickBooks, Xero) ----------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportLine {
+    pub account_code: String,
+    pub account_name: String,
+    pub account_type: String,
+    pub system_key: Option<String>,
+    pub debit: f64,
+    pub credit: f64,
+    pub property_name: Option<String>,
+    pub memo: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportEntry {
+    pub entry_id: String,
+    pub number: usize,
+    pub entry_date: NaiveDate,
+    pub description: String,
+    pub lines: Vec<ExportLine>,
+}
+
+/// General ledger entries in one currency between two dates. The ledger is
+/// what expenses, lease payments, deposits and owner statements post to, so
+/// the journal exports carry every event already reconciled into balanced
+/// entries.
+pub async fn load_journal(
+    pool: &PgPool,
+    org_id: &str,
+    currency: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> AppResult<Vec<ExportEntry>> {
+    let rows = sqlx::query(
+        "SELECT e.id::text AS entry_id, e.entry_date, e.description,
+                a.code, a.name AS account_name, a.account_type, a.system_key,
+                l.debit::float8 AS debit, l.credit::float8 AS credit,
+                p.name AS property_name, l.memo
+         FROM gl_journal_entries e
+         JOIN gl_journal_lines l ON l.entry_id = e.id
+         JOIN gl_accounts a ON a.id = l.account_id
+         LEFT JOIN properties p ON p.id = l.property_id
+         WHERE e.organization_id = $1::uuid
+           AND e.currency = $2
+           AND e.entry_date BETWEEN $3 AND $4
+         ORDER BY e.entry_date, e.created_at, e.id, l.debit DESC, l.created_at",
+    )
+    .bind(org_id)
+    .bind(currency)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load journal entries."))?;
+
+    let mut entries: Vec<ExportEntry> = Vec::new();
+    for row in &rows {
+        let entry_id: String = row.try_get("entry_id").unwrap_or_default();
+        if entries
+            .last()
+            .is_none_or(|entry| entry.entry_id != entry_id)
+        {
+            entries.push(ExportEntry {
+                number: entries.len() + 1,
+                entry_id,
+                entry_date: row.try_get("entry_date").unwrap_or(from),
+                description: row.try_get("description").unwrap_or_default(),
+                lines: Vec::new(),
+            });
+        }
+        if let Some(entry) = entries.last_mut() {
+            entry.lines.push(ExportLine {
+                account_code: row.try_get("code").unwrap_or_default(),
+                account_name: row.try_get("account_name").unwrap_or_default(),
+                account_type: row.try_get("account_type").unwrap_or_default(),
+                system_key: row.try_get("system_key").ok().flatten(),
+                debit: row.try_get("debit").unwrap_or(0.0),
+                credit: row.try_get("credit").unwrap_or(0.0),
+                property_name: row.try_get("property_name").ok().flatten(),
+                memo: row.try_get("memo").ok().flatten(),
+            });
+        }
+    }
+    Ok(entries)
+}
+
+/// QuickBooks Desktop IIF: the accounts used, then one general journal
+/// transaction per entry. Debits are positive, credits negative; the
+/// property goes in CLASS.
+pub fn render_iif(entries: &[ExportEntry]) -> String {
+    let mut out = String::new();
+    out.push_str("!ACCNT\tNAME\tACCNTTYPE\tACCNUM\n");
+    let mut seen: Vec<&str> = Vec::new();
+    for line in entries.iter().flat_map(|entry| &entry.lines) {
+        if seen.contains(&line.account_code.as_str()) {
+            continue;
+        }
+        seen.push(&line.account_code);
+        out.push_str(&format!(
+            "ACCNT\t{}\t{}\t{}\n",
+            iif_field(&line.account_name),
+            iif_account_type(line),
+            iif_field(&line.account_code)
+        ));
+    }
+
+    out.push_str("!TRNS\tTRNSID\tTRNSTYPE\tDATE\tACCNT\tCLASS\tAMOUNT\tDOCNUM\tMEMO\n");
+    out.push_str("!SPL\tSPLID\tTRNSTYPE\tDATE\tACCNT\tCLASS\tAMOUNT\tDOCNUM\tMEMO\n");
+    out.push_str("!ENDTRNS\n");
+    for entry in entries {
+        let date = entry.entry_date.format("%m/%d/%Y");
+        for (index, line) in entry.lines.iter().enumerate() {
+            let kind = if index == 0 { "TRNS" } else { "SPL" };
+            out.push_str(&format!(
+                "{kind}\t\tGENERAL JOURNAL\t{date}\t{}\t{}\t{:.2}\t{}\t{}\n",
+                iif_field(&line.account_name),
+                iif_field(line.property_name.as_deref().unwrap_or_default()),
+                line.debit - line.credit,
+                entry.number,
+                iif_field(line.memo.as_deref().unwrap_or(&entry.description)),
+            ));
+        }
+        out.push_str("ENDTRNS\n");
+    }
+    out
+}
+
+/// QuickBooks Online journal entry import.
+pub fn render_quickbooks_csv(entries: &[ExportEntry]) -> String {
+    let mut out = csv_row(&[
+        "JournalNo",
+        "JournalDate",
+        "AccountName",
+        "Debits",
+        "Credits",
+        "Description",
+        "Class",
+    ]);
+    for entry in entries {
+        let date = entry.entry_date.format("%m/%d/%Y").to_string();
+        for line in &entry.lines {
+            out.push_str(&csv_row(&[
+                &entry.number.to_string(),
+                &date,
+                &line.account_name,
+                &amount_or_blank(line.debit),
+                &amount_or_blank(line.credit),
+                line.memo.as_deref().unwrap_or(&entry.description),
+                line.property_name.as_deref().unwrap_or_default(),
+            ]));
+        }
+    }
+    out
+}
+
+/// Xero manual journal import. Amounts are signed (debit positive) and
+/// the property is tracked as a "Property" tracking category.
+pub fn render_xero_csv(entries: &[ExportEntry]) -> String {
+    let mut out = csv_row(&[
+        "*Narration",
+        "*Date",
+        "Description",
+        "*AccountCode",
+        "*TaxRate",
+        "*Amount",
+        "TrackingName1",
+        "TrackingOption1",
+    ]);
+    for entry in entries {
+        let date = entry.entry_date.format("%d/%m/%Y").to_string();
+        for line in &entry.lines {
+            let property = line.property_name.as_deref().unwrap_or_default();
+            out.push_str(&csv_row(&[
+                &entry.description,
+                &date,
+                line.memo.as_deref().unwrap_or_default(),
+                &line.account_code,
+                "Tax Exempt",
+                &format!("{:.2}", line.debit - line.credit),
+                if property.is_empty() { "" } else { "Property" },
+                property,
+            ]));
+        }
+    }
+    out
+}
+
+fn iif_account_type(line: &ExportLine) -> &'static str {
+    match (line.account_type.as_str(), line.system_key.as_deref()) {
+        (_, Some("trust_cash" | "operating_cash")) => "BANK",
+        ("asset", _) => "OCASSET",
+        ("liability", _) => "OCLIAB",
+        ("equity", _) => "EQUITY",
+        ("revenue", _) => "INC",
+        _ => "EXP",
+    }
+}
+
+fn iif_field(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c == '\t' || c == '\n' || c == '\r' {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+fn amount_or_blank(value: f64) -> String {
+    if value > 0.0 {
+        format!("{value:.2}")
+    } else {
+        String::new()
+    }
+}
+
+// ---------- SET books (Libro de Ventas / Libro de Compras) ----------
+
+/// A receipt amount split into the columns SET asks for. Taxed amounts
+/// include their IVA, as in the Marangatu comprobante registry.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct IvaSplit {
+    pub gravada_10: f64,
+    pub iva_10: f64,
+    pub gravada_5: f64,
+    pub iva_5: f64,
+    pub exenta: f64,
+}
+
+impl IvaSplit {
+    /// Split an IVA-inclusive `total` taxed at `rate` percent (10, 5 or 0).
+    pub fn from_total(total: f64, rate: u8) -> Self {
+        let total = round_pyg(total);
+        match rate {
+            10 => Self {
+                gravada_10: total,
+                iva_10: round_pyg(total / 11.0),
+                ..Self::default()
+            },
+            5 => Self {
+                gravada_5: total,
+                iva_5: round_pyg(total / 21.0),
+                ..Self::default()
+            },
+            _ => Self {
+                exenta: total,
+                ..Self::default()
+            },
+        }
+    }
+
+    pub fn total(&self) -> f64 {
+        self.gravada_10 + self.gravada_5 + self.exenta
+    }
+}
+
+/// IVA rate (10, 5 or 0) that best explains `iva` on `amount`, and the
+/// IVA-inclusive total. `amount` may be the net base (IVA added on top, as
+/// expenses record it by default) or already include the IVA.
+pub fn infer_iva(amount: f64, iva: f64) -> (u8, f64) {
+    if iva <= 0.0 || amount <= 0.0 {
+        return (0, amount);
+    }
+    let candidates = [
+        (10_u8, amount * 0.10, amount + iva),
+        (10, amount / 11.0, amount),
+        (5, amount * 0.05, amount + iva),
+        (5, amount / 21.0, amount),
+    ];
+    let (rate, _, total) = candidates
+        .iter()
+        .copied()
+        .min_by(|a, b| (a.1 - iva).abs().total_cmp(&(b.1 - iva).abs()))
+        .unwrap_or((10, iva, amount + iva));
+    (rate, total)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookRow {
+    pub date: NaiveDate,
+    pub counterparty_ruc: String,
+    pub counterparty_name: String,
+    pub timbrado: String,
+    pub document_number: String,
+    pub split: IvaSplit,
+    pub currency: String,
+    /// Rate used to convert into guaraníes; 1 for PYG.
+    pub fx_rate: f64,
+    pub source: &'static str,
+    pub source_id: String,
+}
+
+/// Issuer details printed at the top of the books.
+#[derive(Debug, Clone, Default)]
+pub struct Issuer {
+    pub ruc: String,
+    pub name: String,
+    pub timbrado: String,
+}
+
+pub async fn load_issuer(pool: &PgPool, org_id: &str) -> AppResult<Issuer> {
+    let row = sqlx::query(
+        "SELECT COALESCE(ruc, '') AS ruc, COALESCE(legal_name, name) AS name,
+                COALESCE(timbrado_number, '') AS timbrado
+         FROM organizations WHERE id = $1::uuid",
+    )
+    .bind(org_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load organization."))?
+    .ok_or_else(|| AppError::NotFound("Organization not found.".to_string()))?;
+    Ok(Issuer {
+        ruc: row.try_get("ruc").unwrap_or_default(),
+        name: row.try_get("name").unwrap_or_default(),
+        timbrado: row.try_get("timbrado").unwrap_or_default(),
+    })
+}
+
+/// Libro de Ventas: rent collected in the period (taxed like the lease's
+/// `tax_iva`) and management fees from finalized owner statements whose
+/// period ends in it (10%), numbered by their approved SIFEN invoice when
+/// there is one. Amounts are converted to guaraníes.
+pub async fn load_sales_book(
+    pool: &PgPool,
+    org_id: &str,
+    issuer: &Issuer,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> AppResult<Vec<BookRow>> {
+    let mut rates = RateCache::default();
+    let mut rows = Vec::new();
+
+    let collections = sqlx::query(
+        "SELECT cr.id::text AS id, cr.paid_at::date AS paid_on,
+                cr.amount_paid::float8 AS amount, cr.currency::text AS currency,
+                l.tenant_full_name, COALESCE(l.tenant_ruc, '') AS tenant_ruc,
+                (l.monthly_rent + l.service_fee_flat)::float8 AS taxable_base,
+                l.tax_iva::float8 AS tax_iva,
+                COALESCE(ed.establishment || '-' || ed.expedition_point || '-'
+                         || lpad(ed.document_number::text, 7, '0'), '') AS document_number
+         FROM collection_records cr
+         JOIN leases l ON l.id = cr.lease_id
+         LEFT JOIN electronic_documents ed
+           ON ed.collection_record_id = cr.id AND ed.status = 'approved'
+         WHERE cr.organization_id = $1::uuid
+           AND cr.status = 'paid'
+           AND cr.paid_at::date BETWEEN $2 AND $3
+         ORDER BY cr.paid_at, cr.id",
+    )
+    .bind(org_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load collections."))?;
+
+    for row in &collections {
+        let date: NaiveDate = row.try_get("paid_on").unwrap_or(from);
+        let currency: String = row.try_get("currency").unwrap_or_default();
+        let fx_rate = rates.pyg_rate(pool, &currency, date).await?;
+        let amount = row.try_get::<f64, _>("amount").unwrap_or(0.0) * fx_rate;
+        let (rate, _) = infer_iva(
+            row.try_get("taxable_base").unwrap_or(0.0),
+            row.try_get("tax_iva").unwrap_or(0.0),
+        );
+        let (ruc, name) = counterparty(
+            &row.try_get::<String, _>("tenant_ruc").unwrap_or_default(),
+            &row.try_get::<String, _>("tenant_full_name")
+                .unwrap_or_default(),
+        );
+        rows.push(BookRow {
+            date,
+            counterparty_ruc: ruc,
+            counterparty_name: name,
+            timbrado: issuer.timbrado.clone(),
+            document_number: row.try_get("document_number").unwrap_or_default(),
+            split: IvaSplit::from_total(amount, rate),
+            currency,
+            fx_rate,
+            source: "collection",
+            source_id: row.try_get("id").unwrap_or_default(),
+        });
+    }
+
+    let statements = sqlx::query(
+        "SELECT os.id::text AS id, os.period_end, os.currency::text AS currency,
+                (os.service_fees + os.collection_fees + os.management_fees)::float8 AS fees,
+                COALESCE(p.asset_owner_ruc, '') AS owner_ruc,
+                COALESCE(p.asset_owner_name, '') AS owner_name,
+                COALESCE(ed.establishment || '-' || ed.expedition_point || '-'
+                         || lpad(ed.document_number::text, 7, '0'), '') AS document_number
+         FROM owner_statements os
+         LEFT JOIN units u ON u.id = os.unit_id
+         LEFT JOIN properties p ON p.id = COALESCE(os.property_id, u.property_id)
+         LEFT JOIN electronic_documents ed
+           ON ed.owner_statement_id = os.id AND ed.status = 'approved'
+         WHERE os.organization_id = $1::uuid
+           AND os.status IN ('finalized', 'sent', 'paid')
+           AND os.period_end BETWEEN $2 AND $3
+           AND os.service_fees + os.collection_fees + os.management_fees > 0
+         ORDER BY os.period_end, os.id",
+    )
+    .bind(org_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load owner statements."))?;
+
+    for row in &statements {
+        let date: NaiveDate = row.try_get("period_end").unwrap_or(to);
+        let currency: String = row.try_get("currency").unwrap_or_default();
+        let fx_rate = rates.pyg_rate(pool, &currency, date).await?;
+        let fees = row.try_get::<f64, _>("fees").unwrap_or(0.0) * fx_rate;
+        let (ruc, name) = counterparty(
+            &row.try_get::<String, _>("owner_ruc").unwrap_or_default(),
+            &row.try_get::<String, _>("owner_name").unwrap_or_default(),
+        );
+        rows.push(BookRow {
+            date,
+            counterparty_ruc: ruc,
+            counterparty_name: name,
+            timbrado: issuer.timbrado.clone(),
+            document_number: row.try_get("document_number").unwrap_or_default(),
+            split: IvaSplit::from_total(fees, 10),
+            currency,
+            fx_rate,
+            source: "management_fee",
+            source_id: row.try_get("id").unwrap_or_default(),
+        });
+    }
+
+    rows.sort_by(|a, b| a.date.cmp(&b.date));
+    Ok(rows)
+}
+
+/// Libro de Compras: approved expenses dated in the period, with the
+/// supplier's RUC, timbrado and invoice number as entered on the expense.
+pub async fn load_purchases_book(
+    pool: &PgPool,
+    org_id: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> AppResult<Vec<BookRow>> {
+    let mut rates = RateCache::default();
+    let expenses = sqlx::query(
+        "SELECT id::text AS id, expense_date, amount::float8 AS amount,
+                currency::text AS currency, fx_rate_to_pyg::float8 AS fx_rate_to_pyg,
+                iva_applicable, iva_amount::float8 AS iva_amount,
+                COALESCE(vendor_name, '') AS vendor_name,
+                COALESCE(invoice_ruc, '') AS invoice_ruc,
+                COALESCE(invoice_number, '') AS invoice_number,
+                COALESCE(invoice_timbrado, '') AS invoice_timbrado
+         FROM expenses
+         WHERE organization_id = $1::uuid
+           AND approval_status = 'approved'
+           AND expense_date BETWEEN $2 AND $3
+         ORDER BY expense_date, created_at",
+    )
+    .bind(org_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load expenses."))?;
+
+    let mut rows = Vec::with_capacity(expenses.len());
+    for row in &expenses {
+        let date: NaiveDate = row.try_get("expense_date").unwrap_or(from);
+        let currency: String = row.try_get("currency").unwrap_or_default();
+        let fx_rate = match row
+            .try_get::<Option<f64>, _>("fx_rate_to_pyg")
+            .ok()
+            .flatten()
+        {
+            Some(rate) if currency != "PYG" && rate > 0.0 => rate,
+            _ => rates.pyg_rate(pool, &currency, date).await?,
+        };
+        let amount: f64 = row.try_get("amount").unwrap_or(0.0);
+        let iva = if row.try_get::<bool, _>("iva_applicable").unwrap_or(false) {
+            row.try_get("iva_amount").unwrap_or(0.0)
+        } else {
+            0.0
+        };
+        let (rate, total) = infer_iva(amount, iva);
+        rows.push(BookRow {
+            date,
+            counterparty_ruc: row.try_get("invoice_ruc").unwrap_or_default(),
+            counterparty_name: row.try_get("vendor_name").unwrap_or_default(),
+            timbrado: row.try_get("invoice_timbrado").unwrap_or_default(),
+            document_number: row.try_get("invoice_number").unwrap_or_default(),
+            split: IvaSplit::from_total(total * fx_rate, rate),
+            currency,
+            fx_rate,
+            source: "expense",
+            source_id: row.try_get("id").unwrap_or_default(),
+        });
+    }
+    Ok(rows)
+}
+
+/// CSV laid out like the SET comprobante registry, one receipt per row,
+/// preceded by the issuer and period and followed by a totals row.
+pub fn render_set_book(
+    title: &str,
+    issuer: &Issuer,
+    from: NaiveDate,
+    to: NaiveDate,
+    rows: &[BookRow],
+) -> String {
+    let mut out = csv_row(&[title]);
+    out.push_str(&csv_row(&["RUC", &issuer.ruc]));
+    out.push_str(&csv_row(&["Razón social", &issuer.name]));
+    out.push_str(&csv_row(&[
+        "Periodo",
+        &from.format("%d/%m/%Y").to_string(),
+        &to.format("%d/%m/%Y").to_string(),
+    ]));
+    out.push_str(&csv_row(&[
+        "Fecha",
+        "Tipo de identificación",
+        "RUC / Documento",
+        "Nombre o razón social",
+        "Tipo de comprobante",
+        "Timbrado",
+        "Número de comprobante",
+        "Gravada 10% (IVA incluido)",
+        "IVA 10%",
+        "Gravada 5% (IVA incluido)",
+        "IVA 5%",
+        "Exenta",
+        "Total",
+        "Moneda",
+        "Tipo de cambio",
+        "Origen",
+        "Referencia",
+    ]));
+
+    let mut totals = IvaSplit::default();
+    for row in rows {
+        totals.gravada_10 += row.split.gravada_10;
+        totals.iva_10 += row.split.iva_10;
+        totals.gravada_5 += row.split.gravada_5;
+        totals.iva_5 += row.split.iva_5;
+        totals.exenta += row.split.exenta;
+        out.push_str(&csv_row(&[
+            &row.date.format("%d/%m/%Y").to_string(),
+            identification_type(&row.counterparty_ruc),
+            &row.counterparty_ruc,
+            &row.counterparty_name,
+            "Factura",
+            &row.timbrado,
+            &row.document_number,
+            &pyg(row.split.gravada_10),
+            &pyg(row.split.iva_10),
+            &pyg(row.split.gravada_5),
+            &pyg(row.split.iva_5),
+            &pyg(row.split.exenta),
+            &pyg(row.split.total()),
+            &row.currency,
+            &format!("{:.2}", row.fx_rate),
+            row.source,
+            &row.source_id,
+        ]));
+    }
+    out.push_str(&csv_row(&[
+        "Totales",
+        "",
+        "",
+        "",
+        "",
+        "",
+        "",
+        &pyg(totals.gravada_10),
+        &pyg(totals.iva_10),
+        &pyg(totals.gravada_5),
+        &pyg(totals.iva_5),
+        &pyg(totals.exenta),
+        &pyg(totals.total()),
+    ]));
+    out
+}
+
+/// Buyer identification, falling back to the generic final consumer when
+/// neither a RUC nor a name is known.
+fn counterparty(ruc: &str, name: &str) -> (String, String) {
+    let ruc = ruc.trim();
+    let name = name.trim();
+    match (ruc.is_empty(), name.is_empty()) {
+        (false, false) => (ruc.to_string(), name.to_string()),
+        (false, true) => (ruc.to_string(), INNOMINADO_NAME.to_string()),
+        (true, _) => (INNOMINADO_RUC.to_string(), INNOMINADO_NAME.to_string()),
+    }
+}
+
+fn identification_type(ruc: &str) -> &'static str {
+    if ruc == INNOMINADO_RUC {
+        "Innominado"
+    } else if ruc.contains('-') {
+        "RUC"
+    } else if !ruc.is_empty() && ruc.chars().all(|c| c.is_ascii_digit()) {
+        "Cédula"
+    } else {
+        "Otro"
+    }
+}
+
+#[derive(Default)]
+struct RateCache {
+    rates: HashMap<(String, NaiveDate), f64>,
+}
+
+impl RateCache {
+    /// Stored rate into guaraníes. The books must be in PYG, so a missing
+    /// rate is an error rather than a silent 1:1.
+    async fn pyg_rate(&mut self, pool: &PgPool, currency: &str, date: NaiveDate) -> AppResult<f64> {
+        if currency.is_empty() || currency == "PYG" {
+            return Ok(1.0);
+        }
+        let key = (currency.to_string(), date);
+        if let Some(rate) = self.rates.get(&key) {
+            return Ok(*rate);
+        }
+        let rate = fx::stored_rate(pool, currency, "PYG", date)
+            .await?
+            .map(|rate| rate.rate)
+            .ok_or_else(|| {
+                AppError::UnprocessableEntity(format!(
+                    "No {currency}→PYG exchange rate is stored for {date}."
+                ))
+            })?;
+        self.rates.insert(key, rate);
+        Ok(rate)
+    }
+}
+
+/// One CSV record, quoted where needed and CRLF-terminated.
+pub fn csv_row(fields: &[&str]) -> String {
+    let mut line = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                (*field).to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
+
+fn pyg(value: f64) -> String {
+    format!("{:.0}", round_pyg(value))
+}
+
+fn round_pyg(value: f64) -> f64 {
+    value.round()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").expect("valid date")
+    }
+
+    fn entry() -> ExportEntry {
+        let line = |code: &str, name: &str, kind: &str, debit: f64, credit: f64| ExportLine {
+            account_code: code.to_string(),
+            account_name: name.to_string(),
+            account_type: kind.to_string(),
+            system_key: (code == "1000").then(|| "trust_cash".to_string()),
+            debit,
+            credit,
+            property_name: Some("Edificio Sol, Asunción".to_string()),
+            memo: None,
+        };
+        ExportEntry {
+            entry_id: "e1".to_string(),
+            number: 1,
+            entry_date: date("2026-09-05"),
+            description: "Lease payment received (manual)".to_string(),
+            lines: vec![
+                line("1000", "Trust cash", "asset", 2_500_000.0, 0.0),
+                line("2000", "Owner payable", "liability", 0.0, 2_500_000.0),
+            ],
+        }
+    }
+
+    #[test]
+    fn iva_rate_is_inferred_from_base_or_inclusive_amounts() {
+        assert_eq!(infer_iva(1_000_000.0, 100_000.0), (10, 1_100_000.0));
+        assert_eq!(infer_iva(1_100_000.0, 100_000.0), (10, 1_100_000.0));
+        assert_eq!(infer_iva(2_000_000.0, 100_000.0), (5, 2_100_000.0));
+        assert_eq!(infer_iva(500_000.0, 0.0), (0, 500_000.0));
+
+        let split = IvaSplit::from_total(1_100_000.0, 10);
+        assert_eq!(split.iva_10, 100_000.0);
+        assert_eq!(split.total(), 1_100_000.0);
+        assert_eq!(IvaSplit::from_total(2_100_000.0, 5).iva_5, 100_000.0);
+    }
+
+    #[test]
+    fn journal_exports_sign_and_quote_lines() {
+        let entries = [entry()];
+
+        let iif = render_iif(&entries);
+        assert!(iif.contains("ACCNT\tTrust cash\tBANK\t1000\n"));
+        assert!(iif.contains(
+            "TRNS\t\tGENERAL JOURNAL\t09/05/2026\tTrust cash\tEdificio Sol, Asunción\t2500000.00\t1\t"
+        ));
+        assert!(iif.contains("SPL\t\tGENERAL JOURNAL\t09/05/2026\tOwner payable"));
+        assert!(iif.contains("\t-2500000.00\t"));
+        assert!(iif.ends_with("ENDTRNS\n"));
+
+        let xero = render_xero_csv(&entries);
+        assert!(xero.contains(
+            "Lease payment received (manual),05/09/2026,,2000,Tax Exempt,-2500000.00,Property,\"Edificio Sol, Asunción\"\r\n"
+        ));
+
+        let qbo = render_quickbooks_csv(&entries);
+        assert!(qbo.contains("1,09/05/2026,Trust cash,2500000.00,,"));
+    }
+
+    #[test]
+    fn set_book_falls_back_to_final_consumer_and_totals() {
+        let (ruc, name) = counterparty("", "Juan Pérez");
+        assert_eq!(
+            (ruc.as_str(), name.as_str()),
+            (INNOMINADO_RUC, INNOMINADO_NAME)
+        );
+
+        let issuer = Issuer {
+            ruc: "80012345-6".to_string(),
+            name: "Casaora SA".to_string(),
+            timbrado: "12345678".to_string(),
+        };
+        let row = BookRow {
+            date: date("2026-09-05"),
+            counterparty_ruc: "4567890-1".to_string(),
+            counterparty_name: "Juan Pérez".to_string(),
+            timbrado: issuer.timbrado.clone(),
+            document_number: "001-001-0000001".to_string(),
+            split: IvaSplit::from_total(1_100_000.0, 10),
+            currency: "PYG".to_string(),
+            fx_rate: 1.0,
+            source: "collection",
+            source_id: "c1".to_string(),
+        };
+        let book = render_set_book(
+            "Libro de Ventas",
+            &issuer,
+            date("2026-09-01"),
+            date("2026-09-30"),
+            &[row.clone(), row],
+        );
+        assert!(book.contains(
+            "05/09/2026,RUC,4567890-1,Juan Pérez,Factura,12345678,001-001-0000001,1100000,100000,0,0,0,1100000,PYG,1.00,collection,c1\r\n"
+        ));
+        assert!(book.contains("Totales,,,,,,,2200000,200000,0,0,0,2200000\r\n"));
+    }
+}
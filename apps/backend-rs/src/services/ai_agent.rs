@@ -17,6 +17,7 @@ use crate::{
         tool_validator::{normalize_tool_result, normalized_tool_error, validate_tool_args},
     },
     state::AppState,
+    tenancy::{get_org_membership, property_scope},
 };
 
 // S17: In-memory rate limiter removed — replaced by DB-backed `agent_rate_limits` table.
@@ -2859,7 +2860,8 @@ pub fn tool_definitions(allowed_tools: Option<&[String]>) -> Vec<Value> {
                 "parameters": {
                     "type": "object",
                     "properties": {
-                        "period_month": {"type": "string", "description": "Month in YYYY-MM format (optional)."}
+                        "period_month": {"type": "string", "description": "Month in YYYY-MM format (optional)."},
+                        "import_id": {"type": "string", "description": "Only reconcile transactions from this bank statement import (optional)."}
                     }
                 }
             }
@@ -3464,6 +3466,28 @@ pub struct ToolContext<'a> {
     pub approved_execution: bool,
 }
 
+/// Tools that stay available to members limited to property groups.
+const PROPERTY_SCOPED_TOOLS: &[&str] = &[
+    "list_tables",
+    "list_rows",
+    "get_row",
+    "search_knowledge",
+    "get_regulatory_guidance",
+    "create_execution_plan",
+    "summarize_conversation",
+];
+
+async fn requester_property_scope(
+    state: &AppState,
+    org_id: &str,
+    user_id: &str,
+) -> AppResult<Option<Vec<String>>> {
+    match get_org_membership(state, user_id, org_id).await? {
+        Some(membership) => property_scope(state, org_id, &membership).await,
+        None => Ok(None),
+    }
+}
+
 pub async fn execute_tool(
     state: &AppState,
     tool_name: &str,
@@ -3493,6 +3517,22 @@ pub async fn execute_tool(
         ));
     }
 
+    // Members limited to property groups only reach the row tools, which are
+    // filtered to their properties; other data tools span the whole portfolio.
+    let property_scope = match context.requested_by_user_id {
+        Some(user_id) => requester_property_scope(state, context.org_id, user_id).await?,
+        None => None,
+    };
+    if property_scope.is_some() && !PROPERTY_SCOPED_TOOLS.contains(&tool_name) {
+        return Ok(normalized_tool_error(
+            "property_scope",
+            format!("Tool '{tool_name}' is not available to members limited to property groups."),
+            false,
+            Some("Use list_rows or get_row to query your properties.".to_string()),
+        ));
+    }
+    let property_scope = property_scope.as_deref();
+
     // --- Guardrails ---
     // Content moderation: block send_message with prohibited keywords
     if tool_name == "send_message" && !context.approved_execution {
@@ -3578,10 +3618,16 @@ pub async fn execute_tool(
     }
 
     match tool_name {
-        "list_tables" => Ok(json!({ "ok": true, "tables": list_supported_tables() })),
+        "list_tables" => {
+            let tables = list_supported_tables()
+                .into_iter()
+                .filter(|table| property_scope.is_none() || property_path(table).is_some())
+                .collect::<Vec<_>>();
+            Ok(json!({ "ok": true, "tables": tables }))
+        }
         "get_org_snapshot" => tool_get_org_snapshot(state, context.org_id).await,
-        "list_rows" => tool_list_rows(state, context.org_id, args).await,
-        "get_row" => tool_get_row(state, context.org_id, args).await,
+        "list_rows" => tool_list_rows(state, context.org_id, property_scope, args).await,
+        "get_row" => tool_get_row(state, context.org_id, property_scope, args).await,
         "create_row" => tool_create_row(state, &context, args).await,
         "update_row" => tool_update_row(state, &context, args).await,
         "delete_row" => tool_delete_row(state, &context, args).await,
@@ -4010,11 +4056,16 @@ pub async fn execute_tool(
 async fn tool_list_rows(
     state: &AppState,
     org_id: &str,
+    property_scope: Option<&[String]>,
     args: &Map<String, Value>,
 ) -> AppResult<Value> {
     let table = normalize_table(args.get("table"))?;
     let table_cfg = table_config(&table)?;
     let org_column = table_cfg.org_column;
+    let scope = match scoped_property_path(&table, property_scope) {
+        Ok(scope) => scope,
+        Err(denied) => return Ok(denied),
+    };
     let limit = coerce_limit(args.get("limit"), 30);
     let order_by = args
         .get("order_by")
@@ -4047,6 +4098,9 @@ async fn tool_list_rows(
         }
         apply_filter(&mut query, column, &value)?;
     }
+    if let Some((path, property_ids)) = scope {
+        push_property_scope(&mut query, path, property_ids);
+    }
 
     query.push(" ORDER BY t.").push(order_name);
     if ascending {
@@ -4077,11 +4131,16 @@ async fn tool_list_rows(
 async fn tool_get_row(
     state: &AppState,
     org_id: &str,
+    property_scope: Option<&[String]>,
     args: &Map<String, Value>,
 ) -> AppResult<Value> {
     let table = normalize_table(args.get("table"))?;
     let table_cfg = table_config(&table)?;
     let org_column = table_cfg.org_column;
+    let scope = match scoped_property_path(&table, property_scope) {
+        Ok(scope) => scope,
+        Err(denied) => return Ok(denied),
+    };
 
     let row_id = args
         .get("row_id")
@@ -4103,7 +4162,8 @@ async fn tool_get_row(
     let table_name = validate_identifier(&table)?;
     let id_name = validate_identifier(id_field)?;
 
-    let row = QueryBuilder::<Postgres>::new("SELECT row_to_json(t) AS row FROM ")
+    let mut query = QueryBuilder::<Postgres>::new("SELECT row_to_json(t) AS row FROM ");
+    query
         .push(table_name)
         .push(" t WHERE (to_jsonb(t) ->> ")
         .push_bind(id_name)
@@ -4112,7 +4172,11 @@ async fn tool_get_row(
         .push(" AND (to_jsonb(t) ->> ")
         .push_bind(org_column)
         .push(") = ")
-        .push_bind(org_id)
+        .push_bind(org_id);
+    if let Some((path, property_ids)) = scope {
+        push_property_scope(&mut query, path, property_ids);
+    }
+    let row = query
         .push(" LIMIT 1")
         .build()
         .fetch_optional(pool)
@@ -4130,6 +4194,96 @@ async fn tool_get_row(
     }))
 }
 
+/// How rows of an agent-readable table reach a property, for members limited
+/// to property groups. Tables without a known path are withheld from them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PropertyPath {
+    /// `properties` itself, by id.
+    Id,
+    /// A `property_id` column.
+    Column,
+    /// `unit_id` through `units.property_id`.
+    Unit,
+    /// `lease_id` through `leases.property_id`.
+    Lease,
+}
+
+fn property_path(table: &str) -> Option<PropertyPath> {
+    match table {
+        "properties" => Some(PropertyPath::Id),
+        "units"
+        | "leases"
+        | "listings"
+        | "maintenance_requests"
+        | "tasks"
+        | "expenses"
+        | "owner_statements" => Some(PropertyPath::Column),
+        "reservations" | "calendar_blocks" | "integrations" => Some(PropertyPath::Unit),
+        "lease_charges" | "collection_records" => Some(PropertyPath::Lease),
+        _ => None,
+    }
+}
+
+/// `property_path` for a scoped requester, or the tool error when the table
+/// has no path to a property. `None` scope means the whole portfolio.
+fn scoped_property_path<'a>(
+    table: &str,
+    property_scope: Option<&'a [String]>,
+) -> Result<Option<(PropertyPath, &'a [String])>, Value> {
+    let Some(property_ids) = property_scope else {
+        return Ok(None);
+    };
+    match property_path(table) {
+        Some(path) => Ok(Some((path, property_ids))),
+        None => Err(normalized_tool_error(
+            "property_scope",
+            format!("Table '{table}' is not available to members limited to property groups."),
+            false,
+            Some(
+                "Query properties, units, reservations, leases, tasks or maintenance_requests instead."
+                    .to_string(),
+            ),
+        )),
+    }
+}
+
+/// Limits `t` to rows on the given properties.
+fn push_property_scope(
+    query: &mut QueryBuilder<'_, Postgres>,
+    path: PropertyPath,
+    property_ids: &[String],
+) {
+    let property_ids = property_ids.to_vec();
+    match path {
+        PropertyPath::Id => {
+            query
+                .push(" AND t.id::text = ANY(")
+                .push_bind(property_ids)
+                .push(")");
+        }
+        PropertyPath::Column => {
+            query
+                .push(" AND t.property_id::text = ANY(")
+                .push_bind(property_ids)
+                .push(")");
+        }
+        PropertyPath::Unit => {
+            query
+                .push(
+                    " AND t.unit_id IN (SELECT u.id FROM units u WHERE u.property_id::text = ANY(",
+                )
+                .push_bind(property_ids)
+                .push("))");
+        }
+        PropertyPath::Lease => {
+            query
+                .push(" AND t.lease_id IN (SELECT l.id FROM leases l WHERE l.property_id::text = ANY(")
+                .push_bind(property_ids)
+                .push("))");
+        }
+    }
+}
+
 /// Check if an approval is required by policy and enqueue it.
 /// Returns Some(json) for pending approval, or None to proceed immediately.
 async fn maybe_create_approval(
@@ -8323,6 +8477,7 @@ mod tests {
             config,
             db_pool: None,
             http_client,
+            webhook_http_client: reqwest::Client::new(),
             llm_client,
             clerk_jwks_cache: None,
             org_membership_cache: CacheLayer::new(
@@ -8426,4 +8581,120 @@ mod tests {
             Some(false)
         );
     }
+
+    #[test]
+    fn tables_without_a_property_path_are_withheld_from_scoped_members() {
+        use super::{property_path, PropertyPath};
+
+        assert_eq!(property_path("properties"), Some(PropertyPath::Id));
+        assert_eq!(property_path("tasks"), Some(PropertyPath::Column));
+        assert_eq!(property_path("reservations"), Some(PropertyPath::Unit));
+        assert_eq!(
+            property_path("collection_records"),
+            Some(PropertyPath::Lease)
+        );
+        for table in ["guests", "message_logs", "organizations", "audit_logs"] {
+            assert_eq!(property_path(table), None, "{table}");
+        }
+    }
+
+    #[tokio::test]
+    async fn row_tools_only_return_rows_on_scoped_properties() {
+        use serde_json::{json, Map};
+
+        use super::{tool_get_row, tool_list_rows};
+        use crate::test_support::{
+            create_org, create_property, create_unit, create_user, test_pool, test_state,
+        };
+
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let state = test_state(Some(pool.clone()));
+        let owner = create_user(&pool).await;
+        let org = create_org(&pool, &owner).await;
+        let inside = create_property(&pool, &org).await;
+        let outside = create_property(&pool, &org).await;
+        let mut reservations = Vec::new();
+        for property in [&inside, &outside] {
+            let unit = create_unit(&pool, &org, property).await;
+            let id = sqlx::query_scalar::<_, String>(
+                "INSERT INTO reservations (organization_id, unit_id, check_in_date, check_out_date)
+                 VALUES ($1::uuid, $2::uuid, current_date + 30, current_date + 33)
+                 RETURNING id::text",
+            )
+            .bind(&org)
+            .bind(&unit)
+            .fetch_one(&pool)
+            .await
+            .expect("insert reservation");
+            reservations.push(id);
+        }
+        let scope = vec![inside.clone()];
+        let args = |value: Value| value.as_object().cloned().unwrap_or_else(Map::new);
+
+        let listed = tool_list_rows(
+            &state,
+            &org,
+            Some(&scope),
+            &args(json!({ "table": "reservations" })),
+        )
+        .await
+        .expect("list reservations");
+        let rows = listed["rows"].as_array().expect("rows");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["id"], reservations[0].as_str());
+
+        let hidden = tool_get_row(
+            &state,
+            &org,
+            Some(&scope),
+            &args(json!({ "table": "reservations", "row_id": reservations[1] })),
+        )
+        .await
+        .expect("get reservation");
+        assert_eq!(hidden["ok"], false);
+
+        let guests = tool_list_rows(
+            &state,
+            &org,
+            Some(&scope),
+            &args(json!({ "table": "guests" })),
+        )
+        .await
+        .expect("list guests");
+        assert_eq!(guests["error"]["code"], "property_scope");
+
+        // Every mapped table resolves its property path.
+        for table in [
+            "properties",
+            "units",
+            "leases",
+            "listings",
+            "maintenance_requests",
+            "tasks",
+            "expenses",
+            "owner_statements",
+            "calendar_blocks",
+            "integrations",
+            "lease_charges",
+            "collection_records",
+        ] {
+            let result =
+                tool_list_rows(&state, &org, Some(&scope), &args(json!({ "table": table })))
+                    .await
+                    .unwrap_or_else(|error| panic!("list {table}: {error:?}"));
+            assert_eq!(result["ok"], true, "{table}");
+        }
+
+        let unscoped = tool_list_rows(
+            &state,
+            &org,
+            None,
+            &args(json!({ "table": "reservations" })),
+        )
+        .await
+        .expect("list reservations");
+        assert_eq!(unscoped["rows"].as_array().map(Vec::len), Some(2));
+    }
 }
@@ -1,7 +1,11 @@
+use chrono::NaiveDate;
 use reqwest::Client;
 use serde::Deserialize;
 use serde_json::{json, Value};
-use sqlx::PgPool;
+
+use crate::services::channel_connector::{
+    AriDay, ChannelConnector, ChannelIntegration, ChannelReservation,
+};
 
 const AIRBNB_API_BASE: &str = "https://api.airbnb.com/v3";
 const AIRBNB_AUTH_URL: &str = "https://www.airbnb.com/oauth2/auth";
@@ -121,9 +125,8 @@ struct AirbnbReservation {
     end_date: Option<String>,
     status: Option<String>,
     guest: Option<AirbnbGuest>,
-    listing_id: Option<i64>,
-    nights: Option<i64>,
     expected_payout_amount_accurate: Option<f64>,
+    currency: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -137,382 +140,147 @@ struct AirbnbReservationsResponse {
     reservations: Option<Vec<AirbnbReservation>>,
 }
 
-/// Fetch reservations from Airbnb API for a listing.
-pub async fn fetch_reservations(
-    http: &Client,
-    access_token: &str,
-    listing_id: &str,
-) -> Result<Vec<Value>, String> {
-    let url = format!(
-        "{AIRBNB_API_BASE}/reservations?listing_id={listing_id}&status=accept,pending&_limit=50"
-    );
-
-    let res = http
-        .get(&url)
-        .header("Authorization", format!("Bearer {access_token}"))
-        .header("Accept", "application/json")
-        .send()
-        .await
-        .map_err(|e| format!("HTTP error: {e}"))?;
-
-    if !res.status().is_success() {
-        let text = res.text().await.unwrap_or_default();
-        return Err(format!("Airbnb API error: {text}"));
-    }
-
-    let body = res
-        .json::<AirbnbReservationsResponse>()
-        .await
-        .map_err(|e| format!("Parse error: {e}"))?;
-
-    let reservations = body.reservations.unwrap_or_default();
-    Ok(reservations
-        .into_iter()
-        .map(|r| {
-            let guest_name = r
-                .guest
-                .as_ref()
-                .map(|g| {
-                    format!(
-                        "{} {}",
-                        g.first_name.as_deref().unwrap_or(""),
-                        g.last_name.as_deref().unwrap_or("")
-                    )
-                    .trim()
-                    .to_string()
-                })
-                .unwrap_or_default();
-
-            json!({
-                "confirmation_code": r.confirmation_code,
-                "check_in": r.start_date,
-                "check_out": r.end_date,
-                "status": r.status,
-                "guest_name": guest_name,
-                "listing_id": r.listing_id,
-                "nights": r.nights,
-                "payout_amount": r.expected_payout_amount_accurate,
-            })
-        })
-        .collect())
+/// Airbnb API connector for one listing, authorized through the OAuth tokens
+/// stored in the integration metadata by the connect flow.
+#[derive(Debug, Clone)]
+pub struct AirbnbConnector {
+    pub access_token: String,
+    pub listing_id: String,
+    pub api_base: String,
 }
 
-/// Push availability (calendar blocks) to Airbnb.
-pub async fn push_availability(
-    http: &Client,
-    access_token: &str,
-    listing_id: &str,
-    dates: &[(String, bool)], // (date YYYY-MM-DD, available)
-) -> Result<(), String> {
-    let days: Vec<Value> = dates
-        .iter()
-        .map(|(date, available)| {
-            json!({
-                "date": date,
-                "available": available,
-            })
+impl AirbnbConnector {
+    pub fn from_integration(integration: &ChannelIntegration) -> Result<Self, String> {
+        let access_token = integration
+            .metadata_str("airbnb_access_token")
+            .ok_or_else(|| "No Airbnb access token configured for this integration.".to_string())?;
+        let listing_id = integration
+            .metadata_str("airbnb_listing_id")
+            .or_else(|| integration.external_listing_id.clone())
+            .ok_or_else(|| "No Airbnb listing id configured for this integration.".to_string())?;
+        Ok(Self {
+            access_token,
+            listing_id,
+            api_base: AIRBNB_API_BASE.to_string(),
         })
-        .collect();
-
-    let url = format!("{AIRBNB_API_BASE}/calendars/{listing_id}");
-
-    let res = http
-        .put(&url)
-        .header("Authorization", format!("Bearer {access_token}"))
-        .header("Content-Type", "application/json")
-        .json(&json!({ "days": days }))
-        .send()
-        .await
-        .map_err(|e| format!("HTTP error: {e}"))?;
-
-    if !res.status().is_success() {
-        let text = res.text().await.unwrap_or_default();
-        return Err(format!("Airbnb calendar push failed: {text}"));
     }
-
-    Ok(())
 }
 
-/// Push pricing updates to Airbnb.
-pub async fn push_pricing(
-    http: &Client,
-    access_token: &str,
-    listing_id: &str,
-    dates: &[(String, f64)], // (date, nightly_price)
-) -> Result<(), String> {
-    let days: Vec<Value> = dates
-        .iter()
-        .map(|(date, price)| {
-            json!({
-                "date": date,
-                "price": price,
-            })
-        })
-        .collect();
-
-    let url = format!("{AIRBNB_API_BASE}/calendars/{listing_id}");
-
-    let res = http
-        .put(&url)
-        .header("Authorization", format!("Bearer {access_token}"))
-        .header("Content-Type", "application/json")
-        .json(&json!({ "days": days }))
-        .send()
-        .await
-        .map_err(|e| format!("HTTP error: {e}"))?;
-
-    if !res.status().is_success() {
-        let text = res.text().await.unwrap_or_default();
-        return Err(format!("Airbnb pricing push failed: {text}"));
+fn local_status(airbnb_status: &str) -> &'static str {
+    match airbnb_status {
+        "accept" | "accepted" => "confirmed",
+        "denied" | "cancelled" | "cancelled_by_host" | "cancelled_by_guest" => "cancelled",
+        _ => "pending",
     }
-
-    Ok(())
 }
 
-/// Sync a single integration: pull Airbnb reservations → upsert locally.
-pub async fn sync_airbnb_integration(
-    pool: &PgPool,
-    http: &Client,
-    integration_id: &str,
-    access_token: &str,
-    listing_id: &str,
-    org_id: &str,
-    unit_id: &str,
-) -> Result<Value, String> {
-    let reservations = fetch_reservations(http, access_token, listing_id).await?;
-
-    let mut created = 0i64;
-    let mut updated = 0i64;
-    let mut skipped = 0i64;
-
-    for res in &reservations {
-        let ext_id = res
-            .get("confirmation_code")
-            .and_then(Value::as_str)
-            .unwrap_or_default();
-        if ext_id.is_empty() {
-            skipped += 1;
-            continue;
-        }
-
-        let check_in = res.get("check_in").and_then(Value::as_str).unwrap_or("");
-        let check_out = res.get("check_out").and_then(Value::as_str).unwrap_or("");
-        let guest_name = res
-            .get("guest_name")
-            .and_then(Value::as_str)
-            .unwrap_or("Airbnb Guest");
-        let payout = res
-            .get("payout_amount")
-            .and_then(Value::as_f64)
-            .unwrap_or(0.0);
-        let airbnb_status = res
-            .get("status")
-            .and_then(Value::as_str)
-            .unwrap_or("pending");
-
-        let local_status = match airbnb_status {
-            "accept" | "accepted" => "confirmed",
-            "pending" => "pending",
-            "denied" | "cancelled" | "cancelled_by_host" | "cancelled_by_guest" => "cancelled",
-            _ => "pending",
-        };
-
-        // Check if reservation exists
-        let existing = sqlx::query_scalar::<_, String>(
-            "SELECT id::text FROM reservations
-             WHERE organization_id = $1::uuid
-               AND external_reservation_id = $2
-             LIMIT 1",
-        )
-        .bind(org_id)
-        .bind(ext_id)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| format!("DB error: {e}"))?;
-
-        if let Some(existing_id) = existing {
-            // Update
-            sqlx::query(
-                "UPDATE reservations SET
-                   check_in_date = $2::date, check_out_date = $3::date,
-                   status = $4, total_amount = $5, updated_at = now()
-                 WHERE id = $1::uuid",
-            )
-            .bind(&existing_id)
-            .bind(check_in)
-            .bind(check_out)
-            .bind(local_status)
-            .bind(payout)
-            .execute(pool)
-            .await
-            .map_err(|e| format!("DB error: {e}"))?;
-
-            updated += 1;
-        } else {
-            // Create
-            sqlx::query(
-                "INSERT INTO reservations (organization_id, unit_id, integration_id,
-                   external_reservation_id, source, check_in_date, check_out_date,
-                   status, guest_name, total_amount, currency)
-                 VALUES ($1::uuid, $2::uuid, $3::uuid, $4, 'ical', $5::date, $6::date,
-                   $7, $8, $9, 'USD')",
-            )
-            .bind(org_id)
-            .bind(unit_id)
-            .bind(integration_id)
-            .bind(ext_id)
-            .bind(check_in)
-            .bind(check_out)
-            .bind(local_status)
-            .bind(guest_name)
-            .bind(payout)
-            .execute(pool)
-            .await
-            .map_err(|e| format!("DB error: {e}"))?;
-
-            created += 1;
-        }
+impl ChannelConnector for AirbnbConnector {
+    fn kind(&self) -> &'static str {
+        "airbnb"
     }
 
-    // Update sync timestamp
-    sqlx::query(
-        "UPDATE integrations SET last_ical_sync_at = now(), ical_sync_error = NULL
-         WHERE id = $1::uuid",
-    )
-    .bind(integration_id)
-    .execute(pool)
-    .await
-    .ok();
-
-    Ok(json!({
-        "created": created,
-        "updated": updated,
-        "skipped": skipped,
-        "total_fetched": reservations.len(),
-    }))
-}
-
-/// S23: Push current pricing rates to all Airbnb-connected units.
-pub async fn sync_all_outbound_rates(state: &crate::state::AppState) {
-    let pool = match state.db_pool.as_ref() {
-        Some(p) => p,
-        None => return,
-    };
-
-    // Find integrations with Airbnb access tokens
-    let rows: Vec<(String, String, String, String, String)> = sqlx::query_as(
-        "SELECT i.id::text, i.organization_id::text, i.unit_id::text,
-                (i.metadata->>'airbnb_access_token')::text AS token,
-                (i.metadata->>'airbnb_listing_id')::text AS listing_id
-         FROM integrations i
-         WHERE i.provider = 'airbnb'
-           AND i.is_active = true
-           AND i.metadata->>'airbnb_access_token' IS NOT NULL
-           AND i.metadata->>'airbnb_listing_id' IS NOT NULL
-         LIMIT 200",
-    )
-    .fetch_all(pool)
-    .await
-    .unwrap_or_default();
+    fn reservation_source(&self) -> &'static str {
+        "airbnb"
+    }
 
-    let mut synced = 0u32;
-    for (_integration_id, org_id, unit_id, token, listing_id) in &rows {
-        if token.is_empty() || listing_id.is_empty() {
-            continue;
-        }
+    /// Fetch reservations from the Airbnb API for the listing.
+    async fn pull_reservations(&self, http: &Client) -> Result<Vec<ChannelReservation>, String> {
+        let url = format!(
+            "{}/reservations?listing_id={}&status=accept,pending&_limit=50",
+            self.api_base, self.listing_id
+        );
 
-        // Get current pricing for the unit (next 90 days)
-        let prices: Vec<(String, f64)> = sqlx::query_as(
-            "SELECT to_char(d::date, 'YYYY-MM-DD'), COALESCE(pt.base_price, 0)::float8
-             FROM generate_series(CURRENT_DATE, CURRENT_DATE + 89, '1 day') AS d
-             LEFT JOIN pricing_templates pt ON pt.unit_id = $1::uuid AND pt.is_active = true
-             WHERE COALESCE(pt.base_price, 0) > 0
-             LIMIT 90",
-        )
-        .bind(unit_id)
-        .fetch_all(pool)
-        .await
-        .unwrap_or_default();
+        let res = http
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .map_err(|e| format!("HTTP error: {e}"))?;
 
-        if prices.is_empty() {
-            continue;
+        if !res.status().is_success() {
+            let text = res.text().await.unwrap_or_default();
+            return Err(format!("Airbnb API error: {text}"));
         }
 
-        // Get blocked dates (existing reservations)
-        let blocked_dates: Vec<(String,)> = sqlx::query_as(
-            "SELECT DISTINCT to_char(d::date, 'YYYY-MM-DD')
-             FROM reservations r,
-                  generate_series(r.check_in_date, r.check_out_date - 1, '1 day') AS d
-             WHERE r.unit_id = $1::uuid
-               AND r.organization_id = $2::uuid
-               AND r.status IN ('confirmed', 'checked_in')
-               AND r.check_in_date >= CURRENT_DATE
-               AND r.check_in_date < CURRENT_DATE + 90",
-        )
-        .bind(unit_id)
-        .bind(org_id)
-        .fetch_all(pool)
-        .await
-        .unwrap_or_default();
-
-        let blocked_set: std::collections::HashSet<String> =
-            blocked_dates.into_iter().map(|(d,)| d).collect();
-
-        // Push pricing
-        if let Err(e) = push_pricing(&state.http_client, token, listing_id, &prices).await {
-            tracing::warn!(listing_id, error = %e, "Outbound OTA pricing push failed");
-            continue;
-        }
+        let body = res
+            .json::<AirbnbReservationsResponse>()
+            .await
+            .map_err(|e| format!("Parse error: {e}"))?;
+
+        Ok(body
+            .reservations
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|r| {
+                let external_id = r.confirmation_code.filter(|code| !code.is_empty())?;
+                let check_in =
+                    NaiveDate::parse_from_str(r.start_date.as_deref()?, "%Y-%m-%d").ok()?;
+                let check_out =
+                    NaiveDate::parse_from_str(r.end_date.as_deref()?, "%Y-%m-%d").ok()?;
+                let guest_name = r.guest.as_ref().map(|g| {
+                    format!(
+                        "{} {}",
+                        g.first_name.as_deref().unwrap_or(""),
+                        g.last_name.as_deref().unwrap_or("")
+                    )
+                    .trim()
+                    .to_string()
+                });
+                Some(ChannelReservation {
+                    external_id,
+                    check_in,
+                    check_out,
+                    status: local_status(r.status.as_deref().unwrap_or("pending")),
+                    guest_name,
+                    total_amount: r.expected_payout_amount_accurate.unwrap_or(0.0),
+                    currency: r.currency.map(|code| code.to_ascii_uppercase()),
+                })
+            })
+            .collect())
+    }
 
-        // Push availability (block reserved dates)
-        let availability: Vec<(String, bool)> = (0..90)
-            .map(|offset| {
-                let date = (chrono::Utc::now().date_naive() + chrono::Duration::days(offset))
-                    .format("%Y-%m-%d")
-                    .to_string();
-                let available = !blocked_set.contains(&date);
-                (date, available)
+    /// Push availability, nightly prices and stay restrictions to the listing
+    /// calendar in one request.
+    async fn push_ari(&self, http: &Client, days: &[AriDay]) -> Result<(), String> {
+        let days: Vec<Value> = days
+            .iter()
+            .map(|day| {
+                let mut entry = json!({
+                    "date": day.date.format("%Y-%m-%d").to_string(),
+                    "available": day.available,
+                });
+                if let Some(price) = day.rate {
+                    entry["price"] = json!(price);
+                }
+                if let Some(min_nights) = day.min_stay {
+                    entry["min_nights"] = json!(min_nights);
+                }
+                if let Some(max_nights) = day.max_stay {
+                    entry["max_nights"] = json!(max_nights);
+                }
+                entry["closed_to_arrival"] = json!(day.closed_to_arrival);
+                entry["closed_to_departure"] = json!(day.closed_to_departure);
+                entry
             })
             .collect();
 
-        if let Err(e) =
-            push_availability(&state.http_client, token, listing_id, &availability).await
-        {
-            tracing::warn!(listing_id, error = %e, "Outbound OTA availability push failed");
-            continue;
-        }
-
-        synced += 1;
-    }
+        let url = format!("{}/calendars/{}", self.api_base, self.listing_id);
 
-    if synced > 0 {
-        tracing::info!(
-            synced,
-            "Scheduler: outbound OTA rate/availability sync completed"
-        );
-    }
-}
-
-/// Get the stored Airbnb access token for an integration.
-pub async fn get_integration_airbnb_token(
-    pool: &PgPool,
-    integration_id: &str,
-) -> Result<(String, String), String> {
-    let row = sqlx::query_as::<_, (Option<String>, Option<String>)>(
-        "SELECT
-           (metadata->>'airbnb_access_token')::text,
-           (metadata->>'airbnb_listing_id')::text
-         FROM integrations WHERE id = $1::uuid",
-    )
-    .bind(integration_id)
-    .fetch_optional(pool)
-    .await
-    .map_err(|e| format!("DB error: {e}"))?;
+        let res = http
+            .put(&url)
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .header("Content-Type", "application/json")
+            .json(&json!({ "days": days }))
+            .send()
+            .await
+            .map_err(|e| format!("HTTP error: {e}"))?;
 
-    match row {
-        Some((Some(token), Some(listing_id))) if !token.is_empty() && !listing_id.is_empty() => {
-            Ok((token, listing_id))
+        if !res.status().is_success() {
+            let text = res.text().await.unwrap_or_default();
+            return Err(format!("Airbnb calendar push failed: {text}"));
         }
-        _ => Err("No Airbnb access token configured for this integration.".to_string()),
+
+        Ok(())
     }
 }
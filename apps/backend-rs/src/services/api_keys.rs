@@ -0,0 +1,304 @@
+//! Organization API keys for integrations that should not borrow a human
+//! session (the MCP server, the warehouse loader). A key authenticates as
+//! its own service user and is a member of exactly one organization; its
+//! scopes decide which built-in roles it can stand in for when
+//! `tenancy::assert_permission` checks a permission's default roles.
+//! Only a SHA-256 hash of the token is stored.
+
+use serde_json::{json, Value};
+use sqlx::Row;
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{AppError, AppResult},
+    services::token_hash::hash_token,
+};
+
+pub const TOKEN_PREFIX: &str = "csk_";
+
+pub const SCOPES: &[&str] = &["read_only", "finance", "operations", "agents"];
+
+/// Longest grace period for the previous token after a rotation.
+pub const MAX_ROTATION_GRACE_HOURS: i64 = 168;
+
+/// Membership role a key reports where code reads the role directly
+/// (e.g. agent tool mutation checks).
+pub fn effective_role(scopes: &[String]) -> &'static str {
+    let has = |scope: &str| scopes.iter().any(|s| s == scope);
+    if has("operations") || has("agents") {
+        "operator"
+    } else if has("finance") {
+        "accountant"
+    } else {
+        "viewer"
+    }
+}
+
+/// Member roles a scope may act as. `read_only` passes membership checks
+/// but no role check, so it cannot reach any write endpoint.
+fn scope_roles(scope: &str) -> &'static [&'static str] {
+    match scope {
+        "finance" => &["accountant"],
+        "operations" | "agents" => &["operator"],
+        _ => &[],
+    }
+}
+
+pub fn scopes_allow_role(scopes: &[String], allowed_roles: &[&str]) -> bool {
+    scopes.iter().any(|scope| {
+        scope_roles(scope)
+            .iter()
+            .any(|role| allowed_roles.contains(role))
+    })
+}
+
+/// Validate and de-duplicate requested scopes.
+pub fn normalize_scopes(requested: &[String]) -> Result<Vec<String>, String> {
+    let mut scopes: Vec<String> = Vec::new();
+    for scope in requested {
+        let scope = scope.trim().to_ascii_lowercase();
+        if scope.is_empty() {
+            continue;
+        }
+        if !SCOPES.contains(&scope.as_str()) {
+            return Err(format!(
+                "Unknown scope '{scope}'. Use: {}.",
+                SCOPES.join(", ")
+            ));
+        }
+        if !scopes.contains(&scope) {
+            scopes.push(scope);
+        }
+    }
+    if scopes.is_empty() {
+        return Err("At least one scope is required.".to_string());
+    }
+    if scopes.len() > 1 && scopes.iter().any(|scope| scope == "read_only") {
+        return Err("read_only cannot be combined with other scopes.".to_string());
+    }
+    Ok(scopes)
+}
+
+/// A new raw token. It is shown once; only its hash is kept.
+pub fn generate_token() -> String {
+    format!(
+        "{TOKEN_PREFIX}{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+/// The leading characters kept in clear so keys can be told apart.
+pub fn display_prefix(token: &str) -> String {
+    token.chars().take(TOKEN_PREFIX.len() + 8).collect()
+}
+
+pub fn is_api_key_token(token: &str) -> bool {
+    token.starts_with(TOKEN_PREFIX)
+}
+
+/// Whether a principal was authenticated with an API key rather than a
+/// user session.
+pub fn is_api_key_user(user: &AuthenticatedUser) -> bool {
+    user.user_metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get("api_key_id"))
+        .is_some()
+}
+
+/// Keys are bound to one organization; they cannot create or join others.
+pub fn reject_api_key_user(user: &AuthenticatedUser) -> AppResult<()> {
+    if is_api_key_user(user) {
+        return Err(AppError::Forbidden(
+            "Forbidden: API keys cannot create or join organizations.".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Authenticate a raw key. The previous token of a rotated key still works
+/// until its grace period ends.
+pub async fn resolve_token(pool: &sqlx::PgPool, token: &str) -> Option<AuthenticatedUser> {
+    let token_hash = hash_token(token);
+    let row = sqlx::query(
+        "SELECT k.id::text AS id,
+                k.organization_id::text AS organization_id,
+                k.service_user_id::text AS service_user_id,
+                k.name,
+                u.email::text AS email,
+                (k.last_used_at IS NULL OR k.last_used_at < now() - interval '1 minute')
+                  AS usage_stale
+         FROM organization_api_keys k
+         JOIN app_users u ON u.id = k.service_user_id
+         WHERE u.is_active
+           AND k.revoked_at IS NULL
+           AND (k.expires_at IS NULL OR k.expires_at > now())
+           AND (
+             k.token_hash = $1
+             OR (k.previous_token_hash = $1 AND k.previous_token_expires_at > now())
+           )
+         LIMIT 1",
+    )
+    .bind(&token_hash)
+    .fetch_optional(pool)
+    .await
+    .map_err(|error| tracing::warn!(%error, "API key lookup failed"))
+    .ok()
+    .flatten()?;
+
+    let key_id = row.try_get::<String, _>("id").ok()?;
+    // Last-used tracking at minute resolution keeps hot keys from writing
+    // on every request.
+    if row.try_get::<bool, _>("usage_stale").unwrap_or(false) {
+        let _ = sqlx::query(
+            "UPDATE organization_api_keys SET last_used_at = now() WHERE id = $1::uuid",
+        )
+        .bind(&key_id)
+        .execute(pool)
+        .await;
+    }
+
+    Some(AuthenticatedUser {
+        id: row.try_get::<String, _>("service_user_id").ok()?,
+        email: row.try_get::<Option<String>, _>("email").ok().flatten(),
+        user_metadata: Some(json!({
+            "full_name": row.try_get::<String, _>("name").unwrap_or_default(),
+            "api_key_id": key_id,
+            "organization_id": row.try_get::<String, _>("organization_id").unwrap_or_default(),
+        })),
+    })
+}
+
+/// The membership a key's service user holds in `org_id`, if any.
+pub async fn api_key_membership(
+    pool: &sqlx::PgPool,
+    user_id: &str,
+    org_id: &str,
+) -> AppResult<Option<Value>> {
+    let row = sqlx::query(
+        "SELECT id::text AS id, scopes
+         FROM organization_api_keys
+         WHERE service_user_id = $1::uuid
+           AND organization_id = $2::uuid
+           AND revoked_at IS NULL
+           AND (expires_at IS NULL OR expires_at > now())
+         LIMIT 1",
+    )
+    .bind(user_id)
+    .bind(org_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|error| AppError::from_database_error(&error, "Database request failed."))?;
+
+    Ok(row.map(|row| {
+        let scopes = row.try_get::<Vec<String>, _>("scopes").unwrap_or_default();
+        json!({
+            "organization_id": org_id,
+            "user_id": user_id,
+            "role": effective_role(&scopes),
+            "api_key_id": row.try_get::<String, _>("id").unwrap_or_default(),
+            "scopes": scopes,
+        })
+    }))
+}
+
+/// Scopes on a membership produced by `api_key_membership`; `None` for
+/// human members.
+pub fn membership_scopes(membership: &Value) -> Option<Vec<String>> {
+    membership.get("api_key_id")?;
+    Some(
+        membership
+            .get("scopes")
+            .and_then(Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(ToOwned::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    )
+}
+
+/// Create the service user a new key authenticates as.
+pub async fn create_service_user(pool: &sqlx::PgPool, key_name: &str) -> AppResult<String> {
+    let email = format!(
+        "api-key-{}@service.casaora.invalid",
+        uuid::Uuid::new_v4().simple()
+    );
+    sqlx::query_scalar::<_, String>(
+        "INSERT INTO app_users (email, full_name)
+         VALUES ($1::citext, $2)
+         RETURNING id::text",
+    )
+    .bind(email)
+    .bind(format!("API key: {}", key_name.trim()))
+    .fetch_one(pool)
+    .await
+    .map_err(|error| AppError::from_database_error(&error, "Failed to create API key user."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scopes(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    #[test]
+    fn scopes_map_to_the_roles_they_can_act_as() {
+        let finance = scopes(&["finance"]);
+        assert!(scopes_allow_role(&finance, &["owner_admin", "accountant"]));
+        assert!(!scopes_allow_role(&finance, &["owner_admin", "operator"]));
+        assert_eq!(effective_role(&finance), "accountant");
+
+        let operations = scopes(&["operations", "finance"]);
+        assert!(scopes_allow_role(&operations, &["owner_admin", "operator"]));
+        assert_eq!(effective_role(&operations), "operator");
+
+        let read_only = scopes(&["read_only"]);
+        assert!(!scopes_allow_role(
+            &read_only,
+            &["owner_admin", "operator", "accountant", "viewer"]
+        ));
+        assert_eq!(effective_role(&read_only), "viewer");
+
+        assert!(!scopes_allow_role(
+            &scopes(&["agents", "finance"]),
+            &["owner_admin"]
+        ));
+    }
+
+    #[test]
+    fn scope_lists_are_validated() {
+        assert_eq!(
+            normalize_scopes(&scopes(&[" Finance", "agents", "finance"])).unwrap(),
+            scopes(&["finance", "agents"])
+        );
+        assert!(normalize_scopes(&scopes(&["admin"])).is_err());
+        assert!(normalize_scopes(&scopes(&[""])).is_err());
+        assert!(normalize_scopes(&scopes(&["read_only", "finance"])).is_err());
+    }
+
+    #[test]
+    fn tokens_are_recognizable_and_hashed() {
+        let token = generate_token();
+        assert!(is_api_key_token(&token));
+        assert_eq!(token.len(), TOKEN_PREFIX.len() + 64);
+        assert_eq!(display_prefix(&token).len(), 12);
+        assert_ne!(hash_token(&token), token);
+        assert!(membership_scopes(&json!({ "role": "operator" })).is_none());
+        let user = AuthenticatedUser {
+            id: "u1".to_string(),
+            email: None,
+            user_metadata: Some(json!({ "api_key_id": "k1" })),
+        };
+        assert!(reject_api_key_user(&user).is_err());
+        assert_eq!(
+            membership_scopes(&json!({ "api_key_id": "k1", "scopes": ["agents"] })),
+            Some(scopes(&["agents"]))
+        );
+    }
+}
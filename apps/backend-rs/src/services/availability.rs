@@ -0,0 +1,662 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_json::{json, Value};
+use sqlx::{postgres::PgRow, PgPool, Row};
+
+use crate::error::{AppError, AppResult};
+
+/// What holds a unit for a date range. Backed by the `unit_occupancy`
+/// ledger, which triggers keep in step with active reservations and every
+/// calendar block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OccupancyKind {
+    Reservation,
+    Block,
+}
+
+impl OccupancyKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Reservation => "reservation",
+            Self::Block => "block",
+        }
+    }
+
+    fn from_source_table(table: &str) -> Self {
+        if table == "reservations" {
+            Self::Reservation
+        } else {
+            Self::Block
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Occupancy {
+    pub kind: OccupancyKind,
+    pub id: String,
+    pub unit_id: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub source: String,
+    pub integration_id: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl Occupancy {
+    /// Half-open `[start, end)` overlap, matching the `daterange` semantics.
+    pub fn overlaps(&self, start: NaiveDate, end: NaiveDate) -> bool {
+        self.start < end && start < self.end
+    }
+
+    /// Rows imported from a channel feed or connector rather than entered in
+    /// Casaora. Only first-party rows are enforced by the exclusion constraint.
+    pub fn is_channel_managed(&self) -> bool {
+        self.integration_id.is_some()
+    }
+
+    fn contains(&self, other: &Occupancy) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "kind": self.kind.as_str(),
+            "id": self.id,
+            "unit_id": self.unit_id,
+            "start_date": self.start.to_string(),
+            "end_date": self.end.to_string(),
+            "source": self.source,
+            "integration_id": self.integration_id,
+            "created_at": self.created_at.map(|value| value.to_rfc3339()),
+        })
+    }
+}
+
+const OCCUPANCY_COLUMNS: &str = "o.source_table,
+       o.source_id::text AS id,
+       o.unit_id::text AS unit_id,
+       lower(o.period) AS start_date,
+       upper(o.period) AS end_date,
+       o.source,
+       o.integration_id::text AS integration_id,
+       o.created_at";
+
+fn occupancy_from_row(row: &PgRow, prefix: &str) -> Option<Occupancy> {
+    let column = |name: &str| format!("{prefix}{name}");
+    let table: String = row.try_get(column("source_table").as_str()).ok()?;
+    Some(Occupancy {
+        kind: OccupancyKind::from_source_table(&table),
+        id: row.try_get(column("id").as_str()).ok()?,
+        unit_id: row.try_get(column("unit_id").as_str()).ok()?,
+        start: row.try_get(column("start_date").as_str()).ok()?,
+        end: row.try_get(column("end_date").as_str()).ok()?,
+        source: row
+            .try_get::<Option<String>, _>(column("source").as_str())
+            .ok()
+            .flatten()
+            .unwrap_or_default(),
+        integration_id: row
+            .try_get::<Option<String>, _>(column("integration_id").as_str())
+            .ok()
+            .flatten(),
+        created_at: row
+            .try_get::<Option<DateTime<Utc>>, _>(column("created_at").as_str())
+            .ok()
+            .flatten(),
+    })
+}
+
+/// Everything holding units of an organization within `[start, end)`,
+/// optionally narrowed to one unit.
+pub async fn occupied_ranges(
+    pool: &PgPool,
+    org_id: &str,
+    unit_id: Option<&str>,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> AppResult<Vec<Occupancy>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {OCCUPANCY_COLUMNS}
+         FROM unit_occupancy o
+         WHERE o.organization_id = $1::uuid
+           AND ($2::uuid IS NULL OR o.unit_id = $2::uuid)
+           AND o.period && daterange($3::date, $4::date, '[)')
+         ORDER BY o.unit_id, lower(o.period)"
+    ))
+    .bind(org_id)
+    .bind(unit_id)
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Failed to load unit availability."))?;
+
+    Ok(rows
+        .iter()
+        .filter_map(|row| occupancy_from_row(row, ""))
+        .collect())
+}
+
+/// Upcoming occupancy of a unit from `from` onwards, skipping rows that
+/// belong to `exclude_integration_id` (a feed comparing itself against the
+/// rest of the calendar).
+pub async fn load_unit_occupancy(
+    pool: &PgPool,
+    unit_id: &str,
+    from: NaiveDate,
+    exclude_integration_id: Option<&str>,
+) -> AppResult<Vec<Occupancy>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {OCCUPANCY_COLUMNS}
+         FROM unit_occupancy o
+         WHERE o.unit_id = $1::uuid
+           AND upper(o.period) > $2::date
+           AND ($3::uuid IS NULL OR o.integration_id IS DISTINCT FROM $3::uuid)
+         ORDER BY lower(o.period)"
+    ))
+    .bind(unit_id)
+    .bind(from)
+    .bind(exclude_integration_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Failed to load unit availability."))?;
+
+    Ok(rows
+        .iter()
+        .filter_map(|row| occupancy_from_row(row, ""))
+        .collect())
+}
+
+/// Entries of `occupancy` that overlap `[start, end)`.
+pub fn overlapping(
+    occupancy: &[Occupancy],
+    start: NaiveDate,
+    end: NaiveDate,
+) -> impl Iterator<Item = &Occupancy> {
+    occupancy
+        .iter()
+        .filter(move |item| item.overlaps(start, end))
+}
+
+/// Reservations and blocks on `unit_id` overlapping `[start, end)`, ignoring
+/// `exclude_source_id` (the row being edited).
+pub async fn find_overlaps(
+    pool: &PgPool,
+    unit_id: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+    exclude_source_id: Option<&str>,
+) -> AppResult<Vec<Occupancy>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {OCCUPANCY_COLUMNS}
+         FROM unit_occupancy o
+         WHERE o.unit_id = $1::uuid
+           AND o.period && daterange($2::date, $3::date, '[)')
+           AND ($4::uuid IS NULL OR o.source_id <> $4::uuid)
+         ORDER BY lower(o.period)"
+    ))
+    .bind(unit_id)
+    .bind(start)
+    .bind(end)
+    .bind(exclude_source_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Failed to check unit availability."))?;
+
+    Ok(rows
+        .iter()
+        .filter_map(|row| occupancy_from_row(row, ""))
+        .collect())
+}
+
+/// Reject `[start, end)` on `unit_id` when any reservation or block holds
+/// those nights. The exclusion constraint on `unit_occupancy` remains the
+/// final guard against races; this gives callers a readable error first.
+pub async fn ensure_available(
+    pool: &PgPool,
+    unit_id: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+    exclude_source_id: Option<&str>,
+) -> AppResult<()> {
+    if end <= start {
+        return Err(AppError::BadRequest(
+            "End date must be after start date.".to_string(),
+        ));
+    }
+
+    let overlaps = find_overlaps(pool, unit_id, start, end, exclude_source_id).await?;
+    let Some(first) = overlaps.first() else {
+        return Ok(());
+    };
+
+    let message = match first.kind {
+        OccupancyKind::Reservation => format!(
+            "Selected dates overlap with an existing reservation ({} to {}).",
+            first.start, first.end
+        ),
+        OccupancyKind::Block => format!(
+            "Selected dates overlap with a calendar block ({} to {}).",
+            first.start, first.end
+        ),
+    };
+    Err(AppError::Conflict(message))
+}
+
+/// How the inbox proposes to settle an overlapping pair.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolution {
+    pub action: &'static str,
+    pub target_kind: OccupancyKind,
+    pub target_id: String,
+    pub explanation: String,
+}
+
+impl Resolution {
+    fn to_json(&self) -> Value {
+        json!({
+            "action": self.action,
+            "target": {
+                "kind": self.target_kind.as_str(),
+                "id": self.target_id,
+            },
+            "explanation": self.explanation,
+        })
+    }
+}
+
+fn newer<'a>(a: &'a Occupancy, b: &'a Occupancy) -> &'a Occupancy {
+    match (a.created_at, b.created_at) {
+        (Some(left), Some(right)) if left > right => a,
+        (Some(_), Some(_)) => b,
+        _ => {
+            if a.id > b.id {
+                a
+            } else {
+                b
+            }
+        }
+    }
+}
+
+fn describe(item: &Occupancy) -> String {
+    format!(
+        "{} {} ({} to {}, {})",
+        item.kind.as_str(),
+        item.id,
+        item.start,
+        item.end,
+        item.source
+    )
+}
+
+/// Suggest a resolution for two overlapping occupancy rows.
+///
+/// Channel blocks that sit entirely inside a reservation from elsewhere are
+/// almost always the channel echoing Casaora's own export back, so those are
+/// flagged as safe to ignore. Otherwise the suggestion touches whatever
+/// Casaora controls: first-party rows before channel rows, newer before older.
+pub fn suggest_resolution(a: &Occupancy, b: &Occupancy) -> Resolution {
+    use OccupancyKind::{Block, Reservation};
+
+    match (a.kind, b.kind) {
+        (Block, Block) => {
+            let target = match (a.is_channel_managed(), b.is_channel_managed()) {
+                (true, false) => b,
+                (false, true) => a,
+                _ => newer(a, b),
+            };
+            Resolution {
+                action: "merge_blocks",
+                target_kind: Block,
+                target_id: target.id.clone(),
+                explanation: format!(
+                    "Both blocks hold the unit. Remove {} or merge the two into a single range.",
+                    describe(target)
+                ),
+            }
+        }
+        (Reservation, Block) | (Block, Reservation) => {
+            let (reservation, block) = if a.kind == Reservation {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            if block.is_channel_managed()
+                && block.integration_id != reservation.integration_id
+                && reservation.contains(block)
+            {
+                Resolution {
+                    action: "ignore_channel_echo",
+                    target_kind: Block,
+                    target_id: block.id.clone(),
+                    explanation: format!(
+                        "The {} block falls inside {}; it is most likely the channel echoing the exported calendar. Dismiss this conflict.",
+                        block.source,
+                        describe(reservation)
+                    ),
+                }
+            } else if !block.is_channel_managed() {
+                Resolution {
+                    action: "remove_block",
+                    target_kind: Block,
+                    target_id: block.id.clone(),
+                    explanation: format!(
+                        "A manual block overlaps {}. Remove or shorten the block, or move the guest to another unit.",
+                        describe(reservation)
+                    ),
+                }
+            } else {
+                Resolution {
+                    action: "relocate_reservation",
+                    target_kind: Reservation,
+                    target_id: reservation.id.clone(),
+                    explanation: format!(
+                        "{} reports the unit unavailable for part of {}. Move the reservation to another unit or confirm the dates with the channel.",
+                        block.source,
+                        describe(reservation)
+                    ),
+                }
+            }
+        }
+        (Reservation, Reservation) => {
+            let target = match (a.is_channel_managed(), b.is_channel_managed()) {
+                (true, false) => b,
+                (false, true) => a,
+                _ => newer(a, b),
+            };
+            Resolution {
+                action: "relocate_reservation",
+                target_kind: Reservation,
+                target_id: target.id.clone(),
+                explanation: format!(
+                    "The unit is double-booked. Move {} to another unit, or cancel it if the guest agrees.",
+                    describe(target)
+                ),
+            }
+        }
+    }
+}
+
+/// Filters accepted by [`list_conflicts`].
+#[derive(Debug, Default)]
+pub struct ConflictFilters<'a> {
+    pub unit_id: Option<&'a str>,
+    pub include_dismissed: bool,
+    pub from: Option<NaiveDate>,
+    pub limit: i64,
+}
+
+/// Overlapping pairs on the organization's units that have not ended yet,
+/// each with a suggested resolution.
+pub async fn list_conflicts(
+    pool: &PgPool,
+    org_id: &str,
+    filters: &ConflictFilters<'_>,
+) -> AppResult<Vec<Value>> {
+    let from = filters.from.unwrap_or_else(|| Utc::now().date_naive());
+    let rows = sqlx::query(
+        "SELECT a.source_table AS a_source_table,
+                a.source_id::text AS a_id,
+                a.unit_id::text AS a_unit_id,
+                lower(a.period) AS a_start_date,
+                upper(a.period) AS a_end_date,
+                a.source AS a_source,
+                a.integration_id::text AS a_integration_id,
+                a.created_at AS a_created_at,
+                b.source_table AS b_source_table,
+                b.source_id::text AS b_id,
+                b.unit_id::text AS b_unit_id,
+                lower(b.period) AS b_start_date,
+                upper(b.period) AS b_end_date,
+                b.source AS b_source,
+                b.integration_id::text AS b_integration_id,
+                b.created_at AS b_created_at,
+                lower(a.period * b.period) AS overlap_start,
+                upper(a.period * b.period) AS overlap_end,
+                u.name AS unit_name,
+                d.id IS NOT NULL AS dismissed,
+                d.note AS dismissal_note
+         FROM unit_occupancy a
+         JOIN unit_occupancy b
+           ON b.unit_id = a.unit_id
+          AND b.source_id > a.source_id
+          AND b.period && a.period
+         LEFT JOIN units u ON u.id = a.unit_id
+         LEFT JOIN availability_conflict_dismissals d
+           ON d.organization_id = a.organization_id
+          AND d.first_source_id = a.source_id
+          AND d.second_source_id = b.source_id
+         WHERE a.organization_id = $1::uuid
+           AND ($2::uuid IS NULL OR a.unit_id = $2::uuid)
+           AND upper(a.period * b.period) > $3::date
+           AND ($4 OR d.id IS NULL)
+         ORDER BY lower(a.period * b.period), a.unit_id
+         LIMIT $5",
+    )
+    .bind(org_id)
+    .bind(filters.unit_id)
+    .bind(from)
+    .bind(filters.include_dismissed)
+    .bind(filters.limit.clamp(1, 500))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Failed to list availability conflicts."))?;
+
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            let first = occupancy_from_row(row, "a_")?;
+            let second = occupancy_from_row(row, "b_")?;
+            let overlap_start: NaiveDate = row.try_get("overlap_start").ok()?;
+            let overlap_end: NaiveDate = row.try_get("overlap_end").ok()?;
+            let suggestion = suggest_resolution(&first, &second);
+            Some(json!({
+                "unit_id": first.unit_id,
+                "unit_name": row.try_get::<Option<String>, _>("unit_name").ok().flatten(),
+                "overlap_start": overlap_start.to_string(),
+                "overlap_end": overlap_end.to_string(),
+                "nights": (overlap_end - overlap_start).num_days(),
+                "first": first.to_json(),
+                "second": second.to_json(),
+                "suggestion": suggestion.to_json(),
+                "dismissed": row.try_get::<bool, _>("dismissed").unwrap_or(false),
+                "dismissal_note": row.try_get::<Option<String>, _>("dismissal_note").ok().flatten(),
+            }))
+        })
+        .collect())
+}
+
+/// Hide an overlapping pair from the inbox. The pair is stored in canonical
+/// order so either side can be passed first.
+pub async fn dismiss_conflict(
+    pool: &PgPool,
+    org_id: &str,
+    first_id: &str,
+    second_id: &str,
+    note: Option<&str>,
+    user_id: &str,
+) -> AppResult<Value> {
+    let known: i64 = sqlx::query_scalar(
+        "SELECT count(*)
+         FROM unit_occupancy
+         WHERE organization_id = $1::uuid
+           AND source_id IN ($2::uuid, $3::uuid)",
+    )
+    .bind(org_id)
+    .bind(first_id)
+    .bind(second_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Failed to dismiss conflict."))?;
+    if first_id == second_id || known < 2 {
+        return Err(AppError::NotFound(
+            "Conflict not found for this organization.".to_string(),
+        ));
+    }
+
+    let row = sqlx::query(
+        "INSERT INTO availability_conflict_dismissals (
+           organization_id, first_source_id, second_source_id, note, dismissed_by_user_id
+         )
+         VALUES (
+           $1::uuid, LEAST($2::uuid, $3::uuid), GREATEST($2::uuid, $3::uuid), $4, $5::uuid
+         )
+         ON CONFLICT (organization_id, first_source_id, second_source_id) DO UPDATE
+         SET note = EXCLUDED.note,
+             dismissed_by_user_id = EXCLUDED.dismissed_by_user_id,
+             created_at = now()
+         RETURNING row_to_json(availability_conflict_dismissals) AS row",
+    )
+    .bind(org_id)
+    .bind(first_id)
+    .bind(second_id)
+    .bind(note)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Failed to dismiss conflict."))?;
+
+    Ok(row
+        .try_get::<Option<Value>, _>("row")
+        .ok()
+        .flatten()
+        .unwrap_or(Value::Null))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(
+        kind: OccupancyKind,
+        id: &str,
+        start: &str,
+        end: &str,
+        source: &str,
+        integration_id: Option<&str>,
+    ) -> Occupancy {
+        Occupancy {
+            kind,
+            id: id.to_string(),
+            unit_id: "unit".to_string(),
+            start: NaiveDate::parse_from_str(start, "%Y-%m-%d").unwrap(),
+            end: NaiveDate::parse_from_str(end, "%Y-%m-%d").unwrap(),
+            source: source.to_string(),
+            integration_id: integration_id.map(ToOwned::to_owned),
+            created_at: None,
+        }
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn checkout_day_does_not_overlap_next_checkin() {
+        let stay = item(
+            OccupancyKind::Reservation,
+            "r1",
+            "2026-11-01",
+            "2026-11-05",
+            "manual",
+            None,
+        );
+        assert!(!stay.overlaps(date("2026-11-05"), date("2026-11-07")));
+        assert!(stay.overlaps(date("2026-11-04"), date("2026-11-07")));
+        assert!(!stay.overlaps(date("2026-10-28"), date("2026-11-01")));
+    }
+
+    #[test]
+    fn channel_block_inside_reservation_is_an_echo() {
+        let reservation = item(
+            OccupancyKind::Reservation,
+            "r1",
+            "2026-11-01",
+            "2026-11-05",
+            "direct_booking",
+            None,
+        );
+        let block = item(
+            OccupancyKind::Block,
+            "b1",
+            "2026-11-01",
+            "2026-11-05",
+            "ical",
+            Some("airbnb"),
+        );
+        let suggestion = suggest_resolution(&block, &reservation);
+        assert_eq!(suggestion.action, "ignore_channel_echo");
+        assert_eq!(suggestion.target_id, "b1");
+    }
+
+    #[test]
+    fn channel_block_spilling_past_reservation_relocates_the_guest() {
+        let reservation = item(
+            OccupancyKind::Reservation,
+            "r1",
+            "2026-11-01",
+            "2026-11-05",
+            "manual",
+            None,
+        );
+        let block = item(
+            OccupancyKind::Block,
+            "b1",
+            "2026-11-03",
+            "2026-11-09",
+            "ical",
+            Some("airbnb"),
+        );
+        let suggestion = suggest_resolution(&reservation, &block);
+        assert_eq!(suggestion.action, "relocate_reservation");
+        assert_eq!(suggestion.target_kind, OccupancyKind::Reservation);
+        assert_eq!(suggestion.target_id, "r1");
+    }
+
+    #[test]
+    fn manual_block_over_reservation_is_removed() {
+        let reservation = item(
+            OccupancyKind::Reservation,
+            "r1",
+            "2026-11-01",
+            "2026-11-05",
+            "airbnb",
+            Some("airbnb"),
+        );
+        let block = item(
+            OccupancyKind::Block,
+            "b1",
+            "2026-11-02",
+            "2026-11-03",
+            "manual",
+            None,
+        );
+        let suggestion = suggest_resolution(&reservation, &block);
+        assert_eq!(suggestion.action, "remove_block");
+        assert_eq!(suggestion.target_id, "b1");
+    }
+
+    #[test]
+    fn double_booking_moves_the_first_party_reservation() {
+        let channel = item(
+            OccupancyKind::Reservation,
+            "r1",
+            "2026-11-01",
+            "2026-11-05",
+            "airbnb",
+            Some("airbnb"),
+        );
+        let direct = item(
+            OccupancyKind::Reservation,
+            "r2",
+            "2026-11-03",
+            "2026-11-06",
+            "direct_booking",
+            None,
+        );
+        let suggestion = suggest_resolution(&channel, &direct);
+        assert_eq!(suggestion.action, "relocate_reservation");
+        assert_eq!(suggestion.target_id, "r2");
+    }
+}
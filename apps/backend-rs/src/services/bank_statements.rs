@@ -0,0 +1,859 @@
+//! Bank statement file parsing for reconciliation imports: delimited CSV
+//! exports described by a per-organization column mapping profile, OFX/QFX
+//! (SGML 1.x and XML 2.x) and ISO 20022 CAMT.053. Every format is reduced to
+//! [`StatementLine`]s with signed amounts and a stable dedupe hash.
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+
+use crate::services::{token_hash::hash_token, xml};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementFormat {
+    Csv,
+    Ofx,
+    Camt053,
+}
+
+impl StatementFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "csv" | "txt" => Some(Self::Csv),
+            "ofx" | "qfx" => Some(Self::Ofx),
+            "camt053" | "camt.053" | "camt" | "xml" => Some(Self::Camt053),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Ofx => "ofx",
+            Self::Camt053 => "camt053",
+        }
+    }
+
+    /// Guess the format from the file name, then from the content.
+    pub fn detect(file_name: Option<&str>, text: &str) -> Option<Self> {
+        let extension = file_name
+            .and_then(|name| name.rsplit_once('.'))
+            .map(|(_, extension)| extension.to_ascii_lowercase());
+        if let Some(format) = extension.as_deref().and_then(Self::parse) {
+            if format != Self::Camt053 || text.contains("BkToCstmrStmt") {
+                return Some(format);
+            }
+        }
+        let head = text.trim_start_matches('\u{feff}').trim_start();
+        if head.starts_with("OFXHEADER") || head.contains("<OFX>") {
+            return Some(Self::Ofx);
+        }
+        if head.starts_with('<') && text.contains("BkToCstmrStmt") {
+            return Some(Self::Camt053);
+        }
+        None
+    }
+}
+
+/// Column mapping for one bank's CSV export, stored in
+/// `bank_import_profiles`. Columns are header names (case-insensitive) or,
+/// when the file has no header, 1-based positions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvProfile {
+    pub delimiter: char,
+    /// 1-based line holding the column headers; 0 when the file has none.
+    /// Lines above it (account banners, export dates) are ignored.
+    pub header_row: usize,
+    pub date_column: String,
+    pub date_format: String,
+    pub description_column: Option<String>,
+    pub amount_column: Option<String>,
+    pub credit_column: Option<String>,
+    pub debit_column: Option<String>,
+    pub reference_column: Option<String>,
+    pub counterparty_column: Option<String>,
+    pub external_id_column: Option<String>,
+    pub currency_column: Option<String>,
+    pub decimal_separator: char,
+    pub default_currency: String,
+}
+
+impl CsvProfile {
+    pub fn from_row(row: &Value) -> Self {
+        let text = |key: &str| {
+            row.get(key)
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(ToOwned::to_owned)
+        };
+        let first_char = |key: &str, default: char| {
+            row.get(key)
+                .and_then(Value::as_str)
+                .and_then(|value| value.chars().next())
+                .unwrap_or(default)
+        };
+        Self {
+            delimiter: first_char("delimiter", ','),
+            header_row: row
+                .get("header_row")
+                .and_then(Value::as_u64)
+                .map_or(1, |value| value as usize),
+            date_column: text("date_column").unwrap_or_else(|| "1".to_string()),
+            date_format: text("date_format").unwrap_or_else(|| "%d/%m/%Y".to_string()),
+            description_column: text("description_column"),
+            amount_column: text("amount_column"),
+            credit_column: text("credit_column"),
+            debit_column: text("debit_column"),
+            reference_column: text("reference_column"),
+            counterparty_column: text("counterparty_column"),
+            external_id_column: text("external_id_column"),
+            currency_column: text("currency_column"),
+            decimal_separator: first_char("decimal_separator", ','),
+            default_currency: text("default_currency").unwrap_or_else(|| "PYG".to_string()),
+        }
+    }
+}
+
+/// One transaction read from a statement file. `amount` is positive for
+/// money received (credits) and negative for money paid out (debits).
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementLine {
+    pub line: usize,
+    pub date: NaiveDate,
+    pub amount: f64,
+    pub currency: String,
+    pub description: String,
+    pub reference: Option<String>,
+    pub counterparty: Option<String>,
+    pub external_id: Option<String>,
+}
+
+impl StatementLine {
+    pub fn direction(&self) -> &'static str {
+        if self.amount >= 0.0 {
+            "credit"
+        } else {
+            "debit"
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "line": self.line,
+            "date": self.date.to_string(),
+            "amount": self.amount.abs(),
+            "direction": self.direction(),
+            "currency": self.currency,
+            "description": self.description,
+            "reference": self.reference,
+            "counterparty_name": self.counterparty,
+            "external_id": self.external_id,
+        })
+    }
+
+    /// Read back a line stored by [`StatementLine::to_json`].
+    pub fn from_json(row: &Value) -> Option<Self> {
+        let text = |key: &str| {
+            row.get(key)
+                .and_then(Value::as_str)
+                .filter(|value| !value.is_empty())
+                .map(ToOwned::to_owned)
+        };
+        let amount = row.get("amount").and_then(Value::as_f64)?.abs();
+        Some(Self {
+            line: row.get("line").and_then(Value::as_u64).unwrap_or(0) as usize,
+            date: NaiveDate::parse_from_str(&text("date")?, "%Y-%m-%d").ok()?,
+            amount: if text("direction").as_deref() == Some("debit") {
+                -amount
+            } else {
+                amount
+            },
+            currency: text("currency").unwrap_or_else(|| "PYG".to_string()),
+            description: text("description").unwrap_or_default(),
+            reference: text("reference"),
+            counterparty: text("counterparty_name"),
+            external_id: text("external_id"),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedStatement {
+    pub account_number: Option<String>,
+    pub bank_name: Option<String>,
+    pub lines: Vec<StatementLine>,
+    /// Rows that could not be read, as `(line, reason)`.
+    pub rejected: Vec<(usize, String)>,
+}
+
+/// Decode an uploaded file. Bank exports are UTF-8 or Windows-1252; the
+/// latter is read as Latin-1, which agrees on every letter Spanish uses.
+pub fn decode_text(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes.iter().map(|&byte| char::from(byte)).collect(),
+    }
+}
+
+pub fn parse(
+    format: StatementFormat,
+    text: &str,
+    profile: Option<&CsvProfile>,
+) -> Result<ParsedStatement, String> {
+    match format {
+        StatementFormat::Csv => {
+            let profile =
+                profile.ok_or_else(|| "CSV imports need a column mapping profile.".to_string())?;
+            parse_csv(text, profile)
+        }
+        StatementFormat::Ofx => parse_ofx(text),
+        StatementFormat::Camt053 => parse_camt053(text),
+    }
+}
+
+// ── CSV ────────────────────────────────────────────────────────────────
+
+pub fn parse_csv(text: &str, profile: &CsvProfile) -> Result<ParsedStatement, String> {
+    let records = csv_records(text, profile.delimiter);
+    let headers: Vec<String> = if profile.header_row == 0 {
+        Vec::new()
+    } else {
+        records
+            .iter()
+            .find(|(line, _)| *line == profile.header_row)
+            .map(|(_, cells)| {
+                cells
+                    .iter()
+                    .map(|cell| cell.trim().to_lowercase())
+                    .collect()
+            })
+            .ok_or_else(|| format!("Header row {} is missing.", profile.header_row))?
+    };
+    let column = |name: &Option<String>| -> Result<Option<usize>, String> {
+        let Some(name) = name else {
+            return Ok(None);
+        };
+        let wanted = name.trim().to_lowercase();
+        if let Some(index) = headers.iter().position(|header| *header == wanted) {
+            return Ok(Some(index));
+        }
+        match wanted.parse::<usize>() {
+            Ok(position) if position >= 1 => Ok(Some(position - 1)),
+            _ => Err(format!("Column '{name}' is not in the file header.")),
+        }
+    };
+
+    let date_at = column(&Some(profile.date_column.clone()))?.unwrap_or(0);
+    let amount_at = column(&profile.amount_column)?;
+    let credit_at = column(&profile.credit_column)?;
+    let debit_at = column(&profile.debit_column)?;
+    if amount_at.is_none() && credit_at.is_none() && debit_at.is_none() {
+        return Err("The profile maps no amount, credit or debit column.".to_string());
+    }
+    let description_at = column(&profile.description_column)?;
+    let reference_at = column(&profile.reference_column)?;
+    let counterparty_at = column(&profile.counterparty_column)?;
+    let external_id_at = column(&profile.external_id_column)?;
+    let currency_at = column(&profile.currency_column)?;
+
+    let mut statement = ParsedStatement::default();
+    for (line, cells) in records
+        .iter()
+        .filter(|(line, _)| *line > profile.header_row)
+    {
+        if cells.iter().all(|cell| cell.trim().is_empty()) {
+            continue;
+        }
+        let cell = |index: Option<usize>| {
+            index
+                .and_then(|index| cells.get(index))
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+        };
+
+        let raw_date = cell(Some(date_at)).unwrap_or_default();
+        let Some(date) = parse_date(raw_date, &profile.date_format) else {
+            statement
+                .rejected
+                .push((*line, format!("Unreadable date '{raw_date}'.")));
+            continue;
+        };
+
+        let amount_in =
+            |index| cell(index).and_then(|raw| parse_amount(raw, profile.decimal_separator));
+        let amount = match amount_at {
+            Some(_) => amount_in(amount_at),
+            None => {
+                let credit = amount_in(credit_at).map(f64::abs).unwrap_or(0.0);
+                let debit = amount_in(debit_at).map(f64::abs).unwrap_or(0.0);
+                (credit > 0.0 || debit > 0.0).then_some(credit - debit)
+            }
+        };
+        let Some(amount) = amount.filter(|amount| amount.abs() >= 0.005) else {
+            statement
+                .rejected
+                .push((*line, "Missing or zero amount.".to_string()));
+            continue;
+        };
+
+        statement.lines.push(StatementLine {
+            line: *line,
+            date,
+            amount,
+            currency: cell(currency_at)
+                .and_then(crate::services::fx::normalize_currency)
+                .unwrap_or_else(|| profile.default_currency.clone()),
+            description: cell(description_at).unwrap_or_default().to_string(),
+            reference: cell(reference_at).map(ToOwned::to_owned),
+            counterparty: cell(counterparty_at).map(ToOwned::to_owned),
+            external_id: cell(external_id_at).map(ToOwned::to_owned),
+        });
+    }
+    Ok(statement)
+}
+
+/// Split delimited text into records, honoring double-quoted fields (with
+/// `""` escapes and embedded newlines). Each record carries the 1-based line
+/// it starts on.
+fn csv_records(text: &str, delimiter: char) -> Vec<(usize, Vec<String>)> {
+    let mut records = Vec::new();
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    cell.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    cell.push(c);
+                }
+                _ => cell.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if cell.trim().is_empty() => {
+                cell.clear();
+                in_quotes = true;
+            }
+            '\r' => {}
+            '\n' => {
+                cells.push(std::mem::take(&mut cell));
+                records.push((record_line, std::mem::take(&mut cells)));
+                line += 1;
+                record_line = line;
+            }
+            c if c == delimiter => cells.push(std::mem::take(&mut cell)),
+            _ => cell.push(c),
+        }
+    }
+    if !cell.is_empty() || !cells.is_empty() {
+        cells.push(cell);
+        records.push((record_line, cells));
+    }
+    records
+}
+
+/// Read a localized amount such as `1.234.567,50`, `-1,234.50`, `Gs. 150.000`,
+/// `(1.500,00)` or `1.500,00-`.
+pub fn parse_amount(raw: &str, decimal_separator: char) -> Option<f64> {
+    let raw = raw.trim();
+    let negative =
+        raw.starts_with('-') || raw.ends_with('-') || (raw.starts_with('(') && raw.ends_with(')'));
+    let thousands = if decimal_separator == ',' { '.' } else { ',' };
+    let digits: String = raw
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == decimal_separator || *c == thousands)
+        .filter(|c| *c != thousands)
+        .map(|c| if c == decimal_separator { '.' } else { c })
+        .collect();
+    let digits = digits.trim_matches('.');
+    if digits.is_empty() {
+        return None;
+    }
+    let value: f64 = digits.parse().ok()?;
+    Some(if negative { -value } else { value })
+}
+
+fn parse_date(raw: &str, format: &str) -> Option<NaiveDate> {
+    let raw = raw.trim();
+    NaiveDate::parse_from_str(raw, format)
+        .ok()
+        .or_else(|| {
+            raw.split_whitespace()
+                .next()
+                .and_then(|day| NaiveDate::parse_from_str(day, format).ok())
+        })
+        .or_else(|| NaiveDate::parse_from_str(raw.get(..10)?, "%Y-%m-%d").ok())
+}
+
+// ── OFX / QFX ──────────────────────────────────────────────────────────
+
+/// Parse OFX 1.x (SGML, unclosed leaf tags) or 2.x (XML). Both are read by
+/// tag scanning, since SGML OFX is not well-formed XML.
+pub fn parse_ofx(text: &str) -> Result<ParsedStatement, String> {
+    let upper = text.to_ascii_uppercase();
+    if !upper.contains("<OFX>") {
+        return Err("Not an OFX file: missing <OFX> root.".to_string());
+    }
+    let currency = ofx_value(text, &upper, "CURDEF").unwrap_or_else(|| "PYG".to_string());
+    let mut statement = ParsedStatement {
+        account_number: ofx_value(text, &upper, "ACCTID"),
+        bank_name: ofx_value(text, &upper, "ORG"),
+        ..ParsedStatement::default()
+    };
+
+    for (index, (block, upper_block)) in ofx_blocks(text, &upper, "STMTTRN").into_iter().enumerate()
+    {
+        let line = index + 1;
+        let value = |tag: &str| ofx_value(block, upper_block, tag);
+        let Some(date) = value("DTPOSTED")
+            .and_then(|raw| NaiveDate::parse_from_str(raw.get(..8)?, "%Y%m%d").ok())
+        else {
+            statement
+                .rejected
+                .push((line, "Missing or unreadable DTPOSTED.".to_string()));
+            continue;
+        };
+        let Some(amount) = value("TRNAMT").and_then(|raw| {
+            let separator = if raw.contains(',') && !raw.contains('.') {
+                ','
+            } else {
+                '.'
+            };
+            parse_amount(&raw, separator)
+        }) else {
+            statement
+                .rejected
+                .push((line, "Missing or unreadable TRNAMT.".to_string()));
+            continue;
+        };
+        if amount.abs() < 0.005 {
+            continue;
+        }
+
+        let name = value("NAME");
+        let memo = value("MEMO");
+        let description = match (&name, &memo) {
+            (Some(name), Some(memo)) if memo != name => format!("{name} {memo}"),
+            (Some(name), _) => name.clone(),
+            (None, Some(memo)) => memo.clone(),
+            (None, None) => value("TRNTYPE").unwrap_or_default(),
+        };
+        statement.lines.push(StatementLine {
+            line,
+            date,
+            amount,
+            currency: currency.clone(),
+            description,
+            reference: value("REFNUM").or_else(|| value("CHECKNUM")),
+            counterparty: name,
+            external_id: value("FITID"),
+        });
+    }
+    Ok(statement)
+}
+
+/// Bodies of every `<TAG>…</TAG>` aggregate, paired with their upper-cased
+/// copies (ASCII upper-casing keeps byte offsets aligned).
+fn ofx_blocks<'a>(text: &'a str, upper: &'a str, tag: &str) -> Vec<(&'a str, &'a str)> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let mut blocks = Vec::new();
+    let mut at = 0;
+    while let Some(start) = upper[at..]
+        .find(&open)
+        .map(|offset| at + offset + open.len())
+    {
+        let end = upper[start..]
+            .find(&close)
+            .or_else(|| upper[start..].find(&open))
+            .map_or(upper.len(), |offset| start + offset);
+        blocks.push((&text[start..end], &upper[start..end]));
+        at = end;
+    }
+    blocks
+}
+
+/// Value of a leaf element: the text after `<TAG>` up to the next tag or
+/// line break.
+fn ofx_value(text: &str, upper: &str, tag: &str) -> Option<String> {
+    let open = format!("<{tag}>");
+    let start = upper.find(&open)? + open.len();
+    let rest = &text[start..];
+    let end = rest.find(['<', '\r', '\n']).unwrap_or(rest.len());
+    let value = rest[..end]
+        .trim()
+        .replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">");
+    (!value.is_empty()).then_some(value)
+}
+
+// ── CAMT.053 ───────────────────────────────────────────────────────────
+
+/// Parse an ISO 20022 bank-to-customer statement. Only booked entries are
+/// read; batched entries with per-transaction amounts become one line per
+/// transaction.
+pub fn parse_camt053(text: &str) -> Result<ParsedStatement, String> {
+    let root = xml::parse(text)?;
+    let report = if root.local_name() == "BkToCstmrStmt" {
+        &root
+    } else {
+        root.child("BkToCstmrStmt")
+            .ok_or_else(|| "Not a CAMT.053 file: missing BkToCstmrStmt.".to_string())?
+    };
+
+    let mut statement = ParsedStatement::default();
+    let mut line = 0;
+    for stmt in report.children_named("Stmt") {
+        let account = stmt.child("Acct");
+        if statement.account_number.is_none() {
+            statement.account_number = account.and_then(|acct| {
+                acct.find(&["Id", "IBAN"])
+                    .or_else(|| acct.find(&["Id", "Othr", "Id"]))
+                    .map(|id| id.text.trim().to_string())
+            });
+            statement.bank_name = account.and_then(|acct| {
+                let institution = acct.find(&["Svcr", "FinInstnId"])?;
+                institution
+                    .child_text("Nm")
+                    .or_else(|| institution.child_text("BICFI"))
+                    .or_else(|| institution.child_text("BIC"))
+                    .map(ToOwned::to_owned)
+            });
+        }
+        let account_currency = account
+            .and_then(|acct| acct.child_text("Ccy"))
+            .unwrap_or("PYG")
+            .to_string();
+
+        for entry in stmt.children_named("Ntry") {
+            line += 1;
+            let status = entry
+                .child("Sts")
+                .map(|sts| sts.child_text("Cd").unwrap_or(sts.text.trim()).to_string())
+                .unwrap_or_default();
+            if !status.is_empty() && status != "BOOK" {
+                continue;
+            }
+            let Some(date) = camt_date(entry, "BookgDt").or_else(|| camt_date(entry, "ValDt"))
+            else {
+                statement
+                    .rejected
+                    .push((line, "Entry has no booking or value date.".to_string()));
+                continue;
+            };
+            let credit = entry.child_text("CdtDbtInd") != Some("DBIT");
+            let entry_reference = entry
+                .child_text("AcctSvcrRef")
+                .or_else(|| entry.child_text("NtryRef"))
+                .map(ToOwned::to_owned);
+            let entry_info = entry.child_text("AddtlNtryInf").unwrap_or_default();
+
+            let details: Vec<&xml::XmlElement> = entry
+                .children_named("NtryDtls")
+                .flat_map(|block| block.children_named("TxDtls"))
+                .collect();
+            let split = details.len() > 1 && details.iter().all(|tx| camt_amount(tx).is_some());
+            let parts: Vec<Option<&xml::XmlElement>> = if split {
+                details.iter().copied().map(Some).collect()
+            } else {
+                vec![details.first().copied()]
+            };
+
+            for (index, tx) in parts.into_iter().enumerate() {
+                let amount_source = if split { tx } else { Some(entry) };
+                let Some((amount, currency)) = amount_source.and_then(camt_amount) else {
+                    statement
+                        .rejected
+                        .push((line, "Entry has no readable amount.".to_string()));
+                    continue;
+                };
+                let amount = if credit { amount } else { -amount };
+
+                let remittance = tx
+                    .and_then(|tx| tx.child("RmtInf"))
+                    .map(|info| {
+                        info.children_named("Ustrd")
+                            .map(|ustrd| ustrd.text.trim())
+                            .filter(|text| !text.is_empty())
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    })
+                    .unwrap_or_default();
+                let structured_reference = tx
+                    .and_then(|tx| tx.find(&["RmtInf", "Strd", "CdtrRefInf", "Ref"]))
+                    .map(|reference| reference.text.trim().to_string())
+                    .filter(|reference| !reference.is_empty());
+                let end_to_end = tx
+                    .and_then(|tx| tx.find(&["Refs", "EndToEndId"]))
+                    .map(|reference| reference.text.trim().to_string())
+                    .filter(|reference| !reference.is_empty() && reference != "NOTPROVIDED");
+                let tx_reference = tx
+                    .and_then(|tx| tx.find(&["Refs", "AcctSvcrRef"]))
+                    .map(|reference| reference.text.trim().to_string())
+                    .filter(|reference| !reference.is_empty());
+                let party = if credit { "Dbtr" } else { "Cdtr" };
+                let counterparty = tx
+                    .and_then(|tx| tx.find(&["RltdPties", party]))
+                    .and_then(|party| {
+                        party
+                            .child_text("Nm")
+                            .or_else(|| party.find(&["Pty", "Nm"]).map(|nm| nm.text.trim()))
+                    })
+                    .map(ToOwned::to_owned);
+
+                let external_id = match (&tx_reference, &entry_reference) {
+                    (Some(reference), _) if split => Some(reference.clone()),
+                    (_, Some(reference)) if split => Some(format!("{reference}/{}", index + 1)),
+                    (_, Some(reference)) => Some(reference.clone()),
+                    (Some(reference), None) => Some(reference.clone()),
+                    (None, None) => None,
+                };
+                let description = if remittance.is_empty() {
+                    entry_info.to_string()
+                } else {
+                    remittance
+                };
+
+                statement.lines.push(StatementLine {
+                    line,
+                    date,
+                    amount,
+                    currency: currency.unwrap_or_else(|| account_currency.clone()),
+                    description,
+                    reference: structured_reference.or(end_to_end),
+                    counterparty,
+                    external_id,
+                });
+            }
+        }
+    }
+    Ok(statement)
+}
+
+fn camt_date(entry: &xml::XmlElement, name: &str) -> Option<NaiveDate> {
+    let element = entry.child(name)?;
+    let raw = element
+        .child_text("Dt")
+        .or_else(|| element.child_text("DtTm"))?;
+    NaiveDate::parse_from_str(raw.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// `Amt` of an entry or transaction (falling back to `AmtDtls/TxAmt/Amt`),
+/// with its `Ccy` attribute.
+fn camt_amount(element: &xml::XmlElement) -> Option<(f64, Option<String>)> {
+    let amount = element
+        .child("Amt")
+        .or_else(|| element.find(&["AmtDtls", "TxAmt", "Amt"]))?;
+    let value = amount.text.trim().parse::<f64>().ok()?;
+    Some((value.abs(), amount.attr("Ccy").map(ToOwned::to_owned)))
+}
+
+// ── Dedupe ─────────────────────────────────────────────────────────────
+
+/// Stable identity for each line, used to skip rows already imported.
+/// Lines with a bank-assigned id hash that id (scoped to the account);
+/// others hash their content plus how many identical lines precede them in
+/// the same file, so two equal transfers on one day stay distinct while a
+/// re-uploaded export still matches.
+pub fn dedupe_hashes(lines: &[StatementLine], account_number: Option<&str>) -> Vec<String> {
+    let account = account_number.unwrap_or_default();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    lines
+        .iter()
+        .map(|line| {
+            let key = match line.external_id.as_deref() {
+                Some(external_id) => format!("id|{account}|{external_id}"),
+                None => {
+                    let content = format!(
+                        "row|{account}|{}|{:.2}|{}|{}|{}",
+                        line.date,
+                        line.amount,
+                        line.currency,
+                        line.description.trim().to_lowercase(),
+                        line.reference.as_deref().unwrap_or_default()
+                    );
+                    let occurrence = seen.entry(content.clone()).or_insert(0);
+                    *occurrence += 1;
+                    format!("{content}|{occurrence}")
+                }
+            };
+            hash_token(&key)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn itau_profile() -> CsvProfile {
+        CsvProfile::from_row(&json!({
+            "delimiter": ";",
+            "header_row": 3,
+            "date_column": "Fecha",
+            "date_format": "%d/%m/%Y",
+            "description_column": "Concepto",
+            "credit_column": "Crédito",
+            "debit_column": "Débito",
+            "reference_column": "Comprobante",
+        }))
+    }
+
+    #[test]
+    fn parses_mapped_csv_with_banner_lines_and_quotes() {
+        let text =
+            "Banco Itaú Paraguay\nCuenta;1234567\nFecha;Concepto;Comprobante;Débito;Crédito\n\
+            05/03/2026;\"Transferencia; Juan Pérez\";PAY-ABC123;;2.500.000\n\
+            06/03/2026 10:22;Comisión;;15.000,50;\n\
+            Saldo final;;;;9.000.000\n";
+        let statement = parse_csv(text, &itau_profile()).expect("csv");
+        assert_eq!(statement.lines.len(), 2);
+        assert_eq!(statement.lines[0].description, "Transferencia; Juan Pérez");
+        assert_eq!(statement.lines[0].amount, 2_500_000.0);
+        assert_eq!(statement.lines[0].reference.as_deref(), Some("PAY-ABC123"));
+        assert_eq!(statement.lines[1].amount, -15_000.5);
+        assert_eq!(statement.lines[1].direction(), "debit");
+        assert_eq!(
+            statement.rejected,
+            vec![(6, "Unreadable date 'Saldo final'.".to_string())]
+        );
+
+        let mut missing = itau_profile();
+        missing.credit_column = Some("Importe".to_string());
+        assert!(parse_csv(text, &missing).is_err());
+    }
+
+    #[test]
+    fn parses_localized_amounts() {
+        assert_eq!(parse_amount("1.234.567,50", ','), Some(1_234_567.5));
+        assert_eq!(parse_amount("-1,234.50", '.'), Some(-1234.5));
+        assert_eq!(parse_amount("Gs. 150.000", ','), Some(150_000.0));
+        assert_eq!(parse_amount("(1.500,00)", ','), Some(-1500.0));
+        assert_eq!(parse_amount("1.500,00-", ','), Some(-1500.0));
+        assert_eq!(parse_amount("n/a", ','), None);
+    }
+
+    #[test]
+    fn parses_sgml_ofx() {
+        let text = "OFXHEADER:100\nDATA:OFXSGML\n\n<OFX><SIGNONMSGSRSV1><SONRS><FI><ORG>Banco Continental</FI></SONRS></SIGNONMSGSRSV1>\
+            <BANKMSGSRSV1><STMTTRNRS><STMTRS><CURDEF>PYG<BANKACCTFROM><ACCTID>998877</BANKACCTFROM>\
+            <BANKTRANLIST>\n<STMTTRN>\n<TRNTYPE>CREDIT\n<DTPOSTED>20260305120000[-3:PYT]\n<TRNAMT>2500000.00\n\
+            <FITID>TX-1\n<NAME>PEREZ JUAN\n<MEMO>Alquiler marzo PAY-ABC123\n</STMTTRN>\n\
+            <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20260306<TRNAMT>-15000<FITID>TX-2<NAME>Comision &amp; gastos</STMTTRN>\
+            </BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>";
+        let statement = parse_ofx(text).expect("ofx");
+        assert_eq!(statement.account_number.as_deref(), Some("998877"));
+        assert_eq!(statement.bank_name.as_deref(), Some("Banco Continental"));
+        assert_eq!(statement.lines.len(), 2);
+        let first = &statement.lines[0];
+        assert_eq!(first.date, NaiveDate::from_ymd_opt(2026, 3, 5).unwrap());
+        assert_eq!(first.description, "PEREZ JUAN Alquiler marzo PAY-ABC123");
+        assert_eq!(first.external_id.as_deref(), Some("TX-1"));
+        assert_eq!(statement.lines[1].amount, -15_000.0);
+        assert_eq!(statement.lines[1].description, "Comision & gastos");
+        assert_eq!(
+            StatementLine::from_json(&first.to_json()).as_ref(),
+            Some(first)
+        );
+    }
+
+    #[test]
+    fn parses_camt053_booked_entries_and_batches() {
+        let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02">
+  <BkToCstmrStmt>
+    <Stmt>
+      <Acct><Id><Othr><Id>445566</Id></Othr></Id><Ccy>PYG</Ccy>
+        <Svcr><FinInstnId><Nm>Banco Basa</Nm></FinInstnId></Svcr></Acct>
+      <Ntry>
+        <NtryRef>E1</NtryRef><Amt Ccy="PYG">2500000</Amt><CdtDbtInd>CRDT</CdtDbtInd>
+        <Sts>BOOK</Sts><BookgDt><Dt>2026-03-05</Dt></BookgDt>
+        <NtryDtls><TxDtls>
+          <Refs><EndToEndId>NOTPROVIDED</EndToEndId></Refs>
+          <RltdPties><Dbtr><Nm>Juan Perez</Nm></Dbtr></RltdPties>
+          <RmtInf><Strd><CdtrRefInf><Ref>PAY-ABC123</Ref></CdtrRefInf></Strd></RmtInf>
+        </TxDtls></NtryDtls>
+      </Ntry>
+      <Ntry>
+        <NtryRef>E2</NtryRef><Amt Ccy="PYG">300</Amt><CdtDbtInd>CRDT</CdtDbtInd>
+        <Sts><Cd>PDNG</Cd></Sts><BookgDt><Dt>2026-03-06</Dt></BookgDt>
+      </Ntry>
+      <Ntry>
+        <NtryRef>E3</NtryRef><Amt Ccy="USD">150</Amt><CdtDbtInd>DBIT</CdtDbtInd>
+        <Sts>BOOK</Sts><BookgDt><DtTm>2026-03-07T09:00:00</DtTm></BookgDt>
+        <NtryDtls>
+          <TxDtls><Amt Ccy="USD">100</Amt><RmtInf><Ustrd>Plomero</Ustrd></RmtInf></TxDtls>
+          <TxDtls><Amt Ccy="USD">50</Amt><RmtInf><Ustrd>Pintura</Ustrd></RmtInf></TxDtls>
+        </NtryDtls>
+      </Ntry>
+    </Stmt>
+  </BkToCstmrStmt>
+</Document>"#;
+        let statement = parse_camt053(text).expect("camt");
+        assert_eq!(statement.account_number.as_deref(), Some("445566"));
+        assert_eq!(statement.bank_name.as_deref(), Some("Banco Basa"));
+        assert_eq!(statement.lines.len(), 3);
+        let credit = &statement.lines[0];
+        assert_eq!(credit.amount, 2_500_000.0);
+        assert_eq!(credit.reference.as_deref(), Some("PAY-ABC123"));
+        assert_eq!(credit.counterparty.as_deref(), Some("Juan Perez"));
+        assert_eq!(credit.external_id.as_deref(), Some("E1"));
+        let batch: Vec<(f64, &str, Option<&str>)> = statement.lines[1..]
+            .iter()
+            .map(|line| {
+                (
+                    line.amount,
+                    line.currency.as_str(),
+                    line.external_id.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            batch,
+            vec![(-100.0, "USD", Some("E3/1")), (-50.0, "USD", Some("E3/2"))]
+        );
+    }
+
+    #[test]
+    fn dedupe_hashes_are_stable_and_keep_identical_rows_apart() {
+        let line = StatementLine {
+            line: 1,
+            date: NaiveDate::from_ymd_opt(2026, 3, 5).unwrap(),
+            amount: 100.0,
+            currency: "PYG".to_string(),
+            description: "Deposito".to_string(),
+            reference: None,
+            counterparty: None,
+            external_id: None,
+        };
+        let lines = vec![line.clone(), line.clone()];
+        let first = dedupe_hashes(&lines, Some("123"));
+        assert_ne!(first[0], first[1]);
+        assert_eq!(first, dedupe_hashes(&lines, Some("123")));
+        assert_ne!(first, dedupe_hashes(&lines, Some("456")));
+
+        assert_eq!(
+            StatementFormat::detect(Some("extracto.QFX"), ""),
+            Some(StatementFormat::Ofx)
+        );
+        assert_eq!(
+            StatementFormat::detect(None, "<?xml?><Document><BkToCstmrStmt/></Document>"),
+            Some(StatementFormat::Camt053)
+        );
+    }
+}
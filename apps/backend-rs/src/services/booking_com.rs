@@ -0,0 +1,472 @@
+use chrono::NaiveDate;
+use reqwest::Client;
+
+use crate::services::{
+    channel_connector::{
+        compress_ari_days, AriDay, ChannelConnector, ChannelIntegration, ChannelReservation,
+    },
+    xml::{self, XmlElement},
+};
+
+const BOOKING_COM_API_BASE: &str = "https://secure-supply-xml.booking.com";
+
+/// Booking.com connectivity-partner credentials from env vars. One machine
+/// account serves every property mapped to Casaora.
+#[derive(Debug, Clone)]
+pub struct BookingComConfig {
+    pub username: String,
+    pub password: String,
+    pub api_base: String,
+}
+
+impl BookingComConfig {
+    pub fn from_env() -> Option<Self> {
+        let username = std::env::var("BOOKING_COM_USERNAME").ok()?;
+        let password = std::env::var("BOOKING_COM_PASSWORD").ok()?;
+        let api_base = std::env::var("BOOKING_COM_API_BASE")
+            .ok()
+            .map(|value| value.trim().trim_end_matches('/').to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| BOOKING_COM_API_BASE.to_string());
+        Some(Self {
+            username,
+            password,
+            api_base,
+        })
+    }
+}
+
+/// B.XML connector for one Booking.com room/rate mapped to a Casaora unit.
+///
+/// Mapping lives in the integration: `metadata.bookingcom_hotel_id` (or
+/// `external_account_ref`), `metadata.bookingcom_room_id` (or
+/// `external_listing_id`) and `metadata.bookingcom_rate_id` for prices.
+#[derive(Debug, Clone)]
+pub struct BookingComConnector {
+    pub config: BookingComConfig,
+    pub hotel_id: String,
+    pub room_id: String,
+    pub rate_id: Option<String>,
+}
+
+impl BookingComConnector {
+    pub fn from_integration(integration: &ChannelIntegration) -> Result<Self, String> {
+        let config = BookingComConfig::from_env()
+            .ok_or_else(|| "Booking.com API credentials not configured.".to_string())?;
+        let hotel_id = integration
+            .metadata_str("bookingcom_hotel_id")
+            .or_else(|| integration.external_account_ref.clone())
+            .ok_or_else(|| "Booking.com hotel id is not mapped.".to_string())?;
+        let room_id = integration
+            .metadata_str("bookingcom_room_id")
+            .or_else(|| integration.external_listing_id.clone())
+            .ok_or_else(|| "Booking.com room id is not mapped.".to_string())?;
+        Ok(Self {
+            config,
+            hotel_id,
+            room_id,
+            rate_id: integration.metadata_str("bookingcom_rate_id"),
+        })
+    }
+
+    async fn post_xml(&self, http: &Client, path: &str, body: String) -> Result<String, String> {
+        let res = http
+            .post(format!("{}{path}", self.config.api_base))
+            .basic_auth(&self.config.username, Some(&self.config.password))
+            .header("Content-Type", "text/xml; charset=utf-8")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| format!("HTTP error: {e}"))?;
+        let status = res.status();
+        let text = res.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(format!("Booking.com API error ({status}): {text}"));
+        }
+        Ok(text)
+    }
+}
+
+impl ChannelConnector for BookingComConnector {
+    fn kind(&self) -> &'static str {
+        "bookingcom"
+    }
+
+    fn reservation_source(&self) -> &'static str {
+        "booking.com"
+    }
+
+    async fn pull_reservations(&self, http: &Client) -> Result<Vec<ChannelReservation>, String> {
+        let body = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<request>\n  <hotel_id>{}</hotel_id>\n</request>\n",
+            xml::escape(&self.hotel_id)
+        );
+        let text = self
+            .post_xml(http, "/hotels/xml/reservations", body)
+            .await?;
+        parse_reservations(&text, &self.room_id)
+    }
+
+    async fn push_ari(&self, http: &Client, days: &[AriDay]) -> Result<(), String> {
+        if days.is_empty() {
+            return Ok(());
+        }
+        let body = build_availability_request(&self.room_id, self.rate_id.as_deref(), days);
+        let text = self
+            .post_xml(http, "/hotels/xml/availability", body)
+            .await?;
+        check_ari_response(&text)
+    }
+}
+
+fn reservation_status(raw: &str) -> &'static str {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "cancelled" | "canceled" => "cancelled",
+        _ => "confirmed",
+    }
+}
+
+fn parse_date(element: &XmlElement, name: &str) -> Option<NaiveDate> {
+    element
+        .child_text(name)
+        .and_then(|raw| NaiveDate::parse_from_str(raw, "%Y-%m-%d").ok())
+}
+
+fn parse_amount(element: &XmlElement, name: &str) -> Option<f64> {
+    element
+        .child_text(name)
+        .and_then(|raw| raw.replace(',', "").parse::<f64>().ok())
+}
+
+/// Parse a B.XML `<reservations>` response, keeping the rooms booked on
+/// `room_id`. A reservation holding several of those rooms yields one entry
+/// per room reservation.
+pub fn parse_reservations(text: &str, room_id: &str) -> Result<Vec<ChannelReservation>, String> {
+    let root = xml::parse(text).map_err(|e| format!("Parse error: {e}"))?;
+    if root.local_name() != "reservations" {
+        let errors = error_messages(&root);
+        let detail = if errors.is_empty() {
+            format!("unexpected response <{}>", root.local_name())
+        } else {
+            errors.join("; ")
+        };
+        return Err(format!("Booking.com API error: {detail}"));
+    }
+
+    let mut parsed = Vec::new();
+    for reservation in root.children_named("reservation") {
+        let Some(reservation_id) = reservation.child_text("id") else {
+            continue;
+        };
+        let status = reservation_status(reservation.child_text("status").unwrap_or("new"));
+        let currency = reservation
+            .child_text("currencycode")
+            .map(|code| code.to_ascii_uppercase());
+        let customer_name = reservation.child("customer").map(|customer| {
+            format!(
+                "{} {}",
+                customer.child_text("first_name").unwrap_or_default(),
+                customer.child_text("last_name").unwrap_or_default()
+            )
+            .trim()
+            .to_string()
+        });
+
+        let rooms: Vec<&XmlElement> = reservation
+            .children_named("room")
+            .filter(|room| room.child_text("id") == Some(room_id))
+            .collect();
+        for room in &rooms {
+            let (Some(check_in), Some(check_out)) = (
+                parse_date(room, "arrival_date"),
+                parse_date(room, "departure_date"),
+            ) else {
+                continue;
+            };
+            if check_out <= check_in {
+                continue;
+            }
+            let external_id = match room.child_text("roomreservation_id") {
+                Some(room_reservation) if rooms.len() > 1 => {
+                    format!("{reservation_id}-{room_reservation}")
+                }
+                _ => reservation_id.to_string(),
+            };
+            parsed.push(ChannelReservation {
+                external_id,
+                check_in,
+                check_out,
+                status,
+                guest_name: room
+                    .child_text("guest_name")
+                    .map(ToOwned::to_owned)
+                    .or_else(|| customer_name.clone())
+                    .filter(|name| !name.is_empty()),
+                total_amount: parse_amount(room, "totalprice")
+                    .or_else(|| parse_amount(reservation, "totalprice"))
+                    .unwrap_or(0.0),
+                currency: currency.clone(),
+            });
+        }
+    }
+    Ok(parsed)
+}
+
+/// Build a B.XML availability request. Consecutive nights with identical
+/// values are sent as one inclusive `from`/`to` block.
+pub fn build_availability_request(room_id: &str, rate_id: Option<&str>, days: &[AriDay]) -> String {
+    let mut body = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<request>\n");
+    body.push_str("  <version>1.0</version>\n");
+    body.push_str(&format!("  <room id=\"{}\">\n", xml::escape(room_id)));
+    for (from, to, day) in compress_ari_days(days) {
+        body.push_str(&format!(
+            "    <date from=\"{}\" to=\"{}\">\n",
+            from.format("%Y-%m-%d"),
+            to.format("%Y-%m-%d")
+        ));
+        body.push_str(&format!(
+            "      <roomstosell>{}</roomstosell>\n",
+            u8::from(day.available)
+        ));
+        if let Some(rate_id) = rate_id {
+            body.push_str(&format!("      <rate id=\"{}\"/>\n", xml::escape(rate_id)));
+            body.push_str(&format!(
+                "      <closed>{}</closed>\n",
+                u8::from(!day.available)
+            ));
+            if let Some(price) = day.rate {
+                body.push_str(&format!("      <price>{price:.2}</price>\n"));
+            }
+            if let Some(min_stay) = day.min_stay {
+                body.push_str(&format!("      <minimumstay>{min_stay}</minimumstay>\n"));
+            }
+            if let Some(max_stay) = day.max_stay {
+                body.push_str(&format!("      <maximumstay>{max_stay}</maximumstay>\n"));
+            }
+            body.push_str(&format!(
+                "      <closedonarrival>{}</closedonarrival>\n",
+                u8::from(day.closed_to_arrival)
+            ));
+            body.push_str(&format!(
+                "      <closedondeparture>{}</closedondeparture>\n",
+                u8::from(day.closed_to_departure)
+            ));
+        }
+        body.push_str("    </date>\n");
+    }
+    body.push_str("  </room>\n</request>\n");
+    body
+}
+
+/// B.XML answers `<ok>` on success, possibly with `<warning>`s, and
+/// `<error>` elements otherwise.
+fn check_ari_response(text: &str) -> Result<(), String> {
+    let root = xml::parse(text).map_err(|e| format!("Parse error: {e}"))?;
+    let errors = error_messages(&root);
+    if !errors.is_empty() {
+        return Err(format!(
+            "Booking.com ARI push failed: {}",
+            errors.join("; ")
+        ));
+    }
+    if root.local_name() != "ok" && root.child("ok").is_none() {
+        return Err(format!(
+            "Booking.com ARI push returned unexpected response <{}>.",
+            root.local_name()
+        ));
+    }
+    for warning in root.descendants("warning") {
+        tracing::warn!(warning = %warning.text.trim(), "Booking.com ARI push warning");
+    }
+    Ok(())
+}
+
+fn error_messages(root: &XmlElement) -> Vec<String> {
+    std::iter::once(root)
+        .filter(|element| element.local_name() == "error")
+        .chain(root.descendants("error"))
+        .map(|error| error.text.trim().to_string())
+        .filter(|text| !text.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::{routing::post, Router};
+
+    use super::*;
+
+    /// Recorded B.XML reservations response (identifiers anonymized).
+    const RESERVATIONS_FIXTURE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<reservations>
+  <reservation>
+    <commissionamount>36.00</commissionamount>
+    <currencycode>USD</currencycode>
+    <customer>
+      <first_name>Ana</first_name>
+      <last_name>Benítez</last_name>
+    </customer>
+    <date>2026-10-15</date>
+    <hotel_id>1234567</hotel_id>
+    <id>4012345678</id>
+    <room>
+      <arrival_date>2026-11-06</arrival_date>
+      <departure_date>2026-11-09</departure_date>
+      <guest_name>Ana Benítez</guest_name>
+      <id>123456701</id>
+      <roomreservation_id>9876543210</roomreservation_id>
+      <totalprice>240.00</totalprice>
+    </room>
+    <status>new</status>
+    <totalprice>240.00</totalprice>
+  </reservation>
+  <reservation>
+    <currencycode>EUR</currencycode>
+    <id>4012345679</id>
+    <room>
+      <arrival_date>2026-11-20</arrival_date>
+      <departure_date>2026-11-22</departure_date>
+      <id>123456702</id>
+    </room>
+    <status>new</status>
+  </reservation>
+  <reservation>
+    <currencycode>USD</currencycode>
+    <id>4012345680</id>
+    <room>
+      <arrival_date>2026-12-01</arrival_date>
+      <departure_date>2026-12-04</departure_date>
+      <id>123456701</id>
+      <totalprice>300.00</totalprice>
+    </room>
+    <status>cancelled</status>
+  </reservation>
+</reservations>"#;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    /// Serve the recorded responses on an ephemeral port and capture what the
+    /// connector sends.
+    async fn mock_server(captured: Arc<Mutex<Vec<(String, String)>>>) -> String {
+        let reservations_log = captured.clone();
+        let availability_log = captured;
+        let app = Router::new()
+            .route(
+                "/hotels/xml/reservations",
+                post(move |body: String| {
+                    let log = reservations_log.clone();
+                    async move {
+                        log.lock().unwrap().push(("reservations".to_string(), body));
+                        RESERVATIONS_FIXTURE
+                    }
+                }),
+            )
+            .route(
+                "/hotels/xml/availability",
+                post(move |body: String| {
+                    let log = availability_log.clone();
+                    async move {
+                        log.lock().unwrap().push(("availability".to_string(), body));
+                        "<?xml version=\"1.0\" encoding=\"UTF-8\"?><ok></ok>"
+                    }
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{addr}")
+    }
+
+    fn connector(api_base: String) -> BookingComConnector {
+        BookingComConnector {
+            config: BookingComConfig {
+                username: "casaora".to_string(),
+                password: "secret".to_string(),
+                api_base,
+            },
+            hotel_id: "1234567".to_string(),
+            room_id: "123456701".to_string(),
+            rate_id: Some("555".to_string()),
+        }
+    }
+
+    #[test]
+    fn parses_recorded_reservations_for_the_mapped_room() {
+        let parsed = parse_reservations(RESERVATIONS_FIXTURE, "123456701").unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].external_id, "4012345678");
+        assert_eq!(parsed[0].check_in, date("2026-11-06"));
+        assert_eq!(parsed[0].check_out, date("2026-11-09"));
+        assert_eq!(parsed[0].status, "confirmed");
+        assert_eq!(parsed[0].guest_name.as_deref(), Some("Ana Benítez"));
+        assert_eq!(parsed[0].total_amount, 240.0);
+        assert_eq!(parsed[0].currency.as_deref(), Some("USD"));
+        assert_eq!(parsed[1].status, "cancelled");
+    }
+
+    #[test]
+    fn error_responses_are_reported() {
+        let err = parse_reservations("<error>Authentication failed</error>", "1").unwrap_err();
+        assert!(err.contains("Authentication failed"));
+        assert!(check_ari_response("<ok><warning>rate closed</warning></ok>").is_ok());
+        assert!(check_ari_response("<failure><error>Invalid room</error></failure>").is_err());
+    }
+
+    #[tokio::test]
+    async fn round_trips_against_mock_server() {
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let connector = connector(mock_server(captured.clone()).await);
+        let http = Client::new();
+
+        let pulled = connector.pull_reservations(&http).await.unwrap();
+        assert_eq!(pulled.len(), 2);
+
+        let days = vec![
+            AriDay {
+                date: date("2026-11-01"),
+                available: true,
+                rate: Some(80.0),
+                min_stay: Some(2),
+                ..AriDay::default()
+            },
+            AriDay {
+                date: date("2026-11-02"),
+                available: true,
+                rate: Some(80.0),
+                min_stay: Some(2),
+                ..AriDay::default()
+            },
+            AriDay {
+                date: date("2026-11-03"),
+                available: false,
+                rate: Some(80.0),
+                min_stay: Some(2),
+                ..AriDay::default()
+            },
+        ];
+        connector.push_ari(&http, &days).await.unwrap();
+
+        let log = captured.lock().unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].0, "reservations");
+        assert!(log[0].1.contains("<hotel_id>1234567</hotel_id>"));
+        assert_eq!(log[1].0, "availability");
+        let request = xml::parse(&log[1].1).unwrap();
+        let room = request.child("room").unwrap();
+        assert_eq!(room.attr("id"), Some("123456701"));
+        let blocks: Vec<_> = room.children_named("date").collect();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].attr("from"), Some("2026-11-01"));
+        assert_eq!(blocks[0].attr("to"), Some("2026-11-02"));
+        assert_eq!(blocks[0].child_text("price"), Some("80.00"));
+        assert_eq!(blocks[0].child_text("minimumstay"), Some("2"));
+        assert_eq!(blocks[1].child_text("closed"), Some("1"));
+        assert_eq!(blocks[1].child_text("roomstosell"), Some("0"));
+    }
+}
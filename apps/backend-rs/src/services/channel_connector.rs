@@ -0,0 +1,708 @@
+use std::future::Future;
+
+use chrono::{Duration, NaiveDate, Utc};
+use reqwest::Client;
+use serde_json::{json, Value};
+use sqlx::{PgPool, Row};
+
+use crate::{
+    error::AppError,
+    services::{
+        airbnb::AirbnbConnector,
+        availability::{self, Occupancy},
+        booking_com::BookingComConnector,
+        stay_restrictions::{self, GapNights, StayRestriction},
+    },
+    state::AppState,
+};
+
+/// Days of availability, rates and restrictions pushed to each channel.
+const ARI_HORIZON_DAYS: i64 = 90;
+
+/// A booking as reported by a channel, normalized to Casaora's vocabulary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelReservation {
+    pub external_id: String,
+    pub check_in: NaiveDate,
+    pub check_out: NaiveDate,
+    /// Local `reservation_status`: `pending`, `confirmed` or `cancelled`.
+    pub status: &'static str,
+    pub guest_name: Option<String>,
+    pub total_amount: f64,
+    pub currency: Option<String>,
+}
+
+/// One night of availability, rate and restrictions (ARI) for a listing.
+/// Stay limits apply to arrivals on `date`, closed-to-departure to
+/// departures on it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AriDay {
+    pub date: NaiveDate,
+    pub available: bool,
+    pub rate: Option<f64>,
+    pub min_stay: Option<u32>,
+    pub max_stay: Option<u32>,
+    pub closed_to_arrival: bool,
+    pub closed_to_departure: bool,
+}
+
+/// Two-way API connection to a distribution channel: pull bookings in, push
+/// availability, rates and restrictions out. Each connector holds the
+/// credentials and listing mapping of one integration.
+pub trait ChannelConnector {
+    /// `integrations.kind` this connector serves.
+    fn kind(&self) -> &'static str;
+
+    /// Value written to `reservations.source` for pulled bookings.
+    fn reservation_source(&self) -> &'static str;
+
+    fn pull_reservations(
+        &self,
+        http: &Client,
+    ) -> impl Future<Output = Result<Vec<ChannelReservation>, String>> + Send;
+
+    fn push_ari(
+        &self,
+        http: &Client,
+        days: &[AriDay],
+    ) -> impl Future<Output = Result<(), String>> + Send;
+}
+
+/// An `integrations` row with an API-connected channel.
+#[derive(Debug, Clone)]
+pub struct ChannelIntegration {
+    pub id: String,
+    pub organization_id: String,
+    pub unit_id: String,
+    pub kind: String,
+    pub external_account_ref: Option<String>,
+    pub external_listing_id: Option<String>,
+    pub metadata: Value,
+}
+
+impl ChannelIntegration {
+    pub fn metadata_str(&self, key: &str) -> Option<String> {
+        self.metadata
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(ToOwned::to_owned)
+    }
+
+    /// Minimum stay pushed with ARI for dates without a unit stay
+    /// restriction, from `metadata.min_stay`.
+    fn default_min_stay(&self) -> Option<u32> {
+        self.metadata
+            .get("min_stay")
+            .and_then(|value| {
+                value
+                    .as_u64()
+                    .or_else(|| value.as_str().and_then(|raw| raw.trim().parse().ok()))
+            })
+            .and_then(|value| u32::try_from(value).ok())
+            .filter(|value| *value > 0)
+    }
+}
+
+/// Connector for any supported channel, chosen by `integrations.kind`.
+pub enum Connector {
+    Airbnb(AirbnbConnector),
+    BookingCom(BookingComConnector),
+}
+
+impl Connector {
+    /// Build the connector for an integration, or explain what is missing.
+    pub fn for_integration(integration: &ChannelIntegration) -> Result<Self, String> {
+        match integration.kind.as_str() {
+            "airbnb" => AirbnbConnector::from_integration(integration).map(Self::Airbnb),
+            "bookingcom" => {
+                BookingComConnector::from_integration(integration).map(Self::BookingCom)
+            }
+            other => Err(format!("Channel '{other}' has no API connector.")),
+        }
+    }
+}
+
+impl ChannelConnector for Connector {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Airbnb(connector) => connector.kind(),
+            Self::BookingCom(connector) => connector.kind(),
+        }
+    }
+
+    fn reservation_source(&self) -> &'static str {
+        match self {
+            Self::Airbnb(connector) => connector.reservation_source(),
+            Self::BookingCom(connector) => connector.reservation_source(),
+        }
+    }
+
+    async fn pull_reservations(&self, http: &Client) -> Result<Vec<ChannelReservation>, String> {
+        match self {
+            Self::Airbnb(connector) => connector.pull_reservations(http).await,
+            Self::BookingCom(connector) => connector.pull_reservations(http).await,
+        }
+    }
+
+    async fn push_ari(&self, http: &Client, days: &[AriDay]) -> Result<(), String> {
+        match self {
+            Self::Airbnb(connector) => connector.push_ari(http, days).await,
+            Self::BookingCom(connector) => connector.push_ari(http, days).await,
+        }
+    }
+}
+
+/// Active API-connected integrations, optionally a single one by id.
+pub async fn load_channel_integrations(
+    pool: &PgPool,
+    integration_id: Option<&str>,
+) -> Result<Vec<ChannelIntegration>, String> {
+    let rows = sqlx::query(
+        "SELECT id::text AS id,
+                organization_id::text AS organization_id,
+                unit_id::text AS unit_id,
+                kind::text AS kind,
+                external_account_ref,
+                external_listing_id,
+                COALESCE(metadata, '{}'::jsonb) AS metadata
+         FROM integrations
+         WHERE kind IN ('airbnb', 'bookingcom')
+           AND ($1::uuid IS NULL OR id = $1::uuid)
+           AND ($1::uuid IS NOT NULL OR is_active = true)
+         ORDER BY organization_id, id
+         LIMIT 500",
+    )
+    .bind(integration_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("DB error: {e}"))?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            Some(ChannelIntegration {
+                id: row.try_get("id").ok()?,
+                organization_id: row.try_get("organization_id").ok()?,
+                unit_id: row.try_get("unit_id").ok()?,
+                kind: row.try_get("kind").ok()?,
+                external_account_ref: row.try_get("external_account_ref").ok().flatten(),
+                external_listing_id: row.try_get("external_listing_id").ok().flatten(),
+                metadata: row.try_get("metadata").unwrap_or(Value::Null),
+            })
+        })
+        .collect())
+}
+
+/// Pull a channel's reservations and upsert them on the integration's unit.
+///
+/// Overlaps with anything else on the unit are reported under `conflicts`
+/// (they also surface in the calendar conflicts inbox); a row the database
+/// refuses is reported there too instead of aborting the whole sync.
+pub async fn sync_channel_reservations<C: ChannelConnector>(
+    pool: &PgPool,
+    http: &Client,
+    connector: &C,
+    integration: &ChannelIntegration,
+) -> Result<Value, String> {
+    let reservations = connector.pull_reservations(http).await?;
+
+    let today = Utc::now().date_naive();
+    let occupancy =
+        availability::load_unit_occupancy(pool, &integration.unit_id, today, Some(&integration.id))
+            .await
+            .map_err(|e| format!("DB error: {}", e.detail_message()))?;
+
+    let mut created = 0i64;
+    let mut updated = 0i64;
+    let mut conflicts: Vec<Value> = Vec::new();
+
+    for reservation in &reservations {
+        if reservation.status != "cancelled" {
+            conflicts.extend(overlap_conflicts(reservation, &occupancy));
+        }
+
+        let (currency, total_amount) = match reservation
+            .currency
+            .as_deref()
+            .and_then(crate::services::fx::normalize_currency)
+        {
+            Some(code) => (Some(code), reservation.total_amount),
+            None => (None, 0.0),
+        };
+        let notes = channel_notes(connector.kind(), reservation, currency.is_none());
+
+        let existing = sqlx::query_scalar::<_, String>(
+            "SELECT id::text FROM reservations
+             WHERE organization_id = $1::uuid
+               AND integration_id = $2::uuid
+               AND external_reservation_id = $3
+             LIMIT 1",
+        )
+        .bind(&integration.organization_id)
+        .bind(&integration.id)
+        .bind(&reservation.external_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("DB error: {e}"))?;
+
+        let result = if let Some(existing_id) = existing {
+            sqlx::query(
+                "UPDATE reservations SET
+                   check_in_date = $2, check_out_date = $3,
+                   status = $4::reservation_status,
+                   total_amount = $5,
+                   currency = COALESCE($6, currency),
+                   updated_at = now()
+                 WHERE id = $1::uuid",
+            )
+            .bind(&existing_id)
+            .bind(reservation.check_in)
+            .bind(reservation.check_out)
+            .bind(reservation.status)
+            .bind(total_amount)
+            .bind(currency.as_deref())
+            .execute(pool)
+            .await
+            .map(|_| false)
+        } else if reservation.status == "cancelled" {
+            continue;
+        } else {
+            sqlx::query(
+                "INSERT INTO reservations (
+                   organization_id, unit_id, integration_id, external_reservation_id, source,
+                   check_in_date, check_out_date, status, total_amount, currency, notes
+                 )
+                 VALUES ($1::uuid, $2::uuid, $3::uuid, $4, $5, $6, $7,
+                   $8::reservation_status, $9, COALESCE($10, 'PYG'), $11)",
+            )
+            .bind(&integration.organization_id)
+            .bind(&integration.unit_id)
+            .bind(&integration.id)
+            .bind(&reservation.external_id)
+            .bind(connector.reservation_source())
+            .bind(reservation.check_in)
+            .bind(reservation.check_out)
+            .bind(reservation.status)
+            .bind(total_amount)
+            .bind(currency.as_deref())
+            .bind(&notes)
+            .execute(pool)
+            .await
+            .map(|_| true)
+        };
+
+        match result {
+            Ok(true) => created += 1,
+            Ok(false) => updated += 1,
+            Err(e) => conflicts.push(json!({
+                "external_reservation_id": reservation.external_id,
+                "check_in_date": reservation.check_in.to_string(),
+                "check_out_date": reservation.check_out.to_string(),
+                "error": AppError::from_database_error(&e, "Failed to store channel reservation.")
+                    .detail_message(),
+            })),
+        }
+    }
+
+    sqlx::query(
+        "UPDATE integrations SET last_ical_sync_at = now(), ical_sync_error = NULL
+         WHERE id = $1::uuid",
+    )
+    .bind(&integration.id)
+    .execute(pool)
+    .await
+    .ok();
+
+    Ok(json!({
+        "channel": connector.kind(),
+        "created": created,
+        "updated": updated,
+        "total_fetched": reservations.len(),
+        "conflicts": conflicts,
+    }))
+}
+
+fn overlap_conflicts(reservation: &ChannelReservation, occupancy: &[Occupancy]) -> Vec<Value> {
+    availability::overlapping(occupancy, reservation.check_in, reservation.check_out)
+        .map(|other| {
+            json!({
+                "external_reservation_id": reservation.external_id,
+                "check_in_date": reservation.check_in.to_string(),
+                "check_out_date": reservation.check_out.to_string(),
+                "conflicting_kind": other.kind.as_str(),
+                "conflicting_id": other.id,
+                "conflicting_source": other.source,
+                "conflicting_start_date": other.start.to_string(),
+                "conflicting_end_date": other.end.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn channel_notes(kind: &str, reservation: &ChannelReservation, unknown_currency: bool) -> String {
+    let mut notes = format!("{kind}: {}", reservation.external_id);
+    if let Some(name) = reservation.guest_name.as_deref().filter(|n| !n.is_empty()) {
+        notes.push_str(&format!(" · {name}"));
+    }
+    if unknown_currency && reservation.total_amount > 0.0 {
+        notes.push_str(&format!(
+            " · channel total {:.2} {}",
+            reservation.total_amount,
+            reservation.currency.as_deref().unwrap_or("?")
+        ));
+    }
+    notes
+}
+
+/// An applied pricing recommendation for a unit, inclusive of both ends.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateOverride {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub rate: f64,
+}
+
+/// Lay out the ARI calendar: a night is available when nothing else holds
+/// the unit, and priced at the most recently applied recommendation covering
+/// it, falling back to the unit's base nightly rate.
+pub fn compose_ari_days(
+    from: NaiveDate,
+    days: i64,
+    occupancy: &[Occupancy],
+    base_rate: Option<f64>,
+    overrides: &[RateOverride],
+) -> Vec<AriDay> {
+    (0..days)
+        .map(|offset| {
+            let date = from + Duration::days(offset);
+            let available = !occupancy
+                .iter()
+                .any(|item| item.overlaps(date, date + Duration::days(1)));
+            let rate = overrides
+                .iter()
+                .rev()
+                .find(|item| item.start <= date && date <= item.end)
+                .map(|item| item.rate)
+                .or(base_rate)
+                .filter(|rate| *rate > 0.0);
+            AriDay {
+                date,
+                available,
+                rate,
+                ..AriDay::default()
+            }
+        })
+        .collect()
+}
+
+/// Attach the unit's stay restrictions to each day, falling back to
+/// `default_min_stay`. Arrivals on the first night of a fillable orphan gap
+/// get a minimum stay equal to the gap, so channels can sell it.
+pub fn apply_stay_rules(
+    days: &mut [AriDay],
+    restrictions: &[StayRestriction],
+    default_min_stay: Option<u32>,
+    gaps: &[GapNights],
+) {
+    for day in days.iter_mut() {
+        let rules = stay_restrictions::rules_for(restrictions, day.date);
+        day.min_stay = gaps
+            .iter()
+            .find(|gap| gap.allow_gap_fill && gap.start == day.date)
+            .map(|gap| gap.nights)
+            .or(rules.min_nights)
+            .or(default_min_stay);
+        day.max_stay = rules.max_nights;
+        day.closed_to_arrival = rules.closed_to_arrival;
+        day.closed_to_departure = rules.closed_to_departure;
+    }
+}
+
+/// Build the outbound ARI calendar for an integration from the availability
+/// engine and dynamic pricing.
+pub async fn build_ari_days(
+    pool: &PgPool,
+    integration: &ChannelIntegration,
+    from: NaiveDate,
+    days: i64,
+) -> Result<Vec<AriDay>, String> {
+    // Everything except this channel's own bookings and blocks, which would
+    // otherwise be echoed back to it.
+    let occupancy =
+        availability::load_unit_occupancy(pool, &integration.unit_id, from, Some(&integration.id))
+            .await
+            .map_err(|e| format!("DB error: {}", e.detail_message()))?;
+
+    let base_rate: Option<f64> = sqlx::query_scalar(
+        "SELECT COALESCE(NULLIF(default_nightly_rate, 0), base_price_nightly)::float8
+         FROM units
+         WHERE id = $1::uuid",
+    )
+    .bind(&integration.unit_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("DB error: {e}"))?
+    .flatten();
+
+    let overrides = sqlx::query(
+        "SELECT date_range_start AS start_date,
+                date_range_end AS end_date,
+                COALESCE(
+                  (to_jsonb(pr) ->> 'recommended_rate')::float8,
+                  (to_jsonb(pr) ->> 'recommended_price')::float8
+                ) AS rate
+         FROM pricing_recommendations pr
+         WHERE pr.unit_id = $1::uuid
+           AND pr.status = 'applied'
+           AND pr.date_range_start IS NOT NULL
+           AND pr.date_range_end >= $2::date
+         ORDER BY COALESCE(pr.applied_at, pr.created_at)",
+    )
+    .bind(&integration.unit_id)
+    .bind(from)
+    .fetch_all(pool)
+    .await
+    .map(|rows| {
+        rows.into_iter()
+            .filter_map(|row| {
+                Some(RateOverride {
+                    start: row.try_get("start_date").ok()?,
+                    end: row.try_get("end_date").ok()?,
+                    rate: row.try_get::<Option<f64>, _>("rate").ok().flatten()?,
+                })
+            })
+            .collect::<Vec<_>>()
+    })
+    .unwrap_or_else(|e| {
+        tracing::warn!(error = %e, unit_id = %integration.unit_id, "Failed to load applied pricing recommendations");
+        Vec::new()
+    });
+
+    let to = from + Duration::days(days);
+    let restrictions = stay_restrictions::load_restrictions(
+        pool,
+        Some(&integration.organization_id),
+        Some(&integration.unit_id),
+        from,
+        to,
+    )
+    .await
+    .map_err(|e| format!("DB error: {}", e.detail_message()))?;
+    // Gaps are measured against every booking, this channel's included.
+    let all_occupancy = availability::load_unit_occupancy(pool, &integration.unit_id, from, None)
+        .await
+        .map_err(|e| format!("DB error: {}", e.detail_message()))?;
+    let gaps = stay_restrictions::find_gap_nights(&all_occupancy, &restrictions, from, to);
+
+    let mut ari = compose_ari_days(from, days, &occupancy, base_rate, &overrides);
+    apply_stay_rules(
+        &mut ari,
+        &restrictions,
+        integration.default_min_stay(),
+        &gaps,
+    );
+    Ok(ari)
+}
+
+/// Build and push the ARI calendar for one integration, recording the
+/// outcome on the integration row.
+pub async fn push_integration_ari(
+    pool: &PgPool,
+    http: &Client,
+    integration: &ChannelIntegration,
+) -> Result<Value, String> {
+    let outcome = async {
+        let connector = Connector::for_integration(integration)?;
+        let today = Utc::now().date_naive();
+        let days = build_ari_days(pool, integration, today, ARI_HORIZON_DAYS).await?;
+        connector.push_ari(http, &days).await?;
+        Ok::<_, String>(json!({
+            "integration_id": integration.id,
+            "channel": connector.kind(),
+            "days": days.len(),
+            "closed": days.iter().filter(|day| !day.available).count(),
+            "priced": days.iter().filter(|day| day.rate.is_some()).count(),
+        }))
+    }
+    .await;
+
+    let error = outcome.as_ref().err();
+    sqlx::query(
+        "UPDATE integrations
+         SET last_ari_push_at = now(), ari_push_error = $2
+         WHERE id = $1::uuid",
+    )
+    .bind(&integration.id)
+    .bind(error)
+    .execute(pool)
+    .await
+    .ok();
+
+    outcome
+}
+
+/// Push availability, dynamic rates and restrictions to every API-connected
+/// channel. Integrations whose connector is not configured are skipped.
+pub async fn sync_all_outbound_rates(state: &AppState) -> Result<Value, String> {
+    let Some(pool) = state.db_pool.as_ref() else {
+        return Ok(Value::Null);
+    };
+
+    let integrations = load_channel_integrations(pool, None).await?;
+    let mut synced = 0u32;
+    let mut skipped = 0u32;
+    let mut failed = 0u32;
+
+    for integration in &integrations {
+        if let Err(reason) = Connector::for_integration(integration) {
+            tracing::debug!(integration_id = %integration.id, reason, "Outbound ARI push skipped");
+            skipped += 1;
+            continue;
+        }
+        match push_integration_ari(pool, &state.http_client, integration).await {
+            Ok(_) => synced += 1,
+            Err(error) => {
+                tracing::warn!(
+                    integration_id = %integration.id,
+                    channel = %integration.kind,
+                    error = %error,
+                    "Outbound channel ARI push failed"
+                );
+                failed += 1;
+            }
+        }
+    }
+
+    if synced > 0 {
+        tracing::info!(
+            synced,
+            failed,
+            "Scheduler: outbound channel rate/availability sync completed"
+        );
+    }
+
+    Ok(json!({
+        "integrations": integrations.len(),
+        "synced": synced,
+        "skipped": skipped,
+        "failed": failed,
+    }))
+}
+
+/// Collapse consecutive days with identical values into inclusive ranges,
+/// as accepted by ARI endpoints that take `from`/`to` blocks.
+pub fn compress_ari_days(days: &[AriDay]) -> Vec<(NaiveDate, NaiveDate, &AriDay)> {
+    let mut ranges: Vec<(NaiveDate, NaiveDate, &AriDay)> = Vec::new();
+    for day in days {
+        match ranges.last_mut() {
+            Some((_, end, template))
+                if *end + Duration::days(1) == day.date
+                    && template.available == day.available
+                    && template.rate == day.rate
+                    && template.min_stay == day.min_stay
+                    && template.max_stay == day.max_stay
+                    && template.closed_to_arrival == day.closed_to_arrival
+                    && template.closed_to_departure == day.closed_to_departure =>
+            {
+                *end = day.date;
+            }
+            _ => ranges.push((day.date, day.date, day)),
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::availability::OccupancyKind;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn ari_days_close_occupied_nights_and_apply_latest_override() {
+        let occupancy = vec![Occupancy {
+            kind: OccupancyKind::Reservation,
+            id: "r1".to_string(),
+            unit_id: "u1".to_string(),
+            start: date("2026-11-02"),
+            end: date("2026-11-04"),
+            source: "manual".to_string(),
+            integration_id: None,
+            created_at: None,
+        }];
+        let overrides = vec![
+            RateOverride {
+                start: date("2026-11-01"),
+                end: date("2026-11-05"),
+                rate: 90.0,
+            },
+            RateOverride {
+                start: date("2026-11-04"),
+                end: date("2026-11-04"),
+                rate: 120.0,
+            },
+        ];
+        let days = compose_ari_days(date("2026-11-01"), 7, &occupancy, Some(80.0), &overrides);
+
+        let available: Vec<bool> = days.iter().map(|day| day.available).collect();
+        assert_eq!(available, vec![true, false, false, true, true, true, true]);
+        let rates: Vec<Option<f64>> = days.iter().map(|day| day.rate).collect();
+        assert_eq!(
+            rates,
+            vec![
+                Some(90.0),
+                Some(90.0),
+                Some(90.0),
+                Some(120.0),
+                Some(90.0),
+                Some(80.0),
+                Some(80.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn stay_rules_override_default_and_open_gap_nights() {
+        let mut days = compose_ari_days(date("2026-11-01"), 4, &[], Some(80.0), &[]);
+        let restrictions = vec![StayRestriction {
+            id: "s1".to_string(),
+            unit_id: "u1".to_string(),
+            starts_on: Some(date("2026-11-03")),
+            ends_on: None,
+            days_of_week: Vec::new(),
+            min_nights: Some(3),
+            max_nights: Some(10),
+            closed_to_arrival: false,
+            closed_to_departure: true,
+            allow_gap_fill: true,
+        }];
+        let gaps = vec![GapNights {
+            start: date("2026-11-04"),
+            end: date("2026-11-05"),
+            nights: 1,
+            min_nights: 3,
+            allow_gap_fill: true,
+        }];
+        apply_stay_rules(&mut days, &restrictions, Some(2), &gaps);
+
+        let min_stays: Vec<Option<u32>> = days.iter().map(|day| day.min_stay).collect();
+        assert_eq!(min_stays, vec![Some(2), Some(2), Some(3), Some(1)]);
+        assert_eq!(days[0].max_stay, None);
+        assert_eq!(days[2].max_stay, Some(10));
+        assert!(days[2].closed_to_departure && !days[1].closed_to_departure);
+    }
+
+    #[test]
+    fn compress_merges_identical_consecutive_days() {
+        let days = compose_ari_days(date("2026-11-01"), 5, &[], Some(50.0), &[]);
+        let ranges = compress_ari_days(&days);
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0].0, date("2026-11-01"));
+        assert_eq!(ranges[0].1, date("2026-11-05"));
+    }
+}
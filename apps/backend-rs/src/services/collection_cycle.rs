@@ -5,16 +5,23 @@ use tracing::{info, warn};
 
 use crate::{
     repository::table_service::{create_row, list_rows, update_row},
-    services::notification_center::{emit_event, EmitNotificationEventInput},
+    services::{
+        late_fees::apply_late_fees,
+        notification_center::{emit_event, EmitNotificationEventInput},
+        payment_ledger::{apply_all_credit, refresh_lease_delinquency},
+    },
 };
 
 /// Result of a daily collection cycle run.
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct CollectionCycleResult {
     pub activated: u32,
+    pub credit_allocations: u32,
     pub reminders_queued: u32,
     pub marked_late: u32,
     pub escalated: u32,
+    pub late_fees_assessed: u32,
+    pub late_fees_accrued: u32,
     pub errors: u32,
 }
 
@@ -26,6 +33,9 @@ pub struct CollectionCycleResult {
 ///   D-day: Send final reminder ("payment due today")
 ///   D+3:  Mark as late, send late notice
 ///   D+7:  Escalate — send urgent notice to tenant + alert to owner
+///
+/// Late fees are assessed each run according to the lease's late-fee policy
+/// (see `services::late_fees`), independently of the D+3 late marking.
 pub async fn run_daily_collection_cycle(
     pool: &PgPool,
     org_id: Option<&str>,
@@ -34,9 +44,12 @@ pub async fn run_daily_collection_cycle(
     let today = Utc::now().date_naive();
     let mut result = CollectionCycleResult {
         activated: 0,
+        credit_allocations: 0,
         reminders_queued: 0,
         marked_late: 0,
         escalated: 0,
+        late_fees_assessed: 0,
+        late_fees_accrued: 0,
         errors: 0,
     };
 
@@ -44,6 +57,12 @@ pub async fn run_daily_collection_cycle(
     let d_minus_3 = today + chrono::Duration::days(3);
     activate_upcoming_collections(pool, org_id, &d_minus_3, &mut result).await;
 
+    // Phase 1b: Apply credit carried forward from earlier overpayments, so
+    // tenants already covered are not reminded
+    let (credit_allocations, credit_errors) = apply_all_credit(pool, org_id).await;
+    result.credit_allocations = credit_allocations;
+    result.errors += credit_errors;
+
     // Phase 2: Send reminders for pending collections
     send_reminders(pool, org_id, &today, app_public_url, &mut result).await;
 
@@ -55,11 +74,20 @@ pub async fn run_daily_collection_cycle(
     let d_plus_7 = today - chrono::Duration::days(7);
     escalate_late_collections(pool, org_id, &d_plus_7, app_public_url, &mut result).await;
 
+    // Phase 5: Assess and accrue late fees past each policy's grace period
+    let late_fees = apply_late_fees(pool, org_id, today).await;
+    result.late_fees_assessed = late_fees.assessed;
+    result.late_fees_accrued = late_fees.accrued;
+    result.errors += late_fees.errors;
+
     info!(
         activated = result.activated,
+        credit_allocations = result.credit_allocations,
         reminders = result.reminders_queued,
         late = result.marked_late,
         escalated = result.escalated,
+        late_fees_assessed = result.late_fees_assessed,
+        late_fees_accrued = result.late_fees_accrued,
         errors = result.errors,
         "Collection cycle completed"
     );
@@ -345,7 +373,7 @@ async fn mark_late_collections(
 
         // Refresh lease status to delinquent
         if !lease_id.is_empty() {
-            let _ = refresh_lease_delinquent(pool, &lease_id).await;
+            let _ = refresh_lease_delinquency(pool, &lease_id).await;
         }
 
         // Send late payment notice to tenant
@@ -753,29 +781,6 @@ async fn already_sent_today(
     })
 }
 
-/// Refresh lease status based on overdue collections.
-async fn refresh_lease_delinquent(
-    pool: &PgPool,
-    lease_id: &str,
-) -> Result<(), crate::error::AppError> {
-    let lease = crate::repository::table_service::get_row(pool, "leases", lease_id, "id").await?;
-    let status = val_str(&lease, "lease_status");
-    if status != "active" && status != "delinquent" {
-        return Ok(());
-    }
-
-    if status != "delinquent" {
-        let mut patch = Map::new();
-        patch.insert(
-            "lease_status".to_string(),
-            Value::String("delinquent".to_string()),
-        );
-        update_row(pool, "leases", lease_id, &patch, "id").await?;
-    }
-
-    Ok(())
-}
-
 fn format_amount(amount: f64, currency: &str) -> String {
     match currency {
         "PYG" => format!("₲{}", format_number_with_dots(amount as i64)),
@@ -1,25 +1,58 @@
 #![allow(dead_code)]
 
-use chrono::{Datelike, NaiveDateTime, Timelike, Utc};
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc,
+};
+use chrono_tz::Tz;
 
-/// Simple cron expression parser supporting: minute hour day_of_month month day_of_week
-/// Supports: specific values, wildcards (*), and lists (1,15).
+const MONTH_NAMES: &[&str] = &[
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAY_NAMES: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// How far ahead `next_after` searches. Five years covers `0 0 29 2 *`.
+const MAX_SEARCH_DAYS: i64 = 366 * 5;
+
+/// Cron expression parser supporting: minute hour day_of_month month day_of_week
+///
+/// Supports specific values, wildcards (`*` / `?`), lists (`1,15`), ranges
+/// (`1-5`), steps (`*/15`, `10-40/10`), month and weekday names (`JAN`,
+/// `MON-FRI`), `L` for the last day of the month, `5L` / `FRIL` for the last
+/// given weekday, `MON#2` for the nth weekday, and the `@yearly`, `@monthly`,
+/// `@weekly`, `@daily` and `@hourly` macros.
+///
+/// Schedules are evaluated in their IANA timezone (UTC unless one is given).
+/// When day-of-month and day-of-week are both restricted, a day matches if
+/// either does (classic Vixie cron semantics).
+///
+/// DST transitions: a wall-clock time skipped by a spring-forward gap fires at
+/// the first valid instant after the gap; a time repeated by a fall-back
+/// overlap fires once, on its first occurrence.
+#[derive(Debug, Clone)]
 pub struct CronSchedule {
     minutes: Vec<u32>,
     hours: Vec<u32>,
     days_of_month: Vec<u32>,
+    last_day_of_month: bool,
     months: Vec<u32>,
     days_of_week: Vec<u32>,
+    nth_weekdays: Vec<(u32, u32)>,
+    last_weekdays: Vec<u32>,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+    timezone: Tz,
 }
 
 impl CronSchedule {
-    /// Parse a standard 5-field cron expression.
+    /// Parse a standard 5-field cron expression evaluated in UTC.
     /// Format: "minute hour day_of_month month day_of_week"
     /// Example: "0 8 * * *" = every day at 08:00
     /// Example: "0 */2 * * *" = every 2 hours
-    /// Example: "0 9 * * 1-5" = weekdays at 09:00
+    /// Example: "0 9 * * MON-FRI" = weekdays at 09:00
+    /// Example: "0 18 L * *" = last day of every month at 18:00
     pub fn parse(expr: &str) -> Result<Self, String> {
-        let parts: Vec<&str> = expr.split_whitespace().collect();
+        let expanded = expand_macro(expr.trim())?;
+        let parts: Vec<&str> = expanded.split_whitespace().collect();
         if parts.len() != 5 {
             return Err(format!(
                 "Invalid cron expression: expected 5 fields, got {}",
@@ -27,149 +60,345 @@ impl CronSchedule {
             ));
         }
 
+        let (days_of_month, last_day_of_month) = parse_day_of_month_field(parts[2])?;
+        let (days_of_week, nth_weekdays, last_weekdays) = parse_day_of_week_field(parts[4])?;
+
         Ok(CronSchedule {
-            minutes: parse_field(parts[0], 0, 59)?,
-            hours: parse_field(parts[1], 0, 23)?,
-            days_of_month: parse_field(parts[2], 1, 31)?,
-            months: parse_field(parts[3], 1, 12)?,
-            days_of_week: parse_field(parts[4], 0, 6)?,
+            minutes: parse_field(parts[0], 0, 59, &[])?,
+            hours: parse_field(parts[1], 0, 23, &[])?,
+            days_of_month,
+            last_day_of_month,
+            months: parse_field(parts[3], 1, 12, MONTH_NAMES)?,
+            days_of_week,
+            nth_weekdays,
+            last_weekdays,
+            day_of_month_restricted: !is_wildcard(parts[2]),
+            day_of_week_restricted: !is_wildcard(parts[4]),
+            timezone: Tz::UTC,
         })
     }
 
-    /// Check if the given datetime matches this cron schedule.
+    /// Parse an expression evaluated in the given IANA timezone
+    /// (e.g. `America/Asuncion`).
+    pub fn parse_in_timezone(expr: &str, timezone: &str) -> Result<Self, String> {
+        let timezone = parse_timezone(timezone)?;
+        Ok(Self::parse(expr)?.with_timezone(timezone))
+    }
+
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    /// Check if the given local (wall-clock) datetime matches this schedule.
     pub fn matches(&self, dt: &NaiveDateTime) -> bool {
-        let minute = dt.minute();
-        let hour = dt.hour();
-        let day = dt.day();
-        let month = dt.month();
-        let weekday = dt.weekday().num_days_from_sunday(); // 0=Sun, 6=Sat
-
-        self.minutes.contains(&minute)
-            && self.hours.contains(&hour)
-            && self.days_of_month.contains(&day)
-            && self.months.contains(&month)
-            && self.days_of_week.contains(&weekday)
-    }
-
-    /// Compute the next occurrence after the given datetime.
-    pub fn next_after(&self, after: &NaiveDateTime) -> Option<NaiveDateTime> {
-        let mut dt = *after + chrono::Duration::minutes(1);
-        // Zero out seconds
-        dt = dt
-            .date()
-            .and_hms_opt(dt.hour(), dt.minute(), 0)
-            .unwrap_or(dt);
-
-        // Search up to 1 year ahead
-        let limit = *after + chrono::Duration::days(366);
-        while dt < limit {
-            if self.matches(&dt) {
-                return Some(dt);
+        self.minutes.contains(&dt.minute())
+            && self.hours.contains(&dt.hour())
+            && self.day_matches(dt.date())
+    }
+
+    /// Compute the next fire time strictly after the given instant.
+    pub fn next_after(&self, after: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let local_after = after.with_timezone(&self.timezone).naive_local();
+        let start = local_after.date();
+
+        for offset in 0..MAX_SEARCH_DAYS {
+            let date = start + Duration::days(offset);
+            if !self.day_matches(date) {
+                continue;
             }
-            dt += chrono::Duration::minutes(1);
-            // Skip ahead if hour doesn't match
-            if !self.hours.contains(&dt.hour()) {
-                dt = dt.date().and_hms_opt(dt.hour() + 1, 0, 0).unwrap_or(dt);
-                if dt.hour() == 0 {
-                    dt += chrono::Duration::days(1);
-                    dt = dt.date().and_hms_opt(0, 0, 0).unwrap_or(dt);
+            for &hour in &self.hours {
+                for &minute in &self.minutes {
+                    let Some(local) = date.and_hms_opt(hour, minute, 0) else {
+                        continue;
+                    };
+                    if let Some(instant) = self.resolve_local(local) {
+                        if instant > *after {
+                            return Some(instant);
+                        }
+                    }
                 }
             }
         }
         None
     }
 
-    /// Check if this schedule should fire now (within the last minute window).
+    /// The next `count` fire times strictly after the given instant.
+    pub fn upcoming(&self, after: &DateTime<Utc>, count: usize) -> Vec<DateTime<Utc>> {
+        let mut times = Vec::with_capacity(count);
+        let mut cursor = *after;
+        while times.len() < count {
+            let Some(next) = self.next_after(&cursor) else {
+                break;
+            };
+            times.push(next);
+            cursor = next;
+        }
+        times
+    }
+
+    /// Whether a fire time has passed since `last_run` (i.e. the job is due).
+    pub fn is_due(&self, last_run: &DateTime<Utc>, now: &DateTime<Utc>) -> bool {
+        self.next_after(last_run).is_some_and(|next| next <= *now)
+    }
+
+    /// Check if this schedule should fire now (within the current minute).
     pub fn should_fire_now(&self) -> bool {
-        let now = Utc::now().naive_utc();
+        let now = Utc::now().with_timezone(&self.timezone).naive_local();
         self.matches(&now)
     }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        if !self.months.contains(&date.month()) {
+            return false;
+        }
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => self.day_of_month_matches(date) || self.day_of_week_matches(date),
+            (true, false) => self.day_of_month_matches(date),
+            (false, true) => self.day_of_week_matches(date),
+            (false, false) => true,
+        }
+    }
+
+    fn day_of_month_matches(&self, date: NaiveDate) -> bool {
+        self.days_of_month.contains(&date.day())
+            || (self.last_day_of_month && date.day() == last_day_of_month(date))
+    }
+
+    fn day_of_week_matches(&self, date: NaiveDate) -> bool {
+        let weekday = date.weekday().num_days_from_sunday(); // 0=Sun, 6=Sat
+        let day = date.day();
+        self.days_of_week.contains(&weekday)
+            || self
+                .nth_weekdays
+                .iter()
+                .any(|&(wd, nth)| wd == weekday && (day - 1) / 7 + 1 == nth)
+            || self
+                .last_weekdays
+                .iter()
+                .any(|&wd| wd == weekday && day + 7 > last_day_of_month(date))
+    }
+
+    /// Map a local wall-clock time to the instant it fires at.
+    fn resolve_local(&self, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self.timezone.from_local_datetime(&local) {
+            LocalResult::Single(dt) => Some(dt.with_timezone(&Utc)),
+            LocalResult::Ambiguous(earliest, _) => Some(earliest.with_timezone(&Utc)),
+            LocalResult::None => (1..=180).find_map(|minutes| {
+                match self
+                    .timezone
+                    .from_local_datetime(&(local + Duration::minutes(minutes)))
+                {
+                    LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => {
+                        Some(dt.with_timezone(&Utc))
+                    }
+                    LocalResult::None => None,
+                }
+            }),
+        }
+    }
 }
 
-/// Parse a single cron field into a list of matching values.
-fn parse_field(field: &str, min: u32, max: u32) -> Result<Vec<u32>, String> {
-    let field = field.trim();
+/// Parse an IANA timezone name such as `America/Asuncion`.
+pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Ok(Tz::UTC);
+    }
+    name.parse::<Tz>()
+        .map_err(|_| format!("Unknown timezone: {name}"))
+}
 
-    // Wildcard
-    if field == "*" {
-        return Ok((min..=max).collect());
+fn expand_macro(expr: &str) -> Result<String, String> {
+    if !expr.starts_with('@') {
+        return Ok(expr.to_string());
     }
+    let expanded = match expr.to_ascii_lowercase().as_str() {
+        "@yearly" | "@annually" => "0 0 1 1 *",
+        "@monthly" => "0 0 1 * *",
+        "@weekly" => "0 0 * * 0",
+        "@daily" | "@midnight" => "0 0 * * *",
+        "@hourly" => "0 * * * *",
+        _ => return Err(format!("Unsupported cron macro: {expr}")),
+    };
+    Ok(expanded.to_string())
+}
+
+fn is_wildcard(field: &str) -> bool {
+    matches!(field.trim(), "*" | "?")
+}
+
+fn last_day_of_month(date: NaiveDate) -> u32 {
+    let (year, month) = if date.month() == 12 {
+        (date.year() + 1, 1)
+    } else {
+        (date.year(), date.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|first| first.pred_opt())
+        .map(|last| last.day())
+        .unwrap_or(28)
+}
 
-    // Step: */N or M/N
-    if field.contains('/') {
-        let parts: Vec<&str> = field.splitn(2, '/').collect();
-        let start = if parts[0] == "*" {
-            min
+/// Day-of-month field: regular values plus `L` (last day of the month).
+fn parse_day_of_month_field(field: &str) -> Result<(Vec<u32>, bool), String> {
+    let mut last_day = false;
+    let mut regular = Vec::new();
+    for item in field.trim().split(',') {
+        if item.trim().eq_ignore_ascii_case("L") {
+            last_day = true;
         } else {
-            parts[0]
-                .parse::<u32>()
-                .map_err(|_| format!("Invalid cron field: {field}"))?
-        };
-        let step = parts[1]
-            .parse::<u32>()
-            .map_err(|_| format!("Invalid step in cron field: {field}"))?;
-        if step == 0 {
-            return Err(format!("Step cannot be zero in: {field}"));
+            regular.push(item);
         }
-        let mut values = Vec::new();
-        let mut v = start;
-        while v <= max {
-            values.push(v);
-            v += step;
+    }
+    let days = if regular.is_empty() {
+        Vec::new()
+    } else {
+        parse_field(&regular.join(","), 1, 31, &[])?
+    };
+    Ok((days, last_day))
+}
+
+type DayOfWeekField = (Vec<u32>, Vec<(u32, u32)>, Vec<u32>);
+
+/// Day-of-week field: regular values plus `FRI#2` (nth weekday of the month)
+/// and `5L` / `FRIL` (last given weekday of the month). `7` is Sunday.
+fn parse_day_of_week_field(field: &str) -> Result<DayOfWeekField, String> {
+    let mut nth = Vec::new();
+    let mut last = Vec::new();
+    let mut regular = Vec::new();
+
+    for item in field.trim().split(',') {
+        let item = item.trim();
+        if let Some((weekday, n)) = item.split_once('#') {
+            let weekday = parse_weekday(weekday, field)?;
+            let n = n
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid nth weekday in: {field}"))?;
+            if !(1..=5).contains(&n) {
+                return Err(format!("Nth weekday must be 1-5 in: {field}"));
+            }
+            nth.push((weekday, n));
+        } else if item.len() > 1 && (item.ends_with('L') || item.ends_with('l')) {
+            last.push(parse_weekday(&item[..item.len() - 1], field)?);
+        } else {
+            regular.push(item);
         }
-        return Ok(values);
-    }
-
-    // Range: M-N
-    if field.contains('-') {
-        let parts: Vec<&str> = field.splitn(2, '-').collect();
-        let start = parts[0]
-            .parse::<u32>()
-            .map_err(|_| format!("Invalid range start: {field}"))?;
-        let end = parts[1]
-            .parse::<u32>()
-            .map_err(|_| format!("Invalid range end: {field}"))?;
-        if start > end || start < min || end > max {
-            return Err(format!("Invalid range: {field}"));
+    }
+
+    let mut days = if regular.is_empty() {
+        Vec::new()
+    } else {
+        parse_field(&regular.join(","), 0, 7, WEEKDAY_NAMES)?
+    };
+    for day in &mut days {
+        if *day == 7 {
+            *day = 0;
         }
-        return Ok((start..=end).collect());
-    }
-
-    // List: M,N,O
-    if field.contains(',') {
-        let values: Result<Vec<u32>, _> = field
-            .split(',')
-            .map(|v| {
-                v.trim()
-                    .parse::<u32>()
-                    .map_err(|_| format!("Invalid value in list: {field}"))
-            })
-            .collect();
-        let values = values?;
-        for v in &values {
-            if *v < min || *v > max {
-                return Err(format!("Value {v} out of range {min}-{max} in: {field}"));
+    }
+    days.sort_unstable();
+    days.dedup();
+    Ok((days, nth, last))
+}
+
+fn parse_weekday(value: &str, field: &str) -> Result<u32, String> {
+    let weekday = parse_value(value, 0, 7, WEEKDAY_NAMES, field)?;
+    Ok(if weekday == 7 { 0 } else { weekday })
+}
+
+/// Parse a single cron field into a sorted list of matching values.
+/// `names` maps symbolic names to values starting at `min`.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<Vec<u32>, String> {
+    let field = field.trim();
+    if field.is_empty() {
+        return Err("Empty cron field".to_string());
+    }
+
+    let mut values = Vec::new();
+    for item in field.split(',') {
+        values.extend(parse_item(item.trim(), min, max, names, field)?);
+    }
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+/// One list item: `*`, `N`, `M-N`, optionally followed by `/step`.
+fn parse_item(
+    item: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    field: &str,
+) -> Result<Vec<u32>, String> {
+    let (range, step) = match item.split_once('/') {
+        Some((range, step)) => {
+            let step = step
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid step in cron field: {field}"))?;
+            if step == 0 {
+                return Err(format!("Step cannot be zero in: {field}"));
             }
+            (range, Some(step))
         }
-        return Ok(values);
-    }
+        None => (item, None),
+    };
+
+    let (start, end) = if is_wildcard(range) {
+        (min, max)
+    } else if let Some((start, end)) = range.split_once('-') {
+        let start = parse_value(start, min, max, names, field)?;
+        let end = parse_value(end, min, max, names, field)?;
+        if start > end {
+            return Err(format!("Invalid range: {field}"));
+        }
+        (start, end)
+    } else {
+        let value = parse_value(range, min, max, names, field)?;
+        // `M/N` means "from M to the maximum, every N".
+        (value, if step.is_some() { max } else { value })
+    };
+
+    Ok((start..=end).step_by(step.unwrap_or(1) as usize).collect())
+}
 
-    // Single value
-    let value = field
+fn parse_value(
+    value: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    field: &str,
+) -> Result<u32, String> {
+    let value = value.trim();
+    if let Some(index) = names
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(value))
+    {
+        return Ok(min + index as u32);
+    }
+    let parsed = value
         .parse::<u32>()
         .map_err(|_| format!("Invalid cron field: {field}"))?;
-    if value < min || value > max {
+    if parsed < min || parsed > max {
         return Err(format!(
-            "Value {value} out of range {min}-{max} in: {field}"
+            "Value {parsed} out of range {min}-{max} in: {field}"
         ));
     }
-    Ok(vec![value])
+    Ok(parsed)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn utc(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
     #[test]
     fn parse_every_minute() {
         let schedule = CronSchedule::parse("* * * * *").unwrap();
@@ -213,5 +442,119 @@ mod tests {
     fn invalid_expression_rejected() {
         assert!(CronSchedule::parse("bad").is_err());
         assert!(CronSchedule::parse("* * *").is_err());
+        assert!(CronSchedule::parse("@fortnightly").is_err());
+        assert!(CronSchedule::parse("0 9 * * MON#6").is_err());
+        assert!(CronSchedule::parse_in_timezone("0 9 * * *", "Mars/Olympus").is_err());
+    }
+
+    #[test]
+    fn parses_names_lists_and_stepped_ranges() {
+        let schedule = CronSchedule::parse("10-40/10 9 * JAN,MAR-APR mon-fri").unwrap();
+        assert_eq!(schedule.minutes, vec![10, 20, 30, 40]);
+        assert_eq!(schedule.months, vec![1, 3, 4]);
+        assert_eq!(schedule.days_of_week, vec![1, 2, 3, 4, 5]);
+
+        let sunday = CronSchedule::parse("0 0 * * 7").unwrap();
+        assert_eq!(sunday.days_of_week, vec![0]);
+    }
+
+    #[test]
+    fn macros_expand() {
+        let weekly = CronSchedule::parse("@weekly").unwrap();
+        // 2026-02-22 is a Sunday.
+        assert_eq!(
+            weekly.next_after(&utc(2026, 2, 18, 12, 0)),
+            Some(utc(2026, 2, 22, 0, 0))
+        );
+        let daily = CronSchedule::parse("@DAILY").unwrap();
+        assert_eq!(
+            daily.next_after(&utc(2026, 2, 22, 0, 0)),
+            Some(utc(2026, 2, 23, 0, 0))
+        );
+    }
+
+    #[test]
+    fn last_day_of_month() {
+        let schedule = CronSchedule::parse("0 18 L * *").unwrap();
+        assert_eq!(
+            schedule.next_after(&utc(2026, 2, 10, 0, 0)),
+            Some(utc(2026, 2, 28, 18, 0))
+        );
+        assert_eq!(
+            schedule.next_after(&utc(2028, 2, 10, 0, 0)),
+            Some(utc(2028, 2, 29, 18, 0))
+        );
+    }
+
+    #[test]
+    fn nth_and_last_weekday() {
+        // Second Monday of March 2026 is the 9th.
+        let second_monday = CronSchedule::parse("0 9 * * MON#2").unwrap();
+        assert_eq!(
+            second_monday.next_after(&utc(2026, 3, 1, 0, 0)),
+            Some(utc(2026, 3, 9, 9, 0))
+        );
+
+        // Last Friday of March 2026 is the 27th.
+        let last_friday = CronSchedule::parse("0 9 * * 5L").unwrap();
+        assert_eq!(
+            last_friday.next_after(&utc(2026, 3, 1, 0, 0)),
+            Some(utc(2026, 3, 27, 9, 0))
+        );
+    }
+
+    #[test]
+    fn day_of_month_or_day_of_week() {
+        // Fires on the 15th and on every Monday.
+        let schedule = CronSchedule::parse("0 8 15 * MON").unwrap();
+        let fires = schedule.upcoming(&utc(2026, 3, 10, 0, 0), 3);
+        assert_eq!(
+            fires,
+            vec![
+                utc(2026, 3, 15, 8, 0),
+                utc(2026, 3, 16, 8, 0),
+                utc(2026, 3, 23, 8, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn evaluates_in_timezone() {
+        let schedule = CronSchedule::parse_in_timezone("0 9 * * *", "America/Asuncion").unwrap();
+        // Asunción is UTC-3 in March.
+        assert_eq!(
+            schedule.next_after(&utc(2026, 3, 2, 0, 0)),
+            Some(utc(2026, 3, 2, 12, 0))
+        );
+    }
+
+    #[test]
+    fn dst_gap_fires_after_transition() {
+        // New York springs forward 2026-03-08 02:00 -> 03:00 EDT (07:00 UTC).
+        let schedule = CronSchedule::parse_in_timezone("30 2 * * *", "America/New_York").unwrap();
+        assert_eq!(
+            schedule.next_after(&utc(2026, 3, 8, 0, 0)),
+            Some(utc(2026, 3, 8, 7, 0))
+        );
+        assert_eq!(
+            schedule.next_after(&utc(2026, 3, 8, 7, 0)),
+            Some(utc(2026, 3, 9, 6, 30))
+        );
+    }
+
+    #[test]
+    fn dst_overlap_fires_once() {
+        // New York falls back 2026-11-01 02:00 EDT -> 01:00 EST; 01:30 happens twice.
+        let schedule = CronSchedule::parse_in_timezone("30 1 * * *", "America/New_York").unwrap();
+        let first = schedule.next_after(&utc(2026, 11, 1, 0, 0)).unwrap();
+        assert_eq!(first, utc(2026, 11, 1, 5, 30));
+        assert_eq!(schedule.next_after(&first), Some(utc(2026, 11, 2, 6, 30)));
+    }
+
+    #[test]
+    fn is_due_after_missed_fire() {
+        let schedule = CronSchedule::parse("0 8 * * *").unwrap();
+        assert!(schedule.is_due(&utc(2026, 3, 1, 9, 0), &utc(2026, 3, 2, 8, 1)));
+        assert!(!schedule.is_due(&utc(2026, 3, 2, 8, 0), &utc(2026, 3, 2, 20, 0)));
     }
 }
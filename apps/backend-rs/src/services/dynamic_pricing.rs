@@ -1,8 +1,12 @@
+use std::collections::BTreeSet;
+
+use chrono::{Duration, Utc};
 use serde_json::{json, Map, Value};
 use sqlx::Row;
 
 use crate::{
     error::{AppError, AppResult},
+    services::{availability, stay_restrictions},
     state::AppState,
 };
 
@@ -112,6 +116,7 @@ pub async fn tool_generate_pricing_recommendations(
                 low_season_discount_pct::float8, high_season_premium_pct::float8,
                 last_minute_days::int, last_minute_discount_pct::float8,
                 long_stay_threshold_days::int, long_stay_discount_pct::float8,
+                gap_night_discount_pct::float8,
                 day_of_week_factors
          FROM pricing_rule_sets
          WHERE org_id = $1::uuid AND is_active = true
@@ -163,6 +168,14 @@ pub async fn tool_generate_pricing_recommendations(
                 .flatten()
         })
         .unwrap_or(0.0);
+    let gap_night_discount = rule_set
+        .as_ref()
+        .and_then(|r| {
+            r.try_get::<Option<f64>, _>("gap_night_discount_pct")
+                .ok()
+                .flatten()
+        })
+        .unwrap_or(DEFAULT_GAP_NIGHT_DISCOUNT_PCT);
 
     // Current month for seasonal factor
     let now = chrono::Utc::now();
@@ -371,9 +384,20 @@ pub async fn tool_generate_pricing_recommendations(
         }));
     }
 
+    let gap_night_discounts = recommend_gap_night_discounts(
+        pool,
+        org_id,
+        target_unit_id,
+        period_days,
+        gap_night_discount,
+        min_rate,
+    )
+    .await;
+
     Ok(json!({
         "ok": true,
         "period_days": period_days,
+        "gap_night_discounts": gap_night_discounts,
         "portfolio_occupancy_pct": (occupancy * 10000.0).round() / 100.0,
         "avg_daily_rate": (avg_rate * 100.0).round() / 100.0,
         "market_avg_rate": (market_avg * 100.0).round() / 100.0,
@@ -383,7 +407,147 @@ pub async fn tool_generate_pricing_recommendations(
     }))
 }
 
-/// Apply a pricing recommendation by updating the pricing template.
+/// Discount applied to orphan gap nights when no rule set configures one.
+const DEFAULT_GAP_NIGHT_DISCOUNT_PCT: f64 = 15.0;
+
+/// Gap nights are free nights between two bookings that are shorter than the
+/// unit's minimum stay. Rather than leave them blocked, recommend a
+/// discounted rate for exactly those nights; once applied, the dated rate is
+/// pushed to channels with a minimum stay matching the gap.
+async fn recommend_gap_night_discounts(
+    pool: &sqlx::PgPool,
+    org_id: &str,
+    target_unit_id: Option<&str>,
+    period_days: i64,
+    discount_pct: f64,
+    min_rate: f64,
+) -> Vec<Value> {
+    if discount_pct <= 0.0 {
+        return Vec::new();
+    }
+    let today = Utc::now().date_naive();
+    let horizon = today + Duration::days(period_days);
+
+    let restrictions = match stay_restrictions::load_restrictions(
+        pool,
+        Some(org_id),
+        target_unit_id,
+        today,
+        horizon,
+    )
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::warn!(org_id, error = %e, "Gap nights: failed to load stay restrictions");
+            return Vec::new();
+        }
+    };
+    let unit_ids: BTreeSet<&str> = restrictions
+        .iter()
+        .filter(|rule| rule.min_nights.is_some_and(|nights| nights > 1))
+        .map(|rule| rule.unit_id.as_str())
+        .collect();
+
+    let mut recommendations = Vec::new();
+    for unit_id in unit_ids {
+        let unit_rules: Vec<_> = restrictions
+            .iter()
+            .filter(|rule| rule.unit_id == unit_id)
+            .cloned()
+            .collect();
+        let Ok(occupancy) = availability::load_unit_occupancy(pool, unit_id, today, None).await
+        else {
+            continue;
+        };
+        let gaps: Vec<_> =
+            stay_restrictions::find_gap_nights(&occupancy, &unit_rules, today, horizon)
+                .into_iter()
+                .filter(|gap| gap.allow_gap_fill)
+                .collect();
+        if gaps.is_empty() {
+            continue;
+        }
+
+        let base_rate: f64 = sqlx::query_scalar(
+            "SELECT COALESCE(NULLIF(default_nightly_rate, 0), base_price_nightly)::float8
+             FROM units
+             WHERE id = $1::uuid",
+        )
+        .bind(unit_id)
+        .fetch_optional(pool)
+        .await
+        .ok()
+        .flatten()
+        .flatten()
+        .unwrap_or(0.0);
+        if base_rate <= 0.0 {
+            continue;
+        }
+        let recommended_rate =
+            ((base_rate * (1.0 - discount_pct / 100.0)).max(min_rate) * 100.0).round() / 100.0;
+
+        for gap in gaps {
+            let last_night = gap.end - Duration::days(1);
+            let reasoning = format!(
+                "{}-night gap from {} to {} is shorter than the {}-night minimum stay; \
+                 a {:.0}% discount lets it sell as a gap fill instead of staying empty.",
+                gap.nights, gap.start, gap.end, gap.min_nights, discount_pct
+            );
+            // One recommendation per gap, whether still pending or applied.
+            let inserted: Option<String> = sqlx::query_scalar(
+                "INSERT INTO pricing_recommendations (
+                    organization_id, unit_id, recommendation_type,
+                    current_rate, recommended_rate, confidence, reasoning,
+                    revenue_impact_estimate, date_range_start, date_range_end, status
+                 )
+                 SELECT $1::uuid, $2::uuid, 'gap_night_discount',
+                        $3, $4, 0.7, $5, $6, $7, $8, 'pending'
+                 WHERE NOT EXISTS (
+                   SELECT 1 FROM pricing_recommendations
+                   WHERE unit_id = $2::uuid
+                     AND recommendation_type = 'gap_night_discount'
+                     AND date_range_start = $7
+                     AND date_range_end = $8
+                     AND status IN ('pending', 'applied')
+                 )
+                 RETURNING id::text",
+            )
+            .bind(org_id)
+            .bind(unit_id)
+            .bind(base_rate)
+            .bind(recommended_rate)
+            .bind(&reasoning)
+            .bind(recommended_rate * f64::from(gap.nights))
+            .bind(gap.start)
+            .bind(last_night)
+            .fetch_optional(pool)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(unit_id, error = %e, "Gap nights: failed to store recommendation");
+                None
+            });
+
+            let Some(recommendation_id) = inserted else {
+                continue;
+            };
+            recommendations.push(json!({
+                "recommendation_id": recommendation_id,
+                "unit_id": unit_id,
+                "gap": gap.to_json(),
+                "current_rate": (base_rate * 100.0).round() / 100.0,
+                "recommended_rate": recommended_rate,
+                "discount_pct": discount_pct,
+                "reason": reasoning,
+            }));
+        }
+    }
+    recommendations
+}
+
+/// Apply a pricing recommendation by updating the pricing template. Gap-night
+/// discounts are dated rates rather than template changes; applying one just
+/// marks it applied so the ARI push publishes it.
 pub async fn tool_apply_pricing_recommendation(
     state: &AppState,
     org_id: &str,
@@ -399,6 +563,42 @@ pub async fn tool_apply_pricing_recommendation(
         return Ok(json!({ "ok": false, "error": "recommendation_id is required." }));
     }
 
+    let gap_night = sqlx::query(
+        "UPDATE pricing_recommendations
+         SET status = 'applied', applied_at = now(), updated_at = now()
+         WHERE id = $1::uuid AND organization_id = $2::uuid
+           AND status = 'pending'
+           AND recommendation_type = 'gap_night_discount'
+         RETURNING date_range_start, date_range_end, recommended_rate::float8 AS recommended_rate",
+    )
+    .bind(rec_id)
+    .bind(org_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, "Failed to apply gap-night recommendation");
+        AppError::Dependency("Failed to apply recommendation.".to_string())
+    })?;
+    if let Some(row) = gap_night {
+        let start = row
+            .try_get::<Option<chrono::NaiveDate>, _>("date_range_start")
+            .ok()
+            .flatten();
+        let end = row
+            .try_get::<Option<chrono::NaiveDate>, _>("date_range_end")
+            .ok()
+            .flatten();
+        return Ok(json!({
+            "ok": true,
+            "recommendation_id": rec_id,
+            "recommendation_type": "gap_night_discount",
+            "date_range_start": start.map(|date| date.to_string()),
+            "date_range_end": end.map(|date| date.to_string()),
+            "new_price": row.try_get::<Option<f64>, _>("recommended_rate").ok().flatten(),
+            "status": "applied",
+        }));
+    }
+
     // Fetch recommendation
     let rec = sqlx::query(
         "SELECT pricing_template_id::text, recommended_price::float8
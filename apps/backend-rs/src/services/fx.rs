@@ -1,80 +1,439 @@
-use serde_json::Value;
+use std::collections::HashMap;
 
-use crate::state::AppState;
+use chrono::{Duration, NaiveDate, Utc};
+use serde_json::{json, Value};
+use sqlx::Row;
 
-/// Default fallback rate if no external API is reachable.
-const FALLBACK_USD_PYG: f64 = 7500.0;
+use crate::{
+    error::{AppError, AppResult},
+    state::AppState,
+};
 
-const FX_CACHE_KEY: &str = "usd_pyg_latest";
+/// Currencies whose USD rates are stored every day, so any pair among them
+/// can be crossed through USD without a network call.
+pub const TRACKED_CURRENCIES: &[&str] = &["USD", "PYG", "BRL", "ARS", "EUR"];
 
-/// Get USD→PYG rate for a specific date (no caching, used for expense enrichment).
-pub async fn get_usd_to_pyg_rate(http_client: &reqwest::Client, value_date: &str) -> Option<f64> {
-    let day = value_date.trim();
-    if day.is_empty() {
-        return None;
+/// How many past days the daily refresh re-checks for missing rates.
+const DAILY_BACKFILL_DAYS: i64 = 7;
+
+/// Upper bound on the number of days a single backfill may fetch.
+const MAX_BACKFILL_DAYS: i64 = 366;
+
+/// Exchange rate for one pair: 1 `base` buys `rate` `quote` on `rate_date`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FxRate {
+    pub base: String,
+    pub quote: String,
+    pub rate_date: NaiveDate,
+    pub rate: f64,
+    pub source: String,
+}
+
+impl FxRate {
+    fn identity(currency: &str, date: NaiveDate) -> Self {
+        Self {
+            base: currency.to_string(),
+            quote: currency.to_string(),
+            rate_date: date,
+            rate: 1.0,
+            source: "identity".to_string(),
+        }
     }
 
-    let sources = [
-        format!(
-            "https://cdn.jsdelivr.net/npm/@fawazahmed0/currency-api@{day}/v1/currencies/usd/pyg.json"
-        ),
-        "https://cdn.jsdelivr.net/npm/@fawazahmed0/currency-api@latest/v1/currencies/usd/pyg.json"
-            .to_string(),
-        "https://open.er-api.com/v6/latest/USD".to_string(),
-    ];
+    fn inverted(&self) -> Self {
+        Self {
+            base: self.quote.clone(),
+            quote: self.base.clone(),
+            rate_date: self.rate_date,
+            rate: 1.0 / self.rate,
+            source: self.source.clone(),
+        }
+    }
 
-    for source in sources {
-        let Some(payload) = fetch_json(http_client, &source).await else {
-            continue;
+    /// Combine base→X and X→quote into base→quote. The effective date is the
+    /// older of the two legs so staleness is never understated.
+    fn cross(first: &Self, second: &Self) -> Self {
+        Self {
+            base: first.base.clone(),
+            quote: second.quote.clone(),
+            rate_date: first.rate_date.min(second.rate_date),
+            rate: first.rate * second.rate,
+            source: if first.source == second.source {
+                format!("{} (via {})", first.source, first.quote)
+            } else {
+                format!("{} + {} (via {})", first.source, second.source, first.quote)
+            },
+        }
+    }
+
+    pub fn to_json(&self, requested: NaiveDate) -> Value {
+        json!({
+            "base": self.base,
+            "quote": self.quote,
+            "date": requested.to_string(),
+            "rate_date": self.rate_date.to_string(),
+            "rate": self.rate,
+            "source": self.source,
+            "stale": self.rate_date < requested,
+        })
+    }
+}
+
+/// Normalize a currency code to upper-case ISO 4217 form.
+pub fn normalize_currency(code: &str) -> Option<String> {
+    let code = code.trim();
+    (code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()))
+        .then(|| code.to_ascii_uppercase())
+}
+
+/// Parse a `BASE-QUOTE` pair such as `usd-pyg`.
+pub fn parse_pair(pair: &str) -> Option<(String, String)> {
+    let (base, quote) = pair.trim().split_once('-')?;
+    Some((normalize_currency(base)?, normalize_currency(quote)?))
+}
+
+/// Currency an owner reports in: the explicit request, else the property's
+/// `owner_reporting_currency`, else the organization's default currency.
+pub async fn reporting_currency(
+    pool: &sqlx::PgPool,
+    organization_id: &str,
+    property_id: Option<&str>,
+    unit_id: Option<&str>,
+    requested: Option<&str>,
+) -> AppResult<String> {
+    if let Some(requested) = requested.map(str::trim).filter(|value| !value.is_empty()) {
+        return normalize_currency(requested).ok_or_else(|| {
+            AppError::BadRequest("currency must be a 3-letter ISO 4217 code.".to_string())
+        });
+    }
+
+    let resolved = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT COALESCE(
+           (SELECT p.owner_reporting_currency::text
+              FROM properties p
+             WHERE p.organization_id = $1::uuid
+               AND p.id = COALESCE(
+                 $2::uuid,
+                 (SELECT u.property_id FROM units u WHERE u.id = $3::uuid)
+               )),
+           (SELECT o.default_currency::text FROM organizations o WHERE o.id = $1::uuid)
+         )",
+    )
+    .bind(organization_id)
+    .bind(property_id.map(str::trim).filter(|value| !value.is_empty()))
+    .bind(unit_id.map(str::trim).filter(|value| !value.is_empty()))
+    .fetch_one(pool)
+    .await
+    .map_err(|error| {
+        AppError::from_database_error(&error, "Failed to resolve the reporting currency.")
+    })?;
+
+    Ok(resolved
+        .as_deref()
+        .and_then(normalize_currency)
+        .unwrap_or_else(|| "PYG".to_string()))
+}
+
+/// Latest stored rate for `base`→`quote` effective on `date`, taken directly,
+/// inverted, or crossed through USD. Never touches the network.
+pub async fn stored_rate(
+    pool: &sqlx::PgPool,
+    base: &str,
+    quote: &str,
+    date: NaiveDate,
+) -> AppResult<Option<FxRate>> {
+    if base == quote {
+        return Ok(Some(FxRate::identity(base, date)));
+    }
+    if let Some(rate) = stored_direct_rate(pool, base, quote, date).await? {
+        return Ok(Some(rate));
+    }
+    if base == "USD" || quote == "USD" {
+        return Ok(None);
+    }
+    let Some(first) = stored_direct_rate(pool, base, "USD", date).await? else {
+        return Ok(None);
+    };
+    let Some(second) = stored_direct_rate(pool, "USD", quote, date).await? else {
+        return Ok(None);
+    };
+    Ok(Some(FxRate::cross(&first, &second)))
+}
+
+async fn stored_direct_rate(
+    pool: &sqlx::PgPool,
+    base: &str,
+    quote: &str,
+    date: NaiveDate,
+) -> AppResult<Option<FxRate>> {
+    let row = sqlx::query(
+        "SELECT base_currency::text AS base_currency, quote_currency::text AS quote_currency,
+                rate_date, rate::float8 AS rate, source
+           FROM fx_rates
+          WHERE ((base_currency = $1 AND quote_currency = $2)
+              OR (base_currency = $2 AND quote_currency = $1))
+            AND rate_date <= $3
+          ORDER BY rate_date DESC, (base_currency = $1) DESC
+          LIMIT 1",
+    )
+    .bind(base)
+    .bind(quote)
+    .bind(date)
+    .fetch_optional(pool)
+    .await
+    .map_err(|error| AppError::from_database_error(&error, "Failed to load FX rate."))?;
+
+    Ok(row.map(|row| {
+        let stored = FxRate {
+            base: row.try_get("base_currency").unwrap_or_default(),
+            quote: row.try_get("quote_currency").unwrap_or_default(),
+            rate_date: row.try_get("rate_date").unwrap_or(date),
+            rate: row.try_get("rate").unwrap_or(0.0),
+            source: row.try_get("source").unwrap_or_default(),
         };
-        if let Some(rate) = parse_rate(&payload) {
-            if rate > 0.0 {
-                return Some(rate);
-            }
+        if stored.base == base {
+            stored
+        } else {
+            stored.inverted()
         }
+    }))
+}
+
+/// Rate for `base`→`quote` on `date`. Stored rates for that exact day are
+/// used as-is; otherwise the day's rates are fetched once, stored, and the
+/// lookup retried. Falls back to the latest earlier stored rate (marked
+/// stale through `rate_date`) when the providers have nothing.
+pub async fn rate_on(
+    pool: &sqlx::PgPool,
+    http: &reqwest::Client,
+    base: &str,
+    quote: &str,
+    date: NaiveDate,
+) -> AppResult<Option<FxRate>> {
+    let stored = stored_rate(pool, base, quote, date).await?;
+    if stored.as_ref().is_some_and(|rate| rate.rate_date == date) {
+        return Ok(stored);
+    }
+    if date > Utc::now().date_naive() {
+        return Ok(stored);
     }
 
-    None
+    let mut wanted = vec![quote.to_string()];
+    if base != "USD" && quote != "USD" {
+        wanted.push("USD".to_string());
+    }
+    if fetch_and_store_day(pool, http, base, &wanted, date).await? > 0 {
+        return stored_rate(pool, base, quote, date).await;
+    }
+    Ok(stored)
 }
 
-/// Get the latest USD→PYG rate with in-memory caching (1-hour TTL via `state.fx_cache`).
-/// Uses `get_or_try_init` for thundering-herd protection on cold starts.
-/// Falls back to hardcoded rate if all external APIs are unreachable.
-pub async fn get_cached_usd_pyg_rate(state: &AppState) -> f64 {
-    let result = state
-        .fx_cache
-        .get_or_try_init(FX_CACHE_KEY, || async {
-            let http = &state.http_client;
-            let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
-            let rate = match get_usd_to_pyg_rate(http, &today).await {
-                Some(r) if r > 0.0 => r,
-                _ => {
-                    let sources = [
-                        "https://cdn.jsdelivr.net/npm/@fawazahmed0/currency-api@latest/v1/currencies/usd/pyg.json".to_string(),
-                        "https://open.er-api.com/v6/latest/USD".to_string(),
-                    ];
-                    let mut found = None;
-                    for source in sources {
-                        if let Some(payload) = fetch_json(http, &source).await {
-                            if let Some(r) = parse_rate(&payload) {
-                                if r > 0.0 {
-                                    found = Some(r);
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                    found.unwrap_or(FALLBACK_USD_PYG)
-                }
-            };
-            Ok(serde_json::json!(rate))
-        })
-        .await;
+/// Like [`rate_on`], memoized in `state.fx_cache` so hot public lookups do
+/// not hit Postgres on every request. Misses are not cached.
+pub async fn cached_rate_on(
+    state: &AppState,
+    base: &str,
+    quote: &str,
+    date: NaiveDate,
+) -> AppResult<Option<FxRate>> {
+    let key = format!("fx:{base}:{quote}:{date}");
+    if let Some(cached) = state.fx_cache.get(&key).await {
+        return Ok(rate_from_cache(&cached));
+    }
+    let pool = state.db_pool.as_ref().ok_or_else(|| {
+        AppError::Dependency("Database is not configured. Set DATABASE_URL.".to_string())
+    })?;
+    let rate = rate_on(pool, &state.http_client, base, quote, date).await?;
+    if let Some(found) = rate.as_ref() {
+        state
+            .fx_cache
+            .insert(
+                key,
+                json!({
+                    "base": found.base,
+                    "quote": found.quote,
+                    "rate_date": found.rate_date.to_string(),
+                    "rate": found.rate,
+                    "source": found.source,
+                }),
+            )
+            .await;
+    }
+    Ok(rate)
+}
 
-    result
-        .ok()
-        .and_then(|v| v.as_f64())
-        .unwrap_or(FALLBACK_USD_PYG)
+fn rate_from_cache(value: &Value) -> Option<FxRate> {
+    Some(FxRate {
+        base: value.get("base")?.as_str()?.to_string(),
+        quote: value.get("quote")?.as_str()?.to_string(),
+        rate_date: NaiveDate::parse_from_str(value.get("rate_date")?.as_str()?, "%Y-%m-%d").ok()?,
+        rate: value.get("rate")?.as_f64()?,
+        source: value.get("source")?.as_str()?.to_string(),
+    })
+}
+
+/// Fetch and store `base` rates for every day in `from..=to` that is missing
+/// any of `quotes`. Returns the number of days fetched and failed.
+pub async fn backfill(
+    pool: &sqlx::PgPool,
+    http: &reqwest::Client,
+    base: &str,
+    quotes: &[String],
+    from: NaiveDate,
+    to: NaiveDate,
+) -> AppResult<Value> {
+    let to = to.min(Utc::now().date_naive());
+    if to < from {
+        return Ok(json!({ "base": base, "days_fetched": 0, "days_failed": 0, "missing": [] }));
+    }
+    if (to - from).num_days() >= MAX_BACKFILL_DAYS {
+        return Err(AppError::BadRequest(format!(
+            "FX backfill is limited to {MAX_BACKFILL_DAYS} days per request."
+        )));
+    }
+
+    let missing_days = sqlx::query_scalar::<_, NaiveDate>(
+        "SELECT d::date
+           FROM generate_series($2::date, $3::date, interval '1 day') AS d
+          WHERE (
+            SELECT COUNT(DISTINCT f.quote_currency)
+              FROM fx_rates f
+             WHERE f.base_currency = $1
+               AND f.rate_date = d::date
+               AND f.quote_currency = ANY($4)
+          ) < cardinality($4)
+          ORDER BY d",
+    )
+    .bind(base)
+    .bind(from)
+    .bind(to)
+    .bind(quotes)
+    .fetch_all(pool)
+    .await
+    .map_err(|error| AppError::from_database_error(&error, "Failed to scan FX rates."))?;
+
+    let mut fetched = 0_u32;
+    let mut failed: Vec<String> = Vec::new();
+    for day in missing_days {
+        if fetch_and_store_day(pool, http, base, quotes, day).await? > 0 {
+            fetched += 1;
+        } else {
+            failed.push(day.to_string());
+        }
+    }
+
+    Ok(json!({
+        "base": base,
+        "quotes": quotes,
+        "from": from.to_string(),
+        "to": to.to_string(),
+        "days_fetched": fetched,
+        "days_failed": failed.len(),
+        "missing": failed,
+    }))
+}
+
+/// Daily job: store today's USD rates for the tracked currencies and fill any
+/// holes left in the previous week by provider outages.
+pub async fn run_daily_fx_refresh(state: &AppState) -> Result<Value, String> {
+    let Some(pool) = state.db_pool.as_ref() else {
+        return Ok(Value::Null);
+    };
+    let today = Utc::now().date_naive();
+    let quotes = TRACKED_CURRENCIES
+        .iter()
+        .filter(|code| **code != "USD")
+        .map(|code| (*code).to_string())
+        .collect::<Vec<_>>();
+    backfill(
+        pool,
+        &state.http_client,
+        "USD",
+        &quotes,
+        today - Duration::days(DAILY_BACKFILL_DAYS),
+        today,
+    )
+    .await
+    .map_err(|error| error.to_string())
+}
+
+/// Fetch one day of `base` rates and store the tracked quotes plus `quotes`.
+/// Returns how many rows were written.
+async fn fetch_and_store_day(
+    pool: &sqlx::PgPool,
+    http: &reqwest::Client,
+    base: &str,
+    quotes: &[String],
+    day: NaiveDate,
+) -> AppResult<usize> {
+    let Some((source, rates)) = fetch_day_rates(http, base, day).await else {
+        return Ok(0);
+    };
+
+    let mut stored = 0;
+    for (quote, rate) in rates {
+        if quote == base
+            || !(TRACKED_CURRENCIES.contains(&quote.as_str()) || quotes.contains(&quote))
+        {
+            continue;
+        }
+        sqlx::query(
+            "INSERT INTO fx_rates (base_currency, quote_currency, rate_date, rate, source)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (base_currency, quote_currency, rate_date)
+             DO UPDATE SET rate = EXCLUDED.rate, source = EXCLUDED.source, fetched_at = now()",
+        )
+        .bind(base)
+        .bind(&quote)
+        .bind(day)
+        .bind(rate)
+        .bind(source)
+        .execute(pool)
+        .await
+        .map_err(|error| AppError::from_database_error(&error, "Failed to store FX rate."))?;
+        stored += 1;
+    }
+    Ok(stored)
+}
+
+/// Rates for every quote currency the providers publish for `base` on `day`.
+/// Historical snapshots come from the currency-api CDN and its mirror; the
+/// latest-only open.er-api feed is consulted for today's rates alone.
+async fn fetch_day_rates(
+    http: &reqwest::Client,
+    base: &str,
+    day: NaiveDate,
+) -> Option<(&'static str, HashMap<String, f64>)> {
+    let lower = base.to_ascii_lowercase();
+    let mut sources = vec![
+        (
+            "currency-api",
+            format!(
+                "https://cdn.jsdelivr.net/npm/@fawazahmed0/currency-api@{day}/v1/currencies/{lower}.json"
+            ),
+        ),
+        (
+            "currency-api",
+            format!("https://{day}.currency-api.pages.dev/v1/currencies/{lower}.json"),
+        ),
+    ];
+    if day == Utc::now().date_naive() {
+        sources.push((
+            "open.er-api",
+            format!("https://open.er-api.com/v6/latest/{base}"),
+        ));
+    }
+
+    for (source, url) in sources {
+        let Some(payload) = fetch_json(http, &url).await else {
+            continue;
+        };
+        let rates = parse_rates(&payload, base);
+        if !rates.is_empty() {
+            return Some((source, rates));
+        }
+    }
+    None
 }
 
 async fn fetch_json(http_client: &reqwest::Client, url: &str) -> Option<Value> {
@@ -90,16 +449,25 @@ async fn fetch_json(http_client: &reqwest::Client, url: &str) -> Option<Value> {
     ok_response.json::<Value>().await.ok()
 }
 
-fn parse_rate(payload: &Value) -> Option<f64> {
-    if let Some(rate) = payload.get("pyg").and_then(numeric_value) {
-        return Some(rate);
-    }
-
-    payload
-        .get("rates")
-        .and_then(Value::as_object)
-        .and_then(|rates| rates.get("PYG"))
-        .and_then(numeric_value)
+/// Extract upper-cased quote rates from either provider shape:
+/// `{"usd": {"pyg": 7300.1, ...}}` (currency-api) or
+/// `{"rates": {"PYG": 7300.1, ...}}` (open.er-api).
+fn parse_rates(payload: &Value, base: &str) -> HashMap<String, f64> {
+    let table = payload
+        .get(base.to_ascii_lowercase())
+        .or_else(|| payload.get("rates"))
+        .and_then(Value::as_object);
+    let Some(table) = table else {
+        return HashMap::new();
+    };
+    table
+        .iter()
+        .filter_map(|(code, value)| {
+            let code = normalize_currency(code)?;
+            let rate = numeric_value(value).filter(|rate| rate.is_finite() && *rate > 0.0)?;
+            Some((code, rate))
+        })
+        .collect()
 }
 
 fn numeric_value(value: &Value) -> Option<f64> {
@@ -109,3 +477,69 @@ fn numeric_value(value: &Value) -> Option<f64> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(base: &str, quote: &str, day: u32, value: f64) -> FxRate {
+        FxRate {
+            base: base.to_string(),
+            quote: quote.to_string(),
+            rate_date: NaiveDate::from_ymd_opt(2026, 3, day).unwrap_or_default(),
+            rate: value,
+            source: "currency-api".to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_pairs_and_codes() {
+        assert_eq!(
+            parse_pair("usd-pyg"),
+            Some(("USD".to_string(), "PYG".to_string()))
+        );
+        assert_eq!(
+            parse_pair("BRL-ars"),
+            Some(("BRL".to_string(), "ARS".to_string()))
+        );
+        assert_eq!(parse_pair("usdpyg"), None);
+        assert_eq!(parse_pair("us-pyg"), None);
+        assert_eq!(normalize_currency(" eur "), Some("EUR".to_string()));
+        assert_eq!(normalize_currency("E1R"), None);
+    }
+
+    #[test]
+    fn parses_both_provider_shapes() {
+        let cdn =
+            json!({ "date": "2026-03-02", "usd": { "pyg": 7310.5, "brl": "5.02", "btc": 0 } });
+        let rates = parse_rates(&cdn, "USD");
+        assert_eq!(rates.get("PYG"), Some(&7310.5));
+        assert_eq!(rates.get("BRL"), Some(&5.02));
+        assert!(!rates.contains_key("BTC"));
+
+        let er_api = json!({ "result": "success", "rates": { "PYG": 7300, "EUR": 0.92 } });
+        let rates = parse_rates(&er_api, "USD");
+        assert_eq!(rates.get("PYG"), Some(&7300.0));
+        assert_eq!(rates.get("EUR"), Some(&0.92));
+
+        assert!(parse_rates(&json!({ "error": "not found" }), "USD").is_empty());
+    }
+
+    #[test]
+    fn crosses_and_inverts_rates() {
+        let brl_usd = rate("USD", "BRL", 2, 5.0).inverted();
+        assert_eq!(brl_usd.base, "BRL");
+        assert!((brl_usd.rate - 0.2).abs() < 1e-12);
+
+        let usd_pyg = rate("USD", "PYG", 1, 7500.0);
+        let brl_pyg = FxRate::cross(&brl_usd, &usd_pyg);
+        assert_eq!(
+            (brl_pyg.base.as_str(), brl_pyg.quote.as_str()),
+            ("BRL", "PYG")
+        );
+        assert!((brl_pyg.rate - 1500.0).abs() < 1e-9);
+        // The older leg decides the effective date.
+        assert_eq!(brl_pyg.rate_date, usd_pyg.rate_date);
+        assert_eq!(brl_pyg.to_json(brl_usd.rate_date)["stale"], json!(true));
+    }
+}
@@ -0,0 +1,1217 @@
+use chrono::{NaiveDate, Utc};
+use serde_json::{json, Map, Value};
+use sqlx::{PgConnection, PgPool, Row};
+use tracing::warn;
+
+use crate::error::{AppError, AppResult};
+
+/// Debit/credit differences below this are rounding noise.
+const EPSILON: f64 = 0.005;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountType {
+    Asset,
+    Liability,
+    Equity,
+    Revenue,
+    Expense,
+}
+
+impl AccountType {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "asset" => Some(Self::Asset),
+            "liability" => Some(Self::Liability),
+            "equity" => Some(Self::Equity),
+            "revenue" | "income" => Some(Self::Revenue),
+            "expense" => Some(Self::Expense),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Asset => "asset",
+            Self::Liability => "liability",
+            Self::Equity => "equity",
+            Self::Revenue => "revenue",
+            Self::Expense => "expense",
+        }
+    }
+
+    /// Assets and expenses grow with debits; everything else with credits.
+    pub fn is_debit_normal(self) -> bool {
+        matches!(self, Self::Asset | Self::Expense)
+    }
+}
+
+/// An account the backend posts to by `system_key`.
+pub struct SystemAccount {
+    pub key: &'static str,
+    pub code: &'static str,
+    pub name: &'static str,
+    pub account_type: AccountType,
+}
+
+/// Seeded for every organization on first use.
+pub const CHART_OF_ACCOUNTS: &[SystemAccount] = &[
+    SystemAccount {
+        key: "trust_cash",
+        code: "1000",
+        name: "Trust cash",
+        account_type: AccountType::Asset,
+    },
+    SystemAccount {
+        key: "operating_cash",
+        code: "1010",
+        name: "Operating cash",
+        account_type: AccountType::Asset,
+    },
+    SystemAccount {
+        key: "owner_payable",
+        code: "2000",
+        name: "Owner payable",
+        account_type: AccountType::Liability,
+    },
+    SystemAccount {
+        key: "tenant_deposits",
+        code: "2100",
+        name: "Tenant deposits held",
+        account_type: AccountType::Liability,
+    },
+    SystemAccount {
+        key: "tenant_prepayments",
+        code: "2200",
+        name: "Tenant prepayments",
+        account_type: AccountType::Liability,
+    },
+    SystemAccount {
+        key: "owner_reserves",
+        code: "2300",
+        name: "Owner reserves held",
+        account_type: AccountType::Liability,
+    },
+    SystemAccount {
+        key: "retained_earnings",
+        code: "3000",
+        name: "Retained earnings",
+        account_type: AccountType::Equity,
+    },
+    SystemAccount {
+        key: "management_fee_revenue",
+        code: "4000",
+        name: "Management fee revenue",
+        account_type: AccountType::Revenue,
+    },
+    SystemAccount {
+        key: "operating_expenses",
+        code: "5000",
+        name: "Operating expenses",
+        account_type: AccountType::Expense,
+    },
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccountRef {
+    /// One of `CHART_OF_ACCOUNTS`, by key.
+    System(&'static str),
+    /// Any active account of the organization, by id.
+    Id(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalLine {
+    pub account: AccountRef,
+    pub debit: f64,
+    pub credit: f64,
+    pub property_id: Option<String>,
+    pub lease_id: Option<String>,
+    pub memo: Option<String>,
+}
+
+impl JournalLine {
+    pub fn debit(account: &'static str, amount: f64) -> Self {
+        Self::new(AccountRef::System(account), amount, 0.0)
+    }
+
+    pub fn credit(account: &'static str, amount: f64) -> Self {
+        Self::new(AccountRef::System(account), 0.0, amount)
+    }
+
+    pub fn new(account: AccountRef, debit: f64, credit: f64) -> Self {
+        Self {
+            account,
+            debit: round2(debit),
+            credit: round2(credit),
+            property_id: None,
+            lease_id: None,
+            memo: None,
+        }
+    }
+
+    pub fn property(mut self, property_id: Option<&str>) -> Self {
+        self.property_id = property_id
+            .filter(|id| !id.is_empty())
+            .map(ToOwned::to_owned);
+        self
+    }
+
+    pub fn lease(mut self, lease_id: Option<&str>) -> Self {
+        self.lease_id = lease_id.filter(|id| !id.is_empty()).map(ToOwned::to_owned);
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewEntry {
+    pub organization_id: String,
+    pub entry_date: NaiveDate,
+    pub description: String,
+    pub source_type: String,
+    pub source_id: Option<String>,
+    /// Posting the same key twice for an organization is a no-op.
+    pub source_key: Option<String>,
+    pub currency: String,
+    pub created_by_user_id: Option<String>,
+    pub lines: Vec<JournalLine>,
+}
+
+/// Reject entries that would not balance or that contain empty or
+/// two-sided lines. Zero lines are dropped by the caller before this.
+pub fn validate_lines(lines: &[JournalLine]) -> AppResult<()> {
+    if lines.len() < 2 {
+        return Err(AppError::UnprocessableEntity(
+            "A journal entry needs at least two lines.".to_string(),
+        ));
+    }
+    for line in lines {
+        let valid = line.debit.is_finite()
+            && line.credit.is_finite()
+            && line.debit >= 0.0
+            && line.credit >= 0.0
+            && ((line.debit > 0.0) != (line.credit > 0.0));
+        if !valid {
+            return Err(AppError::UnprocessableEntity(
+                "Each journal line must have either a positive debit or a positive credit."
+                    .to_string(),
+            ));
+        }
+    }
+    let debits: f64 = lines.iter().map(|line| line.debit).sum();
+    let credits: f64 = lines.iter().map(|line| line.credit).sum();
+    if (debits - credits).abs() >= EPSILON {
+        return Err(AppError::UnprocessableEntity(format!(
+            "Journal entry is unbalanced: debits {:.2}, credits {:.2}.",
+            debits, credits
+        )));
+    }
+    Ok(())
+}
+
+/// Create any system account the organization does not have yet.
+pub async fn ensure_chart_of_accounts(conn: &mut PgConnection, org_id: &str) -> AppResult<()> {
+    for account in CHART_OF_ACCOUNTS {
+        sqlx::query(
+            "INSERT INTO gl_accounts (organization_id, code, name, account_type, system_key)
+             VALUES ($1::uuid, $2, $3, $4, $5)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(org_id)
+        .bind(account.code)
+        .bind(account.name)
+        .bind(account.account_type.as_str())
+        .bind(account.key)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Could not seed chart of accounts."))?;
+    }
+    Ok(())
+}
+
+/// Post a balanced entry on an open connection or transaction. Lines with
+/// nothing on them are skipped, and an entry with nothing left is not
+/// posted. Returns the new entry id, or `None` when nothing was posted or
+/// the source key was already posted.
+pub async fn post_entry_in(conn: &mut PgConnection, entry: &NewEntry) -> AppResult<Option<String>> {
+    let lines: Vec<JournalLine> = entry
+        .lines
+        .iter()
+        .filter(|line| line.debit > 0.0 || line.credit > 0.0)
+        .cloned()
+        .collect();
+    if lines.is_empty() {
+        return Ok(None);
+    }
+    validate_lines(&lines)?;
+    ensure_chart_of_accounts(conn, &entry.organization_id).await?;
+
+    let inserted = sqlx::query(
+        "INSERT INTO gl_journal_entries (
+            organization_id, entry_date, description, source_type, source_id,
+            source_key, currency, created_by_user_id
+         ) VALUES ($1::uuid, $2, $3, $4, $5::uuid, $6, $7, $8::uuid)
+         ON CONFLICT (organization_id, source_key) WHERE source_key IS NOT NULL DO NOTHING
+         RETURNING id::text",
+    )
+    .bind(&entry.organization_id)
+    .bind(entry.entry_date)
+    .bind(&entry.description)
+    .bind(&entry.source_type)
+    .bind(entry.source_id.as_deref())
+    .bind(entry.source_key.as_deref())
+    .bind(&entry.currency)
+    .bind(entry.created_by_user_id.as_deref())
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not post journal entry."))?;
+    let Some(inserted) = inserted else {
+        return Ok(None);
+    };
+    let entry_id: String = inserted.try_get("id").unwrap_or_default();
+
+    for line in lines {
+        let account_id = resolve_account(conn, &entry.organization_id, &line.account).await?;
+        sqlx::query(
+            "INSERT INTO gl_journal_lines (
+                organization_id, entry_id, account_id, debit, credit,
+                property_id, lease_id, memo
+             ) VALUES ($1::uuid, $2::uuid, $3::uuid, $4, $5, $6::uuid, $7::uuid, $8)",
+        )
+        .bind(&entry.organization_id)
+        .bind(&entry_id)
+        .bind(&account_id)
+        .bind(line.debit)
+        .bind(line.credit)
+        .bind(line.property_id.as_deref())
+        .bind(line.lease_id.as_deref())
+        .bind(line.memo.as_deref())
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Could not post journal line."))?;
+    }
+    Ok(Some(entry_id))
+}
+
+/// Post an entry in its own transaction.
+pub async fn post_entry(pool: &PgPool, entry: &NewEntry) -> AppResult<Option<String>> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Dependency(format!("txn begin: {e}")))?;
+    let entry_id = post_entry_in(&mut tx, entry).await?;
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Dependency(format!("txn commit: {e}")))?;
+    Ok(entry_id)
+}
+
+/// Post the ledger side of an operational event that has already been
+/// saved. Failures are logged rather than returned so a ledger problem never
+/// undoes the event itself; the source key lets a retry post it later.
+pub async fn record_event(pool: &PgPool, entry: NewEntry) {
+    if let Err(error) = post_entry(pool, &entry).await {
+        warn!(
+            organization_id = %entry.organization_id,
+            source_type = %entry.source_type,
+            source_key = ?entry.source_key,
+            error = %error,
+            "Failed to post journal entry"
+        );
+    }
+}
+
+/// Post the mirror image of an entry. Each entry can be reversed once.
+pub async fn reverse_entry_in(
+    conn: &mut PgConnection,
+    org_id: &str,
+    entry_id: &str,
+    entry_date: NaiveDate,
+    created_by_user_id: Option<&str>,
+) -> AppResult<String> {
+    let original = sqlx::query(
+        "SELECT description, source_type, source_id::text AS source_id,
+                currency::text AS currency, reversal_of::text AS reversal_of
+         FROM gl_journal_entries
+         WHERE id = $1::uuid AND organization_id = $2::uuid",
+    )
+    .bind(entry_id)
+    .bind(org_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load journal entry."))?
+    .ok_or_else(|| AppError::NotFound("Journal entry not found.".to_string()))?;
+    if original
+        .try_get::<Option<String>, _>("reversal_of")
+        .ok()
+        .flatten()
+        .is_some()
+    {
+        return Err(AppError::Conflict(
+            "A reversal cannot itself be reversed.".to_string(),
+        ));
+    }
+
+    let description: String = original.try_get("description").unwrap_or_default();
+    let inserted = sqlx::query(
+        "INSERT INTO gl_journal_entries (
+            organization_id, entry_date, description, source_type, source_id,
+            currency, reversal_of, created_by_user_id
+         ) VALUES ($1::uuid, $2, $3, $4, $5::uuid, $6, $7::uuid, $8::uuid)
+         ON CONFLICT (reversal_of) WHERE reversal_of IS NOT NULL DO NOTHING
+         RETURNING id::text",
+    )
+    .bind(org_id)
+    .bind(entry_date)
+    .bind(format!("Reversal: {description}"))
+    .bind(
+        original
+            .try_get::<String, _>("source_type")
+            .unwrap_or_default(),
+    )
+    .bind(
+        original
+            .try_get::<Option<String>, _>("source_id")
+            .ok()
+            .flatten(),
+    )
+    .bind(
+        original
+            .try_get::<String, _>("currency")
+            .unwrap_or_default(),
+    )
+    .bind(entry_id)
+    .bind(created_by_user_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not reverse journal entry."))?
+    .ok_or_else(|| AppError::Conflict("Journal entry is already reversed.".to_string()))?;
+    let reversal_id: String = inserted.try_get("id").unwrap_or_default();
+
+    sqlx::query(
+        "INSERT INTO gl_journal_lines (
+            organization_id, entry_id, account_id, debit, credit, property_id, lease_id, memo
+         )
+         SELECT organization_id, $2::uuid, account_id, credit, debit, property_id, lease_id, memo
+         FROM gl_journal_lines
+         WHERE entry_id = $1::uuid",
+    )
+    .bind(entry_id)
+    .bind(&reversal_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not reverse journal lines."))?;
+
+    Ok(reversal_id)
+}
+
+/// Reverse every entry posted for a source row that is not reversed yet,
+/// e.g. when an approved expense is deleted. Returns how many were reversed.
+pub async fn reverse_source(
+    pool: &PgPool,
+    org_id: &str,
+    source_type: &str,
+    source_id: &str,
+    entry_date: NaiveDate,
+    created_by_user_id: Option<&str>,
+) -> AppResult<usize> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Dependency(format!("txn begin: {e}")))?;
+
+    let entry_ids: Vec<String> = sqlx::query_scalar(
+        "SELECT e.id::text
+         FROM gl_journal_entries e
+         WHERE e.organization_id = $1::uuid
+           AND e.source_type = $2
+           AND e.source_id = $3::uuid
+           AND e.reversal_of IS NULL
+           AND NOT EXISTS (SELECT 1 FROM gl_journal_entries r WHERE r.reversal_of = e.id)
+         ORDER BY e.created_at
+         FOR UPDATE",
+    )
+    .bind(org_id)
+    .bind(source_type)
+    .bind(source_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load journal entries."))?;
+
+    for entry_id in &entry_ids {
+        reverse_entry_in(&mut tx, org_id, entry_id, entry_date, created_by_user_id).await?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Dependency(format!("txn commit: {e}")))?;
+    Ok(entry_ids.len())
+}
+
+/// Best-effort `reverse_source` for operational handlers.
+pub async fn reverse_event(
+    pool: &PgPool,
+    org_id: &str,
+    source_type: &str,
+    source_id: &str,
+    entry_date: NaiveDate,
+    created_by_user_id: Option<&str>,
+) {
+    if let Err(error) = reverse_source(
+        pool,
+        org_id,
+        source_type,
+        source_id,
+        entry_date,
+        created_by_user_id,
+    )
+    .await
+    {
+        warn!(
+            organization_id = %org_id,
+            source_type = %source_type,
+            source_id = %source_id,
+            error = %error,
+            "Failed to reverse journal entries"
+        );
+    }
+}
+
+async fn resolve_account(
+    conn: &mut PgConnection,
+    org_id: &str,
+    account: &AccountRef,
+) -> AppResult<String> {
+    let row = match account {
+        AccountRef::System(key) => {
+            sqlx::query(
+                "SELECT id::text FROM gl_accounts
+             WHERE organization_id = $1::uuid AND system_key = $2",
+            )
+            .bind(org_id)
+            .bind(*key)
+            .fetch_optional(&mut *conn)
+            .await
+        }
+        AccountRef::Id(id) => {
+            sqlx::query(
+                "SELECT id::text FROM gl_accounts
+             WHERE organization_id = $1::uuid AND id = $2::uuid AND is_active",
+            )
+            .bind(org_id)
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await
+        }
+    }
+    .map_err(|e| AppError::from_database_error(&e, "Could not load account."))?;
+
+    match row {
+        Some(row) => Ok(row.try_get("id").unwrap_or_default()),
+        None => Err(match account {
+            AccountRef::System(key) => AppError::Conflict(format!(
+                "System account '{key}' is missing; another account already uses its code."
+            )),
+            AccountRef::Id(id) => {
+                AppError::UnprocessableEntity(format!("Account {id} is not an active account."))
+            }
+        }),
+    }
+}
+
+/// Entry for an approved expense. Expenses charged to a property are paid
+/// from trust on the owner's behalf; the rest are the manager's own costs.
+/// The source key includes `updated_at` so an edited expense can be
+/// reversed and posted again.
+pub fn expense_entry(expense: &Value, created_by_user_id: Option<&str>) -> Option<NewEntry> {
+    if value_str(expense, "approval_status") != "approved" {
+        return None;
+    }
+    let expense_id = value_str(expense, "id");
+    let amount = round2(number(expense, "amount"));
+    let entry_date = NaiveDate::parse_from_str(&value_str(expense, "expense_date"), "%Y-%m-%d")
+        .unwrap_or_else(|_| Utc::now().date_naive());
+    let property_id = value_str(expense, "property_id");
+    let lines = if property_id.is_empty() {
+        vec![
+            JournalLine::debit("operating_expenses", amount),
+            JournalLine::credit("operating_cash", amount),
+        ]
+    } else {
+        vec![
+            JournalLine::debit("owner_payable", amount).property(Some(&property_id)),
+            JournalLine::credit("trust_cash", amount).property(Some(&property_id)),
+        ]
+    };
+    let vendor = value_str(expense, "vendor_name");
+    Some(NewEntry {
+        organization_id: value_str(expense, "organization_id"),
+        entry_date,
+        description: if vendor.is_empty() {
+            format!("Expense ({})", value_str(expense, "category"))
+        } else {
+            format!("Expense: {vendor}")
+        },
+        source_type: "expense".to_string(),
+        source_key: Some(format!(
+            "expense:{expense_id}:{}",
+            value_str(expense, "updated_at")
+        )),
+        source_id: Some(expense_id),
+        currency: value_str(expense, "currency"),
+        created_by_user_id: created_by_user_id.map(ToOwned::to_owned),
+        lines,
+    })
+}
+
+/// Whether a change to an expense affects what was posted for it.
+pub fn expense_posting_changed(before: &Value, after: &Value) -> bool {
+    ["approval_status", "currency", "property_id", "expense_date"]
+        .iter()
+        .any(|key| value_str(before, key) != value_str(after, key))
+        || (number(before, "amount") - number(after, "amount")).abs() >= EPSILON
+}
+
+/// Keep the ledger in step with an expense that was approved, rejected,
+/// edited (`before` and `after`) or deleted (`after` is `None`).
+pub async fn sync_expense(
+    pool: &PgPool,
+    before: Option<&Value>,
+    after: Option<&Value>,
+    user_id: Option<&str>,
+) {
+    let was_posted = before.is_some_and(|row| value_str(row, "approval_status") == "approved");
+    let changed = match (before, after) {
+        (Some(before), Some(after)) => expense_posting_changed(before, after),
+        _ => true,
+    };
+    if !changed {
+        return;
+    }
+    let today = Utc::now().date_naive();
+    if let (true, Some(before)) = (was_posted, before) {
+        reverse_event(
+            pool,
+            &value_str(before, "organization_id"),
+            "expense",
+            &value_str(before, "id"),
+            today,
+            user_id,
+        )
+        .await;
+    }
+    if let Some(entry) = after.and_then(|row| expense_entry(row, user_id)) {
+        record_event(pool, entry).await;
+    }
+}
+
+/// Post a reservation deposit movement (`collected`, `released` or
+/// `forfeited`) from the reservation row after the status change.
+/// Forfeited deposits become owed to the owner.
+pub async fn record_deposit_event(
+    pool: &PgPool,
+    reservation: &Value,
+    event_type: &str,
+    user_id: Option<&str>,
+) {
+    let amount = round2(number(reservation, "deposit_amount"));
+    let (debit, credit) = match event_type {
+        "collected" => ("trust_cash", "tenant_deposits"),
+        "released" => ("tenant_deposits", "trust_cash"),
+        "forfeited" => ("tenant_deposits", "owner_payable"),
+        _ => return,
+    };
+    if amount < EPSILON {
+        return;
+    }
+    let property_id: Option<String> =
+        sqlx::query_scalar("SELECT property_id::text FROM units WHERE id = $1::uuid")
+            .bind(value_str(reservation, "unit_id"))
+            .fetch_optional(pool)
+            .await
+            .ok()
+            .flatten();
+
+    let reservation_id = value_str(reservation, "id");
+    let currency = Some(value_str(reservation, "deposit_currency"))
+        .filter(|currency| !currency.is_empty())
+        .unwrap_or_else(|| "PYG".to_string());
+    record_event(
+        pool,
+        NewEntry {
+            organization_id: value_str(reservation, "organization_id"),
+            entry_date: Utc::now().date_naive(),
+            description: format!("Security deposit {event_type}"),
+            source_type: "deposit".to_string(),
+            source_id: Some(reservation_id.clone()),
+            source_key: Some(format!("deposit:{reservation_id}:{event_type}")),
+            currency,
+            created_by_user_id: user_id.map(ToOwned::to_owned),
+            lines: vec![
+                JournalLine::debit(debit, amount).property(property_id.as_deref()),
+                JournalLine::credit(credit, amount).property(property_id.as_deref()),
+            ],
+        },
+    )
+    .await;
+}
+
+/// Post a movement of a lease security deposit. A deposit received
+/// through a recorded lease payment is reclassified from what that payment
+/// credited to the owner; interest is owed to the tenant by the owner;
+/// deductions are kept for the owner and refunds leave trust cash.
+pub async fn record_lease_deposit_event(
+    pool: &PgPool,
+    deposit: &Value,
+    event_type: &str,
+    amount: f64,
+    event_key: &str,
+    user_id: Option<&str>,
+) {
+    let amount = round2(amount);
+    if amount < EPSILON {
+        return;
+    }
+    let (debit, credit) = match event_type {
+        "received" if value_str(deposit, "lease_payment_id").is_empty() => {
+            ("trust_cash", "tenant_deposits")
+        }
+        "received" | "interest" => ("owner_payable", "tenant_deposits"),
+        "deducted" => ("tenant_deposits", "owner_payable"),
+        "refunded" => ("tenant_deposits", "trust_cash"),
+        _ => return,
+    };
+    let property_id = value_str(deposit, "property_id");
+    let property_id = Some(property_id.as_str()).filter(|id| !id.is_empty());
+    let lease_id = value_str(deposit, "lease_id");
+    let lease_id = Some(lease_id.as_str());
+    let deposit_id = value_str(deposit, "id");
+    record_event(
+        pool,
+        NewEntry {
+            organization_id: value_str(deposit, "organization_id"),
+            entry_date: Utc::now().date_naive(),
+            description: format!("Lease security deposit {event_type}"),
+            source_type: "lease_deposit".to_string(),
+            source_id: Some(deposit_id.clone()),
+            source_key: Some(format!("lease_deposit:{deposit_id}:{event_key}")),
+            currency: value_str(deposit, "currency"),
+            created_by_user_id: user_id.map(ToOwned::to_owned),
+            lines: vec![
+                JournalLine::debit(debit, amount)
+                    .property(property_id)
+                    .lease(lease_id),
+                JournalLine::credit(credit, amount)
+                    .property(property_id)
+                    .lease(lease_id),
+            ],
+        },
+    )
+    .await;
+}
+
+/// Post the management fees of a finalized owner statement: they are
+/// earned from the owner's balance and swept from trust to operating cash.
+pub async fn record_statement_fees(pool: &PgPool, statement: &Value, user_id: Option<&str>) {
+    let fees = round2(
+        number(statement, "service_fees")
+            + number(statement, "collection_fees")
+            + number(statement, "management_fees"),
+    );
+    if fees < EPSILON {
+        return;
+    }
+    let property_id = statement_property_id(pool, statement).await;
+    let property_id = property_id.as_deref();
+    let statement_id = value_str(statement, "id");
+    let entry_date = NaiveDate::parse_from_str(&value_str(statement, "period_end"), "%Y-%m-%d")
+        .unwrap_or_else(|_| Utc::now().date_naive());
+    record_event(
+        pool,
+        NewEntry {
+            organization_id: value_str(statement, "organization_id"),
+            entry_date,
+            description: format!(
+                "Management fees {} to {}",
+                value_str(statement, "period_start"),
+                value_str(statement, "period_end")
+            ),
+            source_type: "owner_statement".to_string(),
+            source_id: Some(statement_id.clone()),
+            source_key: Some(format!("owner_statement:{statement_id}:fees")),
+            currency: value_str(statement, "currency"),
+            created_by_user_id: user_id.map(ToOwned::to_owned),
+            lines: vec![
+                JournalLine::debit("owner_payable", fees).property(property_id),
+                JournalLine::credit("management_fee_revenue", fees).property(property_id),
+                JournalLine::debit("operating_cash", fees),
+                JournalLine::credit("trust_cash", fees).property(property_id),
+            ],
+        },
+    )
+    .await;
+}
+
+/// Post the payout of an owner statement's net amount from trust.
+pub async fn record_statement_payout(pool: &PgPool, statement: &Value, user_id: Option<&str>) {
+    let amount = round2(number(statement, "net_payout"));
+    if amount < EPSILON {
+        return;
+    }
+    let property_id = statement_property_id(pool, statement).await;
+    let property_id = property_id.as_deref();
+    let statement_id = value_str(statement, "id");
+    record_event(
+        pool,
+        NewEntry {
+            organization_id: value_str(statement, "organization_id"),
+            entry_date: Utc::now().date_naive(),
+            description: format!(
+                "Owner payout {} to {}",
+                value_str(statement, "period_start"),
+                value_str(statement, "period_end")
+            ),
+            source_type: "owner_statement".to_string(),
+            source_id: Some(statement_id.clone()),
+            source_key: Some(format!("owner_statement:{statement_id}:payout")),
+            currency: value_str(statement, "currency"),
+            created_by_user_id: user_id.map(ToOwned::to_owned),
+            lines: vec![
+                JournalLine::debit("owner_payable", amount).property(property_id),
+                JournalLine::credit("trust_cash", amount).property(property_id),
+            ],
+        },
+    )
+    .await;
+}
+
+/// Post an owner reserve movement. Contributions are cash paid in by the
+/// owner; every other movement moves money between what is owed to the
+/// owner and the reserve held for them.
+pub async fn record_reserve_entry(pool: &PgPool, entry: &Value, user_id: Option<&str>) {
+    let amount = round2(number(entry, "amount"));
+    if amount.abs() < EPSILON {
+        return;
+    }
+    let entry_type = value_str(entry, "entry_type");
+    let counter = if entry_type == "contribution" {
+        "trust_cash"
+    } else {
+        "owner_payable"
+    };
+    let (debit, credit) = if amount > 0.0 {
+        (counter, "owner_reserves")
+    } else {
+        ("owner_reserves", counter)
+    };
+    let property_id = value_str(entry, "property_id");
+    let property_id = Some(property_id.as_str());
+    let entry_id = value_str(entry, "id");
+    record_event(
+        pool,
+        NewEntry {
+            organization_id: value_str(entry, "organization_id"),
+            entry_date: Utc::now().date_naive(),
+            description: format!("Owner reserve {}", entry_type.replace('_', " ")),
+            source_type: "owner_reserve".to_string(),
+            source_id: Some(entry_id.clone()),
+            source_key: Some(format!("owner_reserve:{entry_id}")),
+            currency: value_str(entry, "currency"),
+            created_by_user_id: user_id.map(ToOwned::to_owned),
+            lines: vec![
+                JournalLine::debit(debit, amount.abs()).property(property_id),
+                JournalLine::credit(credit, amount.abs()).property(property_id),
+            ],
+        },
+    )
+    .await;
+}
+
+/// Statements may be scoped to a unit only; the ledger tracks owners by
+/// property.
+async fn statement_property_id(pool: &PgPool, statement: &Value) -> Option<String> {
+    let property_id = value_str(statement, "property_id");
+    if !property_id.is_empty() {
+        return Some(property_id);
+    }
+    let unit_id = value_str(statement, "unit_id");
+    if unit_id.is_empty() {
+        return None;
+    }
+    sqlx::query_scalar("SELECT property_id::text FROM units WHERE id = $1::uuid")
+        .bind(unit_id)
+        .fetch_optional(pool)
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Debit and credit totals for one account in one currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountTotals {
+    pub account_id: String,
+    pub code: String,
+    pub name: String,
+    pub account_type: AccountType,
+    pub currency: String,
+    pub debit: f64,
+    pub credit: f64,
+}
+
+impl AccountTotals {
+    /// Balance on the account's normal side.
+    pub fn balance(&self) -> f64 {
+        if self.account_type.is_debit_normal() {
+            round2(self.debit - self.credit)
+        } else {
+            round2(self.credit - self.debit)
+        }
+    }
+}
+
+/// Totals per account and currency for entries dated on or before `as_of`.
+pub async fn account_totals(
+    pool: &PgPool,
+    org_id: &str,
+    as_of: Option<NaiveDate>,
+) -> AppResult<Vec<AccountTotals>> {
+    let rows = sqlx::query(
+        "SELECT a.id::text AS account_id, a.code, a.name, a.account_type,
+                e.currency::text AS currency,
+                SUM(l.debit)::float8 AS debit, SUM(l.credit)::float8 AS credit
+         FROM gl_journal_lines l
+         JOIN gl_journal_entries e ON e.id = l.entry_id
+         JOIN gl_accounts a ON a.id = l.account_id
+         WHERE l.organization_id = $1::uuid
+           AND ($2::date IS NULL OR e.entry_date <= $2::date)
+         GROUP BY a.id, a.code, a.name, a.account_type, e.currency
+         ORDER BY e.currency, a.code",
+    )
+    .bind(org_id)
+    .bind(as_of)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load account totals."))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| AccountTotals {
+            account_id: row.try_get("account_id").unwrap_or_default(),
+            code: row.try_get("code").unwrap_or_default(),
+            name: row.try_get("name").unwrap_or_default(),
+            account_type: AccountType::parse(
+                &row.try_get::<String, _>("account_type").unwrap_or_default(),
+            )
+            .unwrap_or(AccountType::Asset),
+            currency: row.try_get("currency").unwrap_or_default(),
+            debit: row.try_get("debit").unwrap_or(0.0),
+            credit: row.try_get("credit").unwrap_or(0.0),
+        })
+        .collect())
+}
+
+/// Trial balance per currency: every account with its debit or credit
+/// balance, and whether the two columns agree.
+pub fn trial_balance(totals: &[AccountTotals]) -> Value {
+    let mut by_currency: Map<String, Value> = Map::new();
+    for account in totals {
+        let net = round2(account.debit - account.credit);
+        let section = by_currency
+            .entry(account.currency.clone())
+            .or_insert_with(|| json!({ "accounts": [], "total_debit": 0.0, "total_credit": 0.0 }));
+        let (debit, credit) = if net >= 0.0 { (net, 0.0) } else { (0.0, -net) };
+        section["total_debit"] = json!(round2(number(section, "total_debit") + debit));
+        section["total_credit"] = json!(round2(number(section, "total_credit") + credit));
+        if let Some(accounts) = section["accounts"].as_array_mut() {
+            accounts.push(json!({
+                "account_id": account.account_id,
+                "code": account.code,
+                "name": account.name,
+                "account_type": account.account_type.as_str(),
+                "debit": debit,
+                "credit": credit,
+            }));
+        }
+    }
+    for section in by_currency.values_mut() {
+        let balanced =
+            (number(section, "total_debit") - number(section, "total_credit")).abs() < EPSILON;
+        section["balanced"] = Value::Bool(balanced);
+    }
+    Value::Object(by_currency)
+}
+
+/// Balance sheet per currency. Revenue less expenses is shown as current
+/// earnings under equity so assets equal liabilities plus equity.
+pub fn balance_sheet(totals: &[AccountTotals]) -> Value {
+    let mut by_currency: Map<String, Value> = Map::new();
+    for account in totals {
+        let section = by_currency
+            .entry(account.currency.clone())
+            .or_insert_with(|| {
+                json!({
+                    "assets": [], "liabilities": [], "equity": [],
+                    "total_assets": 0.0, "total_liabilities": 0.0, "total_equity": 0.0,
+                    "current_earnings": 0.0,
+                })
+            });
+        let balance = account.balance();
+        let (list, total) = match account.account_type {
+            AccountType::Asset => ("assets", "total_assets"),
+            AccountType::Liability => ("liabilities", "total_liabilities"),
+            AccountType::Equity => ("equity", "total_equity"),
+            AccountType::Revenue => {
+                section["current_earnings"] =
+                    json!(round2(number(section, "current_earnings") + balance));
+                continue;
+            }
+            AccountType::Expense => {
+                section["current_earnings"] =
+                    json!(round2(number(section, "current_earnings") - balance));
+                continue;
+            }
+        };
+        section[total] = json!(round2(number(section, total) + balance));
+        if let Some(items) = section[list].as_array_mut() {
+            items.push(json!({
+                "account_id": account.account_id,
+                "code": account.code,
+                "name": account.name,
+                "balance": balance,
+            }));
+        }
+    }
+    for section in by_currency.values_mut() {
+        let equity = round2(number(section, "total_equity") + number(section, "current_earnings"));
+        section["total_equity"] = json!(equity);
+        let liabilities_and_equity = round2(number(section, "total_liabilities") + equity);
+        section["total_liabilities_and_equity"] = json!(liabilities_and_equity);
+        section["balanced"] =
+            Value::Bool((number(section, "total_assets") - liabilities_and_equity).abs() < EPSILON);
+    }
+    Value::Object(by_currency)
+}
+
+/// Owner-payable activity for a set of properties between two dates, with
+/// the opening balance and a running balance per currency. Credits (rent
+/// collected, forfeited deposits) increase what is owed to the owner;
+/// debits (expenses, fees, payouts) reduce it.
+pub async fn owner_ledger(
+    pool: &PgPool,
+    org_id: &str,
+    property_ids: &[String],
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> AppResult<Value> {
+    let opening = sqlx::query(
+        "SELECT e.currency::text AS currency,
+                SUM(l.credit - l.debit)::float8 AS balance
+         FROM gl_journal_lines l
+         JOIN gl_journal_entries e ON e.id = l.entry_id
+         JOIN gl_accounts a ON a.id = l.account_id
+         WHERE l.organization_id = $1::uuid
+           AND a.system_key = 'owner_payable'
+           AND l.property_id::text = ANY($2)
+           AND $3::date IS NOT NULL AND e.entry_date < $3::date
+         GROUP BY e.currency",
+    )
+    .bind(org_id)
+    .bind(property_ids)
+    .bind(from)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load opening balance."))?;
+
+    let lines = sqlx::query(
+        "SELECT e.id::text AS entry_id, e.entry_date, e.description, e.source_type,
+                e.source_id::text AS source_id, e.currency::text AS currency,
+                l.property_id::text AS property_id, l.lease_id::text AS lease_id,
+                l.memo, l.debit::float8 AS debit, l.credit::float8 AS credit
+         FROM gl_journal_lines l
+         JOIN gl_journal_entries e ON e.id = l.entry_id
+         JOIN gl_accounts a ON a.id = l.account_id
+         WHERE l.organization_id = $1::uuid
+           AND a.system_key = 'owner_payable'
+           AND l.property_id::text = ANY($2)
+           AND ($3::date IS NULL OR e.entry_date >= $3::date)
+           AND ($4::date IS NULL OR e.entry_date <= $4::date)
+         ORDER BY e.entry_date, e.created_at, l.created_at",
+    )
+    .bind(org_id)
+    .bind(property_ids)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load owner ledger."))?;
+
+    let mut balances: Map<String, Value> = Map::new();
+    for row in &opening {
+        let currency: String = row.try_get("currency").unwrap_or_default();
+        let balance = round2(row.try_get::<f64, _>("balance").unwrap_or(0.0));
+        balances.insert(currency, json!({ "opening": balance, "closing": balance }));
+    }
+
+    let mut entries = Vec::with_capacity(lines.len());
+    for row in &lines {
+        let currency: String = row.try_get("currency").unwrap_or_default();
+        let debit: f64 = row.try_get("debit").unwrap_or(0.0);
+        let credit: f64 = row.try_get("credit").unwrap_or(0.0);
+        let totals = balances
+            .entry(currency.clone())
+            .or_insert_with(|| json!({ "opening": 0.0, "closing": 0.0 }));
+        let running = round2(number(totals, "closing") + credit - debit);
+        totals["closing"] = json!(running);
+
+        entries.push(json!({
+            "entry_id": row.try_get::<String, _>("entry_id").unwrap_or_default(),
+            "entry_date": row
+                .try_get::<NaiveDate, _>("entry_date")
+                .ok()
+                .map(|date| date.to_string()),
+            "description": row.try_get::<String, _>("description").unwrap_or_default(),
+            "source_type": row.try_get::<String, _>("source_type").unwrap_or_default(),
+            "source_id": row.try_get::<Option<String>, _>("source_id").ok().flatten(),
+            "property_id": row.try_get::<Option<String>, _>("property_id").ok().flatten(),
+            "lease_id": row.try_get::<Option<String>, _>("lease_id").ok().flatten(),
+            "memo": row.try_get::<Option<String>, _>("memo").ok().flatten(),
+            "currency": currency,
+            "debit": debit,
+            "credit": credit,
+            "balance": running,
+        }));
+    }
+
+    Ok(json!({
+        "property_ids": property_ids,
+        "from": from.map(|date| date.to_string()),
+        "to": to.map(|date| date.to_string()),
+        "balances": balances,
+        "entries": entries,
+    }))
+}
+
+fn number(row: &Value, key: &str) -> f64 {
+    match row.get(key) {
+        Some(Value::Number(value)) => value.as_f64().unwrap_or(0.0),
+        Some(Value::String(value)) => value.trim().parse().unwrap_or(0.0),
+        _ => 0.0,
+    }
+}
+
+fn value_str(row: &Value, key: &str) -> String {
+    row.get(key)
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn totals(code: &str, account_type: AccountType, debit: f64, credit: f64) -> AccountTotals {
+        AccountTotals {
+            account_id: code.to_string(),
+            code: code.to_string(),
+            name: code.to_string(),
+            account_type,
+            currency: "PYG".to_string(),
+            debit,
+            credit,
+        }
+    }
+
+    #[test]
+    fn rejects_unbalanced_and_two_sided_lines() {
+        let balanced = [
+            JournalLine::debit("trust_cash", 1_000.0),
+            JournalLine::credit("owner_payable", 800.0),
+            JournalLine::credit("tenant_prepayments", 200.0),
+        ];
+        assert!(validate_lines(&balanced).is_ok());
+
+        let unbalanced = [
+            JournalLine::debit("trust_cash", 1_000.0),
+            JournalLine::credit("owner_payable", 999.0),
+        ];
+        assert!(validate_lines(&unbalanced).is_err());
+
+        let two_sided = [
+            JournalLine::new(AccountRef::System("trust_cash"), 10.0, 10.0),
+            JournalLine::credit("owner_payable", 0.0),
+        ];
+        assert!(validate_lines(&two_sided).is_err());
+        assert!(validate_lines(&balanced[..1]).is_err());
+    }
+
+    #[test]
+    fn expense_postings_depend_on_property() {
+        let expense = json!({
+            "id": "e1",
+            "organization_id": "o1",
+            "approval_status": "approved",
+            "property_id": "p1",
+            "amount": "250.00",
+            "currency": "PYG",
+            "expense_date": "2026-10-01",
+            "updated_at": "t1",
+        });
+        let entry = expense_entry(&expense, None).expect("approved expense posts");
+        assert_eq!(entry.lines[0].account, AccountRef::System("owner_payable"));
+        assert_eq!(entry.lines[0].debit, 250.0);
+        assert_eq!(entry.lines[1].property_id.as_deref(), Some("p1"));
+        assert_eq!(entry.source_key.as_deref(), Some("expense:e1:t1"));
+
+        let mut overhead = expense.clone();
+        overhead["property_id"] = Value::Null;
+        let entry = expense_entry(&overhead, None).expect("approved expense posts");
+        assert_eq!(
+            entry.lines[0].account,
+            AccountRef::System("operating_expenses")
+        );
+
+        let mut pending = expense.clone();
+        pending["approval_status"] = json!("pending");
+        assert!(expense_entry(&pending, None).is_none());
+        assert!(expense_posting_changed(&pending, &expense));
+
+        let mut renamed = expense.clone();
+        renamed["notes"] = json!("receipt attached");
+        assert!(!expense_posting_changed(&expense, &renamed));
+    }
+
+    #[test]
+    fn trial_balance_puts_net_balances_on_one_side() {
+        let report = trial_balance(&[
+            totals("1000", AccountType::Asset, 1_500.0, 300.0),
+            totals("2000", AccountType::Liability, 300.0, 1_200.0),
+            totals("4000", AccountType::Revenue, 0.0, 300.0),
+        ]);
+        let pyg = &report["PYG"];
+        assert_eq!(pyg["accounts"][0]["debit"], json!(1_200.0));
+        assert_eq!(pyg["accounts"][1]["credit"], json!(900.0));
+        assert_eq!(pyg["total_debit"], json!(1_200.0));
+        assert_eq!(pyg["total_credit"], json!(1_200.0));
+        assert_eq!(pyg["balanced"], json!(true));
+    }
+
+    #[test]
+    fn balance_sheet_rolls_earnings_into_equity() {
+        let report = balance_sheet(&[
+            totals("1000", AccountType::Asset, 1_000.0, 150.0),
+            totals("1010", AccountType::Asset, 150.0, 50.0),
+            totals("2000", AccountType::Liability, 150.0, 1_000.0),
+            totals("4000", AccountType::Revenue, 0.0, 150.0),
+            totals("5000", AccountType::Expense, 50.0, 0.0),
+        ]);
+        let pyg = &report["PYG"];
+        assert_eq!(pyg["total_assets"], json!(950.0));
+        assert_eq!(pyg["total_liabilities"], json!(850.0));
+        assert_eq!(pyg["current_earnings"], json!(100.0));
+        assert_eq!(pyg["total_liabilities_and_equity"], json!(950.0));
+        assert_eq!(pyg["balanced"], json!(true));
+    }
+}
@@ -1,13 +1,23 @@
-use chrono::{NaiveDate, Utc};
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
 use reqwest::Client;
-use serde_json::{Map, Value};
+use serde_json::{json, Map, Value};
 use sha2::{Digest, Sha256};
 use sqlx::PgPool;
+use tokio::{sync::Semaphore, task::JoinSet};
 
 use crate::{
     error::{AppError, AppResult},
-    repository::table_service::{create_row, list_rows, update_row},
-    services::json_helpers::json_map,
+    repository::table_service::{create_row, delete_row, list_rows, update_row},
+    services::{
+        availability::{self, Occupancy},
+        cron::parse_timezone,
+        ical_parser::{parse_calendar, ExpansionWindow, ICalEvent},
+        json_helpers::json_map,
+        stay_restrictions,
+    },
 };
 
 const ACTIVE_RESERVATION_STATUSES: &[&str] = &["pending", "confirmed", "checked_in"];
@@ -116,6 +126,31 @@ pub async fn build_unit_ical_export(
         );
     }
 
+    // iCal has no notion of stay rules; the closest equivalent is closing
+    // orphan gaps that no stay may fill, so feeds don't sell them.
+    let today = Utc::now().date_naive();
+    let horizon = today + Duration::days(365);
+    let restrictions =
+        stay_restrictions::load_restrictions(pool, Some(org_id), Some(unit_id), today, horizon)
+            .await?;
+    if !restrictions.is_empty() {
+        let occupancy = availability::load_unit_occupancy(pool, unit_id, today, None).await?;
+        for gap in stay_restrictions::find_gap_nights(&occupancy, &restrictions, today, horizon) {
+            if gap.allow_gap_fill {
+                continue;
+            }
+            add_event(
+                &mut lines,
+                &now_stamp,
+                &format!("pa-minstay-{unit_id}-{}", gap.start.format("%Y%m%d")),
+                &gap.start.format("%Y%m%d").to_string(),
+                &gap.end.format("%Y%m%d").to_string(),
+                "Blocked",
+                &format!("Not available (minimum stay {} nights)", gap.min_nights),
+            );
+        }
+    }
+
     lines.push("END:VCALENDAR".to_string());
 
     let mut folded = Vec::new();
@@ -185,10 +220,24 @@ fn string_value(value: Option<&Value>) -> Option<String> {
 // iCal sync all integrations
 // ---------------------------------------------------------------------------
 
-/// Sync all active iCal integrations across all organizations.
-pub async fn sync_all_ical_integrations(pool: &PgPool, client: &Client) -> Value {
+/// Sync all active iCal integrations across all organizations (or only
+/// `org_id`'s), at most `concurrency` feeds at a time. Feeds that answer
+/// `304 Not Modified` or whose events are unchanged since the last sync are
+/// not reprocessed.
+pub async fn sync_all_ical_integrations(
+    pool: &PgPool,
+    client: &Client,
+    concurrency: usize,
+    org_id: Option<&str>,
+) -> Value {
     let mut filters = Map::new();
     filters.insert("is_active".to_string(), Value::Bool(true));
+    if let Some(org_id) = org_id {
+        filters.insert(
+            "organization_id".to_string(),
+            Value::String(org_id.to_string()),
+        );
+    }
 
     let integrations = match list_rows(
         pool,
@@ -212,8 +261,9 @@ pub async fn sync_all_ical_integrations(pool: &PgPool, client: &Client) -> Value
         }
     };
 
-    let mut synced = 0u32;
-    let mut failed = 0u32;
+    let concurrency = concurrency.max(1);
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let mut tasks = JoinSet::new();
     let mut skipped = 0u32;
 
     for integration in &integrations {
@@ -227,30 +277,30 @@ pub async fn sync_all_ical_integrations(pool: &PgPool, client: &Client) -> Value
             continue;
         }
 
-        let integration_id =
-            string_value(integration.as_object().and_then(|o| o.get("id"))).unwrap_or_default();
+        let pool = pool.clone();
+        let client = client.clone();
+        let integration = integration.clone();
+        let semaphore = semaphore.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.ok();
+            sync_scheduled_integration(&pool, &client, &integration).await
+        });
+    }
 
-        match sync_listing_ical_reservations(pool, client, integration, "system").await {
-            Ok(_result) => {
-                // Update last_ical_sync_at, clear error
-                let mut patch = Map::new();
-                patch.insert(
-                    "last_ical_sync_at".to_string(),
-                    Value::String(Utc::now().to_rfc3339()),
-                );
-                patch.insert("ical_sync_error".to_string(), Value::Null);
-                let _ = update_row(pool, "integrations", &integration_id, &patch, "id").await;
+    let mut synced = 0u32;
+    let mut unchanged = 0u32;
+    let mut failed = 0u32;
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(Ok(result)) => {
                 synced += 1;
+                if result.get("not_modified").and_then(Value::as_bool) == Some(true) {
+                    unchanged += 1;
+                }
             }
+            Ok(Err(_)) => failed += 1,
             Err(e) => {
-                let error_msg = format!("{e}");
-                let mut patch = Map::new();
-                patch.insert(
-                    "last_ical_sync_at".to_string(),
-                    Value::String(Utc::now().to_rfc3339()),
-                );
-                patch.insert("ical_sync_error".to_string(), Value::String(error_msg));
-                let _ = update_row(pool, "integrations", &integration_id, &patch, "id").await;
+                tracing::error!(error = %e, "iCal sync task panicked");
                 failed += 1;
             }
         }
@@ -259,205 +309,187 @@ pub async fn sync_all_ical_integrations(pool: &PgPool, client: &Client) -> Value
     serde_json::json!({
         "total_integrations": integrations.len(),
         "synced": synced,
+        "unchanged": unchanged,
         "failed": failed,
         "skipped": skipped,
+        "concurrency": concurrency,
         "synced_at": Utc::now().to_rfc3339(),
     })
 }
 
-// ---------------------------------------------------------------------------
-// iCal import / sync
-// ---------------------------------------------------------------------------
+/// Incrementally sync one integration and record the outcome on its row.
+async fn sync_scheduled_integration(
+    pool: &PgPool,
+    client: &Client,
+    integration: &Value,
+) -> AppResult<Value> {
+    let integration_id =
+        string_value(integration.as_object().and_then(|o| o.get("id"))).unwrap_or_default();
 
-const PA_UID_PREFIXES: &[&str] = &["pa-resv-", "pa-block-", "pa-"];
+    let outcome = sync_listing_ical_reservations(
+        pool,
+        client,
+        integration,
+        "system",
+        IcalSyncMode::Incremental,
+    )
+    .await;
 
-fn unfold_ical_lines(text: &str) -> Vec<String> {
-    let mut unfolded: Vec<String> = Vec::new();
-    for raw in text.lines() {
-        let line = raw.trim_end_matches(['\r', '\n']);
-        if line.is_empty() {
-            continue;
+    let mut patch = Map::new();
+    patch.insert(
+        "last_ical_sync_at".to_string(),
+        Value::String(Utc::now().to_rfc3339()),
+    );
+    match &outcome {
+        Ok(_) => {
+            patch.insert("ical_sync_error".to_string(), Value::Null);
         }
-        if (line.starts_with(' ') || line.starts_with('\t')) && !unfolded.is_empty() {
-            if let Some(last) = unfolded.last_mut() {
-                last.push_str(&line[1..]);
-            }
-        } else {
-            unfolded.push(line.to_string());
+        Err(e) => {
+            patch.insert("ical_sync_error".to_string(), Value::String(format!("{e}")));
         }
     }
-    unfolded
-}
-
-fn parse_ical_date(value: &str, params: &Map<String, Value>) -> Option<NaiveDate> {
-    let value = value.trim();
-    if value.is_empty() {
-        return None;
-    }
-    if value.len() < 8 {
-        return None;
-    }
-    // Both VALUE=DATE (all-day) and date-time formats use YYYYMMDD prefix.
-    let _ = params; // params checked for VALUE=DATE in Python, but logic is identical
-    let yyyymmdd = &value[..8];
-    let formatted = format!(
-        "{}-{}-{}",
-        &yyyymmdd[0..4],
-        &yyyymmdd[4..6],
-        &yyyymmdd[6..8]
-    );
-    NaiveDate::parse_from_str(&formatted, "%Y-%m-%d").ok()
-}
-
-struct ICalEvent {
-    uid: String,
-    start_date: String,
-    end_date: String,
-    summary: String,
-    description: String,
-    status: String,
+    let _ = update_row(pool, "integrations", &integration_id, &patch, "id").await;
+    outcome
 }
 
-fn parse_ical_events(ics_text: &str) -> Vec<ICalEvent> {
-    let unfolded = unfold_ical_lines(ics_text);
-    // Each event is a map of property name -> Vec<(params, value)>.
-    let mut events: Vec<Map<String, Value>> = Vec::new();
-    let mut current: Option<Map<String, Value>> = None;
+// ---------------------------------------------------------------------------
+// iCal import / sync
+// ---------------------------------------------------------------------------
 
-    for line in &unfolded {
-        let upper = line.to_uppercase();
-        if upper == "BEGIN:VEVENT" {
-            current = Some(Map::new());
-            continue;
-        }
-        if upper == "END:VEVENT" {
-            if let Some(event) = current.take() {
-                events.push(event);
-            }
-            continue;
-        }
-        let Some(ref mut event) = current else {
-            continue;
-        };
-        let Some(colon_pos) = line.find(':') else {
-            continue;
-        };
-        let key_part = &line[..colon_pos];
-        let value = line[colon_pos + 1..].trim().to_string();
+const PA_UID_PREFIXES: &[&str] = &["pa-resv-", "pa-block-", "pa-"];
 
-        let key_bits: Vec<&str> = key_part.split(';').collect();
-        let key = key_bits[0].trim().to_uppercase();
-        if key.is_empty() {
-            continue;
-        }
+/// How far ahead recurring feed events (RRULE) are expanded on each sync.
+const ICAL_EXPANSION_HORIZON_DAYS: i64 = 540;
+
+/// Summaries channels use for host/owner blocks rather than guest stays
+/// (Airbnb "Not available", Booking.com "CLOSED - Not available", VRBO "Blocked").
+const BLOCK_SUMMARY_MARKERS: &[&str] = &[
+    "not available",
+    "unavailable",
+    "blocked",
+    "closed",
+    "no disponible",
+    "bloqueado",
+];
+
+fn parse_ical_events(ics_text: &str, unit_tz: Tz, today: NaiveDate) -> Vec<ICalEvent> {
+    parse_calendar(
+        ics_text,
+        unit_tz,
+        ExpansionWindow {
+            start: today,
+            end: today + Duration::days(ICAL_EXPANSION_HORIZON_DAYS),
+        },
+    )
+}
 
-        let mut params = Map::new();
-        for raw_param in &key_bits[1..] {
-            let raw_param = raw_param.trim();
-            if raw_param.is_empty() {
-                continue;
-            }
-            if let Some((pkey, pval)) = raw_param.split_once('=') {
-                params.insert(
-                    pkey.trim().to_uppercase(),
-                    Value::String(pval.trim().to_string()),
-                );
-            } else {
-                params.insert(raw_param.to_uppercase(), Value::String("TRUE".to_string()));
-            }
-        }
+/// Recurring events and explicitly blocked ranges become calendar blocks;
+/// everything else is imported as a reservation.
+fn is_block_event(event: &ICalEvent) -> bool {
+    if event.recurring {
+        return true;
+    }
+    let summary = event.summary.to_lowercase();
+    BLOCK_SUMMARY_MARKERS
+        .iter()
+        .any(|marker| summary.contains(marker))
+}
 
-        // Store as array of [params_object, value_string].
-        let entry = Value::Array(vec![Value::Object(params), Value::String(value)]);
-        if let Some(arr) = event
-            .entry(&key)
-            .or_insert_with(|| Value::Array(Vec::new()))
-            .as_array_mut()
-        {
-            arr.push(entry);
-        }
+async fn organization_timezone(pool: &PgPool, org_id: &str) -> Tz {
+    let name = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT timezone FROM organizations WHERE id = $1::uuid LIMIT 1",
+    )
+    .bind(org_id)
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten()
+    .flatten()
+    .unwrap_or_default();
+    if name.trim().is_empty() {
+        return Tz::America__Asuncion;
     }
+    parse_timezone(&name).unwrap_or(Tz::America__Asuncion)
+}
 
-    let mut parsed = Vec::new();
-    for event in &events {
-        let (uid_params, uid_value) = first_prop(event, "UID");
-        let (dtstart_params, dtstart_value) = first_prop(event, "DTSTART");
-        let (dtend_params, dtend_value) = first_prop(event, "DTEND");
-        let (_summary_params, summary_value) = first_prop(event, "SUMMARY");
-        let (_desc_params, desc_value) = first_prop(event, "DESCRIPTION");
-        let (_status_params, status_value) = first_prop(event, "STATUS");
+fn should_ignore_uid(uid: &str) -> bool {
+    let lowered = uid.trim().to_lowercase();
+    PA_UID_PREFIXES
+        .iter()
+        .any(|prefix| lowered.starts_with(prefix))
+}
 
-        let _ = uid_params;
+/// Whether a listing sync may skip feeds that have not changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IcalSyncMode {
+    /// Conditional GET with the stored ETag/Last-Modified; unchanged feeds are
+    /// not reprocessed and only produce a diff record when something changed.
+    Incremental,
+    /// Always refetch and reconcile, and always record a diff.
+    Full,
+}
 
-        let Some(start) = parse_ical_date(&dtstart_value, &dtstart_params) else {
-            continue;
-        };
-        let Some(end) = parse_ical_date(&dtend_value, &dtend_params) else {
-            continue;
-        };
-        if end <= start {
-            continue;
-        }
+/// Upper bound on entries kept per list in a stored sync diff; counts stay exact.
+const MAX_DIFF_ENTRIES: usize = 200;
 
-        let uid = if uid_value.trim().is_empty() {
-            let stable = format!(
-                "{}|{}|{}|{}",
-                start.format("%Y-%m-%d"),
-                end.format("%Y-%m-%d"),
-                summary_value,
-                desc_value
-            );
-            let mut hasher = Sha256::new();
-            hasher.update(stable.as_bytes());
-            format!("ical-{:x}", hasher.finalize())
-        } else {
-            uid_value.trim().to_string()
-        };
+/// What one sync changed, stored as an `ical_sync_diff` integration event.
+#[derive(Debug, Default)]
+struct SyncDiff {
+    added: Vec<Value>,
+    changed: Vec<Value>,
+    removed: Vec<Value>,
+    conflicts: Vec<Value>,
+}
 
-        parsed.push(ICalEvent {
-            uid,
-            start_date: start.format("%Y-%m-%d").to_string(),
-            end_date: end.format("%Y-%m-%d").to_string(),
-            summary: summary_value.trim().to_string(),
-            description: desc_value.trim().to_string(),
-            status: status_value.trim().to_uppercase(),
-        });
+impl SyncDiff {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.changed.is_empty()
+            && self.removed.is_empty()
+            && self.conflicts.is_empty()
     }
 
-    parsed
+    fn conflict(&mut self, kind: &str, event: &ICalEvent, error: String) {
+        self.conflicts.push(json!({
+            "kind": kind,
+            "uid": event.uid,
+            "start_date": event.start_date.to_string(),
+            "end_date": event.end_date.to_string(),
+            "error": error,
+        }));
+    }
 }
 
-fn first_prop(event: &Map<String, Value>, name: &str) -> (Map<String, Value>, String) {
-    let items = event
-        .get(&name.to_uppercase())
-        .and_then(Value::as_array)
-        .cloned()
-        .unwrap_or_default();
-    if items.is_empty() {
-        return (Map::new(), String::new());
-    }
-    let pair = items[0].as_array().cloned().unwrap_or_default();
-    let params = pair
-        .first()
-        .and_then(Value::as_object)
-        .cloned()
-        .unwrap_or_default();
-    let value = pair
-        .get(1)
-        .and_then(Value::as_str)
-        .unwrap_or_default()
-        .to_string();
-    (params, value)
+fn capped(entries: &[Value]) -> Value {
+    Value::Array(entries.iter().take(MAX_DIFF_ENTRIES).cloned().collect())
 }
 
-fn should_ignore_uid(uid: &str) -> bool {
-    let lowered = uid.trim().to_lowercase();
-    PA_UID_PREFIXES
+/// `{field: {from, to}}` for every field a patch changes on a row.
+fn patch_changes(existing: &Map<String, Value>, patch: &Map<String, Value>) -> Value {
+    let changes = patch
         .iter()
-        .any(|prefix| lowered.starts_with(prefix))
+        .map(|(key, to)| {
+            let from = existing.get(key).cloned().unwrap_or(Value::Null);
+            (key.clone(), json!({ "from": from, "to": to }))
+        })
+        .collect::<Map<String, Value>>();
+    Value::Object(changes)
 }
 
-async fn fetch_ical_text(client: &Client, url: &str) -> AppResult<String> {
+struct FeedFetch {
+    body: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+/// Fetch a feed, sending `If-None-Match`/`If-Modified-Since` when validators
+/// from a previous fetch are given. Returns `None` on `304 Not Modified`.
+async fn fetch_ical_feed(
+    client: &Client,
+    url: &str,
+    etag: Option<&str>,
+    last_modified: Option<&str>,
+) -> AppResult<Option<FeedFetch>> {
     let url = url.trim();
     if url.is_empty() {
         return Err(AppError::BadRequest(
@@ -465,23 +497,31 @@ async fn fetch_ical_text(client: &Client, url: &str) -> AppResult<String> {
         ));
     }
 
-    let resp = client
+    let mut request = client
         .get(url)
         .timeout(std::time::Duration::from_secs(20))
         .header("Accept", "text/calendar, text/plain;q=0.9, */*;q=0.1")
-        .header("User-Agent", "Casaora/1.0 (+https://casaora.co)")
-        .send()
-        .await
-        .map_err(|e| {
-            tracing::error!(error = %e, url = %url, "iCal fetch request failed");
-            if e.is_timeout() {
-                AppError::Dependency("iCal fetch timed out.".to_string())
-            } else {
-                AppError::Dependency("iCal fetch failed.".to_string())
-            }
-        })?;
+        .header("User-Agent", "Casaora/1.0 (+https://casaora.co)");
+    if let Some(etag) = etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = last_modified {
+        request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+    }
+
+    let resp = request.send().await.map_err(|e| {
+        tracing::error!(error = %e, url = %url, "iCal fetch request failed");
+        if e.is_timeout() {
+            AppError::Dependency("iCal fetch timed out.".to_string())
+        } else {
+            AppError::Dependency("iCal fetch failed.".to_string())
+        }
+    })?;
 
     let status = resp.status();
+    if status == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
     if !status.is_success() {
         tracing::error!(status = %status, url = %url, "iCal fetch returned non-success status");
         return Err(AppError::Dependency(
@@ -489,10 +529,145 @@ async fn fetch_ical_text(client: &Client, url: &str) -> AppResult<String> {
         ));
     }
 
-    resp.text().await.map_err(|e| {
+    let header = |name: reqwest::header::HeaderName| {
+        resp.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(ToOwned::to_owned)
+    };
+    let etag = header(reqwest::header::ETAG);
+    let last_modified = header(reqwest::header::LAST_MODIFIED);
+
+    let body = resp.text().await.map_err(|e| {
         tracing::error!(error = %e, url = %url, "iCal fetch body read failed");
         AppError::Dependency("iCal fetch failed.".to_string())
-    })
+    })?;
+
+    Ok(Some(FeedFetch {
+        body,
+        etag,
+        last_modified,
+    }))
+}
+
+/// Stable digest of the parsed events, so feeds that regenerate `DTSTAMP` on
+/// every request still count as unchanged.
+fn events_fingerprint(events: &[ICalEvent]) -> String {
+    let mut lines = events
+        .iter()
+        .map(|event| {
+            format!(
+                "{}|{}|{}|{}|{}",
+                event.uid, event.start_date, event.end_date, event.status, event.summary
+            )
+        })
+        .collect::<Vec<_>>();
+    lines.sort();
+    let mut hasher = Sha256::new();
+    hasher.update(lines.join("\n").as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+async fn store_feed_state(
+    pool: &PgPool,
+    integration_id: &str,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fingerprint: Option<String>,
+) {
+    let patch = json_map(&[
+        ("ical_etag", etag.map(Value::String).unwrap_or(Value::Null)),
+        (
+            "ical_last_modified",
+            last_modified.map(Value::String).unwrap_or(Value::Null),
+        ),
+        (
+            "ical_content_hash",
+            fingerprint.map(Value::String).unwrap_or(Value::Null),
+        ),
+    ]);
+    if let Err(e) = update_row(pool, "integrations", integration_id, &patch, "id").await {
+        tracing::warn!(error = %e, integration_id, "Failed to store iCal feed validators");
+    }
+}
+
+/// Record feed events that overlap reservations or blocks from other sources.
+fn record_overlaps(diff: &mut SyncDiff, occupancy: &[Occupancy], kind: &str, event: &ICalEvent) {
+    for other in availability::overlapping(occupancy, event.start_date, event.end_date) {
+        diff.conflicts.push(json!({
+            "kind": kind,
+            "uid": event.uid,
+            "start_date": event.start_date.to_string(),
+            "end_date": event.end_date.to_string(),
+            "conflicting_kind": other.kind.as_str(),
+            "conflicting_id": other.id,
+            "conflicting_source": other.source,
+            "conflicting_start_date": other.start.to_string(),
+            "conflicting_end_date": other.end.to_string(),
+        }));
+    }
+}
+
+struct DiffContext<'a> {
+    org_id: &'a str,
+    integration_id: &'a str,
+    unit_id: &'a str,
+    user_id: &'a str,
+    mode: IcalSyncMode,
+    processed_at: &'a str,
+}
+
+async fn record_sync_diff(
+    pool: &PgPool,
+    context: DiffContext<'_>,
+    diff: &SyncDiff,
+) -> Option<String> {
+    let trigger = if uuid::Uuid::parse_str(context.user_id).is_ok() {
+        "manual"
+    } else {
+        "system"
+    };
+    let payload = json!({
+        "integration_id": context.integration_id,
+        "unit_id": context.unit_id,
+        "trigger": trigger,
+        "requested_by_user_id": (trigger == "manual").then_some(context.user_id),
+        "mode": match context.mode {
+            IcalSyncMode::Incremental => "incremental",
+            IcalSyncMode::Full => "full",
+        },
+        "counts": {
+            "added": diff.added.len(),
+            "changed": diff.changed.len(),
+            "removed": diff.removed.len(),
+            "conflicts": diff.conflicts.len(),
+        },
+        "added": capped(&diff.added),
+        "changed": capped(&diff.changed),
+        "removed": capped(&diff.removed),
+        "conflicts": capped(&diff.conflicts),
+    });
+
+    let record = json_map(&[
+        ("organization_id", Value::String(context.org_id.to_string())),
+        ("provider", Value::String("ical".to_string())),
+        ("event_type", Value::String("ical_sync_diff".to_string())),
+        ("payload", payload),
+        ("status", Value::String("processed".to_string())),
+        (
+            "processed_at",
+            Value::String(context.processed_at.to_string()),
+        ),
+    ]);
+    match create_row(pool, "integration_events", &record).await {
+        Ok(row) => string_value(row.as_object().and_then(|o| o.get("id"))),
+        Err(e) => {
+            tracing::warn!(error = %e, integration_id = context.integration_id, "Failed to record iCal sync diff");
+            None
+        }
+    }
 }
 
 pub async fn sync_listing_ical_reservations(
@@ -500,6 +675,7 @@ pub async fn sync_listing_ical_reservations(
     client: &Client,
     listing: &Value,
     user_id: &str,
+    mode: IcalSyncMode,
 ) -> AppResult<Value> {
     let obj = listing
         .as_object()
@@ -516,11 +692,53 @@ pub async fn sync_listing_ical_reservations(
         AppError::BadRequest("Listing does not have an iCal import URL configured.".to_string())
     })?;
 
-    let ics_text = fetch_ical_text(client, &ical_url).await?;
-    let events = parse_ical_events(&ics_text);
+    let (etag, last_modified) = match mode {
+        IcalSyncMode::Incremental => (
+            string_value(obj.get("ical_etag")),
+            string_value(obj.get("ical_last_modified")),
+        ),
+        IcalSyncMode::Full => (None, None),
+    };
+    let now_iso = Utc::now().to_rfc3339();
+
+    let fetched =
+        fetch_ical_feed(client, &ical_url, etag.as_deref(), last_modified.as_deref()).await?;
+    let Some(fetched) = fetched else {
+        return Ok(json!({
+            "import_url": ical_url,
+            "not_modified": true,
+            "processed_at": now_iso,
+        }));
+    };
+
+    let unit_tz = organization_timezone(pool, &org_id).await;
+    let today = Utc::now().with_timezone(&unit_tz).date_naive();
+    let events = parse_ical_events(&fetched.body, unit_tz, today);
+    let fingerprint = events_fingerprint(&events);
+
+    if mode == IcalSyncMode::Incremental
+        && string_value(obj.get("ical_content_hash")).as_deref() == Some(fingerprint.as_str())
+    {
+        store_feed_state(
+            pool,
+            &integration_id,
+            fetched.etag,
+            fetched.last_modified,
+            Some(fingerprint),
+        )
+        .await;
+        return Ok(json!({
+            "import_url": ical_url,
+            "not_modified": true,
+            "events_total": events.len(),
+            "processed_at": now_iso,
+        }));
+    }
 
     let mut desired: std::collections::HashMap<String, &ICalEvent> =
         std::collections::HashMap::new();
+    let mut desired_blocks: std::collections::HashMap<String, &ICalEvent> =
+        std::collections::HashMap::new();
     let mut ignored_uid_prefix: u64 = 0;
     for event in &events {
         let uid = event.uid.trim();
@@ -531,7 +749,11 @@ pub async fn sync_listing_ical_reservations(
             ignored_uid_prefix += 1;
             continue;
         }
-        desired.insert(uid.to_string(), event);
+        if is_block_event(event) {
+            desired_blocks.insert(uid.to_string(), event);
+        } else {
+            desired.insert(uid.to_string(), event);
+        }
     }
 
     // Pull only iCal-sourced reservations for this listing.
@@ -564,26 +786,28 @@ pub async fn sync_listing_ical_reservations(
         }
     }
 
+    let occupancy = availability::load_unit_occupancy(pool, &unit_id, today, Some(&integration_id))
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(error = %e.detail_message(), unit_id = %unit_id, "Failed to load unit occupancy for iCal conflict check");
+            Vec::new()
+        });
+
     let mut created: u64 = 0;
     let mut updated: u64 = 0;
     let mut cancelled: u64 = 0;
     let mut ignored: u64 = 0;
-    let mut conflicts: u64 = 0;
     let mut errors: Vec<String> = Vec::new();
-
-    let now_iso = Utc::now().to_rfc3339();
+    let mut diff = SyncDiff::default();
 
     for (uid, event) in &desired {
-        let start_date = event.start_date.trim();
-        let end_date = event.end_date.trim();
+        let start_date = event.start_date.format("%Y-%m-%d").to_string();
+        let end_date = event.end_date.format("%Y-%m-%d").to_string();
+        let start_date = start_date.as_str();
+        let end_date = end_date.as_str();
         let summary = event.summary.trim();
         let description = event.description.trim();
-        let is_cancelled = event.status == "CANCELLED";
-
-        if start_date.is_empty() || end_date.is_empty() {
-            ignored += 1;
-            continue;
-        }
+        let is_cancelled = event.is_cancelled();
 
         let desired_status = if is_cancelled {
             "cancelled"
@@ -608,13 +832,6 @@ pub async fn sync_listing_ical_reservations(
                     Value::String(integration_id.clone()),
                 );
             }
-            if string_value(existing_obj.get("integration_id")).as_deref() != Some(&integration_id)
-            {
-                patch.insert(
-                    "integration_id".to_string(),
-                    Value::String(integration_id.clone()),
-                );
-            }
             if string_value(existing_obj.get("source")).as_deref() != Some("ical") {
                 patch.insert("source".to_string(), Value::String("ical".to_string()));
             }
@@ -673,10 +890,24 @@ pub async fn sync_listing_ical_reservations(
             if !patch.is_empty() {
                 let row_id = string_value(existing_obj.get("id")).unwrap_or_default();
                 match update_row(pool, "reservations", &row_id, &patch, "id").await {
-                    Ok(_) => updated += 1,
+                    Ok(_) => {
+                        updated += 1;
+                        let entry = json!({
+                            "kind": "reservation",
+                            "uid": uid,
+                            "reservation_id": row_id,
+                            "changes": patch_changes(existing_obj, &patch),
+                        });
+                        if is_cancelled {
+                            diff.removed.push(entry);
+                        } else {
+                            record_overlaps(&mut diff, &occupancy, "reservation", event);
+                            diff.changed.push(entry);
+                        }
+                    }
                     Err(e) => {
                         errors.push(e.detail_message());
-                        conflicts += 1;
+                        diff.conflict("reservation", event, e.detail_message());
                     }
                 }
             }
@@ -693,14 +924,19 @@ pub async fn sync_listing_ical_reservations(
             ("organization_id", Value::String(org_id.clone())),
             ("unit_id", Value::String(unit_id.clone())),
             ("integration_id", Value::String(integration_id.clone())),
-            ("integration_id", Value::String(integration_id.clone())),
             ("external_reservation_id", Value::String(uid.clone())),
             ("status", Value::String("confirmed".to_string())),
             ("source", Value::String("ical".to_string())),
             ("check_in_date", Value::String(start_date.to_string())),
             ("check_out_date", Value::String(end_date.to_string())),
-            ("created_by_user_id", Value::String(user_id.to_string())),
         ]);
+        // Scheduled syncs run as "system", which is not an app user.
+        if uuid::Uuid::parse_str(user_id).is_ok() {
+            payload.insert(
+                "created_by_user_id".to_string(),
+                Value::String(user_id.to_string()),
+            );
+        }
         if !summary.is_empty() {
             payload.insert(
                 "notes".to_string(),
@@ -709,10 +945,21 @@ pub async fn sync_listing_ical_reservations(
         }
 
         match create_row(pool, "reservations", &payload).await {
-            Ok(_) => created += 1,
+            Ok(row) => {
+                created += 1;
+                record_overlaps(&mut diff, &occupancy, "reservation", event);
+                diff.added.push(json!({
+                    "kind": "reservation",
+                    "uid": uid,
+                    "reservation_id": string_value(row.as_object().and_then(|o| o.get("id"))),
+                    "check_in_date": start_date,
+                    "check_out_date": end_date,
+                    "summary": summary,
+                }));
+            }
             Err(e) => {
                 errors.push(e.detail_message());
-                conflicts += 1;
+                diff.conflict("reservation", event, e.detail_message());
             }
         }
     }
@@ -740,33 +987,103 @@ pub async fn sync_listing_ical_reservations(
             continue;
         }
         let row_id = string_value(obj.get("id")).unwrap_or_default();
+        // Events now recognised as blocks are recreated by sync_feed_blocks.
+        let reclassified = desired_blocks.contains_key(ext);
+        let cancel_reason = if reclassified {
+            "Reclassified as iCal calendar block"
+        } else {
+            "Removed from iCal feed"
+        };
         let cancel_patch = json_map(&[
             ("status", Value::String("cancelled".to_string())),
-            (
-                "cancel_reason",
-                Value::String("Removed from iCal feed".to_string()),
-            ),
+            ("cancel_reason", Value::String(cancel_reason.to_string())),
             ("cancelled_at", Value::String(now_iso.clone())),
         ]);
         match update_row(pool, "reservations", &row_id, &cancel_patch, "id").await {
-            Ok(_) => cancelled += 1,
+            Ok(_) => {
+                cancelled += 1;
+                diff.removed.push(json!({
+                    "kind": "reservation",
+                    "uid": ext,
+                    "reservation_id": row_id,
+                    "check_in_date": obj.get("check_in_date").cloned().unwrap_or(Value::Null),
+                    "check_out_date": obj.get("check_out_date").cloned().unwrap_or(Value::Null),
+                    "reason": if reclassified { "reclassified_as_block" } else { "removed_from_feed" },
+                }));
+            }
             Err(e) => {
                 errors.push(e.detail_message());
-                conflicts += 1;
+                diff.conflicts.push(json!({
+                    "kind": "reservation",
+                    "uid": ext,
+                    "reservation_id": row_id,
+                    "error": e.detail_message(),
+                }));
             }
         }
     }
 
+    let blocks = sync_feed_blocks(
+        pool,
+        FeedBlockTarget {
+            org_id: &org_id,
+            unit_id: &unit_id,
+            integration_id: &integration_id,
+            user_id,
+            today,
+            occupancy: &occupancy,
+        },
+        &desired_blocks,
+        &mut diff,
+    )
+    .await?;
+    errors.extend(blocks.errors);
+
+    // Failed writes are retried on the next sync, so only remember the
+    // fingerprint once everything applied cleanly.
+    store_feed_state(
+        pool,
+        &integration_id,
+        fetched.etag,
+        fetched.last_modified,
+        errors.is_empty().then_some(fingerprint),
+    )
+    .await;
+
+    let diff_event_id = if mode == IcalSyncMode::Full || !diff.is_empty() {
+        let context = DiffContext {
+            org_id: &org_id,
+            integration_id: &integration_id,
+            unit_id: &unit_id,
+            user_id,
+            mode,
+            processed_at: &now_iso,
+        };
+        record_sync_diff(pool, context, &diff).await
+    } else {
+        None
+    };
+
     let mut result = serde_json::json!({
         "import_url": ical_url,
+        "not_modified": false,
+        "timezone": unit_tz.name(),
         "events_total": events.len(),
-        "events_used": desired.len(),
+        "events_used": desired.len() + desired_blocks.len(),
+        "events_recurring": events.iter().filter(|event| event.recurring).count(),
         "events_ignored_uid_prefix": ignored_uid_prefix,
         "reservations_created": created,
         "reservations_updated": updated,
         "reservations_cancelled": cancelled,
         "reservations_ignored": ignored,
-        "conflicts": conflicts,
+        "blocks_created": blocks.created,
+        "blocks_updated": blocks.updated,
+        "blocks_removed": blocks.removed,
+        "added": diff.added.len(),
+        "changed": diff.changed.len(),
+        "removed": diff.removed.len(),
+        "conflicts": diff.conflicts.len(),
+        "diff_event_id": diff_event_id,
         "processed_at": now_iso,
     });
 
@@ -778,3 +1095,447 @@ pub async fn sync_listing_ical_reservations(
 
     Ok(result)
 }
+
+struct FeedBlockTarget<'a> {
+    org_id: &'a str,
+    unit_id: &'a str,
+    integration_id: &'a str,
+    user_id: &'a str,
+    today: NaiveDate,
+    occupancy: &'a [Occupancy],
+}
+
+#[derive(Default)]
+struct FeedBlockSync {
+    created: u64,
+    updated: u64,
+    removed: u64,
+    errors: Vec<String>,
+}
+
+/// Mirror the feed's block events into `calendar_blocks` for the integration:
+/// create new ones, move changed ones, and delete blocks whose source event
+/// was cancelled or is no longer in the feed. Past blocks that simply aged
+/// out of the feed are kept as history.
+async fn sync_feed_blocks(
+    pool: &PgPool,
+    target: FeedBlockTarget<'_>,
+    desired: &std::collections::HashMap<String, &ICalEvent>,
+    diff: &mut SyncDiff,
+) -> AppResult<FeedBlockSync> {
+    let existing = list_rows(
+        pool,
+        "calendar_blocks",
+        Some(&json_map(&[
+            ("organization_id", Value::String(target.org_id.to_string())),
+            (
+                "integration_id",
+                Value::String(target.integration_id.to_string()),
+            ),
+        ])),
+        5000,
+        0,
+        "starts_on",
+        true,
+    )
+    .await?;
+
+    let mut sync = FeedBlockSync::default();
+    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let today = target.today.format("%Y-%m-%d").to_string();
+
+    for row in &existing {
+        let Some(obj) = row.as_object() else {
+            continue;
+        };
+        let row_id = string_value(obj.get("id")).unwrap_or_default();
+        let Some(external_id) = string_value(obj.get("external_event_id")) else {
+            continue;
+        };
+        let ends_on = string_value(obj.get("ends_on")).unwrap_or_default();
+
+        let event = desired
+            .get(&external_id)
+            .filter(|event| !event.is_cancelled());
+        let Some(event) = event else {
+            let cancelled = desired.contains_key(&external_id);
+            if !cancelled && ends_on.as_str() <= today.as_str() {
+                continue;
+            }
+            match delete_row(pool, "calendar_blocks", &row_id, "id").await {
+                Ok(_) => {
+                    sync.removed += 1;
+                    diff.removed.push(json!({
+                        "kind": "block",
+                        "uid": external_id,
+                        "block_id": row_id,
+                        "starts_on": obj.get("starts_on").cloned().unwrap_or(Value::Null),
+                        "ends_on": ends_on,
+                        "reason": if cancelled { "cancelled_in_feed" } else { "removed_from_feed" },
+                    }));
+                }
+                Err(e) => {
+                    sync.errors.push(e.detail_message());
+                    diff.conflicts.push(json!({
+                        "kind": "block",
+                        "uid": external_id,
+                        "block_id": row_id,
+                        "error": e.detail_message(),
+                    }));
+                }
+            }
+            continue;
+        };
+        seen.insert(external_id.clone());
+
+        let starts_on = event.start_date.format("%Y-%m-%d").to_string();
+        let ends_on_desired = event.end_date.format("%Y-%m-%d").to_string();
+        let reason = block_reason(event);
+        let mut patch = Map::new();
+        if string_value(obj.get("starts_on")).as_deref() != Some(starts_on.as_str()) {
+            patch.insert("starts_on".to_string(), Value::String(starts_on));
+        }
+        if ends_on != ends_on_desired {
+            patch.insert("ends_on".to_string(), Value::String(ends_on_desired));
+        }
+        if string_value(obj.get("reason")).as_deref() != Some(reason.as_str()) {
+            patch.insert("reason".to_string(), Value::String(reason));
+        }
+        if patch.is_empty() {
+            continue;
+        }
+        match update_row(pool, "calendar_blocks", &row_id, &patch, "id").await {
+            Ok(_) => {
+                sync.updated += 1;
+                record_overlaps(diff, target.occupancy, "block", event);
+                diff.changed.push(json!({
+                    "kind": "block",
+                    "uid": external_id,
+                    "block_id": row_id,
+                    "changes": patch_changes(obj, &patch),
+                }));
+            }
+            Err(e) => {
+                sync.errors.push(e.detail_message());
+                diff.conflict("block", event, e.detail_message());
+            }
+        }
+    }
+
+    for (uid, event) in desired {
+        if event.is_cancelled() || seen.contains(uid) {
+            continue;
+        }
+        let starts_on = event.start_date.format("%Y-%m-%d").to_string();
+        let ends_on = event.end_date.format("%Y-%m-%d").to_string();
+        let mut payload = json_map(&[
+            ("organization_id", Value::String(target.org_id.to_string())),
+            ("unit_id", Value::String(target.unit_id.to_string())),
+            (
+                "integration_id",
+                Value::String(target.integration_id.to_string()),
+            ),
+            ("external_event_id", Value::String(uid.clone())),
+            ("source", Value::String("ical".to_string())),
+            ("starts_on", Value::String(starts_on.clone())),
+            ("ends_on", Value::String(ends_on.clone())),
+            ("reason", Value::String(block_reason(event))),
+        ]);
+        if uuid::Uuid::parse_str(target.user_id).is_ok() {
+            payload.insert(
+                "created_by_user_id".to_string(),
+                Value::String(target.user_id.to_string()),
+            );
+        }
+        match create_row(pool, "calendar_blocks", &payload).await {
+            Ok(row) => {
+                sync.created += 1;
+                record_overlaps(diff, target.occupancy, "block", event);
+                diff.added.push(json!({
+                    "kind": "block",
+                    "uid": uid,
+                    "block_id": string_value(row.as_object().and_then(|o| o.get("id"))),
+                    "starts_on": starts_on,
+                    "ends_on": ends_on,
+                    "summary": event.summary,
+                }));
+            }
+            Err(e) => {
+                sync.errors.push(e.detail_message());
+                diff.conflict("block", event, e.detail_message());
+            }
+        }
+    }
+
+    Ok(sync)
+}
+
+fn block_reason(event: &ICalEvent) -> String {
+    let summary = event.summary.trim();
+    if summary.is_empty() {
+        "Blocked (iCal)".to_string()
+    } else {
+        format!("iCal: {summary}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use axum::{
+        extract::State,
+        http::{header, HeaderMap, StatusCode},
+        response::{IntoResponse, Response},
+        routing::get,
+        Router,
+    };
+
+    use super::*;
+    use crate::{
+        repository::table_service::get_row,
+        test_support::{create_org, create_property, create_unit, create_user, test_pool},
+    };
+
+    const LAST_MODIFIED: &str = "Wed, 14 Oct 2026 10:00:00 GMT";
+
+    struct Feed {
+        etag: String,
+        body: String,
+    }
+
+    async fn serve_feed(State(feed): State<Arc<Mutex<Feed>>>, headers: HeaderMap) -> Response {
+        let feed = feed.lock().expect("feed lock");
+        let header = |name: header::HeaderName| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToOwned::to_owned)
+        };
+        // If-Modified-Since is ignored when If-None-Match is sent (RFC 9110).
+        let not_modified = match header(header::IF_NONE_MATCH) {
+            Some(etag) => etag == feed.etag,
+            None => header(header::IF_MODIFIED_SINCE).as_deref() == Some(LAST_MODIFIED),
+        };
+        if not_modified {
+            return StatusCode::NOT_MODIFIED.into_response();
+        }
+        (
+            [
+                (header::ETAG, feed.etag.clone()),
+                (header::LAST_MODIFIED, LAST_MODIFIED.to_string()),
+            ],
+            feed.body.clone(),
+        )
+            .into_response()
+    }
+
+    async fn feed_server(feed: Arc<Mutex<Feed>>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind feed server");
+        let addr = listener.local_addr().expect("feed server address");
+        let app = Router::new()
+            .route("/feed.ics", get(serve_feed))
+            .with_state(feed);
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        format!("http://{addr}/feed.ics")
+    }
+
+    fn calendar(events: &[(&str, NaiveDate, NaiveDate, &str)], stamp: &str) -> String {
+        let mut ics = String::from("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n");
+        for (uid, start, end, summary) in events {
+            ics.push_str(&format!(
+                "BEGIN:VEVENT\r\nUID:{uid}\r\nDTSTAMP:{stamp}\r\nDTSTART;VALUE=DATE:{}\r\nDTEND;VALUE=DATE:{}\r\nSUMMARY:{summary}\r\nEND:VEVENT\r\n",
+                start.format("%Y%m%d"),
+                end.format("%Y%m%d"),
+            ));
+        }
+        ics.push_str("END:VCALENDAR\r\n");
+        ics
+    }
+
+    fn day(offset: i64) -> NaiveDate {
+        Utc::now().date_naive() + Duration::days(offset)
+    }
+
+    #[tokio::test]
+    async fn conditional_fetch_returns_none_when_not_modified() {
+        let feed = Arc::new(Mutex::new(Feed {
+            etag: "\"v1\"".to_string(),
+            body: calendar(&[], "20261014T100000Z"),
+        }));
+        let url = feed_server(feed).await;
+        let client = Client::new();
+
+        let fetched = fetch_ical_feed(&client, &url, None, None)
+            .await
+            .unwrap()
+            .expect("first fetch returns the feed");
+        assert_eq!(fetched.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(fetched.last_modified.as_deref(), Some(LAST_MODIFIED));
+        assert!(fetched.body.starts_with("BEGIN:VCALENDAR"));
+
+        let by_etag = fetch_ical_feed(&client, &url, Some("\"v1\""), None).await;
+        assert!(by_etag.unwrap().is_none());
+        let by_date = fetch_ical_feed(&client, &url, None, Some(LAST_MODIFIED)).await;
+        assert!(by_date.unwrap().is_none());
+        let stale = fetch_ical_feed(&client, &url, Some("\"v0\""), None).await;
+        assert!(stale.unwrap().is_some());
+    }
+
+    #[test]
+    fn fingerprint_ignores_event_order_and_dtstamp() {
+        let a = ("a", day(10), day(12), "Reserved");
+        let b = ("b", day(20), day(21), "Not available");
+        let fingerprint = |events: &[(&str, NaiveDate, NaiveDate, &str)], stamp: &str| {
+            events_fingerprint(&parse_ical_events(
+                &calendar(events, stamp),
+                chrono_tz::UTC,
+                day(0),
+            ))
+        };
+        let base = fingerprint(&[a, b], "20261014T100000Z");
+        assert_eq!(base, fingerprint(&[b, a], "20261015T080000Z"));
+        let moved = ("a", day(11), day(12), "Reserved");
+        assert_ne!(base, fingerprint(&[moved, b], "20261014T100000Z"));
+    }
+
+    #[test]
+    fn patch_changes_pairs_old_and_new_values() {
+        let existing = json_map(&[
+            ("check_in_date", json!("2026-11-01")),
+            ("status", json!("confirmed")),
+        ]);
+        let patch = json_map(&[
+            ("check_in_date", json!("2026-11-02")),
+            ("notes", json!("iCal: Reserved")),
+        ]);
+        assert_eq!(
+            patch_changes(&existing, &patch),
+            json!({
+                "check_in_date": { "from": "2026-11-01", "to": "2026-11-02" },
+                "notes": { "from": null, "to": "iCal: Reserved" },
+            })
+        );
+    }
+
+    #[test]
+    fn stored_diff_lists_are_capped() {
+        let entries = (0..MAX_DIFF_ENTRIES + 5)
+            .map(|index| json!({ "uid": index }))
+            .collect::<Vec<_>>();
+        let stored = capped(&entries);
+        assert_eq!(stored.as_array().map(Vec::len), Some(MAX_DIFF_ENTRIES));
+        assert_eq!(stored[0], json!({ "uid": 0 }));
+    }
+
+    #[tokio::test]
+    async fn incremental_sync_skips_unchanged_feeds_and_records_diffs() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let user_id = create_user(&pool).await;
+        let org_id = create_org(&pool, &user_id).await;
+        let property_id = create_property(&pool, &org_id).await;
+        let unit_id = create_unit(&pool, &org_id, &property_id).await;
+
+        let stay = ("stay-1", day(10), day(13), "Reserved");
+        let block = ("block-1", day(20), day(22), "Not available");
+        let feed = Arc::new(Mutex::new(Feed {
+            etag: "\"v1\"".to_string(),
+            body: calendar(&[stay, block], "20261014T100000Z"),
+        }));
+        let url = feed_server(Arc::clone(&feed)).await;
+        let integration_id = sqlx::query_scalar::<_, String>(
+            "INSERT INTO integrations (organization_id, unit_id, kind, channel_name, public_name, ical_import_url)
+             VALUES ($1::uuid, $2::uuid, 'airbnb', 'Airbnb', 'Test listing', $3)
+             RETURNING id::text",
+        )
+        .bind(&org_id)
+        .bind(&unit_id)
+        .bind(&url)
+        .fetch_one(&pool)
+        .await
+        .expect("insert integration");
+
+        let client = Client::new();
+        let sync = |mode| {
+            let pool = pool.clone();
+            let client = client.clone();
+            let integration_id = integration_id.clone();
+            let user_id = user_id.clone();
+            async move {
+                let listing = get_row(&pool, "integrations", &integration_id, "id")
+                    .await
+                    .expect("load integration");
+                sync_listing_ical_reservations(&pool, &client, &listing, &user_id, mode)
+                    .await
+                    .expect("sync succeeds")
+            }
+        };
+
+        let first = sync(IcalSyncMode::Incremental).await;
+        assert_eq!(first["not_modified"], false);
+        assert_eq!(first["reservations_created"], 1);
+        assert_eq!(first["blocks_created"], 1);
+        assert_eq!(first["added"], 2);
+        assert!(first["diff_event_id"].is_string());
+
+        // The stored ETag gets a 304 from the feed.
+        let unchanged = sync(IcalSyncMode::Incremental).await;
+        assert_eq!(unchanged["not_modified"], true);
+        assert!(unchanged.get("events_total").is_none());
+
+        // A new ETag with the same events is caught by the content hash.
+        {
+            let mut feed = feed.lock().expect("feed lock");
+            feed.etag = "\"v2\"".to_string();
+            feed.body = calendar(&[block, stay], "20261015T080000Z");
+        }
+        let same_events = sync(IcalSyncMode::Incremental).await;
+        assert_eq!(same_events["not_modified"], true);
+        assert_eq!(same_events["events_total"], 2);
+
+        // The stay moves by a day and the block is dropped from the feed.
+        let moved = ("stay-1", day(11), day(14), "Reserved");
+        {
+            let mut feed = feed.lock().expect("feed lock");
+            feed.etag = "\"v3\"".to_string();
+            feed.body = calendar(&[moved], "20261016T080000Z");
+        }
+        let changed = sync(IcalSyncMode::Incremental).await;
+        assert_eq!(changed["not_modified"], false);
+        assert_eq!(changed["reservations_updated"], 1);
+        assert_eq!(changed["blocks_removed"], 1);
+        assert_eq!(changed["added"], 0);
+        assert_eq!(changed["changed"], 1);
+        assert_eq!(changed["removed"], 1);
+
+        let diff_id = changed["diff_event_id"].as_str().expect("diff recorded");
+        let payload = sqlx::query_scalar::<_, Value>(
+            "SELECT payload FROM integration_events WHERE id = $1::uuid",
+        )
+        .bind(diff_id)
+        .fetch_one(&pool)
+        .await
+        .expect("load diff event");
+        assert_eq!(payload["mode"], "incremental");
+        assert_eq!(payload["trigger"], "manual");
+        assert_eq!(
+            payload["counts"],
+            json!({ "added": 0, "changed": 1, "removed": 1, "conflicts": 0 })
+        );
+        let changes = &payload["changed"][0]["changes"];
+        assert_eq!(changes["check_in_date"]["to"], day(11).to_string());
+        assert_eq!(changes["check_out_date"]["to"], day(14).to_string());
+        assert_eq!(payload["removed"][0]["kind"], "block");
+        assert_eq!(payload["removed"][0]["reason"], "removed_from_feed");
+
+        // Full syncs always record a diff, even when nothing changed.
+        let full = sync(IcalSyncMode::Full).await;
+        assert_eq!(full["not_modified"], false);
+        assert_eq!(full["changed"], 0);
+        assert!(full["diff_event_id"].is_string());
+    }
+}
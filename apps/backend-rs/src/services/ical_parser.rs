@@ -0,0 +1,1293 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+    Weekday,
+};
+use chrono_tz::Tz;
+use sha2::{Digest, Sha256};
+
+/// Upper bound on occurrences generated for a single recurring event.
+const MAX_OCCURRENCES: usize = 2000;
+/// Upper bound on recurrence periods walked per rule, so a rule whose BYxxx
+/// parts never match (e.g. `FREQ=MONTHLY;BYMONTHDAY=31;BYMONTH=2`) terminates.
+const MAX_PERIODS: u32 = 50_000;
+
+/// A single calendar occurrence resolved to the unit's local dates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ICalEvent {
+    /// Source UID. Occurrences of recurring events get `"{uid}#{YYYYMMDD}"`
+    /// (the occurrence's original local start date) so they stay stable
+    /// across syncs and match `RECURRENCE-ID` overrides.
+    pub uid: String,
+    pub start_date: NaiveDate,
+    /// Exclusive end date (checkout day / day after the last blocked night).
+    pub end_date: NaiveDate,
+    pub summary: String,
+    pub description: String,
+    pub status: String,
+    pub recurring: bool,
+}
+
+impl ICalEvent {
+    pub fn is_cancelled(&self) -> bool {
+        self.status == "CANCELLED"
+    }
+}
+
+/// Dates for which recurring events are expanded. Single events are always
+/// returned regardless of the window.
+#[derive(Debug, Clone, Copy)]
+pub struct ExpansionWindow {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+/// Parse an iCalendar feed and return every VEVENT occurrence as unit-local
+/// dates.
+///
+/// `DTSTART`/`DTEND` values in UTC or with a `TZID` are converted to
+/// `unit_tz` before taking the date; TZIDs resolve against the IANA database
+/// first and the feed's own VTIMEZONE definitions second. Floating times and
+/// all-day dates are used as written.
+pub fn parse_calendar(ics_text: &str, unit_tz: Tz, window: ExpansionWindow) -> Vec<ICalEvent> {
+    let roots = parse_components(ics_text);
+    let mut vevents = Vec::new();
+    let mut vtimezones = Vec::new();
+    for root in &roots {
+        collect_components(root, &mut vevents, &mut vtimezones);
+    }
+    let zones = TimezoneRegistry::from_components(&vtimezones);
+
+    // RECURRENCE-ID overrides replace the matching occurrence of their master.
+    let mut overridden: HashMap<String, HashSet<String>> = HashMap::new();
+    for event in &vevents {
+        let Some(recurrence_id) = event.date_time("RECURRENCE-ID") else {
+            continue;
+        };
+        let uid = event.text("UID");
+        if uid.is_empty() {
+            continue;
+        }
+        overridden
+            .entry(uid)
+            .or_default()
+            .insert(occurrence_key(&recurrence_id.local()));
+    }
+
+    let mut parsed = Vec::new();
+    for event in &vevents {
+        let Some(start) = event.date_time("DTSTART") else {
+            continue;
+        };
+        let end = event_end(event, &start);
+        let summary = event.text("SUMMARY");
+        let description = event.text("DESCRIPTION");
+        let status = event.text("STATUS").to_uppercase();
+        let uid = event.text("UID");
+
+        if let Some(recurrence_id) = event.date_time("RECURRENCE-ID") {
+            let Some((start_date, end_date)) = resolve_dates(&zones, unit_tz, &start, &end) else {
+                continue;
+            };
+            let base = if uid.is_empty() {
+                fallback_uid(start_date, end_date, &summary, &description)
+            } else {
+                uid
+            };
+            parsed.push(ICalEvent {
+                uid: format!("{base}#{}", occurrence_key(&recurrence_id.local())),
+                start_date,
+                end_date,
+                summary,
+                description,
+                status,
+                recurring: true,
+            });
+            continue;
+        }
+
+        let rule = event.property("RRULE").and_then(|p| parse_rrule(&p.value));
+        let rdates = event.date_times("RDATE");
+        if rule.is_none() && rdates.is_empty() {
+            let Some((start_date, end_date)) = resolve_dates(&zones, unit_tz, &start, &end) else {
+                continue;
+            };
+            let uid = if uid.is_empty() {
+                fallback_uid(start_date, end_date, &summary, &description)
+            } else {
+                uid
+            };
+            parsed.push(ICalEvent {
+                uid,
+                start_date,
+                end_date,
+                summary,
+                description,
+                status,
+                recurring: false,
+            });
+            continue;
+        }
+
+        let length = end.local() - start.local();
+        let limit = (window.end + Duration::days(1)).and_time(NaiveTime::MIN);
+        // A day of slack on each side covers the shift from the event's frame
+        // to unit-local dates; the loop below applies the exact window.
+        let from = (window.start - Duration::days(1)).and_time(NaiveTime::MIN) - length;
+        let mut starts = match &rule {
+            Some(rule) => {
+                let until = rule
+                    .until
+                    .as_ref()
+                    .map(|until| until_in_event_frame(&zones, until, &start));
+                expand_rule(rule, start.local(), until, from, limit)
+            }
+            None => vec![start.local()],
+        };
+        for rdate in &rdates {
+            let local = rdate_in_event_frame(&zones, rdate, &start);
+            if local >= from && local <= limit {
+                starts.push(local);
+            }
+        }
+        starts.sort();
+        starts.dedup();
+
+        let excluded = event
+            .date_times("EXDATE")
+            .into_iter()
+            .map(|exdate| occurrence_key(&rdate_in_event_frame(&zones, &exdate, &start)))
+            .collect::<HashSet<_>>();
+        let overrides = overridden.get(&uid);
+        let base = if uid.is_empty() {
+            let first = resolve_dates(&zones, unit_tz, &start, &end);
+            let (start_date, end_date) =
+                first.unwrap_or((start.local().date(), end.local().date()));
+            fallback_uid(start_date, end_date, &summary, &description)
+        } else {
+            uid.clone()
+        };
+
+        for occurrence in starts.into_iter().take(MAX_OCCURRENCES) {
+            let key = occurrence_key(&occurrence);
+            if excluded.contains(&key) || overrides.is_some_and(|keys| keys.contains(&key)) {
+                continue;
+            }
+            let occurrence_start = start.with_local(occurrence);
+            let occurrence_end = end.with_local(occurrence + length);
+            let Some((start_date, end_date)) =
+                resolve_dates(&zones, unit_tz, &occurrence_start, &occurrence_end)
+            else {
+                continue;
+            };
+            if end_date < window.start || start_date > window.end {
+                continue;
+            }
+            parsed.push(ICalEvent {
+                uid: format!("{base}#{key}"),
+                start_date,
+                end_date,
+                summary: summary.clone(),
+                description: description.clone(),
+                status: status.clone(),
+                recurring: true,
+            });
+        }
+    }
+
+    parsed
+}
+
+fn collect_components<'a>(
+    component: &'a Component,
+    vevents: &mut Vec<&'a Component>,
+    vtimezones: &mut Vec<&'a Component>,
+) {
+    match component.name.as_str() {
+        "VEVENT" => vevents.push(component),
+        "VTIMEZONE" => vtimezones.push(component),
+        _ => {}
+    }
+    for child in &component.children {
+        collect_components(child, vevents, vtimezones);
+    }
+}
+
+fn event_end(event: &Component, start: &IcalDateTime) -> IcalDateTime {
+    if let Some(end) = event.date_time("DTEND") {
+        return end;
+    }
+    if let Some(duration) = event
+        .property("DURATION")
+        .and_then(|p| parse_duration(&p.value))
+    {
+        return start.with_local(start.local() + duration);
+    }
+    match start {
+        IcalDateTime::Date(date) => IcalDateTime::Date(*date + Duration::days(1)),
+        other => other.clone(),
+    }
+}
+
+/// Convert an event's start/end to unit-local dates. Timed events that end on
+/// the day they start still occupy that night; all-day events must end after
+/// they start.
+fn resolve_dates(
+    zones: &TimezoneRegistry,
+    unit_tz: Tz,
+    start: &IcalDateTime,
+    end: &IcalDateTime,
+) -> Option<(NaiveDate, NaiveDate)> {
+    let start_date = zones.unit_date(start, unit_tz);
+    let end_date = zones.unit_date(end, unit_tz);
+    if end_date > start_date {
+        return Some((start_date, end_date));
+    }
+    match (start, end) {
+        (IcalDateTime::Date(_), _) | (_, IcalDateTime::Date(_)) => None,
+        _ if end.local() < start.local() => None,
+        _ => Some((start_date, start_date + Duration::days(1))),
+    }
+}
+
+fn occurrence_key(local: &NaiveDateTime) -> String {
+    local.format("%Y%m%d").to_string()
+}
+
+fn fallback_uid(start: NaiveDate, end: NaiveDate, summary: &str, description: &str) -> String {
+    let stable = format!(
+        "{}|{}|{}|{}",
+        start.format("%Y-%m-%d"),
+        end.format("%Y-%m-%d"),
+        summary,
+        description
+    );
+    let mut hasher = Sha256::new();
+    hasher.update(stable.as_bytes());
+    format!("ical-{:x}", hasher.finalize())
+}
+
+/// Express UNTIL in the wall-clock frame of DTSTART. An all-day UNTIL covers
+/// the whole day.
+fn until_in_event_frame(
+    zones: &TimezoneRegistry,
+    until: &IcalDateTime,
+    start: &IcalDateTime,
+) -> NaiveDateTime {
+    match until {
+        IcalDateTime::Date(date) => {
+            date.and_time(NaiveTime::MIN) + Duration::days(1) - Duration::seconds(1)
+        }
+        _ => rdate_in_event_frame(zones, until, start),
+    }
+}
+
+/// Express an EXDATE/RDATE/UNTIL value in the wall-clock frame of DTSTART so
+/// it can be compared with generated occurrences.
+fn rdate_in_event_frame(
+    zones: &TimezoneRegistry,
+    value: &IcalDateTime,
+    start: &IcalDateTime,
+) -> NaiveDateTime {
+    match value {
+        IcalDateTime::Date(date) => date.and_time(start.local().time()),
+        IcalDateTime::Floating(local) => *local,
+        _ => {
+            let Some(instant) = zones.to_utc(value) else {
+                return value.local();
+            };
+            zones
+                .wall_clock_in(&instant, start)
+                .unwrap_or_else(|| value.local())
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Content lines and components
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Default)]
+struct Property {
+    name: String,
+    params: HashMap<String, String>,
+    value: String,
+}
+
+impl Property {
+    fn param(&self, key: &str) -> Option<&str> {
+        self.params.get(key).map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Component {
+    name: String,
+    properties: Vec<Property>,
+    children: Vec<Component>,
+}
+
+impl Component {
+    fn property(&self, name: &str) -> Option<&Property> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+
+    fn text(&self, name: &str) -> String {
+        self.property(name)
+            .map(|p| unescape_text(p.value.trim()))
+            .unwrap_or_default()
+    }
+
+    fn date_time(&self, name: &str) -> Option<IcalDateTime> {
+        self.property(name)
+            .and_then(|p| parse_date_time(&p.value, &p.params))
+    }
+
+    /// All values of a (possibly repeated, comma-separated) date list property.
+    fn date_times(&self, name: &str) -> Vec<IcalDateTime> {
+        self.properties
+            .iter()
+            .filter(|p| p.name == name)
+            .filter(|p| {
+                p.param("VALUE")
+                    .is_none_or(|kind| !kind.eq_ignore_ascii_case("PERIOD"))
+            })
+            .flat_map(|p| {
+                p.value
+                    .split(',')
+                    .filter_map(|raw| parse_date_time(raw, &p.params))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+fn unfold_lines(text: &str) -> Vec<String> {
+    let mut unfolded: Vec<String> = Vec::new();
+    for raw in text.split('\n') {
+        let line = raw.trim_end_matches('\r');
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with([' ', '\t']) {
+            if let Some(last) = unfolded.last_mut() {
+                last.push_str(&line[1..]);
+                continue;
+            }
+        }
+        unfolded.push(line.to_string());
+    }
+    unfolded
+}
+
+/// Split `NAME;PARAM=value;PARAM="quoted;value":VALUE`, honouring quotes.
+fn parse_content_line(line: &str) -> Option<Property> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut value_start = None;
+
+    for (index, ch) in line.char_indices() {
+        match ch {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(ch);
+            }
+            ';' if !in_quotes => segments.push(std::mem::take(&mut current)),
+            ':' if !in_quotes => {
+                segments.push(std::mem::take(&mut current));
+                value_start = Some(index + 1);
+                break;
+            }
+            _ => current.push(ch),
+        }
+    }
+
+    let value = line[value_start?..].trim().to_string();
+    let name = segments.first()?.trim().to_uppercase();
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut params = HashMap::new();
+    for raw in &segments[1..] {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            continue;
+        }
+        match raw.split_once('=') {
+            Some((key, value)) => params.insert(
+                key.trim().to_uppercase(),
+                value.trim().trim_matches('"').to_string(),
+            ),
+            None => params.insert(raw.to_uppercase(), "TRUE".to_string()),
+        };
+    }
+
+    Some(Property {
+        name,
+        params,
+        value,
+    })
+}
+
+fn parse_components(text: &str) -> Vec<Component> {
+    let mut roots = Vec::new();
+    let mut stack: Vec<Component> = Vec::new();
+
+    fn close(stack: &mut Vec<Component>, roots: &mut Vec<Component>) {
+        if let Some(component) = stack.pop() {
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(component),
+                None => roots.push(component),
+            }
+        }
+    }
+
+    for line in unfold_lines(text) {
+        let Some(property) = parse_content_line(&line) else {
+            continue;
+        };
+        match property.name.as_str() {
+            "BEGIN" => stack.push(Component {
+                name: property.value.to_uppercase(),
+                ..Component::default()
+            }),
+            "END" => close(&mut stack, &mut roots),
+            _ => {
+                if let Some(current) = stack.last_mut() {
+                    current.properties.push(property);
+                }
+            }
+        }
+    }
+    while !stack.is_empty() {
+        close(&mut stack, &mut roots);
+    }
+    roots
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Date-times and timezones
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum IcalDateTime {
+    Date(NaiveDate),
+    Floating(NaiveDateTime),
+    Utc(NaiveDateTime),
+    Zoned(NaiveDateTime, String),
+}
+
+impl IcalDateTime {
+    /// Wall-clock value as written in the feed.
+    fn local(&self) -> NaiveDateTime {
+        match self {
+            Self::Date(date) => date.and_time(NaiveTime::MIN),
+            Self::Floating(local) | Self::Utc(local) | Self::Zoned(local, _) => *local,
+        }
+    }
+
+    /// Same kind and zone, different wall-clock value.
+    fn with_local(&self, local: NaiveDateTime) -> Self {
+        match self {
+            Self::Date(_) => Self::Date(local.date()),
+            Self::Floating(_) => Self::Floating(local),
+            Self::Utc(_) => Self::Utc(local),
+            Self::Zoned(_, tzid) => Self::Zoned(local, tzid.clone()),
+        }
+    }
+}
+
+fn parse_date_time(value: &str, params: &HashMap<String, String>) -> Option<IcalDateTime> {
+    let value = value.trim();
+    let is_date = params
+        .get("VALUE")
+        .is_some_and(|kind| kind.eq_ignore_ascii_case("DATE"));
+    if is_date || value.len() == 8 {
+        return NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d")
+            .ok()
+            .map(IcalDateTime::Date);
+    }
+
+    let (raw, is_utc) = match value.strip_suffix(['Z', 'z']) {
+        Some(stripped) => (stripped, true),
+        None => (value, false),
+    };
+    let parsed = NaiveDateTime::parse_from_str(raw, "%Y%m%dT%H%M%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(raw, "%Y%m%dT%H%M"));
+    let Ok(local) = parsed else {
+        // Tolerate malformed times as long as the date part is readable.
+        return NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d")
+            .ok()
+            .map(IcalDateTime::Date);
+    };
+
+    if is_utc {
+        return Some(IcalDateTime::Utc(local));
+    }
+    match params.get("TZID").map(|tzid| tzid.trim()) {
+        Some(tzid) if !tzid.is_empty() => Some(IcalDateTime::Zoned(local, tzid.to_string())),
+        _ => Some(IcalDateTime::Floating(local)),
+    }
+}
+
+/// `[+-]P[nW][nD][T[nH][nM][nS]]`
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (negative, rest) = match value.as_bytes().first()? {
+        b'-' => (true, &value[1..]),
+        b'+' => (false, &value[1..]),
+        _ => (false, value),
+    };
+    let rest = rest.strip_prefix(['P', 'p'])?;
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    let mut seen_unit = false;
+    for ch in rest.chars() {
+        match ch.to_ascii_uppercase() {
+            'T' => in_time = true,
+            digit if digit.is_ascii_digit() => number.push(digit),
+            unit => {
+                let amount: i64 = number.parse().ok()?;
+                number.clear();
+                total += match (unit, in_time) {
+                    ('W', false) => Duration::weeks(amount),
+                    ('D', false) => Duration::days(amount),
+                    ('H', true) => Duration::hours(amount),
+                    ('M', true) => Duration::minutes(amount),
+                    ('S', true) => Duration::seconds(amount),
+                    _ => return None,
+                };
+                seen_unit = true;
+            }
+        }
+    }
+    if !seen_unit || !number.is_empty() {
+        return None;
+    }
+    Some(if negative { -total } else { total })
+}
+
+/// Resolve a local wall-clock time in `tz`. Ambiguous (fall-back) times take
+/// the earlier instant; times skipped by a spring-forward gap move to the
+/// first valid instant after it.
+fn localize(tz: Tz, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+    let mut candidate = local;
+    for _ in 0..=4 {
+        match tz.from_local_datetime(&candidate) {
+            LocalResult::Single(value) => return Some(value.with_timezone(&Utc)),
+            LocalResult::Ambiguous(earliest, _) => return Some(earliest.with_timezone(&Utc)),
+            LocalResult::None => candidate += Duration::minutes(30),
+        }
+    }
+    None
+}
+
+/// Look up an IANA zone, tolerating prefixed forms such as
+/// `/mozilla.org/20070129_1/America/New_York`.
+fn lookup_iana(tzid: &str) -> Option<Tz> {
+    let mut candidate = tzid.trim().trim_start_matches('/');
+    loop {
+        if let Ok(tz) = candidate.parse::<Tz>() {
+            return Some(tz);
+        }
+        let (_, rest) = candidate.split_once('/')?;
+        candidate = rest;
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Observance {
+    onset: NaiveDateTime,
+    offset_from: i32,
+    offset_to: i32,
+    rule: Option<RRule>,
+    rdates: Vec<NaiveDateTime>,
+}
+
+impl Observance {
+    fn latest_onset_at_or_before(&self, local: NaiveDateTime) -> Option<NaiveDateTime> {
+        if self.onset > local {
+            return None;
+        }
+        let mut latest = self.onset;
+        if let Some(rule) = &self.rule {
+            let until = rule.until.as_ref().map(IcalDateTime::local);
+            if let Some(last) = expand_rule(rule, self.onset, until, self.onset, local).last() {
+                latest = latest.max(*last);
+            }
+        }
+        for rdate in &self.rdates {
+            if *rdate <= local {
+                latest = latest.max(*rdate);
+            }
+        }
+        Some(latest)
+    }
+}
+
+/// A VTIMEZONE definition from the feed: offsets switch at each observance
+/// onset (STANDARD/DAYLIGHT DTSTART plus its RRULE/RDATEs).
+#[derive(Debug, Clone, Default)]
+struct VTimezone {
+    observances: Vec<Observance>,
+}
+
+impl VTimezone {
+    fn from_component(component: &Component) -> Self {
+        let observances = component
+            .children
+            .iter()
+            .filter(|child| child.name == "STANDARD" || child.name == "DAYLIGHT")
+            .filter_map(|child| {
+                let onset = child.date_time("DTSTART")?.local();
+                let offset_to = parse_utc_offset(&child.property("TZOFFSETTO")?.value)?;
+                let offset_from = child
+                    .property("TZOFFSETFROM")
+                    .and_then(|p| parse_utc_offset(&p.value))
+                    .unwrap_or(offset_to);
+                Some(Observance {
+                    onset,
+                    offset_from,
+                    offset_to,
+                    rule: child.property("RRULE").and_then(|p| parse_rrule(&p.value)),
+                    rdates: child
+                        .date_times("RDATE")
+                        .iter()
+                        .map(IcalDateTime::local)
+                        .collect(),
+                })
+            })
+            .collect();
+        Self { observances }
+    }
+
+    fn offset_at(&self, local: NaiveDateTime) -> Option<i32> {
+        let mut best: Option<(NaiveDateTime, i32)> = None;
+        for observance in &self.observances {
+            let Some(onset) = observance.latest_onset_at_or_before(local) else {
+                continue;
+            };
+            if best.is_none_or(|(current, _)| onset > current) {
+                best = Some((onset, observance.offset_to));
+            }
+        }
+        best.map(|(_, offset)| offset).or_else(|| {
+            self.observances
+                .iter()
+                .min_by_key(|observance| observance.onset)
+                .map(|observance| observance.offset_from)
+        })
+    }
+}
+
+fn parse_utc_offset(value: &str) -> Option<i32> {
+    let value = value.trim();
+    let sign = match value.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let digits = &value[1..];
+    if !(digits.len() == 4 || digits.len() == 6) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[0..2].parse().ok()?;
+    let minutes: i32 = digits[2..4].parse().ok()?;
+    let seconds: i32 = digits.get(4..6).map_or(Ok(0), str::parse).ok()?;
+    Some(sign * (hours * 3600 + minutes * 60 + seconds))
+}
+
+#[derive(Debug, Default)]
+struct TimezoneRegistry {
+    definitions: HashMap<String, VTimezone>,
+}
+
+impl TimezoneRegistry {
+    fn from_components(components: &[&Component]) -> Self {
+        let definitions = components
+            .iter()
+            .filter_map(|component| {
+                let tzid = component.text("TZID");
+                (!tzid.is_empty()).then(|| (tzid, VTimezone::from_component(component)))
+            })
+            .collect();
+        Self { definitions }
+    }
+
+    /// Absolute instant for a value, or `None` for floating times and dates.
+    fn to_utc(&self, value: &IcalDateTime) -> Option<DateTime<Utc>> {
+        match value {
+            IcalDateTime::Date(_) | IcalDateTime::Floating(_) => None,
+            IcalDateTime::Utc(local) => Some(Utc.from_utc_datetime(local)),
+            IcalDateTime::Zoned(local, tzid) => {
+                if let Some(tz) = lookup_iana(tzid) {
+                    return localize(tz, *local);
+                }
+                let offset = self.definitions.get(tzid)?.offset_at(*local)?;
+                Some(Utc.from_utc_datetime(&(*local - Duration::seconds(offset.into()))))
+            }
+        }
+    }
+
+    /// Wall-clock value of `instant` in the zone of `frame`.
+    fn wall_clock_in(
+        &self,
+        instant: &DateTime<Utc>,
+        frame: &IcalDateTime,
+    ) -> Option<NaiveDateTime> {
+        match frame {
+            IcalDateTime::Utc(_) => Some(instant.naive_utc()),
+            IcalDateTime::Zoned(_, tzid) => {
+                if let Some(tz) = lookup_iana(tzid) {
+                    return Some(instant.with_timezone(&tz).naive_local());
+                }
+                let zone = self.definitions.get(tzid)?;
+                let guess = instant.naive_utc();
+                let offset = zone.offset_at(guess)?;
+                Some(guess + Duration::seconds(offset.into()))
+            }
+            IcalDateTime::Date(_) | IcalDateTime::Floating(_) => None,
+        }
+    }
+
+    fn unit_date(&self, value: &IcalDateTime, unit_tz: Tz) -> NaiveDate {
+        match self.to_utc(value) {
+            Some(instant) => instant.with_timezone(&unit_tz).date_naive(),
+            None => value.local().date(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// RRULE
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Clone)]
+struct RRule {
+    freq: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<IcalDateTime>,
+    by_month: Vec<u32>,
+    by_month_day: Vec<i32>,
+    /// `(ordinal, weekday)`; ordinal 0 means every such weekday in the period.
+    by_day: Vec<(i32, Weekday)>,
+    by_set_pos: Vec<i32>,
+    week_start: Weekday,
+}
+
+/// Parse an RRULE value. Sub-daily frequencies are not meaningful for
+/// night-based availability and are rejected, which leaves the event as a
+/// single occurrence.
+fn parse_rrule(value: &str) -> Option<RRule> {
+    let mut freq = None;
+    let mut rule = RRule {
+        freq: Frequency::Daily,
+        interval: 1,
+        count: None,
+        until: None,
+        by_month: Vec::new(),
+        by_month_day: Vec::new(),
+        by_day: Vec::new(),
+        by_set_pos: Vec::new(),
+        week_start: Weekday::Mon,
+    };
+
+    for part in value.trim().split(';') {
+        let Some((key, raw)) = part.split_once('=') else {
+            continue;
+        };
+        let raw = raw.trim();
+        match key.trim().to_uppercase().as_str() {
+            "FREQ" => {
+                freq = Some(match raw.to_uppercase().as_str() {
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    "YEARLY" => Frequency::Yearly,
+                    _ => return None,
+                })
+            }
+            "INTERVAL" => rule.interval = raw.parse().ok().filter(|n| *n > 0)?,
+            "COUNT" => rule.count = Some(raw.parse().ok()?),
+            "UNTIL" => rule.until = parse_date_time(raw, &HashMap::new()),
+            "BYMONTH" => rule.by_month = parse_list(raw, |n: u32| (1..=12).contains(&n))?,
+            "BYMONTHDAY" => {
+                rule.by_month_day = parse_list(raw, |n: i32| n != 0 && (-31..=31).contains(&n))?
+            }
+            "BYSETPOS" => {
+                rule.by_set_pos = parse_list(raw, |n: i32| n != 0 && (-366..=366).contains(&n))?
+            }
+            "BYDAY" => {
+                rule.by_day = raw
+                    .split(',')
+                    .map(parse_by_day)
+                    .collect::<Option<Vec<_>>>()?
+            }
+            "WKST" => rule.week_start = parse_weekday(raw)?,
+            _ => {}
+        }
+    }
+
+    rule.freq = freq?;
+    Some(rule)
+}
+
+fn parse_list<T: std::str::FromStr + Copy>(raw: &str, valid: impl Fn(T) -> bool) -> Option<Vec<T>> {
+    raw.split(',')
+        .map(|item| item.trim().parse::<T>().ok().filter(|n| valid(*n)))
+        .collect()
+}
+
+fn parse_by_day(raw: &str) -> Option<(i32, Weekday)> {
+    let raw = raw.trim();
+    if raw.len() < 2 {
+        return None;
+    }
+    let (ordinal, day) = raw.split_at(raw.len() - 2);
+    let ordinal = match ordinal {
+        "" => 0,
+        value => value
+            .trim_start_matches('+')
+            .parse::<i32>()
+            .ok()
+            .filter(|n| *n != 0 && (-53..=53).contains(n))?,
+    };
+    Some((ordinal, parse_weekday(day)?))
+}
+
+fn parse_weekday(raw: &str) -> Option<Weekday> {
+    Some(match raw.trim().to_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+/// Occurrence start times for `rule` anchored at `start`, in order. DTSTART
+/// always counts as the first occurrence. Occurrences before `from` count
+/// towards COUNT but are not returned, so [`MAX_OCCURRENCES`] only limits
+/// occurrences from `from` on. Stops at COUNT, `until`, `limit` or
+/// [`MAX_OCCURRENCES`], whichever comes first.
+fn expand_rule(
+    rule: &RRule,
+    start: NaiveDateTime,
+    until: Option<NaiveDateTime>,
+    from: NaiveDateTime,
+    limit: NaiveDateTime,
+) -> Vec<NaiveDateTime> {
+    let within = |value: NaiveDateTime| value <= limit && until.is_none_or(|u| value <= u);
+    let mut occurrences = Vec::new();
+    if !within(start) {
+        return occurrences;
+    }
+    if start >= from {
+        occurrences.push(start);
+    }
+    let mut emitted = 1u32;
+    let time = start.time();
+
+    let mut period = 0u32;
+    while period < MAX_PERIODS {
+        let Some((period_start, dates)) = period_dates(rule, start.date(), period) else {
+            break;
+        };
+        if period_start.and_time(NaiveTime::MIN) > limit {
+            break;
+        }
+        for date in dates {
+            let candidate = date.and_time(time);
+            if candidate <= start {
+                continue;
+            }
+            if !within(candidate)
+                || rule.count.is_some_and(|count| emitted >= count)
+                || occurrences.len() >= MAX_OCCURRENCES
+            {
+                return occurrences;
+            }
+            if candidate >= from {
+                occurrences.push(candidate);
+            }
+            emitted += 1;
+        }
+        period += rule.interval;
+    }
+    occurrences
+}
+
+/// Candidate dates in the `index`-th period after the one containing
+/// `anchor`, sorted, with BYSETPOS applied. Returns the period's first day
+/// alongside.
+fn period_dates(
+    rule: &RRule,
+    anchor: NaiveDate,
+    index: u32,
+) -> Option<(NaiveDate, Vec<NaiveDate>)> {
+    let (period_start, mut dates) = match rule.freq {
+        Frequency::Daily => {
+            let day = anchor.checked_add_signed(Duration::days(index.into()))?;
+            let matches = month_allowed(rule, day)
+                && (rule.by_month_day.is_empty() || month_day_matches(&rule.by_month_day, day))
+                && (rule.by_day.is_empty()
+                    || rule.by_day.iter().any(|(_, wd)| *wd == day.weekday()));
+            (day, if matches { vec![day] } else { Vec::new() })
+        }
+        Frequency::Weekly => {
+            let back = (7 + anchor.weekday().num_days_from_monday()
+                - rule.week_start.num_days_from_monday())
+                % 7;
+            let week = anchor
+                .checked_sub_signed(Duration::days(back.into()))?
+                .checked_add_signed(Duration::weeks(index.into()))?;
+            let dates = (0..7)
+                .map(|offset| week + Duration::days(offset))
+                .filter(|day| {
+                    if rule.by_day.is_empty() {
+                        day.weekday() == anchor.weekday()
+                    } else {
+                        rule.by_day.iter().any(|(_, wd)| *wd == day.weekday())
+                    }
+                })
+                .filter(|day| month_allowed(rule, *day))
+                .collect();
+            (week, dates)
+        }
+        Frequency::Monthly => {
+            let months = anchor.year() * 12 + anchor.month0() as i32 + index as i32;
+            let (year, month) = (months.div_euclid(12), months.rem_euclid(12) as u32 + 1);
+            let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+            let dates = if month_allowed(rule, first) {
+                month_dates(rule, year, month, anchor.day())
+            } else {
+                Vec::new()
+            };
+            (first, dates)
+        }
+        Frequency::Yearly => {
+            let year = anchor.year() + index as i32;
+            let first = NaiveDate::from_ymd_opt(year, 1, 1)?;
+            let dates = if !rule.by_month.is_empty() {
+                rule.by_month
+                    .iter()
+                    .flat_map(|month| month_dates(rule, year, *month, anchor.day()))
+                    .collect()
+            } else if !rule.by_day.is_empty() && rule.by_month_day.is_empty() {
+                year_weekday_dates(&rule.by_day, year)
+            } else if !rule.by_month_day.is_empty() {
+                (1..=12)
+                    .flat_map(|month| month_dates(rule, year, month, anchor.day()))
+                    .collect()
+            } else {
+                NaiveDate::from_ymd_opt(year, anchor.month(), anchor.day())
+                    .into_iter()
+                    .collect()
+            };
+            (first, dates)
+        }
+    };
+
+    dates.sort();
+    dates.dedup();
+    if !rule.by_set_pos.is_empty() {
+        let total = dates.len() as i32;
+        let mut picked = rule
+            .by_set_pos
+            .iter()
+            .filter_map(|pos| {
+                let index = if *pos > 0 { pos - 1 } else { total + pos };
+                (0..total).contains(&index).then(|| dates[index as usize])
+            })
+            .collect::<Vec<_>>();
+        picked.sort();
+        picked.dedup();
+        dates = picked;
+    }
+    Some((period_start, dates))
+}
+
+fn month_allowed(rule: &RRule, date: NaiveDate) -> bool {
+    rule.by_month.is_empty() || rule.by_month.contains(&date.month())
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|next| next.pred_opt())
+        .map_or(28, |last| last.day())
+}
+
+fn month_day_matches(by_month_day: &[i32], date: NaiveDate) -> bool {
+    let days = days_in_month(date.year(), date.month()) as i32;
+    let day = date.day() as i32;
+    by_month_day
+        .iter()
+        .any(|md| *md == day || (*md < 0 && days + md + 1 == day))
+}
+
+/// Dates in a month matching BYMONTHDAY/BYDAY (intersected when both are
+/// present), or `default_day` when neither is.
+fn month_dates(rule: &RRule, year: i32, month: u32, default_day: u32) -> Vec<NaiveDate> {
+    let days = days_in_month(year, month);
+    if rule.by_month_day.is_empty() && rule.by_day.is_empty() {
+        return NaiveDate::from_ymd_opt(year, month, default_day)
+            .into_iter()
+            .collect();
+    }
+    (1..=days)
+        .filter_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+        .filter(|date| rule.by_month_day.is_empty() || month_day_matches(&rule.by_month_day, *date))
+        .filter(|date| {
+            rule.by_day.is_empty()
+                || rule.by_day.iter().any(|(ordinal, weekday)| {
+                    date.weekday() == *weekday && ordinal_matches(*ordinal, date.day(), days)
+                })
+        })
+        .collect()
+}
+
+/// BYDAY with YEARLY frequency and no BYMONTH: ordinals count within the year.
+fn year_weekday_dates(by_day: &[(i32, Weekday)], year: i32) -> Vec<NaiveDate> {
+    let Some(first) = NaiveDate::from_ymd_opt(year, 1, 1) else {
+        return Vec::new();
+    };
+    let days = if NaiveDate::from_ymd_opt(year, 2, 29).is_some() {
+        366
+    } else {
+        365
+    };
+    (0..days)
+        .map(|offset| first + Duration::days(offset))
+        .filter(|date| {
+            by_day.iter().any(|(ordinal, weekday)| {
+                date.weekday() == *weekday && ordinal_matches(*ordinal, date.ordinal(), days as u32)
+            })
+        })
+        .collect()
+}
+
+/// Whether the `position`-th day (1-based) of a span of `span` days is the
+/// `ordinal`-th occurrence of its weekday (negative counts from the end).
+fn ordinal_matches(ordinal: i32, position: u32, span: u32) -> bool {
+    match ordinal {
+        0 => true,
+        n if n > 0 => ((position - 1) / 7 + 1) as i32 == n,
+        n => ((span - position) / 7 + 1) as i32 == -n,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).expect("valid date")
+    }
+
+    fn window() -> ExpansionWindow {
+        ExpansionWindow {
+            start: date(2026, 1, 1),
+            end: date(2026, 12, 31),
+        }
+    }
+
+    fn calendar(events: &str) -> String {
+        format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{events}END:VCALENDAR\r\n")
+    }
+
+    #[test]
+    fn all_day_events_use_dates_as_written() {
+        let ics = calendar(
+            "BEGIN:VEVENT\r\nUID:abc\r\nDTSTART;VALUE=DATE:20260310\r\nDTEND;VALUE=DATE:20260314\r\nSUMMARY:Reserved\r\nEND:VEVENT\r\n",
+        );
+        let events = parse_calendar(&ics, chrono_tz::America::Asuncion, window());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].uid, "abc");
+        assert_eq!(events[0].start_date, date(2026, 3, 10));
+        assert_eq!(events[0].end_date, date(2026, 3, 14));
+        assert!(!events[0].recurring);
+    }
+
+    #[test]
+    fn utc_times_near_midnight_resolve_to_unit_local_date() {
+        // 02:00Z is 23:00 the previous evening in Asunción (UTC-3).
+        let ics = calendar(
+            "BEGIN:VEVENT\r\nUID:late\r\nDTSTART:20260310T020000Z\r\nDTEND:20260314T020000Z\r\nEND:VEVENT\r\n",
+        );
+        let events = parse_calendar(&ics, chrono_tz::America::Asuncion, window());
+        assert_eq!(events[0].start_date, date(2026, 3, 9));
+        assert_eq!(events[0].end_date, date(2026, 3, 13));
+    }
+
+    #[test]
+    fn tzid_times_convert_through_the_event_zone() {
+        // 22:00 in Madrid (UTC+1 in March) is 18:00 in Asunción (UTC-3).
+        let ics = calendar(
+            "BEGIN:VEVENT\r\nUID:tz\r\nDTSTART;TZID=Europe/Madrid:20260310T220000\r\nDTEND;TZID=\"/mozilla.org/20070129_1/Europe/Madrid\":20260312T030000\r\nEND:VEVENT\r\n",
+        );
+        let events = parse_calendar(&ics, chrono_tz::America::Asuncion, window());
+        assert_eq!(events[0].start_date, date(2026, 3, 10));
+        assert_eq!(events[0].end_date, date(2026, 3, 11));
+    }
+
+    #[test]
+    fn custom_vtimezone_definitions_are_applied() {
+        let ics = calendar(
+            "BEGIN:VTIMEZONE\r\nTZID:Eastern Standard Time\r\n\
+             BEGIN:STANDARD\r\nDTSTART:16010101T020000\r\nTZOFFSETFROM:-0400\r\nTZOFFSETTO:-0500\r\nRRULE:FREQ=YEARLY;BYDAY=1SU;BYMONTH=11\r\nEND:STANDARD\r\n\
+             BEGIN:DAYLIGHT\r\nDTSTART:16010101T020000\r\nTZOFFSETFROM:-0500\r\nTZOFFSETTO:-0400\r\nRRULE:FREQ=YEARLY;BYDAY=2SU;BYMONTH=3\r\nEND:DAYLIGHT\r\n\
+             END:VTIMEZONE\r\n\
+             BEGIN:VEVENT\r\nUID:win\r\nDTSTART;TZID=Eastern Standard Time:20260701T220000\r\nDTEND;TZID=Eastern Standard Time:20260703T100000\r\nEND:VEVENT\r\n",
+        );
+        // 22:00 EDT (UTC-4) is 02:00Z the next day, i.e. 2026-07-02 in UTC.
+        let events = parse_calendar(&ics, chrono_tz::UTC, window());
+        assert_eq!(events[0].start_date, date(2026, 7, 2));
+        assert_eq!(events[0].end_date, date(2026, 7, 3));
+    }
+
+    #[test]
+    fn weekly_rule_expands_with_exdate_and_override() {
+        let ics = calendar(
+            "BEGIN:VEVENT\r\nUID:owner\r\nDTSTART;VALUE=DATE:20260105\r\nDTEND;VALUE=DATE:20260107\r\n\
+             RRULE:FREQ=WEEKLY;COUNT=4\r\nEXDATE;VALUE=DATE:20260112\r\nSUMMARY:Owner stay\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nUID:owner\r\nRECURRENCE-ID;VALUE=DATE:20260119\r\nDTSTART;VALUE=DATE:20260119\r\nDTEND;VALUE=DATE:20260120\r\nSTATUS:CANCELLED\r\nEND:VEVENT\r\n",
+        );
+        let events = parse_calendar(&ics, chrono_tz::America::Asuncion, window());
+        let summary = events
+            .iter()
+            .map(|e| (e.uid.as_str(), e.start_date, e.is_cancelled()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("owner#20260105", date(2026, 1, 5), false),
+                ("owner#20260126", date(2026, 1, 26), false),
+                ("owner#20260119", date(2026, 1, 19), true),
+            ]
+        );
+        assert!(events.iter().all(|e| e.recurring));
+    }
+
+    #[test]
+    fn monthly_rules_support_ordinals_and_negative_month_days() {
+        let rule = parse_rrule("FREQ=MONTHLY;BYDAY=-1FR;COUNT=3").expect("rule");
+        let start = date(2026, 1, 30).and_time(NaiveTime::MIN);
+        let limit = date(2027, 1, 1).and_time(NaiveTime::MIN);
+        let dates = expand_rule(&rule, start, None, start, limit)
+            .iter()
+            .map(|dt| dt.date())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dates,
+            vec![date(2026, 1, 30), date(2026, 2, 27), date(2026, 3, 27)]
+        );
+
+        let rule = parse_rrule("FREQ=MONTHLY;BYMONTHDAY=-1;UNTIL=20260430").expect("rule");
+        let start = date(2026, 1, 31).and_time(NaiveTime::MIN);
+        let until = Some(date(2026, 4, 30).and_hms_opt(23, 59, 59).expect("time"));
+        let dates = expand_rule(&rule, start, until, start, limit)
+            .iter()
+            .map(|dt| dt.date())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dates,
+            vec![
+                date(2026, 1, 31),
+                date(2026, 2, 28),
+                date(2026, 3, 31),
+                date(2026, 4, 30)
+            ]
+        );
+    }
+
+    #[test]
+    fn unbounded_rules_stop_at_the_window() {
+        let ics = calendar(
+            "BEGIN:VEVENT\r\nUID:daily\r\nDTSTART;VALUE=DATE:20200101\r\nDTEND;VALUE=DATE:20200102\r\nRRULE:FREQ=DAILY;INTERVAL=10\r\nEND:VEVENT\r\n",
+        );
+        let events = parse_calendar(&ics, chrono_tz::UTC, window());
+        assert!(events
+            .iter()
+            .all(|e| e.end_date >= date(2026, 1, 1) && e.start_date <= date(2026, 12, 31)));
+        assert_eq!(events.len(), 36);
+    }
+
+    #[test]
+    fn occurrences_before_the_window_do_not_use_up_the_cap() {
+        // Over 9,000 daily occurrences precede the window.
+        let ics = calendar(
+            "BEGIN:VEVENT\r\nUID:old\r\nDTSTART;VALUE=DATE:20000101\r\nDTEND;VALUE=DATE:20000102\r\nRRULE:FREQ=DAILY\r\nEND:VEVENT\r\n",
+        );
+        let events = parse_calendar(&ics, chrono_tz::UTC, window());
+        // The 2025-12-31 night checks out on the window's first day.
+        assert_eq!(events.len(), 366);
+        assert_eq!(events[0].uid, "old#20251231");
+        assert_eq!(events[365].start_date, date(2026, 12, 31));
+    }
+
+    #[test]
+    fn count_includes_occurrences_before_the_window() {
+        let ics = calendar(
+            "BEGIN:VEVENT\r\nUID:counted\r\nDTSTART;VALUE=DATE:20251228\r\nDTEND;VALUE=DATE:20251229\r\nRRULE:FREQ=DAILY;COUNT=5\r\nEND:VEVENT\r\n",
+        );
+        let events = parse_calendar(&ics, chrono_tz::UTC, window());
+        let starts = events.iter().map(|e| e.start_date).collect::<Vec<_>>();
+        assert_eq!(starts, vec![date(2025, 12, 31), date(2026, 1, 1)]);
+    }
+
+    #[test]
+    fn folded_lines_quoted_params_and_escapes_are_parsed() {
+        let ics = calendar(
+            "BEGIN:VEVENT\r\nUID:fold\r\nDTSTART;VALUE=DATE:20260310\r\nDTEND;VALUE=DATE:20260311\r\nSUMMARY;X-NOTE=\"a;b:c\":Not available\\, owner\r\n  block\r\nEND:VEVENT\r\n",
+        );
+        let events = parse_calendar(&ics, chrono_tz::UTC, window());
+        assert_eq!(events[0].summary, "Not available, owner block");
+    }
+
+    #[test]
+    fn durations_parse() {
+        assert_eq!(parse_duration("P3D"), Some(Duration::days(3)));
+        assert_eq!(parse_duration("P1W"), Some(Duration::weeks(1)));
+        assert_eq!(parse_duration("PT1H30M"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("P1"), None);
+    }
+}
@@ -0,0 +1,628 @@
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+use sqlx::{PgPool, Row};
+use tracing::warn;
+
+use crate::services::audit::write_audit_log;
+
+/// Overdue collections examined per cycle run.
+const MAX_OVERDUE_PER_RUN: i64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LateFeeType {
+    /// Flat amount, charged once.
+    Fixed,
+    /// Percent of the overdue amount, charged once.
+    Percentage,
+    /// Percent of the overdue amount per day late, re-accrued daily.
+    DailyInterest,
+}
+
+impl LateFeeType {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "fixed" => Some(Self::Fixed),
+            "percentage" | "percent" => Some(Self::Percentage),
+            "daily_interest" => Some(Self::DailyInterest),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Fixed => "fixed",
+            Self::Percentage => "percentage",
+            Self::DailyInterest => "daily_interest",
+        }
+    }
+}
+
+/// The pricing part of a `late_fee_policies` row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LateFeePolicy {
+    pub fee_type: LateFeeType,
+    /// Flat amount for `Fixed`, otherwise a percent.
+    pub amount: f64,
+    pub grace_days: i64,
+    pub max_fee_amount: Option<f64>,
+    /// Cap as a percent of the overdue amount.
+    pub max_fee_percent: Option<f64>,
+}
+
+/// Late fee owed on `principal` due on `due_date`, as of `as_of`. `None`
+/// while the collection is still within its grace period. Daily interest
+/// runs from the due date once the grace period has passed, so a tenant who
+/// pays one day after grace owes interest for every day late.
+pub fn compute_late_fee(
+    policy: &LateFeePolicy,
+    principal: f64,
+    due_date: NaiveDate,
+    as_of: NaiveDate,
+    currency: &str,
+) -> Option<f64> {
+    let days_late = (as_of - due_date).num_days();
+    if days_late <= policy.grace_days || principal <= 0.0 {
+        return None;
+    }
+
+    let mut fee = match policy.fee_type {
+        LateFeeType::Fixed => policy.amount,
+        LateFeeType::Percentage => principal * policy.amount / 100.0,
+        LateFeeType::DailyInterest => principal * policy.amount / 100.0 * days_late as f64,
+    };
+    if let Some(cap) = policy.max_fee_amount {
+        fee = fee.min(cap);
+    }
+    if let Some(percent) = policy.max_fee_percent {
+        fee = fee.min(principal * percent / 100.0);
+    }
+
+    let fee = round_currency(fee, currency);
+    (fee > 0.0).then_some(fee)
+}
+
+/// Guaraníes have no minor unit; everything else rounds to cents.
+pub fn round_currency(amount: f64, currency: &str) -> f64 {
+    if currency.eq_ignore_ascii_case("PYG") {
+        amount.round()
+    } else {
+        (amount * 100.0).round() / 100.0
+    }
+}
+
+/// Policy suggested by the `late_fee`/`late_fee_type` terms of a lease
+/// abstraction, if both were extracted.
+pub fn policy_from_abstraction(extracted: &Value) -> Option<(LateFeeType, f64)> {
+    let fee_type = extracted
+        .get("late_fee_type")
+        .and_then(Value::as_str)
+        .and_then(LateFeeType::parse)
+        .filter(|fee_type| *fee_type != LateFeeType::DailyInterest)?;
+    let amount = number(extracted.get("late_fee")).filter(|value| *value > 0.0)?;
+    Some((fee_type, amount))
+}
+
+/// Store the abstraction's late-fee terms as an inactive policy on the
+/// lease for staff to review. Leases that already have a policy keep it.
+pub async fn seed_policy_from_abstraction(
+    pool: &PgPool,
+    org_id: &str,
+    lease_id: &str,
+    extracted: &Value,
+) -> bool {
+    let Some((fee_type, amount)) = policy_from_abstraction(extracted) else {
+        return false;
+    };
+    match sqlx::query(
+        "INSERT INTO late_fee_policies
+            (organization_id, lease_id, fee_type, amount, is_active, source, notes)
+         VALUES ($1::uuid, $2::uuid, $3, $4, false, 'lease_abstraction',
+                 'Extracted from the lease document; review before activating.')
+         ON CONFLICT (lease_id) WHERE lease_id IS NOT NULL DO NOTHING",
+    )
+    .bind(org_id)
+    .bind(lease_id)
+    .bind(fee_type.as_str())
+    .bind(amount)
+    .execute(pool)
+    .await
+    {
+        Ok(done) => done.rows_affected() > 0,
+        Err(error) => {
+            warn!(lease_id, error = %error, "Failed to seed late fee policy");
+            false
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LateFeeRun {
+    pub assessed: u32,
+    pub accrued: u32,
+    pub errors: u32,
+}
+
+/// Assess late fees on overdue collections. Each overdue collection gets at
+/// most one `late_fee` lease charge plus a pending collection record for it;
+/// daily-interest charges are re-priced every day until the original
+/// collection is paid or the fee is waived or adjusted by staff. Late-fee
+/// collections never accrue fees themselves. A lease's own policy applies
+/// only while active; otherwise the organization default does. Collections
+/// whose fee is final are left out of the scan so they can't crowd newer
+/// overdue collections out of the per-run limit.
+pub async fn apply_late_fees(pool: &PgPool, org_id: Option<&str>, today: NaiveDate) -> LateFeeRun {
+    let mut run = LateFeeRun::default();
+
+    let rows = match sqlx::query(
+        "SELECT cr.id::text AS collection_id,
+                cr.organization_id::text AS organization_id,
+                cr.lease_id::text AS lease_id,
+                cr.due_date,
+                cr.amount::float8 AS amount,
+                cr.currency::text AS currency,
+                p.id::text AS policy_id,
+                p.fee_type,
+                p.amount::float8 AS policy_amount,
+                p.grace_days,
+                p.max_fee_amount::float8 AS max_fee_amount,
+                p.max_fee_percent::float8 AS max_fee_percent,
+                fee.id::text AS fee_charge_id,
+                fee.amount::float8 AS fee_amount,
+                fee.status::text AS fee_status,
+                fee.adjusted_at IS NOT NULL AS fee_adjusted
+           FROM collection_records cr
+           LEFT JOIN lease_charges src ON src.id = cr.lease_charge_id
+           JOIN LATERAL (
+                SELECT *
+                  FROM late_fee_policies lp
+                 WHERE lp.organization_id = cr.organization_id
+                   AND (lp.lease_id = cr.lease_id OR lp.lease_id IS NULL)
+                   AND lp.is_active
+                 ORDER BY lp.lease_id NULLS LAST
+                 LIMIT 1
+           ) p ON true
+           LEFT JOIN lease_charges fee ON fee.source_collection_id = cr.id
+          WHERE cr.status IN ('pending', 'late')
+            AND cr.due_date < $1
+            AND cr.amount > 0
+            AND (src.id IS NULL OR src.charge_type::text <> 'late_fee')
+            AND (
+                fee.id IS NULL
+                OR (
+                    p.fee_type = 'daily_interest'
+                    AND fee.adjusted_at IS NULL
+                    AND fee.status::text NOT IN ('paid', 'waived')
+                )
+            )
+            AND ($2::uuid IS NULL OR cr.organization_id = $2::uuid)
+          ORDER BY cr.due_date
+          LIMIT $3",
+    )
+    .bind(today)
+    .bind(org_id)
+    .bind(MAX_OVERDUE_PER_RUN)
+    .fetch_all(pool)
+    .await
+    {
+        Ok(rows) => rows,
+        Err(error) => {
+            warn!("Failed to fetch overdue collections for late fees: {error}");
+            run.errors += 1;
+            return run;
+        }
+    };
+
+    for row in rows {
+        let collection_id: String = row.try_get("collection_id").unwrap_or_default();
+        let organization_id: String = row.try_get("organization_id").unwrap_or_default();
+        let currency: String = row.try_get("currency").unwrap_or_default();
+        let Ok(due_date) = row.try_get::<NaiveDate, _>("due_date") else {
+            continue;
+        };
+        let principal = row.try_get::<f64, _>("amount").unwrap_or(0.0);
+        let policy = LateFeePolicy {
+            fee_type: match row
+                .try_get::<String, _>("fee_type")
+                .ok()
+                .as_deref()
+                .and_then(LateFeeType::parse)
+            {
+                Some(fee_type) => fee_type,
+                None => continue,
+            },
+            amount: row.try_get("policy_amount").unwrap_or(0.0),
+            grace_days: i64::from(row.try_get::<i32, _>("grace_days").unwrap_or(0)),
+            max_fee_amount: row.try_get("max_fee_amount").ok().flatten(),
+            max_fee_percent: row.try_get("max_fee_percent").ok().flatten(),
+        };
+        let Some(fee) = compute_late_fee(&policy, principal, due_date, today, &currency) else {
+            continue;
+        };
+
+        let existing: Option<String> = row.try_get("fee_charge_id").ok().flatten();
+        let outcome = match existing {
+            None => {
+                let lease_id: String = row.try_get("lease_id").unwrap_or_default();
+                let policy_id: String = row.try_get("policy_id").unwrap_or_default();
+                assess_fee(
+                    pool,
+                    &organization_id,
+                    &lease_id,
+                    &collection_id,
+                    &policy_id,
+                    due_date,
+                    today,
+                    fee,
+                    &currency,
+                )
+                .await
+                .map(|created| {
+                    if created {
+                        run.assessed += 1;
+                    }
+                })
+            }
+            Some(charge_id) => {
+                let current = row.try_get::<f64, _>("fee_amount").unwrap_or(0.0);
+                let status: String = row.try_get("fee_status").unwrap_or_default();
+                let adjusted = row.try_get::<bool, _>("fee_adjusted").unwrap_or(false);
+                if policy.fee_type != LateFeeType::DailyInterest
+                    || adjusted
+                    || matches!(status.as_str(), "paid" | "waived")
+                    || fee <= current
+                {
+                    continue;
+                }
+                accrue_fee(pool, &organization_id, &charge_id, current, fee, today)
+                    .await
+                    .map(|updated| {
+                        if updated {
+                            run.accrued += 1;
+                        }
+                    })
+            }
+        };
+        if let Err(error) = outcome {
+            warn!(collection_id = %collection_id, error = %error, "Failed to apply late fee");
+            run.errors += 1;
+        }
+    }
+
+    run
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn assess_fee(
+    pool: &PgPool,
+    org_id: &str,
+    lease_id: &str,
+    collection_id: &str,
+    policy_id: &str,
+    due_date: NaiveDate,
+    today: NaiveDate,
+    fee: f64,
+    currency: &str,
+) -> Result<bool, sqlx::Error> {
+    let description = format!("Late fee — payment due {due_date}");
+    let created = sqlx::query(
+        "WITH charge AS (
+            INSERT INTO lease_charges (
+                organization_id, lease_id, charge_date, charge_type, description,
+                amount, currency, status, source_collection_id, late_fee_policy_id,
+                accrued_through, original_amount
+            ) VALUES (
+                $1::uuid, $2::uuid, $3, 'late_fee', $4,
+                $5, $6, 'pending', $7::uuid, $8::uuid,
+                $3, $5
+            )
+            ON CONFLICT (source_collection_id) WHERE source_collection_id IS NOT NULL DO NOTHING
+            RETURNING id, organization_id, lease_id, charge_date, amount, currency, description
+         ), fee_collection AS (
+            INSERT INTO collection_records (
+                organization_id, lease_id, lease_charge_id, due_date, amount, currency, status, notes
+            )
+            SELECT organization_id, lease_id, id, charge_date, amount, currency, 'pending', description
+              FROM charge
+            RETURNING id
+         )
+         SELECT charge.id::text AS charge_id,
+                (SELECT id::text FROM fee_collection) AS fee_collection_id
+           FROM charge",
+    )
+    .bind(org_id)
+    .bind(lease_id)
+    .bind(today)
+    .bind(&description)
+    .bind(fee)
+    .bind(currency)
+    .bind(collection_id)
+    .bind(policy_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(created) = created else {
+        return Ok(false);
+    };
+    let charge_id: String = created.try_get("charge_id").unwrap_or_default();
+    let fee_collection_id: Option<String> = created.try_get("fee_collection_id").ok().flatten();
+    write_audit_log(
+        Some(pool),
+        Some(org_id),
+        None,
+        "late_fee_assessed",
+        "lease_charges",
+        Some(&charge_id),
+        None,
+        Some(json!({
+            "amount": fee,
+            "currency": currency,
+            "source_collection_id": collection_id,
+            "fee_collection_id": fee_collection_id,
+            "late_fee_policy_id": policy_id,
+            "due_date": due_date.to_string(),
+        })),
+    )
+    .await;
+    Ok(true)
+}
+
+async fn accrue_fee(
+    pool: &PgPool,
+    org_id: &str,
+    charge_id: &str,
+    current: f64,
+    fee: f64,
+    today: NaiveDate,
+) -> Result<bool, sqlx::Error> {
+    let updated = sqlx::query(
+        "WITH charge AS (
+            UPDATE lease_charges
+               SET amount = $2, accrued_through = $3
+             WHERE id = $1::uuid
+               AND adjusted_at IS NULL
+               AND status NOT IN ('paid', 'waived')
+            RETURNING id
+         )
+         UPDATE collection_records
+            SET amount = $2
+          WHERE lease_charge_id IN (SELECT id FROM charge)
+            AND status NOT IN ('paid', 'waived')",
+    )
+    .bind(charge_id)
+    .bind(fee)
+    .bind(today)
+    .execute(pool)
+    .await?;
+
+    if updated.rows_affected() == 0 {
+        return Ok(false);
+    }
+    write_audit_log(
+        Some(pool),
+        Some(org_id),
+        None,
+        "late_fee_accrued",
+        "lease_charges",
+        Some(charge_id),
+        Some(json!({ "amount": current })),
+        Some(json!({ "amount": fee, "accrued_through": today.to_string() })),
+    )
+    .await;
+    Ok(true)
+}
+
+fn number(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn policy(fee_type: LateFeeType, amount: f64) -> LateFeePolicy {
+        LateFeePolicy {
+            fee_type,
+            amount,
+            grace_days: 3,
+            max_fee_amount: None,
+            max_fee_percent: None,
+        }
+    }
+
+    #[test]
+    fn grace_period_suppresses_fee() {
+        let fixed = policy(LateFeeType::Fixed, 50_000.0);
+        let due = date("2026-10-01");
+        assert_eq!(
+            compute_late_fee(&fixed, 2_500_000.0, due, date("2026-10-04"), "PYG"),
+            None
+        );
+        assert_eq!(
+            compute_late_fee(&fixed, 2_500_000.0, due, date("2026-10-05"), "PYG"),
+            Some(50_000.0)
+        );
+    }
+
+    #[test]
+    fn percentage_and_daily_interest_respect_caps() {
+        let due = date("2026-10-01");
+        let percent = policy(LateFeeType::Percentage, 5.0);
+        assert_eq!(
+            compute_late_fee(&percent, 1_234.56, due, date("2026-10-10"), "USD"),
+            Some(61.73)
+        );
+
+        let mut interest = policy(LateFeeType::DailyInterest, 0.1);
+        // 10 days late at 0.1% a day on 2,500,000.
+        assert_eq!(
+            compute_late_fee(&interest, 2_500_000.0, due, date("2026-10-11"), "PYG"),
+            Some(25_000.0)
+        );
+        interest.max_fee_percent = Some(0.5);
+        assert_eq!(
+            compute_late_fee(&interest, 2_500_000.0, due, date("2026-10-11"), "PYG"),
+            Some(12_500.0)
+        );
+        interest.max_fee_amount = Some(10_000.0);
+        assert_eq!(
+            compute_late_fee(&interest, 2_500_000.0, due, date("2026-10-11"), "PYG"),
+            Some(10_000.0)
+        );
+    }
+
+    #[test]
+    fn abstraction_terms_map_to_policy() {
+        assert_eq!(
+            policy_from_abstraction(&json!({ "late_fee": 5, "late_fee_type": "percentage" })),
+            Some((LateFeeType::Percentage, 5.0))
+        );
+        assert_eq!(
+            policy_from_abstraction(&json!({ "late_fee": "150000", "late_fee_type": "fixed" })),
+            Some((LateFeeType::Fixed, 150_000.0))
+        );
+        assert_eq!(
+            policy_from_abstraction(&json!({ "late_fee": null, "late_fee_type": "fixed" })),
+            None
+        );
+    }
+
+    mod db {
+        use super::*;
+        use crate::test_support::{create_lease, create_org, create_user, test_pool};
+
+        async fn setup(pool: &PgPool) -> (String, String) {
+            let user_id = create_user(pool).await;
+            let org_id = create_org(pool, &user_id).await;
+            let lease_id = create_lease(pool, &org_id, "PYG").await;
+            (org_id, lease_id)
+        }
+
+        async fn org_policy(pool: &PgPool, org_id: &str, fee_type: &str, amount: f64) {
+            sqlx::query(
+                "INSERT INTO late_fee_policies (organization_id, fee_type, amount, grace_days)
+                 VALUES ($1::uuid, $2, $3, 0)",
+            )
+            .bind(org_id)
+            .bind(fee_type)
+            .bind(amount)
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+
+        async fn overdue(pool: &PgPool, org_id: &str, lease_id: &str, amount: f64) -> String {
+            sqlx::query_scalar(
+                "INSERT INTO collection_records (organization_id, lease_id, due_date, amount, currency, status)
+                 VALUES ($1::uuid, $2::uuid, '2026-10-01', $3, 'PYG', 'late')
+                 RETURNING id::text",
+            )
+            .bind(org_id)
+            .bind(lease_id)
+            .bind(amount)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+        }
+
+        async fn fee_for(pool: &PgPool, collection_id: &str) -> Option<(String, f64)> {
+            sqlx::query_as(
+                "SELECT id::text, amount::float8 FROM lease_charges
+                 WHERE source_collection_id = $1::uuid",
+            )
+            .bind(collection_id)
+            .fetch_optional(pool)
+            .await
+            .unwrap()
+        }
+
+        async fn run(pool: &PgPool, org_id: &str, today: &str) -> LateFeeRun {
+            let run = apply_late_fees(pool, Some(org_id), date(today)).await;
+            assert_eq!(run.errors, 0);
+            run
+        }
+
+        #[tokio::test]
+        async fn inactive_seeded_lease_policy_falls_back_to_org_default() {
+            let Some(pool) = test_pool().await else {
+                return;
+            };
+            let (org_id, lease_id) = setup(&pool).await;
+            org_policy(&pool, &org_id, "fixed", 50_000.0).await;
+            let terms = json!({ "late_fee": 5, "late_fee_type": "percentage" });
+            assert!(seed_policy_from_abstraction(&pool, &org_id, &lease_id, &terms).await);
+            let collection_id = overdue(&pool, &org_id, &lease_id, 1_000_000.0).await;
+
+            assert_eq!(run(&pool, &org_id, "2026-10-10").await.assessed, 1);
+            let (_, amount) = fee_for(&pool, &collection_id).await.expect("fee assessed");
+            assert_eq!(amount, 50_000.0);
+        }
+
+        #[tokio::test]
+        async fn fee_is_assessed_once_and_fee_collections_accrue_nothing() {
+            let Some(pool) = test_pool().await else {
+                return;
+            };
+            let (org_id, lease_id) = setup(&pool).await;
+            org_policy(&pool, &org_id, "fixed", 50_000.0).await;
+            overdue(&pool, &org_id, &lease_id, 1_000_000.0).await;
+
+            assert_eq!(run(&pool, &org_id, "2026-10-10").await.assessed, 1);
+            // The fee's own collection is overdue by now, but is never penalized.
+            for today in ["2026-10-11", "2026-11-15"] {
+                let again = run(&pool, &org_id, today).await;
+                assert_eq!((again.assessed, again.accrued), (0, 0));
+            }
+            let charges: i64 =
+                sqlx::query_scalar("SELECT count(*) FROM lease_charges WHERE lease_id = $1::uuid")
+                    .bind(&lease_id)
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+            assert_eq!(charges, 1);
+        }
+
+        #[tokio::test]
+        async fn daily_interest_stops_after_waive_or_adjust() {
+            let Some(pool) = test_pool().await else {
+                return;
+            };
+            let (org_id, lease_id) = setup(&pool).await;
+            org_policy(&pool, &org_id, "daily_interest", 0.1).await;
+            let waived = overdue(&pool, &org_id, &lease_id, 1_000_000.0).await;
+            let adjusted = overdue(&pool, &org_id, &lease_id, 2_000_000.0).await;
+            let open = overdue(&pool, &org_id, &lease_id, 3_000_000.0).await;
+
+            assert_eq!(run(&pool, &org_id, "2026-10-10").await.assessed, 3);
+            assert_eq!(run(&pool, &org_id, "2026-10-11").await.accrued, 3);
+            assert_eq!(fee_for(&pool, &open).await.unwrap().1, 30_000.0);
+
+            let (waived_fee, waived_amount) = fee_for(&pool, &waived).await.unwrap();
+            sqlx::query("UPDATE lease_charges SET status = 'waived' WHERE id = $1::uuid")
+                .bind(&waived_fee)
+                .execute(&pool)
+                .await
+                .unwrap();
+            let (adjusted_fee, _) = fee_for(&pool, &adjusted).await.unwrap();
+            sqlx::query(
+                "UPDATE lease_charges SET amount = 5000, adjusted_at = now() WHERE id = $1::uuid",
+            )
+            .bind(&adjusted_fee)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+            assert_eq!(run(&pool, &org_id, "2026-10-12").await.accrued, 1);
+            assert_eq!(fee_for(&pool, &waived).await.unwrap().1, waived_amount);
+            assert_eq!(fee_for(&pool, &adjusted).await.unwrap().1, 5_000.0);
+            assert_eq!(fee_for(&pool, &open).await.unwrap().1, 33_000.0);
+        }
+    }
+}
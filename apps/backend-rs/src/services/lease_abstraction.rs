@@ -3,6 +3,7 @@ use sqlx::Row;
 
 use crate::{
     error::{AppError, AppResult},
+    services::late_fees,
     state::AppState,
 };
 
@@ -250,6 +251,14 @@ pub async fn tool_abstract_lease_document(
         }
     }
 
+    // Late-fee terms become an inactive policy on the lease for review
+    let late_fee_policy_seeded = match lease_id.map(str::trim).filter(|id| !id.is_empty()) {
+        Some(lease_id) => {
+            late_fees::seed_policy_from_abstraction(pool, org_id, lease_id, &extracted).await
+        }
+        None => false,
+    };
+
     Ok(json!({
         "ok": true,
         "abstraction_id": abstraction_id,
@@ -258,6 +267,7 @@ pub async fn tool_abstract_lease_document(
         "extracted_terms": extracted,
         "clauses_count": clauses.as_array().map(|a| a.len()).unwrap_or(0),
         "deadlines_count": deadlines.as_array().map(|a| a.len()).unwrap_or(0),
+        "late_fee_policy_seeded": late_fee_policy_seeded,
         "field_count": field_count,
         "avg_confidence": avg_confidence,
         "reviewed": false,
@@ -0,0 +1,313 @@
+//! Security deposits on long-term leases. A deposit is received at move-in
+//! and held in trust, may earn simple interest for the tenant, and is
+//! settled at move-out: itemized deductions (linked to maintenance requests
+//! and inspection photos) are kept for the owner and the rest is refunded,
+//! through the provider that took the payment when there is one.
+
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+
+use crate::{
+    config::AppConfig,
+    error::{AppError, AppResult},
+    repository::table_service::{get_row, list_rows},
+    services::{mercado_pago, payments},
+};
+
+/// Amounts below this are rounding noise.
+const EPSILON: f64 = 0.005;
+
+pub const DEDUCTION_CATEGORIES: &[&str] = &[
+    "damage",
+    "cleaning",
+    "unpaid_rent",
+    "unpaid_utilities",
+    "missing_items",
+    "other",
+];
+
+/// Simple interest on `principal` at `annual_rate` from `from` to `to`,
+/// counting actual days over a 365-day year.
+pub fn accrue_interest(principal: f64, annual_rate: f64, from: NaiveDate, to: NaiveDate) -> f64 {
+    let days = (to - from).num_days();
+    if days <= 0 || principal <= 0.0 || annual_rate <= 0.0 {
+        return 0.0;
+    }
+    round2(principal * annual_rate * days as f64 / 365.0)
+}
+
+/// How a deposit settles at move-out.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Settlement {
+    pub held: f64,
+    pub interest: f64,
+    pub deductions: f64,
+    /// Deductions actually kept from the deposit.
+    pub applied: f64,
+    pub refund: f64,
+    /// Deductions the deposit does not cover; still owed by the tenant.
+    pub balance_due: f64,
+}
+
+impl Settlement {
+    pub fn compute(held: f64, interest: f64, deductions: f64) -> Self {
+        let available = round2(held + interest);
+        let applied = round2(deductions.min(available));
+        Self {
+            held: round2(held),
+            interest: round2(interest),
+            deductions: round2(deductions),
+            applied,
+            refund: round2(available - applied),
+            balance_due: round2((deductions - available).max(0.0)),
+        }
+    }
+
+    pub fn to_json(self) -> Value {
+        json!({
+            "held": self.held,
+            "interest": self.interest,
+            "deductions": self.deductions,
+            "applied_deductions": self.applied,
+            "refund": self.refund,
+            "balance_due": self.balance_due,
+        })
+    }
+}
+
+/// Rooms the move-out inspection found worse than at move-in, as draft
+/// deductions for the operator to price.
+pub fn suggested_deductions(comparison: &Value, report_id: &str) -> Vec<Value> {
+    comparison
+        .get("room_comparisons")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|room| room.get("degraded").and_then(Value::as_bool) == Some(true))
+        .map(|room| {
+            let name = room
+                .get("room")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown");
+            let defects = room
+                .get("current_defects")
+                .and_then(Value::as_array)
+                .map(|defects| {
+                    defects
+                        .iter()
+                        .filter_map(|defect| {
+                            defect.as_str().map(ToOwned::to_owned).or_else(|| {
+                                defect
+                                    .get("description")
+                                    .and_then(Value::as_str)
+                                    .map(ToOwned::to_owned)
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            let mut description = format!(
+                "{name}: condition {} at move-in, {} at move-out",
+                score(room.get("baseline_score")),
+                score(room.get("current_score"))
+            );
+            if !defects.is_empty() {
+                description.push_str(&format!(" ({})", defects.join("; ")));
+            }
+            json!({
+                "category": "damage",
+                "room": name,
+                "description": description,
+                "inspection_report_id": report_id,
+            })
+        })
+        .collect()
+}
+
+fn score(value: Option<&Value>) -> String {
+    let value = value.and_then(Value::as_f64).unwrap_or(0.0);
+    format!("{value:.1}")
+}
+
+/// The deposit's deductions, oldest first.
+pub async fn load_deductions(pool: &sqlx::PgPool, deposit_id: &str) -> AppResult<Vec<Value>> {
+    list_rows(
+        pool,
+        "lease_deposit_deductions",
+        Some(&serde_json::Map::from_iter([(
+            "deposit_id".to_string(),
+            Value::String(deposit_id.to_string()),
+        )])),
+        500,
+        0,
+        "created_at",
+        true,
+    )
+    .await
+}
+
+pub fn deductions_total(deductions: &[Value]) -> f64 {
+    round2(deductions.iter().map(|row| number(row, "amount")).sum())
+}
+
+/// Refund `amount` through the provider that took the deposit payment.
+/// `idempotency_key` names the local refund (see [`refund_idempotency_key`]).
+/// Returns the provider and its refund id.
+pub async fn refund_through_provider(
+    pool: &sqlx::PgPool,
+    http: &reqwest::Client,
+    config: &AppConfig,
+    lease_payment_id: &str,
+    amount: f64,
+    idempotency_key: &str,
+) -> AppResult<(String, String)> {
+    let payment = get_row(pool, "lease_payments", lease_payment_id, "id").await?;
+    let external_id = value_str(&payment, "external_id");
+    let currency = value_str(&payment, "currency");
+    if amount > number(&payment, "amount") + EPSILON {
+        return Err(AppError::UnprocessableEntity(
+            "The refund exceeds the original deposit payment.".to_string(),
+        ));
+    }
+
+    match value_str(&payment, "source").as_str() {
+        "stripe" => {
+            let session_id = external_id.strip_prefix("stripe:").unwrap_or(&external_id);
+            let refund =
+                payments::create_stripe_refund(
+                    http,
+                    config,
+                    session_id,
+                    amount,
+                    &currency,
+                    idempotency_key,
+                )
+                .await
+                .map_err(AppError::Dependency)?;
+            Ok(("stripe".to_string(), value_str(&refund, "id")))
+        }
+        "mercado_pago" => {
+            let payment_id = external_id.strip_prefix("mp:").unwrap_or(&external_id);
+            let organization_id = value_str(&payment, "organization_id");
+            let access_token = mercado_pago::get_org_mp_access_token(pool, &organization_id)
+                .await
+                .map_err(AppError::Dependency)?;
+            let refund = mercado_pago::create_mp_refund(
+                http,
+                &access_token,
+                payment_id,
+                amount,
+                idempotency_key,
+            )
+            .await
+            .map_err(AppError::Dependency)?;
+            let refund_id = refund
+                .get("id")
+                .map(|id| match id {
+                    Value::String(id) => id.clone(),
+                    other => other.to_string(),
+                })
+                .unwrap_or_default();
+            Ok(("mercado_pago".to_string(), refund_id))
+        }
+        other => Err(AppError::UnprocessableEntity(format!(
+            "Deposit payments from '{other}' cannot be refunded automatically; refund by bank transfer."
+        ))),
+    }
+}
+
+/// Provider idempotency key for a deposit's settlement refund. Each deposit
+/// is refunded once, so its id tells a retry apart from a separate refund
+/// of the same amount on the same payment.
+pub fn refund_idempotency_key(deposit_id: &str) -> String {
+    format!("deposit-refund-{deposit_id}")
+}
+
+/// Whether the payment came through a provider that can refund it.
+pub fn provider_refundable(payment: &Value) -> bool {
+    matches!(
+        value_str(payment, "source").as_str(),
+        "stripe" | "mercado_pago"
+    ) && !value_str(payment, "external_id").is_empty()
+}
+
+fn value_str(row: &Value, key: &str) -> String {
+    row.as_object()
+        .and_then(|obj| obj.get(key))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+fn number(row: &Value, key: &str) -> f64 {
+    match row.get(key) {
+        Some(Value::Number(value)) => value.as_f64().unwrap_or(0.0),
+        Some(Value::String(text)) => text.trim().parse().unwrap_or(0.0),
+        _ => 0.0,
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn interest_accrues_by_day() {
+        assert_eq!(
+            accrue_interest(3_650_000.0, 0.05, date(2026, 1, 1), date(2027, 1, 1)),
+            182_500.0
+        );
+        assert_eq!(
+            accrue_interest(1_000.0, 0.0365, date(2026, 3, 1), date(2026, 3, 11)),
+            1.0
+        );
+        assert_eq!(
+            accrue_interest(1_000.0, 0.05, date(2026, 3, 1), date(2026, 2, 1)),
+            0.0
+        );
+    }
+
+    #[test]
+    fn settlement_refunds_the_rest_or_reports_what_is_owed() {
+        let partial = Settlement::compute(3_000_000.0, 50_000.0, 800_000.0);
+        assert_eq!(partial.refund, 2_250_000.0);
+        assert_eq!(partial.applied, 800_000.0);
+        assert_eq!(partial.balance_due, 0.0);
+
+        let short = Settlement::compute(1_000_000.0, 0.0, 1_400_000.0);
+        assert_eq!(short.refund, 0.0);
+        assert_eq!(short.applied, 1_000_000.0);
+        assert_eq!(short.balance_due, 400_000.0);
+    }
+
+    #[test]
+    fn degraded_rooms_become_draft_deductions() {
+        let comparison = json!({
+            "room_comparisons": [
+                { "room": "Kitchen", "baseline_score": 4.0, "current_score": 2.0,
+                  "degraded": true, "current_defects": ["burn mark on counter", { "description": "broken tile" }] },
+                { "room": "Bedroom", "baseline_score": 4.0, "current_score": 4.0, "degraded": false },
+            ]
+        });
+        let suggestions = suggested_deductions(&comparison, "rep-1");
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0]["room"], "Kitchen");
+        assert_eq!(
+            suggestions[0]["description"],
+            "Kitchen: condition 4.0 at move-in, 2.0 at move-out (burn mark on counter; broken tile)"
+        );
+        assert!(
+            suggested_deductions(&json!("No baseline found for comparison."), "rep-1").is_empty()
+        );
+    }
+}
@@ -0,0 +1,776 @@
+//! Management fee schedules: the fee terms of an owner's management
+//! contract as a list of rules, evaluated against a statement period. Each
+//! computed fee carries the figures it was computed from and a one-line
+//! explanation for the statement.
+//!
+//! Rules are JSON objects with a `type`:
+//! - `percent_of_gross`: `rate` of gross revenue; `basis` is `all`
+//!   (default), `reservations` or `leases`.
+//! - `percent_of_net`: `rate` of gross revenue less platform fees and
+//!   operating expenses.
+//! - `flat_per_unit`: `amount` per unit per month, pro-rated by day.
+//! - `tiered_by_occupancy`: `tiers` of `{ min_occupancy, rate }` applied to
+//!   the gross (default) or net `basis`; the highest tier reached applies.
+//! - `leasing_fee`: per lease starting in the period, `percent_of_rent` of
+//!   its monthly rent or a flat `amount`; renewals only with
+//!   `include_renewals`.
+//! - `maintenance_markup`: `rate` on expenses in `categories` (default
+//!   `maintenance`).
+//!
+//! Any rule may set a `label`. Percentage rules also take `minimum` and
+//! `maximum`. Flat amounts and percentage bounds are in the schedule's
+//! currency.
+
+use chrono::{Datelike, NaiveDate};
+use serde_json::{json, Value};
+use sqlx::Row;
+
+use crate::error::{AppError, AppResult};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GrossBasis {
+    All,
+    Reservations,
+    Leases,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OccupancyTier {
+    pub min_occupancy: f64,
+    pub rate: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeeRule {
+    PercentOfGross {
+        rate: f64,
+        basis: GrossBasis,
+    },
+    PercentOfNet {
+        rate: f64,
+    },
+    FlatPerUnit {
+        amount: f64,
+    },
+    TieredByOccupancy {
+        tiers: Vec<OccupancyTier>,
+        on_net: bool,
+    },
+    LeasingFee {
+        percent_of_rent: Option<f64>,
+        amount: Option<f64>,
+        include_renewals: bool,
+    },
+    MaintenanceMarkup {
+        rate: f64,
+        categories: Vec<String>,
+    },
+}
+
+impl FeeRule {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::PercentOfGross { .. } => "percent_of_gross",
+            Self::PercentOfNet { .. } => "percent_of_net",
+            Self::FlatPerUnit { .. } => "flat_per_unit",
+            Self::TieredByOccupancy { .. } => "tiered_by_occupancy",
+            Self::LeasingFee { .. } => "leasing_fee",
+            Self::MaintenanceMarkup { .. } => "maintenance_markup",
+        }
+    }
+
+    fn default_label(&self) -> &'static str {
+        match self {
+            Self::PercentOfGross { .. } => "Management fee (gross)",
+            Self::PercentOfNet { .. } => "Management fee (net)",
+            Self::FlatPerUnit { .. } => "Management fee per unit",
+            Self::TieredByOccupancy { .. } => "Management fee (occupancy tier)",
+            Self::LeasingFee { .. } => "Leasing fee",
+            Self::MaintenanceMarkup { .. } => "Maintenance markup",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeRuleSpec {
+    pub rule: FeeRule,
+    pub label: String,
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+}
+
+/// Parse and validate the `rules` array of a fee schedule.
+pub fn parse_rules(rules: &Value) -> Result<Vec<FeeRuleSpec>, String> {
+    let Some(items) = rules.as_array() else {
+        return Err("rules must be an array.".to_string());
+    };
+    if items.is_empty() {
+        return Err("A fee schedule needs at least one rule.".to_string());
+    }
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            parse_rule(item).map_err(|message| format!("rules[{index}]: {message}"))
+        })
+        .collect()
+}
+
+fn parse_rule(item: &Value) -> Result<FeeRuleSpec, String> {
+    if !item.is_object() {
+        return Err("each rule must be an object.".to_string());
+    }
+    let rule = match item.get("type").and_then(Value::as_str).unwrap_or_default() {
+        "percent_of_gross" => FeeRule::PercentOfGross {
+            rate: rate(item, "rate")?,
+            basis: match item.get("basis").and_then(Value::as_str).unwrap_or("all") {
+                "all" => GrossBasis::All,
+                "reservations" => GrossBasis::Reservations,
+                "leases" => GrossBasis::Leases,
+                other => return Err(format!("unknown gross basis '{other}'.")),
+            },
+        },
+        "percent_of_net" => FeeRule::PercentOfNet {
+            rate: rate(item, "rate")?,
+        },
+        "flat_per_unit" => FeeRule::FlatPerUnit {
+            amount: amount(item, "amount")?,
+        },
+        "tiered_by_occupancy" => {
+            let mut tiers = item
+                .get("tiers")
+                .and_then(Value::as_array)
+                .filter(|tiers| !tiers.is_empty())
+                .ok_or("tiers must be a non-empty array.")?
+                .iter()
+                .map(|tier| {
+                    Ok(OccupancyTier {
+                        min_occupancy: rate(tier, "min_occupancy")?,
+                        rate: rate(tier, "rate")?,
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            tiers.sort_by(|a, b| a.min_occupancy.total_cmp(&b.min_occupancy));
+            FeeRule::TieredByOccupancy {
+                tiers,
+                on_net: match item.get("basis").and_then(Value::as_str).unwrap_or("gross") {
+                    "gross" => false,
+                    "net" => true,
+                    other => return Err(format!("unknown tier basis '{other}'.")),
+                },
+            }
+        }
+        "leasing_fee" => {
+            let percent_of_rent = optional(item, "percent_of_rent", rate)?;
+            let amount = optional(item, "amount", amount)?;
+            if percent_of_rent.is_some() == amount.is_some() {
+                return Err(
+                    "leasing_fee needs exactly one of percent_of_rent or amount.".to_string(),
+                );
+            }
+            FeeRule::LeasingFee {
+                percent_of_rent,
+                amount,
+                include_renewals: item
+                    .get("include_renewals")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+            }
+        }
+        "maintenance_markup" => FeeRule::MaintenanceMarkup {
+            rate: rate(item, "rate")?,
+            categories: match item.get("categories") {
+                None | Some(Value::Null) => vec!["maintenance".to_string()],
+                Some(Value::Array(values)) if !values.is_empty() => values
+                    .iter()
+                    .map(|value| {
+                        value
+                            .as_str()
+                            .map(|category| category.trim().to_ascii_lowercase())
+                            .filter(|category| !category.is_empty())
+                            .ok_or_else(|| "categories must be strings.".to_string())
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+                Some(_) => return Err("categories must be a non-empty array.".to_string()),
+            },
+        },
+        "" => return Err("type is required.".to_string()),
+        other => return Err(format!("unknown rule type '{other}'.")),
+    };
+
+    let minimum = optional(item, "minimum", amount)?;
+    let maximum = optional(item, "maximum", amount)?;
+    let is_percentage = matches!(
+        rule,
+        FeeRule::PercentOfGross { .. }
+            | FeeRule::PercentOfNet { .. }
+            | FeeRule::TieredByOccupancy { .. }
+    );
+    if !is_percentage && (minimum.is_some() || maximum.is_some()) {
+        return Err(format!("{} does not take minimum or maximum.", rule.kind()));
+    }
+    if let (Some(minimum), Some(maximum)) = (minimum, maximum) {
+        if minimum > maximum {
+            return Err("minimum cannot exceed maximum.".to_string());
+        }
+    }
+    let label = item
+        .get("label")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|label| !label.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| rule.default_label().to_string());
+
+    Ok(FeeRuleSpec {
+        rule,
+        label,
+        minimum,
+        maximum,
+    })
+}
+
+/// A rate as a fraction: 0.2 is 20%.
+fn rate(item: &Value, key: &str) -> Result<f64, String> {
+    match item.get(key).and_then(Value::as_f64) {
+        Some(value) if (0.0..=1.0).contains(&value) => Ok(value),
+        Some(_) => Err(format!("{key} must be a fraction between 0 and 1.")),
+        None => Err(format!("{key} is required.")),
+    }
+}
+
+fn amount(item: &Value, key: &str) -> Result<f64, String> {
+    match item.get(key).and_then(Value::as_f64) {
+        Some(value) if value >= 0.0 && value.is_finite() => Ok(value),
+        Some(_) => Err(format!("{key} cannot be negative.")),
+        None => Err(format!("{key} is required.")),
+    }
+}
+
+fn optional(
+    item: &Value,
+    key: &str,
+    parse: fn(&Value, &str) -> Result<f64, String>,
+) -> Result<Option<f64>, String> {
+    match item.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(_) => parse(item, key).map(Some),
+    }
+}
+
+/// The schedule a statement is evaluated against.
+#[derive(Debug, Clone)]
+pub struct FeeSchedule {
+    pub id: String,
+    pub name: String,
+    pub currency: String,
+    pub rules: Vec<FeeRuleSpec>,
+}
+
+/// The active schedule in effect during the period: the property's own
+/// schedule if it has one, otherwise the organization default. When several
+/// overlap the period the most recent `effective_from` wins.
+pub async fn applicable_schedule(
+    pool: &sqlx::PgPool,
+    organization_id: &str,
+    property_id: Option<&str>,
+    period_start: NaiveDate,
+    period_end: NaiveDate,
+) -> AppResult<Option<FeeSchedule>> {
+    let row = sqlx::query(
+        "SELECT id::text AS id, name, currency::text AS currency, rules
+         FROM management_fee_schedules
+         WHERE organization_id = $1::uuid
+           AND is_active
+           AND (property_id IS NULL OR property_id = $2::uuid)
+           AND effective_from <= $4::date
+           AND (effective_to IS NULL OR effective_to >= $3::date)
+         ORDER BY property_id IS NULL, effective_from DESC
+         LIMIT 1",
+    )
+    .bind(organization_id)
+    .bind(property_id)
+    .bind(period_start)
+    .bind(period_end)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        AppError::from_database_error(&e, "Could not load the management fee schedule.")
+    })?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let name: String = row.try_get("name").unwrap_or_default();
+    let rules = parse_rules(&row.try_get::<Value, _>("rules").unwrap_or(Value::Null)).map_err(
+        |message| {
+            AppError::UnprocessableEntity(format!(
+                "Management fee schedule '{name}' is invalid: {message}"
+            ))
+        },
+    )?;
+    Ok(Some(FeeSchedule {
+        id: row.try_get("id").unwrap_or_default(),
+        name,
+        currency: row
+            .try_get::<String, _>("currency")
+            .unwrap_or_else(|_| "PYG".to_string()),
+        rules,
+    }))
+}
+
+/// A lease that started in the statement period, rent in statement currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewLease {
+    pub lease_id: String,
+    pub monthly_rent: f64,
+    pub is_renewal: bool,
+}
+
+/// An expense of the period, amount in statement currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodExpense {
+    pub expense_id: String,
+    pub category: String,
+    pub amount: f64,
+}
+
+/// Period figures a schedule is evaluated against, all in the statement
+/// currency.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeeInputs {
+    pub currency: String,
+    pub reservation_revenue: f64,
+    pub lease_collections: f64,
+    pub platform_fees: f64,
+    pub operating_expenses: f64,
+    pub unit_count: usize,
+    /// Calendar months covered, pro-rated by day.
+    pub months: f64,
+    /// Booked unit-nights over available unit-nights, 0 to 1.
+    pub occupancy: f64,
+    pub new_leases: Vec<NewLease>,
+    pub expenses: Vec<PeriodExpense>,
+    /// Converts the schedule's flat amounts into the statement currency.
+    pub flat_amount_rate: f64,
+}
+
+impl FeeInputs {
+    fn gross(&self, basis: &GrossBasis) -> f64 {
+        match basis {
+            GrossBasis::All => self.reservation_revenue + self.lease_collections,
+            GrossBasis::Reservations => self.reservation_revenue,
+            GrossBasis::Leases => self.lease_collections,
+        }
+    }
+
+    fn net(&self) -> f64 {
+        self.gross(&GrossBasis::All) - self.platform_fees - self.operating_expenses
+    }
+}
+
+/// One fee line of a statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComputedFee {
+    pub rule_index: usize,
+    pub kind: &'static str,
+    pub label: String,
+    pub amount: f64,
+    pub basis_amount: f64,
+    pub rate: Option<f64>,
+    pub explanation: String,
+    /// The lease or expense the fee was charged on, if any.
+    pub source: Option<(&'static str, String)>,
+}
+
+impl ComputedFee {
+    pub fn line_item(&self, currency: &str, schedule_id: &str) -> Value {
+        let (source_table, source_id) = match &self.source {
+            Some((table, id)) => (*table, id.as_str()),
+            None => ("management_fee_schedules", schedule_id),
+        };
+        json!({
+            "bucket": "management_fees",
+            "source_table": source_table,
+            "source_id": source_id,
+            "kind": self.kind,
+            "label": self.label,
+            "amount": self.amount,
+            "currency": currency,
+            "basis_amount": self.basis_amount,
+            "rate": self.rate,
+            "rule_index": self.rule_index,
+            "explanation": self.explanation,
+        })
+    }
+}
+
+/// Compute every fee the rules charge for the period. Rules that charge
+/// nothing produce no lines.
+pub fn evaluate(rules: &[FeeRuleSpec], inputs: &FeeInputs) -> Vec<ComputedFee> {
+    let currency = inputs.currency.as_str();
+    let mut fees = Vec::new();
+    for (rule_index, spec) in rules.iter().enumerate() {
+        let fee =
+            |amount: f64, basis_amount: f64, rate: Option<f64>, explanation: String| ComputedFee {
+                rule_index,
+                kind: spec.rule.kind(),
+                label: spec.label.clone(),
+                amount: round2(amount),
+                basis_amount: round2(basis_amount),
+                rate,
+                explanation,
+                source: None,
+            };
+        match &spec.rule {
+            FeeRule::PercentOfGross { rate, basis } => {
+                let base = inputs.gross(basis).max(0.0);
+                let basis_label = match basis {
+                    GrossBasis::All => "gross revenue",
+                    GrossBasis::Reservations => "reservation revenue",
+                    GrossBasis::Leases => "lease collections",
+                };
+                fees.push(percent_fee(spec, &fee, *rate, base, basis_label, inputs));
+            }
+            FeeRule::PercentOfNet { rate } => {
+                let base = inputs.net().max(0.0);
+                fees.push(percent_fee(
+                    spec,
+                    &fee,
+                    *rate,
+                    base,
+                    "net revenue after platform fees and expenses",
+                    inputs,
+                ));
+            }
+            FeeRule::TieredByOccupancy { tiers, on_net } => {
+                let Some(tier) = tiers
+                    .iter()
+                    .rev()
+                    .find(|tier| inputs.occupancy + 1e-9 >= tier.min_occupancy)
+                else {
+                    continue;
+                };
+                let (base, basis_label) = if *on_net {
+                    (inputs.net().max(0.0), "net revenue")
+                } else {
+                    (inputs.gross(&GrossBasis::All).max(0.0), "gross revenue")
+                };
+                let mut computed = percent_fee(spec, &fee, tier.rate, base, basis_label, inputs);
+                computed.explanation = format!(
+                    "Occupancy {} reached the {} tier: {}",
+                    percent(inputs.occupancy),
+                    percent(tier.min_occupancy),
+                    computed.explanation
+                );
+                fees.push(computed);
+            }
+            FeeRule::FlatPerUnit { amount } => {
+                let per_unit = amount * inputs.flat_amount_rate;
+                let total = per_unit * inputs.unit_count as f64 * inputs.months;
+                fees.push(fee(
+                    total,
+                    inputs.unit_count as f64,
+                    None,
+                    format!(
+                        "{} per unit × {} units × {} months = {}",
+                        money(per_unit, currency),
+                        inputs.unit_count,
+                        decimal(inputs.months),
+                        money(total, currency)
+                    ),
+                ));
+            }
+            FeeRule::LeasingFee {
+                percent_of_rent,
+                amount,
+                include_renewals,
+            } => {
+                for lease in &inputs.new_leases {
+                    if lease.is_renewal && !include_renewals {
+                        continue;
+                    }
+                    let mut computed = match (percent_of_rent, amount) {
+                        (Some(rate), _) => {
+                            let total = lease.monthly_rent * rate;
+                            fee(
+                                total,
+                                lease.monthly_rent,
+                                Some(*rate),
+                                format!(
+                                    "{} of first month's rent {} = {}",
+                                    percent(*rate),
+                                    money(lease.monthly_rent, currency),
+                                    money(total, currency)
+                                ),
+                            )
+                        }
+                        (None, Some(flat)) => {
+                            let total = flat * inputs.flat_amount_rate;
+                            fee(
+                                total,
+                                lease.monthly_rent,
+                                None,
+                                format!("Flat fee for a new lease = {}", money(total, currency)),
+                            )
+                        }
+                        (None, None) => continue,
+                    };
+                    computed.source = Some(("leases", lease.lease_id.clone()));
+                    fees.push(computed);
+                }
+            }
+            FeeRule::MaintenanceMarkup { rate, categories } => {
+                for expense in &inputs.expenses {
+                    if !categories.contains(&expense.category) {
+                        continue;
+                    }
+                    let total = expense.amount * rate;
+                    let mut computed = fee(
+                        total,
+                        expense.amount,
+                        Some(*rate),
+                        format!(
+                            "{} markup on {} invoice {} = {}",
+                            percent(*rate),
+                            expense.category,
+                            money(expense.amount, currency),
+                            money(total, currency)
+                        ),
+                    );
+                    computed.source = Some(("expenses", expense.expense_id.clone()));
+                    fees.push(computed);
+                }
+            }
+        }
+    }
+    fees.retain(|fee| fee.amount.abs() >= 0.005);
+    fees
+}
+
+fn percent_fee(
+    spec: &FeeRuleSpec,
+    fee: &dyn Fn(f64, f64, Option<f64>, String) -> ComputedFee,
+    rate: f64,
+    base: f64,
+    basis_label: &str,
+    inputs: &FeeInputs,
+) -> ComputedFee {
+    let currency = inputs.currency.as_str();
+    let raw = base * rate;
+    let mut amount = raw;
+    let mut explanation = format!(
+        "{} of {basis_label} {} = {}",
+        percent(rate),
+        money(base, currency),
+        money(raw, currency)
+    );
+    // Bounds are set in the schedule's currency, like flat amounts.
+    let minimum = spec
+        .minimum
+        .map(|minimum| minimum * inputs.flat_amount_rate);
+    let maximum = spec
+        .maximum
+        .map(|maximum| maximum * inputs.flat_amount_rate);
+    if let Some(minimum) = minimum.filter(|minimum| raw < *minimum) {
+        amount = minimum;
+        explanation.push_str(&format!(
+            ", raised to the minimum {}",
+            money(minimum, currency)
+        ));
+    }
+    if let Some(maximum) = maximum.filter(|maximum| raw > *maximum) {
+        amount = maximum;
+        explanation.push_str(&format!(
+            ", capped at the maximum {}",
+            money(maximum, currency)
+        ));
+    }
+    fee(amount, base, Some(rate), explanation)
+}
+
+/// Calendar months between two dates inclusive, each month counted by the
+/// share of its days covered.
+pub fn months_in_period(start: NaiveDate, end: NaiveDate) -> f64 {
+    let mut months = 0.0;
+    let mut cursor = start;
+    while cursor <= end {
+        let first = NaiveDate::from_ymd_opt(cursor.year(), cursor.month(), 1).unwrap_or(cursor);
+        let next_month = first
+            .checked_add_months(chrono::Months::new(1))
+            .unwrap_or(first);
+        let days_in_month = (next_month - first).num_days() as f64;
+        let last_covered = end.min(next_month.pred_opt().unwrap_or(end));
+        months += ((last_covered - cursor).num_days() + 1) as f64 / days_in_month;
+        cursor = next_month;
+    }
+    months
+}
+
+fn money(value: f64, currency: &str) -> String {
+    if currency == "PYG" {
+        format!("{currency} {:.0}", value.round())
+    } else {
+        format!("{currency} {value:.2}")
+    }
+}
+
+fn percent(rate: f64) -> String {
+    format!("{}%", decimal(rate * 100.0))
+}
+
+/// At most two decimals, without trailing zeros.
+fn decimal(value: f64) -> String {
+    let text = format!("{value:.2}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs() -> FeeInputs {
+        FeeInputs {
+            currency: "PYG".to_string(),
+            reservation_revenue: 6_000_000.0,
+            lease_collections: 4_000_000.0,
+            platform_fees: 1_000_000.0,
+            operating_expenses: 2_000_000.0,
+            unit_count: 3,
+            months: 1.0,
+            occupancy: 0.85,
+            new_leases: vec![
+                NewLease {
+                    lease_id: "l1".to_string(),
+                    monthly_rent: 3_000_000.0,
+                    is_renewal: false,
+                },
+                NewLease {
+                    lease_id: "l2".to_string(),
+                    monthly_rent: 2_500_000.0,
+                    is_renewal: true,
+                },
+            ],
+            expenses: vec![
+                PeriodExpense {
+                    expense_id: "e1".to_string(),
+                    category: "maintenance".to_string(),
+                    amount: 800_000.0,
+                },
+                PeriodExpense {
+                    expense_id: "e2".to_string(),
+                    category: "utilities".to_string(),
+                    amount: 1_200_000.0,
+                },
+            ],
+            flat_amount_rate: 1.0,
+        }
+    }
+
+    #[test]
+    fn every_rule_type_computes_with_an_explanation() {
+        let rules = parse_rules(&json!([
+            { "type": "percent_of_gross", "rate": 0.1 },
+            { "type": "percent_of_net", "rate": 0.2, "label": "Net fee" },
+            { "type": "flat_per_unit", "amount": 150000 },
+            { "type": "tiered_by_occupancy", "tiers": [
+                { "min_occupancy": 0.8, "rate": 0.12 },
+                { "min_occupancy": 0, "rate": 0.15 }
+            ] },
+            { "type": "leasing_fee", "percent_of_rent": 0.5 },
+            { "type": "maintenance_markup", "rate": 0.1 }
+        ]))
+        .unwrap();
+        let fees = evaluate(&rules, &inputs());
+        let amounts: Vec<(&str, f64)> = fees.iter().map(|fee| (fee.kind, fee.amount)).collect();
+        assert_eq!(
+            amounts,
+            vec![
+                ("percent_of_gross", 1_000_000.0),
+                ("percent_of_net", 1_400_000.0),
+                ("flat_per_unit", 450_000.0),
+                ("tiered_by_occupancy", 1_200_000.0),
+                ("leasing_fee", 1_500_000.0),
+                ("maintenance_markup", 80_000.0),
+            ]
+        );
+        assert_eq!(fees[1].label, "Net fee");
+        assert_eq!(
+            fees[0].explanation,
+            "10% of gross revenue PYG 10000000 = PYG 1000000"
+        );
+        assert!(fees[3]
+            .explanation
+            .starts_with("Occupancy 85% reached the 80% tier"));
+        assert_eq!(fees[4].source, Some(("leases", "l1".to_string())));
+        assert_eq!(fees[5].source, Some(("expenses", "e1".to_string())));
+    }
+
+    #[test]
+    fn minimum_and_maximum_bound_percentage_fees() {
+        let rules = parse_rules(&json!([
+            { "type": "percent_of_net", "rate": 0.1, "minimum": 500000 },
+            { "type": "percent_of_gross", "rate": 0.5, "maximum": 2000000 }
+        ]))
+        .unwrap();
+        let fees = evaluate(&rules, &inputs());
+        assert_eq!(fees[0].amount, 700_000.0);
+        assert_eq!(fees[1].amount, 2_000_000.0);
+        assert!(fees[1]
+            .explanation
+            .ends_with("capped at the maximum PYG 2000000"));
+
+        let mut losing = inputs();
+        losing.operating_expenses = 20_000_000.0;
+        let fees = evaluate(&rules, &losing);
+        assert_eq!(fees[0].amount, 500_000.0);
+        assert_eq!(fees[0].basis_amount, 0.0);
+    }
+
+    #[test]
+    fn percentage_bounds_convert_from_the_schedule_currency() {
+        let rules = parse_rules(&json!([
+            { "type": "percent_of_net", "rate": 0.01, "minimum": 100 },
+            { "type": "percent_of_gross", "rate": 0.5, "maximum": 300 }
+        ]))
+        .unwrap();
+        let mut converted = inputs();
+        converted.flat_amount_rate = 7_500.0;
+        let fees = evaluate(&rules, &converted);
+        assert_eq!(fees[0].amount, 750_000.0);
+        assert!(fees[0]
+            .explanation
+            .ends_with("raised to the minimum PYG 750000"));
+        assert_eq!(fees[1].amount, 2_250_000.0);
+        assert!(fees[1]
+            .explanation
+            .ends_with("capped at the maximum PYG 2250000"));
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        assert!(parse_rules(&json!([])).is_err());
+        assert!(parse_rules(&json!([{ "type": "percent_of_gross", "rate": 20 }])).is_err());
+        assert!(
+            parse_rules(&json!([{ "type": "flat_per_unit", "amount": 1, "minimum": 2 }])).is_err()
+        );
+        assert!(parse_rules(&json!([{ "type": "leasing_fee" }])).is_err());
+        let error =
+            parse_rules(&json!([{ "type": "percent_of_net", "rate": 0.1 }, { "type": "bogus" }]))
+                .unwrap_err();
+        assert_eq!(error, "rules[1]: unknown rule type 'bogus'.");
+    }
+
+    #[test]
+    fn months_are_pro_rated_by_day() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(months_in_period(date(2026, 2, 1), date(2026, 2, 28)), 1.0);
+        assert_eq!(months_in_period(date(2026, 1, 1), date(2026, 3, 31)), 3.0);
+        let half = months_in_period(date(2026, 4, 16), date(2026, 4, 30));
+        assert!((half - 0.5).abs() < 1e-9);
+    }
+}
@@ -154,6 +154,49 @@ pub async fn get_mp_payment(
     }
 }
 
+/// Refund part or all of an approved Mercado Pago payment. `idempotency_key`
+/// identifies the local record being refunded, so a retried request is not
+/// refunded twice while separate refunds of the same amount still go through.
+pub async fn create_mp_refund(
+    http_client: &Client,
+    access_token: &str,
+    payment_id: &str,
+    amount: f64,
+    idempotency_key: &str,
+) -> Result<Value, String> {
+    let response = http_client
+        .post(format!("{MP_API_BASE}/v1/payments/{payment_id}/refunds"))
+        .bearer_auth(access_token)
+        .header("X-Idempotency-Key", idempotency_key)
+        .json(&json!({ "amount": amount }))
+        .send()
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Mercado Pago refund request failed");
+            "Mercado Pago refund request failed.".to_string()
+        })?;
+
+    let status = response.status();
+    let resp_body: Value = response
+        .json()
+        .await
+        .unwrap_or(json!({"error": "failed to parse response"}));
+
+    if status.is_success() {
+        Ok(json!({
+            "id": resp_body.get("id"),
+            "status": resp_body.get("status").and_then(Value::as_str).unwrap_or("unknown"),
+            "amount": resp_body.get("amount"),
+        }))
+    } else {
+        let error_msg = resp_body
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("Unknown Mercado Pago error");
+        Err(format!("Mercado Pago refund error ({status}): {error_msg}"))
+    }
+}
+
 /// Fetch the Mercado Pago access token for an organization from the integrations table.
 pub async fn get_org_mp_access_token(pool: &sqlx::PgPool, org_id: &str) -> Result<String, String> {
     let row: Option<(Option<String>,)> = sqlx::query_as(
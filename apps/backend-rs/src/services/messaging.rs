@@ -12,10 +12,12 @@ use crate::{
 /// Process all queued messages — poll `message_logs` where status = 'queued',
 /// send via appropriate channel, update status.
 /// Also retries failed messages with retry_count < 3.
+/// `org_id` limits both queues to one organization.
 pub async fn process_queued_messages(
     pool: &sqlx::PgPool,
     http_client: &Client,
     config: &AppConfig,
+    org_id: Option<&str>,
 ) -> (u32, u32) {
     let mut sent = 0u32;
     let mut failed = 0u32;
@@ -23,6 +25,12 @@ pub async fn process_queued_messages(
     // Fetch queued messages
     let mut filters = Map::new();
     filters.insert("status".to_string(), Value::String("queued".to_string()));
+    if let Some(org_id) = org_id {
+        filters.insert(
+            "organization_id".to_string(),
+            Value::String(org_id.to_string()),
+        );
+    }
 
     let mut messages = match list_rows(
         pool,
@@ -45,6 +53,12 @@ pub async fn process_queued_messages(
     // Also fetch failed messages with retry_count < 3 for retry
     let mut retry_filters = Map::new();
     retry_filters.insert("status".to_string(), Value::String("failed".to_string()));
+    if let Some(org_id) = org_id {
+        retry_filters.insert(
+            "organization_id".to_string(),
+            Value::String(org_id.to_string()),
+        );
+    }
 
     if let Ok(failed_msgs) = list_rows(
         pool,
@@ -1,3 +1,4 @@
+pub mod accounting_exports;
 pub mod agent_chats;
 pub mod agent_runs;
 pub mod agent_runtime_rollout;
@@ -10,7 +11,12 @@ pub mod airbnb;
 pub mod alerting;
 pub mod analytics;
 pub mod anomaly_detection;
+pub mod api_keys;
 pub mod audit;
+pub mod availability;
+pub mod bank_statements;
+pub mod booking_com;
+pub mod channel_connector;
 pub mod channel_optimizer;
 pub mod collection_cycle;
 pub mod cron;
@@ -21,10 +27,14 @@ pub mod enrichment;
 pub mod event_bus;
 pub mod expense_categorization;
 pub mod fx;
+pub mod general_ledger;
 pub mod ical;
+pub mod ical_parser;
 pub mod iot;
 pub mod json_helpers;
+pub mod late_fees;
 pub mod lease_abstraction;
+pub mod lease_deposits;
 pub mod lease_renewal;
 pub mod lease_schedule;
 pub mod leases;
@@ -32,6 +42,7 @@ pub mod leasing_agent;
 pub mod listings;
 pub mod llm_client;
 pub mod maintenance_dispatch;
+pub mod management_fees;
 #[allow(dead_code)]
 pub mod mercado_pago;
 pub mod messaging;
@@ -39,7 +50,14 @@ pub mod metering;
 pub mod ml_pipeline;
 pub mod notification_center;
 pub mod operations;
+pub mod outbound_webhooks;
+pub mod owner_payouts;
+pub mod owner_reserves;
+pub mod payment_ledger;
 pub mod payments;
+pub mod pdf;
+pub mod pdf_documents;
+pub mod permissions;
 pub mod plan_limits;
 pub mod portfolio;
 pub mod pricing;
@@ -48,7 +66,11 @@ pub mod reconciliation;
 pub mod reservations;
 pub mod scenario_simulation;
 pub mod scheduler;
+pub mod scheduler_leases;
+pub mod scheduler_runs;
 pub mod sequences;
+pub mod sifen;
+pub mod stay_restrictions;
 pub mod storage;
 pub mod tenant_screening;
 pub mod token_hash;
@@ -56,4 +78,6 @@ pub mod tool_validator;
 pub mod vision_ai;
 #[allow(dead_code)]
 pub mod voice_agent;
+pub mod webhook_verification;
 pub mod workflows;
+pub mod xml;
@@ -7,7 +7,7 @@ use sqlx::Row;
 use crate::{
     error::{AppError, AppResult},
     repository::table_service::{create_row, list_rows},
-    services::json_helpers::value_str,
+    services::{json_helpers::value_str, outbound_webhooks},
 };
 
 const DEFAULT_RECIPIENT_ROLES: &[&str] = &["owner_admin", "operator"];
@@ -92,8 +92,10 @@ pub async fn emit_event(
         .filter(|value| !value.is_empty())
         .map(ToOwned::to_owned);
 
+    let mut is_new = true;
     let event_row = if let Some(key) = dedupe_key.as_deref() {
         if let Some(existing) = find_event_by_dedupe_key(pool, key).await? {
+            is_new = false;
             existing
         } else {
             insert_event_row(
@@ -151,6 +153,22 @@ pub async fn emit_event(
     });
     notify_org_event(pool, organization_id, &notify_payload).await;
 
+    // Namespaced so they never collide with workflow trigger names.
+    if is_new {
+        let mut webhook_data = input.payload.clone();
+        webhook_data.insert("notification_event_id".to_string(), json!(&event_id));
+        webhook_data.insert("title".to_string(), json!(title));
+        webhook_data.insert("body".to_string(), json!(body));
+        webhook_data.insert("severity".to_string(), json!(severity));
+        outbound_webhooks::enqueue_event(
+            pool,
+            organization_id,
+            &format!("notification.{event_type}"),
+            &webhook_data,
+        )
+        .await;
+    }
+
     Ok(Some(event_row))
 }
 
@@ -7,13 +7,16 @@ use crate::{
     services::json_helpers::{non_empty_opt, value_str},
 };
 
+/// `property_scope` limits the overview to those properties for members
+/// restricted to property groups.
 pub async fn build_operations_overview(
     pool: &sqlx::PgPool,
     query: &OperationsOverviewQuery,
+    property_scope: Option<&[String]>,
 ) -> AppResult<Value> {
-    let summary = fetch_overview_summary(pool, query).await?;
-    let items = fetch_overview_items(pool, query).await?;
-    let attention_items = fetch_attention_items(pool, query).await?;
+    let summary = fetch_overview_summary(pool, query, property_scope).await?;
+    let items = fetch_overview_items(pool, query, property_scope).await?;
+    let attention_items = fetch_attention_items(pool, query, property_scope).await?;
     let ai_briefing_seed = build_ai_briefing_seed(&summary, &attention_items);
 
     Ok(json!({
@@ -50,6 +53,7 @@ struct OverviewSummary {
 async fn fetch_overview_summary(
     pool: &sqlx::PgPool,
     query: &OperationsOverviewQuery,
+    property_scope: Option<&[String]>,
 ) -> AppResult<OverviewSummary> {
     let mut builder = QueryBuilder::<Postgres>::new(
         "SELECT
@@ -77,7 +81,7 @@ async fn fetch_overview_summary(
 
     push_overview_base_select(&mut builder, query)?;
     builder.push(") base WHERE 1=1");
-    push_overview_outer_filters(&mut builder, query, false, "base");
+    push_overview_outer_filters(&mut builder, query, property_scope, false, "base");
 
     let row = builder
         .build()
@@ -98,11 +102,12 @@ async fn fetch_overview_summary(
 async fn fetch_overview_items(
     pool: &sqlx::PgPool,
     query: &OperationsOverviewQuery,
+    property_scope: Option<&[String]>,
 ) -> AppResult<Vec<Value>> {
     let mut builder = QueryBuilder::<Postgres>::new("SELECT row_to_json(t) AS row FROM (");
     push_overview_base_select(&mut builder, query)?;
     builder.push(") t WHERE 1=1");
-    push_overview_outer_filters(&mut builder, query, true, "t");
+    push_overview_outer_filters(&mut builder, query, property_scope, true, "t");
     push_overview_sort(&mut builder, query.sort.as_deref());
     builder
         .push(" LIMIT ")
@@ -126,6 +131,7 @@ async fn fetch_overview_items(
 async fn fetch_attention_items(
     pool: &sqlx::PgPool,
     query: &OperationsOverviewQuery,
+    property_scope: Option<&[String]>,
 ) -> AppResult<Vec<Value>> {
     let mut builder = QueryBuilder::<Postgres>::new("SELECT row_to_json(t) AS row FROM (");
     push_overview_base_select(&mut builder, query)?;
@@ -136,7 +142,7 @@ async fn fetch_attention_items(
             OR t.kind IN ('turnover', 'availability_conflict')
         )",
     );
-    push_overview_outer_filters(&mut builder, query, true, "t");
+    push_overview_outer_filters(&mut builder, query, property_scope, true, "t");
     push_overview_sort(&mut builder, query.sort.as_deref());
     builder.push(" LIMIT 6");
 
@@ -523,6 +529,7 @@ fn push_availability_conflict_select(
 fn push_overview_outer_filters(
     builder: &mut QueryBuilder<'_, Postgres>,
     query: &OperationsOverviewQuery,
+    property_scope: Option<&[String]>,
     include_view: bool,
     alias: &str,
 ) {
@@ -533,6 +540,14 @@ fn push_overview_outer_filters(
             .push(".kind = ")
             .push_bind(kind.to_ascii_lowercase());
     }
+    if let Some(property_ids) = property_scope {
+        builder
+            .push(" AND ")
+            .push(alias)
+            .push(".property_id = ANY(")
+            .push_bind(property_ids.to_vec())
+            .push(")");
+    }
 
     if !include_view {
         return;
@@ -0,0 +1,610 @@
+//! Outbound webhooks: organizations subscribe external systems (an ERP, a
+//! Zapier-style tool) to domain events. Every workflow trigger and
+//! notification event is matched against the org's subscriptions and
+//! queued in `webhook_deliveries`; the scheduler sends due deliveries signed
+//! with the subscription secret, retries failures with exponential backoff
+//! and disables a subscription whose endpoint keeps failing.
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration as StdDuration,
+};
+
+use chrono::{Duration, Utc};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use serde_json::{json, Map, Value};
+use sqlx::Row;
+
+use crate::{
+    error::{AppError, AppResult},
+    repository::table_service::{create_row, get_row, list_rows, update_row},
+    services::{scheduler_runs::JobOutcome, webhook_verification},
+    state::AppState,
+};
+
+/// Attempts per delivery before it is marked failed.
+pub const MAX_ATTEMPTS: i32 = 8;
+
+/// Consecutive failed attempts after which a subscription is disabled.
+pub const DISABLE_AFTER_FAILURES: i32 = 20;
+
+const BATCH_SIZE: i64 = 50;
+const REQUEST_TIMEOUT: StdDuration = StdDuration::from_secs(10);
+const RESPONSE_BODY_LIMIT: usize = 2000;
+
+/// Delay before retry number `attempt` (1-based): 30s doubling up to 6h.
+pub fn backoff(attempt: i32) -> Duration {
+    let exponent = attempt.saturating_sub(1).clamp(0, 16) as u32;
+    let seconds = 30_i64.saturating_mul(1_i64 << exponent);
+    Duration::seconds(seconds.min(6 * 3600))
+}
+
+/// Whether a subscription's `event_types` filter includes `event_type`.
+/// An empty filter or `*` matches everything; `prefix*` matches by prefix.
+pub fn matches_event(event_types: &Value, event_type: &str) -> bool {
+    let patterns: Vec<&str> = event_types
+        .as_array()
+        .map(|items| items.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    patterns.is_empty()
+        || patterns.iter().any(|pattern| {
+            let pattern = pattern.trim();
+            match pattern.strip_suffix('*') {
+                Some(prefix) => event_type.starts_with(prefix),
+                None => pattern == event_type,
+            }
+        })
+}
+
+/// Check a subscription URL: https only (http is allowed outside production
+/// for local receivers), and never an internal address in production. Host
+/// names are checked again when resolved at delivery time, see
+/// [`delivery_client`].
+pub fn validate_target_url(url: &str, allow_insecure: bool) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|_| "url is not valid.".to_string())?;
+    match parsed.scheme() {
+        "https" => {}
+        "http" if allow_insecure => {}
+        _ => return Err("url must use https.".to_string()),
+    }
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| "url must include a host.".to_string())?;
+    if !allow_insecure {
+        let internal = host.eq_ignore_ascii_case("localhost")
+            || host.ends_with(".internal")
+            || host.ends_with(".local")
+            || host
+                .trim_matches(|c| c == '[' || c == ']')
+                .parse::<IpAddr>()
+                .is_ok_and(is_internal_ip);
+        if internal {
+            return Err("url cannot point to an internal address.".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Addresses a production webhook may never reach: loopback, private,
+/// shared (CGNAT), link-local (cloud metadata), unspecified, multicast and
+/// the IPv6 equivalents, including IPv4-mapped forms.
+pub fn is_internal_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b))
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_internal_ip(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+/// The client deliveries are sent with. It never follows redirects, and in
+/// production its resolver refuses host names that resolve to an internal
+/// address, so a public name pointed at the private network is caught on
+/// every attempt rather than only when the subscription is saved.
+pub fn delivery_client(allow_internal: bool) -> reqwest::Result<reqwest::Client> {
+    let builder = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none());
+    if allow_internal {
+        builder.build()
+    } else {
+        builder.dns_resolver(Arc::new(PublicResolver)).build()
+    }
+}
+
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            if addrs.iter().any(|addr| is_internal_ip(addr.ip())) {
+                return Err(Box::new(InternalAddress) as Box<dyn std::error::Error + Send + Sync>);
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[derive(Debug)]
+struct InternalAddress;
+
+impl std::fmt::Display for InternalAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("host resolves to an internal address")
+    }
+}
+
+impl std::error::Error for InternalAddress {}
+
+/// The JSON body receivers get for an event.
+pub fn envelope(event_id: &str, organization_id: &str, event_type: &str, data: &Value) -> Value {
+    json!({
+        "id": event_id,
+        "type": event_type,
+        "organization_id": organization_id,
+        "created_at": Utc::now().to_rfc3339(),
+        "data": data,
+    })
+}
+
+/// Queue `event_type` for every active subscription of the org that wants
+/// it. Failures are logged: publishing must never break the caller.
+pub async fn enqueue_event(
+    pool: &sqlx::PgPool,
+    organization_id: &str,
+    event_type: &str,
+    data: &Map<String, Value>,
+) {
+    if let Err(error) = try_enqueue_event(pool, organization_id, event_type, data).await {
+        tracing::warn!(
+            organization_id,
+            event_type,
+            ?error,
+            "Failed to queue outbound webhooks"
+        );
+    }
+}
+
+async fn try_enqueue_event(
+    pool: &sqlx::PgPool,
+    organization_id: &str,
+    event_type: &str,
+    data: &Map<String, Value>,
+) -> AppResult<usize> {
+    if organization_id.trim().is_empty() || event_type.trim().is_empty() {
+        return Ok(0);
+    }
+    let mut filters = Map::new();
+    filters.insert(
+        "organization_id".to_string(),
+        Value::String(organization_id.to_string()),
+    );
+    filters.insert("is_active".to_string(), Value::Bool(true));
+    let subscriptions = list_rows(
+        pool,
+        "webhook_subscriptions",
+        Some(&filters),
+        100,
+        0,
+        "created_at",
+        true,
+    )
+    .await?;
+
+    let matching: Vec<&Value> = subscriptions
+        .iter()
+        .filter(|subscription| {
+            matches_event(
+                subscription.get("event_types").unwrap_or(&Value::Null),
+                event_type,
+            )
+        })
+        .collect();
+    if matching.is_empty() {
+        return Ok(0);
+    }
+
+    let event_id = uuid::Uuid::new_v4().to_string();
+    let payload = envelope(
+        &event_id,
+        organization_id,
+        event_type,
+        &Value::Object(data.clone()),
+    );
+    for subscription in &matching {
+        let mut record = Map::new();
+        record.insert(
+            "organization_id".to_string(),
+            Value::String(organization_id.to_string()),
+        );
+        record.insert(
+            "subscription_id".to_string(),
+            Value::String(value_str(subscription, "id")),
+        );
+        record.insert("event_id".to_string(), Value::String(event_id.clone()));
+        record.insert(
+            "event_type".to_string(),
+            Value::String(event_type.to_string()),
+        );
+        record.insert("payload".to_string(), payload.clone());
+        create_row(pool, "webhook_deliveries", &record).await?;
+    }
+    Ok(matching.len())
+}
+
+/// Queue a fresh attempt of a past delivery with the same event and body.
+pub async fn replay_delivery(pool: &sqlx::PgPool, delivery: &Value) -> AppResult<Value> {
+    let mut record = Map::new();
+    for key in [
+        "organization_id",
+        "subscription_id",
+        "event_id",
+        "event_type",
+        "payload",
+    ] {
+        if let Some(value) = delivery.get(key) {
+            record.insert(key.to_string(), value.clone());
+        }
+    }
+    record.insert(
+        "replay_of_delivery_id".to_string(),
+        Value::String(value_str(delivery, "id")),
+    );
+    create_row(pool, "webhook_deliveries", &record).await
+}
+
+/// Send the deliveries that are due. Called by the scheduler.
+pub async fn process_due_deliveries(state: &AppState) -> JobOutcome {
+    let Some(pool) = state.db_pool.as_ref() else {
+        return Ok(Value::Null);
+    };
+
+    // Claim a batch; deliveries stuck in `sending` by a crashed instance
+    // are picked up again after ten minutes.
+    let claimed = sqlx::query(
+        "UPDATE webhook_deliveries
+         SET status = 'sending', last_attempt_at = now()
+         WHERE id IN (
+           SELECT id FROM webhook_deliveries
+           WHERE (status = 'pending' AND next_attempt_at <= now())
+              OR (status = 'sending' AND last_attempt_at < now() - interval '10 minutes')
+           ORDER BY next_attempt_at
+           LIMIT $1
+           FOR UPDATE SKIP LOCKED
+         )
+         RETURNING row_to_json(webhook_deliveries.*) AS row",
+    )
+    .bind(BATCH_SIZE)
+    .fetch_all(pool)
+    .await
+    .map_err(|error| format!("Failed to claim webhook deliveries: {error}"))?;
+
+    let mut succeeded = 0;
+    let mut failed = 0;
+    for row in claimed {
+        let Ok(delivery) = row.try_get::<Value, _>("row") else {
+            continue;
+        };
+        match send_delivery(state, pool, &delivery).await {
+            Ok(true) => succeeded += 1,
+            Ok(false) => failed += 1,
+            Err(error) => {
+                failed += 1;
+                tracing::warn!(
+                    delivery_id = %value_str(&delivery, "id"),
+                    ?error,
+                    "Failed to record webhook delivery attempt"
+                );
+            }
+        }
+    }
+
+    Ok(json!({ "succeeded": succeeded, "failed": failed }))
+}
+
+/// One attempt at a claimed delivery. Returns whether the receiver accepted it.
+async fn send_delivery(state: &AppState, pool: &sqlx::PgPool, delivery: &Value) -> AppResult<bool> {
+    let delivery_id = value_str(delivery, "id");
+    let subscription_id = value_str(delivery, "subscription_id");
+    let subscription = match get_row(pool, "webhook_subscriptions", &subscription_id, "id").await {
+        Ok(subscription) if subscription.get("is_active") == Some(&Value::Bool(true)) => {
+            subscription
+        }
+        Ok(_) | Err(AppError::NotFound(_)) => {
+            update_row(
+                pool,
+                "webhook_deliveries",
+                &delivery_id,
+                &json_map(json!({
+                    "status": "cancelled",
+                    "error_message": "Subscription is disabled.",
+                })),
+                "id",
+            )
+            .await?;
+            return Ok(false);
+        }
+        Err(error) => return Err(error),
+    };
+
+    let body = delivery
+        .get("payload")
+        .cloned()
+        .unwrap_or(Value::Null)
+        .to_string();
+    let timestamp = Utc::now().timestamp();
+    let signature = webhook_verification::sign_payload(
+        &value_str(&subscription, "secret"),
+        timestamp,
+        body.as_bytes(),
+    );
+
+    // Subscriptions saved before a URL rule existed are held to it here too.
+    let url = value_str(&subscription, "url");
+    let response = match validate_target_url(&url, !state.config.is_production()) {
+        Ok(()) => state
+            .webhook_http_client
+            .post(url)
+            .header("content-type", "application/json")
+            .header("user-agent", "Casaora-Webhooks/1.0")
+            .header(webhook_verification::SIGNATURE_HEADER, signature)
+            .header(
+                webhook_verification::TIMESTAMP_HEADER,
+                timestamp.to_string(),
+            )
+            .header(
+                webhook_verification::EVENT_ID_HEADER,
+                value_str(delivery, "event_id"),
+            )
+            .header("x-casaora-event-type", value_str(delivery, "event_type"))
+            .header("x-casaora-delivery-id", &delivery_id)
+            .body(body)
+            .send()
+            .await
+            .map_err(|error| request_error(&error)),
+        Err(error) => Err(error),
+    };
+
+    let (response_status, response_body, error_message) = match response {
+        Ok(response) => {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            let error = (!status.is_success()).then(|| format!("Receiver responded {status}."));
+            (Some(status.as_u16()), truncate(&text), error)
+        }
+        Err(error) => (None, String::new(), Some(error)),
+    };
+
+    let attempt_count = delivery
+        .get("attempt_count")
+        .and_then(Value::as_i64)
+        .unwrap_or(0) as i32
+        + 1;
+    let mut patch = json_map(json!({
+        "attempt_count": attempt_count,
+        "response_status": response_status,
+        "response_body": response_body,
+        "error_message": error_message,
+    }));
+
+    let Some(error_message) = error_message else {
+        patch.insert("status".to_string(), json!("succeeded"));
+        patch.insert("delivered_at".to_string(), json!(Utc::now().to_rfc3339()));
+        update_row(pool, "webhook_deliveries", &delivery_id, &patch, "id").await?;
+        sqlx::query(
+            "UPDATE webhook_subscriptions
+             SET consecutive_failures = 0, last_delivery_at = now(), last_success_at = now()
+             WHERE id = $1::uuid",
+        )
+        .bind(&subscription_id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Failed to update webhook subscription."))?;
+        return Ok(true);
+    };
+
+    if attempt_count >= MAX_ATTEMPTS {
+        patch.insert("status".to_string(), json!("failed"));
+    } else {
+        patch.insert("status".to_string(), json!("pending"));
+        patch.insert(
+            "next_attempt_at".to_string(),
+            json!((Utc::now() + backoff(attempt_count)).to_rfc3339()),
+        );
+    }
+    update_row(pool, "webhook_deliveries", &delivery_id, &patch, "id").await?;
+
+    let disabled: Option<(i32,)> = sqlx::query_as(
+        "UPDATE webhook_subscriptions
+         SET consecutive_failures = consecutive_failures + 1,
+             last_delivery_at = now(),
+             is_active = consecutive_failures + 1 < $2,
+             disabled_at = CASE WHEN consecutive_failures + 1 >= $2 THEN now() ELSE disabled_at END,
+             disabled_reason = CASE WHEN consecutive_failures + 1 >= $2 THEN $3 ELSE disabled_reason END
+         WHERE id = $1::uuid
+         RETURNING consecutive_failures",
+    )
+    .bind(&subscription_id)
+    .bind(DISABLE_AFTER_FAILURES)
+    .bind(format!(
+        "Disabled after {DISABLE_AFTER_FAILURES} consecutive failed deliveries. Last error: {error_message}"
+    ))
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Failed to update webhook subscription."))?;
+
+    if disabled.is_some_and(|(failures,)| failures == DISABLE_AFTER_FAILURES) {
+        tracing::warn!(
+            subscription_id,
+            "Webhook subscription disabled after repeated failures"
+        );
+        sqlx::query(
+            "UPDATE webhook_deliveries
+             SET status = 'cancelled', error_message = 'Subscription was disabled.'
+             WHERE subscription_id = $1::uuid AND status = 'pending'",
+        )
+        .bind(&subscription_id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Failed to cancel webhook deliveries."))?;
+    }
+
+    Ok(false)
+}
+
+fn request_error(error: &reqwest::Error) -> String {
+    let mut source = std::error::Error::source(error);
+    while let Some(inner) = source {
+        if inner.is::<InternalAddress>() {
+            return "url cannot point to an internal address.".to_string();
+        }
+        source = inner.source();
+    }
+    if error.is_timeout() {
+        "Receiver timed out.".to_string()
+    } else if error.is_connect() {
+        "Could not connect to receiver.".to_string()
+    } else {
+        "Request to receiver failed.".to_string()
+    }
+}
+
+fn truncate(text: &str) -> String {
+    text.chars().take(RESPONSE_BODY_LIMIT).collect()
+}
+
+fn json_map(value: Value) -> Map<String, Value> {
+    value.as_object().cloned().unwrap_or_default()
+}
+
+fn value_str(row: &Value, key: &str) -> String {
+    row.as_object()
+        .and_then(|obj| obj.get(key))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        assert_eq!(backoff(1), Duration::seconds(30));
+        assert_eq!(backoff(2), Duration::seconds(60));
+        assert_eq!(backoff(5), Duration::seconds(480));
+        assert_eq!(backoff(30), Duration::seconds(6 * 3600));
+    }
+
+    #[test]
+    fn event_filters_match_exactly_or_by_prefix() {
+        assert!(matches_event(&json!([]), "payment_received"));
+        assert!(matches_event(&Value::Null, "payment_received"));
+        assert!(matches_event(&json!(["*"]), "lease_created"));
+        assert!(matches_event(&json!(["payment_*"]), "payment_received"));
+        assert!(matches_event(
+            &json!(["reservation_confirmed", "lease_created"]),
+            "lease_created"
+        ));
+        assert!(!matches_event(&json!(["payment_*"]), "lease_created"));
+    }
+
+    #[test]
+    fn target_urls_must_be_public_https_in_production() {
+        assert!(validate_target_url("https://erp.example.com/hooks", false).is_ok());
+        assert!(validate_target_url("http://erp.example.com/hooks", false).is_err());
+        assert!(validate_target_url("https://localhost/hooks", false).is_err());
+        assert!(validate_target_url("https://10.0.0.4/hooks", false).is_err());
+        assert!(validate_target_url("https://[::1]/hooks", false).is_err());
+        assert!(validate_target_url("https://169.254.169.254/latest", false).is_err());
+        assert!(validate_target_url("https://[::ffff:10.0.0.1]/hooks", false).is_err());
+        assert!(validate_target_url("http://localhost:4000/hooks", true).is_ok());
+        assert!(validate_target_url("not a url", true).is_err());
+    }
+
+    #[test]
+    fn internal_ranges_cover_ipv4_and_ipv6_forms() {
+        for internal in [
+            "0.0.0.0",
+            "10.1.2.3",
+            "100.64.0.1",
+            "100.127.255.254",
+            "127.0.0.1",
+            "169.254.169.254",
+            "172.16.0.1",
+            "192.168.1.1",
+            "::1",
+            "::",
+            "fc00::1",
+            "fd12:3456::1",
+            "fe80::1",
+            "::ffff:10.0.0.1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(is_internal_ip(internal.parse().unwrap()), "{internal}");
+        }
+        for public in [
+            "8.8.8.8",
+            "100.128.0.1",
+            "2606:4700::1111",
+            "::ffff:1.1.1.1",
+        ] {
+            assert!(!is_internal_ip(public.parse().unwrap()), "{public}");
+        }
+    }
+
+    #[tokio::test]
+    async fn resolver_refuses_names_that_resolve_internally() {
+        let name: Name = "localhost".parse().unwrap();
+        let error = PublicResolver.resolve(name).await.err().expect("refused");
+        assert!(error.is::<InternalAddress>());
+    }
+
+    #[tokio::test]
+    async fn delivery_client_does_not_follow_redirects() {
+        use axum::{response::Redirect, routing::post, Router};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind receiver");
+        let addr = listener.local_addr().expect("receiver address");
+        let app = Router::new()
+            .route(
+                "/hook",
+                post(|| async { Redirect::temporary("http://169.254.169.254/latest") }),
+            )
+            .route("/latest", post(|| async { "metadata" }));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let response = delivery_client(true)
+            .unwrap()
+            .post(format!("http://{addr}/hook"))
+            .send()
+            .await
+            .expect("receiver responds");
+        assert_eq!(response.status(), reqwest::StatusCode::TEMPORARY_REDIRECT);
+    }
+}
@@ -0,0 +1,592 @@
+//! Owner payout batches: grouping finalized statements into one transfer
+//! per owner bank account, and rendering the batch as a bulk-transfer file
+//! for online banking, either as a generic CSV or in a bank's fixed-width
+//! layout configured per organization.
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde_json::Value;
+
+use crate::services::accounting_exports::csv_row;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayoutStatus {
+    Scheduled,
+    Sent,
+    Confirmed,
+    Failed,
+}
+
+impl PayoutStatus {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "scheduled" => Some(Self::Scheduled),
+            "sent" => Some(Self::Sent),
+            "confirmed" => Some(Self::Confirmed),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Scheduled => "scheduled",
+            Self::Sent => "sent",
+            Self::Confirmed => "confirmed",
+            Self::Failed => "failed",
+        }
+    }
+
+    /// Batches move forward only: scheduled → sent → confirmed, and any
+    /// unconfirmed batch may fail.
+    pub fn can_become(self, next: Self) -> bool {
+        matches!(
+            (self, next),
+            (Self::Scheduled, Self::Sent)
+                | (Self::Sent, Self::Confirmed)
+                | (Self::Scheduled | Self::Sent, Self::Failed)
+        )
+    }
+}
+
+// ---------- Grouping ----------
+
+/// A finalized statement ready to be paid, with the owner's bank account
+/// from its property.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementPayout {
+    pub statement_id: String,
+    pub payee_name: String,
+    pub payee_tax_id: String,
+    pub bank_name: String,
+    pub account_number: String,
+    pub account_type: String,
+    pub currency: String,
+    pub net_payout: f64,
+    pub management_fees: f64,
+    /// Held back from the payout into the owner's reserve.
+    pub reserve_amount: f64,
+}
+
+impl StatementPayout {
+    pub fn amount(&self) -> f64 {
+        round2(self.net_payout - self.reserve_amount)
+    }
+}
+
+/// One bank transfer: every statement of a batch paid to the same account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferLine {
+    pub sequence: u32,
+    pub payee_name: String,
+    pub payee_tax_id: String,
+    pub bank_name: String,
+    pub account_number: String,
+    pub account_type: String,
+    pub currency: String,
+    pub amount: f64,
+    pub statement_ids: Vec<String>,
+}
+
+/// Merge statements paid into the same account into numbered transfers,
+/// ordered by payee name.
+pub fn group_transfers(statements: &[StatementPayout]) -> Vec<TransferLine> {
+    let mut groups: BTreeMap<(String, String, String), TransferLine> = BTreeMap::new();
+    for statement in statements {
+        let key = (
+            statement.payee_name.to_lowercase(),
+            normalize_account(&statement.account_number),
+            statement.currency.clone(),
+        );
+        let line = groups.entry(key).or_insert_with(|| TransferLine {
+            sequence: 0,
+            payee_name: statement.payee_name.clone(),
+            payee_tax_id: statement.payee_tax_id.clone(),
+            bank_name: statement.bank_name.clone(),
+            account_number: normalize_account(&statement.account_number),
+            account_type: statement.account_type.clone(),
+            currency: statement.currency.clone(),
+            amount: 0.0,
+            statement_ids: Vec::new(),
+        });
+        line.amount = round2(line.amount + statement.amount());
+        line.statement_ids.push(statement.statement_id.clone());
+    }
+    groups
+        .into_values()
+        .enumerate()
+        .map(|(index, mut line)| {
+            line.sequence = index as u32 + 1;
+            line
+        })
+        .collect()
+}
+
+/// Account numbers as banks expect them: digits and letters only.
+pub fn normalize_account(value: &str) -> String {
+    value.chars().filter(char::is_ascii_alphanumeric).collect()
+}
+
+// ---------- Files ----------
+
+/// Batch-level values available to file headers and trailers.
+#[derive(Debug, Clone, Default)]
+pub struct BatchFile {
+    pub reference: String,
+    pub payment_date: NaiveDate,
+    pub currency: String,
+    pub origin_name: String,
+    pub origin_tax_id: String,
+    pub origin_account: String,
+}
+
+/// Generic bulk-transfer CSV accepted by most Paraguayan online banking
+/// portals' manual mapping.
+pub fn render_generic_csv(batch: &BatchFile, lines: &[TransferLine]) -> String {
+    let mut out = csv_row(&[
+        "sequence",
+        "payee_name",
+        "payee_tax_id",
+        "bank_name",
+        "account_number",
+        "account_type",
+        "currency",
+        "amount",
+        "reference",
+        "payment_date",
+    ]);
+    for line in lines {
+        out.push_str(&csv_row(&[
+            &line.sequence.to_string(),
+            &line.payee_name,
+            &line.payee_tax_id,
+            &line.bank_name,
+            &line.account_number,
+            &line.account_type,
+            &line.currency,
+            &format_amount(line.amount, &line.currency),
+            &format!("{}-{}", batch.reference, line.sequence),
+            &batch.payment_date.format("%Y-%m-%d").to_string(),
+        ]));
+    }
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// Where a fixed-width field takes its value from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldSource {
+    Literal(String),
+    Sequence,
+    PayeeName,
+    PayeeTaxId,
+    BankName,
+    AccountNumber,
+    AccountType,
+    Amount,
+    Currency,
+    Reference,
+    BatchReference,
+    PaymentDate,
+    OriginName,
+    OriginTaxId,
+    OriginAccount,
+    RecordCount,
+    TotalAmount,
+}
+
+impl FieldSource {
+    fn parse(value: &str) -> Option<Self> {
+        Some(match value {
+            "sequence" => Self::Sequence,
+            "payee_name" => Self::PayeeName,
+            "payee_tax_id" => Self::PayeeTaxId,
+            "bank_name" => Self::BankName,
+            "account_number" => Self::AccountNumber,
+            "account_type" => Self::AccountType,
+            "amount" => Self::Amount,
+            "currency" => Self::Currency,
+            "reference" => Self::Reference,
+            "batch_reference" => Self::BatchReference,
+            "payment_date" => Self::PaymentDate,
+            "origin_name" => Self::OriginName,
+            "origin_tax_id" => Self::OriginTaxId,
+            "origin_account" => Self::OriginAccount,
+            "record_count" => Self::RecordCount,
+            "total_amount" => Self::TotalAmount,
+            _ => return None,
+        })
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Self::Sequence | Self::Amount | Self::RecordCount | Self::TotalAmount
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSpec {
+    pub source: FieldSource,
+    pub width: usize,
+    pub align: Align,
+    pub pad: char,
+    /// Implied decimals for amounts (`12345` = 123.45 with 2).
+    pub decimals: u32,
+    /// chrono format for dates.
+    pub date_format: String,
+}
+
+/// A bank's fixed-width layout: an optional header and trailer record and
+/// one detail record per transfer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedWidthLayout {
+    pub header: Vec<FieldSpec>,
+    pub detail: Vec<FieldSpec>,
+    pub trailer: Vec<FieldSpec>,
+    pub line_ending: &'static str,
+}
+
+impl FixedWidthLayout {
+    /// Layout from its stored JSON records. Each field is an object such as
+    /// `{"field": "amount", "width": 15, "decimals": 2}` or
+    /// `{"value": "D", "width": 1}` for a literal.
+    pub fn from_json(
+        header: &Value,
+        detail: &Value,
+        trailer: &Value,
+        line_ending: &str,
+    ) -> Result<Self, String> {
+        let layout = Self {
+            header: parse_record(header, "header")?,
+            detail: parse_record(detail, "detail")?,
+            trailer: parse_record(trailer, "trailer")?,
+            line_ending: if line_ending == "lf" { "\n" } else { "\r\n" },
+        };
+        if layout.detail.is_empty() {
+            return Err("The detail record needs at least one field.".to_string());
+        }
+        Ok(layout)
+    }
+
+    pub fn render(&self, batch: &BatchFile, lines: &[TransferLine]) -> Result<String, String> {
+        let total: f64 = lines.iter().map(|line| line.amount).sum();
+        let mut out = String::new();
+        if !self.header.is_empty() {
+            out.push_str(&render_record(
+                &self.header,
+                batch,
+                None,
+                lines.len(),
+                total,
+            )?);
+            out.push_str(self.line_ending);
+        }
+        for line in lines {
+            out.push_str(&render_record(
+                &self.detail,
+                batch,
+                Some(line),
+                lines.len(),
+                total,
+            )?);
+            out.push_str(self.line_ending);
+        }
+        if !self.trailer.is_empty() {
+            out.push_str(&render_record(
+                &self.trailer,
+                batch,
+                None,
+                lines.len(),
+                total,
+            )?);
+            out.push_str(self.line_ending);
+        }
+        Ok(out)
+    }
+}
+
+fn parse_record(value: &Value, record: &str) -> Result<Vec<FieldSpec>, String> {
+    let Some(fields) = value.as_array() else {
+        return if value.is_null() {
+            Ok(Vec::new())
+        } else {
+            Err(format!("{record} must be an array of fields."))
+        };
+    };
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let position = index + 1;
+            let source = match (
+                field.get("value").and_then(Value::as_str),
+                field.get("field").and_then(Value::as_str),
+            ) {
+                (Some(literal), _) => FieldSource::Literal(literal.to_string()),
+                (None, Some(name)) => FieldSource::parse(name)
+                    .ok_or_else(|| format!("{record} field {position}: unknown field '{name}'."))?,
+                (None, None) => {
+                    return Err(format!(
+                        "{record} field {position} needs a 'field' or a 'value'."
+                    ))
+                }
+            };
+            let width = field
+                .get("width")
+                .and_then(Value::as_u64)
+                .filter(|width| (1..=200).contains(width))
+                .ok_or_else(|| {
+                    format!("{record} field {position}: width must be between 1 and 200.")
+                })? as usize;
+            let align = match field.get("align").and_then(Value::as_str) {
+                Some("left") => Align::Left,
+                Some("right") => Align::Right,
+                None if source.is_numeric() => Align::Right,
+                None => Align::Left,
+                Some(other) => {
+                    return Err(format!(
+                        "{record} field {position}: align '{other}' must be left or right."
+                    ))
+                }
+            };
+            let pad = match field.get("pad").and_then(Value::as_str) {
+                Some(pad) if pad.chars().count() == 1 => pad.chars().next().unwrap_or(' '),
+                Some(_) => {
+                    return Err(format!(
+                        "{record} field {position}: pad must be a single character."
+                    ))
+                }
+                None if source.is_numeric() => '0',
+                None => ' ',
+            };
+            Ok(FieldSpec {
+                source,
+                width,
+                align,
+                pad,
+                decimals: field
+                    .get("decimals")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(0)
+                    .min(4) as u32,
+                date_format: field
+                    .get("format")
+                    .and_then(Value::as_str)
+                    .unwrap_or("%Y%m%d")
+                    .to_string(),
+            })
+        })
+        .collect()
+}
+
+fn render_record(
+    fields: &[FieldSpec],
+    batch: &BatchFile,
+    line: Option<&TransferLine>,
+    record_count: usize,
+    total: f64,
+) -> Result<String, String> {
+    let mut out = String::new();
+    for field in fields {
+        let text = |value: Option<&str>| value.map(transliterate).unwrap_or_default();
+        let value = match &field.source {
+            FieldSource::Literal(value) => value.clone(),
+            FieldSource::Sequence => line.map(|l| l.sequence.to_string()).unwrap_or_default(),
+            FieldSource::PayeeName => text(line.map(|l| l.payee_name.as_str())),
+            FieldSource::PayeeTaxId => text(line.map(|l| l.payee_tax_id.as_str())),
+            FieldSource::BankName => text(line.map(|l| l.bank_name.as_str())),
+            FieldSource::AccountNumber => text(line.map(|l| l.account_number.as_str())),
+            FieldSource::AccountType => text(line.map(|l| l.account_type.as_str())),
+            FieldSource::Amount => implied_decimals(line.map_or(0.0, |l| l.amount), field.decimals),
+            FieldSource::Currency => line.map_or(batch.currency.clone(), |l| l.currency.clone()),
+            FieldSource::Reference => line
+                .map(|l| format!("{}-{}", batch.reference, l.sequence))
+                .unwrap_or_else(|| batch.reference.clone()),
+            FieldSource::BatchReference => batch.reference.clone(),
+            FieldSource::PaymentDate => batch.payment_date.format(&field.date_format).to_string(),
+            FieldSource::OriginName => transliterate(&batch.origin_name),
+            FieldSource::OriginTaxId => batch.origin_tax_id.clone(),
+            FieldSource::OriginAccount => normalize_account(&batch.origin_account),
+            FieldSource::RecordCount => record_count.to_string(),
+            FieldSource::TotalAmount => implied_decimals(total, field.decimals),
+        };
+        let length = value.chars().count();
+        if length > field.width {
+            if field.source.is_numeric() {
+                return Err(format!(
+                    "{value} does not fit in a {}-character field.",
+                    field.width
+                ));
+            }
+            out.extend(value.chars().take(field.width));
+            continue;
+        }
+        let padding: String = std::iter::repeat_n(field.pad, field.width - length).collect();
+        match field.align {
+            Align::Left => {
+                out.push_str(&value);
+                out.push_str(&padding);
+            }
+            Align::Right => {
+                out.push_str(&padding);
+                out.push_str(&value);
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn implied_decimals(amount: f64, decimals: u32) -> String {
+    format!("{:.0}", (amount * 10_f64.powi(decimals as i32)).round())
+}
+
+/// Upper-case ASCII, as bank files are read by legacy systems that reject
+/// accents and `ñ`.
+fn transliterate(value: &str) -> String {
+    value
+        .chars()
+        .map(|ch| match ch {
+            'á' | 'à' | 'ä' | 'â' | 'Á' | 'À' | 'Ä' | 'Â' => 'A',
+            'é' | 'è' | 'ë' | 'ê' | 'É' | 'È' | 'Ë' | 'Ê' => 'E',
+            'í' | 'ì' | 'ï' | 'î' | 'Í' | 'Ì' | 'Ï' | 'Î' => 'I',
+            'ó' | 'ò' | 'ö' | 'ô' | 'Ó' | 'Ò' | 'Ö' | 'Ô' => 'O',
+            'ú' | 'ù' | 'ü' | 'û' | 'Ú' | 'Ù' | 'Ü' | 'Û' => 'U',
+            'ñ' | 'Ñ' => 'N',
+            'ç' | 'Ç' => 'C',
+            ch if ch.is_ascii_graphic() || ch == ' ' => ch.to_ascii_uppercase(),
+            _ => ' ',
+        })
+        .collect()
+}
+
+/// Guaraníes have no decimals; other currencies use two.
+fn format_amount(value: f64, currency: &str) -> String {
+    if currency == "PYG" {
+        format!("{:.0}", value.round())
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn statement(id: &str, name: &str, account: &str, net: f64) -> StatementPayout {
+        StatementPayout {
+            statement_id: id.to_string(),
+            payee_name: name.to_string(),
+            payee_tax_id: "1234567".to_string(),
+            bank_name: "Banco Itaú".to_string(),
+            account_number: account.to_string(),
+            account_type: "savings".to_string(),
+            currency: "PYG".to_string(),
+            net_payout: net,
+            management_fees: 0.0,
+            reserve_amount: 0.0,
+        }
+    }
+
+    fn batch() -> BatchFile {
+        BatchFile {
+            reference: "PAY-20261017-01".to_string(),
+            payment_date: NaiveDate::from_ymd_opt(2026, 10, 17).unwrap(),
+            currency: "PYG".to_string(),
+            origin_name: "Casaora S.A.".to_string(),
+            origin_tax_id: "80069563-1".to_string(),
+            origin_account: "0012-345678".to_string(),
+        }
+    }
+
+    #[test]
+    fn statements_to_the_same_account_are_one_transfer() {
+        let mut held = statement("s3", "Ana Núñez", "77-001", 500_000.0);
+        held.reserve_amount = 100_000.0;
+        let lines = group_transfers(&[
+            statement("s1", "Ana Núñez", "77001", 1_000_000.0),
+            statement("s2", "Carlos Ortiz", "55002", 2_000_000.0),
+            held,
+        ]);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].sequence, 1);
+        assert_eq!(lines[0].payee_name, "Ana Núñez");
+        assert_eq!(lines[0].amount, 1_400_000.0);
+        assert_eq!(lines[0].statement_ids, vec!["s1", "s3"]);
+        assert_eq!(lines[1].sequence, 2);
+
+        let csv = render_generic_csv(&batch(), &lines);
+        assert!(csv.contains("1,Ana Núñez,1234567,Banco Itaú,77001,savings,PYG,1400000,PAY-20261017-01-1,2026-10-17\r\n"));
+    }
+
+    #[test]
+    fn fixed_width_layouts_pad_and_total() {
+        let layout = FixedWidthLayout::from_json(
+            &json!([
+                { "value": "H", "width": 1 },
+                { "field": "origin_account", "width": 12 },
+                { "field": "payment_date", "width": 8, "format": "%d%m%Y" },
+            ]),
+            &json!([
+                { "value": "D", "width": 1 },
+                { "field": "account_number", "width": 8 },
+                { "field": "payee_name", "width": 10 },
+                { "field": "amount", "width": 12, "decimals": 2 },
+            ]),
+            &json!([
+                { "value": "T", "width": 1 },
+                { "field": "record_count", "width": 4 },
+                { "field": "total_amount", "width": 12, "decimals": 2 },
+            ]),
+            "crlf",
+        )
+        .unwrap();
+        let lines = group_transfers(&[
+            statement("s1", "Ana Núñez", "77001", 1_000_000.0),
+            statement("s2", "Carlos Ortiz Benítez", "55002", 2_500_000.5),
+        ]);
+        let file = layout.render(&batch(), &lines).unwrap();
+        let records: Vec<&str> = file.split("\r\n").collect();
+        assert_eq!(records[0], "H0012345678  17102026");
+        assert_eq!(records[1], "D77001   ANA NUNEZ 000100000000");
+        assert_eq!(records[2], "D55002   CARLOS ORT000250000050");
+        assert_eq!(records[3], "T0002000350000050");
+
+        let too_small = FixedWidthLayout::from_json(
+            &Value::Null,
+            &json!([{ "field": "amount", "width": 3 }]),
+            &Value::Null,
+            "lf",
+        )
+        .unwrap();
+        assert!(too_small.render(&batch(), &lines).is_err());
+        assert!(FixedWidthLayout::from_json(
+            &Value::Null,
+            &json!([{ "field": "iban", "width": 3 }]),
+            &Value::Null,
+            "lf",
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn statuses_only_move_forward() {
+        assert!(PayoutStatus::Scheduled.can_become(PayoutStatus::Sent));
+        assert!(PayoutStatus::Sent.can_become(PayoutStatus::Confirmed));
+        assert!(PayoutStatus::Sent.can_become(PayoutStatus::Failed));
+        assert!(!PayoutStatus::Scheduled.can_become(PayoutStatus::Confirmed));
+        assert!(!PayoutStatus::Confirmed.can_become(PayoutStatus::Failed));
+    }
+}
@@ -0,0 +1,475 @@
+//! Owner reserves and contributions. A property can hold an operating
+//! reserve that statements top up until it reaches its target. When a
+//! period's expenses exceed its revenue, the statement draws on the reserve
+//! and carries any deficit left into the owner's next statement. The owner
+//! covers a deficit by paying a contribution, which is credited to the
+//! reserve and so settles the deficit on the next statement.
+
+use serde_json::{json, Value};
+use sqlx::Row;
+
+use crate::{
+    error::{AppError, AppResult},
+    services::general_ledger,
+};
+
+/// Amounts below this are rounding noise.
+const EPSILON: f64 = 0.005;
+
+/// A property's reserve settings.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ReservePolicy {
+    pub target: f64,
+    /// Most a single statement may withhold; `None` tops up in one go.
+    pub top_up_cap: Option<f64>,
+}
+
+/// How a statement settles with the owner's balance and reserve.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StatementBalances {
+    /// Deficit carried in from the previous statement (zero or negative).
+    pub previous_balance: f64,
+    pub reserve_draw: f64,
+    pub reserve_top_up: f64,
+    pub net_payout: f64,
+    /// Deficit the owner still owes after this statement (zero or negative).
+    pub balance_carried_forward: f64,
+}
+
+impl StatementBalances {
+    /// Settle a period's net result. A deficit, including one carried in, is
+    /// covered from the reserve first and carried forward for the rest; a
+    /// surplus tops the reserve up to its target before it is paid out.
+    pub fn settle(
+        period_net: f64,
+        previous_balance: f64,
+        reserve_balance: f64,
+        policy: ReservePolicy,
+    ) -> Self {
+        let previous_balance = round2(previous_balance.min(0.0));
+        let available = period_net + previous_balance;
+        if available < -EPSILON {
+            let reserve_draw = round2(reserve_balance.max(0.0).min(-available));
+            return Self {
+                previous_balance,
+                reserve_draw,
+                reserve_top_up: 0.0,
+                net_payout: 0.0,
+                balance_carried_forward: round2((available + reserve_draw).min(0.0)),
+            };
+        }
+
+        let available = available.max(0.0);
+        let shortfall = (policy.target - reserve_balance).max(0.0);
+        let mut reserve_top_up = shortfall.min(available);
+        if let Some(cap) = policy.top_up_cap {
+            reserve_top_up = reserve_top_up.min(cap.max(0.0));
+        }
+        let reserve_top_up = round2(reserve_top_up);
+        Self {
+            previous_balance,
+            reserve_draw: 0.0,
+            reserve_top_up,
+            net_payout: round2(available - reserve_top_up),
+            balance_carried_forward: 0.0,
+        }
+    }
+
+    /// The figures stored on an owner statement row.
+    pub fn from_statement(statement: &Value) -> Self {
+        Self {
+            previous_balance: number(statement, "previous_balance"),
+            reserve_draw: number(statement, "reserve_draw"),
+            reserve_top_up: number(statement, "reserve_top_up"),
+            net_payout: number(statement, "net_payout"),
+            balance_carried_forward: number(statement, "balance_carried_forward"),
+        }
+    }
+
+    /// Net payout these balances give for a period's net result.
+    pub fn payout_for(&self, period_net: f64) -> f64 {
+        round2(
+            period_net + self.previous_balance + self.reserve_draw
+                - self.reserve_top_up
+                - self.balance_carried_forward,
+        )
+    }
+
+    pub fn to_json(self) -> Value {
+        json!({
+            "previous_balance": self.previous_balance,
+            "reserve_draw": self.reserve_draw,
+            "reserve_top_up": self.reserve_top_up,
+            "net_payout": self.net_payout,
+            "balance_carried_forward": self.balance_carried_forward,
+        })
+    }
+}
+
+/// Settle a new statement's `period_net` against the deficit carried out of
+/// the owner's previous non-draft statement with the same scope and
+/// currency, and against the property's reserve in that currency.
+pub async fn statement_balances(
+    pool: &sqlx::PgPool,
+    organization_id: &str,
+    property_id: Option<&str>,
+    unit_id: Option<&str>,
+    period_start: &str,
+    currency: &str,
+    period_net: f64,
+) -> AppResult<StatementBalances> {
+    let property_id = property_id.map(str::trim).filter(|value| !value.is_empty());
+    let unit_id = unit_id.map(str::trim).filter(|value| !value.is_empty());
+
+    let previous_balance: f64 = sqlx::query_scalar(
+        "SELECT balance_carried_forward::float8
+         FROM owner_statements
+         WHERE organization_id = $1::uuid
+           AND property_id IS NOT DISTINCT FROM $2::uuid
+           AND unit_id IS NOT DISTINCT FROM $3::uuid
+           AND currency = $4
+           AND status <> 'draft'
+           AND period_end < $5::date
+         ORDER BY period_end DESC
+         LIMIT 1",
+    )
+    .bind(organization_id)
+    .bind(property_id)
+    .bind(unit_id)
+    .bind(currency)
+    .bind(period_start)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load the previous statement."))?
+    .unwrap_or(0.0);
+
+    let reserve = sqlx::query(
+        "SELECT p.reserve_target_amount::float8 AS target,
+                p.reserve_top_up_cap::float8 AS top_up_cap,
+                COALESCE((
+                  SELECT SUM(e.amount)
+                  FROM owner_reserve_entries e
+                  WHERE e.property_id = p.id AND e.currency = $4
+                ), 0)::float8 AS balance
+         FROM properties p
+         WHERE p.organization_id = $1::uuid
+           AND p.id = COALESCE(
+             $2::uuid,
+             (SELECT u.property_id FROM units u WHERE u.id = $3::uuid)
+           )",
+    )
+    .bind(organization_id)
+    .bind(property_id)
+    .bind(unit_id)
+    .bind(currency)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load the owner reserve."))?;
+
+    let (policy, reserve_balance) = match reserve {
+        Some(row) => (
+            ReservePolicy {
+                target: row.try_get("target").unwrap_or(0.0),
+                top_up_cap: row.try_get("top_up_cap").ok().flatten(),
+            },
+            row.try_get("balance").unwrap_or(0.0),
+        ),
+        None => (ReservePolicy::default(), 0.0),
+    };
+
+    Ok(StatementBalances::settle(
+        period_net,
+        previous_balance,
+        reserve_balance,
+        policy,
+    ))
+}
+
+/// Move a finalized statement's top-up and draw into its property's
+/// reserve. Safe to repeat: each statement moves the reserve once.
+pub async fn record_statement_reserve(
+    pool: &sqlx::PgPool,
+    statement: &Value,
+    user_id: Option<&str>,
+) -> AppResult<()> {
+    let statement_id = value_str(statement, "id");
+    for (entry_type, amount) in [
+        ("top_up", number(statement, "reserve_top_up")),
+        ("draw", -number(statement, "reserve_draw")),
+    ] {
+        if amount.abs() < EPSILON {
+            continue;
+        }
+        let entry: Option<Value> = sqlx::query_scalar(
+            "INSERT INTO owner_reserve_entries (
+               organization_id, property_id, currency, entry_type, amount,
+               owner_statement_id, created_by_user_id
+             )
+             SELECT os.organization_id, COALESCE(os.property_id, u.property_id), os.currency,
+                    $2, $3, os.id, $4::uuid
+             FROM owner_statements os
+             LEFT JOIN units u ON u.id = os.unit_id
+             WHERE os.id = $1::uuid
+               AND COALESCE(os.property_id, u.property_id) IS NOT NULL
+             ON CONFLICT DO NOTHING
+             RETURNING to_jsonb(owner_reserve_entries.*)",
+        )
+        .bind(&statement_id)
+        .bind(entry_type)
+        .bind(round2(amount))
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Could not update the owner reserve."))?;
+        if let Some(entry) = entry {
+            general_ledger::record_reserve_entry(pool, &entry, user_id).await;
+        }
+    }
+    Ok(())
+}
+
+/// Add a manual movement to a property's reserve, such as releasing part
+/// of it back to the owner (negative) or funding it from the owner balance.
+pub async fn record_adjustment(
+    pool: &sqlx::PgPool,
+    organization_id: &str,
+    property_id: &str,
+    currency: &str,
+    amount: f64,
+    notes: Option<&str>,
+    user_id: Option<&str>,
+) -> AppResult<Value> {
+    let entry: Value = sqlx::query_scalar(
+        "INSERT INTO owner_reserve_entries (
+           organization_id, property_id, currency, entry_type, amount, notes,
+           created_by_user_id
+         )
+         VALUES ($1::uuid, $2::uuid, $3, 'adjustment', $4, $5, $6::uuid)
+         RETURNING to_jsonb(owner_reserve_entries.*)",
+    )
+    .bind(organization_id)
+    .bind(property_id)
+    .bind(currency)
+    .bind(round2(amount))
+    .bind(notes)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not adjust the owner reserve."))?;
+    general_ledger::record_reserve_entry(pool, &entry, user_id).await;
+    Ok(entry)
+}
+
+/// Mark a requested contribution paid and credit it to the reserve.
+/// Returns `None` when the contribution was not waiting for payment, so a
+/// repeated provider notification changes nothing.
+pub async fn record_contribution_payment(
+    pool: &sqlx::PgPool,
+    contribution_id: &str,
+    amount: Option<f64>,
+    payment_reference: Option<&str>,
+    user_id: Option<&str>,
+) -> AppResult<Option<Value>> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Could not start a transaction."))?;
+
+    let updated: Option<Value> = sqlx::query_scalar(
+        "UPDATE owner_contributions
+         SET status = 'paid',
+             paid_amount = COALESCE($2, amount),
+             paid_at = now(),
+             payment_reference = $3
+         WHERE id = $1::uuid AND status = 'requested'
+         RETURNING to_jsonb(owner_contributions.*)",
+    )
+    .bind(contribution_id)
+    .bind(amount.filter(|amount| *amount > 0.0).map(round2))
+    .bind(payment_reference)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not record the contribution."))?;
+    let Some(updated) = updated else {
+        return Ok(None);
+    };
+
+    let entry: Option<Value> = sqlx::query_scalar(
+        "INSERT INTO owner_reserve_entries (
+           organization_id, property_id, currency, entry_type, amount,
+           owner_contribution_id, notes, created_by_user_id
+         )
+         SELECT organization_id, property_id, currency, 'contribution', paid_amount,
+                id, reason, $2::uuid
+         FROM owner_contributions
+         WHERE id = $1::uuid
+         ON CONFLICT DO NOTHING
+         RETURNING to_jsonb(owner_reserve_entries.*)",
+    )
+    .bind(contribution_id)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not update the owner reserve."))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Could not record the contribution."))?;
+
+    if let Some(entry) = entry {
+        general_ledger::record_reserve_entry(pool, &entry, user_id).await;
+    }
+    Ok(Some(updated))
+}
+
+/// Settle the contribution a provider payment refers to, if any. Returns
+/// whether `reference_code` belongs to a contribution.
+pub async fn settle_contribution_by_reference(
+    pool: &sqlx::PgPool,
+    reference_code: &str,
+    amount: Option<f64>,
+    payment_reference: &str,
+) -> AppResult<bool> {
+    let contribution_id: Option<String> =
+        sqlx::query_scalar("SELECT id::text FROM owner_contributions WHERE reference_code = $1")
+            .bind(reference_code)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::from_database_error(&e, "Could not load the contribution."))?;
+    let Some(contribution_id) = contribution_id else {
+        return Ok(false);
+    };
+    record_contribution_payment(
+        pool,
+        &contribution_id,
+        amount,
+        Some(payment_reference),
+        None,
+    )
+    .await?;
+    Ok(true)
+}
+
+/// Reserve target, balance and open contributions of each property that has
+/// a reserve target or reserve activity, in its reporting currency.
+pub async fn reserve_balances(
+    pool: &sqlx::PgPool,
+    organization_id: &str,
+    property_id: Option<&str>,
+) -> AppResult<Vec<Value>> {
+    let rows: Vec<Value> = sqlx::query_scalar(
+        "WITH scoped AS (
+           SELECT p.id, p.name, p.reserve_target_amount, p.reserve_top_up_cap,
+                  COALESCE(p.owner_reporting_currency, o.default_currency, 'PYG')::text AS currency
+           FROM properties p
+           JOIN organizations o ON o.id = p.organization_id
+           WHERE p.organization_id = $1::uuid
+             AND ($2::uuid IS NULL OR p.id = $2::uuid)
+         )
+         SELECT jsonb_build_object(
+                  'property_id', s.id,
+                  'property_name', s.name,
+                  'currency', s.currency,
+                  'reserve_target_amount', s.reserve_target_amount,
+                  'reserve_top_up_cap', s.reserve_top_up_cap,
+                  'balance', COALESCE(b.balance, 0),
+                  'shortfall', GREATEST(s.reserve_target_amount - COALESCE(b.balance, 0), 0),
+                  'open_contributions', COALESCE(c.total, 0)
+                )
+         FROM scoped s
+         LEFT JOIN LATERAL (
+           SELECT SUM(e.amount) AS balance, COUNT(*) AS entries
+           FROM owner_reserve_entries e
+           WHERE e.property_id = s.id AND e.currency = s.currency
+         ) b ON true
+         LEFT JOIN LATERAL (
+           SELECT SUM(oc.amount) AS total
+           FROM owner_contributions oc
+           WHERE oc.property_id = s.id AND oc.status = 'requested'
+         ) c ON true
+         WHERE s.reserve_target_amount > 0 OR b.entries > 0 OR c.total IS NOT NULL
+         ORDER BY s.name",
+    )
+    .bind(organization_id)
+    .bind(property_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load owner reserves."))?;
+    Ok(rows)
+}
+
+fn number(row: &Value, key: &str) -> f64 {
+    match row.get(key) {
+        Some(Value::Number(value)) => value.as_f64().unwrap_or(0.0),
+        Some(Value::String(value)) => value.trim().parse().unwrap_or(0.0),
+        _ => 0.0,
+    }
+}
+
+fn value_str(row: &Value, key: &str) -> String {
+    row.get(key)
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned)
+        .unwrap_or_default()
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: ReservePolicy = ReservePolicy {
+        target: 1_000_000.0,
+        top_up_cap: Some(300_000.0),
+    };
+
+    #[test]
+    fn surpluses_top_up_the_reserve_within_the_cap() {
+        let balances = StatementBalances::settle(2_000_000.0, 0.0, 800_000.0, POLICY);
+        assert_eq!(balances.reserve_top_up, 200_000.0);
+        assert_eq!(balances.net_payout, 1_800_000.0);
+
+        let balances = StatementBalances::settle(2_000_000.0, 0.0, 0.0, POLICY);
+        assert_eq!(balances.reserve_top_up, 300_000.0);
+        assert_eq!(balances.net_payout, 1_700_000.0);
+
+        let uncapped = ReservePolicy {
+            top_up_cap: None,
+            ..POLICY
+        };
+        let balances = StatementBalances::settle(400_000.0, 0.0, 0.0, uncapped);
+        assert_eq!(balances.reserve_top_up, 400_000.0);
+        assert_eq!(balances.net_payout, 0.0);
+        assert_eq!(balances.payout_for(400_000.0), 0.0);
+    }
+
+    #[test]
+    fn deficits_draw_on_the_reserve_and_carry_forward() {
+        let balances = StatementBalances::settle(-500_000.0, 0.0, 200_000.0, POLICY);
+        assert_eq!(balances.reserve_draw, 200_000.0);
+        assert_eq!(balances.net_payout, 0.0);
+        assert_eq!(balances.balance_carried_forward, -300_000.0);
+        assert_eq!(balances.payout_for(-500_000.0), 0.0);
+
+        // Next period: the deficit is settled before anything is paid out
+        // or put back into the empty reserve.
+        let next = StatementBalances::settle(1_000_000.0, -300_000.0, 0.0, POLICY);
+        assert_eq!(next.previous_balance, -300_000.0);
+        assert_eq!(next.reserve_top_up, 300_000.0);
+        assert_eq!(next.net_payout, 400_000.0);
+        assert_eq!(next.balance_carried_forward, 0.0);
+        assert_eq!(next.payout_for(1_000_000.0), next.net_payout);
+    }
+
+    #[test]
+    fn a_paid_contribution_covers_the_carried_deficit() {
+        // The owner paid 300,000 into the reserve after a -300,000 statement.
+        let balances = StatementBalances::settle(0.0, -300_000.0, 300_000.0, POLICY);
+        assert_eq!(balances.reserve_draw, 300_000.0);
+        assert_eq!(balances.balance_carried_forward, 0.0);
+        assert_eq!(balances.net_payout, 0.0);
+    }
+}
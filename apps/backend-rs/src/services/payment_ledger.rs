@@ -0,0 +1,850 @@
+use chrono::{NaiveDate, Utc};
+use serde_json::{json, Map, Value};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+
+use crate::{
+    error::{AppError, AppResult},
+    repository::table_service::{get_row, update_row},
+    services::general_ledger::{self, JournalLine, NewEntry},
+};
+
+/// Balances below this are treated as settled.
+const EPSILON: f64 = 0.005;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentSource {
+    Manual,
+    Stripe,
+    MercadoPago,
+    BankImport,
+}
+
+impl PaymentSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::Stripe => "stripe",
+            Self::MercadoPago => "mercado_pago",
+            Self::BankImport => "bank_import",
+        }
+    }
+
+    /// `payment_method` recorded when the caller does not give one.
+    fn default_method(self) -> Option<&'static str> {
+        match self {
+            Self::Manual => None,
+            Self::Stripe => Some("card"),
+            Self::MercadoPago => Some("other"),
+            Self::BankImport => Some("bank_transfer"),
+        }
+    }
+}
+
+/// A payment to record in `lease_payments`.
+#[derive(Debug, Clone)]
+pub struct NewPayment {
+    pub organization_id: String,
+    pub lease_id: String,
+    pub source: PaymentSource,
+    pub amount: f64,
+    pub currency: String,
+    pub payment_method: Option<String>,
+    /// RFC 3339 timestamp or `YYYY-MM-DD`; defaults to now.
+    pub received_at: Option<String>,
+    pub reference: Option<String>,
+    /// Provider id used to make webhook retries and re-imports idempotent.
+    pub external_id: Option<String>,
+    pub bank_transaction_id: Option<String>,
+    pub notes: Option<String>,
+    pub created_by_user_id: Option<String>,
+}
+
+/// An open collection record and what is still owed on it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenBalance {
+    pub collection_id: String,
+    pub balance: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Allocation {
+    pub collection_id: String,
+    pub amount: f64,
+    pub settled: bool,
+}
+
+impl Allocation {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "collection_id": self.collection_id,
+            "amount": self.amount,
+            "settled": self.settled,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordedPayment {
+    pub payment: Value,
+    pub allocations: Vec<Allocation>,
+    /// Left on the payment as credit for future collections.
+    pub credit: f64,
+    /// The external id was already recorded; nothing new was allocated.
+    pub duplicate: bool,
+}
+
+impl RecordedPayment {
+    pub fn allocated_to(&self, collection_id: &str) -> f64 {
+        self.allocations
+            .iter()
+            .filter(|allocation| allocation.collection_id == collection_id)
+            .map(|allocation| allocation.amount)
+            .sum()
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "payment": self.payment,
+            "allocations": self.allocations.iter().map(Allocation::to_json).collect::<Vec<_>>(),
+            "credit": self.credit,
+            "duplicate": self.duplicate,
+        })
+    }
+}
+
+/// Split `available` across `open`: `targets` first in the order given,
+/// then everything else in the order of `open` (oldest first). Returns the
+/// allocations and what is left over.
+pub fn plan_allocations(
+    available: f64,
+    open: &[OpenBalance],
+    targets: &[String],
+) -> (Vec<(String, f64)>, f64) {
+    let ordered = targets
+        .iter()
+        .filter_map(|target| open.iter().find(|item| &item.collection_id == target))
+        .chain(
+            open.iter()
+                .filter(|item| !targets.contains(&item.collection_id)),
+        );
+
+    let mut remaining = round2(available);
+    let mut planned = Vec::new();
+    for item in ordered {
+        if remaining < EPSILON {
+            break;
+        }
+        let amount = round2(remaining.min(item.balance));
+        if amount < EPSILON {
+            continue;
+        }
+        planned.push((item.collection_id.clone(), amount));
+        remaining = round2(remaining - amount);
+    }
+    (planned, remaining.max(0.0))
+}
+
+/// Record a received payment and allocate it across the lease's open
+/// collections: `targets` first, then collections already due, oldest
+/// first. Any remainder stays on the payment as credit. Recording the same
+/// `external_id` twice returns the original payment.
+pub async fn record_payment(
+    pool: &PgPool,
+    payment: &NewPayment,
+    targets: &[String],
+) -> AppResult<RecordedPayment> {
+    if !payment.amount.is_finite() || payment.amount <= 0.0 {
+        return Err(AppError::BadRequest(
+            "Payment amount must be greater than zero.".to_string(),
+        ));
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Dependency(format!("txn begin: {e}")))?;
+
+    let payment_method = payment
+        .payment_method
+        .clone()
+        .or_else(|| payment.source.default_method().map(ToOwned::to_owned));
+    let inserted = sqlx::query(
+        "INSERT INTO lease_payments (
+            organization_id, lease_id, source, payment_method, amount, currency,
+            unallocated_amount, received_at, reference, external_id,
+            bank_transaction_id, notes, created_by_user_id
+         ) VALUES (
+            $1::uuid, $2::uuid, $3, $4::payment_method, $5, $6,
+            $5, COALESCE($7::timestamptz, now()), $8, $9,
+            $10::uuid, $11, $12::uuid
+         )
+         ON CONFLICT (organization_id, external_id) WHERE external_id IS NOT NULL DO NOTHING
+         RETURNING id::text",
+    )
+    .bind(&payment.organization_id)
+    .bind(&payment.lease_id)
+    .bind(payment.source.as_str())
+    .bind(payment_method.as_deref())
+    .bind(round2(payment.amount))
+    .bind(&payment.currency)
+    .bind(payment.received_at.as_deref())
+    .bind(payment.reference.as_deref())
+    .bind(payment.external_id.as_deref())
+    .bind(payment.bank_transaction_id.as_deref())
+    .bind(payment.notes.as_deref())
+    .bind(payment.created_by_user_id.as_deref())
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not record payment."))?;
+
+    let Some(inserted) = inserted else {
+        tx.rollback().await.ok();
+        let existing = sqlx::query(
+            "SELECT id::text FROM lease_payments
+             WHERE organization_id = $1::uuid AND external_id = $2",
+        )
+        .bind(&payment.organization_id)
+        .bind(payment.external_id.as_deref())
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Could not load payment."))?;
+        let payment_id: String = existing.try_get("id").unwrap_or_default();
+        let payment = get_row(pool, "lease_payments", &payment_id, "id").await?;
+        return Ok(RecordedPayment {
+            credit: number(&payment, "unallocated_amount"),
+            payment,
+            allocations: Vec::new(),
+            duplicate: true,
+        });
+    };
+    let payment_id: String = inserted.try_get("id").unwrap_or_default();
+
+    let (allocations, credit) = allocate_payment(
+        &mut tx,
+        &payment.lease_id,
+        &payment_id,
+        payment.amount,
+        &payment.currency,
+        targets,
+    )
+    .await?;
+    let allocated = round2(payment.amount - credit);
+    post_receipt(&mut tx, payment, &payment_id, allocated, credit).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Dependency(format!("txn commit: {e}")))?;
+
+    refresh_lease_delinquency(pool, &payment.lease_id).await?;
+    let payment = get_row(pool, "lease_payments", &payment_id, "id").await?;
+    Ok(RecordedPayment {
+        payment,
+        allocations,
+        credit,
+        duplicate: false,
+    })
+}
+
+/// Apply credit left on earlier payments to collections that have since
+/// come due, oldest payment first.
+pub async fn apply_lease_credit(pool: &PgPool, lease_id: &str) -> AppResult<Vec<Allocation>> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Dependency(format!("txn begin: {e}")))?;
+
+    let payments = sqlx::query(
+        "SELECT id::text, unallocated_amount::float8 AS available, currency::text AS currency
+         FROM lease_payments
+         WHERE lease_id = $1::uuid AND unallocated_amount > 0
+         ORDER BY received_at, created_at
+         FOR UPDATE",
+    )
+    .bind(lease_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load lease credit."))?;
+
+    let mut applied = Vec::new();
+    for payment in payments {
+        let payment_id: String = payment.try_get("id").unwrap_or_default();
+        let available: f64 = payment.try_get("available").unwrap_or(0.0);
+        let currency: String = payment.try_get("currency").unwrap_or_default();
+        let (allocations, _) =
+            allocate_payment(&mut tx, lease_id, &payment_id, available, &currency, &[]).await?;
+        let amount = round2(allocations.iter().map(|allocation| allocation.amount).sum());
+        post_credit_application(&mut tx, &payment_id, amount).await?;
+        applied.extend(allocations);
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Dependency(format!("txn commit: {e}")))?;
+
+    if !applied.is_empty() {
+        refresh_lease_delinquency(pool, lease_id).await?;
+    }
+    Ok(applied)
+}
+
+/// Apply outstanding credit on every lease that has some. Used by the daily
+/// collection cycle once scheduled collections turn pending.
+pub async fn apply_all_credit(pool: &PgPool, org_id: Option<&str>) -> (u32, u32) {
+    let leases = match sqlx::query(
+        "SELECT DISTINCT lease_id::text AS lease_id
+         FROM lease_payments
+         WHERE unallocated_amount > 0
+           AND ($1::uuid IS NULL OR organization_id = $1::uuid)",
+    )
+    .bind(org_id)
+    .fetch_all(pool)
+    .await
+    {
+        Ok(rows) => rows,
+        Err(error) => {
+            tracing::warn!("Failed to load leases with credit: {error}");
+            return (0, 1);
+        }
+    };
+
+    let (mut applied, mut errors) = (0_u32, 0_u32);
+    for row in leases {
+        let lease_id: String = row.try_get("lease_id").unwrap_or_default();
+        match apply_lease_credit(pool, &lease_id).await {
+            Ok(allocations) => applied += allocations.len() as u32,
+            Err(error) => {
+                tracing::warn!(lease_id = %lease_id, error = %error, "Failed to apply lease credit");
+                errors += 1;
+            }
+        }
+    }
+    (applied, errors)
+}
+
+/// Allocate up to `available` of a payment to the lease's open collections
+/// in the payment's currency and store the remainder as its credit.
+/// Explicit targets may be any open collection; automatic allocation only
+/// reaches collections that are already due or pending.
+async fn allocate_payment(
+    tx: &mut Transaction<'_, Postgres>,
+    lease_id: &str,
+    payment_id: &str,
+    available: f64,
+    currency: &str,
+    targets: &[String],
+) -> AppResult<(Vec<Allocation>, f64)> {
+    let rows = sqlx::query(
+        "SELECT id::text AS collection_id, (amount - amount_paid)::float8 AS balance
+         FROM collection_records
+         WHERE lease_id = $1::uuid
+           AND currency = $2
+           AND status IN ('scheduled', 'pending', 'late')
+           AND amount - amount_paid > 0
+           AND (status <> 'scheduled' OR due_date <= $3 OR id::text = ANY($4))
+         ORDER BY due_date, created_at
+         FOR UPDATE",
+    )
+    .bind(lease_id)
+    .bind(currency)
+    .bind(Utc::now().date_naive())
+    .bind(targets)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load open collections."))?;
+
+    let open: Vec<OpenBalance> = rows
+        .iter()
+        .map(|row| OpenBalance {
+            collection_id: row.try_get("collection_id").unwrap_or_default(),
+            balance: row.try_get("balance").unwrap_or(0.0),
+        })
+        .collect();
+    let (planned, credit) = plan_allocations(available, &open, targets);
+
+    let mut allocations = Vec::with_capacity(planned.len());
+    for (collection_id, amount) in planned {
+        sqlx::query(
+            "INSERT INTO payment_allocations (organization_id, payment_id, collection_record_id, amount)
+             SELECT organization_id, id, $2::uuid, $3 FROM lease_payments WHERE id = $1::uuid",
+        )
+        .bind(payment_id)
+        .bind(&collection_id)
+        .bind(amount)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Could not allocate payment."))?;
+
+        let updated = sqlx::query(
+            "UPDATE collection_records cr
+             SET amount_paid = cr.amount_paid + $3,
+                 status = CASE WHEN cr.amount_paid + $3 >= cr.amount - 0.005
+                               THEN 'paid'::collection_status ELSE cr.status END,
+                 paid_at = CASE WHEN cr.amount_paid + $3 >= cr.amount - 0.005
+                                THEN p.received_at ELSE cr.paid_at END,
+                 payment_method = COALESCE(p.payment_method, cr.payment_method),
+                 payment_reference = COALESCE(p.reference, cr.payment_reference)
+             FROM lease_payments p
+             WHERE cr.id = $1::uuid AND p.id = $2::uuid
+             RETURNING cr.status::text AS status, cr.lease_charge_id::text AS lease_charge_id",
+        )
+        .bind(&collection_id)
+        .bind(payment_id)
+        .bind(amount)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Could not update collection."))?;
+
+        let settled = updated
+            .try_get::<String, _>("status")
+            .is_ok_and(|status| status == "paid");
+        if settled {
+            if let Some(charge_id) = updated
+                .try_get::<Option<String>, _>("lease_charge_id")
+                .ok()
+                .flatten()
+            {
+                sqlx::query("UPDATE lease_charges SET status = 'paid' WHERE id = $1::uuid")
+                    .bind(charge_id)
+                    .execute(&mut **tx)
+                    .await
+                    .map_err(|e| AppError::from_database_error(&e, "Could not update charge."))?;
+            }
+        }
+        allocations.push(Allocation {
+            collection_id,
+            amount,
+            settled,
+        });
+    }
+
+    sqlx::query("UPDATE lease_payments SET unallocated_amount = $2 WHERE id = $1::uuid")
+        .bind(payment_id)
+        .bind(credit)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::from_database_error(&e, "Could not update payment."))?;
+
+    Ok((allocations, credit))
+}
+
+/// Journal a new payment: cash into trust, the allocated part owed to the
+/// owner and the rest held as a tenant prepayment.
+async fn post_receipt(
+    tx: &mut Transaction<'_, Postgres>,
+    payment: &NewPayment,
+    payment_id: &str,
+    allocated: f64,
+    credit: f64,
+) -> AppResult<()> {
+    let row = sqlx::query(
+        "SELECT p.received_at::date AS entry_date, l.property_id::text AS property_id
+         FROM lease_payments p
+         JOIN leases l ON l.id = p.lease_id
+         WHERE p.id = $1::uuid",
+    )
+    .bind(payment_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load payment."))?;
+    let entry_date: NaiveDate = row
+        .try_get("entry_date")
+        .unwrap_or_else(|_| Utc::now().date_naive());
+    let property_id: Option<String> = row.try_get("property_id").ok().flatten();
+    let property_id = property_id.as_deref();
+    let lease_id = Some(payment.lease_id.as_str());
+
+    general_ledger::post_entry_in(
+        tx,
+        &NewEntry {
+            organization_id: payment.organization_id.clone(),
+            entry_date,
+            description: format!("Lease payment received ({})", payment.source.as_str()),
+            source_type: "lease_payment".to_string(),
+            source_id: Some(payment_id.to_string()),
+            source_key: Some(format!("lease_payment:{payment_id}")),
+            currency: payment.currency.clone(),
+            created_by_user_id: payment.created_by_user_id.clone(),
+            lines: vec![
+                JournalLine::debit("trust_cash", allocated + credit)
+                    .property(property_id)
+                    .lease(lease_id),
+                JournalLine::credit("owner_payable", allocated)
+                    .property(property_id)
+                    .lease(lease_id),
+                JournalLine::credit("tenant_prepayments", credit)
+                    .property(property_id)
+                    .lease(lease_id),
+            ],
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+/// Journal credit from an earlier payment applied to a collection that has
+/// come due: the prepayment becomes owed to the owner.
+async fn post_credit_application(
+    tx: &mut Transaction<'_, Postgres>,
+    payment_id: &str,
+    amount: f64,
+) -> AppResult<()> {
+    if amount < EPSILON {
+        return Ok(());
+    }
+    let row = sqlx::query(
+        "SELECT p.organization_id::text AS organization_id, p.lease_id::text AS lease_id,
+                p.currency::text AS currency, l.property_id::text AS property_id
+         FROM lease_payments p
+         JOIN leases l ON l.id = p.lease_id
+         WHERE p.id = $1::uuid",
+    )
+    .bind(payment_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load payment."))?;
+    let lease_id: Option<String> = row.try_get("lease_id").ok();
+    let property_id: Option<String> = row.try_get("property_id").ok().flatten();
+
+    general_ledger::post_entry_in(
+        tx,
+        &NewEntry {
+            organization_id: row.try_get("organization_id").unwrap_or_default(),
+            entry_date: Utc::now().date_naive(),
+            description: "Tenant credit applied".to_string(),
+            source_type: "lease_payment".to_string(),
+            source_id: Some(payment_id.to_string()),
+            source_key: None,
+            currency: row.try_get("currency").unwrap_or_default(),
+            created_by_user_id: None,
+            lines: vec![
+                JournalLine::debit("tenant_prepayments", amount)
+                    .property(property_id.as_deref())
+                    .lease(lease_id.as_deref()),
+                JournalLine::credit("owner_payable", amount)
+                    .property(property_id.as_deref())
+                    .lease(lease_id.as_deref()),
+            ],
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+/// Set an active lease delinquent while any collection past its due date
+/// still has a balance, and back to active once everything due is covered.
+pub async fn refresh_lease_delinquency(pool: &PgPool, lease_id: &str) -> AppResult<()> {
+    let lease = get_row(pool, "leases", lease_id, "id").await?;
+    let status = lease
+        .get("lease_status")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if status != "active" && status != "delinquent" {
+        return Ok(());
+    }
+
+    let overdue: bool = sqlx::query_scalar(
+        "SELECT EXISTS (
+            SELECT 1 FROM collection_records
+            WHERE lease_id = $1::uuid
+              AND status NOT IN ('paid', 'waived')
+              AND due_date < $2
+              AND amount - amount_paid > 0.005
+         )",
+    )
+    .bind(lease_id)
+    .bind(Utc::now().date_naive())
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not check lease balance."))?;
+
+    let next_status = if overdue { "delinquent" } else { "active" };
+    if next_status != status {
+        let mut patch = Map::new();
+        patch.insert(
+            "lease_status".to_string(),
+            Value::String(next_status.to_string()),
+        );
+        update_row(pool, "leases", lease_id, &patch, "id").await?;
+    }
+    Ok(())
+}
+
+/// Open balances and unapplied credit for a lease.
+pub async fn lease_balance(pool: &PgPool, lease_id: &str, today: NaiveDate) -> AppResult<Value> {
+    let open = sqlx::query(
+        "SELECT id::text AS collection_id, due_date, status::text AS status,
+                currency::text AS currency, amount::float8 AS amount,
+                amount_paid::float8 AS amount_paid,
+                (amount - amount_paid)::float8 AS balance
+         FROM collection_records
+         WHERE lease_id = $1::uuid
+           AND status IN ('scheduled', 'pending', 'late')
+           AND amount - amount_paid > 0
+         ORDER BY due_date, created_at",
+    )
+    .bind(lease_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load lease balance."))?;
+
+    let credit = sqlx::query(
+        "SELECT currency::text AS currency, SUM(unallocated_amount)::float8 AS credit
+         FROM lease_payments
+         WHERE lease_id = $1::uuid AND unallocated_amount > 0
+         GROUP BY currency
+         ORDER BY currency",
+    )
+    .bind(lease_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Could not load lease credit."))?;
+
+    let mut totals: Map<String, Value> = Map::new();
+    let mut collections = Vec::with_capacity(open.len());
+    for row in &open {
+        let currency: String = row.try_get("currency").unwrap_or_default();
+        let balance: f64 = row.try_get("balance").unwrap_or(0.0);
+        let due_date: Option<NaiveDate> = row.try_get("due_date").ok();
+        let overdue = due_date.is_some_and(|due| due < today);
+
+        let entry = totals
+            .entry(currency.clone())
+            .or_insert_with(|| json!({ "outstanding": 0.0, "overdue": 0.0, "credit": 0.0 }));
+        entry["outstanding"] = json!(round2(number(entry, "outstanding") + balance));
+        if overdue {
+            entry["overdue"] = json!(round2(number(entry, "overdue") + balance));
+        }
+
+        collections.push(json!({
+            "collection_id": row.try_get::<String, _>("collection_id").unwrap_or_default(),
+            "due_date": due_date.map(|date| date.to_string()),
+            "status": row.try_get::<String, _>("status").unwrap_or_default(),
+            "currency": currency,
+            "amount": row.try_get::<f64, _>("amount").unwrap_or(0.0),
+            "amount_paid": row.try_get::<f64, _>("amount_paid").unwrap_or(0.0),
+            "balance": balance,
+            "overdue": overdue,
+        }));
+    }
+    for row in &credit {
+        let currency: String = row.try_get("currency").unwrap_or_default();
+        let entry = totals
+            .entry(currency)
+            .or_insert_with(|| json!({ "outstanding": 0.0, "overdue": 0.0, "credit": 0.0 }));
+        entry["credit"] = json!(round2(row.try_get::<f64, _>("credit").unwrap_or(0.0)));
+    }
+
+    Ok(json!({
+        "lease_id": lease_id,
+        "as_of": today.to_string(),
+        "totals": totals,
+        "open_collections": collections,
+    }))
+}
+
+fn number(row: &Value, key: &str) -> f64 {
+    match row.get(key) {
+        Some(Value::Number(value)) => value.as_f64().unwrap_or(0.0),
+        Some(Value::String(value)) => value.trim().parse().unwrap_or(0.0),
+        _ => 0.0,
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open(id: &str, balance: f64) -> OpenBalance {
+        OpenBalance {
+            collection_id: id.to_string(),
+            balance,
+        }
+    }
+
+    #[test]
+    fn allocates_oldest_first_and_keeps_credit() {
+        let balances = [open("jan", 1_000.0), open("feb", 1_000.0)];
+        let (planned, credit) = plan_allocations(1_500.0, &balances, &[]);
+        assert_eq!(
+            planned,
+            vec![("jan".to_string(), 1_000.0), ("feb".to_string(), 500.0)]
+        );
+        assert_eq!(credit, 0.0);
+
+        let (planned, credit) = plan_allocations(2_250.5, &balances, &[]);
+        assert_eq!(planned.len(), 2);
+        assert_eq!(credit, 250.5);
+    }
+
+    #[test]
+    fn targets_are_paid_before_older_balances() {
+        let balances = [open("jan", 300.0), open("feb", 1_000.0)];
+        let (planned, credit) = plan_allocations(1_100.0, &balances, &["feb".to_string()]);
+        assert_eq!(
+            planned,
+            vec![("feb".to_string(), 1_000.0), ("jan".to_string(), 100.0)]
+        );
+        assert_eq!(credit, 0.0);
+
+        // Unknown targets are ignored rather than creating allocations.
+        let (planned, credit) = plan_allocations(50.0, &[], &["gone".to_string()]);
+        assert!(planned.is_empty());
+        assert_eq!(credit, 50.0);
+    }
+
+    mod db {
+        use super::*;
+        use crate::test_support::{create_lease, create_org, create_user, test_pool};
+
+        async fn setup(pool: &PgPool) -> (String, String) {
+            let user_id = create_user(pool).await;
+            let org_id = create_org(pool, &user_id).await;
+            let lease_id = create_lease(pool, &org_id, "PYG").await;
+            (org_id, lease_id)
+        }
+
+        async fn collection(
+            pool: &PgPool,
+            org_id: &str,
+            lease_id: &str,
+            due_date: &str,
+            status: &str,
+        ) -> String {
+            sqlx::query_scalar(
+                "INSERT INTO collection_records (organization_id, lease_id, due_date, amount, currency, status)
+                 VALUES ($1::uuid, $2::uuid, $3::date, 1000000, 'PYG', $4::collection_status)
+                 RETURNING id::text",
+            )
+            .bind(org_id)
+            .bind(lease_id)
+            .bind(due_date)
+            .bind(status)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+        }
+
+        async fn paid_on(pool: &PgPool, collection_id: &str) -> (f64, String) {
+            sqlx::query_as(
+                "SELECT amount_paid::float8, status::text FROM collection_records WHERE id = $1::uuid",
+            )
+            .bind(collection_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+        }
+
+        fn payment(org_id: &str, lease_id: &str, amount: f64, external_id: &str) -> NewPayment {
+            NewPayment {
+                organization_id: org_id.to_string(),
+                lease_id: lease_id.to_string(),
+                source: PaymentSource::Stripe,
+                amount,
+                currency: "PYG".to_string(),
+                payment_method: None,
+                received_at: None,
+                reference: None,
+                external_id: Some(external_id.to_string()),
+                bank_transaction_id: None,
+                notes: None,
+                created_by_user_id: None,
+            }
+        }
+
+        #[tokio::test]
+        async fn replayed_partial_payment_is_allocated_once() {
+            let Some(pool) = test_pool().await else {
+                return;
+            };
+            let (org_id, lease_id) = setup(&pool).await;
+            let due = collection(&pool, &org_id, &lease_id, "2026-09-01", "pending").await;
+            let partial = payment(&org_id, &lease_id, 400_000.0, "pi_partial");
+
+            let first = record_payment(&pool, &partial, &[]).await.unwrap();
+            assert!(!first.duplicate);
+            assert_eq!(first.allocated_to(&due), 400_000.0);
+            assert_eq!(first.credit, 0.0);
+
+            let replay = record_payment(&pool, &partial, &[]).await.unwrap();
+            assert!(replay.duplicate);
+            assert!(replay.allocations.is_empty());
+            assert_eq!(replay.payment["id"], first.payment["id"]);
+
+            assert_eq!(
+                paid_on(&pool, &due).await,
+                (400_000.0, "pending".to_string())
+            );
+            let balance = lease_balance(&pool, &lease_id, date("2026-10-17"))
+                .await
+                .unwrap();
+            assert_eq!(balance["totals"]["PYG"]["overdue"], json!(600_000.0));
+            assert_eq!(balance["totals"]["PYG"]["credit"], json!(0.0));
+        }
+
+        #[tokio::test]
+        async fn overpayment_is_held_as_credit_until_applied() {
+            let Some(pool) = test_pool().await else {
+                return;
+            };
+            let (org_id, lease_id) = setup(&pool).await;
+            let due = collection(&pool, &org_id, &lease_id, "2026-09-01", "pending").await;
+            let upcoming = collection(&pool, &org_id, &lease_id, "2099-01-01", "scheduled").await;
+
+            let recorded = record_payment(
+                &pool,
+                &payment(&org_id, &lease_id, 1_500_000.0, "pi_over"),
+                &[],
+            )
+            .await
+            .unwrap();
+            assert_eq!(recorded.allocated_to(&due), 1_000_000.0);
+            assert_eq!(recorded.allocated_to(&upcoming), 0.0);
+            assert_eq!(recorded.credit, 500_000.0);
+            assert_eq!(paid_on(&pool, &due).await.1, "paid");
+
+            // Nothing new is due yet, so the credit stays put.
+            assert!(apply_lease_credit(&pool, &lease_id)
+                .await
+                .unwrap()
+                .is_empty());
+
+            sqlx::query(
+                "UPDATE collection_records SET due_date = '2026-10-01', status = 'pending'
+                 WHERE id = $1::uuid",
+            )
+            .bind(&upcoming)
+            .execute(&pool)
+            .await
+            .unwrap();
+            let applied = apply_lease_credit(&pool, &lease_id).await.unwrap();
+            assert_eq!(
+                applied,
+                vec![Allocation {
+                    collection_id: upcoming.clone(),
+                    amount: 500_000.0,
+                    settled: false,
+                }]
+            );
+            assert_eq!(
+                paid_on(&pool, &upcoming).await,
+                (500_000.0, "pending".to_string())
+            );
+            let credit: f64 = sqlx::query_scalar(
+                "SELECT unallocated_amount::float8 FROM lease_payments WHERE lease_id = $1::uuid",
+            )
+            .bind(&lease_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            assert_eq!(credit, 0.0);
+        }
+
+        fn date(value: &str) -> NaiveDate {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+        }
+    }
+}
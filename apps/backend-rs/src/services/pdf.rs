@@ -0,0 +1,639 @@
+//! Minimal PDF 1.4 writer for server-rendered documents (owner statements,
+//! payment receipts, contracts). Supports the standard Helvetica faces with
+//! WinAnsi encoding (covers Spanish), filled rectangles, rules, and JPEG or
+//! 8-bit non-interlaced grey/RGB PNG images, which are embedded without
+//! decoding. Page content streams are Flate-compressed.
+
+use std::io::Write;
+
+use flate2::{write::ZlibEncoder, Compression};
+
+/// A4 portrait, in points.
+pub const PAGE_WIDTH: f32 = 595.28;
+pub const PAGE_HEIGHT: f32 = 841.89;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource(self) -> &'static str {
+        match self {
+            Self::Regular => "F1",
+            Self::Bold => "F2",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color(pub f32, pub f32, pub f32);
+
+impl Color {
+    pub const BLACK: Self = Self(0.1, 0.1, 0.12);
+    pub const MUTED: Self = Self(0.42, 0.45, 0.5);
+    pub const RULE: Self = Self(0.85, 0.87, 0.9);
+    pub const WHITE: Self = Self(1.0, 1.0, 1.0);
+
+    /// Parse `#rrggbb` or `rrggbb`.
+    pub fn from_hex(value: &str) -> Option<Self> {
+        let hex = value.trim().trim_start_matches('#');
+        if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel = |at: usize| {
+            u8::from_str_radix(&hex[at..at + 2], 16)
+                .map(|v| f32::from(v) / 255.0)
+                .ok()
+        };
+        Some(Self(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// The same hue mixed with white, for table stripes and header fills.
+    pub fn tint(self, amount: f32) -> Self {
+        let mix = |c: f32| c + (1.0 - c) * amount.clamp(0.0, 1.0);
+        Self(mix(self.0), mix(self.1), mix(self.2))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImageKind {
+    Jpeg { components: u8 },
+    Png { colors: u8 },
+}
+
+/// An image ready to embed as an XObject.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    kind: ImageKind,
+    data: Vec<u8>,
+}
+
+impl Image {
+    /// Recognize a JPEG (baseline or progressive, grey or RGB) or a PNG that
+    /// PDF can decode natively. Anything else returns `None`.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0xFF, 0xD8]) {
+            return parse_jpeg(bytes);
+        }
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            return parse_png(bytes);
+        }
+        None
+    }
+}
+
+fn parse_jpeg(bytes: &[u8]) -> Option<Image> {
+    let mut at = 2;
+    while at + 4 <= bytes.len() {
+        if bytes[at] != 0xFF {
+            return None;
+        }
+        let marker = bytes[at + 1];
+        if marker == 0xFF {
+            at += 1;
+            continue;
+        }
+        let length = usize::from(u16::from_be_bytes([bytes[at + 2], bytes[at + 3]]));
+        let is_sof = (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+        if is_sof {
+            let segment = bytes.get(at + 4..at + 2 + length)?;
+            let height = u32::from(u16::from_be_bytes([*segment.get(1)?, *segment.get(2)?]));
+            let width = u32::from(u16::from_be_bytes([*segment.get(3)?, *segment.get(4)?]));
+            let components = *segment.get(5)?;
+            if width == 0 || height == 0 || !matches!(components, 1 | 3) {
+                return None;
+            }
+            return Some(Image {
+                width,
+                height,
+                kind: ImageKind::Jpeg { components },
+                data: bytes.to_vec(),
+            });
+        }
+        if marker == 0xDA {
+            return None;
+        }
+        at += 2 + length;
+    }
+    None
+}
+
+fn parse_png(bytes: &[u8]) -> Option<Image> {
+    let mut at = 8;
+    let mut header: Option<(u32, u32, u8)> = None;
+    let mut data = Vec::new();
+    while at + 8 <= bytes.len() {
+        let length = u32::from_be_bytes(bytes[at..at + 4].try_into().ok()?) as usize;
+        let kind = &bytes[at + 4..at + 8];
+        let body = bytes.get(at + 8..at + 8 + length)?;
+        match kind {
+            b"IHDR" => {
+                if body.len() < 13 {
+                    return None;
+                }
+                let width = u32::from_be_bytes(body[0..4].try_into().ok()?);
+                let height = u32::from_be_bytes(body[4..8].try_into().ok()?);
+                let (bit_depth, color_type, interlace) = (body[8], body[9], body[12]);
+                let colors = match color_type {
+                    0 => 1,
+                    2 => 3,
+                    _ => return None,
+                };
+                if bit_depth != 8 || interlace != 0 || width == 0 || height == 0 {
+                    return None;
+                }
+                header = Some((width, height, colors));
+            }
+            b"IDAT" => data.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+        at += 12 + length;
+    }
+    let (width, height, colors) = header?;
+    if data.is_empty() {
+        return None;
+    }
+    Some(Image {
+        width,
+        height,
+        kind: ImageKind::Png { colors },
+        data,
+    })
+}
+
+/// Handle returned by [`Document::add_image`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageId(usize);
+
+/// Drawing operations for one page. Coordinates are in points from the
+/// bottom-left corner, as in PDF.
+#[derive(Debug, Default, Clone)]
+pub struct Page {
+    ops: Vec<u8>,
+    images: Vec<usize>,
+}
+
+impl Page {
+    pub fn text(&mut self, x: f32, y: f32, font: Font, size: f32, color: Color, text: &str) {
+        let mut op = format!(
+            "BT /{} {size:.2} Tf {:.3} {:.3} {:.3} rg {x:.2} {y:.2} Td (",
+            font.resource(),
+            color.0,
+            color.1,
+            color.2
+        )
+        .into_bytes();
+        op.extend(escape_string(&encode_win_ansi(text)));
+        op.extend_from_slice(b") Tj ET\n");
+        self.ops.extend(op);
+    }
+
+    /// Text whose right edge sits at `right`.
+    pub fn text_right(
+        &mut self,
+        right: f32,
+        y: f32,
+        font: Font,
+        size: f32,
+        color: Color,
+        text: &str,
+    ) {
+        let x = right - text_width(text, font, size);
+        self.text(x, y, font, size, color, text);
+    }
+
+    pub fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color) {
+        self.ops.extend(
+            format!(
+                "{:.3} {:.3} {:.3} rg {x:.2} {y:.2} {width:.2} {height:.2} re f\n",
+                color.0, color.1, color.2
+            )
+            .into_bytes(),
+        );
+    }
+
+    pub fn rule(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, width: f32, color: Color) {
+        self.ops.extend(
+            format!(
+                "{:.3} {:.3} {:.3} RG {width:.2} w {x1:.2} {y1:.2} m {x2:.2} {y2:.2} l S\n",
+                color.0, color.1, color.2
+            )
+            .into_bytes(),
+        );
+    }
+
+    pub fn image(&mut self, image: ImageId, x: f32, y: f32, width: f32, height: f32) {
+        if !self.images.contains(&image.0) {
+            self.images.push(image.0);
+        }
+        self.ops.extend(
+            format!(
+                "q {width:.2} 0 0 {height:.2} {x:.2} {y:.2} cm /Im{} Do Q\n",
+                image.0
+            )
+            .into_bytes(),
+        );
+    }
+}
+
+/// An in-memory PDF built page by page and serialized with [`to_bytes`].
+///
+/// [`to_bytes`]: Document::to_bytes
+#[derive(Debug, Default, Clone)]
+pub struct Document {
+    title: String,
+    creation_date: Option<String>,
+    images: Vec<Image>,
+    pages: Vec<Page>,
+}
+
+impl Document {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Stamp `/CreationDate` in the document info (UTC).
+    pub fn with_creation_date(mut self, at: chrono::DateTime<chrono::Utc>) -> Self {
+        self.creation_date = Some(at.format("D:%Y%m%d%H%M%SZ").to_string());
+        self
+    }
+
+    pub fn add_image(&mut self, image: Image) -> ImageId {
+        self.images.push(image);
+        ImageId(self.images.len() - 1)
+    }
+
+    pub fn add_page(&mut self) -> &mut Page {
+        self.pages.push(Page::default());
+        let last = self.pages.len() - 1;
+        &mut self.pages[last]
+    }
+
+    /// The page being drawn on, starting one if the document is empty.
+    pub fn last_page(&mut self) -> &mut Page {
+        if self.pages.is_empty() {
+            self.pages.push(Page::default());
+        }
+        let last = self.pages.len() - 1;
+        &mut self.pages[last]
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn page_mut(&mut self, index: usize) -> Option<&mut Page> {
+        self.pages.get_mut(index)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // 1 catalog, 2 page tree, 3-4 fonts, 5 info, then images, then a
+        // (page, content) pair per page.
+        let image_base = 6;
+        let page_base = image_base + self.images.len();
+        let page_ids = (0..self.pages.len())
+            .map(|index| page_base + index * 2)
+            .collect::<Vec<_>>();
+
+        let mut objects: Vec<Vec<u8>> = Vec::new();
+        objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+        objects.push(
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                page_ids
+                    .iter()
+                    .map(|id| format!("{id} 0 R"))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                page_ids.len()
+            )
+            .into_bytes(),
+        );
+        for base_font in ["Helvetica", "Helvetica-Bold"] {
+            objects.push(
+                format!(
+                    "<< /Type /Font /Subtype /Type1 /BaseFont /{base_font} /Encoding /WinAnsiEncoding >>"
+                )
+                .into_bytes(),
+            );
+        }
+        let mut info = b"<< /Producer (Casaora) /Title (".to_vec();
+        info.extend(escape_string(&encode_win_ansi(&self.title)));
+        info.push(b')');
+        if let Some(date) = self.creation_date.as_deref() {
+            info.extend(format!(" /CreationDate ({date})").into_bytes());
+        }
+        info.extend_from_slice(b" >>");
+        objects.push(info);
+
+        for image in &self.images {
+            let (filter, color_space, parms) = match image.kind {
+                ImageKind::Jpeg { components } => (
+                    "/DCTDecode",
+                    if components == 1 { "/DeviceGray" } else { "/DeviceRGB" },
+                    String::new(),
+                ),
+                ImageKind::Png { colors } => (
+                    "/FlateDecode",
+                    if colors == 1 { "/DeviceGray" } else { "/DeviceRGB" },
+                    format!(
+                        " /DecodeParms << /Predictor 15 /Colors {colors} /BitsPerComponent 8 /Columns {} >>",
+                        image.width
+                    ),
+                ),
+            };
+            let mut object = format!(
+                "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace {color_space} /BitsPerComponent 8 /Filter {filter}{parms} /Length {} >>\nstream\n",
+                image.width,
+                image.height,
+                image.data.len()
+            )
+            .into_bytes();
+            object.extend_from_slice(&image.data);
+            object.extend_from_slice(b"\nendstream");
+            objects.push(object);
+        }
+
+        for (index, page) in self.pages.iter().enumerate() {
+            let content_id = page_ids[index] + 1;
+            let xobjects = if page.images.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    " /XObject << {} >>",
+                    page.images
+                        .iter()
+                        .map(|image| format!("/Im{image} {} 0 R", image_base + image))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                )
+            };
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >>{xobjects} >> /Contents {content_id} 0 R >>"
+                )
+                .into_bytes(),
+            );
+            let compressed = deflate(&page.ops);
+            let mut content = format!(
+                "<< /Filter /FlateDecode /Length {} >>\nstream\n",
+                compressed.len()
+            )
+            .into_bytes();
+            content.extend(compressed);
+            content.extend_from_slice(b"\nendstream");
+            objects.push(content);
+        }
+
+        let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend(format!("{} 0 obj\n", index + 1).into_bytes());
+            out.extend_from_slice(object);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+        let xref_at = out.len();
+        out.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+        for offset in offsets {
+            out.extend(format!("{offset:010} 00000 n \n").into_bytes());
+        }
+        out.extend(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R /Info 5 0 R >>\nstartxref\n{xref_at}\n%%EOF\n",
+                objects.len() + 1
+            )
+            .into_bytes(),
+        );
+        out
+    }
+}
+
+fn deflate(bytes: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    // Writing into a Vec cannot fail.
+    let _ = encoder.write_all(bytes);
+    encoder.finish().unwrap_or_default()
+}
+
+/// Encode text for the WinAnsi-encoded standard fonts. Latin-1 maps
+/// directly; common typographic characters use their cp1252 slots and
+/// anything else becomes `?`.
+fn encode_win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '\u{20}'..='\u{7E}' => c as u8,
+            '\u{A0}'..='\u{FF}' => c as u32 as u8,
+            '€' => 0x80,
+            '‚' => 0x82,
+            '„' => 0x84,
+            '…' => 0x85,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            '™' => 0x99,
+            '\t' | '\n' | '\r' => b' ',
+            _ => b'?',
+        })
+        .collect()
+}
+
+fn escape_string(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    for &byte in bytes {
+        if matches!(byte, b'(' | b')' | b'\\') {
+            out.push(b'\\');
+        }
+        out.push(byte);
+    }
+    out
+}
+
+/// Helvetica advance widths (1/1000 em) for printable ASCII.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Helvetica-Bold advance widths (1/1000 em) for printable ASCII.
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+/// Rendered width of `text` in points. Accented letters are measured as
+/// their base letter, which is exact for the Helvetica metrics.
+pub fn text_width(text: &str, font: Font, size: f32) -> f32 {
+    let table = match font {
+        Font::Regular => &HELVETICA_WIDTHS,
+        Font::Bold => &HELVETICA_BOLD_WIDTHS,
+    };
+    let units: u32 = text
+        .chars()
+        .map(|c| {
+            let base = match c {
+                'á' | 'à' | 'â' | 'ä' | 'ã' => 'a',
+                'é' | 'è' | 'ê' | 'ë' => 'e',
+                'í' | 'ì' | 'î' | 'ï' => 'i',
+                'ó' | 'ò' | 'ô' | 'ö' | 'õ' => 'o',
+                'ú' | 'ù' | 'û' | 'ü' => 'u',
+                'ñ' => 'n',
+                'Á' | 'À' | 'Â' | 'Ä' | 'Ã' => 'A',
+                'É' | 'È' | 'Ê' | 'Ë' => 'E',
+                'Í' | 'Ì' | 'Î' | 'Ï' => 'I',
+                'Ó' | 'Ò' | 'Ô' | 'Ö' | 'Õ' => 'O',
+                'Ú' | 'Ù' | 'Û' | 'Ü' => 'U',
+                'Ñ' => 'N',
+                'ç' => 'c',
+                'Ç' => 'C',
+                other => other,
+            };
+            match base {
+                ' '..='~' => u32::from(table[base as usize - 32]),
+                _ => 556,
+            }
+        })
+        .sum();
+    units as f32 * size / 1000.0
+}
+
+/// Greedy word wrap to `max_width` points. Words longer than a line are
+/// split by character; explicit newlines always break.
+pub fn wrap(text: &str, font: Font, size: f32, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{line} {word}")
+            };
+            if text_width(&candidate, font, size) <= max_width {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            for c in word.chars() {
+                line.push(c);
+                if text_width(&line, font, size) > max_width && line.chars().count() > 1 {
+                    let last = line.pop().unwrap_or(c);
+                    lines.push(std::mem::take(&mut line));
+                    line.push(last);
+                }
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_spanish_text_and_escapes_delimiters() {
+        assert_eq!(
+            encode_win_ansi("Año – €"),
+            vec![b'A', 0xF1, b'o', b' ', 0x96, b' ', 0x80]
+        );
+        assert_eq!(escape_string(b"(a)\\"), b"\\(a\\)\\\\".to_vec());
+        assert_eq!(
+            Color::from_hex("#2563eb"),
+            Some(Color(37.0 / 255.0, 99.0 / 255.0, 235.0 / 255.0))
+        );
+        assert_eq!(Color::from_hex("blue"), None);
+    }
+
+    #[test]
+    fn wraps_on_measured_width() {
+        assert!((text_width("Hello", Font::Regular, 10.0) - 22.78).abs() < 0.01);
+        assert_eq!(
+            text_width("ñ", Font::Bold, 10.0),
+            text_width("n", Font::Bold, 10.0)
+        );
+        let lines = wrap("uno dos tres cuatro\ncinco", Font::Regular, 10.0, 40.0);
+        assert_eq!(lines, vec!["uno dos", "tres", "cuatro", "cinco"]);
+    }
+
+    #[test]
+    fn recognizes_embeddable_images() {
+        // SOI, APP0 (length 4), SOF0 for a 3x2 RGB image, EOI.
+        let jpeg = [
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x00,
+            0x02, 0x00, 0x03, 0x03, 0x01, 0x11, 0x00, 0x02, 0x11, 0x00, 0x03, 0x11, 0x00, 0xFF,
+            0xD9,
+        ];
+        let image = Image::from_bytes(&jpeg).expect("jpeg");
+        assert_eq!((image.width, image.height), (3, 2));
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png.extend_from_slice(&13u32.to_be_bytes());
+        png.extend_from_slice(b"IHDR");
+        png.extend_from_slice(&[0, 0, 0, 4, 0, 0, 0, 1, 8, 2, 0, 0, 0]);
+        png.extend_from_slice(&[0; 4]);
+        png.extend_from_slice(&2u32.to_be_bytes());
+        png.extend_from_slice(b"IDAT");
+        png.extend_from_slice(&[0x78, 0x9C]);
+        png.extend_from_slice(&[0; 4]);
+        let image = Image::from_bytes(&png).expect("png");
+        assert_eq!((image.width, image.height), (4, 1));
+
+        // RGBA PNGs need an alpha split and are not embedded.
+        png[25] = 6;
+        assert!(Image::from_bytes(&png).is_none());
+        assert!(Image::from_bytes(b"GIF89a").is_none());
+    }
+
+    #[test]
+    fn serializes_a_consistent_xref_table() {
+        let mut doc = Document::new("Estado (prueba)");
+        doc.add_page()
+            .text(40.0, 800.0, Font::Bold, 12.0, Color::BLACK, "Hola");
+        doc.add_page().fill_rect(0.0, 0.0, 10.0, 10.0, Color::MUTED);
+        let bytes = doc.to_bytes();
+        assert!(bytes.starts_with(b"%PDF-1.4"));
+        assert!(bytes.ends_with(b"%%EOF\n"));
+
+        // Content streams are compressed, so offsets are read from bytes.
+        let text = String::from_utf8_lossy(&bytes);
+        let xref_at: usize = text
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|tail| tail.lines().next())
+            .and_then(|line| line.parse().ok())
+            .expect("startxref");
+        assert!(bytes[xref_at..].starts_with(b"xref\n0 10\n"));
+        // Every xref entry points at the start of its object.
+        let xref = std::str::from_utf8(&bytes[xref_at..]).expect("ascii xref");
+        let entries = xref.lines().skip(3).take(9);
+        for (index, entry) in entries.enumerate() {
+            let offset: usize = entry[..10].parse().expect("offset");
+            let header = format!("{} 0 obj", index + 1);
+            assert!(bytes[offset..].starts_with(header.as_bytes()), "{header}");
+        }
+        assert!(text.contains("/Count 2"));
+        assert!(text.contains("/Title (Estado \\(prueba\\))"));
+    }
+}
@@ -0,0 +1,1223 @@
+//! Branded PDF layouts for owner statements, payment receipts and rendered
+//! contracts, in Spanish or English, plus storage of the rendered files.
+
+use std::time::Duration;
+
+use chrono::{NaiveDate, Utc};
+use serde_json::Value;
+use sqlx::Row;
+
+use crate::{
+    config::AppConfig,
+    error::{AppError, AppResult},
+    repository::table_service::update_row,
+    services::{
+        pdf::{self, Color, Document, Font, Image, ImageId, PAGE_HEIGHT, PAGE_WIDTH},
+        storage::{upload_public_object, StorageNamespace, StoredObject},
+    },
+    state::AppState,
+};
+
+const MARGIN: f32 = 48.0;
+const HEADER_HEIGHT: f32 = 64.0;
+const CONTENT_TOP: f32 = PAGE_HEIGHT - HEADER_HEIGHT - 36.0;
+const CONTENT_BOTTOM: f32 = 64.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - MARGIN * 2.0;
+const ROW_HEIGHT: f32 = 16.0;
+
+/// Logos larger than this are skipped rather than embedded.
+const MAX_LOGO_BYTES: usize = 2 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    #[default]
+    Es,
+    En,
+}
+
+impl Lang {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "es" | "es-py" | "spanish" => Some(Self::Es),
+            "en" | "en-us" | "english" => Some(Self::En),
+            _ => None,
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Es => "es",
+            Self::En => "en",
+        }
+    }
+
+    fn pick<'a>(self, es: &'a str, en: &'a str) -> &'a str {
+        match self {
+            Self::Es => es,
+            Self::En => en,
+        }
+    }
+}
+
+/// Organization identity printed on every page.
+#[derive(Debug, Clone)]
+pub struct Branding {
+    pub name: String,
+    pub legal_name: Option<String>,
+    pub ruc: Option<String>,
+    pub accent: Color,
+    pub logo: Option<Image>,
+    pub lang: Lang,
+}
+
+impl Branding {
+    /// Language for a render: the explicit request or the org default.
+    pub fn lang_for(&self, requested: Option<&str>) -> AppResult<Lang> {
+        match requested.map(str::trim).filter(|value| !value.is_empty()) {
+            Some(value) => Lang::parse(value)
+                .ok_or_else(|| AppError::BadRequest("lang must be 'es' or 'en'.".to_string())),
+            None => Ok(self.lang),
+        }
+    }
+}
+
+/// Load the organization's name, brand color, logo and document language.
+/// A logo that cannot be fetched or embedded is left out.
+pub async fn load_branding(
+    pool: &sqlx::PgPool,
+    http: &reqwest::Client,
+    org_id: &str,
+) -> AppResult<Branding> {
+    let row = sqlx::query(
+        "SELECT name, legal_name, ruc, brand_color, logo_url, document_language
+           FROM organizations
+          WHERE id = $1::uuid",
+    )
+    .bind(org_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|error| AppError::from_database_error(&error, "Failed to load organization."))?
+    .ok_or_else(|| AppError::NotFound("Organization not found.".to_string()))?;
+
+    let text = |column: &str| {
+        row.try_get::<Option<String>, _>(column)
+            .ok()
+            .flatten()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let logo = match text("logo_url") {
+        Some(url) => fetch_logo(http, &url).await,
+        None => None,
+    };
+
+    Ok(Branding {
+        name: text("name").unwrap_or_else(|| "Casaora".to_string()),
+        legal_name: text("legal_name"),
+        ruc: text("ruc"),
+        accent: text("brand_color")
+            .as_deref()
+            .and_then(Color::from_hex)
+            .unwrap_or(Color(0.145, 0.388, 0.922)),
+        logo,
+        lang: text("document_language")
+            .as_deref()
+            .and_then(Lang::parse)
+            .unwrap_or_default(),
+    })
+}
+
+async fn fetch_logo(http: &reqwest::Client, url: &str) -> Option<Image> {
+    let response = http
+        .get(url)
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?;
+    if response
+        .content_length()
+        .is_some_and(|length| length as usize > MAX_LOGO_BYTES)
+    {
+        return None;
+    }
+    let bytes = response.bytes().await.ok()?;
+    if bytes.len() > MAX_LOGO_BYTES {
+        return None;
+    }
+    let image = Image::from_bytes(&bytes);
+    if image.is_none() {
+        tracing::debug!(url, "Organization logo is not an embeddable JPEG/PNG");
+    }
+    image
+}
+
+/// Upload a rendered PDF under `{org_id}/{folder}/{entity_id}-{random}.pdf`.
+pub async fn store_pdf(
+    config: &AppConfig,
+    namespace: StorageNamespace,
+    org_id: &str,
+    folder: &str,
+    entity_id: &str,
+    bytes: Vec<u8>,
+) -> AppResult<StoredObject> {
+    let key = format!(
+        "{org_id}/{folder}/{entity_id}-{}.pdf",
+        uuid::Uuid::new_v4().simple()
+    );
+    upload_public_object(config, namespace, &key, bytes, "application/pdf").await
+}
+
+/// Format an amount the way local documents print it: guaraníes without
+/// decimals, everything else with two; Spanish uses `.` for thousands.
+pub fn format_money(amount: f64, currency: &str, lang: Lang) -> String {
+    let currency = currency.trim().to_ascii_uppercase();
+    let decimals = if currency == "PYG" { 0 } else { 2 };
+    let (thousands, decimal) = match lang {
+        Lang::Es => ('.', ','),
+        Lang::En => (',', '.'),
+    };
+    let fixed = format!("{:.*}", decimals, amount.abs());
+    let (whole, fraction) = fixed.split_once('.').unwrap_or((fixed.as_str(), ""));
+    let mut grouped = String::new();
+    for (index, digit) in whole.chars().enumerate() {
+        if index > 0 && (whole.len() - index) % 3 == 0 {
+            grouped.push(thousands);
+        }
+        grouped.push(digit);
+    }
+    if !fraction.is_empty() {
+        grouped.push(decimal);
+        grouped.push_str(fraction);
+    }
+    let symbol = match currency.as_str() {
+        "PYG" => "Gs.".to_string(),
+        "USD" => "US$".to_string(),
+        "EUR" => "€".to_string(),
+        "BRL" => "R$".to_string(),
+        "ARS" => "AR$".to_string(),
+        other => other.to_string(),
+    };
+    let sign = if amount < 0.0 && grouped.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        "-"
+    } else {
+        ""
+    };
+    format!("{sign}{symbol} {grouped}")
+}
+
+/// `dd/mm/yyyy` in Spanish, `Mon d, yyyy` in English. Unparseable input is
+/// printed as-is.
+pub fn format_date(value: &str, lang: Lang) -> String {
+    let trimmed = value.trim();
+    let Ok(date) = NaiveDate::parse_from_str(trimmed.get(..10).unwrap_or(trimmed), "%Y-%m-%d")
+    else {
+        return trimmed.to_string();
+    };
+    match lang {
+        Lang::Es => date.format("%d/%m/%Y").to_string(),
+        Lang::En => date.format("%b %-d, %Y").to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Align {
+    Left,
+    Right,
+}
+
+/// Flowing page layout: branded header band, running cursor, automatic page
+/// breaks and numbered footers.
+struct Sheet<'a> {
+    doc: Document,
+    branding: &'a Branding,
+    lang: Lang,
+    title: String,
+    logo: Option<ImageId>,
+    y: f32,
+}
+
+impl<'a> Sheet<'a> {
+    fn new(branding: &'a Branding, lang: Lang, title: &str) -> Self {
+        let mut doc = Document::new(title).with_creation_date(Utc::now());
+        let logo = branding.logo.clone().map(|image| doc.add_image(image));
+        let mut sheet = Self {
+            doc,
+            branding,
+            lang,
+            title: title.to_string(),
+            logo,
+            y: CONTENT_TOP,
+        };
+        sheet.new_page();
+        sheet
+    }
+
+    fn new_page(&mut self) {
+        let accent = self.branding.accent;
+        let logo = self.logo;
+        let page = self.doc.add_page();
+        let band_y = PAGE_HEIGHT - HEADER_HEIGHT;
+        page.fill_rect(0.0, band_y, PAGE_WIDTH, HEADER_HEIGHT, accent);
+
+        let mut name_x = MARGIN;
+        if let (Some(id), Some(image)) = (logo, self.branding.logo.as_ref()) {
+            let height = 40.0_f32;
+            let width = (image.width as f32 / image.height as f32 * height).min(120.0);
+            page.fill_rect(
+                MARGIN - 4.0,
+                band_y + 8.0,
+                width + 8.0,
+                height + 8.0,
+                Color::WHITE,
+            );
+            page.image(id, MARGIN, band_y + 12.0, width, height);
+            name_x += width + 16.0;
+        }
+        page.text(
+            name_x,
+            band_y + 26.0,
+            Font::Bold,
+            14.0,
+            Color::WHITE,
+            &self.branding.name,
+        );
+        page.text_right(
+            PAGE_WIDTH - MARGIN,
+            band_y + 26.0,
+            Font::Regular,
+            11.0,
+            Color::WHITE,
+            &self.title,
+        );
+
+        let mut identity = Vec::new();
+        if let Some(legal_name) = self.branding.legal_name.as_deref() {
+            identity.push(legal_name.to_string());
+        }
+        if let Some(ruc) = self.branding.ruc.as_deref() {
+            identity.push(format!("RUC {ruc}"));
+        }
+        if !identity.is_empty() {
+            page.text(
+                MARGIN,
+                band_y - 16.0,
+                Font::Regular,
+                8.0,
+                Color::MUTED,
+                &identity.join(" · "),
+            );
+        }
+        self.y = CONTENT_TOP;
+    }
+
+    fn ensure(&mut self, height: f32) {
+        if self.y - height < CONTENT_BOTTOM {
+            self.new_page();
+        }
+    }
+
+    fn gap(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.ensure(36.0);
+        self.y -= 18.0;
+        let accent = self.branding.accent;
+        let page = self.doc.last_page();
+        page.text(MARGIN, self.y, Font::Bold, 13.0, Color::BLACK, text);
+        page.rule(
+            MARGIN,
+            self.y - 6.0,
+            MARGIN + 36.0,
+            self.y - 6.0,
+            2.0,
+            accent,
+        );
+        self.y -= 14.0;
+    }
+
+    fn paragraph(&mut self, text: &str, size: f32) {
+        let leading = size * 1.45;
+        for line in pdf::wrap(text, Font::Regular, size, CONTENT_WIDTH) {
+            self.ensure(leading);
+            self.y -= leading;
+            self.doc
+                .last_page()
+                .text(MARGIN, self.y, Font::Regular, size, Color::BLACK, &line);
+        }
+    }
+
+    /// Two-column label/value list.
+    fn details(&mut self, rows: &[(String, String)]) {
+        for (label, value) in rows.iter().filter(|(_, value)| !value.is_empty()) {
+            let lines = pdf::wrap(value, Font::Regular, 10.0, CONTENT_WIDTH * 0.65);
+            self.ensure(ROW_HEIGHT * lines.len() as f32);
+            self.y -= ROW_HEIGHT;
+            let page = self.doc.last_page();
+            page.text(MARGIN, self.y, Font::Regular, 9.0, Color::MUTED, label);
+            for (index, line) in lines.iter().enumerate() {
+                page.text(
+                    MARGIN + CONTENT_WIDTH * 0.35,
+                    self.y - ROW_HEIGHT * index as f32,
+                    Font::Regular,
+                    10.0,
+                    Color::BLACK,
+                    line,
+                );
+            }
+            self.y -= ROW_HEIGHT * (lines.len().saturating_sub(1)) as f32;
+        }
+    }
+
+    /// Table with a tinted header row that repeats after page breaks. Column
+    /// widths are fractions of the content width; cells that do not fit are
+    /// truncated with an ellipsis.
+    fn table(&mut self, columns: &[(&str, f32, Align)], rows: &[Vec<String>]) {
+        self.table_header(columns);
+        for (index, row) in rows.iter().enumerate() {
+            if self.y - ROW_HEIGHT < CONTENT_BOTTOM {
+                self.new_page();
+                self.table_header(columns);
+            }
+            self.y -= ROW_HEIGHT;
+            let stripe = self.branding.accent.tint(0.95);
+            let page = self.doc.last_page();
+            if index % 2 == 1 {
+                page.fill_rect(MARGIN, self.y - 5.0, CONTENT_WIDTH, ROW_HEIGHT, stripe);
+            }
+            let mut x = MARGIN;
+            for ((_, fraction, align), cell) in columns.iter().zip(row) {
+                let width = CONTENT_WIDTH * fraction;
+                let text = fit(cell, Font::Regular, 9.0, width - 8.0);
+                match align {
+                    Align::Left => {
+                        page.text(x + 4.0, self.y, Font::Regular, 9.0, Color::BLACK, &text)
+                    }
+                    Align::Right => page.text_right(
+                        x + width - 4.0,
+                        self.y,
+                        Font::Regular,
+                        9.0,
+                        Color::BLACK,
+                        &text,
+                    ),
+                }
+                x += width;
+            }
+        }
+        self.y -= 8.0;
+    }
+
+    fn table_header(&mut self, columns: &[(&str, f32, Align)]) {
+        self.ensure(ROW_HEIGHT * 2.0);
+        self.y -= ROW_HEIGHT;
+        let fill = self.branding.accent.tint(0.85);
+        let page = self.doc.last_page();
+        page.fill_rect(MARGIN, self.y - 5.0, CONTENT_WIDTH, ROW_HEIGHT, fill);
+        let mut x = MARGIN;
+        for (label, fraction, align) in columns {
+            let width = CONTENT_WIDTH * fraction;
+            match align {
+                Align::Left => page.text(x + 4.0, self.y, Font::Bold, 9.0, Color::BLACK, label),
+                Align::Right => page.text_right(
+                    x + width - 4.0,
+                    self.y,
+                    Font::Bold,
+                    9.0,
+                    Color::BLACK,
+                    label,
+                ),
+            }
+            x += width;
+        }
+    }
+
+    /// Label/amount line; `emphasis` draws the grand total in the accent.
+    fn amount_line(&mut self, label: &str, amount: &str, emphasis: bool) {
+        let height = if emphasis { 26.0 } else { ROW_HEIGHT };
+        self.ensure(height);
+        self.y -= height;
+        let accent = self.branding.accent;
+        let page = self.doc.last_page();
+        if emphasis {
+            page.fill_rect(MARGIN, self.y - 8.0, CONTENT_WIDTH, 24.0, accent.tint(0.9));
+            page.text(MARGIN + 6.0, self.y, Font::Bold, 11.0, Color::BLACK, label);
+            page.text_right(
+                PAGE_WIDTH - MARGIN - 6.0,
+                self.y,
+                Font::Bold,
+                12.0,
+                accent,
+                amount,
+            );
+        } else {
+            page.text(
+                MARGIN + 6.0,
+                self.y,
+                Font::Regular,
+                10.0,
+                Color::BLACK,
+                label,
+            );
+            page.text_right(
+                PAGE_WIDTH - MARGIN - 6.0,
+                self.y,
+                Font::Regular,
+                10.0,
+                Color::BLACK,
+                amount,
+            );
+            page.rule(
+                MARGIN,
+                self.y - 5.0,
+                PAGE_WIDTH - MARGIN,
+                self.y - 5.0,
+                0.5,
+                Color::RULE,
+            );
+        }
+    }
+
+    fn signatures(&mut self, labels: &[String]) {
+        self.ensure(90.0);
+        self.y -= 70.0;
+        let slot = CONTENT_WIDTH / labels.len().max(1) as f32;
+        let page = self.doc.last_page();
+        for (index, label) in labels.iter().enumerate() {
+            let x = MARGIN + slot * index as f32;
+            page.rule(x + 8.0, self.y, x + slot - 16.0, self.y, 0.8, Color::BLACK);
+            page.text(
+                x + 8.0,
+                self.y - 14.0,
+                Font::Regular,
+                9.0,
+                Color::MUTED,
+                label,
+            );
+        }
+        self.y -= 20.0;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let total = self.doc.page_count();
+        let generated = format!(
+            "{} {} · {}",
+            self.lang.pick("Generado el", "Generated on"),
+            format_date(&Utc::now().date_naive().to_string(), self.lang),
+            self.branding.name
+        );
+        for index in 0..total {
+            let label = match self.lang {
+                Lang::Es => format!("Página {} de {total}", index + 1),
+                Lang::En => format!("Page {} of {total}", index + 1),
+            };
+            if let Some(page) = self.doc.page_mut(index) {
+                page.rule(MARGIN, 44.0, PAGE_WIDTH - MARGIN, 44.0, 0.5, Color::RULE);
+                page.text(MARGIN, 30.0, Font::Regular, 8.0, Color::MUTED, &generated);
+                page.text_right(
+                    PAGE_WIDTH - MARGIN,
+                    30.0,
+                    Font::Regular,
+                    8.0,
+                    Color::MUTED,
+                    &label,
+                );
+            }
+        }
+        self.doc.to_bytes()
+    }
+}
+
+/// Truncate `text` with an ellipsis so it fits `width` points.
+fn fit(text: &str, font: Font, size: f32, width: f32) -> String {
+    if pdf::text_width(text, font, size) <= width {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    for c in text.chars() {
+        if pdf::text_width(&format!("{out}{c}…"), font, size) > width {
+            break;
+        }
+        out.push(c);
+    }
+    format!("{out}…")
+}
+
+fn value_text(row: &Value, key: &str) -> String {
+    match row.get(key) {
+        Some(Value::String(text)) => text.trim().to_string(),
+        Some(Value::Number(number)) => number.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn value_number(row: &Value, key: &str) -> f64 {
+    match row.get(key) {
+        Some(Value::Number(number)) => number.as_f64().unwrap_or(0.0),
+        Some(Value::String(text)) => text.trim().parse().unwrap_or(0.0),
+        _ => 0.0,
+    }
+}
+
+fn bucket_label(bucket: &str, lang: Lang) -> String {
+    let label = match bucket {
+        "gross_revenue" => lang.pick("Ingresos por reservas", "Reservation revenue"),
+        "lease_collections" => lang.pick("Cobros de alquiler", "Lease collections"),
+        "platform_fees" => lang.pick("Comisiones de plataforma", "Platform fees"),
+        "taxes_collected" => lang.pick("Impuestos cobrados", "Taxes collected"),
+        "operating_expenses" => lang.pick("Gastos operativos", "Operating expenses"),
+        "service_fees" => lang.pick("Honorarios de servicio", "Service fees"),
+        "collection_fees" => lang.pick("Comisiones de cobranza", "Collection fees"),
+        "management_fees" => lang.pick("Honorarios de administración", "Management fees"),
+        other => other,
+    };
+    label.to_string()
+}
+
+/// Statement data for [`render_owner_statement`]: the stored row plus the
+/// breakdown recomputed for it.
+pub struct StatementPdf<'a> {
+    pub statement: &'a Value,
+    pub property_name: Option<String>,
+    pub unit_name: Option<String>,
+    pub line_items: &'a [Value],
+    pub fx_rates: &'a [Value],
+}
+
+pub fn render_owner_statement(branding: &Branding, lang: Lang, input: &StatementPdf) -> Vec<u8> {
+    let statement = input.statement;
+    let currency = value_text(statement, "currency");
+    let currency = if currency.is_empty() {
+        "PYG".to_string()
+    } else {
+        currency
+    };
+    let money = |amount: f64| format_money(amount, &currency, lang);
+
+    let title = lang.pick("Estado de cuenta del propietario", "Owner statement");
+    let mut sheet = Sheet::new(branding, lang, title);
+
+    sheet.heading(title);
+    sheet.details(&[
+        (
+            lang.pick("Período", "Period").to_string(),
+            format!(
+                "{} – {}",
+                format_date(&value_text(statement, "period_start"), lang),
+                format_date(&value_text(statement, "period_end"), lang)
+            ),
+        ),
+        (
+            lang.pick("Propiedad", "Property").to_string(),
+            input.property_name.clone().unwrap_or_default(),
+        ),
+        (
+            lang.pick("Unidad", "Unit").to_string(),
+            input.unit_name.clone().unwrap_or_default(),
+        ),
+        (
+            lang.pick("Moneda", "Currency").to_string(),
+            currency.clone(),
+        ),
+        (
+            lang.pick("Estado", "Status").to_string(),
+            value_text(statement, "status"),
+        ),
+        (
+            lang.pick("Referencia", "Reference").to_string(),
+            value_text(statement, "id"),
+        ),
+    ]);
+
+    sheet.heading(lang.pick("Resumen", "Summary"));
+    for (bucket, positive) in [
+        ("gross_revenue", true),
+        ("lease_collections", true),
+        ("platform_fees", false),
+        ("service_fees", false),
+        ("collection_fees", false),
+        ("management_fees", false),
+        ("operating_expenses", false),
+    ] {
+        let amount = value_number(statement, bucket);
+        if amount == 0.0 && !positive {
+            continue;
+        }
+        let shown = if positive { amount } else { -amount };
+        sheet.amount_line(&bucket_label(bucket, lang), &money(shown), false);
+    }
+    let taxes = value_number(statement, "taxes_collected");
+    if taxes != 0.0 {
+        sheet.amount_line(
+            &format!(
+                "{} ({})",
+                bucket_label("taxes_collected", lang),
+                lang.pick("informativo", "informational")
+            ),
+            &money(taxes),
+            false,
+        );
+    }
+    for (label, amount) in [
+        (
+            lang.pick("Saldo anterior", "Previous balance"),
+            value_number(statement, "previous_balance"),
+        ),
+        (
+            lang.pick("Uso del fondo de reserva", "Reserve draw"),
+            value_number(statement, "reserve_draw"),
+        ),
+        (
+            lang.pick("Aporte al fondo de reserva", "Reserve top-up"),
+            -value_number(statement, "reserve_top_up"),
+        ),
+    ] {
+        if amount != 0.0 {
+            sheet.amount_line(label, &money(amount), false);
+        }
+    }
+    sheet.gap(4.0);
+    sheet.amount_line(
+        lang.pick("Neto a liquidar", "Net payout"),
+        &money(value_number(statement, "net_payout")),
+        true,
+    );
+    let carried = value_number(statement, "balance_carried_forward");
+    if carried != 0.0 {
+        sheet.amount_line(
+            lang.pick("Saldo a cargo del propietario", "Balance owed by owner"),
+            &money(-carried),
+            false,
+        );
+    }
+
+    let management_fees = input
+        .line_items
+        .iter()
+        .filter(|item| value_text(item, "bucket") == "management_fees")
+        .collect::<Vec<_>>();
+    if !management_fees.is_empty() {
+        sheet.heading(lang.pick(
+            "Cálculo de honorarios de administración",
+            "Management fee calculation",
+        ));
+        for item in management_fees {
+            sheet.amount_line(
+                &value_text(item, "label"),
+                &money(-value_number(item, "amount")),
+                false,
+            );
+            sheet.paragraph(&value_text(item, "explanation"), 8.0);
+        }
+    }
+
+    if !input.line_items.is_empty() {
+        sheet.heading(lang.pick("Detalle de movimientos", "Transaction detail"));
+        let rows = input
+            .line_items
+            .iter()
+            .map(|item| {
+                let date = match value_text(item, "date") {
+                    date if !date.is_empty() => format_date(&date, lang),
+                    _ => format_date(&value_text(item, "from"), lang),
+                };
+                let mut source = value_text(item, "kind").replace('_', " ");
+                let original_currency = value_text(item, "original_currency");
+                if !original_currency.is_empty() {
+                    source = format!(
+                        "{source} ({})",
+                        format_money(
+                            value_number(item, "original_amount"),
+                            &original_currency,
+                            lang
+                        )
+                    );
+                }
+                let amount = if item.get("amount").is_some() {
+                    value_number(item, "amount")
+                } else {
+                    value_number(item, "amount_pyg")
+                };
+                vec![
+                    date,
+                    bucket_label(&value_text(item, "bucket"), lang),
+                    source,
+                    money(amount),
+                ]
+            })
+            .collect::<Vec<_>>();
+        sheet.table(
+            &[
+                (lang.pick("Fecha", "Date"), 0.16, Align::Left),
+                (lang.pick("Concepto", "Category"), 0.3, Align::Left),
+                (lang.pick("Detalle", "Detail"), 0.32, Align::Left),
+                (lang.pick("Importe", "Amount"), 0.22, Align::Right),
+            ],
+            &rows,
+        );
+    }
+
+    if !input.fx_rates.is_empty() {
+        let mut pairs = input
+            .fx_rates
+            .iter()
+            .map(|rate| format!("{}→{}", value_text(rate, "base"), value_text(rate, "quote")))
+            .collect::<Vec<_>>();
+        pairs.sort();
+        pairs.dedup();
+        sheet.gap(6.0);
+        sheet.paragraph(
+            &format!(
+                "{} {}.",
+                lang.pick(
+                    "Los importes en otras monedas se convirtieron al tipo de cambio vigente en la fecha de cada transacción:",
+                    "Amounts in other currencies were converted at the exchange rate in effect on each transaction date:"
+                ),
+                pairs.join(", ")
+            ),
+            8.0,
+        );
+    }
+
+    sheet.finish()
+}
+
+/// Data printed on a payment receipt.
+pub struct ReceiptPdf {
+    pub receipt_number: String,
+    pub payer_name: String,
+    pub property_name: Option<String>,
+    pub unit_name: Option<String>,
+    pub due_date: String,
+    pub paid_at: String,
+    pub amount: f64,
+    pub currency: String,
+    pub payment_method: Option<String>,
+    pub payment_reference: Option<String>,
+    /// CDC and QR link of the collection's SIFEN electronic invoice.
+    pub sifen_cdc: Option<String>,
+    pub sifen_qr_url: Option<String>,
+}
+
+pub fn render_payment_receipt(branding: &Branding, lang: Lang, input: &ReceiptPdf) -> Vec<u8> {
+    let title = lang.pick("Recibo de pago", "Payment receipt");
+    let mut sheet = Sheet::new(branding, lang, title);
+    let amount = format_money(input.amount, &input.currency, lang);
+
+    sheet.heading(&format!("{title} Nº {}", input.receipt_number));
+    sheet.paragraph(
+        &match lang {
+            Lang::Es => format!(
+                "Recibimos de {} la suma de {amount} en concepto de alquiler con vencimiento el {}.",
+                input.payer_name,
+                format_date(&input.due_date, lang)
+            ),
+            Lang::En => format!(
+                "Received from {} the sum of {amount} for rent due on {}.",
+                input.payer_name,
+                format_date(&input.due_date, lang)
+            ),
+        },
+        10.0,
+    );
+    sheet.gap(8.0);
+    sheet.details(&[
+        (
+            lang.pick("Inquilino", "Tenant").to_string(),
+            input.payer_name.clone(),
+        ),
+        (
+            lang.pick("Propiedad", "Property").to_string(),
+            input.property_name.clone().unwrap_or_default(),
+        ),
+        (
+            lang.pick("Unidad", "Unit").to_string(),
+            input.unit_name.clone().unwrap_or_default(),
+        ),
+        (
+            lang.pick("Fecha de pago", "Payment date").to_string(),
+            format_date(&input.paid_at, lang),
+        ),
+        (
+            lang.pick("Medio de pago", "Payment method").to_string(),
+            input
+                .payment_method
+                .clone()
+                .unwrap_or_default()
+                .replace('_', " "),
+        ),
+        (
+            lang.pick("Referencia", "Reference").to_string(),
+            input.payment_reference.clone().unwrap_or_default(),
+        ),
+    ]);
+    sheet.gap(8.0);
+    sheet.amount_line(lang.pick("Total recibido", "Total received"), &amount, true);
+    if let Some(cdc) = &input.sifen_cdc {
+        sheet.gap(8.0);
+        sheet.paragraph(
+            &format!(
+                "{} CDC {cdc}",
+                lang.pick("Documento electrónico SIFEN:", "SIFEN electronic document:")
+            ),
+            8.0,
+        );
+        if let Some(qr_url) = &input.sifen_qr_url {
+            sheet.paragraph(qr_url, 7.0);
+        }
+    }
+    sheet.signatures(&[branding
+        .legal_name
+        .clone()
+        .unwrap_or_else(|| branding.name.clone())]);
+
+    sheet.finish()
+}
+
+/// Render and store the receipt for a paid collection, then link it from
+/// `collection_records.receipt_pdf_url`. Returns the updated collection row.
+pub async fn generate_collection_receipt(
+    state: &AppState,
+    pool: &sqlx::PgPool,
+    collection: &Value,
+    lang: Option<&str>,
+) -> AppResult<(Value, StoredObject)> {
+    let collection_id = value_text(collection, "id");
+    let org_id = value_text(collection, "organization_id");
+    if value_text(collection, "status") != "paid" {
+        return Err(AppError::BadRequest(
+            "Receipts can only be generated for paid collections.".to_string(),
+        ));
+    }
+
+    let lease = sqlx::query(
+        "SELECT l.tenant_full_name, p.name AS property_name, u.name AS unit_name
+           FROM leases l
+           LEFT JOIN properties p ON p.id = l.property_id
+           LEFT JOIN units u ON u.id = l.unit_id
+          WHERE l.id = $1::uuid",
+    )
+    .bind(value_text(collection, "lease_id"))
+    .fetch_optional(pool)
+    .await
+    .map_err(|error| AppError::from_database_error(&error, "Failed to load lease."))?;
+    let lease_text = |column: &str| {
+        lease
+            .as_ref()
+            .and_then(|row| row.try_get::<Option<String>, _>(column).ok().flatten())
+            .filter(|value| !value.trim().is_empty())
+    };
+
+    let branding = load_branding(pool, &state.http_client, &org_id).await?;
+    let lang = branding.lang_for(lang)?;
+    let paid_at = value_text(collection, "paid_at");
+    let receipt = ReceiptPdf {
+        receipt_number: receipt_number(&collection_id, &paid_at),
+        payer_name: lease_text("tenant_full_name").unwrap_or_default(),
+        property_name: lease_text("property_name"),
+        unit_name: lease_text("unit_name"),
+        due_date: value_text(collection, "due_date"),
+        paid_at,
+        amount: value_number(collection, "amount"),
+        currency: value_text(collection, "currency"),
+        payment_method: Some(value_text(collection, "payment_method"))
+            .filter(|value| !value.is_empty()),
+        payment_reference: Some(value_text(collection, "payment_reference"))
+            .filter(|value| !value.is_empty()),
+        sifen_cdc: Some(value_text(collection, "sifen_cdc")).filter(|value| !value.is_empty()),
+        sifen_qr_url: Some(value_text(collection, "sifen_qr_url"))
+            .filter(|value| !value.is_empty()),
+    };
+    let bytes = render_payment_receipt(&branding, lang, &receipt);
+    let stored = store_pdf(
+        &state.config,
+        StorageNamespace::Receipts,
+        &org_id,
+        "receipts",
+        &collection_id,
+        bytes,
+    )
+    .await?;
+
+    let mut patch = serde_json::Map::new();
+    patch.insert(
+        "receipt_pdf_url".to_string(),
+        Value::String(stored.public_url.clone()),
+    );
+    let updated = update_row(pool, "collection_records", &collection_id, &patch, "id").await?;
+    Ok((updated, stored))
+}
+
+/// Receipt numbers are stable per collection: payment month plus the first
+/// block of the collection id.
+fn receipt_number(collection_id: &str, paid_at: &str) -> String {
+    let month = paid_at
+        .get(..7)
+        .map(|value| value.replace('-', ""))
+        .unwrap_or_else(|| Utc::now().format("%Y%m").to_string());
+    let short = collection_id.split('-').next().unwrap_or(collection_id);
+    format!("{month}-{}", short.to_ascii_uppercase())
+}
+
+/// Data printed on a security deposit deduction letter.
+pub struct DepositLetterPdf<'a> {
+    pub tenant_name: String,
+    pub property_name: Option<String>,
+    pub unit_name: Option<String>,
+    pub lease_starts_on: String,
+    pub lease_ends_on: Option<String>,
+    pub currency: String,
+    pub received: f64,
+    pub interest: f64,
+    pub deductions: &'a [Value],
+    pub refund: f64,
+    pub balance_due: f64,
+    pub inspected_at: Option<String>,
+}
+
+pub fn render_deposit_letter(branding: &Branding, lang: Lang, input: &DepositLetterPdf) -> Vec<u8> {
+    let title = lang.pick(
+        "Liquidación de depósito de garantía",
+        "Security deposit settlement",
+    );
+    let mut sheet = Sheet::new(branding, lang, title);
+    let money = |amount: f64| format_money(amount, &input.currency, lang);
+
+    sheet.heading(title);
+    sheet.paragraph(
+        &match lang {
+            Lang::Es => format!(
+                "Estimado/a {}: detallamos a continuación la liquidación del depósito de garantía de su contrato, con los descuentos aplicados al finalizar la ocupación.",
+                input.tenant_name
+            ),
+            Lang::En => format!(
+                "Dear {}: below is the settlement of the security deposit on your lease, with the deductions made at move-out.",
+                input.tenant_name
+            ),
+        },
+        10.0,
+    );
+    sheet.gap(8.0);
+    sheet.details(&[
+        (
+            lang.pick("Inquilino", "Tenant").to_string(),
+            input.tenant_name.clone(),
+        ),
+        (
+            lang.pick("Propiedad", "Property").to_string(),
+            input.property_name.clone().unwrap_or_default(),
+        ),
+        (
+            lang.pick("Unidad", "Unit").to_string(),
+            input.unit_name.clone().unwrap_or_default(),
+        ),
+        (
+            lang.pick("Contrato", "Lease").to_string(),
+            match &input.lease_ends_on {
+                Some(ends_on) => format!(
+                    "{} – {}",
+                    format_date(&input.lease_starts_on, lang),
+                    format_date(ends_on, lang)
+                ),
+                None => format_date(&input.lease_starts_on, lang),
+            },
+        ),
+        (
+            lang.pick("Inspección de salida", "Move-out inspection")
+                .to_string(),
+            input
+                .inspected_at
+                .as_deref()
+                .map(|value| format_date(value, lang))
+                .unwrap_or_default(),
+        ),
+    ]);
+
+    if !input.deductions.is_empty() {
+        sheet.heading(lang.pick("Descuentos", "Deductions"));
+        let rows = input
+            .deductions
+            .iter()
+            .map(|item| {
+                let mut evidence = Vec::new();
+                let photos = item
+                    .get("photo_urls")
+                    .and_then(Value::as_array)
+                    .map_or(0, Vec::len);
+                if photos > 0 {
+                    evidence.push(format!("{photos} {}", lang.pick("fotos", "photos")));
+                }
+                if !value_text(item, "maintenance_request_id").is_empty() {
+                    evidence.push(
+                        lang.pick("orden de mantenimiento", "work order")
+                            .to_string(),
+                    );
+                }
+                vec![
+                    value_text(item, "category").replace('_', " "),
+                    value_text(item, "description"),
+                    evidence.join(", "),
+                    money(value_number(item, "amount")),
+                ]
+            })
+            .collect::<Vec<_>>();
+        sheet.table(
+            &[
+                (lang.pick("Concepto", "Category"), 0.18, Align::Left),
+                (lang.pick("Detalle", "Detail"), 0.44, Align::Left),
+                (lang.pick("Respaldo", "Evidence"), 0.18, Align::Left),
+                (lang.pick("Importe", "Amount"), 0.2, Align::Right),
+            ],
+            &rows,
+        );
+    }
+
+    sheet.heading(lang.pick("Resumen", "Summary"));
+    sheet.amount_line(
+        lang.pick("Depósito recibido", "Deposit received"),
+        &money(input.received),
+        false,
+    );
+    if input.interest > 0.0 {
+        sheet.amount_line(
+            lang.pick("Intereses", "Interest"),
+            &money(input.interest),
+            false,
+        );
+    }
+    let deductions = input
+        .deductions
+        .iter()
+        .map(|item| value_number(item, "amount"))
+        .sum::<f64>();
+    sheet.amount_line(
+        lang.pick("Descuentos", "Deductions"),
+        &money(-deductions),
+        false,
+    );
+    sheet.amount_line(
+        lang.pick("Monto a devolver", "Amount refunded"),
+        &money(input.refund),
+        true,
+    );
+    if input.balance_due > 0.0 {
+        sheet.amount_line(
+            lang.pick("Saldo a cargo del inquilino", "Balance owed by tenant"),
+            &money(input.balance_due),
+            true,
+        );
+    }
+    sheet.signatures(&[branding
+        .legal_name
+        .clone()
+        .unwrap_or_else(|| branding.name.clone())]);
+
+    sheet.finish()
+}
+
+/// A contract rendered from a template. Blank lines separate paragraphs.
+pub fn render_contract(
+    branding: &Branding,
+    lang: Lang,
+    title: &str,
+    body: &str,
+    tenant_name: &str,
+) -> Vec<u8> {
+    let mut sheet = Sheet::new(branding, lang, lang.pick("Contrato", "Contract"));
+    sheet.heading(title);
+    for paragraph in body.split("\n\n") {
+        let paragraph = paragraph.trim();
+        if paragraph.is_empty() {
+            continue;
+        }
+        sheet.paragraph(paragraph, 10.0);
+        sheet.gap(6.0);
+    }
+    let landlord = branding
+        .legal_name
+        .clone()
+        .unwrap_or_else(|| branding.name.clone());
+    sheet.signatures(&[
+        format!("{} — {landlord}", lang.pick("Locador", "Landlord")),
+        format!("{} — {tenant_name}", lang.pick("Locatario", "Tenant")),
+    ]);
+    sheet.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn branding() -> Branding {
+        Branding {
+            name: "Casa Demo".to_string(),
+            legal_name: Some("Casa Demo S.A.".to_string()),
+            ruc: Some("80012345-6".to_string()),
+            accent: Color(0.2, 0.3, 0.8),
+            logo: None,
+            lang: Lang::Es,
+        }
+    }
+
+    #[test]
+    fn formats_money_per_language() {
+        assert_eq!(format_money(1_234_567.4, "PYG", Lang::Es), "Gs. 1.234.567");
+        assert_eq!(format_money(1234.5, "USD", Lang::En), "US$ 1,234.50");
+        assert_eq!(format_money(-980.0, "BRL", Lang::Es), "-R$ 980,00");
+        assert_eq!(format_money(12.0, "CLP", Lang::En), "CLP 12.00");
+        assert_eq!(format_date("2026-03-05", Lang::Es), "05/03/2026");
+        assert_eq!(format_date("2026-03-05T10:00:00Z", Lang::En), "Mar 5, 2026");
+        assert_eq!(Lang::parse("EN"), Some(Lang::En));
+        assert!(branding().lang_for(Some("pt")).is_err());
+    }
+
+    #[test]
+    fn long_statements_break_across_pages() {
+        let statement = json!({
+            "id": "st-1",
+            "currency": "PYG",
+            "period_start": "2026-02-01",
+            "period_end": "2026-02-28",
+            "gross_revenue": 5_000_000,
+            "operating_expenses": 250_000,
+            "net_payout": 4_750_000,
+            "status": "finalized",
+        });
+        let items = (0..80)
+            .map(|index| {
+                json!({
+                    "bucket": "gross_revenue",
+                    "kind": "reservation_total",
+                    "from": "2026-02-01",
+                    "amount": 62_500 + index,
+                })
+            })
+            .collect::<Vec<_>>();
+        let bytes = render_owner_statement(
+            &branding(),
+            Lang::Es,
+            &StatementPdf {
+                statement: &statement,
+                property_name: Some("Edificio Centro".to_string()),
+                unit_name: None,
+                line_items: &items,
+                fx_rates: &[],
+            },
+        );
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(bytes.starts_with(b"%PDF-1.4"));
+        assert!(text.contains("/Count 3"), "expected three pages");
+    }
+
+    #[test]
+    fn fits_cells_with_an_ellipsis() {
+        assert_eq!(fit("corto", Font::Regular, 9.0, 100.0), "corto");
+        let cut = fit("una descripción muy larga", Font::Regular, 9.0, 40.0);
+        assert!(cut.ends_with('…'));
+        assert!(pdf::text_width(&cut, Font::Regular, 9.0) <= 40.0);
+    }
+}
@@ -0,0 +1,381 @@
+//! Permission model: every protected action is a `resource.action` pair with
+//! the built-in roles that hold it by default. Members can instead be given
+//! an organization-defined custom role that lists its permissions explicitly
+//! and may be limited to property groups. `tenancy::assert_permission` is the
+//! single entry point routes use.
+
+use serde_json::{json, Value};
+
+use crate::services::api_keys;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permission {
+    pub resource: &'static str,
+    pub action: &'static str,
+    /// Built-in member roles granted this permission.
+    pub default_roles: &'static [&'static str],
+}
+
+impl Permission {
+    const fn new(
+        resource: &'static str,
+        action: &'static str,
+        default_roles: &'static [&'static str],
+    ) -> Self {
+        Self {
+            resource,
+            action,
+            default_roles,
+        }
+    }
+
+    pub fn key(&self) -> String {
+        format!("{}.{}", self.resource, self.action)
+    }
+}
+
+const ADMIN: &[&str] = &["owner_admin"];
+const OPS: &[&str] = &["owner_admin", "operator"];
+const FINANCE: &[&str] = &["owner_admin", "accountant"];
+const STAFF: &[&str] = &["owner_admin", "operator", "accountant"];
+const FIELD: &[&str] = &["owner_admin", "operator", "cleaner"];
+
+// Organization administration
+pub const ORGANIZATION_MANAGE: Permission = Permission::new("organization", "manage", ADMIN);
+pub const MEMBERS_MANAGE: Permission = Permission::new("members", "manage", ADMIN);
+pub const ROLES_MANAGE: Permission = Permission::new("roles", "manage", ADMIN);
+pub const API_KEYS_MANAGE: Permission = Permission::new("api_keys", "manage", ADMIN);
+pub const BILLING_MANAGE: Permission = Permission::new("billing", "manage", ADMIN);
+pub const WEBHOOKS_MANAGE: Permission = Permission::new("webhooks", "manage", ADMIN);
+pub const INTEGRATIONS_MANAGE: Permission = Permission::new("integrations", "manage", ADMIN);
+pub const INTEGRATIONS_SYNC: Permission = Permission::new("integrations", "sync", OPS);
+
+// Portfolio and operations
+pub const PROPERTIES_MANAGE: Permission = Permission::new("properties", "manage", ADMIN);
+pub const RESERVATIONS_EDIT: Permission = Permission::new("reservations", "edit", OPS);
+pub const RESERVATION_DEPOSITS_VIEW: Permission =
+    Permission::new("reservation_deposits", "view", STAFF);
+pub const RESERVATION_DEPOSITS_EDIT: Permission =
+    Permission::new("reservation_deposits", "edit", OPS);
+pub const CALENDAR_EDIT: Permission = Permission::new("calendar", "edit", OPS);
+pub const CANCELLATION_POLICIES_EDIT: Permission =
+    Permission::new("cancellation_policies", "edit", OPS);
+pub const GUESTS_EDIT: Permission = Permission::new("guests", "edit", OPS);
+pub const GUESTS_VERIFY: Permission = Permission::new("guests", "verify", ADMIN);
+pub const TASKS_EDIT: Permission = Permission::new("tasks", "edit", OPS);
+pub const TASKS_COMPLETE: Permission = Permission::new("tasks", "complete", FIELD);
+pub const MAINTENANCE_EDIT: Permission = Permission::new("maintenance", "edit", OPS);
+pub const PRICING_EDIT: Permission = Permission::new("pricing", "edit", STAFF);
+pub const MARKETPLACE_EDIT: Permission = Permission::new("marketplace", "edit", OPS);
+pub const REVIEWS_EDIT: Permission = Permission::new("reviews", "edit", OPS);
+pub const MESSAGING_SEND: Permission = Permission::new("messaging", "send", OPS);
+pub const NOTIFICATIONS_EDIT: Permission = Permission::new("notifications", "edit", OPS);
+pub const SEQUENCES_EDIT: Permission = Permission::new("sequences", "edit", OPS);
+pub const SEQUENCES_DELETE: Permission = Permission::new("sequences", "delete", ADMIN);
+pub const WORKFLOWS_EDIT: Permission = Permission::new("workflows", "edit", OPS);
+pub const DOCUMENTS_EDIT: Permission = Permission::new("documents", "edit", STAFF);
+pub const AGENTS_MANAGE: Permission = Permission::new("agents", "manage", OPS);
+pub const AGENT_RUNS_MANAGE: Permission = Permission::new("agent_runs", "manage", OPS);
+pub const APPROVALS_REVIEW: Permission = Permission::new("approvals", "review", STAFF);
+
+// Leasing
+pub const APPLICATIONS_EDIT: Permission = Permission::new("applications", "edit", OPS);
+pub const LEASES_EDIT: Permission = Permission::new("leases", "edit", STAFF);
+pub const LEASES_RENEW: Permission = Permission::new("leases", "renew", ADMIN);
+pub const CONTRACT_TEMPLATES_EDIT: Permission =
+    Permission::new("contract_templates", "edit", ADMIN);
+pub const CONTRACTS_GENERATE: Permission = Permission::new("contracts", "generate", STAFF);
+pub const LEASE_DEPOSITS_EDIT: Permission = Permission::new("lease_deposits", "edit", STAFF);
+pub const LEASE_DEPOSITS_SETTLE: Permission = Permission::new("lease_deposits", "settle", FINANCE);
+pub const COLLECTIONS_EDIT: Permission = Permission::new("collections", "edit", STAFF);
+pub const PAYMENTS_EDIT: Permission = Permission::new("payments", "edit", STAFF);
+pub const LATE_FEES_EDIT: Permission = Permission::new("late_fees", "edit", FINANCE);
+
+// Finance
+pub const EXPENSES_EDIT: Permission = Permission::new("expenses", "edit", FINANCE);
+pub const EXPENSES_APPROVE: Permission = Permission::new("expenses", "approve", ADMIN);
+pub const LEDGER_EDIT: Permission = Permission::new("ledger", "edit", FINANCE);
+pub const BANK_IMPORTS_EDIT: Permission = Permission::new("bank_imports", "edit", FINANCE);
+pub const FX_MANAGE: Permission = Permission::new("fx", "manage", FINANCE);
+pub const INVOICING_EDIT: Permission = Permission::new("invoicing", "edit", FINANCE);
+pub const MANAGEMENT_FEES_EDIT: Permission = Permission::new("management_fees", "edit", FINANCE);
+pub const OWNER_STATEMENTS_EDIT: Permission = Permission::new("owner_statements", "edit", FINANCE);
+pub const OWNER_STATEMENTS_APPROVE: Permission =
+    Permission::new("owner_statements", "approve", ADMIN);
+pub const OWNER_RESERVES_EDIT: Permission = Permission::new("owner_reserves", "edit", FINANCE);
+pub const OWNER_PAYOUTS_EDIT: Permission = Permission::new("owner_payouts", "edit", FINANCE);
+
+pub const CATALOG: &[Permission] = &[
+    ORGANIZATION_MANAGE,
+    MEMBERS_MANAGE,
+    ROLES_MANAGE,
+    API_KEYS_MANAGE,
+    BILLING_MANAGE,
+    WEBHOOKS_MANAGE,
+    INTEGRATIONS_MANAGE,
+    INTEGRATIONS_SYNC,
+    PROPERTIES_MANAGE,
+    RESERVATIONS_EDIT,
+    RESERVATION_DEPOSITS_VIEW,
+    RESERVATION_DEPOSITS_EDIT,
+    CALENDAR_EDIT,
+    CANCELLATION_POLICIES_EDIT,
+    GUESTS_EDIT,
+    GUESTS_VERIFY,
+    TASKS_EDIT,
+    TASKS_COMPLETE,
+    MAINTENANCE_EDIT,
+    PRICING_EDIT,
+    MARKETPLACE_EDIT,
+    REVIEWS_EDIT,
+    MESSAGING_SEND,
+    NOTIFICATIONS_EDIT,
+    SEQUENCES_EDIT,
+    SEQUENCES_DELETE,
+    WORKFLOWS_EDIT,
+    DOCUMENTS_EDIT,
+    AGENTS_MANAGE,
+    AGENT_RUNS_MANAGE,
+    APPROVALS_REVIEW,
+    APPLICATIONS_EDIT,
+    LEASES_EDIT,
+    LEASES_RENEW,
+    CONTRACT_TEMPLATES_EDIT,
+    CONTRACTS_GENERATE,
+    LEASE_DEPOSITS_EDIT,
+    LEASE_DEPOSITS_SETTLE,
+    COLLECTIONS_EDIT,
+    PAYMENTS_EDIT,
+    LATE_FEES_EDIT,
+    EXPENSES_EDIT,
+    EXPENSES_APPROVE,
+    LEDGER_EDIT,
+    BANK_IMPORTS_EDIT,
+    FX_MANAGE,
+    INVOICING_EDIT,
+    MANAGEMENT_FEES_EDIT,
+    OWNER_STATEMENTS_EDIT,
+    OWNER_STATEMENTS_APPROVE,
+    OWNER_RESERVES_EDIT,
+    OWNER_PAYOUTS_EDIT,
+];
+
+/// Administrative permissions a custom role may not carry, so only
+/// `owner_admin` members can change who holds what.
+const RESERVED: &[Permission] = &[MEMBERS_MANAGE, ROLES_MANAGE, API_KEYS_MANAGE];
+
+/// Whether a granted entry (`resource.action` or `resource.*`) covers
+/// `permission`.
+fn grant_covers(grant: &str, permission: Permission) -> bool {
+    match grant.split_once('.') {
+        Some((resource, "*")) => resource == permission.resource,
+        Some((resource, action)) => resource == permission.resource && action == permission.action,
+        None => false,
+    }
+}
+
+/// The permission list of the custom role attached to a membership, if any.
+pub fn custom_role_permissions(membership: &Value) -> Option<Vec<String>> {
+    let permissions = membership.get("custom_role")?.get("permissions")?;
+    Some(
+        permissions
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(ToOwned::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    )
+}
+
+/// Property groups the membership is limited to, combining its custom
+/// role's groups with groups the member is assigned to directly; `None`
+/// when it can reach every property. Owners and admins are never scoped.
+pub fn membership_property_groups(membership: &Value) -> Option<Vec<String>> {
+    if membership.get("role").and_then(Value::as_str) == Some("owner_admin") {
+        return None;
+    }
+    let role_groups = membership
+        .get("custom_role")
+        .and_then(|role| role.get("property_group_ids"));
+    let member_groups = membership.get("property_group_ids");
+
+    let mut groups: Vec<String> = Vec::new();
+    for source in [role_groups, member_groups].into_iter().flatten() {
+        for id in source.as_array().into_iter().flatten() {
+            if let Some(id) = id.as_str().filter(|id| !groups.iter().any(|g| g == id)) {
+                groups.push(id.to_string());
+            }
+        }
+    }
+    (!groups.is_empty()).then_some(groups)
+}
+
+pub fn membership_allows(membership: &Value, permission: Permission) -> bool {
+    if let Some(scopes) = api_keys::membership_scopes(membership) {
+        return api_keys::scopes_allow_role(&scopes, permission.default_roles);
+    }
+    if let Some(grants) = custom_role_permissions(membership) {
+        return grants.iter().any(|grant| grant_covers(grant, permission));
+    }
+    let role = membership
+        .get("role")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    permission.default_roles.contains(&role)
+}
+
+/// The role the AI agent evaluates for tool access. Agent tools write rows
+/// generically and cannot be checked against the catalog, so members on a
+/// custom role get the read-only `viewer` toolset.
+pub fn agent_role(membership: &Value) -> String {
+    if custom_role_permissions(membership).is_some() {
+        return "viewer".to_string();
+    }
+    membership
+        .get("role")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or("viewer")
+        .to_string()
+}
+
+/// Validate and de-duplicate the permission list of a custom role.
+pub fn normalize_grants(requested: &[String]) -> Result<Vec<String>, String> {
+    let mut grants: Vec<String> = Vec::new();
+    for grant in requested {
+        let grant = grant.trim().to_ascii_lowercase();
+        if grant.is_empty() {
+            continue;
+        }
+        let known = match grant.split_once('.') {
+            Some((resource, "*")) => CATALOG.iter().any(|p| p.resource == resource),
+            Some(_) => CATALOG.iter().any(|p| p.key() == grant),
+            None => false,
+        };
+        if !known {
+            return Err(format!("Unknown permission '{grant}'."));
+        }
+        if let Some(reserved) = RESERVED.iter().find(|p| grant_covers(&grant, **p)) {
+            return Err(format!(
+                "Permission '{}' is reserved for owner_admin members.",
+                reserved.key()
+            ));
+        }
+        if !grants.contains(&grant) {
+            grants.push(grant);
+        }
+    }
+    Ok(grants)
+}
+
+/// The catalog as JSON, for role editors.
+pub fn catalog_json() -> Value {
+    json!(CATALOG
+        .iter()
+        .map(|permission| json!({
+            "key": permission.key(),
+            "resource": permission.resource,
+            "action": permission.action,
+            "default_roles": permission.default_roles,
+            "reserved": RESERVED.contains(permission),
+        }))
+        .collect::<Vec<_>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_roles_follow_catalog_defaults() {
+        let accountant = json!({ "role": "accountant" });
+        assert!(membership_allows(&accountant, EXPENSES_EDIT));
+        assert!(!membership_allows(&accountant, EXPENSES_APPROVE));
+        assert!(!membership_allows(&accountant, RESERVATIONS_EDIT));
+
+        let cleaner = json!({ "role": "cleaner" });
+        assert!(membership_allows(&cleaner, TASKS_COMPLETE));
+        assert!(!membership_allows(&cleaner, TASKS_EDIT));
+
+        let key = json!({ "role": "accountant", "api_key_id": "k1", "scopes": ["finance"] });
+        assert!(membership_allows(&key, LEDGER_EDIT));
+        assert!(!membership_allows(&key, OWNER_STATEMENTS_APPROVE));
+    }
+
+    #[test]
+    fn custom_roles_replace_role_defaults() {
+        let bookkeeper = json!({
+            "role": "operator",
+            "custom_role": {
+                "permissions": ["expenses.*", "ledger.edit"],
+                "property_group_ids": []
+            }
+        });
+        assert!(membership_allows(&bookkeeper, EXPENSES_APPROVE));
+        assert!(membership_allows(&bookkeeper, LEDGER_EDIT));
+        assert!(!membership_allows(&bookkeeper, RESERVATIONS_EDIT));
+        assert!(membership_property_groups(&bookkeeper).is_none());
+        assert_eq!(agent_role(&bookkeeper), "viewer");
+        assert_eq!(agent_role(&json!({ "role": "operator" })), "operator");
+
+        let field_agent = json!({
+            "role": "operator",
+            "custom_role": {
+                "permissions": ["maintenance.*", "tasks.complete"],
+                "property_group_ids": ["g1"]
+            }
+        });
+        assert!(membership_allows(&field_agent, MAINTENANCE_EDIT));
+        assert!(!membership_allows(&field_agent, TASKS_EDIT));
+        assert_eq!(
+            membership_property_groups(&field_agent),
+            Some(vec!["g1".to_string()])
+        );
+    }
+
+    #[test]
+    fn member_group_assignments_merge_with_role_groups() {
+        let regional = json!({
+            "role": "operator",
+            "property_group_ids": ["g2", "g1"],
+            "custom_role": { "permissions": ["tasks.*"], "property_group_ids": ["g1"] }
+        });
+        assert_eq!(
+            membership_property_groups(&regional),
+            Some(vec!["g1".to_string(), "g2".to_string()])
+        );
+
+        let assigned = json!({ "role": "accountant", "property_group_ids": ["g3"] });
+        assert_eq!(
+            membership_property_groups(&assigned),
+            Some(vec!["g3".to_string()])
+        );
+
+        let owner = json!({ "role": "owner_admin", "property_group_ids": ["g3"] });
+        assert!(membership_property_groups(&owner).is_none());
+        assert!(membership_property_groups(&json!({ "role": "operator" })).is_none());
+    }
+
+    #[test]
+    fn grants_are_validated_against_the_catalog() {
+        let grants = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            normalize_grants(&grants(&["Expenses.Approve", "tasks.*", "tasks.*"])).unwrap(),
+            grants(&["expenses.approve", "tasks.*"])
+        );
+        assert!(normalize_grants(&grants(&["expenses.delete"])).is_err());
+        assert!(normalize_grants(&grants(&["nothing.*"])).is_err());
+        assert!(normalize_grants(&grants(&["members.manage"])).is_err());
+        assert!(normalize_grants(&grants(&["*"])).is_err());
+
+        let mut keys = CATALOG.iter().map(Permission::key).collect::<Vec<_>>();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), CATALOG.len());
+    }
+}
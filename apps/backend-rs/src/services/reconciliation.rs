@@ -5,7 +5,14 @@ use sqlx::{PgPool, Row};
 use crate::{
     config::WorkflowEngineMode,
     repository::table_service::{create_row, get_row, update_row},
-    services::workflows::fire_trigger,
+    services::{
+        bank_statements::{self, StatementLine},
+        payment_ledger::{self, NewPayment, PaymentSource},
+        pdf_documents,
+        storage::storage_enabled,
+        workflows::fire_trigger,
+    },
+    state::AppState,
 };
 
 /// Result of reconciling a payment against a collection record.
@@ -29,15 +36,18 @@ pub enum ReconciliationOutcome {
 
 /// Reconcile a payment received against its linked collection record.
 ///
-/// Handles 3 cases:
-/// - **Exact match**: amount_paid == expected → status = `paid`
-/// - **Partial payment**: amount_paid < expected → update `amount_paid`, keep `pending`
-/// - **Overpayment**: amount_paid > expected → status = `paid`, emit notification
+/// The payment is recorded in the allocation ledger (see
+/// [`payment_ledger::record_payment`]), targeted at the instruction's
+/// collection. Handles 3 cases:
+/// - **Exact match**: the balance is covered → status = `paid`
+/// - **Partial payment**: less than the balance → `amount_paid` grows, status unchanged
+/// - **Overpayment**: more than the balance → status = `paid`, the excess
+///   settles older balances or is kept as lease credit, and staff are notified
 pub async fn reconcile_payment(
     pool: &PgPool,
     instruction: &Value,
     payment_amount: f64,
-    payment_method: &str,
+    source: PaymentSource,
     payment_reference: &str,
     engine_mode: WorkflowEngineMode,
 ) -> ReconciliationResult {
@@ -58,86 +68,79 @@ pub async fn reconcile_payment(
     )
     .await;
 
+    let no_collection = |collection_id: String| ReconciliationResult {
+        outcome: ReconciliationOutcome::NoCollection,
+        collection_id,
+        expected: 0.0,
+        paid: payment_amount,
+        remaining: 0.0,
+    };
     if collection_id.is_empty() {
-        return ReconciliationResult {
-            outcome: ReconciliationOutcome::NoCollection,
-            collection_id: String::new(),
-            expected: 0.0,
-            paid: payment_amount,
-            remaining: 0.0,
-        };
+        return no_collection(String::new());
     }
 
     // Fetch the collection record
-    let collection = match get_row(pool, "collection_records", &collection_id, "id").await {
-        Ok(c) => c,
-        Err(_) => {
-            return ReconciliationResult {
-                outcome: ReconciliationOutcome::NoCollection,
-                collection_id,
-                expected: 0.0,
-                paid: payment_amount,
-                remaining: 0.0,
-            };
-        }
+    let Ok(collection) = get_row(pool, "collection_records", &collection_id, "id").await else {
+        return no_collection(collection_id);
     };
 
-    let expected_amount = collection
-        .as_object()
-        .and_then(|o| o.get("amount"))
-        .and_then(|v| {
-            v.as_f64()
-                .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
-        })
-        .unwrap_or(0.0);
-
-    let prior_paid = collection
-        .as_object()
-        .and_then(|o| o.get("amount_paid"))
-        .and_then(|v| {
-            v.as_f64()
-                .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
-        })
-        .unwrap_or(0.0);
-
+    let expected_amount = val_f64(&collection, "amount");
+    let prior_paid = val_f64(&collection, "amount_paid");
     let currency = val_str(&collection, "currency");
-    let total_paid = prior_paid + payment_amount;
 
-    // Small tolerance for floating point (0.01)
-    let is_exact = (total_paid - expected_amount).abs() < 0.01;
-    let is_overpayment = total_paid > expected_amount + 0.01;
+    let recorded = match payment_ledger::record_payment(
+        pool,
+        &NewPayment {
+            organization_id: val_str(&collection, "organization_id"),
+            lease_id: val_str(&collection, "lease_id"),
+            source,
+            amount: payment_amount,
+            currency: currency.clone(),
+            payment_method: None,
+            received_at: None,
+            reference: Some(payment_reference.to_string()),
+            external_id: Some(payment_reference.to_string()),
+            bank_transaction_id: None,
+            notes: None,
+            created_by_user_id: None,
+        },
+        std::slice::from_ref(&collection_id),
+    )
+    .await
+    {
+        Ok(recorded) => recorded,
+        Err(error) => {
+            tracing::error!(collection_id, error = %error, "Failed to record payment in ledger");
+            return no_collection(collection_id);
+        }
+    };
 
-    let (outcome, new_status) = if is_exact || is_overpayment {
-        let outcome = if is_overpayment {
-            ReconciliationOutcome::Overpayment
-        } else {
-            ReconciliationOutcome::ExactMatch
+    let total_paid = prior_paid + recorded.allocated_to(&collection_id);
+    let remaining = (expected_amount - total_paid).max(0.0);
+    if recorded.duplicate {
+        // Webhook retry: the payment was already applied.
+        return ReconciliationResult {
+            outcome: if remaining < 0.01 {
+                ReconciliationOutcome::ExactMatch
+            } else {
+                ReconciliationOutcome::PartialPayment
+            },
+            collection_id,
+            expected: expected_amount,
+            paid: total_paid,
+            remaining,
         };
-        (outcome, "paid")
+    }
+
+    let overpayment = payment_amount - recorded.allocated_to(&collection_id);
+    let outcome = if remaining >= 0.01 {
+        ReconciliationOutcome::PartialPayment
+    } else if overpayment >= 0.01 {
+        ReconciliationOutcome::Overpayment
     } else {
-        (ReconciliationOutcome::PartialPayment, "pending")
+        ReconciliationOutcome::ExactMatch
     };
 
-    // Update the collection record
-    let mut cr_patch = Map::new();
-    cr_patch.insert("status".to_string(), Value::String(new_status.to_string()));
-    cr_patch.insert("amount_paid".to_string(), json!(total_paid));
-    cr_patch.insert(
-        "payment_method".to_string(),
-        Value::String(payment_method.to_string()),
-    );
-    cr_patch.insert(
-        "payment_reference".to_string(),
-        Value::String(payment_reference.to_string()),
-    );
-    if new_status == "paid" {
-        cr_patch.insert(
-            "paid_at".to_string(),
-            Value::String(Utc::now().to_rfc3339()),
-        );
-    }
-    let _ = update_row(pool, "collection_records", &collection_id, &cr_patch, "id").await;
-
     // Fire workflow trigger
     if !org_id.is_empty() {
         let mut wf_ctx = Map::new();
@@ -147,7 +150,13 @@ pub async fn reconcile_payment(
         );
         wf_ctx.insert(
             "payment_method".to_string(),
-            Value::String(payment_method.to_string()),
+            Value::String(
+                match source {
+                    PaymentSource::Stripe => "card",
+                    other => other.as_str(),
+                }
+                .to_string(),
+            ),
         );
         wf_ctx.insert(
             "reference_code".to_string(),
@@ -166,7 +175,6 @@ pub async fn reconcile_payment(
 
     // On overpayment, create a notification for the org
     if outcome == ReconciliationOutcome::Overpayment && !org_id.is_empty() {
-        let overpayment = total_paid - expected_amount;
         let amount_display = if currency == "PYG" {
             format!("₲{}", overpayment as i64)
         } else {
@@ -184,7 +192,7 @@ pub async fn reconcile_payment(
         notification.insert(
             "body".to_string(),
             Value::String(format!(
-                "Payment ref {reference_code} received {amount_display} more than expected for collection {collection_id}. Review and issue a refund or credit if needed."
+                "Payment ref {reference_code} received {amount_display} more than the balance of collection {collection_id}. The excess was applied to older balances or kept as credit on the lease; issue a refund if needed."
             )),
         );
         notification.insert(
@@ -193,18 +201,13 @@ pub async fn reconcile_payment(
                 "type": "overpayment",
                 "collection_id": collection_id,
                 "overpayment_amount": overpayment,
+                "credit_amount": recorded.credit,
                 "currency": currency,
             }),
         );
         let _ = create_row(pool, "notifications", &notification).await;
     }
 
-    let remaining = if total_paid >= expected_amount {
-        0.0
-    } else {
-        expected_amount - total_paid
-    };
-
     ReconciliationResult {
         outcome,
         collection_id,
@@ -214,8 +217,15 @@ pub async fn reconcile_payment(
     }
 }
 
-/// Queue a WhatsApp payment receipt notification.
-pub async fn queue_payment_receipt(pool: &PgPool, instruction: &Value, payment_amount: f64) {
+/// Queue a WhatsApp payment receipt notification. When the payment settled
+/// its collection and storage is configured, the receipt PDF is rendered and
+/// linked in the message; a rendering failure only drops the link.
+pub async fn queue_payment_receipt(
+    state: &AppState,
+    pool: &PgPool,
+    instruction: &Value,
+    payment_amount: f64,
+) {
     let tenant_phone = val_str(instruction, "tenant_phone_e164");
     let org_id = val_str(instruction, "organization_id");
     let reference_code = val_str(instruction, "reference_code");
@@ -231,8 +241,12 @@ pub async fn queue_payment_receipt(pool: &PgPool, instruction: &Value, payment_a
         format!("${payment_amount:.2}")
     };
 
+    let receipt_link = match receipt_pdf_url(state, pool, instruction).await {
+        Some(url) => format!("\n\nRecibo: {url}"),
+        None => String::new(),
+    };
     let body = format!(
-        "✅ Pago recibido\n\nTu pago de {amount_display} (ref: {reference_code}) ha sido procesado exitosamente.\n\n— Casaora"
+        "✅ Pago recibido\n\nTu pago de {amount_display} (ref: {reference_code}) ha sido procesado exitosamente.{receipt_link}\n\n— Casaora"
     );
 
     let mut msg = Map::new();
@@ -250,6 +264,26 @@ pub async fn queue_payment_receipt(pool: &PgPool, instruction: &Value, payment_a
     let _ = create_row(pool, "message_logs", &msg).await;
 }
 
+async fn receipt_pdf_url(state: &AppState, pool: &PgPool, instruction: &Value) -> Option<String> {
+    let collection_id = val_str(instruction, "collection_record_id");
+    if collection_id.is_empty() || !storage_enabled(&state.config) {
+        return None;
+    }
+    let collection = get_row(pool, "collection_records", &collection_id, "id")
+        .await
+        .ok()?;
+    if val_str(&collection, "status") != "paid" {
+        return None;
+    }
+    match pdf_documents::generate_collection_receipt(state, pool, &collection, None).await {
+        Ok((_, stored)) => Some(stored.public_url),
+        Err(error) => {
+            tracing::warn!(collection_id, error = %error, "Could not render payment receipt PDF");
+            None
+        }
+    }
+}
+
 /// Auto-reconcile all pending collection records by scanning for matching payments.
 /// Used by the finance-agent tool.
 pub async fn tool_auto_reconcile_all(
@@ -355,94 +389,271 @@ pub async fn tool_import_bank_transactions(
         return Ok(json!({ "ok": false, "error": "transactions array is required." }));
     };
 
-    let mut imported = 0u32;
     let mut skipped = 0u32;
-
-    for txn in txns {
-        let date = txn
-            .get("date")
-            .or_else(|| txn.get("transaction_date"))
-            .and_then(Value::as_str)
-            .unwrap_or_default();
-        let description = txn
-            .get("description")
-            .or_else(|| txn.get("desc"))
-            .and_then(Value::as_str)
-            .unwrap_or_default();
+    let mut lines = Vec::new();
+    let mut raw_rows = Vec::new();
+    for (index, txn) in txns.iter().enumerate() {
+        let text = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| txn.get(*key).and_then(Value::as_str))
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(ToOwned::to_owned)
+        };
+        let date = text(&["date", "transaction_date"])
+            .and_then(|date| chrono::NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d").ok());
         let amount = txn.get("amount").and_then(Value::as_f64).unwrap_or(0.0);
-        let reference = txn
-            .get("reference")
-            .or_else(|| txn.get("ref"))
-            .and_then(Value::as_str);
-        let currency = txn.get("currency").and_then(Value::as_str).unwrap_or("PYG");
-        let external_id = txn.get("external_id").and_then(Value::as_str);
-        let counterparty = txn
-            .get("counterparty_name")
-            .or_else(|| txn.get("counterparty"))
-            .and_then(Value::as_str);
-
-        if date.is_empty() || amount.abs() < 0.001 {
+        let Some(date) = date.filter(|_| amount.abs() >= 0.001) else {
             skipped += 1;
             continue;
-        }
+        };
 
-        let direction = if amount >= 0.0 { "credit" } else { "debit" };
+        lines.push(StatementLine {
+            line: index + 1,
+            date,
+            amount,
+            currency: text(&["currency"]).unwrap_or_else(|| "PYG".to_string()),
+            description: text(&["description", "desc"]).unwrap_or_default(),
+            reference: text(&["reference", "ref"]),
+            counterparty: text(&["counterparty_name", "counterparty"]),
+            external_id: text(&["external_id"]),
+        });
+        raw_rows.push(txn.clone());
+    }
 
-        // Skip duplicates based on external_id
-        if let Some(eid) = external_id {
-            let existing: Option<(String,)> = sqlx::query_as(
-                "SELECT id::text FROM bank_transactions
-                 WHERE organization_id = $1::uuid AND external_id = $2
-                 LIMIT 1",
-            )
-            .bind(org_id)
-            .bind(eid)
-            .fetch_optional(pool)
-            .await
-            .ok()
-            .flatten();
-            if existing.is_some() {
-                skipped += 1;
-                continue;
-            }
-        }
+    let hashes = bank_statements::dedupe_hashes(&lines, None);
+    let (imported, duplicates) = insert_bank_transactions(
+        pool, org_id, None, bank_name, None, &lines, &hashes, &raw_rows,
+    )
+    .await;
 
-        sqlx::query(
+    Ok(json!({
+        "ok": true,
+        "imported": imported,
+        "skipped": skipped + duplicates,
+        "bank_name": bank_name,
+    }))
+}
+
+/// Insert statement lines as unmatched bank transactions, skipping any
+/// whose dedupe hash (or bank-assigned external id) is already stored.
+/// Returns `(inserted, duplicates)`.
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_bank_transactions(
+    pool: &PgPool,
+    org_id: &str,
+    import_id: Option<&str>,
+    bank_name: &str,
+    account_number: Option<&str>,
+    lines: &[StatementLine],
+    hashes: &[String],
+    raw_rows: &[Value],
+) -> (u32, u32) {
+    let mut inserted = 0u32;
+    let mut duplicates = 0u32;
+    for ((line, hash), raw) in lines.iter().zip(hashes).zip(raw_rows) {
+        let result = sqlx::query(
             "INSERT INTO bank_transactions
-                (organization_id, external_id, bank_name, transaction_date, description,
-                 amount, currency, direction, reference, counterparty_name, raw_data)
-             VALUES ($1::uuid, $2, $3, $4::date, $5, $6, $7, $8, $9, $10, $11::jsonb)",
+                (organization_id, import_id, dedupe_hash, external_id, bank_name, account_number,
+                 transaction_date, description, amount, currency, direction, reference,
+                 counterparty_name, raw_data)
+             SELECT $1::uuid, $2::uuid, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14::jsonb
+              WHERE $4::text IS NULL
+                 OR NOT EXISTS (
+                   SELECT 1 FROM bank_transactions
+                    WHERE organization_id = $1::uuid AND external_id = $4
+                 )
+             ON CONFLICT (organization_id, dedupe_hash) WHERE dedupe_hash IS NOT NULL
+             DO NOTHING",
         )
         .bind(org_id)
-        .bind(external_id)
+        .bind(import_id)
+        .bind(hash)
+        .bind(line.external_id.as_deref())
         .bind(bank_name)
-        .bind(date)
-        .bind(description)
-        .bind(amount.abs())
-        .bind(currency)
-        .bind(direction)
-        .bind(reference)
-        .bind(counterparty)
-        .bind(txn)
+        .bind(account_number)
+        .bind(line.date)
+        .bind(&line.description)
+        .bind(line.amount.abs())
+        .bind(&line.currency)
+        .bind(line.direction())
+        .bind(line.reference.as_deref())
+        .bind(line.counterparty.as_deref())
+        .bind(raw)
         .execute(pool)
-        .await
-        .ok();
+        .await;
 
-        imported += 1;
+        match result {
+            Ok(done) if done.rows_affected() > 0 => inserted += 1,
+            Ok(_) => duplicates += 1,
+            Err(error) => {
+                tracing::error!(error = %error, line = line.line, "Failed to insert bank transaction");
+            }
+        }
     }
+    (inserted, duplicates)
+}
 
-    Ok(json!({
-        "ok": true,
-        "imported": imported,
-        "skipped": skipped,
-        "bank_name": bank_name,
-    }))
+/// An unmatched incoming bank transaction, as seen by [`plan_matches`].
+#[derive(Debug, Clone, Default)]
+pub struct BankCredit {
+    pub id: String,
+    pub date: Option<chrono::NaiveDate>,
+    pub amount: f64,
+    pub reference: String,
+    pub description: String,
+    pub counterparty: String,
+}
+
+/// A collection still waiting for payment, with the reference code of its
+/// payment instruction and the tenant's name.
+#[derive(Debug, Clone, Default)]
+pub struct OpenCollection {
+    pub id: String,
+    pub amount: f64,
+    pub due_date: Option<chrono::NaiveDate>,
+    pub reference_code: String,
+    pub tenant_name: String,
 }
 
-/// Multi-pass auto-reconciliation engine:
-/// 1. Exact reference match
+type MatchRule = dyn Fn(&BankCredit, &OpenCollection) -> bool;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedMatch {
+    pub transaction_id: String,
+    pub collection_id: String,
+    pub confidence: f64,
+    pub method: &'static str,
+}
+
+impl PlannedMatch {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "transaction_id": self.transaction_id,
+            "collection_id": self.collection_id,
+            "confidence": self.confidence,
+            "method": self.method,
+        })
+    }
+}
+
+/// Pair bank credits with open collections in three passes, each taking
+/// only what earlier passes left:
+/// 1. Exact reference match (or the reference code quoted in the description)
 /// 2. Amount + date range (±3 days, exact amount)
 /// 3. Fuzzy match (5% tolerance, tenant name in description)
+///
+/// A transaction and a collection are each matched at most once.
+pub fn plan_matches(credits: &[BankCredit], collections: &[OpenCollection]) -> Vec<PlannedMatch> {
+    let mut planned: Vec<PlannedMatch> = Vec::new();
+    let mut used_txns = std::collections::HashSet::new();
+    let mut used_collections = std::collections::HashSet::new();
+
+    let passes: [(&'static str, f64, &MatchRule); 4] = [
+        ("exact_reference", 1.0, &|txn, col| {
+            !txn.reference.is_empty() && col.reference_code == txn.reference
+        }),
+        ("reference_in_description", 0.95, &|txn, col| {
+            col.reference_code.len() >= 6
+                && txn
+                    .description
+                    .to_uppercase()
+                    .contains(&col.reference_code.to_uppercase())
+        }),
+        ("amount_date", 0.85, &|txn, col| {
+            (txn.amount - col.amount).abs() <= 0.01
+                && match (txn.date, col.due_date) {
+                    (Some(paid), Some(due)) => (paid - due).num_days().abs() <= 3,
+                    _ => false,
+                }
+        }),
+        ("fuzzy_name", 0.70, &|txn, col| {
+            if (txn.amount - col.amount).abs() > col.amount * 0.05 {
+                return false;
+            }
+            let description = txn.description.to_lowercase();
+            let counterparty = txn.counterparty.to_lowercase();
+            col.tenant_name
+                .to_lowercase()
+                .split_whitespace()
+                .any(|part| {
+                    part.len() >= 3 && (description.contains(part) || counterparty.contains(part))
+                })
+        }),
+    ];
+
+    for (method, confidence, matches) in passes {
+        for txn in credits {
+            if used_txns.contains(&txn.id) {
+                continue;
+            }
+            let Some(col) = collections
+                .iter()
+                .find(|col| !used_collections.contains(&col.id) && matches(txn, col))
+            else {
+                continue;
+            };
+            used_txns.insert(txn.id.clone());
+            used_collections.insert(col.id.clone());
+            planned.push(PlannedMatch {
+                transaction_id: txn.id.clone(),
+                collection_id: col.id.clone(),
+                confidence,
+                method,
+            });
+        }
+    }
+    planned
+}
+
+/// Collections still waiting for payment, optionally limited to one
+/// `YYYY-MM` due month.
+pub async fn load_open_collections(
+    pool: &PgPool,
+    org_id: &str,
+    period_month: &str,
+) -> Vec<OpenCollection> {
+    let rows = sqlx::query(
+        "SELECT cr.id::text AS collection_id,
+                (cr.amount - cr.amount_paid)::float8 AS amount, cr.due_date::text,
+                cr.status, pi.reference_code,
+                COALESCE(t.full_name, '') AS tenant_name
+         FROM collection_records cr
+         LEFT JOIN payment_instructions pi ON pi.collection_record_id = cr.id
+         LEFT JOIN leases l ON l.id = cr.lease_id
+         LEFT JOIN app_users t ON t.id = l.tenant_id
+         WHERE cr.organization_id = $1::uuid
+           AND cr.status IN ('scheduled', 'pending', 'late')
+           AND cr.amount - cr.amount_paid > 0
+           AND ($2 = '' OR to_char(cr.due_date, 'YYYY-MM') = $2)
+         ORDER BY cr.due_date ASC",
+    )
+    .bind(org_id)
+    .bind(period_month)
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default();
+
+    rows.iter()
+        .map(|col| OpenCollection {
+            id: col.try_get("collection_id").unwrap_or_default(),
+            amount: col.try_get("amount").unwrap_or(0.0),
+            due_date: col
+                .try_get::<String, _>("due_date")
+                .ok()
+                .and_then(|date| chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()),
+            reference_code: col
+                .try_get::<Option<String>, _>("reference_code")
+                .ok()
+                .flatten()
+                .unwrap_or_default(),
+            tenant_name: col.try_get("tenant_name").unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Multi-pass auto-reconciliation over unmatched bank credits (see
+/// [`plan_matches`]). `import_id` limits the run to one statement import.
 pub async fn tool_auto_reconcile_batch(
     state: &crate::state::AppState,
     org_id: &str,
@@ -456,6 +667,11 @@ pub async fn tool_auto_reconcile_batch(
         .get("period_month")
         .and_then(Value::as_str)
         .unwrap_or_default();
+    let import_id = args
+        .get("import_id")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
 
     // Create reconciliation run
     let run = sqlx::query(
@@ -481,239 +697,86 @@ pub async fn tool_auto_reconcile_batch(
          WHERE organization_id = $1::uuid
            AND match_status = 'unmatched'
            AND direction = 'credit'
+           AND ($2::uuid IS NULL OR import_id = $2::uuid)
          ORDER BY transaction_date DESC
          LIMIT 500",
     )
     .bind(org_id)
+    .bind(import_id)
     .fetch_all(pool)
     .await
     .unwrap_or_default();
 
-    // Fetch pending collections
-    let collections = sqlx::query(
-        "SELECT cr.id::text AS collection_id, cr.amount::float8, cr.due_date::text,
-                cr.status, pi.reference_code,
-                COALESCE(t.full_name, '') AS tenant_name
-         FROM collection_records cr
-         LEFT JOIN payment_instructions pi ON pi.collection_record_id = cr.id
-         LEFT JOIN leases l ON l.id = cr.lease_id
-         LEFT JOIN app_users t ON t.id = l.tenant_id
-         WHERE cr.organization_id = $1::uuid
-           AND cr.status IN ('scheduled', 'pending', 'late')
-           AND ($2 = '' OR to_char(cr.due_date, 'YYYY-MM') = $2)
-         ORDER BY cr.due_date ASC",
-    )
-    .bind(org_id)
-    .bind(period_month)
-    .fetch_all(pool)
-    .await
-    .unwrap_or_default();
-
-    let mut matched_count = 0u32;
-    let partial_count = 0u32;
-    let mut total_matched_amount = 0.0f64;
-    let mut matched_txn_ids = std::collections::HashSet::new();
-
-    // Pass 1: Exact reference match
-    for txn in &txns {
-        let txn_id: String = txn.try_get("id").unwrap_or_default();
-        let txn_ref: String = txn
-            .try_get::<Option<String>, _>("reference")
-            .ok()
-            .flatten()
-            .unwrap_or_default();
-        let txn_amount: f64 = txn.try_get("amount").unwrap_or(0.0);
-
-        if txn_ref.is_empty() || matched_txn_ids.contains(&txn_id) {
-            continue;
-        }
-
-        for col in &collections {
-            let col_ref: String = col
-                .try_get::<Option<String>, _>("reference_code")
-                .ok()
-                .flatten()
-                .unwrap_or_default();
-            let col_id: String = col.try_get("collection_id").unwrap_or_default();
-
-            if col_ref.is_empty() || col_ref != txn_ref {
-                continue;
-            }
-
-            // Match found
-            sqlx::query(
-                "UPDATE bank_transactions
-                 SET match_status = 'matched', match_confidence = 1.0,
-                     matched_collection_id = $3::uuid, match_method = 'exact_reference',
-                     reconciliation_run_id = $4::uuid, updated_at = now()
-                 WHERE id = $1::uuid AND organization_id = $2::uuid",
-            )
-            .bind(&txn_id)
-            .bind(org_id)
-            .bind(&col_id)
-            .bind(&run_id)
-            .execute(pool)
-            .await
-            .ok();
-
-            // Update collection
-            let mut patch = Map::new();
-            patch.insert("status".to_string(), Value::String("paid".to_string()));
-            patch.insert("amount_paid".to_string(), json!(txn_amount));
-            patch.insert(
-                "paid_at".to_string(),
-                Value::String(Utc::now().to_rfc3339()),
-            );
-            let _ = update_row(pool, "collection_records", &col_id, &patch, "id").await;
-
-            matched_count += 1;
-            total_matched_amount += txn_amount;
-            matched_txn_ids.insert(txn_id.clone());
-            break;
-        }
-    }
-
-    // Pass 2: Amount + date range (±3 days, exact amount within 0.01)
-    for txn in &txns {
-        let txn_id: String = txn.try_get("id").unwrap_or_default();
-        if matched_txn_ids.contains(&txn_id) {
-            continue;
-        }
-
-        let txn_amount: f64 = txn.try_get("amount").unwrap_or(0.0);
-        let txn_date: String = txn.try_get("transaction_date").unwrap_or_default();
-
-        for col in &collections {
-            let col_id: String = col.try_get("collection_id").unwrap_or_default();
-            let col_amount: f64 = col.try_get("amount").unwrap_or(0.0);
-            let col_date: String = col.try_get("due_date").unwrap_or_default();
-
-            // Amount must match within 0.01
-            if (txn_amount - col_amount).abs() > 0.01 {
-                continue;
-            }
-
-            // Date within ±3 days
-            let date_ok = if let (Ok(td), Ok(cd)) = (
-                chrono::NaiveDate::parse_from_str(&txn_date, "%Y-%m-%d"),
-                chrono::NaiveDate::parse_from_str(&col_date, "%Y-%m-%d"),
-            ) {
-                (td - cd).num_days().abs() <= 3
-            } else {
-                false
+    let credits: Vec<BankCredit> = txns
+        .iter()
+        .map(|txn| {
+            let text = |column: &str| {
+                txn.try_get::<Option<String>, _>(column)
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default()
             };
-            if !date_ok {
-                continue;
+            BankCredit {
+                id: text("id"),
+                date: chrono::NaiveDate::parse_from_str(&text("transaction_date"), "%Y-%m-%d").ok(),
+                amount: txn.try_get("amount").unwrap_or(0.0),
+                reference: text("reference"),
+                description: text("description"),
+                counterparty: text("counterparty_name"),
             }
+        })
+        .collect();
+    let collections = load_open_collections(pool, org_id, period_month).await;
+    let planned = plan_matches(&credits, &collections);
 
-            sqlx::query(
-                "UPDATE bank_transactions
-                 SET match_status = 'matched', match_confidence = 0.85,
-                     matched_collection_id = $3::uuid, match_method = 'amount_date',
-                     reconciliation_run_id = $4::uuid, updated_at = now()
-                 WHERE id = $1::uuid AND organization_id = $2::uuid",
-            )
-            .bind(&txn_id)
-            .bind(org_id)
-            .bind(&col_id)
-            .bind(&run_id)
-            .execute(pool)
-            .await
-            .ok();
-
-            let mut patch = Map::new();
-            patch.insert("status".to_string(), Value::String("paid".to_string()));
-            patch.insert("amount_paid".to_string(), json!(txn_amount));
-            patch.insert(
-                "paid_at".to_string(),
-                Value::String(Utc::now().to_rfc3339()),
-            );
-            let _ = update_row(pool, "collection_records", &col_id, &patch, "id").await;
-
-            matched_count += 1;
-            total_matched_amount += txn_amount;
-            matched_txn_ids.insert(txn_id.clone());
-            break;
-        }
-    }
+    let mut partial_count = 0u32;
+    let mut total_matched_amount = 0.0f64;
+    for planned_match in &planned {
+        sqlx::query(
+            "UPDATE bank_transactions
+             SET match_status = 'matched', match_confidence = $5,
+                 matched_collection_id = $3::uuid, match_method = $6,
+                 reconciliation_run_id = $4::uuid, updated_at = now()
+             WHERE id = $1::uuid AND organization_id = $2::uuid",
+        )
+        .bind(&planned_match.transaction_id)
+        .bind(org_id)
+        .bind(&planned_match.collection_id)
+        .bind(&run_id)
+        .bind(planned_match.confidence)
+        .bind(planned_match.method)
+        .execute(pool)
+        .await
+        .ok();
 
-    // Pass 3: Fuzzy match (5% tolerance + tenant name in description)
-    for txn in &txns {
-        let txn_id: String = txn.try_get("id").unwrap_or_default();
-        if matched_txn_ids.contains(&txn_id) {
+        let Some(credit) = credits
+            .iter()
+            .find(|txn| txn.id == planned_match.transaction_id)
+        else {
             continue;
-        }
-
-        let txn_amount: f64 = txn.try_get("amount").unwrap_or(0.0);
-        let txn_desc: String = txn
-            .try_get::<Option<String>, _>("description")
-            .ok()
-            .flatten()
-            .unwrap_or_default()
-            .to_lowercase();
-        let txn_counterparty: String = txn
-            .try_get::<Option<String>, _>("counterparty_name")
-            .ok()
-            .flatten()
-            .unwrap_or_default()
-            .to_lowercase();
-
-        for col in &collections {
-            let col_id: String = col.try_get("collection_id").unwrap_or_default();
-            let col_amount: f64 = col.try_get("amount").unwrap_or(0.0);
-            let tenant_name: String = col
-                .try_get::<String, _>("tenant_name")
-                .unwrap_or_default()
-                .to_lowercase();
-
-            // Amount within 5%
-            let tolerance = col_amount * 0.05;
-            if (txn_amount - col_amount).abs() > tolerance {
-                continue;
+        };
+        match record_bank_credit(pool, credit, &planned_match.collection_id).await {
+            Ok(recorded) if !recorded.duplicate => {
+                let settled = recorded.allocations.iter().any(|allocation| {
+                    allocation.collection_id == planned_match.collection_id && allocation.settled
+                });
+                if !settled {
+                    partial_count += 1;
+                }
             }
-
-            // Tenant name must appear in description or counterparty
-            if tenant_name.is_empty() {
-                continue;
+            Ok(_) => {}
+            Err(error) => {
+                tracing::warn!(
+                    transaction_id = %credit.id,
+                    error = %error,
+                    "Failed to record matched bank credit"
+                );
             }
-            let name_parts: Vec<&str> = tenant_name.split_whitespace().collect();
-            let name_match = name_parts.iter().any(|part| {
-                part.len() >= 3 && (txn_desc.contains(part) || txn_counterparty.contains(part))
-            });
-            if !name_match {
-                continue;
-            }
-
-            sqlx::query(
-                "UPDATE bank_transactions
-                 SET match_status = 'matched', match_confidence = 0.70,
-                     matched_collection_id = $3::uuid, match_method = 'fuzzy_name',
-                     reconciliation_run_id = $4::uuid, updated_at = now()
-                 WHERE id = $1::uuid AND organization_id = $2::uuid",
-            )
-            .bind(&txn_id)
-            .bind(org_id)
-            .bind(&col_id)
-            .bind(&run_id)
-            .execute(pool)
-            .await
-            .ok();
-
-            let mut patch = Map::new();
-            patch.insert("status".to_string(), Value::String("paid".to_string()));
-            patch.insert("amount_paid".to_string(), json!(txn_amount));
-            patch.insert(
-                "paid_at".to_string(),
-                Value::String(Utc::now().to_rfc3339()),
-            );
-            let _ = update_row(pool, "collection_records", &col_id, &patch, "id").await;
-
-            matched_count += 1;
-            total_matched_amount += txn_amount;
-            matched_txn_ids.insert(txn_id.clone());
-            break;
         }
+
+        total_matched_amount += credit.amount;
     }
+    let matched_count = planned.len() as u32;
 
     // Mark remaining unmatched as exceptions
     let unmatched_count = txns.len() as u32 - matched_count;
@@ -755,6 +818,7 @@ pub async fn tool_auto_reconcile_batch(
         "exceptions": exception_count,
         "match_rate": (match_rate * 100.0).round() / 100.0,
         "total_matched_amount": total_matched_amount,
+        "matches": planned.iter().map(PlannedMatch::to_json).collect::<Vec<_>>(),
     }))
 }
 
@@ -781,10 +845,8 @@ pub async fn tool_handle_split_payment(
         return Ok(json!({ "ok": false, "error": "transaction_ids array is required." }));
     };
 
-    // Fetch collection expected amount
-    let col = sqlx::query(
-        "SELECT amount::float8, amount_paid::float8, status
-         FROM collection_records
+    let collection = sqlx::query(
+        "SELECT id::text FROM collection_records
          WHERE id = $1::uuid AND organization_id = $2::uuid",
     )
     .bind(collection_id)
@@ -795,17 +857,13 @@ pub async fn tool_handle_split_payment(
         tracing::error!(error = %e, "Failed to fetch collection");
         crate::error::AppError::Dependency("Failed to fetch collection.".to_string())
     })?;
-
-    let Some(col_row) = col else {
+    if collection.is_none() {
         return Ok(json!({ "ok": false, "error": "Collection not found." }));
-    };
-
-    let expected: f64 = col_row.try_get("amount").unwrap_or(0.0);
-    let already_paid: f64 = col_row.try_get("amount_paid").unwrap_or(0.0);
+    }
 
-    let mut total_split = already_paid;
+    // Each transfer is its own ledger payment targeted at the collection;
+    // anything beyond its balance flows to older balances or lease credit.
     let mut matched_txns = Vec::new();
-
     for txn_id_val in txn_ids {
         let txn_id = txn_id_val.as_str().unwrap_or_default();
         if txn_id.is_empty() {
@@ -813,7 +871,9 @@ pub async fn tool_handle_split_payment(
         }
 
         let txn = sqlx::query(
-            "SELECT amount::float8 FROM bank_transactions
+            "SELECT id::text, amount::float8, transaction_date::text, reference,
+                    description, counterparty_name
+             FROM bank_transactions
              WHERE id = $1::uuid AND organization_id = $2::uuid AND match_status = 'unmatched'",
         )
         .bind(txn_id)
@@ -824,8 +884,22 @@ pub async fn tool_handle_split_payment(
         .flatten();
 
         if let Some(txn_row) = txn {
-            let amt: f64 = txn_row.try_get("amount").unwrap_or(0.0);
-            total_split += amt;
+            let text = |column: &str| {
+                txn_row
+                    .try_get::<Option<String>, _>(column)
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default()
+            };
+            let credit = BankCredit {
+                id: text("id"),
+                date: chrono::NaiveDate::parse_from_str(&text("transaction_date"), "%Y-%m-%d").ok(),
+                amount: txn_row.try_get("amount").unwrap_or(0.0),
+                reference: text("reference"),
+                description: text("description"),
+                counterparty: text("counterparty_name"),
+            };
+            let recorded = record_bank_credit(pool, &credit, collection_id).await?;
 
             // Link transaction to collection
             sqlx::query(
@@ -842,39 +916,64 @@ pub async fn tool_handle_split_payment(
             .await
             .ok();
 
-            matched_txns.push(json!({"id": txn_id, "amount": amt}));
+            matched_txns.push(json!({
+                "id": txn_id,
+                "amount": credit.amount,
+                "allocated": recorded.allocated_to(collection_id),
+                "credit": recorded.credit,
+            }));
         }
     }
 
-    // Update collection with total
-    let new_status = if (total_split - expected).abs() < 0.01 || total_split >= expected {
-        "paid"
-    } else {
-        "pending"
-    };
-
-    let mut patch = Map::new();
-    patch.insert("amount_paid".to_string(), json!(total_split));
-    patch.insert("status".to_string(), Value::String(new_status.to_string()));
-    if new_status == "paid" {
-        patch.insert(
-            "paid_at".to_string(),
-            Value::String(Utc::now().to_rfc3339()),
-        );
-    }
-    let _ = update_row(pool, "collection_records", collection_id, &patch, "id").await;
+    let collection = get_row(pool, "collection_records", collection_id, "id").await?;
+    let expected = val_f64(&collection, "amount");
+    let total_paid = val_f64(&collection, "amount_paid");
 
     Ok(json!({
         "ok": true,
         "collection_id": collection_id,
         "expected": expected,
-        "total_paid": total_split,
-        "status": new_status,
+        "total_paid": total_paid,
+        "remaining": (expected - total_paid).max(0.0),
+        "status": val_str(&collection, "status"),
         "transactions_matched": matched_txns.len(),
         "transactions": matched_txns,
     }))
 }
 
+/// Record a matched bank credit in the payment ledger, targeted at
+/// `collection_id`. Re-running a match for the same transaction is a no-op.
+async fn record_bank_credit(
+    pool: &PgPool,
+    credit: &BankCredit,
+    collection_id: &str,
+) -> Result<payment_ledger::RecordedPayment, crate::error::AppError> {
+    let collection = get_row(pool, "collection_records", collection_id, "id").await?;
+    let reference = [&credit.reference, &credit.description]
+        .into_iter()
+        .find(|value| !value.trim().is_empty())
+        .cloned();
+    payment_ledger::record_payment(
+        pool,
+        &NewPayment {
+            organization_id: val_str(&collection, "organization_id"),
+            lease_id: val_str(&collection, "lease_id"),
+            source: PaymentSource::BankImport,
+            amount: credit.amount,
+            currency: val_str(&collection, "currency"),
+            payment_method: None,
+            received_at: credit.date.map(|date| date.to_string()),
+            reference,
+            external_id: Some(format!("bank:{}", credit.id)),
+            bank_transaction_id: Some(credit.id.clone()),
+            notes: None,
+            created_by_user_id: None,
+        },
+        &[collection_id.to_string()],
+    )
+    .await
+}
+
 /// Run daily reconciliation for all active orgs.
 pub async fn run_daily_reconciliation(state: &crate::state::AppState) {
     let Some(pool) = state.db_pool.as_ref() else {
@@ -905,3 +1004,70 @@ fn val_str(row: &Value, key: &str) -> String {
         .map(ToOwned::to_owned)
         .unwrap_or_default()
 }
+
+fn val_f64(row: &Value, key: &str) -> f64 {
+    row.as_object()
+        .and_then(|obj| obj.get(key))
+        .and_then(|v| {
+            v.as_f64()
+                .or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))
+        })
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn credit(id: &str, day: u32, amount: f64, reference: &str, description: &str) -> BankCredit {
+        BankCredit {
+            id: id.to_string(),
+            date: NaiveDate::from_ymd_opt(2026, 3, day),
+            amount,
+            reference: reference.to_string(),
+            description: description.to_string(),
+            counterparty: String::new(),
+        }
+    }
+
+    fn open(id: &str, amount: f64, reference: &str, tenant: &str) -> OpenCollection {
+        OpenCollection {
+            id: id.to_string(),
+            amount,
+            due_date: NaiveDate::from_ymd_opt(2026, 3, 5),
+            reference_code: reference.to_string(),
+            tenant_name: tenant.to_string(),
+        }
+    }
+
+    #[test]
+    fn plans_each_pass_without_reusing_collections() {
+        let credits = vec![
+            credit("t1", 5, 2_500_000.0, "PAY-AAA111", ""),
+            credit("t2", 20, 1_800_000.0, "", "Transf. alquiler pay-bbb222"),
+            credit("t3", 6, 900_000.0, "", ""),
+            credit("t4", 6, 900_000.0, "", ""),
+            credit("t5", 28, 1_230_000.0, "", "Deposito GONZALEZ"),
+        ];
+        let collections = vec![
+            open("c1", 2_500_000.0, "PAY-AAA111", "Ana Benitez"),
+            open("c2", 1_800_000.0, "PAY-BBB222", "Luis Acosta"),
+            open("c3", 900_000.0, "", "Rosa Villalba"),
+            open("c4", 1_200_000.0, "", "Maria Gonzalez"),
+        ];
+        let planned: Vec<(String, String, &str)> = plan_matches(&credits, &collections)
+            .into_iter()
+            .map(|m| (m.transaction_id, m.collection_id, m.method))
+            .collect();
+        assert_eq!(
+            planned,
+            vec![
+                ("t1".into(), "c1".into(), "exact_reference"),
+                ("t2".into(), "c2".into(), "reference_in_description"),
+                ("t3".into(), "c3".into(), "amount_date"),
+                ("t5".into(), "c4".into(), "fuzzy_name"),
+            ]
+        );
+    }
+}
@@ -11,12 +11,15 @@ use crate::{
 
 const ACTIVE_RESERVATION_STATUSES: &[&str] = &["pending", "confirmed", "checked_in"];
 
+/// `property_scope` limits the overview to those properties for members
+/// restricted to property groups.
 pub async fn build_reservations_overview(
     pool: &sqlx::PgPool,
     query: &ReservationsOverviewQuery,
+    property_scope: Option<&[String]>,
 ) -> AppResult<Value> {
-    let summary = fetch_overview_summary(pool, query).await?;
-    let rows = fetch_overview_rows(pool, query).await?;
+    let summary = fetch_overview_summary(pool, query, property_scope).await?;
+    let rows = fetch_overview_rows(pool, query, property_scope).await?;
 
     Ok(json!({
         "summary": {
@@ -338,6 +341,7 @@ struct OverviewSummary {
 async fn fetch_overview_summary(
     pool: &sqlx::PgPool,
     query: &ReservationsOverviewQuery,
+    property_scope: Option<&[String]>,
 ) -> AppResult<OverviewSummary> {
     let mut builder = QueryBuilder::<Postgres>::new(
         "SELECT
@@ -349,7 +353,7 @@ async fn fetch_overview_summary(
          FROM (",
     );
 
-    push_overview_base_select(&mut builder, query)?;
+    push_overview_base_select(&mut builder, query, property_scope)?;
     builder.push(") base WHERE 1=1");
     push_overview_outer_filters(&mut builder, query, false, "base");
 
@@ -371,9 +375,10 @@ async fn fetch_overview_summary(
 async fn fetch_overview_rows(
     pool: &sqlx::PgPool,
     query: &ReservationsOverviewQuery,
+    property_scope: Option<&[String]>,
 ) -> AppResult<Vec<Value>> {
     let mut builder = QueryBuilder::<Postgres>::new("SELECT row_to_json(t) AS row FROM (");
-    push_overview_base_select(&mut builder, query)?;
+    push_overview_base_select(&mut builder, query, property_scope)?;
     builder.push(") t WHERE 1=1");
     push_overview_outer_filters(&mut builder, query, true, "t");
     push_overview_sort(&mut builder, query.sort.as_deref());
@@ -399,6 +404,7 @@ async fn fetch_overview_rows(
 fn push_overview_base_select(
     builder: &mut QueryBuilder<'_, Postgres>,
     query: &ReservationsOverviewQuery,
+    property_scope: Option<&[String]>,
 ) -> AppResult<()> {
     builder.push(
         "SELECT
@@ -494,6 +500,12 @@ fn push_overview_base_select(
             .push(" AND p.id = ")
             .push_bind(parse_uuid(&property_id, "property_id")?);
     }
+    if let Some(property_ids) = property_scope {
+        builder
+            .push(" AND p.id::text = ANY(")
+            .push_bind(property_ids.to_vec())
+            .push(")");
+    }
     if let Some(unit_id) = non_empty_opt(query.unit_id.as_deref()) {
         builder
             .push(" AND u.id = ")
@@ -1,3 +1,4 @@
+use std::future::Future;
 use std::time::Duration;
 
 use chrono::{Datelike, Timelike, Utc};
@@ -5,7 +6,15 @@ use serde_json::{json, Value};
 use sqlx::Row;
 use tokio::time::sleep;
 
-use crate::{config::WorkflowEngineMode, state::AppState};
+use crate::{
+    config::WorkflowEngineMode,
+    services::{
+        cron::CronSchedule,
+        scheduler_leases,
+        scheduler_runs::{self, JobOutcome, RunTrigger},
+    },
+    state::AppState,
+};
 
 /// Tables that agent watchers are allowed to scan. Used to prevent SQL injection
 /// since `watch_table` is interpolated into queries.
@@ -18,12 +27,47 @@ const WATCHABLE_TABLES: &[&str] = &[
     "leases",
 ];
 
+/// How long a daily job's lease survives without a heartbeat before another
+/// instance may take it over.
+const DAILY_LEASE_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// How often each instance re-checks whether daily jobs still need to run.
+const DAILY_CHECK_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Lease TTL for an interval job: long enough that the holder keeps the job
+/// across ticks, short enough that a dead holder is replaced within ~2 ticks.
+fn interval_lease_ttl(interval: Duration) -> Duration {
+    interval * 2 + Duration::from_secs(60)
+}
+
+/// Spawn `job` under the named scheduler lease so only one backend instance
+/// runs it per tick. Daily jobs pass the calendar date as `run_key`.
+fn spawn_leased<F>(
+    pool: &sqlx::PgPool,
+    job_name: &'static str,
+    ttl: Duration,
+    run_key: Option<String>,
+    job: F,
+) where
+    F: Future<Output = JobOutcome> + Send + 'static,
+{
+    let pool = pool.clone();
+    tokio::spawn(async move {
+        scheduler_leases::run_leased(&pool, job_name, ttl, run_key.as_deref(), job).await;
+    });
+}
+
 /// Spawn the background scheduler that runs periodic jobs.
 ///
 /// Each job runs in its own `tokio::spawn` so a failure in one job
-/// never crashes the scheduler loop or other jobs.
+/// never crashes the scheduler loop or other jobs. Every job is guarded by
+/// a Postgres lease (`scheduler_leases`) so that when several backend
+/// instances are deployed, each job runs on exactly one of them.
 pub async fn run_background_scheduler(state: AppState) {
-    tracing::info!("Background scheduler started");
+    tracing::info!(
+        instance_id = scheduler_leases::instance_id(),
+        "Background scheduler started"
+    );
 
     let pool = match state.db_pool.as_ref() {
         Some(p) => p.clone(),
@@ -37,6 +81,13 @@ pub async fn run_background_scheduler(state: AppState) {
         Duration::from_secs(state.config.workflow_poll_interval_seconds.max(30));
     let ical_interval = Duration::from_secs(state.config.ical_sync_interval_minutes.max(5) * 60);
     let message_interval = Duration::from_secs(state.config.message_poll_interval_seconds.max(30));
+    let rate_limit_cleanup_interval = Duration::from_secs(3600);
+    let iot_health_interval = Duration::from_secs(300);
+    let event_bus_interval = Duration::from_secs(30);
+    let outbound_webhook_interval = Duration::from_secs(30);
+    let watcher_interval = Duration::from_secs(60);
+    let twin_refresh_interval = Duration::from_secs(300);
+    let agent_schedule_interval = Duration::from_secs(60);
 
     let mut last_workflow_run = tokio::time::Instant::now();
     let mut last_ical_run = tokio::time::Instant::now();
@@ -44,9 +95,11 @@ pub async fn run_background_scheduler(state: AppState) {
     let mut last_rate_limit_cleanup = tokio::time::Instant::now();
     let mut last_iot_health_check = tokio::time::Instant::now();
     let mut last_event_bus_run = tokio::time::Instant::now();
+    let mut last_outbound_webhook_run = tokio::time::Instant::now();
     let mut last_watcher_run = tokio::time::Instant::now();
     let mut last_twin_refresh = tokio::time::Instant::now();
-    let mut last_daily_run: Option<u32> = None;
+    let mut last_agent_schedule_run = tokio::time::Instant::now();
+    let mut last_daily_check: Option<tokio::time::Instant> = None;
 
     loop {
         sleep(Duration::from_secs(15)).await;
@@ -58,343 +111,691 @@ pub async fn run_background_scheduler(state: AppState) {
         // --- Workflow job processing (every N seconds) ---
         if now_instant.duration_since(last_workflow_run) >= workflow_interval {
             last_workflow_run = now_instant;
-            let pool = pool.clone();
-            tokio::spawn(async move {
-                let summary = crate::services::workflows::process_workflow_jobs(&pool, 100).await;
-                if summary.picked > 0 {
-                    tracing::info!(
-                        picked = summary.picked,
-                        succeeded = summary.succeeded,
-                        failed = summary.failed,
-                        "Scheduler: processed workflow jobs"
-                    );
-                }
-            });
+            let job_pool = pool.clone();
+            spawn_leased(
+                &pool,
+                "workflow_jobs",
+                interval_lease_ttl(workflow_interval),
+                None,
+                async move {
+                    let summary =
+                        crate::services::workflows::process_workflow_jobs(&job_pool, 100).await;
+                    if summary.picked > 0 {
+                        tracing::info!(
+                            picked = summary.picked,
+                            succeeded = summary.succeeded,
+                            failed = summary.failed,
+                            "Scheduler: processed workflow jobs"
+                        );
+                    }
+                    Ok(serde_json::to_value(&summary).unwrap_or_default())
+                },
+            );
         }
 
         // --- iCal sync (every N minutes) ---
         if now_instant.duration_since(last_ical_run) >= ical_interval {
             last_ical_run = now_instant;
-            let pool = pool.clone();
+            let job_pool = pool.clone();
             let client = state.http_client.clone();
-            tokio::spawn(async move {
-                let result =
-                    crate::services::ical::sync_all_ical_integrations(&pool, &client).await;
-                let synced = result.get("synced").and_then(|v| v.as_u64()).unwrap_or(0);
-                if synced > 0 {
-                    tracing::info!(synced, "Scheduler: iCal sync completed");
-                }
-            });
+            let concurrency = state.config.ical_sync_concurrency;
+            spawn_leased(
+                &pool,
+                "ical_sync",
+                interval_lease_ttl(ical_interval),
+                None,
+                async move {
+                    let result = crate::services::ical::sync_all_ical_integrations(
+                        &job_pool,
+                        &client,
+                        concurrency,
+                        None,
+                    )
+                    .await;
+                    let synced = result.get("synced").and_then(|v| v.as_u64()).unwrap_or(0);
+                    if synced > 0 {
+                        tracing::info!(synced, "Scheduler: iCal sync completed");
+                    }
+                    Ok(result)
+                },
+            );
         }
 
         // --- S19: Message processing poll (every N seconds) ---
         if now_instant.duration_since(last_message_run) >= message_interval {
             last_message_run = now_instant;
-            let pool = pool.clone();
+            let job_pool = pool.clone();
             let client = state.http_client.clone();
             let config = state.config.clone();
-            tokio::spawn(async move {
-                let (sent, failed) =
-                    crate::services::messaging::process_queued_messages(&pool, &client, &config)
-                        .await;
-                if sent > 0 || failed > 0 {
-                    tracing::info!(sent, failed, "Scheduler: processed queued messages");
-                }
-            });
+            spawn_leased(
+                &pool,
+                "queued_messages",
+                interval_lease_ttl(message_interval),
+                None,
+                async move {
+                    let (sent, failed) = crate::services::messaging::process_queued_messages(
+                        &job_pool, &client, &config, None,
+                    )
+                    .await;
+                    if sent > 0 || failed > 0 {
+                        tracing::info!(sent, failed, "Scheduler: processed queued messages");
+                    }
+                    Ok(json!({ "sent": sent, "failed": failed }))
+                },
+            );
         }
 
         // --- S17: Hourly rate limit table cleanup ---
-        if now_instant.duration_since(last_rate_limit_cleanup) >= Duration::from_secs(3600) {
+        if now_instant.duration_since(last_rate_limit_cleanup) >= rate_limit_cleanup_interval {
             last_rate_limit_cleanup = now_instant;
-            let pool = pool.clone();
-            tokio::spawn(async move {
-                let current_hour = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs() as i64
-                    / 3600;
-                let cutoff = current_hour - 24;
-                let result = sqlx::query("DELETE FROM agent_rate_limits WHERE hour_bucket < $1")
-                    .bind(cutoff)
-                    .execute(&pool)
-                    .await;
-                if let Ok(r) = result {
+            let job_pool = pool.clone();
+            spawn_leased(
+                &pool,
+                "rate_limit_cleanup",
+                interval_lease_ttl(rate_limit_cleanup_interval),
+                None,
+                async move {
+                    let current_hour = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs() as i64
+                        / 3600;
+                    let cutoff = current_hour - 24;
+                    let r = sqlx::query("DELETE FROM agent_rate_limits WHERE hour_bucket < $1")
+                        .bind(cutoff)
+                        .execute(&job_pool)
+                        .await
+                        .map_err(|e| e.to_string())?;
                     if r.rows_affected() > 0 {
                         tracing::info!(
                             deleted = r.rows_affected(),
                             "Scheduler: cleaned up old rate limit entries"
                         );
                     }
-                }
-            });
+                    Ok(json!({ "deleted": r.rows_affected() }))
+                },
+            );
         }
 
         // --- IoT device health check (every 5 minutes) ---
-        if now_instant.duration_since(last_iot_health_check) >= Duration::from_secs(300) {
+        if now_instant.duration_since(last_iot_health_check) >= iot_health_interval {
             last_iot_health_check = now_instant;
-            let pool = pool.clone();
+            let job_pool = pool.clone();
             let engine_mode = state.config.workflow_engine_mode;
-            tokio::spawn(async move {
-                run_iot_device_health_check(&pool, engine_mode).await;
-            });
+            spawn_leased(
+                &pool,
+                "iot_device_health",
+                interval_lease_ttl(iot_health_interval),
+                None,
+                async move { run_iot_device_health_check(&job_pool, engine_mode).await },
+            );
         }
 
         // --- Agent event bus processing (every 30 seconds) ---
-        if now_instant.duration_since(last_event_bus_run) >= Duration::from_secs(30) {
+        if now_instant.duration_since(last_event_bus_run) >= event_bus_interval {
             last_event_bus_run = now_instant;
             let st = state.clone();
-            tokio::spawn(async move {
-                crate::services::event_bus::process_pending_events(&st).await;
-            });
+            spawn_leased(
+                &pool,
+                "agent_event_bus",
+                interval_lease_ttl(event_bus_interval),
+                None,
+                async move {
+                    crate::services::event_bus::process_pending_events(&st).await;
+                    Ok(Value::Null)
+                },
+            );
+        }
+
+        // --- Outbound webhook deliveries (every 30 seconds) ---
+        if now_instant.duration_since(last_outbound_webhook_run) >= outbound_webhook_interval {
+            last_outbound_webhook_run = now_instant;
+            let st = state.clone();
+            spawn_leased(
+                &pool,
+                "outbound_webhooks",
+                interval_lease_ttl(outbound_webhook_interval),
+                None,
+                async move { crate::services::outbound_webhooks::process_due_deliveries(&st).await },
+            );
         }
 
         // --- Agent watcher scan (every 60 seconds) ---
-        if now_instant.duration_since(last_watcher_run) >= Duration::from_secs(60) {
+        if now_instant.duration_since(last_watcher_run) >= watcher_interval {
             last_watcher_run = now_instant;
-            let pool = pool.clone();
+            let job_pool = pool.clone();
             let engine_mode = state.config.workflow_engine_mode;
-            tokio::spawn(async move {
-                run_agent_watchers(&pool, engine_mode).await;
-            });
+            spawn_leased(
+                &pool,
+                "agent_watchers",
+                interval_lease_ttl(watcher_interval),
+                None,
+                async move { run_agent_watchers(&job_pool, engine_mode).await },
+            );
         }
 
         // --- Digital twin refresh (every 5 minutes) ---
-        if now_instant.duration_since(last_twin_refresh) >= Duration::from_secs(300) {
+        if now_instant.duration_since(last_twin_refresh) >= twin_refresh_interval {
             last_twin_refresh = now_instant;
-            let pool = pool.clone();
-            tokio::spawn(async move {
-                crate::services::digital_twin::refresh_all_twins(&pool).await;
-            });
+            let job_pool = pool.clone();
+            spawn_leased(
+                &pool,
+                "digital_twin_refresh",
+                interval_lease_ttl(twin_refresh_interval),
+                None,
+                async move {
+                    crate::services::digital_twin::refresh_all_twins(&job_pool).await;
+                    Ok(Value::Null)
+                },
+            );
         }
 
-        // --- Daily jobs (run once per calendar day) ---
-        let today_ordinal = today.ordinal();
-        if last_daily_run == Some(today_ordinal) {
-            continue;
+        // --- Cron-scheduled agent playbooks (every minute, evaluated in each
+        // schedule's timezone) ---
+        if now_instant.duration_since(last_agent_schedule_run) >= agent_schedule_interval {
+            last_agent_schedule_run = now_instant;
+            let st = state.clone();
+            spawn_leased(
+                &pool,
+                "scheduled_agent_playbooks",
+                interval_lease_ttl(agent_schedule_interval),
+                None,
+                async move { run_scheduled_agent_playbooks(&st).await },
+            );
+            let st = state.clone();
+            spawn_leased(
+                &pool,
+                "cron_agent_playbooks",
+                interval_lease_ttl(agent_schedule_interval),
+                None,
+                async move { run_cron_agent_playbooks(&st).await },
+            );
         }
 
+        // --- Daily jobs (run once per calendar day across all instances) ---
         // Run daily jobs at or after 05:00 UTC
         if now_utc.hour() < 5 {
             continue;
         }
 
-        last_daily_run = Some(today_ordinal);
-        tracing::info!("Scheduler: running daily jobs for {today}");
+        // Each daily lease is keyed by the date, so re-checking is cheap and
+        // lets a surviving instance pick up jobs whose holder died mid-run.
+        if last_daily_check
+            .is_some_and(|last| now_instant.duration_since(last) < DAILY_CHECK_INTERVAL)
+        {
+            continue;
+        }
+        last_daily_check = Some(now_instant);
+        let day_key = Some(today.to_string());
 
         // 05:00 — SLA breach scan
         {
-            let pool = pool.clone();
+            let job_pool = pool.clone();
             let engine_mode = state.config.workflow_engine_mode;
-            tokio::spawn(async move {
-                run_sla_breach_scan(&pool, engine_mode).await;
-            });
+            spawn_leased(
+                &pool,
+                "sla_breach_scan",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move { run_sla_breach_scan(&job_pool, engine_mode).await },
+            );
+        }
+
+        // 05:00 — Store today's exchange rates and fill gaps from the past week
+        {
+            let st = state.clone();
+            spawn_leased(
+                &pool,
+                "fx_rates_refresh",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move { crate::services::fx::run_daily_fx_refresh(&st).await },
+            );
         }
 
         // 06:00 — Daily pricing recommendations per active org
         {
             let st = state.clone();
-            tokio::spawn(async move {
-                crate::services::dynamic_pricing::run_daily_pricing_recommendations(&st).await;
-            });
+            spawn_leased(
+                &pool,
+                "pricing_recommendations",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move {
+                    crate::services::dynamic_pricing::run_daily_pricing_recommendations(&st).await;
+                    Ok(Value::Null)
+                },
+            );
         }
 
         // 06:10 — Auto-apply pricing within org-configured thresholds
         {
             let st = state.clone();
-            tokio::spawn(async move {
-                crate::services::dynamic_pricing::run_daily_pricing_auto_apply(&st).await;
-            });
+            spawn_leased(
+                &pool,
+                "pricing_auto_apply",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move {
+                    crate::services::dynamic_pricing::run_daily_pricing_auto_apply(&st).await;
+                    Ok(Value::Null)
+                },
+            );
         }
 
         // 06:00 — Anomaly scan per active org
         {
             let st = state.clone();
-            tokio::spawn(async move {
-                run_anomaly_scan_all_orgs(&st).await;
-            });
+            spawn_leased(
+                &pool,
+                "anomaly_scan",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move { run_for_active_orgs(&st, "anomaly_scan").await },
+            );
         }
 
         // 07:00 — Lease renewal scan
         {
-            let pool = pool.clone();
-            let app_url = state.config.app_public_url.clone();
-            let engine_mode = state.config.workflow_engine_mode;
-            tokio::spawn(async move {
-                let result = crate::services::lease_renewal::run_lease_renewal_scan(
-                    &pool,
-                    None,
-                    &app_url,
-                    engine_mode,
-                )
-                .await;
-                tracing::info!(
-                    offers_60d = result.offers_sent_60d,
-                    reminders_30d = result.reminders_sent_30d,
-                    "Scheduler: lease renewal scan completed"
-                );
-            });
+            let st = state.clone();
+            spawn_leased(
+                &pool,
+                "lease_renewal_scan",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move { run_for_active_orgs(&st, "lease_renewal_scan").await },
+            );
         }
 
         // 08:00 — Daily collection cycle
         {
-            let pool = pool.clone();
-            let app_url = state.config.app_public_url.clone();
-            tokio::spawn(async move {
-                let result = crate::services::collection_cycle::run_daily_collection_cycle(
-                    &pool, None, &app_url,
-                )
-                .await;
-                tracing::info!(
-                    activated = result.activated,
-                    reminders = result.reminders_queued,
-                    "Scheduler: daily collection cycle completed"
-                );
-            });
+            let st = state.clone();
+            spawn_leased(
+                &pool,
+                "daily_collection_cycle",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move { run_for_active_orgs(&st, "daily_collection_cycle").await },
+            );
         }
 
         // 08:15 — Daily bank transaction reconciliation
         {
             let st = state.clone();
-            tokio::spawn(async move {
-                crate::services::reconciliation::run_daily_reconciliation(&st).await;
-            });
+            spawn_leased(
+                &pool,
+                "daily_reconciliation",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move {
+                    crate::services::reconciliation::run_daily_reconciliation(&st).await;
+                    Ok(Value::Null)
+                },
+            );
         }
 
         // 08:30 — Daily lease deadline alert scan
         {
             let st = state.clone();
-            tokio::spawn(async move {
-                crate::services::lease_abstraction::run_daily_deadline_scan(&st).await;
-            });
+            spawn_leased(
+                &pool,
+                "lease_deadline_scan",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move {
+                    crate::services::lease_abstraction::run_daily_deadline_scan(&st).await;
+                    Ok(Value::Null)
+                },
+            );
         }
 
         // 08:45 — Auto-generate owner statements (1st of month only)
         if today.day() == 1 {
-            let pool = pool.clone();
-            let engine_mode = state.config.workflow_engine_mode;
-            tokio::spawn(async move {
-                let org_ids: Vec<(String,)> = sqlx::query_as(
-                    "SELECT id::text FROM organizations WHERE is_active = true LIMIT 100",
-                )
-                .fetch_all(&pool)
-                .await
-                .unwrap_or_default();
-
-                let mut total = 0u32;
-                for (org_id,) in &org_ids {
-                    total += crate::routes::owner_statements::auto_generate_monthly_statements(
-                        &pool,
-                        org_id,
-                        engine_mode,
-                    )
-                    .await;
-                }
-                if total > 0 {
-                    tracing::info!(total, "Scheduler: owner statements auto-generated");
-                }
-            });
+            let st = state.clone();
+            spawn_leased(
+                &pool,
+                "owner_statements",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move { run_for_active_orgs(&st, "owner_statements").await },
+            );
         }
 
         // 09:00 — Stalled application scan (>48h without response)
         {
-            let pool = pool.clone();
+            let job_pool = pool.clone();
             let engine_mode = state.config.workflow_engine_mode;
-            tokio::spawn(async move {
-                run_stalled_application_scan(&pool, engine_mode).await;
-            });
-        }
-
-        // 09:30 — Run scheduled agent playbooks
-        {
-            let st = state.clone();
-            tokio::spawn(async move {
-                run_scheduled_agent_playbooks(&st).await;
-            });
+            spawn_leased(
+                &pool,
+                "stalled_application_scan",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move { run_stalled_application_scan(&job_pool, engine_mode).await },
+            );
         }
 
         // 10:00 — Nightly portfolio snapshot capture
         {
-            let pool = pool.clone();
-            tokio::spawn(async move {
-                run_portfolio_snapshots(&pool).await;
-            });
+            let st = state.clone();
+            spawn_leased(
+                &pool,
+                "portfolio_snapshots",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move { run_for_active_orgs(&st, "portfolio_snapshots").await },
+            );
         }
 
         // 10:30 — Maintenance SLA monitoring
         {
-            let pool = pool.clone();
-            tokio::spawn(async move {
-                run_maintenance_sla_scan(&pool).await;
-            });
+            let job_pool = pool.clone();
+            spawn_leased(
+                &pool,
+                "maintenance_sla_scan",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move { run_maintenance_sla_scan(&job_pool).await },
+            );
         }
 
-        // 06:30 — S23: Daily outbound OTA rate/availability sync
+        // 06:30 — S23: Daily outbound channel rate/availability/restriction sync
         {
             let st = state.clone();
-            tokio::spawn(async move {
-                crate::services::airbnb::sync_all_outbound_rates(&st).await;
-            });
+            spawn_leased(
+                &pool,
+                "outbound_rate_sync",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move { crate::services::channel_connector::sync_all_outbound_rates(&st).await },
+            );
         }
 
         // 11:00 — Weekly demand forecast (Sundays only)
         if today.weekday() == chrono::Weekday::Sun {
             let st = state.clone();
-            tokio::spawn(async move {
-                run_weekly_demand_forecast(&st).await;
-            });
+            spawn_leased(
+                &pool,
+                "weekly_demand_forecast",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move { run_for_active_orgs(&st, "weekly_demand_forecast").await },
+            );
 
             // S23: Weekly ML feature computation (Sundays)
             let st2 = state.clone();
-            tokio::spawn(async move {
-                crate::services::ml_pipeline::compute_all_features(&st2).await;
-            });
+            spawn_leased(
+                &pool,
+                "weekly_ml_features",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move {
+                    crate::services::ml_pipeline::compute_all_features(&st2).await;
+                    Ok(Value::Null)
+                },
+            );
 
             // ML prediction reactor — fire triggers for actionable predictions
             let pool2 = pool.clone();
             let engine_mode = state.config.workflow_engine_mode;
-            tokio::spawn(async move {
-                // Small delay to let feature computation finish
-                sleep(Duration::from_secs(120)).await;
-                run_ml_prediction_reactor(&pool2, engine_mode).await;
-            });
+            spawn_leased(
+                &pool,
+                "weekly_ml_prediction_reactor",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move {
+                    // Small delay to let feature computation finish
+                    sleep(Duration::from_secs(120)).await;
+                    run_ml_prediction_reactor(&pool2, engine_mode).await
+                },
+            );
 
             // Phase 3: Weekly agent learning digest
             let st3 = state.clone();
-            tokio::spawn(async move {
-                // Delay to let ML pipeline finish first
-                sleep(Duration::from_secs(180)).await;
-                crate::services::ai_agent::compute_weekly_learning_digest(&st3).await;
-            });
+            spawn_leased(
+                &pool,
+                "weekly_agent_learning_digest",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move {
+                    // Delay to let ML pipeline finish first
+                    sleep(Duration::from_secs(180)).await;
+                    crate::services::ai_agent::compute_weekly_learning_digest(&st3).await;
+                    Ok(Value::Null)
+                },
+            );
         }
 
         // 11:30 — Daily agent health metrics collection
         {
             let st = state.clone();
-            tokio::spawn(async move {
-                crate::services::ai_agent::collect_daily_agent_health(&st).await;
-            });
+            spawn_leased(
+                &pool,
+                "agent_health_metrics",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move {
+                    crate::services::ai_agent::collect_daily_agent_health(&st).await;
+                    Ok(Value::Null)
+                },
+            );
         }
 
         // 12:00 — Expired memory cleanup
         {
-            let pool = pool.clone();
-            tokio::spawn(async move {
-                run_memory_cleanup(&pool).await;
-            });
+            let job_pool = pool.clone();
+            spawn_leased(
+                &pool,
+                "agent_memory_cleanup",
+                DAILY_LEASE_TTL,
+                day_key.clone(),
+                async move { run_memory_cleanup(&job_pool).await },
+            );
         }
 
-        // 12:30 — Execute due agent schedules (cron-based playbooks)
+        // 12:15 — Scheduler run history retention
         {
-            let st = state.clone();
-            tokio::spawn(async move {
-                run_cron_agent_playbooks(&st).await;
-            });
+            let job_pool = pool.clone();
+            let retention_days = state.config.scheduler_run_retention_days;
+            spawn_leased(
+                &pool,
+                "scheduler_run_retention",
+                DAILY_LEASE_TTL,
+                day_key,
+                async move {
+                    let deleted = scheduler_runs::prune_runs(&job_pool, retention_days)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    Ok(json!({ "deleted": deleted, "retention_days": retention_days }))
+                },
+            );
+        }
+    }
+}
+
+/// Jobs that can be triggered on demand for a single organization.
+pub const ORG_SCOPED_JOBS: &[&str] = &[
+    "anomaly_scan",
+    "daily_collection_cycle",
+    "ical_sync",
+    "lease_renewal_scan",
+    "owner_statements",
+    "portfolio_snapshots",
+    "queued_messages",
+    "weekly_demand_forecast",
+];
+
+/// Record a `manual` run of `job_name` scoped to `org_id` and execute it in
+/// the background. Returns the `running` run row so callers can poll it.
+///
+/// Manual runs bypass scheduler leases: they touch a single organization and
+/// are explicitly requested by an operator.
+pub async fn spawn_manual_run(
+    state: &AppState,
+    job_name: &str,
+    org_id: &str,
+    requested_by_user_id: &str,
+) -> Result<Value, String> {
+    if !ORG_SCOPED_JOBS.contains(&job_name) {
+        return Err(format!(
+            "Job '{job_name}' cannot be run for a single organization."
+        ));
+    }
+    let pool = state
+        .db_pool
+        .as_ref()
+        .ok_or_else(|| "Database is not configured.".to_string())?;
+
+    let run = scheduler_runs::start_run(
+        pool,
+        job_name,
+        Some(org_id),
+        RunTrigger::Manual,
+        Some(requested_by_user_id),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    let run_id = run
+        .get("id")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+
+    let st = state.clone();
+    let job_name = job_name.to_string();
+    let org_id = org_id.to_string();
+    tokio::spawn(async move {
+        let outcome = run_job_for_org(&st, &job_name, &org_id).await;
+        if let Some(pool) = st.db_pool.as_ref() {
+            scheduler_runs::finish_run(pool, &run_id, &outcome).await;
+        }
+    });
+
+    Ok(run)
+}
+
+/// Scheduled form of an org-scoped job: run it for every active organization,
+/// recording one `schedule` run per organization so each org's history shows
+/// scheduled executions alongside manual ones.
+async fn run_for_active_orgs(state: &AppState, job_name: &str) -> JobOutcome {
+    let Some(pool) = state.db_pool.as_ref() else {
+        return Ok(Value::Null);
+    };
+    let org_ids: Vec<String> = sqlx::query_scalar(
+        "SELECT id::text FROM organizations WHERE is_active = true ORDER BY created_at",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut succeeded = 0u32;
+    let mut failed = 0u32;
+    for org_id in &org_ids {
+        let outcome = scheduler_runs::record_run(
+            pool,
+            job_name,
+            Some(org_id),
+            RunTrigger::Schedule,
+            None,
+            run_job_for_org(state, job_name, org_id),
+        )
+        .await;
+        match outcome {
+            Ok(_) => succeeded += 1,
+            Err(e) => {
+                failed += 1;
+                tracing::warn!(job = job_name, org_id, error = %e, "Scheduler: org run failed");
+            }
+        }
+    }
+
+    if !org_ids.is_empty() {
+        tracing::info!(
+            job = job_name,
+            orgs = org_ids.len(),
+            failed,
+            "Scheduler: org-scoped job completed"
+        );
+    }
+    Ok(json!({ "orgs": org_ids.len(), "succeeded": succeeded, "failed": failed }))
+}
+
+/// Execute one org-scoped job synchronously and return its summary.
+async fn run_job_for_org(state: &AppState, job_name: &str, org_id: &str) -> JobOutcome {
+    let Some(pool) = state.db_pool.as_ref() else {
+        return Err("Database is not configured.".to_string());
+    };
+    let app_url = state.config.app_public_url.as_str();
+    let engine_mode = state.config.workflow_engine_mode;
+
+    match job_name {
+        "anomaly_scan" => run_anomaly_scan_for_org(state, org_id).await,
+        "ical_sync" => Ok(crate::services::ical::sync_all_ical_integrations(
+            pool,
+            &state.http_client,
+            state.config.ical_sync_concurrency,
+            Some(org_id),
+        )
+        .await),
+        "queued_messages" => {
+            let (sent, failed) = crate::services::messaging::process_queued_messages(
+                pool,
+                &state.http_client,
+                &state.config,
+                Some(org_id),
+            )
+            .await;
+            Ok(json!({ "sent": sent, "failed": failed }))
+        }
+        "daily_collection_cycle" => {
+            let result = crate::services::collection_cycle::run_daily_collection_cycle(
+                pool,
+                Some(org_id),
+                app_url,
+            )
+            .await;
+            Ok(serde_json::to_value(&result).unwrap_or_default())
         }
+        "lease_renewal_scan" => {
+            let result = crate::services::lease_renewal::run_lease_renewal_scan(
+                pool,
+                Some(org_id),
+                app_url,
+                engine_mode,
+            )
+            .await;
+            Ok(json!({
+                "offers_sent_60d": result.offers_sent_60d,
+                "reminders_sent_30d": result.reminders_sent_30d,
+                "expired": result.expired,
+            }))
+        }
+        "owner_statements" => {
+            let generated = crate::routes::owner_statements::auto_generate_monthly_statements(
+                pool,
+                &state.http_client,
+                org_id,
+                engine_mode,
+            )
+            .await;
+            Ok(json!({ "generated": generated }))
+        }
+        "portfolio_snapshots" => {
+            crate::services::portfolio::capture_portfolio_snapshot(pool, org_id).await;
+            Ok(json!({ "orgs": 1 }))
+        }
+        "weekly_demand_forecast" => crate::services::tenant_screening::tool_forecast_demand(
+            state,
+            org_id,
+            &serde_json::Map::new(),
+        )
+        .await
+        .map_err(|e| e.to_string()),
+        other => Err(format!("Unknown job '{other}'.")),
     }
 }
 
 /// Delete expired agent memories.
-async fn run_memory_cleanup(pool: &sqlx::PgPool) {
+async fn run_memory_cleanup(pool: &sqlx::PgPool) -> JobOutcome {
     let result =
         sqlx::query("DELETE FROM agent_memory WHERE expires_at IS NOT NULL AND expires_at < now()")
             .execute(pool)
@@ -408,33 +809,90 @@ async fn run_memory_cleanup(pool: &sqlx::PgPool) {
                     "Scheduler: expired agent memories cleaned up"
                 );
             }
+            Ok(json!({ "deleted": r.rows_affected() }))
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Scheduler: memory cleanup failed");
+            Err(e.to_string())
         }
-        Err(e) => tracing::warn!(error = %e, "Scheduler: memory cleanup failed"),
     }
 }
 
-/// Execute agent playbooks that have cron-based trigger_type and matching schedule.
-async fn run_cron_agent_playbooks(state: &AppState) {
+/// Execute agent playbooks with `trigger_type = 'schedule'` that are due.
+///
+/// A playbook whose `trigger_conditions` carries a `cron` expression is due
+/// when that expression (evaluated in `trigger_conditions.timezone`, falling
+/// back to the organization timezone) has fired since its last run. Playbooks
+/// without one keep the legacy once-a-day behavior after 05:00 UTC.
+async fn run_cron_agent_playbooks(state: &AppState) -> JobOutcome {
     let pool = match state.db_pool.as_ref() {
         Some(p) => p,
-        None => return,
+        None => return Ok(Value::Null),
     };
 
-    // Fetch playbooks with trigger_type='schedule' that haven't run today
-    let rows = sqlx::query_as::<_, (String, String, String, serde_json::Value)>(
-        "SELECT id::text, organization_id::text, name, steps
-         FROM agent_playbooks
-         WHERE trigger_type = 'schedule'
-           AND is_active = true
-           AND (last_run_at IS NULL OR last_run_at::date < CURRENT_DATE)
-         LIMIT 20",
+    let rows = sqlx::query(
+        "SELECT p.id::text AS id,
+                p.organization_id::text AS organization_id,
+                p.name,
+                NULLIF(btrim(p.trigger_conditions->>'cron'), '') AS cron,
+                COALESCE(NULLIF(btrim(p.trigger_conditions->>'timezone'), ''), o.timezone) AS timezone,
+                COALESCE(p.last_run_at, p.created_at) AS last_run_at,
+                p.last_run_at IS NULL AS never_ran
+         FROM agent_playbooks p
+         JOIN organizations o ON o.id = p.organization_id
+         WHERE p.trigger_type = 'schedule'
+           AND p.is_active = true
+         ORDER BY p.last_run_at ASC NULLS FIRST
+         LIMIT 500",
     )
     .fetch_all(pool)
     .await
-    .unwrap_or_default();
+    .map_err(|e| e.to_string())?;
+
+    let now = Utc::now();
+    let mut due = Vec::new();
+    for row in &rows {
+        let playbook_id = row.try_get::<String, _>("id").unwrap_or_default();
+        let cron = row.try_get::<Option<String>, _>("cron").ok().flatten();
+        let last_run_at = row
+            .try_get::<chrono::DateTime<Utc>, _>("last_run_at")
+            .unwrap_or(now);
+        let is_due = match cron {
+            Some(expr) => {
+                let timezone = row
+                    .try_get::<Option<String>, _>("timezone")
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default();
+                match CronSchedule::parse_in_timezone(&expr, &timezone) {
+                    Ok(schedule) => schedule.is_due(&last_run_at, &now),
+                    Err(error) => {
+                        tracing::warn!(playbook_id, error, "Cron playbook has an invalid schedule");
+                        false
+                    }
+                }
+            }
+            None => {
+                let never_ran = row.try_get::<bool, _>("never_ran").unwrap_or(false);
+                now.hour() >= 5 && (never_ran || last_run_at.date_naive() < now.date_naive())
+            }
+        };
+        if is_due {
+            due.push((
+                playbook_id,
+                row.try_get::<String, _>("organization_id")
+                    .unwrap_or_default(),
+                row.try_get::<String, _>("name").unwrap_or_default(),
+            ));
+        }
+        if due.len() >= 20 {
+            break;
+        }
+    }
 
     let mut ran = 0u32;
-    for (playbook_id, org_id, name, _steps) in &rows {
+    let mut failed = 0u32;
+    for (playbook_id, org_id, name) in &due {
         // Execute via supervisor agent
         let params = crate::services::ai_agent::RunAiAgentChatParams {
             org_id,
@@ -458,6 +916,7 @@ async fn run_cron_agent_playbooks(state: &AppState) {
         match crate::services::ai_agent::run_ai_agent_chat(state, params).await {
             Ok(_) => ran += 1,
             Err(e) => {
+                failed += 1;
                 tracing::warn!(playbook = %name, error = %e, "Cron playbook failed");
             }
         }
@@ -472,50 +931,14 @@ async fn run_cron_agent_playbooks(state: &AppState) {
     if ran > 0 {
         tracing::info!(ran, "Scheduler: cron agent playbooks completed");
     }
-}
-
-/// Generate demand forecasts for all active organizations.
-async fn run_weekly_demand_forecast(state: &AppState) {
-    let pool = match state.db_pool.as_ref() {
-        Some(p) => p,
-        None => return,
-    };
-
-    let org_ids: Vec<(String,)> =
-        sqlx::query_as("SELECT id::text FROM organizations WHERE is_active = true LIMIT 100")
-            .fetch_all(pool)
-            .await
-            .unwrap_or_default();
-
-    let mut generated = 0u32;
-    for (org_id,) in &org_ids {
-        let args = serde_json::Map::new();
-        match crate::services::tenant_screening::tool_forecast_demand(state, org_id, &args).await {
-            Ok(result) => {
-                if result
-                    .get("ok")
-                    .and_then(serde_json::Value::as_bool)
-                    .unwrap_or(false)
-                {
-                    generated += 1;
-                }
-            }
-            Err(e) => {
-                tracing::warn!(org_id, error = %e, "Scheduler: demand forecast failed");
-            }
-        }
-    }
-
-    if generated > 0 {
-        tracing::info!(
-            orgs = generated,
-            "Scheduler: weekly demand forecasts completed"
-        );
-    }
+    Ok(json!({ "due": due.len(), "ran": ran, "failed": failed }))
 }
 
 /// Scan for tasks whose SLA has been breached but not yet flagged.
-async fn run_sla_breach_scan(pool: &sqlx::PgPool, engine_mode: crate::config::WorkflowEngineMode) {
+async fn run_sla_breach_scan(
+    pool: &sqlx::PgPool,
+    engine_mode: crate::config::WorkflowEngineMode,
+) -> JobOutcome {
     let rows = sqlx::query_as::<_, (String, String, String)>(
         "SELECT id::text, organization_id::text, title
          FROM tasks
@@ -527,7 +950,7 @@ async fn run_sla_breach_scan(pool: &sqlx::PgPool, engine_mode: crate::config::Wo
     )
     .fetch_all(pool)
     .await
-    .unwrap_or_default();
+    .map_err(|e| e.to_string())?;
 
     let count = rows.len();
     for (task_id, org_id, _title) in rows {
@@ -575,89 +998,83 @@ async fn run_sla_breach_scan(pool: &sqlx::PgPool, engine_mode: crate::config::Wo
     if count > 0 {
         tracing::info!(count, "Scheduler: SLA breach scan completed");
     }
+    Ok(json!({ "breached": count }))
 }
 
-/// Run anomaly scan for all active organizations.
-/// Fires `anomaly_detected` workflow triggers for warning/critical anomalies.
-async fn run_anomaly_scan_all_orgs(state: &AppState) {
+/// Anomaly scan for one organization, firing `anomaly_detected` for each
+/// warning/critical anomaly.
+async fn run_anomaly_scan_for_org(state: &AppState, org_id: &str) -> JobOutcome {
     let pool = match state.db_pool.as_ref() {
         Some(p) => p,
-        None => return,
+        None => return Ok(Value::Null),
     };
-
     let engine_mode = state.config.workflow_engine_mode;
 
-    let org_ids: Vec<(String,)> =
-        sqlx::query_as("SELECT id::text FROM organizations WHERE is_active = true LIMIT 100")
-            .fetch_all(pool)
-            .await
-            .unwrap_or_default();
+    let anomalies = crate::services::anomaly_detection::run_anomaly_scan(state, org_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !anomalies.is_empty() {
+        tracing::info!(
+            org_id,
+            count = anomalies.len(),
+            "Scheduler: anomalies detected"
+        );
+    }
 
-    let mut scanned = 0u32;
+    // Fire workflow trigger for each warning/critical anomaly
     let mut triggers_fired = 0u32;
-    for (org_id,) in &org_ids {
-        match crate::services::anomaly_detection::run_anomaly_scan(state, org_id).await {
-            Ok(anomalies) => {
-                if !anomalies.is_empty() {
-                    tracing::info!(
-                        org_id,
-                        count = anomalies.len(),
-                        "Scheduler: anomalies detected"
-                    );
-                }
-                // Fire workflow trigger for each warning/critical anomaly
-                for anomaly in &anomalies {
-                    let severity = anomaly
-                        .get("severity")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("info");
-                    if severity == "warning" || severity == "critical" {
-                        let mut ctx = serde_json::Map::new();
-                        // Copy all anomaly fields into context
-                        if let Some(obj) = anomaly.as_object() {
-                            for (k, v) in obj {
-                                ctx.insert(k.clone(), v.clone());
-                            }
-                        }
-                        ctx.insert("org_id".to_string(), serde_json::json!(org_id));
-                        crate::services::workflows::fire_trigger(
-                            pool,
-                            org_id,
-                            "anomaly_detected",
-                            &ctx,
-                            engine_mode,
-                        )
-                        .await;
-                        triggers_fired += 1;
-                    }
+    for anomaly in &anomalies {
+        let severity = anomaly
+            .get("severity")
+            .and_then(|v| v.as_str())
+            .unwrap_or("info");
+        if severity == "warning" || severity == "critical" {
+            let mut ctx = serde_json::Map::new();
+            // Copy all anomaly fields into context
+            if let Some(obj) = anomaly.as_object() {
+                for (k, v) in obj {
+                    ctx.insert(k.clone(), v.clone());
                 }
-                scanned += 1;
-            }
-            Err(e) => {
-                tracing::warn!(org_id, error = %e, "Scheduler: anomaly scan failed");
             }
+            ctx.insert("org_id".to_string(), serde_json::json!(org_id));
+            crate::services::workflows::fire_trigger(
+                pool,
+                org_id,
+                "anomaly_detected",
+                &ctx,
+                engine_mode,
+            )
+            .await;
+            triggers_fired += 1;
         }
     }
 
-    if scanned > 0 {
-        tracing::info!(
-            orgs = scanned,
-            triggers = triggers_fired,
-            "Scheduler: anomaly scans completed"
-        );
-    }
+    Ok(json!({ "anomalies": anomalies.len(), "triggers_fired": triggers_fired }))
 }
 
 /// Run scheduled agent playbooks from the agent_schedules table.
-async fn run_scheduled_agent_playbooks(state: &AppState) {
+async fn run_scheduled_agent_playbooks(state: &AppState) -> JobOutcome {
     let pool = match state.db_pool.as_ref() {
         Some(p) => p,
-        None => return,
+        None => return Ok(Value::Null),
     };
 
     // Fetch due schedules
-    let rows = sqlx::query_as::<_, (String, String, String, String, String)>(
-        "SELECT id::text, org_id::text, agent_slug, playbook_name, message
+    let rows = sqlx::query_as::<
+        _,
+        (
+            String,
+            String,
+            String,
+            String,
+            String,
+            Option<String>,
+            String,
+            bool,
+        ),
+    >(
+        "SELECT id::text, org_id::text, agent_slug, playbook_name, message,
+                NULLIF(btrim(cron_expression), ''), timezone, next_run_at IS NULL
          FROM agent_schedules
          WHERE is_active = true
            AND (next_run_at IS NULL OR next_run_at <= now())
@@ -665,10 +1082,55 @@ async fn run_scheduled_agent_playbooks(state: &AppState) {
     )
     .fetch_all(pool)
     .await
-    .unwrap_or_default();
+    .map_err(|e| e.to_string())?;
 
     let mut ran = 0_u32;
-    for (schedule_id, org_id, agent_slug, _playbook_name, message) in &rows {
+    let mut failed = 0_u32;
+    for (
+        schedule_id,
+        org_id,
+        agent_slug,
+        _playbook_name,
+        message,
+        cron_expression,
+        timezone,
+        unscheduled,
+    ) in &rows
+    {
+        let schedule = cron_expression.as_deref().map(|expr| {
+            CronSchedule::parse_in_timezone(expr, timezone).inspect_err(|error| {
+                tracing::warn!(
+                    schedule_id,
+                    error,
+                    "Agent schedule has an invalid cron expression"
+                );
+            })
+        });
+        let next_run_at = match &schedule {
+            Some(Ok(schedule)) => schedule.next_after(&Utc::now()),
+            Some(Err(_)) => None,
+            None => Some(Utc::now() + chrono::Duration::hours(24)),
+        };
+
+        // A cron schedule that was never planned only gets its first fire time.
+        if *unscheduled && matches!(schedule, Some(Ok(_))) {
+            sqlx::query("UPDATE agent_schedules SET next_run_at = $2 WHERE id = $1::uuid")
+                .bind(schedule_id)
+                .bind(next_run_at)
+                .execute(pool)
+                .await
+                .ok();
+            continue;
+        }
+        if matches!(schedule, Some(Err(_))) {
+            sqlx::query("UPDATE agent_schedules SET next_run_at = NULL, is_active = false WHERE id = $1::uuid")
+                .bind(schedule_id)
+                .execute(pool)
+                .await
+                .ok();
+            continue;
+        }
+
         let agent: Option<(String,)> = sqlx::query_as(
             "SELECT name FROM ai_agents WHERE slug = $1 AND is_active = true LIMIT 1",
         )
@@ -709,17 +1171,18 @@ async fn run_scheduled_agent_playbooks(state: &AppState) {
         match crate::services::ai_agent::run_ai_agent_chat(state, params).await {
             Ok(_) => ran += 1,
             Err(e) => {
+                failed += 1;
                 tracing::warn!(agent_slug, error = %e, "Scheduled playbook failed");
             }
         }
 
-        // Update last_run_at and compute next_run_at
+        // Update last_run_at and compute next_run_at from the cron expression
         sqlx::query(
-            "UPDATE agent_schedules SET last_run_at = now(),
-             next_run_at = now() + interval '24 hours'
+            "UPDATE agent_schedules SET last_run_at = now(), next_run_at = $2
              WHERE id = $1::uuid",
         )
         .bind(schedule_id)
+        .bind(next_run_at)
         .execute(pool)
         .await
         .ok();
@@ -728,30 +1191,11 @@ async fn run_scheduled_agent_playbooks(state: &AppState) {
     if ran > 0 {
         tracing::info!(ran, "Scheduler: agent playbooks completed");
     }
-}
-
-/// Capture nightly portfolio snapshots for all active organizations.
-async fn run_portfolio_snapshots(pool: &sqlx::PgPool) {
-    let org_ids: Vec<(String,)> =
-        sqlx::query_as("SELECT id::text FROM organizations WHERE is_active = true LIMIT 100")
-            .fetch_all(pool)
-            .await
-            .unwrap_or_default();
-
-    for (org_id,) in &org_ids {
-        crate::services::portfolio::capture_portfolio_snapshot(pool, org_id).await;
-    }
-
-    if !org_ids.is_empty() {
-        tracing::info!(
-            orgs = org_ids.len(),
-            "Scheduler: portfolio snapshots captured"
-        );
-    }
+    Ok(json!({ "due": rows.len(), "ran": ran, "failed": failed }))
 }
 
 /// Scan for maintenance requests with breached SLAs.
-async fn run_maintenance_sla_scan(pool: &sqlx::PgPool) {
+async fn run_maintenance_sla_scan(pool: &sqlx::PgPool) -> JobOutcome {
     let breached = sqlx::query(
         "UPDATE maintenance_requests
          SET sla_breached = true, updated_at = now()
@@ -765,7 +1209,7 @@ async fn run_maintenance_sla_scan(pool: &sqlx::PgPool) {
     )
     .fetch_all(pool)
     .await
-    .unwrap_or_default();
+    .map_err(|e| e.to_string())?;
 
     if !breached.is_empty() {
         tracing::info!(
@@ -773,13 +1217,14 @@ async fn run_maintenance_sla_scan(pool: &sqlx::PgPool) {
             "Scheduler: maintenance SLA breaches flagged"
         );
     }
+    Ok(json!({ "breached": breached.len() }))
 }
 
 /// Scan for applications stalled >48h and fire workflow trigger.
 async fn run_stalled_application_scan(
     pool: &sqlx::PgPool,
     engine_mode: crate::config::WorkflowEngineMode,
-) {
+) -> JobOutcome {
     let rows = sqlx::query_as::<_, (String, String)>(
         "SELECT id::text, organization_id::text
          FROM application_submissions
@@ -790,7 +1235,7 @@ async fn run_stalled_application_scan(
     )
     .fetch_all(pool)
     .await
-    .unwrap_or_default();
+    .map_err(|e| e.to_string())?;
 
     let count = rows.len();
     for (app_id, org_id) in rows {
@@ -812,6 +1257,7 @@ async fn run_stalled_application_scan(
     if count > 0 {
         tracing::info!(count, "Scheduler: stalled application scan completed");
     }
+    Ok(json!({ "stalled": count }))
 }
 
 /// Check for IoT devices that haven't reported in >10 minutes.
@@ -819,7 +1265,7 @@ async fn run_stalled_application_scan(
 async fn run_iot_device_health_check(
     pool: &sqlx::PgPool,
     engine_mode: crate::config::WorkflowEngineMode,
-) {
+) -> JobOutcome {
     let stale_devices = sqlx::query_as::<_, (String, String, String, String)>(
         "UPDATE iot_devices
          SET status = 'offline', updated_at = now()
@@ -831,7 +1277,7 @@ async fn run_iot_device_health_check(
     )
     .fetch_all(pool)
     .await
-    .unwrap_or_default();
+    .map_err(|e| e.to_string())?;
 
     for (device_id, org_id, device_name, device_type) in &stale_devices {
         let mut ctx = serde_json::Map::new();
@@ -852,11 +1298,12 @@ async fn run_iot_device_health_check(
             "Scheduler: IoT devices marked offline"
         );
     }
+    Ok(json!({ "marked_offline": stale_devices.len() }))
 }
 
 /// Process active agent watchers — scan watched tables for new rows
 /// and invoke the configured agent when new records match the watch filter.
-async fn run_agent_watchers(pool: &sqlx::PgPool, engine_mode: WorkflowEngineMode) {
+async fn run_agent_watchers(pool: &sqlx::PgPool, engine_mode: WorkflowEngineMode) -> JobOutcome {
     // Fetch all active watchers
     let watchers = sqlx::query(
         "SELECT id::text, organization_id::text, watch_table, watch_filter, agent_slug,
@@ -866,10 +1313,10 @@ async fn run_agent_watchers(pool: &sqlx::PgPool, engine_mode: WorkflowEngineMode
     )
     .fetch_all(pool)
     .await
-    .unwrap_or_default();
+    .map_err(|e| e.to_string())?;
 
     if watchers.is_empty() {
-        return;
+        return Ok(json!({ "watchers": 0, "invoked": 0 }));
     }
 
     let mut invoked = 0u32;
@@ -988,11 +1435,15 @@ async fn run_agent_watchers(pool: &sqlx::PgPool, engine_mode: WorkflowEngineMode
     if invoked > 0 {
         tracing::info!(invoked, "Scheduler: agent watchers triggered");
     }
+    Ok(json!({ "watchers": watchers.len(), "invoked": invoked }))
 }
 
 /// Run ML prediction reactor — check for actionable predictions and fire workflow triggers.
 /// Called after weekly ML compute in daily jobs.
-async fn run_ml_prediction_reactor(pool: &sqlx::PgPool, engine_mode: WorkflowEngineMode) {
+async fn run_ml_prediction_reactor(
+    pool: &sqlx::PgPool,
+    engine_mode: WorkflowEngineMode,
+) -> JobOutcome {
     // Look for recent ML predictions that are actionable
     // (features with high-confidence predictions computed in the last 24h)
     let predictions = sqlx::query(
@@ -1005,10 +1456,10 @@ async fn run_ml_prediction_reactor(pool: &sqlx::PgPool, engine_mode: WorkflowEng
     )
     .fetch_all(pool)
     .await
-    .unwrap_or_default();
+    .map_err(|e| e.to_string())?;
 
     if predictions.is_empty() {
-        return;
+        return Ok(json!({ "predictions": 0, "fired": 0 }));
     }
 
     let mut fired = 0u32;
@@ -1067,4 +1518,56 @@ async fn run_ml_prediction_reactor(pool: &sqlx::PgPool, engine_mode: WorkflowEng
     if fired > 0 {
         tracing::info!(fired, "Scheduler: ML prediction reactor triggered");
     }
+    Ok(json!({ "predictions": predictions.len(), "fired": fired }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{create_org, create_user, test_pool, test_state};
+
+    #[tokio::test]
+    async fn manual_runs_are_limited_to_org_scoped_jobs() {
+        let state = test_state(None);
+        let org_id = "11111111-1111-1111-1111-111111111111";
+        let user_id = "22222222-2222-2222-2222-222222222222";
+
+        for job_name in [
+            "workflow_jobs",
+            "sla_breach_scan",
+            "scheduler_run_retention",
+        ] {
+            let error = spawn_manual_run(&state, job_name, org_id, user_id)
+                .await
+                .unwrap_err();
+            assert!(error.contains("cannot be run for a single organization"));
+        }
+
+        // Org-scoped jobs pass the guard and only stop at the missing database.
+        for job_name in ORG_SCOPED_JOBS {
+            let error = spawn_manual_run(&state, job_name, org_id, user_id)
+                .await
+                .unwrap_err();
+            assert_eq!(error, "Database is not configured.");
+        }
+    }
+
+    #[tokio::test]
+    async fn queue_and_ical_jobs_run_for_a_single_org() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let user_id = create_user(&pool).await;
+        let org_id = create_org(&pool, &user_id).await;
+        let state = test_state(Some(pool.clone()));
+
+        let messages = run_job_for_org(&state, "queued_messages", &org_id).await;
+        assert_eq!(messages, Ok(json!({ "sent": 0, "failed": 0 })));
+        let ical = run_job_for_org(&state, "ical_sync", &org_id).await.unwrap();
+        assert_eq!(ical["total_integrations"], 0);
+        assert_eq!(
+            run_job_for_org(&state, "workflow_jobs", &org_id).await,
+            Err("Unknown job 'workflow_jobs'.".to_string())
+        );
+    }
 }
@@ -0,0 +1,399 @@
+use std::{future::Future, sync::OnceLock, time::Duration};
+
+use serde_json::Value;
+use sqlx::{PgPool, Row};
+
+use crate::services::scheduler_runs::{self, JobOutcome, RunTrigger};
+
+/// Identifier of this backend process when competing for scheduler leases.
+///
+/// Uses the container hostname (ECS/Railway set `HOSTNAME`) plus a random
+/// suffix so that a restarted task never inherits its predecessor's leases.
+pub fn instance_id() -> &'static str {
+    static INSTANCE_ID: OnceLock<String> = OnceLock::new();
+    INSTANCE_ID.get_or_init(|| {
+        let host = std::env::var("HOSTNAME")
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| "backend".to_string());
+        let suffix = uuid::Uuid::new_v4().simple().to_string();
+        format!("{host}-{}", &suffix[..8])
+    })
+}
+
+/// Try to claim the lease for `job_name` on behalf of `holder_id`.
+///
+/// A claim succeeds when the lease is free or expired, or when `holder_id`
+/// already holds it and is not still running a previous tick. When `run_key`
+/// is set (e.g. the calendar date for daily jobs) the job is claimed again for
+/// the same key only until a run with that key succeeds, so a failed run or a
+/// holder that died mid-run is retried on a later tick.
+async fn claim_as(
+    pool: &PgPool,
+    holder_id: &str,
+    job_name: &str,
+    ttl: Duration,
+    run_key: Option<&str>,
+) -> bool {
+    let result = sqlx::query(
+        "INSERT INTO scheduler_leases (
+           job_name, holder_id, acquired_at, heartbeat_at, expires_at,
+           last_run_key, last_run_started_at, last_run_status
+         )
+         VALUES ($1, $2, now(), now(), now() + make_interval(secs => $3), $4, now(), 'running')
+         ON CONFLICT (job_name) DO UPDATE
+         SET holder_id = EXCLUDED.holder_id,
+             acquired_at = CASE
+               WHEN scheduler_leases.holder_id = EXCLUDED.holder_id THEN scheduler_leases.acquired_at
+               ELSE now()
+             END,
+             heartbeat_at = now(),
+             expires_at = EXCLUDED.expires_at,
+             last_run_key = EXCLUDED.last_run_key,
+             last_run_started_at = now(),
+             last_run_status = 'running'
+         WHERE (
+             scheduler_leases.expires_at <= now()
+             OR (
+               scheduler_leases.holder_id = EXCLUDED.holder_id
+               AND scheduler_leases.last_run_status IS DISTINCT FROM 'running'
+             )
+           )
+           AND (
+             $4::text IS NULL
+             OR scheduler_leases.last_run_key IS DISTINCT FROM $4::text
+             OR scheduler_leases.last_run_status IS DISTINCT FROM 'succeeded'
+           )
+         RETURNING job_name",
+    )
+    .bind(job_name)
+    .bind(holder_id)
+    .bind(ttl.as_secs_f64())
+    .bind(run_key)
+    .fetch_optional(pool)
+    .await;
+
+    match result {
+        Ok(row) => row.is_some(),
+        Err(error) => {
+            tracing::warn!(job = job_name, error = %error, "Scheduler: lease claim failed");
+            false
+        }
+    }
+}
+
+/// Extend a held lease. Returns `false` if `holder_id` no longer holds it;
+/// a failed update is logged and assumed not to have lost the lease.
+async fn heartbeat(pool: &PgPool, holder_id: &str, job_name: &str, ttl: Duration) -> bool {
+    let result = sqlx::query(
+        "UPDATE scheduler_leases
+         SET heartbeat_at = now(),
+             expires_at = now() + make_interval(secs => $3)
+         WHERE job_name = $1 AND holder_id = $2",
+    )
+    .bind(job_name)
+    .bind(holder_id)
+    .bind(ttl.as_secs_f64())
+    .execute(pool)
+    .await;
+
+    match result {
+        Ok(result) => result.rows_affected() > 0,
+        Err(error) => {
+            tracing::warn!(job = job_name, error = %error, "Scheduler: lease heartbeat failed");
+            true
+        }
+    }
+}
+
+/// Record the outcome of a run. The lease itself stays with this instance
+/// until it expires so the holder keeps priority on the next tick.
+async fn finish(pool: &PgPool, holder_id: &str, job_name: &str, succeeded: bool) {
+    let status = if succeeded { "succeeded" } else { "failed" };
+    let result = sqlx::query(
+        "UPDATE scheduler_leases
+         SET last_run_finished_at = now(),
+             last_run_status = $3,
+             heartbeat_at = now()
+         WHERE job_name = $1 AND holder_id = $2",
+    )
+    .bind(job_name)
+    .bind(holder_id)
+    .bind(status)
+    .execute(pool)
+    .await;
+
+    if let Err(error) = result {
+        tracing::warn!(job = job_name, error = %error, "Scheduler: lease finish failed");
+    }
+}
+
+/// Claim `job_name`, run `job` while heartbeating the lease, then record the
+/// outcome on the lease and in `scheduler_runs`. Returns `false` without
+/// running the job if another instance holds the lease or the job already ran
+/// for `run_key`. If the lease is lost mid-run the job is aborted, since
+/// another instance may already be running it, and the run is recorded as
+/// failed.
+pub async fn run_leased<F>(
+    pool: &PgPool,
+    job_name: &str,
+    ttl: Duration,
+    run_key: Option<&str>,
+    job: F,
+) -> bool
+where
+    F: Future<Output = JobOutcome> + Send + 'static,
+{
+    run_leased_as(pool, instance_id(), job_name, ttl, run_key, job).await
+}
+
+async fn run_leased_as<F>(
+    pool: &PgPool,
+    holder_id: &str,
+    job_name: &str,
+    ttl: Duration,
+    run_key: Option<&str>,
+    job: F,
+) -> bool
+where
+    F: Future<Output = JobOutcome> + Send + 'static,
+{
+    if !claim_as(pool, holder_id, job_name, ttl, run_key).await {
+        return false;
+    }
+
+    let outcome = scheduler_runs::record_run(
+        pool,
+        job_name,
+        None,
+        RunTrigger::Schedule,
+        None,
+        async {
+            let mut handle = tokio::spawn(job);
+            let mut ticker = tokio::time::interval((ttl / 3).max(Duration::from_secs(5)));
+            ticker.tick().await;
+
+            loop {
+                tokio::select! {
+                    joined = &mut handle => {
+                        break joined.unwrap_or_else(|error| {
+                            tracing::error!(job = job_name, error = %error, "Scheduler: job panicked");
+                            Err(format!("Job panicked: {error}"))
+                        });
+                    }
+                    _ = ticker.tick() => {
+                        if !heartbeat(pool, holder_id, job_name, ttl).await {
+                            tracing::warn!(job = job_name, "Scheduler: lost lease while job was running; aborting");
+                            handle.abort();
+                            break Err("Lost the scheduler lease; job aborted.".to_string());
+                        }
+                    }
+                }
+            }
+        },
+    )
+    .await;
+
+    if let Err(error) = &outcome {
+        tracing::warn!(job = job_name, error = %error, "Scheduler: job failed");
+    }
+    finish(pool, holder_id, job_name, outcome.is_ok()).await;
+    true
+}
+
+/// Current lease holders and last-run times for every known job.
+pub async fn list_leases(pool: &PgPool) -> Result<Vec<Value>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT row_to_json(t) AS row
+         FROM (
+           SELECT job_name, holder_id, acquired_at, heartbeat_at, expires_at,
+                  expires_at > now() AS is_held,
+                  last_run_key, last_run_started_at, last_run_finished_at, last_run_status
+           FROM scheduler_leases
+           ORDER BY job_name
+         ) t",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| row.try_get::<Option<Value>, _>("row").ok().flatten())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{test_pool, unique_name};
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    async fn holder(pool: &PgPool, job_name: &str) -> Option<String> {
+        sqlx::query_scalar("SELECT holder_id FROM scheduler_leases WHERE job_name = $1")
+            .bind(job_name)
+            .fetch_optional(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn set_lease(pool: &PgPool, job_name: &str, status: &str, expired: bool) {
+        sqlx::query(
+            "UPDATE scheduler_leases
+             SET last_run_status = $2,
+                 expires_at = CASE WHEN $3 THEN now() - interval '1 second' ELSE expires_at END
+             WHERE job_name = $1",
+        )
+        .bind(job_name)
+        .bind(status)
+        .bind(expired)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn cleanup(pool: &PgPool, job_name: &str) {
+        sqlx::query("DELETE FROM scheduler_leases WHERE job_name = $1")
+            .bind(job_name)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn held_lease_rejects_other_instances() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let job = unique_name("lease-held");
+
+        assert!(claim_as(&pool, "node-a", &job, TTL, None).await);
+        assert!(!claim_as(&pool, "node-b", &job, TTL, None).await);
+        assert_eq!(holder(&pool, &job).await.as_deref(), Some("node-a"));
+
+        // The holder can't overlap its own tick, but keeps the job afterwards.
+        assert!(!claim_as(&pool, "node-a", &job, TTL, None).await);
+        set_lease(&pool, &job, "succeeded", false).await;
+        assert!(claim_as(&pool, "node-a", &job, TTL, None).await);
+        assert!(!claim_as(&pool, "node-b", &job, TTL, None).await);
+
+        cleanup(&pool, &job).await;
+    }
+
+    #[tokio::test]
+    async fn expired_lease_is_taken_over() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let job = unique_name("lease-expired");
+
+        assert!(claim_as(&pool, "node-a", &job, TTL, None).await);
+        set_lease(&pool, &job, "running", true).await;
+        assert!(claim_as(&pool, "node-b", &job, TTL, None).await);
+        assert_eq!(holder(&pool, &job).await.as_deref(), Some("node-b"));
+        assert!(!claim_as(&pool, "node-a", &job, TTL, None).await);
+
+        cleanup(&pool, &job).await;
+    }
+
+    #[tokio::test]
+    async fn run_key_is_claimed_once_unless_the_holder_died() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let job = unique_name("lease-daily");
+
+        assert!(claim_as(&pool, "node-a", &job, TTL, Some("2026-10-17")).await);
+        set_lease(&pool, &job, "succeeded", true).await;
+        assert!(!claim_as(&pool, "node-b", &job, TTL, Some("2026-10-17")).await);
+        assert!(!claim_as(&pool, "node-a", &job, TTL, Some("2026-10-17")).await);
+
+        // A holder that died mid-run lets another instance finish the day.
+        assert!(claim_as(&pool, "node-b", &job, TTL, Some("2026-10-18")).await);
+        set_lease(&pool, &job, "running", true).await;
+        assert!(claim_as(&pool, "node-a", &job, TTL, Some("2026-10-18")).await);
+        assert_eq!(holder(&pool, &job).await.as_deref(), Some("node-a"));
+
+        cleanup(&pool, &job).await;
+    }
+
+    #[tokio::test]
+    async fn failed_run_key_is_retried() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let job = unique_name("lease-retry");
+        let run = |outcome: JobOutcome| {
+            let pool = pool.clone();
+            let job = job.clone();
+            async move {
+                run_leased_as(&pool, "node-a", &job, TTL, Some("2026-10-17"), async {
+                    outcome
+                })
+                .await
+            }
+        };
+
+        assert!(run(Err("FX provider unavailable".to_string())).await);
+        assert!(run(Ok(Value::Null)).await);
+        assert!(!run(Ok(Value::Null)).await);
+
+        // Another instance retries a failed day once the holder's lease lapses.
+        assert!(claim_as(&pool, "node-a", &job, TTL, Some("2026-10-18")).await);
+        set_lease(&pool, &job, "failed", true).await;
+        assert!(claim_as(&pool, "node-b", &job, TTL, Some("2026-10-18")).await);
+
+        cleanup(&pool, &job).await;
+    }
+
+    #[tokio::test]
+    async fn job_is_aborted_when_the_lease_is_lost() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let job = unique_name("lease-lost");
+        // Heartbeats every 5s.
+        let ttl = Duration::from_secs(15);
+        let completed = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let running = {
+            let (pool, job, completed) = (pool.clone(), job.clone(), completed.clone());
+            tokio::spawn(async move {
+                run_leased_as(&pool, "node-a", &job, ttl, None, async move {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    completed.store(true, std::sync::atomic::Ordering::SeqCst);
+                    Ok(Value::Null)
+                })
+                .await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        set_lease(&pool, &job, "running", true).await;
+        assert!(claim_as(&pool, "node-b", &job, ttl, None).await);
+
+        let ran = tokio::time::timeout(Duration::from_secs(30), running)
+            .await
+            .expect("job aborted at the next heartbeat")
+            .unwrap();
+        assert!(ran);
+        assert!(!completed.load(std::sync::atomic::Ordering::SeqCst));
+
+        let (status, error): (String, Option<String>) = sqlx::query_as(
+            "SELECT status, error FROM scheduler_runs
+             WHERE job_name = $1 ORDER BY started_at DESC LIMIT 1",
+        )
+        .bind(&job)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(status, "failed");
+        assert!(error
+            .unwrap_or_default()
+            .contains("Lost the scheduler lease"));
+        // The new holder's lease is left alone.
+        assert_eq!(holder(&pool, &job).await.as_deref(), Some("node-b"));
+
+        cleanup(&pool, &job).await;
+    }
+}
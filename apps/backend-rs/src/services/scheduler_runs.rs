@@ -0,0 +1,312 @@
+use serde_json::Value;
+use sqlx::{PgPool, Row};
+
+use crate::services::scheduler_leases::instance_id;
+
+/// Outcome of a single scheduler job invocation: a JSON summary of the
+/// counts the job produced, or the error text that aborted it.
+pub type JobOutcome = Result<Value, String>;
+
+/// Who started a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunTrigger {
+    Schedule,
+    Manual,
+}
+
+impl RunTrigger {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Schedule => "schedule",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+/// Filters accepted by [`list_runs`].
+#[derive(Debug, Default)]
+pub struct RunFilters<'a> {
+    pub job_name: Option<&'a str>,
+    pub status: Option<&'a str>,
+    pub organization_id: Option<&'a str>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Insert a `running` row for a job invocation and return it.
+pub async fn start_run(
+    pool: &PgPool,
+    job_name: &str,
+    organization_id: Option<&str>,
+    trigger: RunTrigger,
+    requested_by_user_id: Option<&str>,
+) -> Result<Value, sqlx::Error> {
+    let row = sqlx::query(
+        "INSERT INTO scheduler_runs (
+           job_name, organization_id, trigger_source, requested_by_user_id, instance_id
+         )
+         VALUES ($1, $2::uuid, $3, $4::uuid, $5)
+         RETURNING row_to_json(scheduler_runs) AS row",
+    )
+    .bind(job_name)
+    .bind(organization_id)
+    .bind(trigger.as_str())
+    .bind(requested_by_user_id)
+    .bind(instance_id())
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row
+        .try_get::<Option<Value>, _>("row")
+        .ok()
+        .flatten()
+        .unwrap_or(Value::Null))
+}
+
+/// Close a run with its outcome. Failures to record history are logged and
+/// never propagated, so bookkeeping can't break the job itself.
+pub async fn finish_run(pool: &PgPool, run_id: &str, outcome: &JobOutcome) {
+    let (status, result, error) = match outcome {
+        Ok(summary) => ("succeeded", summary.clone(), None),
+        Err(message) => (
+            "failed",
+            Value::Object(serde_json::Map::new()),
+            Some(message),
+        ),
+    };
+    let result = if result.is_null() {
+        Value::Object(serde_json::Map::new())
+    } else {
+        result
+    };
+
+    let updated = sqlx::query(
+        "UPDATE scheduler_runs
+         SET status = $2,
+             result = $3,
+             error = $4,
+             finished_at = now(),
+             duration_ms = (EXTRACT(EPOCH FROM (now() - started_at)) * 1000)::integer
+         WHERE id = $1::uuid",
+    )
+    .bind(run_id)
+    .bind(status)
+    .bind(result)
+    .bind(error)
+    .execute(pool)
+    .await;
+
+    if let Err(error) = updated {
+        tracing::warn!(run_id, error = %error, "Scheduler: failed to record run outcome");
+    }
+}
+
+/// Record a run around `job`: insert the row, await the job, store the outcome.
+pub async fn record_run<F>(
+    pool: &PgPool,
+    job_name: &str,
+    organization_id: Option<&str>,
+    trigger: RunTrigger,
+    requested_by_user_id: Option<&str>,
+    job: F,
+) -> JobOutcome
+where
+    F: std::future::Future<Output = JobOutcome>,
+{
+    let run_id = match start_run(
+        pool,
+        job_name,
+        organization_id,
+        trigger,
+        requested_by_user_id,
+    )
+    .await
+    {
+        Ok(run) => run.get("id").and_then(Value::as_str).map(ToOwned::to_owned),
+        Err(error) => {
+            tracing::warn!(job = job_name, error = %error, "Scheduler: failed to record run start");
+            None
+        }
+    };
+
+    let outcome = job.await;
+    if let Some(run_id) = run_id.as_deref() {
+        finish_run(pool, run_id, &outcome).await;
+    }
+    outcome
+}
+
+pub async fn list_runs(pool: &PgPool, filters: &RunFilters<'_>) -> Result<Vec<Value>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT row_to_json(t) AS row
+         FROM (
+           SELECT *
+           FROM scheduler_runs
+           WHERE ($1::text IS NULL OR job_name = $1)
+             AND ($2::text IS NULL OR status = $2)
+             AND ($3::uuid IS NULL OR organization_id = $3::uuid)
+           ORDER BY started_at DESC, id DESC
+           LIMIT $4 OFFSET $5
+         ) t",
+    )
+    .bind(filters.job_name)
+    .bind(filters.status)
+    .bind(filters.organization_id)
+    .bind(filters.limit)
+    .bind(filters.offset.max(0))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| row.try_get::<Option<Value>, _>("row").ok().flatten())
+        .collect())
+}
+
+pub async fn get_run(pool: &PgPool, run_id: &str) -> Result<Option<Value>, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT row_to_json(scheduler_runs) AS row
+         FROM scheduler_runs
+         WHERE id = $1::uuid",
+    )
+    .bind(run_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.and_then(|row| row.try_get::<Option<Value>, _>("row").ok().flatten()))
+}
+
+/// Delete runs that started more than `retention_days` ago. Returns the
+/// number of rows removed.
+pub async fn prune_runs(pool: &PgPool, retention_days: i64) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "DELETE FROM scheduler_runs
+         WHERE started_at < now() - make_interval(days => $1::integer)",
+    )
+    .bind(retention_days.max(1))
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_support::{create_org, create_user, test_pool, unique_name};
+
+    fn filters(job_name: &str) -> RunFilters<'_> {
+        RunFilters {
+            job_name: Some(job_name),
+            limit: 50,
+            ..RunFilters::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn record_run_stores_outcome_and_scope() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let user_id = create_user(&pool).await;
+        let org_id = create_org(&pool, &user_id).await;
+        let job = unique_name("job-record");
+
+        let ok = record_run(
+            &pool,
+            &job,
+            Some(&org_id),
+            RunTrigger::Manual,
+            Some(&user_id),
+            async { Ok(json!({ "sent": 3 })) },
+        )
+        .await;
+        assert_eq!(ok, Ok(json!({ "sent": 3 })));
+        let failed = record_run(&pool, &job, None, RunTrigger::Schedule, None, async {
+            Err("provider timed out".to_string())
+        })
+        .await;
+        assert!(failed.is_err());
+
+        let runs = list_runs(&pool, &filters(&job)).await.unwrap();
+        assert_eq!(runs.len(), 2);
+        let failed_run = &runs[0];
+        assert_eq!(failed_run["status"], "failed");
+        assert_eq!(failed_run["error"], "provider timed out");
+        assert_eq!(failed_run["trigger_source"], "schedule");
+        assert!(failed_run["organization_id"].is_null());
+        let ok_run = &runs[1];
+        assert_eq!(ok_run["status"], "succeeded");
+        assert_eq!(ok_run["result"], json!({ "sent": 3 }));
+        assert_eq!(ok_run["organization_id"], json!(org_id));
+        assert_eq!(ok_run["requested_by_user_id"], json!(user_id));
+        assert_eq!(ok_run["trigger_source"], "manual");
+        assert!(ok_run["finished_at"].is_string());
+
+        let scoped = RunFilters {
+            organization_id: Some(&org_id),
+            ..filters(&job)
+        };
+        assert_eq!(list_runs(&pool, &scoped).await.unwrap().len(), 1);
+        let failures = RunFilters {
+            status: Some("failed"),
+            ..filters(&job)
+        };
+        assert_eq!(list_runs(&pool, &failures).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn list_runs_pages_newest_first() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let job = unique_name("job-paging");
+        for minutes_ago in [30, 20, 10] {
+            sqlx::query(
+                "INSERT INTO scheduler_runs (job_name, status, started_at)
+                 VALUES ($1, 'succeeded', now() - make_interval(mins => $2))",
+            )
+            .bind(&job)
+            .bind(minutes_ago)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let page = |offset| RunFilters {
+            limit: 2,
+            offset,
+            ..filters(&job)
+        };
+        let first = list_runs(&pool, &page(0)).await.unwrap();
+        let second = list_runs(&pool, &page(2)).await.unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 1);
+        assert!(first[0]["started_at"].as_str() > first[1]["started_at"].as_str());
+        assert!(first[1]["started_at"].as_str() > second[0]["started_at"].as_str());
+    }
+
+    #[tokio::test]
+    async fn prune_runs_removes_runs_past_retention() {
+        let Some(pool) = test_pool().await else {
+            return;
+        };
+        let job = unique_name("job-retention");
+        for days_ago in [45, 1] {
+            sqlx::query(
+                "INSERT INTO scheduler_runs (job_name, status, started_at)
+                 VALUES ($1, 'succeeded', now() - make_interval(days => $2))",
+            )
+            .bind(&job)
+            .bind(days_ago)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        assert!(prune_runs(&pool, 30).await.unwrap() >= 1);
+        let remaining = list_runs(&pool, &filters(&job)).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+    }
+}
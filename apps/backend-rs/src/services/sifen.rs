@@ -0,0 +1,1334 @@
+//! SIFEN (e-Kuatia) electronic documents: CDC generation, the DE XML of a
+//! factura electrónica (Manual Técnico v150 layout), its enveloped XMLDSig
+//! signature, the KuDE QR link, and submission to SET's synchronous
+//! reception service.
+//!
+//! Documents are serialized already in exclusive canonical form (no
+//! whitespace, attributes in order, explicit end tags, canonical escaping)
+//! so the digest computed over the serialized `DE` element is the one a
+//! verifier gets after canonicalizing it.
+
+use std::{future::Future, time::Duration};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use openssl::{
+    asn1::{Asn1Time, Asn1TimeRef},
+    hash::MessageDigest,
+    pkcs12::Pkcs12,
+    pkey::{PKey, Private},
+    sign::Signer,
+    symm::{decrypt_aead, encrypt_aead, Cipher},
+    x509::{X509NameRef, X509},
+};
+use reqwest::Client;
+use sha2::{Digest, Sha256};
+
+use crate::services::xml;
+
+pub const FORMAT_VERSION: &str = "150";
+const SIFEN_NS: &str = "http://ekuatia.set.gov.py/sifen/xsd";
+const XSI_NS: &str = "http://www.w3.org/2001/XMLSchema-instance";
+const DSIG_NS: &str = "http://www.w3.org/2000/09/xmldsig#";
+const EXC_C14N: &str = "http://www.w3.org/2001/10/xml-exc-c14n#";
+const SOAP_NS: &str = "http://www.w3.org/2003/05/soap-envelope";
+
+/// iTiDE for a factura electrónica, the only document type issued today.
+const DOCUMENT_TYPE_INVOICE: u8 = 1;
+
+/// Generic receiver for sales to an unidentified final consumer.
+const INNOMINADO_NAME: &str = "Sin Nombre";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Environment {
+    /// Documents are validated locally and never leave the server.
+    Mock,
+    Test,
+    Production,
+}
+
+impl Environment {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "mock" => Some(Self::Mock),
+            "test" => Some(Self::Test),
+            "production" => Some(Self::Production),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Mock => "mock",
+            Self::Test => "test",
+            Self::Production => "production",
+        }
+    }
+
+    fn qr_base_url(self) -> &'static str {
+        match self {
+            Self::Production => "https://ekuatia.set.gov.py/consultas/qr?",
+            Self::Mock | Self::Test => "https://ekuatia.set.gov.py/consultas-test/qr?",
+        }
+    }
+
+    fn reception_url(self) -> &'static str {
+        match self {
+            Self::Production => "https://sifen.set.gov.py/de/ws/sync/recibe.wsdl",
+            Self::Mock | Self::Test => "https://sifen-test.set.gov.py/de/ws/sync/recibe.wsdl",
+        }
+    }
+}
+
+// ---------- Identifiers ----------
+
+/// SET's modulo-11 check digit, used for RUCs and for the last digit of
+/// the CDC.
+pub fn check_digit(number: &str) -> u8 {
+    let mut total: u32 = 0;
+    let mut weight: u32 = 2;
+    for ch in number.chars().rev() {
+        let value = ch.to_digit(10).unwrap_or(u32::from(ch as u8));
+        total += value * weight;
+        weight = if weight == 11 { 2 } else { weight + 1 };
+    }
+    match total % 11 {
+        0 | 1 => 0,
+        rest => (11 - rest) as u8,
+    }
+}
+
+/// Split `80012345-6` into its number and check digit, validating the
+/// digit.
+pub fn split_ruc(value: &str) -> Result<(String, u8), String> {
+    let value = value.trim();
+    let (number, digit) = value
+        .split_once('-')
+        .ok_or_else(|| format!("RUC '{value}' must be written as number-check digit."))?;
+    let number = number.trim();
+    if number.is_empty() || number.len() > 8 || !number.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("RUC '{value}' is not a valid RUC number."));
+    }
+    let digit: u8 = digit
+        .trim()
+        .parse()
+        .map_err(|_| format!("RUC '{value}' has no check digit."))?;
+    if check_digit(number) != digit {
+        return Err(format!("RUC '{value}' has an invalid check digit."));
+    }
+    Ok((number.to_string(), digit))
+}
+
+/// Nine random digits for dCodSeg.
+pub fn security_code() -> String {
+    let value = uuid::Uuid::new_v4().as_u128() % 1_000_000_000;
+    format!("{:09}", value.max(1))
+}
+
+// ---------- Document model ----------
+
+/// The issuing organization as it appears in gTimb and gEmis.
+#[derive(Debug, Clone, Default)]
+pub struct Emitter {
+    pub ruc: String,
+    pub check_digit: u8,
+    pub name: String,
+    /// 1 = persona física, 2 = persona jurídica.
+    pub taxpayer_type: u8,
+    pub timbrado: String,
+    pub timbrado_valid_from: NaiveDate,
+    pub establishment: String,
+    pub expedition_point: String,
+    pub address: String,
+    pub house_number: String,
+    pub department_code: i32,
+    pub department_name: String,
+    pub city_code: i32,
+    pub city_name: String,
+    pub phone: String,
+    pub email: String,
+    pub activity_code: String,
+    pub activity_description: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Receiver {
+    /// Contribuyente identified by RUC (B2B).
+    Taxpayer {
+        ruc: String,
+        check_digit: u8,
+        name: String,
+    },
+    /// Non-taxpayer identified by cédula (B2C).
+    Individual {
+        document_number: String,
+        name: String,
+    },
+    /// Final consumer without identification (innominado).
+    Unidentified,
+}
+
+impl Receiver {
+    /// Receiver from a stored tax id: `number-digit` is a RUC, bare digits a
+    /// cédula, and nothing at all the innominado receiver.
+    pub fn from_tax_id(tax_id: &str, name: &str) -> Result<Self, String> {
+        let tax_id = tax_id.trim();
+        let name = name.trim();
+        if tax_id.is_empty() {
+            return Ok(Self::Unidentified);
+        }
+        let name = if name.is_empty() {
+            INNOMINADO_NAME.to_string()
+        } else {
+            name.to_string()
+        };
+        if tax_id.contains('-') {
+            let (ruc, check_digit) = split_ruc(tax_id)?;
+            return Ok(Self::Taxpayer {
+                ruc,
+                check_digit,
+                name,
+            });
+        }
+        if tax_id.chars().all(|c| c.is_ascii_digit()) {
+            return Ok(Self::Individual {
+                document_number: tax_id.to_string(),
+                name,
+            });
+        }
+        Err(format!("'{tax_id}' is neither a RUC nor a cédula number."))
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Taxpayer { name, .. } | Self::Individual { name, .. } => name,
+            Self::Unidentified => INNOMINADO_NAME,
+        }
+    }
+
+    pub fn tax_id(&self) -> Option<String> {
+        match self {
+            Self::Taxpayer {
+                ruc, check_digit, ..
+            } => Some(format!("{ruc}-{check_digit}")),
+            Self::Individual {
+                document_number, ..
+            } => Some(document_number.clone()),
+            Self::Unidentified => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentTerms {
+    /// Contado, with the SIFEN iTiPago code and its description.
+    Cash { method: u8, label: &'static str },
+    /// Crédito a plazo.
+    Credit { days: u32 },
+}
+
+impl PaymentTerms {
+    /// Contado terms for a local `payment_method`.
+    pub fn cash(payment_method: &str) -> Self {
+        let (method, label) = match payment_method {
+            "cash" => (1, "Efectivo"),
+            "bank_transfer" => (5, "Transferencia"),
+            "card" => (99, "Tarjeta"),
+            "qr" => (99, "Pago QR"),
+            _ => (99, "Otro"),
+        };
+        Self::Cash { method, label }
+    }
+}
+
+/// One line of the invoice; `amount` includes its IVA.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvoiceItem {
+    pub code: String,
+    pub description: String,
+    pub amount: f64,
+    /// 10, 5 or 0 (exenta).
+    pub iva_rate: u8,
+}
+
+#[derive(Debug, Clone)]
+pub struct Invoice {
+    pub emitter: Emitter,
+    pub receiver: Receiver,
+    pub document_number: u32,
+    pub issued_at: NaiveDateTime,
+    pub security_code: String,
+    pub currency: String,
+    /// Guaraníes per unit of `currency`; required unless it is PYG.
+    pub exchange_rate: Option<f64>,
+    pub payment: PaymentTerms,
+    pub items: Vec<InvoiceItem>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Totals {
+    pub exempt: f64,
+    pub subtotal_5: f64,
+    pub subtotal_10: f64,
+    pub base_5: f64,
+    pub base_10: f64,
+    pub iva_5: f64,
+    pub iva_10: f64,
+    pub total: f64,
+}
+
+impl Totals {
+    pub fn total_iva(&self) -> f64 {
+        self.iva_5 + self.iva_10
+    }
+}
+
+/// Taxable base and IVA of an IVA-inclusive amount.
+fn item_iva(amount: f64, rate: u8, currency: &str) -> (f64, f64) {
+    if rate == 0 {
+        return (0.0, 0.0);
+    }
+    let base = round_amount(amount * 100.0 / (100.0 + f64::from(rate)), currency);
+    (base, round_amount(amount - base, currency))
+}
+
+impl Invoice {
+    /// Código de Control: the 44-digit id of the document.
+    pub fn cdc(&self) -> String {
+        let body = format!(
+            "{:02}{:0>8}{}{}{}{:07}{}{}{}{}",
+            DOCUMENT_TYPE_INVOICE,
+            self.emitter.ruc,
+            self.emitter.check_digit,
+            self.emitter.establishment,
+            self.emitter.expedition_point,
+            self.document_number,
+            self.emitter.taxpayer_type,
+            self.issued_at.format("%Y%m%d"),
+            1,
+            self.security_code,
+        );
+        let digit = check_digit(&body);
+        format!("{body}{digit}")
+    }
+
+    pub fn totals(&self) -> Totals {
+        let mut totals = Totals::default();
+        for item in &self.items {
+            let amount = round_amount(item.amount, &self.currency);
+            let (base, iva) = item_iva(amount, item.iva_rate, &self.currency);
+            match item.iva_rate {
+                10 => {
+                    totals.subtotal_10 += amount;
+                    totals.base_10 += base;
+                    totals.iva_10 += iva;
+                }
+                5 => {
+                    totals.subtotal_5 += amount;
+                    totals.base_5 += base;
+                    totals.iva_5 += iva;
+                }
+                _ => totals.exempt += amount,
+            }
+            totals.total += amount;
+        }
+        totals
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.items.is_empty() {
+            return Err("The invoice has no items.".to_string());
+        }
+        if self.items.iter().any(|item| item.amount <= 0.0) {
+            return Err("Invoice items must have a positive amount.".to_string());
+        }
+        if self.currency != "PYG" && self.exchange_rate.is_none_or(|rate| rate <= 0.0) {
+            return Err(format!(
+                "An exchange rate to PYG is required to invoice in {}.",
+                self.currency
+            ));
+        }
+        if !(1..=9_999_999).contains(&self.document_number) {
+            return Err("Document number is out of range.".to_string());
+        }
+        if self.security_code.len() != 9 || !self.security_code.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err("Security code must be nine digits.".to_string());
+        }
+        Ok(())
+    }
+
+    /// The `DE` element, without the namespace declaration it inherits from
+    /// `rDE`.
+    pub fn to_xml(&self) -> String {
+        self.render_de(false)
+    }
+
+    fn render_de(&self, canonical: bool) -> String {
+        let emitter = &self.emitter;
+        let currency = self.currency.as_str();
+        let totals = self.totals();
+        let issued_at = self.issued_at.format("%Y-%m-%dT%H:%M:%S").to_string();
+        let cdc = self.cdc();
+
+        let mut w = XmlWriter::default();
+        if canonical {
+            w.raw(&format!("<DE xmlns=\"{SIFEN_NS}\" Id=\"{cdc}\">"));
+        } else {
+            w.raw(&format!("<DE Id=\"{cdc}\">"));
+        }
+        w.leaf("dDVId", &cdc[43..]);
+        w.leaf("dFecFirma", &issued_at);
+        w.leaf("dSisFact", "1");
+
+        w.open("gOpeDE");
+        w.leaf("iTipEmi", "1");
+        w.leaf("dDesTipEmi", "Normal");
+        w.leaf("dCodSeg", &self.security_code);
+        w.close("gOpeDE");
+
+        w.open("gTimb");
+        w.leaf("iTiDE", &DOCUMENT_TYPE_INVOICE.to_string());
+        w.leaf("dDesTiDE", "Factura electrónica");
+        w.leaf("dNumTim", &emitter.timbrado);
+        w.leaf("dEst", &emitter.establishment);
+        w.leaf("dPunExp", &emitter.expedition_point);
+        w.leaf("dNumDoc", &format!("{:07}", self.document_number));
+        w.leaf(
+            "dFeIniT",
+            &emitter.timbrado_valid_from.format("%Y-%m-%d").to_string(),
+        );
+        w.close("gTimb");
+
+        w.open("gDatGralOpe");
+        w.leaf("dFeEmiDE", &issued_at);
+        w.open("gOpeCom");
+        w.leaf("iTipTra", "2");
+        w.leaf("dDesTipTra", "Prestación de servicios");
+        w.leaf("iTImp", "1");
+        w.leaf("dDesTImp", "IVA");
+        w.leaf("cMoneOpe", currency);
+        w.leaf("dDesMoneOpe", currency_name(currency));
+        if let (true, Some(rate)) = (currency != "PYG", self.exchange_rate) {
+            w.leaf("dCondTiCam", "1");
+            w.leaf("dTiCam", &format!("{rate:.2}"));
+        }
+        w.close("gOpeCom");
+
+        w.open("gEmis");
+        w.leaf("dRucEm", &emitter.ruc);
+        w.leaf("dDVEmi", &emitter.check_digit.to_string());
+        w.leaf("iTipCont", &emitter.taxpayer_type.to_string());
+        w.leaf("dNomEmi", &emitter.name);
+        w.leaf("dDirEmi", &emitter.address);
+        w.leaf("dNumCas", &emitter.house_number);
+        w.leaf("cDepEmi", &emitter.department_code.to_string());
+        w.leaf("dDesDepEmi", &emitter.department_name);
+        w.leaf("cCiuEmi", &emitter.city_code.to_string());
+        w.leaf("dDesCiuEmi", &emitter.city_name);
+        w.leaf("dTelEmi", &emitter.phone);
+        w.leaf("dEmailE", &emitter.email);
+        w.open("gActEco");
+        w.leaf("cActEco", &emitter.activity_code);
+        w.leaf("dDesActEco", &emitter.activity_description);
+        w.close("gActEco");
+        w.close("gEmis");
+
+        w.open("gDatRec");
+        match &self.receiver {
+            Receiver::Taxpayer {
+                ruc,
+                check_digit,
+                name,
+            } => {
+                w.leaf("iNatRec", "1");
+                w.leaf("iTiOpe", "1");
+                w.leaf("cPaisRec", "PRY");
+                w.leaf("dDesPaisRe", "Paraguay");
+                // RUCs from 80.000.000 up are assigned to companies.
+                let contributor = if ruc.len() == 8 && ruc.starts_with("80") {
+                    "2"
+                } else {
+                    "1"
+                };
+                w.leaf("iTiContRec", contributor);
+                w.leaf("dRucRec", ruc);
+                w.leaf("dDVRec", &check_digit.to_string());
+                w.leaf("dNomRec", name);
+            }
+            Receiver::Individual {
+                document_number,
+                name,
+            } => {
+                w.leaf("iNatRec", "2");
+                w.leaf("iTiOpe", "2");
+                w.leaf("cPaisRec", "PRY");
+                w.leaf("dDesPaisRe", "Paraguay");
+                w.leaf("iTipIDRec", "1");
+                w.leaf("dDTipIDRec", "Cédula paraguaya");
+                w.leaf("dNumIDRec", document_number);
+                w.leaf("dNomRec", name);
+            }
+            Receiver::Unidentified => {
+                w.leaf("iNatRec", "2");
+                w.leaf("iTiOpe", "2");
+                w.leaf("cPaisRec", "PRY");
+                w.leaf("dDesPaisRe", "Paraguay");
+                w.leaf("iTipIDRec", "5");
+                w.leaf("dDTipIDRec", "Innominado");
+                w.leaf("dNumIDRec", "0");
+                w.leaf("dNomRec", INNOMINADO_NAME);
+            }
+        }
+        w.close("gDatRec");
+        w.close("gDatGralOpe");
+
+        w.open("gDtipDE");
+        w.open("gCamFE");
+        w.leaf("iIndPres", "1");
+        w.leaf("dDesIndPres", "Operación presencial");
+        w.close("gCamFE");
+        w.open("gCamCond");
+        match self.payment {
+            PaymentTerms::Cash { method, label } => {
+                w.leaf("iCondOpe", "1");
+                w.leaf("dDCondOpe", "Contado");
+                w.open("gPaConEIni");
+                w.leaf("iTiPago", &method.to_string());
+                w.leaf("dDesTiPag", label);
+                w.leaf("dMonTiPag", &format_amount(totals.total, currency));
+                w.leaf("cMoneTiPag", currency);
+                w.leaf("dDMoneTiPag", currency_name(currency));
+                if let (true, Some(rate)) = (currency != "PYG", self.exchange_rate) {
+                    w.leaf("dTiCamTiPag", &format!("{rate:.2}"));
+                }
+                w.close("gPaConEIni");
+            }
+            PaymentTerms::Credit { days } => {
+                w.leaf("iCondOpe", "2");
+                w.leaf("dDCondOpe", "Crédito");
+                w.open("gPagCred");
+                w.leaf("iCondCred", "1");
+                w.leaf("dDCondCred", "Plazo");
+                w.leaf("dPlazoCre", &format!("{days} días"));
+                w.close("gPagCred");
+            }
+        }
+        w.close("gCamCond");
+        for item in &self.items {
+            let amount = round_amount(item.amount, currency);
+            let (base, iva) = item_iva(amount, item.iva_rate, currency);
+            let amount = format_amount(amount, currency);
+            w.open("gCamItem");
+            w.leaf("dCodInt", &item.code);
+            w.leaf("dDesProSer", &item.description);
+            w.leaf("cUniMed", "77");
+            w.leaf("dDesUniMed", "UNI");
+            w.leaf("dCantProSer", "1");
+            w.open("gValorItem");
+            w.leaf("dPUniProSer", &amount);
+            w.leaf("dTotBruOpeItem", &amount);
+            w.open("gValorRestaItem");
+            w.leaf("dDescItem", "0");
+            w.leaf("dTotOpeItem", &amount);
+            w.close("gValorRestaItem");
+            w.close("gValorItem");
+            w.open("gCamIVA");
+            if item.iva_rate == 0 {
+                w.leaf("iAfecIVA", "3");
+                w.leaf("dDesAfecIVA", "Exento");
+                w.leaf("dPropIVA", "0");
+                w.leaf("dTasaIVA", "0");
+            } else {
+                w.leaf("iAfecIVA", "1");
+                w.leaf("dDesAfecIVA", "Gravado IVA");
+                w.leaf("dPropIVA", "100");
+                w.leaf("dTasaIVA", &item.iva_rate.to_string());
+            }
+            w.leaf("dBasGravIVA", &format_amount(base, currency));
+            w.leaf("dLiqIVAItem", &format_amount(iva, currency));
+            w.close("gCamIVA");
+            w.close("gCamItem");
+        }
+        w.close("gDtipDE");
+
+        w.open("gTotSub");
+        w.leaf("dSubExe", &format_amount(totals.exempt, currency));
+        w.leaf("dSubExo", "0");
+        w.leaf("dSub5", &format_amount(totals.subtotal_5, currency));
+        w.leaf("dSub10", &format_amount(totals.subtotal_10, currency));
+        w.leaf("dTotOpe", &format_amount(totals.total, currency));
+        w.leaf("dTotDesc", "0");
+        w.leaf("dTotDescGlotem", "0");
+        w.leaf("dTotAntItem", "0");
+        w.leaf("dTotAnt", "0");
+        w.leaf("dPorcDescTotal", "0");
+        w.leaf("dDescTotal", "0");
+        w.leaf("dAnticipo", "0");
+        w.leaf("dRedon", "0");
+        w.leaf("dTotGralOpe", &format_amount(totals.total, currency));
+        w.leaf("dIVA5", &format_amount(totals.iva_5, currency));
+        w.leaf("dIVA10", &format_amount(totals.iva_10, currency));
+        w.leaf("dTotIVA", &format_amount(totals.total_iva(), currency));
+        w.leaf("dBaseGrav5", &format_amount(totals.base_5, currency));
+        w.leaf("dBaseGrav10", &format_amount(totals.base_10, currency));
+        w.leaf(
+            "dTBasGraIVA",
+            &format_amount(totals.base_5 + totals.base_10, currency),
+        );
+        if let (true, Some(rate)) = (currency != "PYG", self.exchange_rate) {
+            w.leaf("dTotalGs", &format_amount(totals.total * rate, "PYG"));
+        }
+        w.close("gTotSub");
+        w.close("DE");
+        w.finish()
+    }
+}
+
+#[derive(Default)]
+struct XmlWriter {
+    out: String,
+}
+
+impl XmlWriter {
+    fn raw(&mut self, value: &str) {
+        self.out.push_str(value);
+    }
+
+    fn open(&mut self, name: &str) {
+        self.out.push('<');
+        self.out.push_str(name);
+        self.out.push('>');
+    }
+
+    fn close(&mut self, name: &str) {
+        self.out.push_str("</");
+        self.out.push_str(name);
+        self.out.push('>');
+    }
+
+    fn leaf(&mut self, name: &str, value: &str) {
+        self.open(name);
+        self.out.push_str(&xml::escape_canonical_text(value));
+        self.close(name);
+    }
+
+    fn finish(self) -> String {
+        self.out
+    }
+}
+
+fn currency_name(currency: &str) -> &str {
+    match currency {
+        "PYG" => "Guarani",
+        "USD" => "US Dollar",
+        "BRL" => "Brazilian Real",
+        "ARS" => "Argentine Peso",
+        "EUR" => "Euro",
+        other => other,
+    }
+}
+
+/// Guaraníes have no decimals; other currencies use two.
+fn round_amount(value: f64, currency: &str) -> f64 {
+    if currency == "PYG" {
+        value.round()
+    } else {
+        (value * 100.0).round() / 100.0
+    }
+}
+
+fn format_amount(value: f64, currency: &str) -> String {
+    if currency == "PYG" {
+        format!("{:.0}", value.round())
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+// ---------- Signing ----------
+
+/// Certificate details shown to users; the key itself never is.
+#[derive(Debug, Clone, Default)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub serial_number: String,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_until: Option<DateTime<Utc>>,
+}
+
+/// An organization's signing certificate and its RSA private key.
+pub struct SigningKey {
+    key: PKey<Private>,
+    certificate: X509,
+}
+
+impl SigningKey {
+    pub fn from_pem(certificate_pem: &str, private_key_pem: &str) -> Result<Self, String> {
+        let certificate = X509::from_pem(certificate_pem.as_bytes())
+            .map_err(|_| "The certificate is not valid PEM.".to_string())?;
+        let key = PKey::private_key_from_pem(private_key_pem.as_bytes())
+            .map_err(|_| "The private key is not valid PEM.".to_string())?;
+        Self::new(certificate, key)
+    }
+
+    /// Certificate and key from a PKCS#12 (.p12/.pfx) bundle, as issued by
+    /// the Paraguayan certification authorities.
+    pub fn from_pkcs12(der: &[u8], password: &str) -> Result<Self, String> {
+        let parsed = Pkcs12::from_der(der)
+            .and_then(|bundle| bundle.parse2(password))
+            .map_err(|_| "Could not open the PKCS#12 file; check the password.".to_string())?;
+        match (parsed.cert, parsed.pkey) {
+            (Some(certificate), Some(key)) => Self::new(certificate, key),
+            _ => Err("The PKCS#12 file has no certificate and key pair.".to_string()),
+        }
+    }
+
+    fn new(certificate: X509, key: PKey<Private>) -> Result<Self, String> {
+        if key.rsa().is_err() {
+            return Err("SIFEN signatures require an RSA key.".to_string());
+        }
+        let matches = certificate
+            .public_key()
+            .map(|public| public.public_eq(&key))
+            .unwrap_or(false);
+        if !matches {
+            return Err("The private key does not belong to the certificate.".to_string());
+        }
+        Ok(Self { key, certificate })
+    }
+
+    pub fn info(&self) -> CertificateInfo {
+        CertificateInfo {
+            subject: name_to_string(self.certificate.subject_name()),
+            issuer: name_to_string(self.certificate.issuer_name()),
+            serial_number: self
+                .certificate
+                .serial_number()
+                .to_bn()
+                .and_then(|bn| bn.to_hex_str().map(|hex| hex.to_string()))
+                .unwrap_or_default(),
+            valid_from: asn1_to_utc(self.certificate.not_before()),
+            valid_until: asn1_to_utc(self.certificate.not_after()),
+        }
+    }
+
+    pub fn certificate_pem(&self) -> Result<String, String> {
+        self.certificate
+            .to_pem()
+            .map(|pem| String::from_utf8_lossy(&pem).into_owned())
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn private_key_pem(&self) -> Result<String, String> {
+        self.key
+            .private_key_to_pem_pkcs8()
+            .map(|pem| String::from_utf8_lossy(&pem).into_owned())
+            .map_err(|e| e.to_string())
+    }
+
+    /// The private key encrypted for storage: AES-256-GCM under the app-held
+    /// `encryption_key` (32 bytes, base64), with the organization id as
+    /// associated data so a sealed key copied to another org's row won't open.
+    pub fn sealed_private_key(
+        &self,
+        encryption_key: &str,
+        organization_id: &str,
+    ) -> Result<String, String> {
+        let encryption_key = key_encryption_key(encryption_key)?;
+        let pem = self.private_key_pem()?;
+        let mut nonce = [0_u8; SEALED_NONCE_LEN];
+        openssl::rand::rand_bytes(&mut nonce).map_err(|e| e.to_string())?;
+        let mut tag = [0_u8; SEALED_TAG_LEN];
+        let ciphertext = encrypt_aead(
+            Cipher::aes_256_gcm(),
+            &encryption_key,
+            Some(&nonce),
+            organization_id.as_bytes(),
+            pem.as_bytes(),
+            &mut tag,
+        )
+        .map_err(|e| format!("Could not encrypt the private key: {e}"))?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        sealed.extend_from_slice(&tag);
+        Ok(format!("{SEALED_KEY_PREFIX}{}", BASE64.encode(sealed)))
+    }
+
+    /// Reverse of [`SigningKey::sealed_private_key`].
+    pub fn from_sealed(
+        certificate_pem: &str,
+        sealed_private_key: &str,
+        encryption_key: &str,
+        organization_id: &str,
+    ) -> Result<Self, String> {
+        let encryption_key = key_encryption_key(encryption_key)?;
+        let sealed = sealed_private_key
+            .strip_prefix(SEALED_KEY_PREFIX)
+            .and_then(|encoded| BASE64.decode(encoded).ok())
+            .filter(|bytes| bytes.len() > SEALED_NONCE_LEN + SEALED_TAG_LEN)
+            .ok_or_else(|| "The stored private key is not encrypted.".to_string())?;
+        let (nonce, rest) = sealed.split_at(SEALED_NONCE_LEN);
+        let (ciphertext, tag) = rest.split_at(rest.len() - SEALED_TAG_LEN);
+        let pem = decrypt_aead(
+            Cipher::aes_256_gcm(),
+            &encryption_key,
+            Some(nonce),
+            organization_id.as_bytes(),
+            ciphertext,
+            tag,
+        )
+        .map_err(|_| "Could not decrypt the stored private key.".to_string())?;
+        let pem = String::from_utf8(pem)
+            .map_err(|_| "The stored private key is not valid PEM.".to_string())?;
+        Self::from_pem(certificate_pem, &pem)
+    }
+
+    fn certificate_base64(&self) -> Result<String, String> {
+        self.certificate
+            .to_der()
+            .map(|der| BASE64.encode(der))
+            .map_err(|e| e.to_string())
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let mut signer = Signer::new(MessageDigest::sha256(), &self.key)
+            .map_err(|e| format!("Could not sign the document: {e}"))?;
+        signer
+            .update(data)
+            .and_then(|_| signer.sign_to_vec())
+            .map_err(|e| format!("Could not sign the document: {e}"))
+    }
+}
+
+const SEALED_KEY_PREFIX: &str = "v1:";
+const SEALED_NONCE_LEN: usize = 12;
+const SEALED_TAG_LEN: usize = 16;
+
+fn key_encryption_key(encoded: &str) -> Result<Vec<u8>, String> {
+    BASE64
+        .decode(encoded.trim())
+        .ok()
+        .filter(|bytes| bytes.len() == 32)
+        .ok_or_else(|| "SIFEN_KEY_ENCRYPTION_KEY must be 32 bytes, base64-encoded.".to_string())
+}
+
+fn name_to_string(name: &X509NameRef) -> String {
+    name.entries()
+        .filter_map(|entry| {
+            let key = entry.object().nid().short_name().ok()?;
+            let value = entry.data().as_utf8().ok()?;
+            Some(format!("{key}={value}"))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn asn1_to_utc(time: &Asn1TimeRef) -> Option<DateTime<Utc>> {
+    let epoch = Asn1Time::from_unix(0).ok()?;
+    let diff = epoch.diff(time).ok()?;
+    let seconds = i64::from(diff.days) * 86_400 + i64::from(diff.secs);
+    DateTime::from_timestamp(seconds, 0)
+}
+
+/// `SignedInfo` content for a reference to `#cdc` with `digest_value`.
+fn signed_info(cdc: &str, digest_value: &str, canonical: bool) -> String {
+    let open = if canonical {
+        format!("<SignedInfo xmlns=\"{DSIG_NS}\">")
+    } else {
+        "<SignedInfo>".to_string()
+    };
+    format!(
+        "{open}<CanonicalizationMethod Algorithm=\"{EXC_C14N}\"></CanonicalizationMethod>\
+         <SignatureMethod Algorithm=\"http://www.w3.org/2001/04/xmldsig-more#rsa-sha256\"></SignatureMethod>\
+         <Reference URI=\"#{cdc}\"><Transforms>\
+         <Transform Algorithm=\"http://www.w3.org/2000/09/xmldsig#enveloped-signature\"></Transform>\
+         <Transform Algorithm=\"{EXC_C14N}\"></Transform></Transforms>\
+         <DigestMethod Algorithm=\"http://www.w3.org/2001/04/xmlenc#sha256\"></DigestMethod>\
+         <DigestValue>{digest_value}</DigestValue></Reference></SignedInfo>"
+    )
+}
+
+#[derive(Debug, Clone)]
+pub struct IssuedDocument {
+    pub cdc: String,
+    /// Complete `rDE`, signed and with its QR data.
+    pub xml: String,
+    pub digest_value: String,
+    pub qr_url: String,
+    pub totals: Totals,
+}
+
+/// QR security code (CSC) assigned by SET, with its id.
+#[derive(Debug, Clone)]
+pub struct QrSecret {
+    pub id: String,
+    pub code: String,
+}
+
+/// Build, sign and seal an invoice into a complete `rDE`.
+pub fn issue(
+    invoice: &Invoice,
+    key: &SigningKey,
+    environment: Environment,
+    qr_secret: &QrSecret,
+) -> Result<IssuedDocument, String> {
+    invoice.validate()?;
+    let cdc = invoice.cdc();
+    let digest_value = BASE64.encode(Sha256::digest(invoice.render_de(true).as_bytes()));
+
+    let signature_value =
+        BASE64.encode(key.sign(signed_info(&cdc, &digest_value, true).as_bytes())?);
+    let signature = format!(
+        "<Signature xmlns=\"{DSIG_NS}\">{}<SignatureValue>{signature_value}</SignatureValue>\
+         <KeyInfo><X509Data><X509Certificate>{}</X509Certificate></X509Data></KeyInfo></Signature>",
+        signed_info(&cdc, &digest_value, false),
+        key.certificate_base64()?,
+    );
+
+    let totals = invoice.totals();
+    let qr_url = qr_url(environment, invoice, &totals, &digest_value, qr_secret);
+    let xml = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+         <rDE xmlns=\"{SIFEN_NS}\" xmlns:xsi=\"{XSI_NS}\" \
+         xsi:schemaLocation=\"{SIFEN_NS} siRecepDE_v{FORMAT_VERSION}.xsd\">\
+         <dVerFor>{FORMAT_VERSION}</dVerFor>{}{signature}\
+         <gCamFuFD><dCarQR>{}</dCarQR></gCamFuFD></rDE>",
+        invoice.to_xml(),
+        xml::escape_canonical_text(&qr_url),
+    );
+
+    Ok(IssuedDocument {
+        cdc,
+        xml,
+        digest_value,
+        qr_url,
+        totals,
+    })
+}
+
+/// KuDE QR link: the document's key values, hex-encoding the emission date
+/// and digest, sealed with a SHA-256 over the parameters and the CSC.
+pub fn qr_url(
+    environment: Environment,
+    invoice: &Invoice,
+    totals: &Totals,
+    digest_value: &str,
+    qr_secret: &QrSecret,
+) -> String {
+    let issued_at = invoice.issued_at.format("%Y-%m-%dT%H:%M:%S").to_string();
+    let receiver = match &invoice.receiver {
+        Receiver::Taxpayer { ruc, .. } => format!("dRucRec={ruc}"),
+        Receiver::Individual {
+            document_number, ..
+        } => format!("dNumIDRec={document_number}"),
+        Receiver::Unidentified => "dNumIDRec=0".to_string(),
+    };
+    let params = format!(
+        "nVersion={FORMAT_VERSION}&Id={}&dFeEmiDE={}&{receiver}&dTotGralOpe={}&dTotIVA={}\
+         &cItems={}&DigestValue={}&IdCSC={:0>4}",
+        invoice.cdc(),
+        hex(issued_at.as_bytes()),
+        format_amount(totals.total, &invoice.currency),
+        format_amount(totals.total_iva(), &invoice.currency),
+        invoice.items.len(),
+        hex(digest_value.as_bytes()),
+        qr_secret.id.trim(),
+    );
+    let hash = hex(&Sha256::digest(
+        format!("{params}{}", qr_secret.code).as_bytes(),
+    ));
+    format!("{}{params}&cHashQR={hash}", environment.qr_base_url())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+// ---------- Submission ----------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubmissionResult {
+    pub approved: bool,
+    pub code: String,
+    pub message: String,
+    pub protocol_number: Option<String>,
+    pub raw_response: String,
+}
+
+/// Sends signed documents to SIFEN's reception service. Transport failures
+/// are errors; SET rejections are results.
+pub trait SifenClient {
+    fn environment(&self) -> Environment;
+
+    fn submit(
+        &self,
+        request_id: u64,
+        signed_xml: &str,
+    ) -> impl Future<Output = Result<SubmissionResult, String>> + Send;
+}
+
+/// Local stand-in for SET: approves well-formed, signed documents with a
+/// valid CDC and rejects the rest with SIFEN's own codes.
+pub struct MockSifenClient;
+
+impl MockSifenClient {
+    fn evaluate(signed_xml: &str) -> SubmissionResult {
+        let reject = |code: &str, message: &str| SubmissionResult {
+            approved: false,
+            code: code.to_string(),
+            message: message.to_string(),
+            protocol_number: None,
+            raw_response: String::new(),
+        };
+        let Ok(root) = xml::parse(signed_xml) else {
+            return reject("0160", "XML malformado");
+        };
+        let Some(document) = root.child("DE") else {
+            return reject("0160", "XML malformado");
+        };
+        let cdc = document.attr("Id").unwrap_or_default();
+        if cdc.len() != 44
+            || !cdc.chars().all(|c| c.is_ascii_digit())
+            || check_digit(&cdc[..43]).to_string() != cdc[43..]
+        {
+            return reject("1000", "CDC no corresponde con las informaciones del XML");
+        }
+        if root
+            .find(&["Signature", "SignatureValue"])
+            .is_none_or(|value| value.text.trim().is_empty())
+        {
+            return reject("0141", "Firma digital ausente");
+        }
+        SubmissionResult {
+            approved: true,
+            code: "0260".to_string(),
+            message: "Autorización del DE satisfactoria".to_string(),
+            protocol_number: Some(cdc[34..43].to_string()),
+            raw_response: String::new(),
+        }
+    }
+}
+
+impl SifenClient for MockSifenClient {
+    fn environment(&self) -> Environment {
+        Environment::Mock
+    }
+
+    async fn submit(&self, _request_id: u64, signed_xml: &str) -> Result<SubmissionResult, String> {
+        Ok(Self::evaluate(signed_xml))
+    }
+}
+
+/// SET's synchronous reception web service, authenticated with the
+/// organization's certificate (mutual TLS).
+pub struct SetSifenClient {
+    environment: Environment,
+    http: Client,
+}
+
+impl SetSifenClient {
+    pub fn new(environment: Environment, key: &SigningKey) -> Result<Self, String> {
+        let identity_pem = format!("{}{}", key.private_key_pem()?, key.certificate_pem()?);
+        let identity = reqwest::Identity::from_pem(identity_pem.as_bytes())
+            .map_err(|e| format!("Could not load the certificate for SIFEN: {e}"))?;
+        let http = Client::builder()
+            .identity(identity)
+            .timeout(Duration::from_secs(60))
+            .build()
+            .map_err(|e| format!("Could not build the SIFEN client: {e}"))?;
+        Ok(Self { environment, http })
+    }
+}
+
+impl SifenClient for SetSifenClient {
+    fn environment(&self) -> Environment {
+        self.environment
+    }
+
+    async fn submit(&self, request_id: u64, signed_xml: &str) -> Result<SubmissionResult, String> {
+        let document = signed_xml
+            .trim_start()
+            .strip_prefix("<?xml version=\"1.0\" encoding=\"UTF-8\"?>")
+            .unwrap_or(signed_xml);
+        let envelope = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+             <soap:Envelope xmlns:soap=\"{SOAP_NS}\"><soap:Header/><soap:Body>\
+             <rEnviDe xmlns=\"{SIFEN_NS}\"><dId>{request_id}</dId><xDE>{document}</xDE></rEnviDe>\
+             </soap:Body></soap:Envelope>"
+        );
+        let response = self
+            .http
+            .post(self.environment.reception_url())
+            .header("Content-Type", "application/soap+xml; charset=utf-8")
+            .body(envelope)
+            .send()
+            .await
+            .map_err(|e| format!("SIFEN request failed: {e}"))?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("Could not read the SIFEN response: {e}"))?;
+        if !status.is_success() && !body.contains("rRetEnviDe") {
+            return Err(format!("SIFEN returned HTTP {status}."));
+        }
+        parse_reception_response(&body)
+    }
+}
+
+/// Read `rRetEnviDe` from a reception response.
+pub fn parse_reception_response(body: &str) -> Result<SubmissionResult, String> {
+    let root = xml::parse(body).map_err(|e| format!("Unreadable SIFEN response: {e}"))?;
+    let protocol = root
+        .descendants("rProtDe")
+        .into_iter()
+        .next()
+        .ok_or_else(|| "SIFEN response has no rProtDe.".to_string())?;
+    let state = protocol.child_text("dEstRes").unwrap_or_default();
+    let result = protocol.child("gResProc");
+    Ok(SubmissionResult {
+        approved: state.starts_with("Aprobado"),
+        code: result
+            .and_then(|r| r.child_text("dCodRes"))
+            .unwrap_or_default()
+            .to_string(),
+        message: result
+            .and_then(|r| r.child_text("dMsgRes"))
+            .unwrap_or(state)
+            .to_string(),
+        protocol_number: protocol.child_text("dProtAut").map(ToOwned::to_owned),
+        raw_response: body.to_string(),
+    })
+}
+
+/// Client for an organization's configured environment.
+pub enum Submitter {
+    Mock(MockSifenClient),
+    Set(SetSifenClient),
+}
+
+impl Submitter {
+    pub fn for_environment(environment: Environment, key: &SigningKey) -> Result<Self, String> {
+        match environment {
+            Environment::Mock => Ok(Self::Mock(MockSifenClient)),
+            Environment::Test | Environment::Production => {
+                SetSifenClient::new(environment, key).map(Self::Set)
+            }
+        }
+    }
+}
+
+impl SifenClient for Submitter {
+    fn environment(&self) -> Environment {
+        match self {
+            Self::Mock(client) => client.environment(),
+            Self::Set(client) => client.environment(),
+        }
+    }
+
+    async fn submit(&self, request_id: u64, signed_xml: &str) -> Result<SubmissionResult, String> {
+        match self {
+            Self::Mock(client) => client.submit(request_id, signed_xml).await,
+            Self::Set(client) => client.submit(request_id, signed_xml).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::{
+        bn::BigNum,
+        rsa::Rsa,
+        sign::Verifier,
+        x509::{X509Builder, X509NameBuilder},
+    };
+
+    fn test_key() -> SigningKey {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", "CASAORA TEST S.A.")
+            .unwrap();
+        let name = name.build();
+        let mut builder = X509Builder::new().unwrap();
+        builder.set_version(2).unwrap();
+        builder
+            .set_serial_number(&BigNum::from_u32(7).unwrap().to_asn1_integer().unwrap())
+            .unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(365).unwrap())
+            .unwrap();
+        builder.sign(&key, MessageDigest::sha256()).unwrap();
+        SigningKey::new(builder.build(), key).unwrap()
+    }
+
+    fn invoice() -> Invoice {
+        Invoice {
+            emitter: Emitter {
+                ruc: "80069563".to_string(),
+                check_digit: 1,
+                name: "Casaora & Asociados S.A.".to_string(),
+                taxpayer_type: 2,
+                timbrado: "12560693".to_string(),
+                timbrado_valid_from: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+                establishment: "001".to_string(),
+                expedition_point: "001".to_string(),
+                address: "Av. España 1234".to_string(),
+                house_number: "0".to_string(),
+                department_code: 1,
+                department_name: "CAPITAL".to_string(),
+                city_code: 1,
+                city_name: "ASUNCION (DISTRITO)".to_string(),
+                phone: "021123456".to_string(),
+                email: "admin@example.com".to_string(),
+                activity_code: "68200".to_string(),
+                activity_description: "Actividades inmobiliarias".to_string(),
+            },
+            receiver: Receiver::from_tax_id("4567890", "Ana <Pérez>").unwrap(),
+            document_number: 6,
+            issued_at: NaiveDate::from_ymd_opt(2021, 11, 29)
+                .unwrap()
+                .and_hms_opt(10, 0, 0)
+                .unwrap(),
+            security_code: "759571469".to_string(),
+            currency: "PYG".to_string(),
+            exchange_rate: None,
+            payment: PaymentTerms::cash("bank_transfer"),
+            items: vec![
+                InvoiceItem {
+                    code: "RENT".to_string(),
+                    description: "Alquiler 11/2021".to_string(),
+                    amount: 3_300_000.0,
+                    iva_rate: 10,
+                },
+                InvoiceItem {
+                    code: "FEE".to_string(),
+                    description: "Gastos comunes".to_string(),
+                    amount: 210_000.0,
+                    iva_rate: 5,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn check_digits_match_set_examples() {
+        assert_eq!(check_digit("80069563"), 1);
+        assert!(split_ruc("80069563-1").is_ok());
+        assert!(split_ruc("80069563-2").is_err());
+        assert_eq!(
+            invoice().cdc(),
+            "01800695631001001000000622021112917595714695"
+        );
+
+        let mut cdc = invoice();
+        cdc.emitter.taxpayer_type = 1;
+        assert_eq!(cdc.cdc(), "01800695631001001000000612021112917595714694");
+
+        assert_eq!(
+            Receiver::from_tax_id("", "").unwrap(),
+            Receiver::Unidentified
+        );
+        assert!(matches!(
+            Receiver::from_tax_id("80069563-1", "Owner S.A.").unwrap(),
+            Receiver::Taxpayer { .. }
+        ));
+    }
+
+    #[test]
+    fn totals_split_iva_inclusive_amounts() {
+        let totals = invoice().totals();
+        assert_eq!(totals.base_10, 3_000_000.0);
+        assert_eq!(totals.iva_10, 300_000.0);
+        assert_eq!(totals.base_5, 200_000.0);
+        assert_eq!(totals.iva_5, 10_000.0);
+        assert_eq!(totals.total, 3_510_000.0);
+
+        let xml = invoice().to_xml();
+        assert!(xml.contains("<dNomRec>Ana &lt;Pérez&gt;</dNomRec>"));
+        assert!(xml.contains("<dNomEmi>Casaora &amp; Asociados S.A.</dNomEmi>"));
+        assert!(xml.contains("<dTotIVA>310000</dTotIVA>"));
+    }
+
+    #[test]
+    fn issued_documents_are_signed_and_accepted_by_the_mock() {
+        let key = test_key();
+        let invoice = invoice();
+        let secret = QrSecret {
+            id: "1".to_string(),
+            code: "ABCD0000000000000000000000000000".to_string(),
+        };
+        let issued = issue(&invoice, &key, Environment::Mock, &secret).unwrap();
+
+        let root = xml::parse(&issued.xml).unwrap();
+        let signature = root
+            .find(&["Signature", "SignatureValue"])
+            .map(|value| BASE64.decode(value.text.trim()).unwrap())
+            .unwrap();
+        let mut verifier = Verifier::new(MessageDigest::sha256(), &key.key).unwrap();
+        verifier
+            .update(signed_info(&issued.cdc, &issued.digest_value, true).as_bytes())
+            .unwrap();
+        assert!(verifier.verify(&signature).unwrap());
+
+        assert!(issued
+            .qr_url
+            .starts_with("https://ekuatia.set.gov.py/consultas-test/qr?nVersion=150&Id="));
+        assert!(issued.qr_url.contains("&dNumIDRec=4567890&"));
+        assert!(issued.qr_url.contains("&IdCSC=0001&cHashQR="));
+
+        let result = MockSifenClient::evaluate(&issued.xml);
+        assert!(result.approved, "{result:?}");
+        let last = issued.cdc[43..].parse::<u8>().unwrap();
+        let wrong_cdc = format!("{}{}", &issued.cdc[..43], (last + 1) % 10);
+        let tampered = issued.xml.replace(&issued.cdc, &wrong_cdc);
+        assert_eq!(MockSifenClient::evaluate(&tampered).code, "1000");
+    }
+
+    #[test]
+    fn private_keys_are_sealed_per_organization() {
+        let key = test_key();
+        let encryption_key = BASE64.encode([7_u8; 32]);
+        let certificate = key.certificate_pem().unwrap();
+        let sealed = key.sealed_private_key(&encryption_key, "org-a").unwrap();
+        assert!(sealed.starts_with(SEALED_KEY_PREFIX));
+        assert!(!sealed.contains("PRIVATE KEY"));
+
+        let opened =
+            SigningKey::from_sealed(&certificate, &sealed, &encryption_key, "org-a").unwrap();
+        assert!(opened.key.public_eq(&key.key));
+        assert!(SigningKey::from_sealed(&certificate, &sealed, &encryption_key, "org-b").is_err());
+        let other_key = BASE64.encode([8_u8; 32]);
+        assert!(SigningKey::from_sealed(&certificate, &sealed, &other_key, "org-a").is_err());
+        let plaintext = key.private_key_pem().unwrap();
+        assert!(
+            SigningKey::from_sealed(&certificate, &plaintext, &encryption_key, "org-a").is_err()
+        );
+        assert!(key.sealed_private_key("too-short", "org-a").is_err());
+    }
+
+    #[test]
+    fn reception_responses_are_parsed() {
+        let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<env:Envelope xmlns:env="http://www.w3.org/2003/05/soap-envelope"><env:Body>
+<ns2:rRetEnviDe xmlns:ns2="http://ekuatia.set.gov.py/sifen/xsd"><ns2:rProtDe>
+<ns2:Id>01800695631001001000000612021112917595714694</ns2:Id>
+<ns2:dFecProc>2021-11-29T10:00:05-03:00</ns2:dFecProc>
+<ns2:dEstRes>Rechazado</ns2:dEstRes>
+<ns2:gResProc><ns2:dCodRes>1001</ns2:dCodRes><ns2:dMsgRes>CDC duplicado</ns2:dMsgRes></ns2:gResProc>
+</ns2:rProtDe></ns2:rRetEnviDe></env:Body></env:Envelope>"#;
+        let result = parse_reception_response(body).unwrap();
+        assert!(!result.approved);
+        assert_eq!(result.code, "1001");
+        assert_eq!(result.message, "CDC duplicado");
+        assert_eq!(result.protocol_number, None);
+    }
+}
@@ -0,0 +1,534 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde_json::{json, Value};
+use sqlx::{PgPool, Row};
+
+use crate::{
+    error::{AppError, AppResult},
+    services::availability::{self, Occupancy},
+};
+
+/// Longest window evaluated day by day, to keep calendar responses bounded.
+const MAX_RULE_DAYS: i64 = 366;
+
+/// A `stay_restrictions` row: length-of-stay and arrival/departure rules for
+/// a unit, limited to a date range and/or days of the week.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StayRestriction {
+    pub id: String,
+    pub unit_id: String,
+    pub starts_on: Option<NaiveDate>,
+    /// Inclusive.
+    pub ends_on: Option<NaiveDate>,
+    /// ISO weekdays, 1 = Monday … 7 = Sunday. Empty means every day.
+    pub days_of_week: Vec<u32>,
+    pub min_nights: Option<u32>,
+    pub max_nights: Option<u32>,
+    pub closed_to_arrival: bool,
+    pub closed_to_departure: bool,
+    pub allow_gap_fill: bool,
+}
+
+impl StayRestriction {
+    pub fn applies_on(&self, date: NaiveDate) -> bool {
+        self.starts_on.is_none_or(|start| start <= date)
+            && self.ends_on.is_none_or(|end| date <= end)
+            && (self.days_of_week.is_empty()
+                || self
+                    .days_of_week
+                    .contains(&date.weekday().number_from_monday()))
+    }
+
+    /// Date-ranged rules override weekday rules, which override unit-wide
+    /// defaults.
+    fn specificity(&self) -> u8 {
+        let ranged = self.starts_on.is_some() || self.ends_on.is_some();
+        u8::from(ranged) * 2 + u8::from(!self.days_of_week.is_empty())
+    }
+}
+
+/// The rules in force on one date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayRules {
+    pub min_nights: Option<u32>,
+    pub max_nights: Option<u32>,
+    pub closed_to_arrival: bool,
+    pub closed_to_departure: bool,
+    /// Whether an exact fill of an orphan gap may ignore `min_nights`.
+    pub allow_gap_fill: bool,
+}
+
+impl Default for DayRules {
+    fn default() -> Self {
+        Self {
+            min_nights: None,
+            max_nights: None,
+            closed_to_arrival: false,
+            closed_to_departure: false,
+            allow_gap_fill: true,
+        }
+    }
+}
+
+impl DayRules {
+    pub fn is_unrestricted(&self) -> bool {
+        self.min_nights.is_none()
+            && self.max_nights.is_none()
+            && !self.closed_to_arrival
+            && !self.closed_to_departure
+    }
+
+    pub fn to_json(&self, date: NaiveDate) -> Value {
+        json!({
+            "date": date.to_string(),
+            "min_nights": self.min_nights,
+            "max_nights": self.max_nights,
+            "closed_to_arrival": self.closed_to_arrival,
+            "closed_to_departure": self.closed_to_departure,
+        })
+    }
+}
+
+/// Resolve the rules for `date`. For min/max nights the most specific
+/// matching rules win (the strictest value among equally specific ones);
+/// closed-to-arrival/departure apply when any matching rule sets them.
+pub fn rules_for(restrictions: &[StayRestriction], date: NaiveDate) -> DayRules {
+    let mut matching: Vec<&StayRestriction> = restrictions
+        .iter()
+        .filter(|rule| rule.applies_on(date))
+        .collect();
+    matching.sort_by_key(|rule| std::cmp::Reverse(rule.specificity()));
+
+    let mut rules = DayRules {
+        closed_to_arrival: matching.iter().any(|rule| rule.closed_to_arrival),
+        closed_to_departure: matching.iter().any(|rule| rule.closed_to_departure),
+        ..DayRules::default()
+    };
+
+    if let Some(level) = matching
+        .iter()
+        .find(|rule| rule.min_nights.is_some())
+        .map(|rule| rule.specificity())
+    {
+        let setters = matching
+            .iter()
+            .filter(|rule| rule.specificity() == level && rule.min_nights.is_some());
+        rules.min_nights = setters.clone().filter_map(|rule| rule.min_nights).max();
+        rules.allow_gap_fill = setters.clone().all(|rule| rule.allow_gap_fill);
+    }
+    if let Some(level) = matching
+        .iter()
+        .find(|rule| rule.max_nights.is_some())
+        .map(|rule| rule.specificity())
+    {
+        rules.max_nights = matching
+            .iter()
+            .filter(|rule| rule.specificity() == level)
+            .filter_map(|rule| rule.max_nights)
+            .min();
+    }
+    rules
+}
+
+/// A rule a requested stay breaks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl Violation {
+    pub fn to_json(&self) -> Value {
+        json!({ "code": self.code, "message": self.message })
+    }
+}
+
+/// Whether `[check_in, check_out)` exactly fills the gap between two
+/// bookings or blocks.
+pub fn fills_gap(occupancy: &[Occupancy], check_in: NaiveDate, check_out: NaiveDate) -> bool {
+    occupancy.iter().any(|item| item.end == check_in)
+        && occupancy.iter().any(|item| item.start == check_out)
+}
+
+/// Check a stay against the unit's restrictions. `fills_gap` waives the
+/// minimum stay when the arrival-day rule allows gap fills.
+pub fn evaluate_stay(
+    restrictions: &[StayRestriction],
+    check_in: NaiveDate,
+    check_out: NaiveDate,
+    fills_gap: bool,
+) -> Vec<Violation> {
+    let nights = u32::try_from((check_out - check_in).num_days()).unwrap_or(0);
+    let arrival = rules_for(restrictions, check_in);
+    let departure = rules_for(restrictions, check_out);
+    let mut violations = Vec::new();
+
+    if arrival.closed_to_arrival {
+        violations.push(Violation {
+            code: "closed_to_arrival",
+            message: format!("Check-in is not allowed on {check_in}."),
+        });
+    }
+    if departure.closed_to_departure {
+        violations.push(Violation {
+            code: "closed_to_departure",
+            message: format!("Check-out is not allowed on {check_out}."),
+        });
+    }
+    if let Some(min_nights) = arrival.min_nights {
+        if nights < min_nights && !(fills_gap && arrival.allow_gap_fill) {
+            violations.push(Violation {
+                code: "min_nights",
+                message: format!(
+                    "Stays arriving on {check_in} require at least {min_nights} nights."
+                ),
+            });
+        }
+    }
+    if let Some(max_nights) = arrival.max_nights {
+        if nights > max_nights {
+            violations.push(Violation {
+                code: "max_nights",
+                message: format!(
+                    "Stays arriving on {check_in} are limited to {max_nights} nights."
+                ),
+            });
+        }
+    }
+    violations
+}
+
+/// Free nights between two bookings or blocks that are shorter than the
+/// minimum stay in force on their first night.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GapNights {
+    pub start: NaiveDate,
+    /// Exclusive: the next arrival.
+    pub end: NaiveDate,
+    pub nights: u32,
+    pub min_nights: u32,
+    pub allow_gap_fill: bool,
+}
+
+impl GapNights {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "start_date": self.start.to_string(),
+            "end_date": self.end.to_string(),
+            "nights": self.nights,
+            "min_nights": self.min_nights,
+            "allow_gap_fill": self.allow_gap_fill,
+        })
+    }
+}
+
+/// Orphan gaps starting within `[from, to)`.
+pub fn find_gap_nights(
+    occupancy: &[Occupancy],
+    restrictions: &[StayRestriction],
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<GapNights> {
+    let mut ranges: Vec<(NaiveDate, NaiveDate)> = occupancy
+        .iter()
+        .map(|item| (item.start, item.end))
+        .collect();
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(NaiveDate, NaiveDate)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = (*last_end).max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    merged
+        .windows(2)
+        .filter_map(|pair| {
+            let (start, end) = (pair[0].1, pair[1].0);
+            if start < from || start >= to {
+                return None;
+            }
+            let nights = u32::try_from((end - start).num_days()).ok()?;
+            let rules = rules_for(restrictions, start);
+            let min_nights = rules.min_nights?;
+            (nights < min_nights).then_some(GapNights {
+                start,
+                end,
+                nights,
+                min_nights,
+                allow_gap_fill: rules.allow_gap_fill,
+            })
+        })
+        .collect()
+}
+
+/// Days in `[from, to)` with any rule in force, for calendar views.
+pub fn restricted_days(
+    restrictions: &[StayRestriction],
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<Value> {
+    let days = (to - from).num_days().clamp(0, MAX_RULE_DAYS);
+    (0..days)
+        .map(|offset| from + Duration::days(offset))
+        .filter_map(|date| {
+            let rules = rules_for(restrictions, date);
+            (!rules.is_unrestricted()).then(|| rules.to_json(date))
+        })
+        .collect()
+}
+
+/// Active restrictions of an organization's units (or one unit) that can
+/// apply to any date in `[from, to]`.
+pub async fn load_restrictions(
+    pool: &PgPool,
+    org_id: Option<&str>,
+    unit_id: Option<&str>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> AppResult<Vec<StayRestriction>> {
+    let rows = sqlx::query(
+        "SELECT id::text AS id,
+                unit_id::text AS unit_id,
+                starts_on,
+                ends_on,
+                days_of_week,
+                min_nights,
+                max_nights,
+                closed_to_arrival,
+                closed_to_departure,
+                allow_gap_fill
+         FROM stay_restrictions
+         WHERE is_active = true
+           AND ($1::uuid IS NULL OR organization_id = $1::uuid)
+           AND ($2::uuid IS NULL OR unit_id = $2::uuid)
+           AND (starts_on IS NULL OR starts_on <= $4::date)
+           AND (ends_on IS NULL OR ends_on >= $3::date)
+         ORDER BY unit_id, created_at",
+    )
+    .bind(org_id)
+    .bind(unit_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Failed to load stay restrictions."))?;
+
+    let nights = |row: &sqlx::postgres::PgRow, column: &str| {
+        row.try_get::<Option<i32>, _>(column)
+            .ok()
+            .flatten()
+            .and_then(|value| u32::try_from(value).ok())
+    };
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            Some(StayRestriction {
+                id: row.try_get("id").ok()?,
+                unit_id: row.try_get("unit_id").ok()?,
+                starts_on: row.try_get("starts_on").ok().flatten(),
+                ends_on: row.try_get("ends_on").ok().flatten(),
+                days_of_week: row
+                    .try_get::<Vec<i16>, _>("days_of_week")
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|day| u32::try_from(day).ok())
+                    .collect(),
+                min_nights: nights(row, "min_nights"),
+                max_nights: nights(row, "max_nights"),
+                closed_to_arrival: row.try_get("closed_to_arrival").unwrap_or(false),
+                closed_to_departure: row.try_get("closed_to_departure").unwrap_or(false),
+                allow_gap_fill: row.try_get("allow_gap_fill").unwrap_or(true),
+            })
+        })
+        .collect())
+}
+
+/// Reject a stay on `unit_id` that breaks its restrictions. Bookings and
+/// blocks touching the stay are looked up only when needed to decide
+/// whether it fills an orphan gap.
+pub async fn ensure_stay_allowed(
+    pool: &PgPool,
+    unit_id: &str,
+    check_in: NaiveDate,
+    check_out: NaiveDate,
+) -> AppResult<()> {
+    let restrictions = load_restrictions(pool, None, Some(unit_id), check_in, check_out).await?;
+    if restrictions.is_empty() {
+        return Ok(());
+    }
+
+    let mut violations = evaluate_stay(&restrictions, check_in, check_out, false);
+    if violations
+        .iter()
+        .any(|violation| violation.code == "min_nights")
+    {
+        let neighbours = availability::find_overlaps(
+            pool,
+            unit_id,
+            check_in - Duration::days(1),
+            check_out + Duration::days(1),
+            None,
+        )
+        .await?;
+        if fills_gap(&neighbours, check_in, check_out) {
+            violations = evaluate_stay(&restrictions, check_in, check_out, true);
+        }
+    }
+
+    if violations.is_empty() {
+        return Ok(());
+    }
+    Err(AppError::UnprocessableEntity(
+        violations
+            .iter()
+            .map(|violation| violation.message.as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::availability::OccupancyKind;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn rule() -> StayRestriction {
+        StayRestriction {
+            id: "r".to_string(),
+            unit_id: "u1".to_string(),
+            starts_on: None,
+            ends_on: None,
+            days_of_week: Vec::new(),
+            min_nights: None,
+            max_nights: None,
+            closed_to_arrival: false,
+            closed_to_departure: false,
+            allow_gap_fill: true,
+        }
+    }
+
+    fn booked(start: &str, end: &str) -> Occupancy {
+        Occupancy {
+            kind: OccupancyKind::Reservation,
+            id: format!("{start}-{end}"),
+            unit_id: "u1".to_string(),
+            start: date(start),
+            end: date(end),
+            source: "manual".to_string(),
+            integration_id: None,
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn date_range_rule_overrides_unit_default() {
+        let restrictions = vec![
+            StayRestriction {
+                min_nights: Some(3),
+                ..rule()
+            },
+            StayRestriction {
+                starts_on: Some(date("2026-12-20")),
+                ends_on: Some(date("2027-01-05")),
+                min_nights: Some(7),
+                ..rule()
+            },
+            StayRestriction {
+                days_of_week: vec![6],
+                closed_to_arrival: true,
+                ..rule()
+            },
+        ];
+
+        assert_eq!(
+            rules_for(&restrictions, date("2026-12-01")).min_nights,
+            Some(3)
+        );
+        assert_eq!(
+            rules_for(&restrictions, date("2026-12-24")).min_nights,
+            Some(7)
+        );
+        // 2026-12-05 is a Saturday.
+        assert!(rules_for(&restrictions, date("2026-12-05")).closed_to_arrival);
+        assert!(!rules_for(&restrictions, date("2026-12-04")).closed_to_arrival);
+    }
+
+    #[test]
+    fn evaluates_arrival_departure_and_length_rules() {
+        let restrictions = vec![
+            StayRestriction {
+                min_nights: Some(2),
+                max_nights: Some(14),
+                ..rule()
+            },
+            StayRestriction {
+                // Sundays
+                days_of_week: vec![7],
+                closed_to_departure: true,
+                ..rule()
+            },
+        ];
+
+        let codes = |check_in: &str, check_out: &str| -> Vec<&'static str> {
+            evaluate_stay(&restrictions, date(check_in), date(check_out), false)
+                .into_iter()
+                .map(|violation| violation.code)
+                .collect()
+        };
+        assert!(codes("2026-11-02", "2026-11-05").is_empty());
+        assert_eq!(codes("2026-11-02", "2026-11-03"), vec!["min_nights"]);
+        assert_eq!(codes("2026-11-02", "2026-11-30"), vec!["max_nights"]);
+        assert_eq!(
+            codes("2026-11-05", "2026-11-08"),
+            vec!["closed_to_departure"]
+        );
+    }
+
+    #[test]
+    fn exact_gap_fill_waives_minimum_stay() {
+        let restrictions = vec![StayRestriction {
+            min_nights: Some(3),
+            ..rule()
+        }];
+        let occupancy = vec![
+            booked("2026-11-01", "2026-11-05"),
+            booked("2026-11-06", "2026-11-10"),
+            booked("2026-11-15", "2026-11-20"),
+        ];
+
+        assert!(fills_gap(
+            &occupancy,
+            date("2026-11-05"),
+            date("2026-11-06")
+        ));
+        assert!(
+            evaluate_stay(&restrictions, date("2026-11-05"), date("2026-11-06"), true).is_empty()
+        );
+
+        let gaps = find_gap_nights(
+            &occupancy,
+            &restrictions,
+            date("2026-11-01"),
+            date("2026-12-01"),
+        );
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].start, date("2026-11-05"));
+        assert_eq!(gaps[0].nights, 1);
+
+        let strict = vec![StayRestriction {
+            min_nights: Some(3),
+            allow_gap_fill: false,
+            ..rule()
+        }];
+        assert_eq!(
+            evaluate_stay(&strict, date("2026-11-05"), date("2026-11-06"), true).len(),
+            1
+        );
+    }
+}
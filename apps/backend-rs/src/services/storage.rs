@@ -40,6 +40,13 @@ pub struct PresignedUpload {
     pub expires_in_seconds: u64,
 }
 
+#[derive(Debug, Clone)]
+pub struct StoredObject {
+    pub object_key: String,
+    pub public_url: String,
+    pub size_bytes: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct PresignedDownload {
     pub download_url: String,
@@ -198,6 +205,46 @@ pub async fn presign_private_download(
     })
 }
 
+/// Upload server-generated bytes (e.g. rendered PDFs) to the public bucket
+/// under `namespace`, returning the object key and its public URL.
+pub async fn upload_public_object(
+    config: &AppConfig,
+    namespace: StorageNamespace,
+    key: &str,
+    bytes: Vec<u8>,
+    content_type: &str,
+) -> Result<StoredObject, AppError> {
+    let bucket = config
+        .storage_s3_public_bucket
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| {
+            AppError::ServiceUnavailable("Public storage bucket is not configured.".to_string())
+        })?;
+
+    validate_client_key(key)?;
+    let object_key = public_object_key(namespace, key);
+    let client = s3_client_from_config(config).await?;
+    let size_bytes = bytes.len();
+
+    client
+        .put_object()
+        .bucket(bucket)
+        .key(&object_key)
+        .content_type(content_type)
+        .body(bytes.into())
+        .send()
+        .await
+        .map_err(|err| AppError::ServiceUnavailable(format!("Could not upload object: {err}")))?;
+
+    Ok(StoredObject {
+        public_url: public_object_url(config, &object_key)?,
+        object_key,
+        size_bytes,
+    })
+}
+
 async fn s3_client_from_config(config: &AppConfig) -> Result<S3Client, AppError> {
     let region = config
         .storage_s3_region
@@ -0,0 +1,709 @@
+//! Signature verification for inbound webhooks. Every provider signs its
+//! deliveries differently, so each scheme is a `WebhookVerifier` that checks
+//! the signature (and timestamp, when the scheme carries one) and returns the
+//! event id used for replay protection. Accepted deliveries are recorded in
+//! `webhook_receipts`; a delivery whose event id was already seen is
+//! acknowledged without being processed again, while one whose processing
+//! failed has its receipt released so the provider's retry goes through.
+
+use std::collections::HashMap;
+
+use axum::http::HeaderMap;
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::{Digest as _, Sha256};
+
+use crate::error::{AppError, AppResult};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How far a signed timestamp may drift from our clock.
+pub const DEFAULT_TOLERANCE_SECS: i64 = 300;
+
+/// How long the previous secret keeps verifying after a rotation.
+pub const ROTATION_GRACE_HOURS: i64 = 24;
+
+pub const SIGNATURE_HEADER: &str = "x-casaora-signature";
+pub const TIMESTAMP_HEADER: &str = "x-casaora-timestamp";
+pub const EVENT_ID_HEADER: &str = "x-casaora-event-id";
+
+/// The parts of an inbound request a verifier may look at.
+pub struct WebhookRequest<'a> {
+    pub headers: &'a HeaderMap,
+    pub query: &'a HashMap<String, String>,
+    pub body: &'a [u8],
+}
+
+impl WebhookRequest<'_> {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+
+    fn json(&self) -> Value {
+        serde_json::from_slice(self.body).unwrap_or(Value::Null)
+    }
+
+    /// A stable id for providers that do not send one: the body digest.
+    fn body_digest(&self) -> String {
+        hex_encode(&Sha256::digest(self.body))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedWebhook {
+    pub event_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerificationError {
+    MissingSignature,
+    Malformed,
+    Stale { delta_secs: i64 },
+    Mismatch,
+}
+
+impl std::fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingSignature => write!(f, "signature header is missing"),
+            Self::Malformed => write!(f, "signature header is malformed"),
+            Self::Stale { delta_secs } => write!(f, "signature timestamp is {delta_secs}s off"),
+            Self::Mismatch => write!(f, "signature does not match"),
+        }
+    }
+}
+
+/// One provider's signing scheme.
+pub trait WebhookVerifier: Send + Sync {
+    /// Namespace for the provider's event ids in `webhook_receipts`.
+    fn provider(&self) -> &str;
+
+    fn verify(
+        &self,
+        request: &WebhookRequest<'_>,
+        secret: &str,
+        now: i64,
+    ) -> Result<VerifiedWebhook, VerificationError>;
+}
+
+/// Verify against each candidate secret in turn (the current one and, during
+/// a rotation, the previous one). Returns the index of the secret that
+/// matched, or the most informative failure.
+pub fn verify_with_any(
+    verifier: &dyn WebhookVerifier,
+    request: &WebhookRequest<'_>,
+    secrets: &[String],
+) -> Result<(VerifiedWebhook, usize), VerificationError> {
+    let now = chrono::Utc::now().timestamp();
+    let mut failure = VerificationError::Mismatch;
+    for (index, secret) in secrets.iter().enumerate() {
+        if secret.is_empty() {
+            continue;
+        }
+        match verifier.verify(request, secret, now) {
+            Ok(verified) => return Ok((verified, index)),
+            Err(error @ (VerificationError::MissingSignature | VerificationError::Malformed)) => {
+                return Err(error)
+            }
+            Err(error) => failure = error,
+        }
+    }
+    Err(failure)
+}
+
+/// Map a failed verification to the response the provider sees.
+pub fn rejected(provider: &str, error: &VerificationError) -> AppError {
+    tracing::warn!(provider, error = %error, "Webhook signature verification failed");
+    AppError::Unauthorized("Invalid webhook signature.".to_string())
+}
+
+/// Process a verified delivery once. The receipt is claimed before `process`
+/// runs, so a concurrent replay is acknowledged without being processed; if
+/// `process` fails the receipt is released again so the provider's retry is
+/// handled rather than swallowed as a duplicate. `None` means the event id was
+/// already received.
+pub async fn process_once<T>(
+    pool: &sqlx::PgPool,
+    provider: &str,
+    event_id: &str,
+    organization_id: Option<&str>,
+    process: impl std::future::Future<Output = AppResult<T>>,
+) -> AppResult<Option<T>> {
+    if !record_receipt(pool, provider, event_id, organization_id).await? {
+        return Ok(None);
+    }
+    match process.await {
+        Ok(value) => Ok(Some(value)),
+        Err(error) => {
+            tracing::warn!(provider, event_id, error = %error, "Webhook processing failed; releasing receipt for retry");
+            release_receipt(pool, provider, event_id).await;
+            Err(error)
+        }
+    }
+}
+
+/// Record the delivery; false when the event id was already received.
+async fn record_receipt(
+    pool: &sqlx::PgPool,
+    provider: &str,
+    event_id: &str,
+    organization_id: Option<&str>,
+) -> AppResult<bool> {
+    let inserted: Option<(uuid::Uuid,)> = sqlx::query_as(
+        "INSERT INTO webhook_receipts (provider, event_id, organization_id)
+         VALUES ($1, $2, $3::uuid)
+         ON CONFLICT (provider, event_id) DO NOTHING
+         RETURNING id",
+    )
+    .bind(provider)
+    .bind(event_id)
+    .bind(organization_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Failed to record webhook receipt."))?;
+
+    if inserted.is_none() {
+        tracing::info!(provider, event_id, "Ignoring replayed webhook delivery");
+    }
+    Ok(inserted.is_some())
+}
+
+async fn release_receipt(pool: &sqlx::PgPool, provider: &str, event_id: &str) {
+    if let Err(error) =
+        sqlx::query("DELETE FROM webhook_receipts WHERE provider = $1 AND event_id = $2")
+            .bind(provider)
+            .bind(event_id)
+            .execute(pool)
+            .await
+    {
+        tracing::error!(provider, event_id, error = %error, "Failed to release webhook receipt");
+    }
+}
+
+/// An organization's active signing secret for `provider`: the current
+/// secret, the previous one while its grace period lasts, and the tolerance.
+pub async fn org_signing_secrets(
+    pool: &sqlx::PgPool,
+    organization_id: &str,
+    provider: &str,
+) -> AppResult<Option<(Vec<String>, i64)>> {
+    let row: Option<(String, Option<String>, i32)> = sqlx::query_as(
+        "SELECT secret,
+                CASE WHEN previous_secret_expires_at > now() THEN previous_secret END,
+                tolerance_seconds
+         FROM webhook_signing_secrets
+         WHERE organization_id = $1::uuid AND provider = $2 AND is_active
+         LIMIT 1",
+    )
+    .bind(organization_id)
+    .bind(provider)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::from_database_error(&e, "Failed to load webhook signing secret."))?;
+
+    Ok(row.map(|(secret, previous, tolerance)| {
+        let mut secrets = vec![secret];
+        secrets.extend(previous.filter(|value| !value.is_empty()));
+        (secrets, i64::from(tolerance))
+    }))
+}
+
+/// A fresh signing secret.
+pub fn generate_secret() -> String {
+    format!(
+        "whsec_{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+// ── Stripe ──────────────────────────────────────────────────────────
+
+/// `Stripe-Signature: t=<ts>,v1=<hex>[,v1=<hex>]` over `<ts>.<body>`.
+pub struct StripeVerifier {
+    pub tolerance_secs: i64,
+}
+
+impl Default for StripeVerifier {
+    fn default() -> Self {
+        Self {
+            tolerance_secs: DEFAULT_TOLERANCE_SECS,
+        }
+    }
+}
+
+impl WebhookVerifier for StripeVerifier {
+    fn provider(&self) -> &str {
+        "stripe"
+    }
+
+    fn verify(
+        &self,
+        request: &WebhookRequest<'_>,
+        secret: &str,
+        now: i64,
+    ) -> Result<VerifiedWebhook, VerificationError> {
+        let header = request
+            .header("stripe-signature")
+            .ok_or(VerificationError::MissingSignature)?;
+        let parts = signature_parts(header);
+        let timestamp = parts
+            .iter()
+            .find(|(key, _)| *key == "t")
+            .map(|(_, value)| *value)
+            .ok_or(VerificationError::Malformed)?;
+        let signatures: Vec<&str> = parts
+            .iter()
+            .filter(|(key, _)| *key == "v1")
+            .map(|(_, value)| *value)
+            .collect();
+        if signatures.is_empty() {
+            return Err(VerificationError::Malformed);
+        }
+        check_timestamp(timestamp, now, self.tolerance_secs)?;
+
+        let signed = [timestamp.as_bytes(), b".", request.body].concat();
+        if !signatures
+            .iter()
+            .any(|signature| hmac_matches(secret, &signed, signature))
+        {
+            return Err(VerificationError::Mismatch);
+        }
+
+        let event_id = request
+            .json()
+            .get("id")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned)
+            .unwrap_or_else(|| request.body_digest());
+        Ok(VerifiedWebhook { event_id })
+    }
+}
+
+// ── Meta (WhatsApp Cloud API) ───────────────────────────────────────
+
+/// `X-Hub-Signature-256: sha256=<hex>` over the raw body with the app
+/// secret. Meta signs no timestamp, so replays are caught by body digest.
+pub struct MetaVerifier;
+
+impl WebhookVerifier for MetaVerifier {
+    fn provider(&self) -> &str {
+        "whatsapp"
+    }
+
+    fn verify(
+        &self,
+        request: &WebhookRequest<'_>,
+        secret: &str,
+        _now: i64,
+    ) -> Result<VerifiedWebhook, VerificationError> {
+        let header = request
+            .header("x-hub-signature-256")
+            .ok_or(VerificationError::MissingSignature)?;
+        let signature = header
+            .strip_prefix("sha256=")
+            .ok_or(VerificationError::Malformed)?;
+        if !hmac_matches(secret, request.body, signature) {
+            return Err(VerificationError::Mismatch);
+        }
+        Ok(VerifiedWebhook {
+            event_id: request.body_digest(),
+        })
+    }
+}
+
+// ── Mercado Pago ────────────────────────────────────────────────────
+
+/// `x-signature: ts=<ts>,v1=<hex>` over the manifest
+/// `id:<data.id>;request-id:<x-request-id>;ts:<ts>;`.
+pub struct MercadoPagoVerifier {
+    pub tolerance_secs: i64,
+}
+
+impl Default for MercadoPagoVerifier {
+    fn default() -> Self {
+        Self {
+            tolerance_secs: DEFAULT_TOLERANCE_SECS,
+        }
+    }
+}
+
+impl WebhookVerifier for MercadoPagoVerifier {
+    fn provider(&self) -> &str {
+        "mercado_pago"
+    }
+
+    fn verify(
+        &self,
+        request: &WebhookRequest<'_>,
+        secret: &str,
+        now: i64,
+    ) -> Result<VerifiedWebhook, VerificationError> {
+        let header = request
+            .header("x-signature")
+            .ok_or(VerificationError::MissingSignature)?;
+        let parts = signature_parts(header);
+        let find = |name: &str| {
+            parts
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| *value)
+        };
+        let (Some(timestamp), Some(signature)) = (find("ts"), find("v1")) else {
+            return Err(VerificationError::Malformed);
+        };
+        check_timestamp(timestamp, now, self.tolerance_secs)?;
+
+        let body = request.json();
+        let data_id = request
+            .query
+            .get("data.id")
+            .cloned()
+            .or_else(|| {
+                body.get("data")
+                    .and_then(|data| data.get("id"))
+                    .map(id_text)
+            })
+            .unwrap_or_default();
+        // Mercado Pago lowercases alphanumeric ids in the manifest.
+        let data_id = data_id.to_lowercase();
+        let request_id = request.header("x-request-id");
+
+        let mut manifest = String::new();
+        if !data_id.is_empty() {
+            manifest.push_str(&format!("id:{data_id};"));
+        }
+        if let Some(request_id) = request_id {
+            manifest.push_str(&format!("request-id:{request_id};"));
+        }
+        manifest.push_str(&format!("ts:{timestamp};"));
+
+        if !hmac_matches(secret, manifest.as_bytes(), signature) {
+            return Err(VerificationError::Mismatch);
+        }
+
+        let event_id = body
+            .get("id")
+            .map(id_text)
+            .filter(|id| !id.is_empty())
+            .or_else(|| request_id.map(ToOwned::to_owned))
+            .unwrap_or_else(|| format!("{data_id}:{timestamp}"));
+        Ok(VerifiedWebhook { event_id })
+    }
+}
+
+// ── Generic HMAC (integration webhooks) ─────────────────────────────
+
+/// Our own scheme for external systems posting to
+/// `/integrations/webhooks/{provider}`: `X-Casaora-Timestamp: <unix ts>` and
+/// `X-Casaora-Signature: v1=<hex>` over `<ts>.<body>`, keyed with the
+/// organization's signing secret. `X-Casaora-Event-Id` names the event.
+pub struct HmacVerifier {
+    pub provider: String,
+    pub tolerance_secs: i64,
+}
+
+impl WebhookVerifier for HmacVerifier {
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn verify(
+        &self,
+        request: &WebhookRequest<'_>,
+        secret: &str,
+        now: i64,
+    ) -> Result<VerifiedWebhook, VerificationError> {
+        let header = request
+            .header(SIGNATURE_HEADER)
+            .ok_or(VerificationError::MissingSignature)?;
+        let timestamp = request
+            .header(TIMESTAMP_HEADER)
+            .ok_or(VerificationError::Malformed)?;
+        let signature = header
+            .strip_prefix("v1=")
+            .ok_or(VerificationError::Malformed)?;
+        check_timestamp(timestamp, now, self.tolerance_secs)?;
+
+        let signed = [timestamp.as_bytes(), b".", request.body].concat();
+        if !hmac_matches(secret, &signed, signature) {
+            return Err(VerificationError::Mismatch);
+        }
+
+        let event_id = request
+            .header(EVENT_ID_HEADER)
+            .map(ToOwned::to_owned)
+            .or_else(|| {
+                request
+                    .query
+                    .get("external_event_id")
+                    .map(|id| id.trim().to_string())
+                    .filter(|id| !id.is_empty())
+            })
+            .unwrap_or_else(|| request.body_digest());
+        Ok(VerifiedWebhook { event_id })
+    }
+}
+
+/// Sign an outbound delivery with the same scheme `HmacVerifier` checks, so
+/// receivers can verify our calls the way we verify theirs.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let Ok(mut mac) = HmacSha256::new_from_slice(secret.as_bytes()) else {
+        return String::new();
+    };
+    mac.update(format!("{timestamp}.").as_bytes());
+    mac.update(body);
+    format!("v1={}", hex_encode(&mac.finalize().into_bytes()))
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────
+
+fn signature_parts(header: &str) -> Vec<(&str, &str)> {
+    header
+        .split(',')
+        .filter_map(|part| part.trim().split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect()
+}
+
+/// Accepts seconds or, as some providers send, milliseconds.
+fn check_timestamp(
+    timestamp: &str,
+    now: i64,
+    tolerance_secs: i64,
+) -> Result<(), VerificationError> {
+    let mut ts = timestamp
+        .parse::<i64>()
+        .map_err(|_| VerificationError::Malformed)?;
+    if ts > 100_000_000_000 {
+        ts /= 1000;
+    }
+    let delta_secs = (now - ts).abs();
+    if delta_secs > tolerance_secs {
+        return Err(VerificationError::Stale { delta_secs });
+    }
+    Ok(())
+}
+
+/// Constant-time comparison of HMAC-SHA256(secret, message) with `expected_hex`.
+fn hmac_matches(secret: &str, message: &[u8], expected_hex: &str) -> bool {
+    let Ok(expected) = hex_decode(expected_hex) else {
+        return false;
+    };
+    let Ok(mut mac) = HmacSha256::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(message);
+    mac.verify_slice(&expected).is_ok()
+}
+
+fn id_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.trim().to_string(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn hex_decode(hex: &str) -> Result<Vec<u8>, ()> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2).ok_or(())?, 16).map_err(|_| ()))
+        .collect()
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_760_000_000;
+
+    fn sign(secret: &str, message: &[u8]) -> String {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(message);
+        hex_encode(&mac.finalize().into_bytes())
+    }
+
+    fn headers(pairs: &[(&'static str, String)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, value.parse().unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn stripe_accepts_any_v1_and_rejects_stale_timestamps() {
+        let body = br#"{"id":"evt_1","type":"checkout.session.completed"}"#;
+        let signature = sign("whsec_test", &[format!("{NOW}.").as_bytes(), body].concat());
+        let header_map = headers(&[(
+            "stripe-signature",
+            format!("t={NOW},v1=deadbeef,v1={signature}"),
+        )]);
+        let query = HashMap::new();
+        let request = WebhookRequest {
+            headers: &header_map,
+            query: &query,
+            body,
+        };
+        let verifier = StripeVerifier::default();
+
+        let verified = verifier.verify(&request, "whsec_test", NOW).unwrap();
+        assert_eq!(verified.event_id, "evt_1");
+        assert_eq!(
+            verifier.verify(&request, "other", NOW),
+            Err(VerificationError::Mismatch)
+        );
+        assert_eq!(
+            verifier.verify(&request, "whsec_test", NOW + 301),
+            Err(VerificationError::Stale { delta_secs: 301 })
+        );
+    }
+
+    #[test]
+    fn meta_signs_the_raw_body() {
+        let body = br#"{"entry":[]}"#;
+        let header_map = headers(&[(
+            "x-hub-signature-256",
+            format!("sha256={}", sign("app-secret", body)),
+        )]);
+        let query = HashMap::new();
+        let request = WebhookRequest {
+            headers: &header_map,
+            query: &query,
+            body,
+        };
+
+        let verified = MetaVerifier.verify(&request, "app-secret", NOW).unwrap();
+        assert_eq!(verified.event_id.len(), 64);
+        assert_eq!(
+            MetaVerifier.verify(&request, "wrong", NOW),
+            Err(VerificationError::Mismatch)
+        );
+
+        let unsigned = HeaderMap::new();
+        let request = WebhookRequest {
+            headers: &unsigned,
+            query: &query,
+            body,
+        };
+        assert_eq!(
+            MetaVerifier.verify(&request, "app-secret", NOW),
+            Err(VerificationError::MissingSignature)
+        );
+    }
+
+    #[test]
+    fn mercado_pago_signs_the_manifest() {
+        let body = br#"{"id":12345,"type":"payment","data":{"id":"ABC987"}}"#;
+        let manifest = format!("id:abc987;request-id:req-1;ts:{NOW};");
+        let header_map = headers(&[
+            (
+                "x-signature",
+                format!("ts={NOW},v1={}", sign("mp-secret", manifest.as_bytes())),
+            ),
+            ("x-request-id", "req-1".to_string()),
+        ]);
+        let query = HashMap::new();
+        let request = WebhookRequest {
+            headers: &header_map,
+            query: &query,
+            body,
+        };
+        let verifier = MercadoPagoVerifier::default();
+
+        let verified = verifier.verify(&request, "mp-secret", NOW).unwrap();
+        assert_eq!(verified.event_id, "12345");
+
+        // Millisecond timestamps are accepted too.
+        let ms = NOW * 1000;
+        let manifest = format!("id:abc987;request-id:req-1;ts:{ms};");
+        let header_map = headers(&[
+            (
+                "x-signature",
+                format!("ts={ms},v1={}", sign("mp-secret", manifest.as_bytes())),
+            ),
+            ("x-request-id", "req-1".to_string()),
+        ]);
+        let request = WebhookRequest {
+            headers: &header_map,
+            query: &query,
+            body,
+        };
+        assert!(verifier.verify(&request, "mp-secret", NOW).is_ok());
+    }
+
+    #[test]
+    fn any_secret_during_rotation_verifies() {
+        let body = br#"{"kind":"sync"}"#;
+        let now = chrono::Utc::now().timestamp();
+        let header_map = headers(&[
+            (SIGNATURE_HEADER, sign_payload("old-secret", now, body)),
+            (TIMESTAMP_HEADER, now.to_string()),
+            (EVENT_ID_HEADER, "ext-42".to_string()),
+        ]);
+        let query = HashMap::new();
+        let request = WebhookRequest {
+            headers: &header_map,
+            query: &query,
+            body,
+        };
+        let verifier = HmacVerifier {
+            provider: "pms".to_string(),
+            tolerance_secs: DEFAULT_TOLERANCE_SECS,
+        };
+
+        let (verified, index) = verify_with_any(
+            &verifier,
+            &request,
+            &["new-secret".to_string(), "old-secret".to_string()],
+        )
+        .unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(verified.event_id, "ext-42");
+        assert_eq!(
+            verify_with_any(&verifier, &request, &["new-secret".to_string()]),
+            Err(VerificationError::Mismatch)
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_processing_releases_the_receipt_for_retry() {
+        let Some(pool) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let provider = crate::test_support::unique_name("test-provider");
+
+        let failed = process_once(&pool, &provider, "evt-1", None, async {
+            Err::<(), _>(AppError::Dependency("upstream down".to_string()))
+        })
+        .await;
+        assert!(failed.is_err());
+
+        let retried = process_once(&pool, &provider, "evt-1", None, async { Ok("processed") })
+            .await
+            .expect("retry succeeds");
+        assert_eq!(retried, Some("processed"));
+
+        let mut ran = false;
+        let replayed = process_once(&pool, &provider, "evt-1", None, async {
+            ran = true;
+            Ok(())
+        })
+        .await
+        .expect("replay is acknowledged");
+        assert_eq!(replayed, None);
+        assert!(!ran);
+    }
+}
@@ -58,6 +58,9 @@ enum ExecutionOutcome {
 ///
 /// Legacy mode:
 /// - Execute immediately (or delayed in-memory sleep) to preserve old behavior.
+///
+/// In both modes the event is also queued for the org's outbound webhook
+/// subscriptions.
 pub async fn fire_trigger(
     pool: &sqlx::PgPool,
     org_id: &str,
@@ -65,6 +68,8 @@ pub async fn fire_trigger(
     context: &Map<String, Value>,
     engine_mode: WorkflowEngineMode,
 ) {
+    crate::services::outbound_webhooks::enqueue_event(pool, org_id, trigger_event, context).await;
+
     let queue_mode = engine_mode == WorkflowEngineMode::Queue && queue_enabled_for_org(org_id);
 
     let mut filters = Map::new();
@@ -0,0 +1,370 @@
+//! Minimal XML reader and writer helpers for the XML-speaking integrations
+//! (channel managers, bank statements, e-invoicing). Supports elements,
+//! attributes, text, CDATA, comments, processing instructions and the
+//! predefined/numeric entities; DTDs are skipped. Namespace prefixes are kept
+//! in `name` and stripped by [`XmlElement::local_name`].
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct XmlElement {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<XmlElement>,
+    pub text: String,
+}
+
+impl XmlElement {
+    /// Element name without its namespace prefix.
+    pub fn local_name(&self) -> &str {
+        self.name.rsplit(':').next().unwrap_or(&self.name)
+    }
+
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name || key.rsplit(':').next() == Some(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// First direct child with the given local name.
+    pub fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children
+            .iter()
+            .find(|child| child.local_name() == name)
+    }
+
+    /// Direct children with the given local name.
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children
+            .iter()
+            .filter(move |child| child.local_name() == name)
+    }
+
+    /// Follow a path of local names, e.g. `["Stmt", "Acct", "Id"]`.
+    pub fn find(&self, path: &[&str]) -> Option<&XmlElement> {
+        path.iter()
+            .try_fold(self, |element, name| element.child(name))
+    }
+
+    /// Trimmed text of the first child with the given local name, if non-empty.
+    pub fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name)
+            .map(|child| child.text.trim())
+            .filter(|text| !text.is_empty())
+    }
+
+    /// All descendants (depth-first) with the given local name.
+    pub fn descendants<'a>(&'a self, name: &str) -> Vec<&'a XmlElement> {
+        let mut found = Vec::new();
+        let mut stack: Vec<&XmlElement> = self.children.iter().rev().collect();
+        while let Some(element) = stack.pop() {
+            if element.local_name() == name {
+                found.push(element);
+            }
+            stack.extend(element.children.iter().rev());
+        }
+        found
+    }
+}
+
+/// Parse a document and return its root element.
+pub fn parse(input: &str) -> Result<XmlElement, String> {
+    let mut parser = Parser {
+        input: input.trim_start_matches('\u{feff}'),
+        pos: 0,
+    };
+    parser.skip_prolog()?;
+    let root = parser.element()?;
+    parser.skip_misc()?;
+    if parser.pos < parser.input.len() {
+        return Err(format!(
+            "Unexpected content after root element at {}.",
+            parser.pos
+        ));
+    }
+    Ok(root)
+}
+
+/// Escape text for use in element content or a double-quoted attribute.
+pub fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Escape element content the way Canonical XML writes it, so documents
+/// that are signed as serialized digest the same after a verifier
+/// re-canonicalizes them.
+pub fn escape_canonical_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '\r' => out.push_str("&#xD;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.pos = self.input.len() - trimmed.len();
+    }
+
+    fn skip_until(&mut self, terminator: &str) -> Result<&'a str, String> {
+        let rest = self.rest();
+        let index = rest
+            .find(terminator)
+            .ok_or_else(|| format!("Unterminated construct, expected '{terminator}'."))?;
+        self.pos += index + terminator.len();
+        Ok(&rest[..index])
+    }
+
+    /// Skip comments, processing instructions and whitespace; returns true if
+    /// anything was consumed.
+    fn skip_misc_once(&mut self) -> Result<bool, String> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        if rest.starts_with("<!--") {
+            self.skip_until("-->")?;
+            Ok(true)
+        } else if rest.starts_with("<?") {
+            self.skip_until("?>")?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn skip_misc(&mut self) -> Result<(), String> {
+        while self.skip_misc_once()? {}
+        Ok(())
+    }
+
+    fn skip_prolog(&mut self) -> Result<(), String> {
+        loop {
+            self.skip_misc()?;
+            if self.rest().starts_with("<!DOCTYPE") {
+                self.skip_doctype()?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn skip_doctype(&mut self) -> Result<(), String> {
+        let mut depth = 0usize;
+        for (offset, ch) in self.rest().char_indices() {
+            match ch {
+                '[' => depth += 1,
+                ']' => depth = depth.saturating_sub(1),
+                '>' if depth == 0 => {
+                    self.pos += offset + 1;
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+        Err("Unterminated DOCTYPE.".to_string())
+    }
+
+    fn name(&mut self) -> Result<&'a str, String> {
+        let rest = self.rest();
+        let end = rest
+            .find(|ch: char| ch.is_whitespace() || matches!(ch, '/' | '>' | '='))
+            .unwrap_or(rest.len());
+        if end == 0 {
+            return Err(format!("Expected a name at {}.", self.pos));
+        }
+        self.pos += end;
+        Ok(&rest[..end])
+    }
+
+    fn element(&mut self) -> Result<XmlElement, String> {
+        if !self.rest().starts_with('<') {
+            return Err(format!("Expected an element at {}.", self.pos));
+        }
+        self.pos += 1;
+        let mut element = XmlElement {
+            name: self.name()?.to_string(),
+            ..XmlElement::default()
+        };
+
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if let Some(stripped) = rest.strip_prefix("/>") {
+                self.pos = self.input.len() - stripped.len();
+                return Ok(element);
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let key = self.name()?.to_string();
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(format!("Attribute '{key}' has no value."));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = self
+                .rest()
+                .chars()
+                .next()
+                .filter(|ch| *ch == '"' || *ch == '\'')
+                .ok_or_else(|| format!("Attribute '{key}' value must be quoted."))?;
+            self.pos += 1;
+            let raw = self.skip_until(&quote.to_string())?;
+            element.attributes.push((key, unescape(raw)));
+        }
+
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err(format!("Element '{}' is not closed.", element.name));
+            }
+            if let Some(stripped) = rest.strip_prefix("</") {
+                self.pos = self.input.len() - stripped.len();
+                let closing = self.name()?;
+                if closing != element.name {
+                    return Err(format!(
+                        "Mismatched closing tag '{closing}' for '{}'.",
+                        element.name
+                    ));
+                }
+                self.skip_whitespace();
+                if !self.rest().starts_with('>') {
+                    return Err(format!("Malformed closing tag '{closing}'."));
+                }
+                self.pos += 1;
+                return Ok(element);
+            }
+            if rest.starts_with("<![CDATA[") {
+                self.pos += "<![CDATA[".len();
+                let data = self.skip_until("]]>")?;
+                element.text.push_str(data);
+            } else if rest.starts_with("<!--") {
+                self.skip_until("-->")?;
+            } else if rest.starts_with("<?") {
+                self.skip_until("?>")?;
+            } else if rest.starts_with('<') {
+                let child = self.element()?;
+                element.children.push(child);
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                element.text.push_str(&unescape(&rest[..end]));
+                self.pos += end;
+            }
+        }
+    }
+}
+
+fn unescape(raw: &str) -> String {
+    if !raw.contains('&') {
+        return raw.to_string();
+    }
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(index) = rest.find('&') {
+        out.push_str(&rest[..index]);
+        rest = &rest[index..];
+        let Some(end) = rest.find(';') else {
+            out.push_str(rest);
+            return out;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| {
+                    entity
+                        .strip_prefix('#')
+                        .and_then(|dec| dec.parse::<u32>().ok())
+                })
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(ch) => {
+                out.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_elements_attributes_and_entities() {
+        let doc = parse(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <!-- feed -->
+            <ns:Document xmlns:ns="urn:test">
+              <ns:Item id="1" label='A &amp; B'>Caf&#233; &lt;ok&gt;</ns:Item>
+              <ns:Item id="2"><![CDATA[<raw>]]></ns:Item>
+              <ns:Empty/>
+            </ns:Document>"#,
+        )
+        .unwrap();
+
+        assert_eq!(doc.local_name(), "Document");
+        let items: Vec<_> = doc.children_named("Item").collect();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].attr("label"), Some("A & B"));
+        assert_eq!(items[0].text, "Café <ok>");
+        assert_eq!(items[1].text, "<raw>");
+        assert!(doc.child("Empty").is_some());
+        assert_eq!(doc.descendants("Item").len(), 2);
+    }
+
+    #[test]
+    fn rejects_mismatched_tags() {
+        assert!(parse("<a><b></a></b>").is_err());
+        assert!(parse("<a>").is_err());
+    }
+
+    #[test]
+    fn escape_round_trips_through_parse() {
+        let text = r#"Tom & "Jerry" <3"#;
+        let doc = parse(&format!("<v a=\"{0}\">{0}</v>", escape(text))).unwrap();
+        assert_eq!(doc.text, text);
+        assert_eq!(doc.attr("a"), Some(text));
+    }
+}
@@ -66,6 +66,8 @@ pub struct AppState {
     pub config: Arc<AppConfig>,
     pub db_pool: Option<PgPool>,
     pub http_client: Client,
+    /// Sends outbound webhook deliveries; see `outbound_webhooks::delivery_client`.
+    pub webhook_http_client: Client,
     pub llm_client: LlmClient,
     pub clerk_jwks_cache: Option<JwksCache>,
     pub org_membership_cache: CacheLayer,
@@ -86,6 +88,15 @@ impl AppState {
                 crate::error::AppError::Internal(format!("Could not build HTTP client: {error}"))
             })?;
 
+        let webhook_http_client = crate::services::outbound_webhooks::delivery_client(
+            !config.is_production(),
+        )
+        .map_err(|error| {
+            crate::error::AppError::Internal(format!(
+                "Could not build webhook HTTP client: {error}"
+            ))
+        })?;
+
         let clerk_jwks_cache = config
             .clerk_jwks_url
             .as_ref()
@@ -129,6 +140,7 @@ impl AppState {
             config,
             db_pool,
             http_client,
+            webhook_http_client,
             llm_client,
             clerk_jwks_cache,
             org_membership_cache,
@@ -1,9 +1,18 @@
 #![allow(dead_code)]
 
-use serde_json::{json, Value};
+use serde_json::{json, Map, Value};
 use sqlx::{PgPool, Row};
 
-use crate::{auth::AuthenticatedUser, cache::org_key, error::AppError, state::AppState};
+use crate::{
+    auth::AuthenticatedUser,
+    cache::org_key,
+    error::AppError,
+    services::{
+        api_keys,
+        permissions::{self, Permission},
+    },
+    state::AppState,
+};
 
 fn db_pool(state: &AppState) -> Result<&PgPool, AppError> {
     state.db_pool.as_ref().ok_or_else(|| {
@@ -25,9 +34,25 @@ pub async fn get_org_membership(
         .get_or_try_init(&cache_key, || async {
             let pool = db_pool(state)?;
             let row = sqlx::query(
-                "SELECT row_to_json(t) AS row
+                "SELECT to_jsonb(t) || jsonb_build_object(
+                          'custom_role',
+                          CASE WHEN r.id IS NULL THEN NULL ELSE jsonb_build_object(
+                            'id', r.id,
+                            'name', r.name,
+                            'permissions', to_jsonb(r.permissions),
+                            'property_group_ids', to_jsonb(r.property_group_ids)
+                          ) END,
+                          'property_group_ids',
+                          COALESCE((
+                            SELECT jsonb_agg(m.group_id)
+                            FROM property_group_members m
+                            WHERE m.organization_id = t.organization_id
+                              AND m.user_id = t.user_id
+                          ), '[]'::jsonb)
+                        ) AS row
                  FROM organization_members t
-                 WHERE organization_id = $1::uuid AND user_id = $2::uuid
+                 LEFT JOIN organization_roles r ON r.id = t.custom_role_id
+                 WHERE t.organization_id = $1::uuid AND t.user_id = $2::uuid
                  LIMIT 1",
             )
             .bind(org_id)
@@ -38,6 +63,10 @@ pub async fn get_org_membership(
 
             let membership =
                 row.and_then(|value| value.try_get::<Option<Value>, _>("row").ok().flatten());
+            let membership = match membership {
+                Some(membership) => Some(membership),
+                None => api_keys::api_key_membership(pool, user_id, org_id).await?,
+            };
             Ok(membership.unwrap_or(Value::Null))
         })
         .await?;
@@ -61,27 +90,208 @@ pub async fn assert_org_member(
         })
 }
 
-pub async fn assert_org_role(
+/// The single authorization check for protected actions. Built-in roles
+/// hold the permission's defaults, custom roles their explicit grants and
+/// API keys whatever their scopes stand in for.
+pub async fn assert_permission(
     state: &AppState,
     user_id: &str,
     org_id: &str,
-    allowed_roles: &[&str],
+    permission: Permission,
 ) -> Result<Value, AppError> {
     let membership = assert_org_member(state, user_id, org_id).await?;
-    let role = membership
-        .get("role")
-        .and_then(Value::as_str)
-        .unwrap_or("unknown");
-
-    if allowed_roles.contains(&role) {
+    if permissions::membership_allows(&membership, permission) {
         return Ok(membership);
     }
-
     Err(AppError::Forbidden(format!(
-        "Forbidden: role '{role}' is not allowed for this action."
+        "Forbidden: missing permission '{}'.",
+        permission.key()
     )))
 }
 
+/// `assert_permission` for an action on one property: members limited to
+/// property groups must have the property in one of them.
+pub async fn assert_property_permission(
+    state: &AppState,
+    user_id: &str,
+    org_id: &str,
+    permission: Permission,
+    property_id: &str,
+) -> Result<Value, AppError> {
+    let membership = assert_permission(state, user_id, org_id, permission).await?;
+    assert_property_in_scope(state, org_id, &membership, Some(property_id)).await?;
+    Ok(membership)
+}
+
+/// Rejects a record on a property outside the membership's scope. Records
+/// with no property are only visible to members with the whole portfolio.
+pub async fn assert_property_in_scope(
+    state: &AppState,
+    org_id: &str,
+    membership: &Value,
+    property_id: Option<&str>,
+) -> Result<(), AppError> {
+    let Some(property_ids) = property_scope(state, org_id, membership).await? else {
+        return Ok(());
+    };
+    check_property_in_scope(&property_ids, property_id.unwrap_or_default())
+}
+
+/// `assert_property_in_scope` for records linked to a property through a
+/// unit (reservations, leases, calendar blocks).
+pub async fn assert_unit_in_scope(
+    state: &AppState,
+    org_id: &str,
+    membership: &Value,
+    unit_id: Option<&str>,
+) -> Result<(), AppError> {
+    let Some(property_ids) = property_scope(state, org_id, membership).await? else {
+        return Ok(());
+    };
+    let property_id = match unit_id.filter(|id| !id.is_empty()) {
+        Some(unit_id) => sqlx::query_scalar::<_, String>(
+            "SELECT property_id::text FROM units
+             WHERE id = $1::uuid AND organization_id = $2::uuid",
+        )
+        .bind(unit_id)
+        .bind(org_id)
+        .fetch_optional(db_pool(state)?)
+        .await
+        .map_err(|error| AppError::from_database_error(&error, "Database request failed."))?,
+        None => None,
+    };
+    check_property_in_scope(&property_ids, property_id.as_deref().unwrap_or_default())
+}
+
+/// Narrows a list query to the membership's property scope by filtering
+/// `column` on the allowed property ids. An explicit filter on a property
+/// outside the scope is rejected; `false` means the member sees no rows.
+pub async fn scope_property_filter(
+    state: &AppState,
+    org_id: &str,
+    membership: &Value,
+    filters: &mut Map<String, Value>,
+    column: &str,
+) -> Result<bool, AppError> {
+    let Some(property_ids) = property_scope(state, org_id, membership).await? else {
+        return Ok(true);
+    };
+    if let Some(property_id) = filters.get(column).and_then(Value::as_str) {
+        check_property_in_scope(&property_ids, property_id)?;
+        return Ok(true);
+    }
+    if property_ids.is_empty() {
+        return Ok(false);
+    }
+    filters.insert(column.to_string(), json!(property_ids));
+    Ok(true)
+}
+
+/// `scope_property_filter` for tables linked to properties through
+/// `unit_id`: the scope, and an optional `property_id` filter, become a
+/// `unit_id` filter on the units of those properties. An explicit `unit_id`
+/// filter outside the scope is rejected; `false` means no rows match.
+pub async fn scope_unit_filter(
+    state: &AppState,
+    org_id: &str,
+    membership: &Value,
+    filters: &mut Map<String, Value>,
+    property_id: Option<&str>,
+) -> Result<bool, AppError> {
+    let scope = property_scope(state, org_id, membership).await?;
+    if let (Some(scope), Some(property_id)) = (&scope, property_id) {
+        check_property_in_scope(scope, property_id)?;
+    }
+    let scoped = scope.is_some();
+    let property_ids = match (property_id, scope) {
+        (Some(property_id), _) => vec![property_id.to_string()],
+        (None, Some(scope)) => scope,
+        (None, None) => return Ok(true),
+    };
+    let unit_ids = if property_ids.is_empty() {
+        Vec::new()
+    } else {
+        sqlx::query_scalar::<_, String>(
+            "SELECT id::text FROM units
+             WHERE organization_id = $1::uuid
+               AND property_id::text = ANY($2)",
+        )
+        .bind(org_id)
+        .bind(&property_ids)
+        .fetch_all(db_pool(state)?)
+        .await
+        .map_err(|error| AppError::from_database_error(&error, "Database request failed."))?
+    };
+
+    if let Some(unit_id) = filters.get("unit_id").and_then(Value::as_str) {
+        if unit_ids.iter().any(|id| id == unit_id) {
+            return Ok(true);
+        }
+        if scoped && property_id.is_none() {
+            return Err(AppError::Forbidden(
+                "Forbidden: this unit is outside your access scope.".to_string(),
+            ));
+        }
+        return Ok(false);
+    }
+    if unit_ids.is_empty() {
+        return Ok(false);
+    }
+    filters.insert("unit_id".to_string(), json!(unit_ids));
+    Ok(true)
+}
+
+fn check_property_in_scope(property_ids: &[String], property_id: &str) -> Result<(), AppError> {
+    if property_ids.iter().any(|id| id == property_id) {
+        return Ok(());
+    }
+    Err(AppError::Forbidden(
+        "Forbidden: this property is outside your access scope.".to_string(),
+    ))
+}
+
+/// Properties a membership is limited to, or `None` for the whole portfolio.
+pub async fn property_scope(
+    state: &AppState,
+    org_id: &str,
+    membership: &Value,
+) -> Result<Option<Vec<String>>, AppError> {
+    let Some(group_ids) = permissions::membership_property_groups(membership) else {
+        return Ok(None);
+    };
+    let pool = db_pool(state)?;
+    let property_ids = sqlx::query_scalar::<_, String>(
+        "SELECT DISTINCT property_id::text
+         FROM property_groups g
+         CROSS JOIN LATERAL unnest(g.property_ids) AS property_id
+         WHERE g.organization_id = $1::uuid
+           AND g.id = ANY($2::uuid[])",
+    )
+    .bind(org_id)
+    .bind(&group_ids)
+    .fetch_all(pool)
+    .await
+    .map_err(|error| AppError::from_database_error(&error, "Database request failed."))?;
+    Ok(Some(property_ids))
+}
+
+/// Membership check for endpoints an API key may only reach with a given
+/// scope (e.g. `agents`). Human members pass on membership alone.
+pub async fn assert_org_scope(
+    state: &AppState,
+    user_id: &str,
+    org_id: &str,
+    scope: &str,
+) -> Result<Value, AppError> {
+    let membership = assert_org_member(state, user_id, org_id).await?;
+    match api_keys::membership_scopes(&membership) {
+        Some(scopes) if !scopes.iter().any(|item| item == scope) => Err(AppError::Forbidden(
+            format!("Forbidden: this API key needs the '{scope}' scope."),
+        )),
+        _ => Ok(membership),
+    }
+}
+
 pub async fn ensure_app_user(
     state: &AppState,
     user: &AuthenticatedUser,
@@ -126,6 +336,12 @@ pub async fn list_user_org_ids(state: &AppState, user_id: &str) -> Result<Vec<St
         "SELECT organization_id::text AS organization_id
          FROM organization_members
          WHERE user_id = $1::uuid
+         UNION
+         SELECT organization_id::text
+         FROM organization_api_keys
+         WHERE service_user_id = $1::uuid
+           AND revoked_at IS NULL
+           AND (expires_at IS NULL OR expires_at > now())
          LIMIT 500",
     )
     .bind(user_id)
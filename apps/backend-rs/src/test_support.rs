@@ -0,0 +1,194 @@
+//! Fixtures for tests that need Postgres.
+//!
+//! These tests run against the database named by `TEST_DATABASE_URL`, which
+//! must have `db/schema.sql` applied. They return early when it is unset so
+//! `cargo test` stays green without a database.
+
+#![allow(dead_code)]
+
+use std::{sync::Arc, time::Duration};
+
+use axum::{
+    body::Body,
+    http::{HeaderMap, HeaderValue, Method, Request, StatusCode},
+};
+use serde_json::Value;
+use sqlx::{postgres::PgPoolOptions, PgPool};
+use tower::ServiceExt;
+
+use crate::{cache::CacheLayer, config::AppConfig, state::AppState};
+
+pub async fn test_pool() -> Option<PgPool> {
+    let url = std::env::var("TEST_DATABASE_URL")
+        .ok()
+        .filter(|value| !value.trim().is_empty());
+    let Some(url) = url else {
+        eprintln!("TEST_DATABASE_URL is not set; skipping database test.");
+        return None;
+    };
+    let pool = PgPoolOptions::new()
+        .max_connections(4)
+        .connect(&url)
+        .await
+        .expect("connect to TEST_DATABASE_URL");
+    Some(pool)
+}
+
+/// App state backed by `pool`, trusting the `x-user-id` dev header.
+pub fn test_state(pool: Option<PgPool>) -> AppState {
+    let mut config = AppConfig::from_env();
+    config.environment = "development".to_string();
+    config.dev_auth_overrides_enabled = true;
+    config.default_user_id = None;
+    config.ai_agent_enabled = false;
+
+    let config = Arc::new(config);
+    let http_client = reqwest::Client::new();
+    let llm_client =
+        crate::services::llm_client::LlmClient::new(http_client.clone(), Arc::clone(&config));
+
+    AppState {
+        config,
+        db_pool: pool,
+        http_client,
+        webhook_http_client: crate::services::outbound_webhooks::delivery_client(true)
+            .expect("webhook client"),
+        llm_client,
+        clerk_jwks_cache: None,
+        org_membership_cache: CacheLayer::new("org_membership", 1000, Duration::from_secs(30)),
+        public_listings_cache: CacheLayer::new("public_listings", 100, Duration::from_secs(15)),
+        report_response_cache: CacheLayer::new("reports", 100, Duration::from_secs(20)),
+        enrichment_cache: CacheLayer::new("enrichment", 100, Duration::from_secs(120)),
+        agent_config_cache: CacheLayer::new("agent_config", 100, Duration::from_secs(60)),
+        fx_cache: CacheLayer::new("fx", 10, Duration::from_secs(3600)),
+    }
+}
+
+pub fn user_headers(user_id: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "x-user-id",
+        HeaderValue::from_str(user_id).expect("valid user id header"),
+    );
+    headers
+}
+
+/// Send one request through the v1 router as `user_id`; returns the status
+/// and the JSON body (`Null` when empty).
+pub async fn send(
+    state: &AppState,
+    method: Method,
+    uri: &str,
+    user_id: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("x-user-id", user_id);
+    let body = match body {
+        Some(body) => {
+            request = request.header("content-type", "application/json");
+            Body::from(body.to_string())
+        }
+        None => Body::empty(),
+    };
+    let response = crate::routes::v1_router()
+        .with_state(state.clone())
+        .oneshot(request.body(body).expect("valid request"))
+        .await
+        .expect("router response");
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("response body");
+    let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, json)
+}
+
+pub fn unique_name(prefix: &str) -> String {
+    format!("{prefix}-{}", uuid::Uuid::new_v4().simple())
+}
+
+pub async fn create_user(pool: &PgPool) -> String {
+    let email = format!("{}@test.casaora.dev", unique_name("user"));
+    sqlx::query_scalar::<_, String>(
+        "INSERT INTO app_users (email, full_name) VALUES ($1, 'Test User') RETURNING id::text",
+    )
+    .bind(email)
+    .fetch_one(pool)
+    .await
+    .expect("insert app user")
+}
+
+/// A new organization whose owner is an `owner_admin` member.
+pub async fn create_org(pool: &PgPool, owner_user_id: &str) -> String {
+    let org_id = sqlx::query_scalar::<_, String>(
+        "INSERT INTO organizations (name, owner_user_id)
+         VALUES ($1, $2::uuid)
+         RETURNING id::text",
+    )
+    .bind(unique_name("org"))
+    .bind(owner_user_id)
+    .fetch_one(pool)
+    .await
+    .expect("insert organization");
+    add_member(pool, &org_id, owner_user_id, "owner_admin").await;
+    org_id
+}
+
+pub async fn add_member(pool: &PgPool, org_id: &str, user_id: &str, role: &str) {
+    sqlx::query(
+        "INSERT INTO organization_members (organization_id, user_id, role)
+         VALUES ($1::uuid, $2::uuid, $3::member_role)",
+    )
+    .bind(org_id)
+    .bind(user_id)
+    .bind(role)
+    .execute(pool)
+    .await
+    .expect("insert organization member");
+}
+
+pub async fn create_property(pool: &PgPool, org_id: &str) -> String {
+    sqlx::query_scalar::<_, String>(
+        "INSERT INTO properties (organization_id, name)
+         VALUES ($1::uuid, $2)
+         RETURNING id::text",
+    )
+    .bind(org_id)
+    .bind(unique_name("property"))
+    .fetch_one(pool)
+    .await
+    .expect("insert property")
+}
+
+pub async fn create_unit(pool: &PgPool, org_id: &str, property_id: &str) -> String {
+    let code = unique_name("unit");
+    sqlx::query_scalar::<_, String>(
+        "INSERT INTO units (organization_id, property_id, code, name)
+         VALUES ($1::uuid, $2::uuid, $3, $3)
+         RETURNING id::text",
+    )
+    .bind(org_id)
+    .bind(property_id)
+    .bind(code)
+    .fetch_one(pool)
+    .await
+    .expect("insert unit")
+}
+
+/// An active lease for a test tenant, starting on 2026-01-01.
+pub async fn create_lease(pool: &PgPool, org_id: &str, currency: &str) -> String {
+    sqlx::query_scalar::<_, String>(
+        "INSERT INTO leases (organization_id, tenant_full_name, lease_status, starts_on, currency)
+         VALUES ($1::uuid, $2, 'active', '2026-01-01', $3)
+         RETURNING id::text",
+    )
+    .bind(org_id)
+    .bind(unique_name("tenant"))
+    .bind(currency)
+    .fetch_one(pool)
+    .await
+    .expect("insert lease")
+}
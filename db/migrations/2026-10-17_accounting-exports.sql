@@ -0,0 +1,17 @@
+-- Fiscal identifiers needed by the accounting exports: the organization's
+-- SET timbrado (printed-invoice authorization) for the Libro de Ventas,
+-- tenant and owner RUCs for the counterparty column, and the supplier's
+-- timbrado on expenses for the Libro de Compras.
+
+ALTER TABLE organizations
+  ADD COLUMN IF NOT EXISTS timbrado_number text,
+  ADD COLUMN IF NOT EXISTS timbrado_valid_until date;
+
+ALTER TABLE properties
+  ADD COLUMN IF NOT EXISTS asset_owner_ruc text;
+
+ALTER TABLE leases
+  ADD COLUMN IF NOT EXISTS tenant_ruc text;
+
+ALTER TABLE expenses
+  ADD COLUMN IF NOT EXISTS invoice_timbrado text;
@@ -0,0 +1,103 @@
+-- Bank statement file imports feeding reconciliation. Finance staff upload
+-- the monthly export (CSV, OFX/QFX or CAMT.053); the parsed rows are kept on
+-- the import for a preview, then committed into bank_transactions and
+-- handed to the auto-reconciliation batch.
+
+-- Column mapping for one bank's CSV export, saved per organization.
+-- Columns are header names, or 1-based positions when header_row = 0.
+CREATE TABLE IF NOT EXISTS bank_import_profiles (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  name text NOT NULL,
+  bank_name text,
+  delimiter text NOT NULL DEFAULT ',' CHECK (char_length(delimiter) = 1),
+  header_row integer NOT NULL DEFAULT 1 CHECK (header_row >= 0),
+  date_column text NOT NULL,
+  date_format text NOT NULL DEFAULT '%d/%m/%Y',
+  description_column text,
+  amount_column text,
+  credit_column text,
+  debit_column text,
+  reference_column text,
+  counterparty_column text,
+  external_id_column text,
+  currency_column text,
+  decimal_separator text NOT NULL DEFAULT ',' CHECK (decimal_separator IN (',', '.')),
+  default_currency char(3) NOT NULL DEFAULT 'PYG' CHECK (default_currency ~ '^[A-Z]{3}$'),
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  CHECK (amount_column IS NOT NULL OR credit_column IS NOT NULL OR debit_column IS NOT NULL),
+  UNIQUE (organization_id, name)
+);
+
+DROP TRIGGER IF EXISTS trg_bank_import_profiles_updated_at ON bank_import_profiles;
+CREATE TRIGGER trg_bank_import_profiles_updated_at
+  BEFORE UPDATE ON bank_import_profiles
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE bank_import_profiles ENABLE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS bank_import_profiles_org_member_all ON bank_import_profiles;
+CREATE POLICY bank_import_profiles_org_member_all
+  ON bank_import_profiles FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+-- One uploaded statement file. `rows` holds every parsed line with its
+-- dedupe hash and whether it was already imported, so the preview and the
+-- commit see exactly the same data.
+CREATE TABLE IF NOT EXISTS bank_statement_imports (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  profile_id uuid REFERENCES bank_import_profiles(id) ON DELETE SET NULL,
+  file_name text,
+  file_format text NOT NULL CHECK (file_format IN ('csv', 'ofx', 'camt053')),
+  bank_name text,
+  account_number text,
+  status text NOT NULL DEFAULT 'preview'
+    CHECK (status IN ('preview', 'committed', 'discarded')),
+  rows jsonb NOT NULL DEFAULT '[]'::jsonb,
+  rejected_rows jsonb NOT NULL DEFAULT '[]'::jsonb,
+  total_rows integer NOT NULL DEFAULT 0,
+  new_rows integer NOT NULL DEFAULT 0,
+  duplicate_rows integer NOT NULL DEFAULT 0,
+  imported_rows integer NOT NULL DEFAULT 0,
+  reconciliation_run_id uuid REFERENCES reconciliation_runs(id) ON DELETE SET NULL,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  committed_at timestamptz,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_bank_statement_imports_org
+  ON bank_statement_imports(organization_id, created_at DESC);
+
+DROP TRIGGER IF EXISTS trg_bank_statement_imports_updated_at ON bank_statement_imports;
+CREATE TRIGGER trg_bank_statement_imports_updated_at
+  BEFORE UPDATE ON bank_statement_imports
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE bank_statement_imports ENABLE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS bank_statement_imports_org_member_all ON bank_statement_imports;
+CREATE POLICY bank_statement_imports_org_member_all
+  ON bank_statement_imports FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+-- Imported transactions remember their file and a content hash; the hash
+-- is unique per organization so re-uploading an overlapping export never
+-- duplicates rows.
+ALTER TABLE bank_transactions
+  ADD COLUMN IF NOT EXISTS import_id uuid REFERENCES bank_statement_imports(id) ON DELETE SET NULL;
+ALTER TABLE bank_transactions
+  ADD COLUMN IF NOT EXISTS dedupe_hash text;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_bank_transactions_dedupe
+  ON bank_transactions(organization_id, dedupe_hash)
+  WHERE dedupe_hash IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_bank_transactions_external_id
+  ON bank_transactions(organization_id, external_id)
+  WHERE external_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_bank_transactions_import
+  ON bank_transactions(import_id)
+  WHERE import_id IS NOT NULL;
@@ -0,0 +1,9 @@
+-- API channel connectors (Airbnb, Booking.com) keep credentials and
+-- room/rate mappings in integration metadata and record the outcome of the
+-- last outbound availability/rates/restrictions (ARI) push.
+ALTER TABLE integrations
+  ADD COLUMN IF NOT EXISTS metadata jsonb NOT NULL DEFAULT '{}'::jsonb;
+ALTER TABLE integrations
+  ADD COLUMN IF NOT EXISTS last_ari_push_at timestamptz;
+ALTER TABLE integrations
+  ADD COLUMN IF NOT EXISTS ari_push_error text;
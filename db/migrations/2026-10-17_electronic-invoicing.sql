@@ -0,0 +1,141 @@
+-- SIFEN (e-Kuatia) electronic invoicing. Each organization configures its
+-- establishment, expedition point and QR security code (CSC), uploads the
+-- certificate its documents are signed with, and issues one electronic
+-- document (DE) per collection or owner statement. The CDC and QR link of a
+-- collection's invoice are copied onto the collection for receipts.
+
+CREATE TABLE IF NOT EXISTS sifen_settings (
+  organization_id uuid PRIMARY KEY REFERENCES organizations(id) ON DELETE CASCADE,
+  -- 'mock' never leaves the server; 'test' and 'production' reach SET.
+  environment text NOT NULL DEFAULT 'mock'
+    CHECK (environment IN ('mock', 'test', 'production')),
+  establishment text NOT NULL DEFAULT '001' CHECK (establishment ~ '^[0-9]{3}$'),
+  expedition_point text NOT NULL DEFAULT '001' CHECK (expedition_point ~ '^[0-9]{3}$'),
+  next_document_number integer NOT NULL DEFAULT 1
+    CHECK (next_document_number BETWEEN 1 AND 9999999),
+  timbrado_valid_from date,
+  -- 1 = persona física, 2 = persona jurídica.
+  taxpayer_type smallint NOT NULL DEFAULT 2 CHECK (taxpayer_type IN (1, 2)),
+  fiscal_address text,
+  house_number text NOT NULL DEFAULT '0',
+  department_code smallint,
+  department_name text,
+  city_code integer,
+  city_name text,
+  phone text,
+  email text,
+  activity_code text,
+  activity_description text,
+  csc_id text,
+  csc text,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+DROP TRIGGER IF EXISTS trg_sifen_settings_updated_at ON sifen_settings;
+CREATE TRIGGER trg_sifen_settings_updated_at
+  BEFORE UPDATE ON sifen_settings
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE sifen_settings ENABLE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS sifen_settings_org_member_all ON sifen_settings;
+CREATE POLICY sifen_settings_org_member_all
+  ON sifen_settings FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+-- Signing certificates. The private key is never returned by the API.
+CREATE TABLE IF NOT EXISTS sifen_certificates (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  subject text NOT NULL,
+  issuer text,
+  serial_number text,
+  valid_from timestamptz,
+  valid_until timestamptz,
+  certificate_pem text NOT NULL,
+  private_key_pem text NOT NULL,
+  is_active boolean NOT NULL DEFAULT true,
+  uploaded_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_sifen_certificates_active
+  ON sifen_certificates(organization_id)
+  WHERE is_active;
+
+DROP TRIGGER IF EXISTS trg_sifen_certificates_updated_at ON sifen_certificates;
+CREATE TRIGGER trg_sifen_certificates_updated_at
+  BEFORE UPDATE ON sifen_certificates
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE sifen_certificates ENABLE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS sifen_certificates_org_member_all ON sifen_certificates;
+CREATE POLICY sifen_certificates_org_member_all
+  ON sifen_certificates FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+-- One electronic invoice per collection or owner statement. A rejected
+-- document is rebuilt in place and keeps its number.
+CREATE TABLE IF NOT EXISTS electronic_documents (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  collection_record_id uuid REFERENCES collection_records(id) ON DELETE SET NULL,
+  owner_statement_id uuid REFERENCES owner_statements(id) ON DELETE SET NULL,
+  -- iTiDE: 1 = factura electrónica.
+  document_type smallint NOT NULL DEFAULT 1,
+  establishment text NOT NULL,
+  expedition_point text NOT NULL,
+  document_number integer NOT NULL,
+  cdc text NOT NULL CHECK (cdc ~ '^[0-9]{44}$'),
+  issued_at timestamp NOT NULL,
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
+  total_amount numeric(14, 2) NOT NULL DEFAULT 0,
+  total_iva numeric(14, 2) NOT NULL DEFAULT 0,
+  receiver_name text NOT NULL,
+  receiver_tax_id text,
+  signed_xml text NOT NULL,
+  digest_value text NOT NULL,
+  qr_url text NOT NULL,
+  environment text NOT NULL,
+  status text NOT NULL DEFAULT 'signed'
+    CHECK (status IN ('signed', 'approved', 'rejected')),
+  submitted_at timestamptz,
+  response_code text,
+  response_message text,
+  protocol_number text,
+  response jsonb,
+  certificate_id uuid REFERENCES sifen_certificates(id) ON DELETE SET NULL,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (cdc),
+  UNIQUE (organization_id, establishment, expedition_point, document_number)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_electronic_documents_collection
+  ON electronic_documents(collection_record_id)
+  WHERE collection_record_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS uq_electronic_documents_statement
+  ON electronic_documents(owner_statement_id)
+  WHERE owner_statement_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_electronic_documents_org_status
+  ON electronic_documents(organization_id, status, issued_at);
+
+DROP TRIGGER IF EXISTS trg_electronic_documents_updated_at ON electronic_documents;
+CREATE TRIGGER trg_electronic_documents_updated_at
+  BEFORE UPDATE ON electronic_documents
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE electronic_documents ENABLE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS electronic_documents_org_member_all ON electronic_documents;
+CREATE POLICY electronic_documents_org_member_all
+  ON electronic_documents FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+ALTER TABLE collection_records
+  ADD COLUMN IF NOT EXISTS sifen_cdc text,
+  ADD COLUMN IF NOT EXISTS sifen_qr_url text;
@@ -0,0 +1,60 @@
+-- Fine-grained permissions. Routes check `resource.action` permissions whose
+-- defaults reproduce the built-in member roles; organizations can define
+-- custom roles with explicit permissions, optionally limited to property
+-- groups, and assign them to members.
+
+CREATE TABLE IF NOT EXISTS property_groups (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  name text NOT NULL,
+  description text,
+  property_ids uuid[] NOT NULL DEFAULT '{}',
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (organization_id, name)
+);
+
+-- Custom roles list `resource.action` / `resource.*` permissions explicitly
+-- and replace the built-in role's defaults for members who hold them.
+-- property_group_ids limits the role to those groups (empty = all).
+CREATE TABLE IF NOT EXISTS organization_roles (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  name text NOT NULL,
+  description text,
+  permissions text[] NOT NULL DEFAULT '{}',
+  property_group_ids uuid[] NOT NULL DEFAULT '{}',
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (organization_id, name)
+);
+
+ALTER TABLE organization_members
+  ADD COLUMN IF NOT EXISTS custom_role_id uuid
+    REFERENCES organization_roles(id);
+
+DROP TRIGGER IF EXISTS trg_property_groups_updated_at ON property_groups;
+CREATE TRIGGER trg_property_groups_updated_at
+  BEFORE UPDATE ON property_groups
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+DROP TRIGGER IF EXISTS trg_organization_roles_updated_at ON organization_roles;
+CREATE TRIGGER trg_organization_roles_updated_at
+  BEFORE UPDATE ON organization_roles
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE property_groups ENABLE ROW LEVEL SECURITY;
+ALTER TABLE organization_roles ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS property_groups_org_member_all ON property_groups;
+CREATE POLICY property_groups_org_member_all
+  ON property_groups FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+DROP POLICY IF EXISTS organization_roles_org_member_all ON organization_roles;
+CREATE POLICY organization_roles_org_member_all
+  ON organization_roles FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
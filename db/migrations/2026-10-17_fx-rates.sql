@@ -0,0 +1,99 @@
+-- Daily exchange rates for any currency pair. One row per (base, quote, day):
+-- 1 unit of base_currency buys `rate` units of quote_currency on rate_date.
+-- Rows are filled on demand by the FX service and backfilled daily for the
+-- tracked currencies, so historical conversions never hit the network twice.
+CREATE TABLE IF NOT EXISTS fx_rates (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  base_currency char(3) NOT NULL CHECK (base_currency ~ '^[A-Z]{3}$'),
+  quote_currency char(3) NOT NULL CHECK (quote_currency ~ '^[A-Z]{3}$'),
+  rate_date date NOT NULL,
+  rate numeric(24, 10) NOT NULL CHECK (rate > 0),
+  source text NOT NULL,
+  fetched_at timestamptz NOT NULL DEFAULT now(),
+  CHECK (base_currency <> quote_currency),
+  UNIQUE (base_currency, quote_currency, rate_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_fx_rates_pair_date
+  ON fx_rates(base_currency, quote_currency, rate_date DESC);
+
+-- Reference data shared by every organization: readable by anyone, written
+-- only by the backend's service role.
+ALTER TABLE fx_rates ENABLE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS fx_rates_public_read ON fx_rates;
+CREATE POLICY fx_rates_public_read ON fx_rates FOR SELECT USING (true);
+
+-- Rate for base→quote effective on a day: the latest stored rate on or
+-- before that day, taken directly, inverted, or crossed through USD.
+-- Returns NULL when no usable rate is stored.
+CREATE OR REPLACE FUNCTION fx_rate_on(p_base text, p_quote text, p_on date)
+RETURNS numeric
+LANGUAGE plpgsql
+STABLE
+AS $$
+DECLARE
+  v_base text := upper(trim(p_base));
+  v_quote text := upper(trim(p_quote));
+  v_rate numeric;
+  v_leg numeric;
+BEGIN
+  IF v_base = v_quote THEN
+    RETURN 1;
+  END IF;
+
+  SELECT CASE WHEN f.base_currency = v_base THEN f.rate ELSE 1 / f.rate END
+    INTO v_rate
+    FROM fx_rates f
+   WHERE ((f.base_currency = v_base AND f.quote_currency = v_quote)
+       OR (f.base_currency = v_quote AND f.quote_currency = v_base))
+     AND f.rate_date <= p_on
+   ORDER BY f.rate_date DESC, (f.base_currency = v_base) DESC
+   LIMIT 1;
+  IF v_rate IS NOT NULL OR v_base = 'USD' OR v_quote = 'USD' THEN
+    RETURN v_rate;
+  END IF;
+
+  v_leg := fx_rate_on(v_base, 'USD', p_on);
+  IF v_leg IS NULL THEN
+    RETURN NULL;
+  END IF;
+  v_rate := fx_rate_on('USD', v_quote, p_on);
+  RETURN v_leg * v_rate;
+END;
+$$;
+
+-- Transaction and reporting currencies accept any ISO 4217 code now that
+-- amounts can be converted through fx_rates.
+ALTER TABLE organizations DROP CONSTRAINT IF EXISTS organizations_default_currency_check;
+ALTER TABLE organizations ADD CONSTRAINT organizations_default_currency_check
+  CHECK (default_currency ~ '^[A-Z]{3}$');
+
+ALTER TABLE reservations DROP CONSTRAINT IF EXISTS reservations_currency_check;
+ALTER TABLE reservations ADD CONSTRAINT reservations_currency_check
+  CHECK (currency ~ '^[A-Z]{3}$');
+
+ALTER TABLE expenses DROP CONSTRAINT IF EXISTS expenses_currency_check;
+ALTER TABLE expenses ADD CONSTRAINT expenses_currency_check
+  CHECK (currency ~ '^[A-Z]{3}$');
+
+ALTER TABLE owner_statements DROP CONSTRAINT IF EXISTS owner_statements_currency_check;
+ALTER TABLE owner_statements ADD CONSTRAINT owner_statements_currency_check
+  CHECK (currency ~ '^[A-Z]{3}$');
+
+ALTER TABLE leases DROP CONSTRAINT IF EXISTS leases_currency_check;
+ALTER TABLE leases ADD CONSTRAINT leases_currency_check
+  CHECK (currency ~ '^[A-Z]{3}$');
+
+ALTER TABLE lease_charges DROP CONSTRAINT IF EXISTS lease_charges_currency_check;
+ALTER TABLE lease_charges ADD CONSTRAINT lease_charges_currency_check
+  CHECK (currency ~ '^[A-Z]{3}$');
+
+ALTER TABLE collection_records DROP CONSTRAINT IF EXISTS collection_records_currency_check;
+ALTER TABLE collection_records ADD CONSTRAINT collection_records_currency_check
+  CHECK (currency ~ '^[A-Z]{3}$');
+
+-- Currency the asset owner of a property receives statements and reports in.
+-- NULL means the managing organization's default currency.
+ALTER TABLE properties
+  ADD COLUMN IF NOT EXISTS owner_reporting_currency char(3)
+    CHECK (owner_reporting_currency IS NULL OR owner_reporting_currency ~ '^[A-Z]{3}$');
@@ -0,0 +1,133 @@
+-- Double-entry general ledger. Each organization gets a chart of accounts
+-- (seeded on first use by services::general_ledger) and every financial
+-- event (payments, expenses, deposits, statements, payouts) posts a
+-- balanced journal entry. Entries are immutable; corrections are posted as
+-- reversals.
+
+CREATE TABLE IF NOT EXISTS gl_accounts (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  code text NOT NULL,
+  name text NOT NULL,
+  account_type text NOT NULL
+    CHECK (account_type IN ('asset', 'liability', 'equity', 'revenue', 'expense')),
+  -- Accounts the backend posts to automatically (trust_cash, owner_payable…).
+  system_key text,
+  is_active boolean NOT NULL DEFAULT true,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (organization_id, code)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_gl_accounts_system_key
+  ON gl_accounts(organization_id, system_key)
+  WHERE system_key IS NOT NULL;
+
+DROP TRIGGER IF EXISTS trg_gl_accounts_updated_at ON gl_accounts;
+CREATE TRIGGER trg_gl_accounts_updated_at
+  BEFORE UPDATE ON gl_accounts
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE gl_accounts ENABLE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS gl_accounts_org_member_all ON gl_accounts;
+CREATE POLICY gl_accounts_org_member_all
+  ON gl_accounts FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE TABLE IF NOT EXISTS gl_journal_entries (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  entry_date date NOT NULL,
+  description text NOT NULL,
+  -- What produced the entry (lease_payment, expense, deposit, owner_statement,
+  -- manual…) and the row it came from.
+  source_type text NOT NULL DEFAULT 'manual',
+  source_id uuid,
+  -- Idempotency key: posting the same event twice is a no-op.
+  source_key text,
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
+  reversal_of uuid REFERENCES gl_journal_entries(id) ON DELETE SET NULL,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_gl_journal_entries_source_key
+  ON gl_journal_entries(organization_id, source_key)
+  WHERE source_key IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS uq_gl_journal_entries_reversal_of
+  ON gl_journal_entries(reversal_of)
+  WHERE reversal_of IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_gl_journal_entries_org_date
+  ON gl_journal_entries(organization_id, entry_date);
+CREATE INDEX IF NOT EXISTS idx_gl_journal_entries_source
+  ON gl_journal_entries(source_type, source_id);
+
+ALTER TABLE gl_journal_entries ENABLE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS gl_journal_entries_org_member_all ON gl_journal_entries;
+CREATE POLICY gl_journal_entries_org_member_all
+  ON gl_journal_entries FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE TABLE IF NOT EXISTS gl_journal_lines (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  entry_id uuid NOT NULL REFERENCES gl_journal_entries(id) ON DELETE CASCADE,
+  account_id uuid NOT NULL REFERENCES gl_accounts(id) ON DELETE RESTRICT,
+  debit numeric(14, 2) NOT NULL DEFAULT 0 CHECK (debit >= 0),
+  credit numeric(14, 2) NOT NULL DEFAULT 0 CHECK (credit >= 0),
+  -- Sub-ledger dimensions used by the per-owner ledger.
+  property_id uuid REFERENCES properties(id) ON DELETE SET NULL,
+  lease_id uuid REFERENCES leases(id) ON DELETE SET NULL,
+  memo text,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CHECK ((debit > 0) <> (credit > 0))
+);
+
+CREATE INDEX IF NOT EXISTS idx_gl_journal_lines_entry
+  ON gl_journal_lines(entry_id);
+CREATE INDEX IF NOT EXISTS idx_gl_journal_lines_account
+  ON gl_journal_lines(account_id);
+CREATE INDEX IF NOT EXISTS idx_gl_journal_lines_property
+  ON gl_journal_lines(property_id)
+  WHERE property_id IS NOT NULL;
+
+ALTER TABLE gl_journal_lines ENABLE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS gl_journal_lines_org_member_all ON gl_journal_lines;
+CREATE POLICY gl_journal_lines_org_member_all
+  ON gl_journal_lines FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+-- Debits must equal credits for every entry. Checked at commit so the lines
+-- of an entry can be inserted one by one inside a transaction.
+CREATE OR REPLACE FUNCTION gl_assert_entry_balanced()
+RETURNS trigger
+LANGUAGE plpgsql
+AS $$
+DECLARE
+  v_entry_id uuid;
+  v_debit numeric;
+  v_credit numeric;
+BEGIN
+  v_entry_id := CASE WHEN TG_OP = 'DELETE' THEN OLD.entry_id ELSE NEW.entry_id END;
+
+  SELECT COALESCE(SUM(debit), 0), COALESCE(SUM(credit), 0)
+    INTO v_debit, v_credit
+    FROM gl_journal_lines
+   WHERE entry_id = v_entry_id;
+
+  IF v_debit <> v_credit THEN
+    RAISE EXCEPTION 'Journal entry % is unbalanced (debits %, credits %)',
+      v_entry_id, v_debit, v_credit;
+  END IF;
+  RETURN NULL;
+END;
+$$;
+
+DROP TRIGGER IF EXISTS trg_gl_journal_lines_balanced ON gl_journal_lines;
+CREATE CONSTRAINT TRIGGER trg_gl_journal_lines_balanced
+  AFTER INSERT OR UPDATE OR DELETE ON gl_journal_lines
+  DEFERRABLE INITIALLY DEFERRED
+  FOR EACH ROW EXECUTE FUNCTION gl_assert_entry_balanced();
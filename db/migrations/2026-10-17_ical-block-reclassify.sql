@@ -0,0 +1,45 @@
+-- Feeds synced before block events were recognised imported owner blocks and
+-- "Not available" ranges as reservations. Move them to feed-managed calendar
+-- blocks and clear the feed validators so the next sync re-reads each
+-- affected feed in full.
+BEGIN;
+
+CREATE TEMP TABLE ical_stale_block_reservations ON COMMIT DROP AS
+SELECT id, organization_id, unit_id, integration_id, external_reservation_id,
+       check_in_date, check_out_date, notes
+FROM reservations
+WHERE source = 'ical'
+  AND integration_id IS NOT NULL
+  AND external_reservation_id IS NOT NULL
+  AND status IN ('pending', 'confirmed')
+  AND notes ILIKE 'iCal:%'
+  AND notes ~* '(not available|unavailable|blocked|closed|no disponible|bloqueado)';
+
+UPDATE integrations i
+SET ical_etag = NULL,
+    ical_last_modified = NULL,
+    ical_content_hash = NULL
+WHERE i.id IN (SELECT DISTINCT integration_id FROM ical_stale_block_reservations);
+
+INSERT INTO calendar_blocks (
+  organization_id, unit_id, integration_id, external_event_id, source, starts_on, ends_on, reason
+)
+SELECT s.organization_id, s.unit_id, s.integration_id, s.external_reservation_id, 'ical',
+       s.check_in_date, s.check_out_date, s.notes
+FROM ical_stale_block_reservations s
+WHERE NOT EXISTS (
+  SELECT 1
+  FROM calendar_blocks b
+  WHERE b.unit_id = s.unit_id
+    AND b.period && daterange(s.check_in_date, s.check_out_date, '[)')
+)
+ON CONFLICT DO NOTHING;
+
+UPDATE reservations r
+SET status = 'cancelled',
+    cancel_reason = 'Reclassified as iCal calendar block',
+    cancelled_at = now(),
+    updated_at = now()
+WHERE r.id IN (SELECT id FROM ical_stale_block_reservations);
+
+COMMIT;
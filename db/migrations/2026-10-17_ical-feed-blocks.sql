@@ -0,0 +1,11 @@
+-- Calendar blocks imported from iCal feeds (owner blocks, "Not available"
+-- ranges and expanded RRULE occurrences) remember their source event so the
+-- next sync can update or remove them.
+ALTER TABLE calendar_blocks
+  ADD COLUMN IF NOT EXISTS integration_id uuid REFERENCES integrations(id) ON DELETE CASCADE;
+ALTER TABLE calendar_blocks
+  ADD COLUMN IF NOT EXISTS external_event_id text;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_calendar_blocks_integration_external
+  ON calendar_blocks(integration_id, external_event_id)
+  WHERE external_event_id IS NOT NULL AND integration_id IS NOT NULL;
@@ -0,0 +1,9 @@
+-- Validators for conditional iCal fetches (If-None-Match / If-Modified-Since)
+-- and a fingerprint of the last applied event set, so unchanged feeds are
+-- not reprocessed on every sync.
+ALTER TABLE integrations ADD COLUMN IF NOT EXISTS ical_etag text;
+ALTER TABLE integrations ADD COLUMN IF NOT EXISTS ical_last_modified text;
+ALTER TABLE integrations ADD COLUMN IF NOT EXISTS ical_content_hash text;
+
+CREATE INDEX IF NOT EXISTS idx_integration_events_org_type
+  ON integration_events(organization_id, event_type, received_at DESC);
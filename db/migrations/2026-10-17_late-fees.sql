@@ -0,0 +1,67 @@
+-- Late fees and interest on overdue collections. A policy applies to a
+-- single lease, or to every lease in the organization when lease_id is NULL.
+-- The daily collection cycle assesses the fee as a `late_fee` lease charge
+-- linked to the overdue collection, plus its own collection record.
+
+ALTER TYPE fee_line_type ADD VALUE IF NOT EXISTS 'late_fee';
+
+-- fee_type semantics for `amount`:
+--   fixed          flat amount in the collection's currency
+--   percentage     percent of the overdue collection amount, charged once
+--   daily_interest percent of the overdue amount per day late, re-accrued daily
+CREATE TABLE IF NOT EXISTS late_fee_policies (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  lease_id uuid REFERENCES leases(id) ON DELETE CASCADE,
+  fee_type text NOT NULL CHECK (fee_type IN ('fixed', 'percentage', 'daily_interest')),
+  amount numeric(12, 4) NOT NULL CHECK (amount > 0),
+  grace_days integer NOT NULL DEFAULT 0 CHECK (grace_days BETWEEN 0 AND 90),
+  max_fee_amount numeric(12, 2) CHECK (max_fee_amount IS NULL OR max_fee_amount > 0),
+  max_fee_percent numeric(6, 2) CHECK (max_fee_percent IS NULL OR max_fee_percent > 0),
+  is_active boolean NOT NULL DEFAULT true,
+  source text NOT NULL DEFAULT 'manual' CHECK (source IN ('manual', 'lease_abstraction')),
+  notes text,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_late_fee_policies_lease
+  ON late_fee_policies(lease_id)
+  WHERE lease_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS uq_late_fee_policies_org_default
+  ON late_fee_policies(organization_id)
+  WHERE lease_id IS NULL;
+
+DROP TRIGGER IF EXISTS trg_late_fee_policies_updated_at ON late_fee_policies;
+CREATE TRIGGER trg_late_fee_policies_updated_at
+  BEFORE UPDATE ON late_fee_policies
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE late_fee_policies ENABLE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS late_fee_policies_org_member_all ON late_fee_policies;
+CREATE POLICY late_fee_policies_org_member_all
+  ON late_fee_policies FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+-- Late-fee charges point back at the collection they penalize. Once staff
+-- adjust a fee (adjusted_at set) daily interest stops re-accruing it.
+ALTER TABLE lease_charges
+  ADD COLUMN IF NOT EXISTS source_collection_id uuid
+    REFERENCES collection_records(id) ON DELETE CASCADE,
+  ADD COLUMN IF NOT EXISTS late_fee_policy_id uuid
+    REFERENCES late_fee_policies(id) ON DELETE SET NULL,
+  ADD COLUMN IF NOT EXISTS accrued_through date,
+  ADD COLUMN IF NOT EXISTS original_amount numeric(12, 2),
+  ADD COLUMN IF NOT EXISTS adjusted_at timestamptz,
+  ADD COLUMN IF NOT EXISTS adjusted_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  ADD COLUMN IF NOT EXISTS adjustment_reason text,
+  ADD COLUMN IF NOT EXISTS waived_at timestamptz,
+  ADD COLUMN IF NOT EXISTS waived_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  ADD COLUMN IF NOT EXISTS waive_reason text;
+
+-- At most one late fee per overdue collection.
+CREATE UNIQUE INDEX IF NOT EXISTS uq_lease_charges_source_collection
+  ON lease_charges(source_collection_id)
+  WHERE source_collection_id IS NOT NULL;
@@ -0,0 +1,89 @@
+-- Security deposits for long-term leases. The reservation deposit flow
+-- (routes/deposits.rs) covers short stays only; lease deposits are received
+-- at move-in, can accrue simple interest, and are settled at move-out with
+-- itemized deductions linked to maintenance requests and inspection
+-- reports, a deduction letter and a refund through the payment provider.
+
+CREATE TABLE IF NOT EXISTS lease_deposits (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  lease_id uuid NOT NULL UNIQUE REFERENCES leases(id) ON DELETE CASCADE,
+  property_id uuid REFERENCES properties(id) ON DELETE SET NULL,
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
+  amount_required numeric(12, 2) NOT NULL DEFAULT 0 CHECK (amount_required >= 0),
+  amount_received numeric(12, 2) NOT NULL DEFAULT 0 CHECK (amount_received >= 0),
+  received_at timestamptz,
+  lease_payment_id uuid REFERENCES lease_payments(id) ON DELETE SET NULL,
+  receipt_reference text,
+  interest_rate numeric(7, 4) NOT NULL DEFAULT 0
+    CHECK (interest_rate >= 0 AND interest_rate <= 1),
+  interest_accrued numeric(12, 2) NOT NULL DEFAULT 0 CHECK (interest_accrued >= 0),
+  interest_accrued_through date,
+  status text NOT NULL DEFAULT 'pending'
+    CHECK (status IN ('pending', 'held', 'move_out', 'settled')),
+  move_out_inspection_id uuid,
+  baseline_inspection_id uuid,
+  inspection_comparison jsonb,
+  deductions_total numeric(12, 2) NOT NULL DEFAULT 0,
+  refund_amount numeric(12, 2) NOT NULL DEFAULT 0,
+  balance_due numeric(12, 2) NOT NULL DEFAULT 0,
+  refund_method text
+    CHECK (refund_method IS NULL OR refund_method IN ('bank_transfer', 'stripe', 'mercado_pago')),
+  refund_reference text,
+  settled_at timestamptz,
+  letter_pdf_url text,
+  notes text,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_lease_deposits_org_status
+  ON lease_deposits(organization_id, status, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS lease_deposit_deductions (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  deposit_id uuid NOT NULL REFERENCES lease_deposits(id) ON DELETE CASCADE,
+  category text NOT NULL
+    CHECK (category IN (
+      'damage', 'cleaning', 'unpaid_rent', 'unpaid_utilities', 'missing_items', 'other'
+    )),
+  description text NOT NULL,
+  amount numeric(12, 2) NOT NULL CHECK (amount > 0),
+  room text,
+  maintenance_request_id uuid REFERENCES maintenance_requests(id) ON DELETE SET NULL,
+  inspection_report_id uuid,
+  photo_urls jsonb NOT NULL DEFAULT '[]'::jsonb,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_lease_deposit_deductions_deposit
+  ON lease_deposit_deductions(deposit_id, created_at);
+
+DROP TRIGGER IF EXISTS trg_lease_deposits_updated_at ON lease_deposits;
+CREATE TRIGGER trg_lease_deposits_updated_at
+  BEFORE UPDATE ON lease_deposits
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+DROP TRIGGER IF EXISTS trg_lease_deposit_deductions_updated_at ON lease_deposit_deductions;
+CREATE TRIGGER trg_lease_deposit_deductions_updated_at
+  BEFORE UPDATE ON lease_deposit_deductions
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE lease_deposits ENABLE ROW LEVEL SECURITY;
+ALTER TABLE lease_deposit_deductions ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS lease_deposits_org_member_all ON lease_deposits;
+CREATE POLICY lease_deposits_org_member_all
+  ON lease_deposits FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+DROP POLICY IF EXISTS lease_deposit_deductions_org_member_all ON lease_deposit_deductions;
+CREATE POLICY lease_deposit_deductions_org_member_all
+  ON lease_deposit_deductions FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
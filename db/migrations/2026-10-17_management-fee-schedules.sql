@@ -0,0 +1,42 @@
+-- Configurable management fee schedules. Owner contracts charge a percent
+-- of gross or net, a flat monthly fee per unit, occupancy-tiered rates, a
+-- leasing fee on new leases and markups on maintenance invoices. Statements
+-- evaluate the schedule in effect for the property (or the organization
+-- default) and store the result in owner_statements.management_fees, with
+-- one explained line item per computed fee.
+
+ALTER TABLE owner_statements
+  ADD COLUMN IF NOT EXISTS management_fees numeric(12, 2) NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS management_fee_schedules (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  property_id uuid REFERENCES properties(id) ON DELETE CASCADE,
+  name text NOT NULL,
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
+  effective_from date NOT NULL,
+  effective_to date,
+  rules jsonb NOT NULL DEFAULT '[]'::jsonb CHECK (jsonb_typeof(rules) = 'array'),
+  is_active boolean NOT NULL DEFAULT true,
+  notes text,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  CHECK (effective_to IS NULL OR effective_to >= effective_from)
+);
+
+CREATE INDEX IF NOT EXISTS idx_management_fee_schedules_scope
+  ON management_fee_schedules(organization_id, property_id, effective_from DESC);
+
+DROP TRIGGER IF EXISTS trg_management_fee_schedules_updated_at ON management_fee_schedules;
+CREATE TRIGGER trg_management_fee_schedules_updated_at
+  BEFORE UPDATE ON management_fee_schedules
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE management_fee_schedules ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS management_fee_schedules_org_member_all ON management_fee_schedules;
+CREATE POLICY management_fee_schedules_org_member_all
+  ON management_fee_schedules FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
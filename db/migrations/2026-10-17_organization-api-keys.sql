@@ -0,0 +1,45 @@
+-- Organization API keys. Integrations (MCP server, warehouse loaders)
+-- authenticate with scoped `csk_` tokens instead of a borrowed user session.
+-- Each key acts as its own service user; only token hashes are stored, and a
+-- rotated-out token keeps working until previous_token_expires_at.
+
+CREATE TABLE IF NOT EXISTS organization_api_keys (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  name text NOT NULL,
+  key_prefix text NOT NULL,
+  token_hash text NOT NULL UNIQUE,
+  previous_token_hash text,
+  previous_token_expires_at timestamptz,
+  scopes text[] NOT NULL
+    CHECK (
+      cardinality(scopes) > 0
+      AND scopes <@ ARRAY['read_only', 'finance', 'operations', 'agents']::text[]
+    ),
+  service_user_id uuid NOT NULL UNIQUE REFERENCES app_users(id) ON DELETE CASCADE,
+  expires_at timestamptz,
+  last_used_at timestamptz,
+  revoked_at timestamptz,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_organization_api_keys_org
+  ON organization_api_keys(organization_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_organization_api_keys_previous_token_hash
+  ON organization_api_keys(previous_token_hash)
+  WHERE previous_token_hash IS NOT NULL;
+
+DROP TRIGGER IF EXISTS trg_organization_api_keys_updated_at ON organization_api_keys;
+CREATE TRIGGER trg_organization_api_keys_updated_at
+  BEFORE UPDATE ON organization_api_keys
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE organization_api_keys ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS organization_api_keys_org_member_all ON organization_api_keys;
+CREATE POLICY organization_api_keys_org_member_all
+  ON organization_api_keys FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
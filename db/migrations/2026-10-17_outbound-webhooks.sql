@@ -0,0 +1,77 @@
+-- Outbound webhooks. Organizations subscribe URLs to domain events
+-- (workflow triggers such as payment_received, and notification.* events);
+-- deliveries are signed with the subscription secret, retried with
+-- exponential backoff, logged for replay, and a subscription is disabled
+-- after repeated consecutive failures.
+
+CREATE TABLE IF NOT EXISTS webhook_subscriptions (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  url text NOT NULL,
+  description text,
+  event_types jsonb NOT NULL DEFAULT '[]'::jsonb,
+  secret text NOT NULL,
+  is_active boolean NOT NULL DEFAULT true,
+  consecutive_failures integer NOT NULL DEFAULT 0,
+  disabled_at timestamptz,
+  disabled_reason text,
+  last_delivery_at timestamptz,
+  last_success_at timestamptz,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_subscriptions_org_active
+  ON webhook_subscriptions(organization_id, is_active);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  subscription_id uuid NOT NULL REFERENCES webhook_subscriptions(id) ON DELETE CASCADE,
+  event_id uuid NOT NULL,
+  event_type text NOT NULL,
+  payload jsonb NOT NULL,
+  status text NOT NULL DEFAULT 'pending'
+    CHECK (status IN ('pending', 'sending', 'succeeded', 'failed', 'cancelled')),
+  attempt_count integer NOT NULL DEFAULT 0,
+  next_attempt_at timestamptz NOT NULL DEFAULT now(),
+  last_attempt_at timestamptz,
+  response_status integer,
+  response_body text,
+  error_message text,
+  delivered_at timestamptz,
+  replay_of_delivery_id uuid REFERENCES webhook_deliveries(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due
+  ON webhook_deliveries(status, next_attempt_at);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_subscription
+  ON webhook_deliveries(subscription_id, created_at DESC);
+
+DROP TRIGGER IF EXISTS trg_webhook_subscriptions_updated_at ON webhook_subscriptions;
+CREATE TRIGGER trg_webhook_subscriptions_updated_at
+  BEFORE UPDATE ON webhook_subscriptions
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+DROP TRIGGER IF EXISTS trg_webhook_deliveries_updated_at ON webhook_deliveries;
+CREATE TRIGGER trg_webhook_deliveries_updated_at
+  BEFORE UPDATE ON webhook_deliveries
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE webhook_subscriptions ENABLE ROW LEVEL SECURITY;
+ALTER TABLE webhook_deliveries ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS webhook_subscriptions_org_member_all ON webhook_subscriptions;
+CREATE POLICY webhook_subscriptions_org_member_all
+  ON webhook_subscriptions FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+DROP POLICY IF EXISTS webhook_deliveries_org_member_all ON webhook_deliveries;
+CREATE POLICY webhook_deliveries_org_member_all
+  ON webhook_deliveries FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
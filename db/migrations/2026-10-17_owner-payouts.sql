@@ -0,0 +1,127 @@
+-- Owner payout batches. Finalized statements are grouped into a batch,
+-- one bank transfer per owner account, and the batch is exported as a bulk
+-- transfer file (generic CSV or a bank's fixed-width layout) and tracked
+-- through scheduled → sent → confirmed/failed. Confirming a batch marks its
+-- statements paid.
+
+ALTER TABLE properties
+  ADD COLUMN IF NOT EXISTS asset_owner_bank_name text,
+  ADD COLUMN IF NOT EXISTS asset_owner_bank_account_number text,
+  ADD COLUMN IF NOT EXISTS asset_owner_bank_account_type text
+    CHECK (asset_owner_bank_account_type IS NULL
+           OR asset_owner_bank_account_type IN ('checking', 'savings'));
+
+-- Fixed-width bulk-transfer layouts of the banks an organization pays
+-- owners from. Each record is a JSON array of field specs.
+CREATE TABLE IF NOT EXISTS payout_bank_layouts (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  name text NOT NULL,
+  bank_name text,
+  file_extension text NOT NULL DEFAULT 'txt' CHECK (file_extension ~ '^[a-z0-9]{1,5}$'),
+  line_ending text NOT NULL DEFAULT 'crlf' CHECK (line_ending IN ('crlf', 'lf')),
+  header_fields jsonb NOT NULL DEFAULT '[]'::jsonb
+    CHECK (jsonb_typeof(header_fields) = 'array'),
+  detail_fields jsonb NOT NULL CHECK (jsonb_typeof(detail_fields) = 'array'),
+  trailer_fields jsonb NOT NULL DEFAULT '[]'::jsonb
+    CHECK (jsonb_typeof(trailer_fields) = 'array'),
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (organization_id, name)
+);
+
+-- A batch of owner payouts sent to the bank together.
+CREATE TABLE IF NOT EXISTS owner_payout_batches (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  reference text NOT NULL,
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
+  status text NOT NULL DEFAULT 'scheduled'
+    CHECK (status IN ('scheduled', 'sent', 'confirmed', 'failed')),
+  scheduled_for date NOT NULL DEFAULT current_date,
+  transfer_count integer NOT NULL DEFAULT 0,
+  total_amount numeric(14, 2) NOT NULL DEFAULT 0,
+  total_management_fees numeric(14, 2) NOT NULL DEFAULT 0,
+  total_reserves numeric(14, 2) NOT NULL DEFAULT 0,
+  notes text,
+  sent_at timestamptz,
+  confirmed_at timestamptz,
+  failed_at timestamptz,
+  failure_reason text,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (organization_id, reference)
+);
+
+CREATE INDEX IF NOT EXISTS idx_owner_payout_batches_org_status
+  ON owner_payout_batches(organization_id, status, scheduled_for);
+
+-- One statement paid by a batch. Statements sharing an owner account are
+-- one transfer (transfer_sequence) in the bank file.
+CREATE TABLE IF NOT EXISTS owner_payout_items (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  batch_id uuid NOT NULL REFERENCES owner_payout_batches(id) ON DELETE CASCADE,
+  owner_statement_id uuid NOT NULL REFERENCES owner_statements(id) ON DELETE CASCADE,
+  transfer_sequence integer NOT NULL,
+  payee_name text NOT NULL,
+  payee_tax_id text,
+  bank_name text,
+  bank_account_number text NOT NULL,
+  bank_account_type text,
+  net_payout numeric(12, 2) NOT NULL,
+  management_fees numeric(12, 2) NOT NULL DEFAULT 0,
+  reserve_amount numeric(12, 2) NOT NULL DEFAULT 0,
+  amount numeric(12, 2) NOT NULL CHECK (amount > 0),
+  status text NOT NULL DEFAULT 'scheduled'
+    CHECK (status IN ('scheduled', 'sent', 'confirmed', 'failed')),
+  failure_reason text,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_owner_payout_items_batch
+  ON owner_payout_items(batch_id, transfer_sequence);
+-- A statement is in at most one batch that has not failed.
+CREATE UNIQUE INDEX IF NOT EXISTS uq_owner_payout_items_statement_active
+  ON owner_payout_items(owner_statement_id)
+  WHERE status <> 'failed';
+
+DROP TRIGGER IF EXISTS trg_payout_bank_layouts_updated_at ON payout_bank_layouts;
+CREATE TRIGGER trg_payout_bank_layouts_updated_at
+  BEFORE UPDATE ON payout_bank_layouts
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+DROP TRIGGER IF EXISTS trg_owner_payout_batches_updated_at ON owner_payout_batches;
+CREATE TRIGGER trg_owner_payout_batches_updated_at
+  BEFORE UPDATE ON owner_payout_batches
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+DROP TRIGGER IF EXISTS trg_owner_payout_items_updated_at ON owner_payout_items;
+CREATE TRIGGER trg_owner_payout_items_updated_at
+  BEFORE UPDATE ON owner_payout_items
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE payout_bank_layouts ENABLE ROW LEVEL SECURITY;
+ALTER TABLE owner_payout_batches ENABLE ROW LEVEL SECURITY;
+ALTER TABLE owner_payout_items ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS payout_bank_layouts_org_member_all ON payout_bank_layouts;
+CREATE POLICY payout_bank_layouts_org_member_all
+  ON payout_bank_layouts FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+DROP POLICY IF EXISTS owner_payout_batches_org_member_all ON owner_payout_batches;
+CREATE POLICY owner_payout_batches_org_member_all
+  ON owner_payout_batches FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+DROP POLICY IF EXISTS owner_payout_items_org_member_all ON owner_payout_items;
+CREATE POLICY owner_payout_items_org_member_all
+  ON owner_payout_items FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
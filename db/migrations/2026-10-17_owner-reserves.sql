@@ -0,0 +1,89 @@
+-- Owner reserves and contributions. Each property can hold an operating
+-- reserve, topped up from statement payouts until it reaches its target.
+-- A statement whose expenses exceed revenue draws on the reserve and
+-- carries any remaining deficit into the next period. The owner can be
+-- asked for a contribution (capital call), paid through a payment link
+-- from the owner portal and credited to the reserve.
+
+ALTER TABLE properties
+  ADD COLUMN IF NOT EXISTS reserve_target_amount numeric(12, 2) NOT NULL DEFAULT 0
+    CHECK (reserve_target_amount >= 0),
+  ADD COLUMN IF NOT EXISTS reserve_top_up_cap numeric(12, 2)
+    CHECK (reserve_top_up_cap IS NULL OR reserve_top_up_cap >= 0);
+
+ALTER TABLE owner_statements
+  ADD COLUMN IF NOT EXISTS previous_balance numeric(12, 2) NOT NULL DEFAULT 0,
+  ADD COLUMN IF NOT EXISTS reserve_draw numeric(12, 2) NOT NULL DEFAULT 0,
+  ADD COLUMN IF NOT EXISTS reserve_top_up numeric(12, 2) NOT NULL DEFAULT 0,
+  ADD COLUMN IF NOT EXISTS balance_carried_forward numeric(12, 2) NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS owner_contributions (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  property_id uuid NOT NULL REFERENCES properties(id) ON DELETE CASCADE,
+  owner_statement_id uuid REFERENCES owner_statements(id) ON DELETE SET NULL,
+  reference_code text NOT NULL UNIQUE DEFAULT encode(gen_random_bytes(8), 'hex'),
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
+  amount numeric(12, 2) NOT NULL CHECK (amount > 0),
+  reason text,
+  due_date date,
+  status text NOT NULL DEFAULT 'requested'
+    CHECK (status IN ('requested', 'paid', 'cancelled')),
+  provider text CHECK (provider IS NULL OR provider IN ('stripe', 'mercado_pago')),
+  checkout_url text,
+  provider_session_id text,
+  paid_amount numeric(12, 2),
+  paid_at timestamptz,
+  payment_reference text,
+  cancelled_at timestamptz,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_owner_contributions_org_status
+  ON owner_contributions(organization_id, status, due_date);
+
+CREATE TABLE IF NOT EXISTS owner_reserve_entries (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  property_id uuid NOT NULL REFERENCES properties(id) ON DELETE CASCADE,
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
+  entry_type text NOT NULL
+    CHECK (entry_type IN ('top_up', 'draw', 'contribution', 'adjustment')),
+  amount numeric(12, 2) NOT NULL CHECK (amount <> 0),
+  owner_statement_id uuid REFERENCES owner_statements(id) ON DELETE CASCADE,
+  owner_contribution_id uuid REFERENCES owner_contributions(id) ON DELETE SET NULL,
+  notes text,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_owner_reserve_entries_property
+  ON owner_reserve_entries(property_id, currency, created_at);
+CREATE UNIQUE INDEX IF NOT EXISTS uq_owner_reserve_entries_statement
+  ON owner_reserve_entries(owner_statement_id, entry_type)
+  WHERE owner_statement_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS uq_owner_reserve_entries_contribution
+  ON owner_reserve_entries(owner_contribution_id)
+  WHERE owner_contribution_id IS NOT NULL;
+
+DROP TRIGGER IF EXISTS trg_owner_contributions_updated_at ON owner_contributions;
+CREATE TRIGGER trg_owner_contributions_updated_at
+  BEFORE UPDATE ON owner_contributions
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE owner_contributions ENABLE ROW LEVEL SECURITY;
+ALTER TABLE owner_reserve_entries ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS owner_contributions_org_member_all ON owner_contributions;
+CREATE POLICY owner_contributions_org_member_all
+  ON owner_contributions FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+DROP POLICY IF EXISTS owner_reserve_entries_org_member_all ON owner_reserve_entries;
+CREATE POLICY owner_reserve_entries_org_member_all
+  ON owner_reserve_entries FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
@@ -0,0 +1,79 @@
+-- Payment allocation ledger. Every payment received against a lease
+-- (manual entry, Stripe, Mercado Pago, bank import) is stored once and
+-- allocated across the lease's open collection records, oldest first.
+-- Whatever is left over stays on the payment as credit and is applied to
+-- the next collection that comes due.
+
+-- Running total of allocations, kept in step by services::payment_ledger.
+-- Reconciliation already wrote this column; it was never created.
+ALTER TABLE collection_records
+  ADD COLUMN IF NOT EXISTS amount_paid numeric(12, 2) NOT NULL DEFAULT 0
+    CHECK (amount_paid >= 0);
+
+UPDATE collection_records
+   SET amount_paid = amount
+ WHERE status = 'paid' AND amount_paid = 0;
+
+CREATE TABLE IF NOT EXISTS lease_payments (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  lease_id uuid NOT NULL REFERENCES leases(id) ON DELETE CASCADE,
+  source text NOT NULL DEFAULT 'manual'
+    CHECK (source IN ('manual', 'stripe', 'mercado_pago', 'bank_import')),
+  payment_method payment_method,
+  amount numeric(12, 2) NOT NULL CHECK (amount > 0),
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
+  unallocated_amount numeric(12, 2) NOT NULL DEFAULT 0 CHECK (unallocated_amount >= 0),
+  received_at timestamptz NOT NULL DEFAULT now(),
+  reference text,
+  -- Provider id (stripe:<session>, mp:<payment>, bank:<transaction>) so
+  -- webhook retries and re-imports do not record the payment twice.
+  external_id text,
+  bank_transaction_id uuid,
+  notes text,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_lease_payments_external_id
+  ON lease_payments(organization_id, external_id)
+  WHERE external_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_lease_payments_lease
+  ON lease_payments(lease_id, received_at);
+CREATE INDEX IF NOT EXISTS idx_lease_payments_credit
+  ON lease_payments(lease_id)
+  WHERE unallocated_amount > 0;
+
+DROP TRIGGER IF EXISTS trg_lease_payments_updated_at ON lease_payments;
+CREATE TRIGGER trg_lease_payments_updated_at
+  BEFORE UPDATE ON lease_payments
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE lease_payments ENABLE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS lease_payments_org_member_all ON lease_payments;
+CREATE POLICY lease_payments_org_member_all
+  ON lease_payments FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE TABLE IF NOT EXISTS payment_allocations (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  payment_id uuid NOT NULL REFERENCES lease_payments(id) ON DELETE CASCADE,
+  collection_record_id uuid NOT NULL REFERENCES collection_records(id) ON DELETE CASCADE,
+  amount numeric(12, 2) NOT NULL CHECK (amount > 0),
+  created_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_allocations_payment
+  ON payment_allocations(payment_id);
+CREATE INDEX IF NOT EXISTS idx_payment_allocations_collection
+  ON payment_allocations(collection_record_id);
+
+ALTER TABLE payment_allocations ENABLE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS payment_allocations_org_member_all ON payment_allocations;
+CREATE POLICY payment_allocations_org_member_all
+  ON payment_allocations FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
@@ -0,0 +1,9 @@
+-- Server-rendered PDFs: the language documents are printed in, and a link
+-- from each paid collection to its generated receipt. Owner statements
+-- already carry pdf_url; rendered contracts are stored as documents rows.
+ALTER TABLE organizations
+  ADD COLUMN IF NOT EXISTS document_language text NOT NULL DEFAULT 'es'
+    CHECK (document_language IN ('es', 'en'));
+
+ALTER TABLE collection_records
+  ADD COLUMN IF NOT EXISTS receipt_pdf_url text;
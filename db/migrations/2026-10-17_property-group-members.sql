@@ -0,0 +1,28 @@
+-- Property-level access scoping. Members can be assigned to property groups
+-- directly; list endpoints, the operations overview and agent row tools then
+-- only return the properties of their groups.
+
+-- Members assigned to a property group only see its properties, on top of
+-- any groups their custom role is limited to.
+CREATE TABLE IF NOT EXISTS property_group_members (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  group_id uuid NOT NULL REFERENCES property_groups(id) ON DELETE CASCADE,
+  user_id uuid NOT NULL,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (group_id, user_id),
+  FOREIGN KEY (organization_id, user_id)
+    REFERENCES organization_members(organization_id, user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_property_group_members_org_user
+  ON property_group_members(organization_id, user_id);
+
+ALTER TABLE property_group_members ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS property_group_members_org_member_all ON property_group_members;
+CREATE POLICY property_group_members_org_member_all
+  ON property_group_members FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
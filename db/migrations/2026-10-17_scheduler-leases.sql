@@ -0,0 +1,24 @@
+-- Scheduler leases: ensure each background job runs on a single backend
+-- instance per tick when multiple API tasks are deployed.
+
+CREATE TABLE IF NOT EXISTS scheduler_leases (
+  job_name             text PRIMARY KEY,
+  holder_id            text NOT NULL,
+  acquired_at          timestamptz NOT NULL DEFAULT now(),
+  heartbeat_at         timestamptz NOT NULL DEFAULT now(),
+  expires_at           timestamptz NOT NULL,
+  last_run_key         text,
+  last_run_started_at  timestamptz,
+  last_run_finished_at timestamptz,
+  last_run_status      text
+                         CHECK (last_run_status IS NULL OR last_run_status IN ('running', 'succeeded', 'failed')),
+  updated_at           timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_scheduler_leases_holder
+  ON scheduler_leases(holder_id, expires_at DESC);
+
+DROP TRIGGER IF EXISTS trg_scheduler_leases_updated_at ON scheduler_leases;
+CREATE TRIGGER trg_scheduler_leases_updated_at
+  BEFORE UPDATE ON scheduler_leases
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
@@ -0,0 +1,30 @@
+-- Scheduler run history: one row per background job invocation (scheduled
+-- or manually triggered) so operators can audit what ran, for whom, and why
+-- it failed.
+
+CREATE TABLE IF NOT EXISTS scheduler_runs (
+  id                    uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  job_name              text NOT NULL,
+  organization_id       uuid REFERENCES organizations(id) ON DELETE CASCADE,
+  trigger_source        text NOT NULL DEFAULT 'schedule'
+                          CHECK (trigger_source IN ('schedule', 'manual')),
+  requested_by_user_id  uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  instance_id           text,
+  status                text NOT NULL DEFAULT 'running'
+                          CHECK (status IN ('running', 'succeeded', 'failed')),
+  result                jsonb NOT NULL DEFAULT '{}'::jsonb,
+  error                 text,
+  started_at            timestamptz NOT NULL DEFAULT now(),
+  finished_at           timestamptz,
+  duration_ms           integer
+);
+
+CREATE INDEX IF NOT EXISTS idx_scheduler_runs_job_started
+  ON scheduler_runs(job_name, started_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_scheduler_runs_status_started
+  ON scheduler_runs(status, started_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_scheduler_runs_org_started
+  ON scheduler_runs(organization_id, started_at DESC)
+  WHERE organization_id IS NOT NULL;
@@ -0,0 +1,24 @@
+-- SIFEN signing keys are encrypted by the backend with an app-held key
+-- (SIFEN_KEY_ENCRYPTION_KEY) and are only ever read by the service role.
+-- Keys stored in plaintext before this change are wiped and their
+-- certificates deactivated; organizations upload them again.
+BEGIN;
+
+DO $$
+BEGIN
+  IF EXISTS (
+    SELECT 1 FROM information_schema.columns
+    WHERE table_name = 'sifen_certificates' AND column_name = 'private_key_pem'
+  ) THEN
+    ALTER TABLE sifen_certificates RENAME COLUMN private_key_pem TO private_key_encrypted;
+  END IF;
+END $$;
+
+UPDATE sifen_certificates
+SET private_key_encrypted = '',
+    is_active = false
+WHERE private_key_encrypted NOT LIKE 'v1:%';
+
+DROP POLICY IF EXISTS sifen_certificates_org_member_all ON sifen_certificates;
+
+COMMIT;
@@ -0,0 +1,49 @@
+-- Length-of-stay and arrival/departure rules per unit. A rule applies to the
+-- dates between starts_on and ends_on (inclusive, open-ended when NULL) that
+-- fall on one of days_of_week (ISO 1 = Monday … 7 = Sunday; empty = every
+-- day). min/max nights are evaluated on the arrival date, closed-to-arrival
+-- on the arrival date and closed-to-departure on the departure date.
+CREATE TABLE IF NOT EXISTS stay_restrictions (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  unit_id uuid NOT NULL REFERENCES units(id) ON DELETE CASCADE,
+  starts_on date,
+  ends_on date,
+  days_of_week smallint[] NOT NULL DEFAULT '{}',
+  min_nights integer CHECK (min_nights IS NULL OR min_nights >= 1),
+  max_nights integer CHECK (max_nights IS NULL OR max_nights >= 1),
+  closed_to_arrival boolean NOT NULL DEFAULT false,
+  closed_to_departure boolean NOT NULL DEFAULT false,
+  -- Stays that exactly fill an orphan gap between two bookings may ignore
+  -- min_nights; dynamic pricing discounts those nights instead.
+  allow_gap_fill boolean NOT NULL DEFAULT true,
+  note text,
+  is_active boolean NOT NULL DEFAULT true,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  CHECK (starts_on IS NULL OR ends_on IS NULL OR ends_on >= starts_on),
+  CHECK (min_nights IS NULL OR max_nights IS NULL OR max_nights >= min_nights),
+  CHECK (days_of_week <@ ARRAY[1, 2, 3, 4, 5, 6, 7]::smallint[])
+);
+
+CREATE INDEX IF NOT EXISTS idx_stay_restrictions_unit
+  ON stay_restrictions(unit_id, starts_on, ends_on)
+  WHERE is_active;
+CREATE INDEX IF NOT EXISTS idx_stay_restrictions_org
+  ON stay_restrictions(organization_id);
+
+DROP TRIGGER IF EXISTS trg_stay_restrictions_updated_at ON stay_restrictions;
+CREATE TRIGGER trg_stay_restrictions_updated_at
+  BEFORE UPDATE ON stay_restrictions
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE stay_restrictions ENABLE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS stay_restrictions_org_member_all ON stay_restrictions;
+CREATE POLICY stay_restrictions_org_member_all ON stay_restrictions FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+-- Discount suggested for orphan gap nights shorter than the minimum stay.
+ALTER TABLE pricing_rule_sets
+  ADD COLUMN IF NOT EXISTS gap_night_discount_pct double precision DEFAULT 15;
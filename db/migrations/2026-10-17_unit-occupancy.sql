@@ -0,0 +1,159 @@
+CREATE EXTENSION IF NOT EXISTS btree_gist;
+
+-- Shared availability ledger: one row per active reservation or calendar
+-- block, maintained by trigger. First-party rows (no integration) are
+-- enforced by the exclusion constraint across both sources; channel-managed
+-- rows are recorded but only surfaced through the conflicts inbox.
+CREATE TABLE IF NOT EXISTS unit_occupancy (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  unit_id uuid NOT NULL REFERENCES units(id) ON DELETE CASCADE,
+  source_table text NOT NULL CHECK (source_table IN ('reservations', 'calendar_blocks')),
+  source_id uuid NOT NULL,
+  source text NOT NULL DEFAULT 'manual',
+  integration_id uuid,
+  period daterange NOT NULL,
+  enforced boolean NOT NULL DEFAULT true,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (source_table, source_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_unit_occupancy_org_unit ON unit_occupancy(organization_id, unit_id);
+CREATE INDEX IF NOT EXISTS idx_unit_occupancy_period_gist ON unit_occupancy USING gist (unit_id, period);
+
+
+CREATE OR REPLACE FUNCTION sync_unit_occupancy()
+RETURNS trigger
+LANGUAGE plpgsql
+AS $$
+DECLARE
+  v_active boolean;
+  v_period daterange;
+BEGIN
+  IF TG_OP = 'DELETE' THEN
+    DELETE FROM unit_occupancy
+    WHERE source_table = TG_TABLE_NAME AND source_id = OLD.id;
+    RETURN OLD;
+  END IF;
+
+  IF TG_TABLE_NAME = 'reservations' THEN
+    v_active := NEW.status IN ('pending', 'confirmed', 'checked_in');
+    v_period := daterange(NEW.check_in_date, NEW.check_out_date, '[)');
+  ELSE
+    v_active := true;
+    v_period := daterange(NEW.starts_on, NEW.ends_on, '[)');
+  END IF;
+
+  IF NOT v_active THEN
+    DELETE FROM unit_occupancy
+    WHERE source_table = TG_TABLE_NAME AND source_id = NEW.id;
+    RETURN NEW;
+  END IF;
+
+  -- Rows grandfathered as unenforced keep that status until their dates move.
+  INSERT INTO unit_occupancy (
+    organization_id, unit_id, source_table, source_id, source, integration_id, period, enforced
+  )
+  VALUES (
+    NEW.organization_id, NEW.unit_id, TG_TABLE_NAME, NEW.id, NEW.source,
+    NEW.integration_id, v_period, NEW.integration_id IS NULL
+  )
+  ON CONFLICT (source_table, source_id) DO UPDATE
+  SET organization_id = EXCLUDED.organization_id,
+      unit_id = EXCLUDED.unit_id,
+      source = EXCLUDED.source,
+      integration_id = EXCLUDED.integration_id,
+      enforced = CASE
+        WHEN unit_occupancy.unit_id = EXCLUDED.unit_id AND unit_occupancy.period = EXCLUDED.period
+          THEN unit_occupancy.enforced AND EXCLUDED.enforced
+        ELSE EXCLUDED.enforced
+      END,
+      period = EXCLUDED.period,
+      updated_at = now();
+  RETURN NEW;
+END;
+$$;
+
+DROP TRIGGER IF EXISTS trg_reservations_unit_occupancy ON reservations;
+CREATE TRIGGER trg_reservations_unit_occupancy
+  AFTER INSERT OR DELETE
+    OR UPDATE OF status, unit_id, check_in_date, check_out_date, integration_id, source
+  ON reservations
+  FOR EACH ROW EXECUTE FUNCTION sync_unit_occupancy();
+
+DROP TRIGGER IF EXISTS trg_calendar_blocks_unit_occupancy ON calendar_blocks;
+CREATE TRIGGER trg_calendar_blocks_unit_occupancy
+  AFTER INSERT OR DELETE
+    OR UPDATE OF unit_id, starts_on, ends_on, integration_id, source
+  ON calendar_blocks
+  FOR EACH ROW EXECUTE FUNCTION sync_unit_occupancy();
+
+-- Backfill from existing active reservations and blocks.
+INSERT INTO unit_occupancy (
+  organization_id, unit_id, source_table, source_id, source, integration_id, period, enforced, created_at
+)
+SELECT organization_id, unit_id, 'reservations', id, source, integration_id,
+       daterange(check_in_date, check_out_date, '[)'), integration_id IS NULL, created_at
+FROM reservations
+WHERE status IN ('pending', 'confirmed', 'checked_in')
+ON CONFLICT (source_table, source_id) DO NOTHING;
+
+INSERT INTO unit_occupancy (
+  organization_id, unit_id, source_table, source_id, source, integration_id, period, enforced, created_at
+)
+SELECT organization_id, unit_id, 'calendar_blocks', id, source, integration_id,
+       daterange(starts_on, ends_on, '[)'), integration_id IS NULL, created_at
+FROM calendar_blocks
+ON CONFLICT (source_table, source_id) DO NOTHING;
+
+-- Existing reservation/block overlaps predate the constraint: keep the oldest
+-- row enforced and leave the rest for the conflicts inbox.
+UPDATE unit_occupancy o
+SET enforced = false
+WHERE o.enforced
+  AND EXISTS (
+    SELECT 1
+    FROM unit_occupancy p
+    WHERE p.unit_id = o.unit_id
+      AND p.enforced
+      AND p.id <> o.id
+      AND p.period && o.period
+      AND (p.created_at, p.id) < (o.created_at, o.id)
+  );
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'unit_occupancy_no_overlap'
+  ) THEN
+    ALTER TABLE unit_occupancy
+      ADD CONSTRAINT unit_occupancy_no_overlap
+      EXCLUDE USING gist (unit_id WITH =, period WITH &&)
+      WHERE (enforced);
+  END IF;
+END $$;
+
+CREATE TABLE IF NOT EXISTS availability_conflict_dismissals (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  first_source_id uuid NOT NULL,
+  second_source_id uuid NOT NULL,
+  note text,
+  dismissed_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CHECK (first_source_id < second_source_id),
+  UNIQUE (organization_id, first_source_id, second_source_id)
+);
+
+ALTER TABLE unit_occupancy ENABLE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS unit_occupancy_org_member_all ON unit_occupancy;
+CREATE POLICY unit_occupancy_org_member_all ON unit_occupancy FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+ALTER TABLE availability_conflict_dismissals ENABLE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS availability_conflict_dismissals_org_member_all ON availability_conflict_dismissals;
+CREATE POLICY availability_conflict_dismissals_org_member_all ON availability_conflict_dismissals FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
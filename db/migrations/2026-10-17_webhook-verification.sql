@@ -0,0 +1,54 @@
+-- Signed inbound webhooks. Stripe, Meta (WhatsApp) and Mercado Pago
+-- deliveries are verified with each provider's HMAC scheme; external
+-- systems sign calls to /integrations/webhooks/{provider} with a
+-- per-organization secret and no longer need a session. Verified
+-- deliveries are recorded by event id so replays are not processed twice.
+
+CREATE TABLE IF NOT EXISTS webhook_signing_secrets (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  provider text NOT NULL,
+  secret text NOT NULL,
+  previous_secret text,
+  previous_secret_expires_at timestamptz,
+  tolerance_seconds integer NOT NULL DEFAULT 300
+    CHECK (tolerance_seconds BETWEEN 30 AND 3600),
+  is_active boolean NOT NULL DEFAULT true,
+  last_used_at timestamptz,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (organization_id, provider)
+);
+
+CREATE TABLE IF NOT EXISTS webhook_receipts (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  provider text NOT NULL,
+  event_id text NOT NULL,
+  organization_id uuid REFERENCES organizations(id) ON DELETE CASCADE,
+  received_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (provider, event_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_receipts_received_at
+  ON webhook_receipts(received_at);
+
+DROP TRIGGER IF EXISTS trg_webhook_signing_secrets_updated_at ON webhook_signing_secrets;
+CREATE TRIGGER trg_webhook_signing_secrets_updated_at
+  BEFORE UPDATE ON webhook_signing_secrets
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE webhook_signing_secrets ENABLE ROW LEVEL SECURITY;
+ALTER TABLE webhook_receipts ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS webhook_signing_secrets_org_member_all ON webhook_signing_secrets;
+CREATE POLICY webhook_signing_secrets_org_member_all
+  ON webhook_signing_secrets FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+DROP POLICY IF EXISTS webhook_receipts_org_member_all ON webhook_receipts;
+CREATE POLICY webhook_receipts_org_member_all
+  ON webhook_receipts FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
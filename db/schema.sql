@@ -169,7 +169,8 @@ CREATE TYPE fee_line_type AS ENUM (
   'guarantee_option_fee',
   'admin_fee',
   'tax_iva',
-  'other'
+  'other',
+  'late_fee'
 );
 
 CREATE TYPE application_status AS ENUM (
@@ -251,8 +252,11 @@ CREATE TABLE organizations (
   name text NOT NULL,
   legal_name text,
   ruc text,
+  -- SET timbrado printed on the organization's invoices.
+  timbrado_number text,
+  timbrado_valid_until date,
   profile_type organization_profile_type NOT NULL DEFAULT 'management_company',
-  default_currency char(3) NOT NULL DEFAULT 'PYG' CHECK (default_currency IN ('PYG', 'USD')),
+  default_currency char(3) NOT NULL DEFAULT 'PYG' CHECK (default_currency ~ '^[A-Z]{3}$'),
   timezone text NOT NULL DEFAULT 'America/Asuncion',
   country_code char(2) NOT NULL DEFAULT 'PY',
   owner_user_id uuid NOT NULL REFERENCES app_users(id),
@@ -266,6 +270,7 @@ CREATE TABLE organizations (
   auto_pricing_max_delta_pct numeric(5,2) NOT NULL DEFAULT 10.0,
   brand_color text DEFAULT '#2563eb',
   logo_url text,
+  document_language text NOT NULL DEFAULT 'es' CHECK (document_language IN ('es', 'en')),
   created_at timestamptz NOT NULL DEFAULT now(),
   updated_at timestamptz NOT NULL DEFAULT now()
 );
@@ -274,10 +279,27 @@ CREATE INDEX idx_org_owner_user_id ON organizations(owner_user_id);
 CREATE INDEX idx_organizations_slug
   ON organizations (org_slug) WHERE org_slug IS NOT NULL;
 
+-- Custom roles list `resource.action` / `resource.*` permissions explicitly
+-- and replace the built-in role's defaults for members who hold them.
+-- property_group_ids limits the role to those groups (empty = all).
+CREATE TABLE organization_roles (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  name text NOT NULL,
+  description text,
+  permissions text[] NOT NULL DEFAULT '{}',
+  property_group_ids uuid[] NOT NULL DEFAULT '{}',
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (organization_id, name)
+);
+
 CREATE TABLE organization_members (
   organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
   user_id uuid NOT NULL REFERENCES app_users(id) ON DELETE CASCADE,
   role member_role NOT NULL DEFAULT 'operator',
+  custom_role_id uuid REFERENCES organization_roles(id),
   is_primary boolean NOT NULL DEFAULT false,
   joined_at timestamptz NOT NULL DEFAULT now(),
   created_at timestamptz NOT NULL DEFAULT now(),
@@ -360,6 +382,21 @@ CREATE TABLE properties (
   shared_wifi_password text,
   asset_owner_organization_id uuid REFERENCES organizations(id) ON DELETE SET NULL,
   asset_owner_name text,
+  asset_owner_ruc text,
+  -- Account the owner's payouts are transferred to.
+  asset_owner_bank_name text,
+  asset_owner_bank_account_number text,
+  asset_owner_bank_account_type text
+    CHECK (asset_owner_bank_account_type IS NULL
+           OR asset_owner_bank_account_type IN ('checking', 'savings')),
+  owner_reporting_currency char(3)
+    CHECK (owner_reporting_currency IS NULL OR owner_reporting_currency ~ '^[A-Z]{3}$'),
+  -- Operating reserve held back from the owner's statements, in the
+  -- reporting currency, and the most a single statement may withhold.
+  reserve_target_amount numeric(12, 2) NOT NULL DEFAULT 0
+    CHECK (reserve_target_amount >= 0),
+  reserve_top_up_cap numeric(12, 2)
+    CHECK (reserve_top_up_cap IS NULL OR reserve_top_up_cap >= 0),
   created_at timestamptz NOT NULL DEFAULT now(),
   updated_at timestamptz NOT NULL DEFAULT now(),
   UNIQUE (organization_id, code)
@@ -372,6 +409,34 @@ CREATE INDEX idx_properties_org_property_type
 CREATE INDEX idx_properties_owner_org
   ON properties(asset_owner_organization_id);
 
+CREATE TABLE property_groups (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  name text NOT NULL,
+  description text,
+  property_ids uuid[] NOT NULL DEFAULT '{}',
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (organization_id, name)
+);
+
+-- Members assigned to a property group only see its properties, on top of
+-- any groups their custom role is limited to.
+CREATE TABLE property_group_members (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  group_id uuid NOT NULL REFERENCES property_groups(id) ON DELETE CASCADE,
+  user_id uuid NOT NULL,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (group_id, user_id),
+  FOREIGN KEY (organization_id, user_id)
+    REFERENCES organization_members(organization_id, user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_property_group_members_org_user
+  ON property_group_members(organization_id, user_id);
+
 CREATE TABLE units (
   id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
   organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
@@ -539,6 +604,14 @@ CREATE TABLE integrations (
   public_slug text,
   ical_import_url text,
   ical_export_token text NOT NULL UNIQUE DEFAULT encode(gen_random_bytes(18), 'hex'),
+  ical_etag text,
+  ical_last_modified text,
+  ical_content_hash text,
+  last_ical_sync_at timestamptz,
+  ical_sync_error text,
+  metadata jsonb NOT NULL DEFAULT '{}'::jsonb,
+  last_ari_push_at timestamptz,
+  ari_push_error text,
   is_active boolean NOT NULL DEFAULT true,
   created_at timestamptz NOT NULL DEFAULT now(),
   updated_at timestamptz NOT NULL DEFAULT now(),
@@ -603,7 +676,7 @@ CREATE TABLE reservations (
   children smallint NOT NULL DEFAULT 0 CHECK (children >= 0),
   infants smallint NOT NULL DEFAULT 0 CHECK (infants >= 0),
   pets smallint NOT NULL DEFAULT 0 CHECK (pets >= 0),
-  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency IN ('PYG', 'USD')),
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
   nightly_rate numeric(12, 2) NOT NULL DEFAULT 0 CHECK (nightly_rate >= 0),
   cleaning_fee numeric(12, 2) NOT NULL DEFAULT 0 CHECK (cleaning_fee >= 0),
   tax_amount numeric(12, 2) NOT NULL DEFAULT 0 CHECK (tax_amount >= 0),
@@ -654,6 +727,8 @@ CREATE TABLE calendar_blocks (
   reason text,
   recurrence_rule text,
   recurrence_end_date date,
+  integration_id uuid REFERENCES integrations(id) ON DELETE CASCADE,
+  external_event_id text,
   created_by_user_id uuid REFERENCES app_users(id),
   created_at timestamptz NOT NULL DEFAULT now(),
   updated_at timestamptz NOT NULL DEFAULT now(),
@@ -664,10 +739,170 @@ CREATE INDEX idx_calendar_blocks_org_id ON calendar_blocks(organization_id);
 CREATE INDEX idx_calendar_blocks_unit_dates ON calendar_blocks(unit_id, starts_on, ends_on);
 CREATE INDEX idx_calendar_blocks_period_gist ON calendar_blocks USING gist (unit_id, period);
 
+CREATE UNIQUE INDEX idx_calendar_blocks_integration_external
+  ON calendar_blocks(integration_id, external_event_id)
+  WHERE external_event_id IS NOT NULL AND integration_id IS NOT NULL;
+
 ALTER TABLE calendar_blocks
   ADD CONSTRAINT calendar_blocks_no_overlap
   EXCLUDE USING gist (unit_id WITH =, period WITH &&);
 
+-- Shared availability ledger: one row per active reservation or calendar
+-- block, maintained by trigger. First-party rows (no integration) are
+-- enforced by the exclusion constraint across both sources; channel-managed
+-- rows are recorded but only surfaced through the conflicts inbox.
+CREATE TABLE unit_occupancy (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  unit_id uuid NOT NULL REFERENCES units(id) ON DELETE CASCADE,
+  source_table text NOT NULL CHECK (source_table IN ('reservations', 'calendar_blocks')),
+  source_id uuid NOT NULL,
+  source text NOT NULL DEFAULT 'manual',
+  integration_id uuid,
+  period daterange NOT NULL,
+  enforced boolean NOT NULL DEFAULT true,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (source_table, source_id)
+);
+
+CREATE INDEX idx_unit_occupancy_org_unit ON unit_occupancy(organization_id, unit_id);
+CREATE INDEX idx_unit_occupancy_period_gist ON unit_occupancy USING gist (unit_id, period);
+
+ALTER TABLE unit_occupancy
+  ADD CONSTRAINT unit_occupancy_no_overlap
+  EXCLUDE USING gist (unit_id WITH =, period WITH &&)
+  WHERE (enforced);
+
+CREATE OR REPLACE FUNCTION sync_unit_occupancy()
+RETURNS trigger
+LANGUAGE plpgsql
+AS $$
+DECLARE
+  v_active boolean;
+  v_period daterange;
+BEGIN
+  IF TG_OP = 'DELETE' THEN
+    DELETE FROM unit_occupancy
+    WHERE source_table = TG_TABLE_NAME AND source_id = OLD.id;
+    RETURN OLD;
+  END IF;
+
+  IF TG_TABLE_NAME = 'reservations' THEN
+    v_active := NEW.status IN ('pending', 'confirmed', 'checked_in');
+    v_period := daterange(NEW.check_in_date, NEW.check_out_date, '[)');
+  ELSE
+    v_active := true;
+    v_period := daterange(NEW.starts_on, NEW.ends_on, '[)');
+  END IF;
+
+  IF NOT v_active THEN
+    DELETE FROM unit_occupancy
+    WHERE source_table = TG_TABLE_NAME AND source_id = NEW.id;
+    RETURN NEW;
+  END IF;
+
+  -- Rows grandfathered as unenforced keep that status until their dates move.
+  INSERT INTO unit_occupancy (
+    organization_id, unit_id, source_table, source_id, source, integration_id, period, enforced
+  )
+  VALUES (
+    NEW.organization_id, NEW.unit_id, TG_TABLE_NAME, NEW.id, NEW.source,
+    NEW.integration_id, v_period, NEW.integration_id IS NULL
+  )
+  ON CONFLICT (source_table, source_id) DO UPDATE
+  SET organization_id = EXCLUDED.organization_id,
+      unit_id = EXCLUDED.unit_id,
+      source = EXCLUDED.source,
+      integration_id = EXCLUDED.integration_id,
+      enforced = CASE
+        WHEN unit_occupancy.unit_id = EXCLUDED.unit_id AND unit_occupancy.period = EXCLUDED.period
+          THEN unit_occupancy.enforced AND EXCLUDED.enforced
+        ELSE EXCLUDED.enforced
+      END,
+      period = EXCLUDED.period,
+      updated_at = now();
+  RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER trg_reservations_unit_occupancy
+  AFTER INSERT OR DELETE
+    OR UPDATE OF status, unit_id, check_in_date, check_out_date, integration_id, source
+  ON reservations
+  FOR EACH ROW EXECUTE FUNCTION sync_unit_occupancy();
+
+CREATE TRIGGER trg_calendar_blocks_unit_occupancy
+  AFTER INSERT OR DELETE
+    OR UPDATE OF unit_id, starts_on, ends_on, integration_id, source
+  ON calendar_blocks
+  FOR EACH ROW EXECUTE FUNCTION sync_unit_occupancy();
+
+CREATE TABLE availability_conflict_dismissals (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  first_source_id uuid NOT NULL,
+  second_source_id uuid NOT NULL,
+  note text,
+  dismissed_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CHECK (first_source_id < second_source_id),
+  UNIQUE (organization_id, first_source_id, second_source_id)
+);
+
+ALTER TABLE unit_occupancy ENABLE ROW LEVEL SECURITY;
+CREATE POLICY unit_occupancy_org_member_all ON unit_occupancy FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+ALTER TABLE availability_conflict_dismissals ENABLE ROW LEVEL SECURITY;
+CREATE POLICY availability_conflict_dismissals_org_member_all ON availability_conflict_dismissals FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+-- Length-of-stay and arrival/departure rules per unit. A rule applies to the
+-- dates between starts_on and ends_on (inclusive, open-ended when NULL) that
+-- fall on one of days_of_week (ISO 1 = Monday … 7 = Sunday; empty = every
+-- day). min/max nights are evaluated on the arrival date, closed-to-arrival
+-- on the arrival date and closed-to-departure on the departure date.
+CREATE TABLE stay_restrictions (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  unit_id uuid NOT NULL REFERENCES units(id) ON DELETE CASCADE,
+  starts_on date,
+  ends_on date,
+  days_of_week smallint[] NOT NULL DEFAULT '{}',
+  min_nights integer CHECK (min_nights IS NULL OR min_nights >= 1),
+  max_nights integer CHECK (max_nights IS NULL OR max_nights >= 1),
+  closed_to_arrival boolean NOT NULL DEFAULT false,
+  closed_to_departure boolean NOT NULL DEFAULT false,
+  -- Stays that exactly fill an orphan gap between two bookings may ignore
+  -- min_nights; dynamic pricing discounts those nights instead.
+  allow_gap_fill boolean NOT NULL DEFAULT true,
+  note text,
+  is_active boolean NOT NULL DEFAULT true,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  CHECK (starts_on IS NULL OR ends_on IS NULL OR ends_on >= starts_on),
+  CHECK (min_nights IS NULL OR max_nights IS NULL OR max_nights >= min_nights),
+  CHECK (days_of_week <@ ARRAY[1, 2, 3, 4, 5, 6, 7]::smallint[])
+);
+
+CREATE INDEX idx_stay_restrictions_unit
+  ON stay_restrictions(unit_id, starts_on, ends_on)
+  WHERE is_active;
+CREATE INDEX idx_stay_restrictions_org ON stay_restrictions(organization_id);
+
+CREATE TRIGGER trg_stay_restrictions_updated_at
+  BEFORE UPDATE ON stay_restrictions
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TABLE stay_restrictions ENABLE ROW LEVEL SECURITY;
+CREATE POLICY stay_restrictions_org_member_all ON stay_restrictions FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
 -- ---------- Task operations ----------
 
 CREATE TABLE tasks (
@@ -716,6 +951,69 @@ CREATE INDEX idx_task_items_task_id ON task_items(task_id);
 
 -- ---------- Finance ----------
 
+-- Daily exchange rates for any currency pair. One row per (base, quote, day):
+-- 1 unit of base_currency buys `rate` units of quote_currency on rate_date.
+-- Rows are filled on demand by the FX service and backfilled daily for the
+-- tracked currencies, so historical conversions never hit the network twice.
+CREATE TABLE fx_rates (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  base_currency char(3) NOT NULL CHECK (base_currency ~ '^[A-Z]{3}$'),
+  quote_currency char(3) NOT NULL CHECK (quote_currency ~ '^[A-Z]{3}$'),
+  rate_date date NOT NULL,
+  rate numeric(24, 10) NOT NULL CHECK (rate > 0),
+  source text NOT NULL,
+  fetched_at timestamptz NOT NULL DEFAULT now(),
+  CHECK (base_currency <> quote_currency),
+  UNIQUE (base_currency, quote_currency, rate_date)
+);
+
+CREATE INDEX idx_fx_rates_pair_date
+  ON fx_rates(base_currency, quote_currency, rate_date DESC);
+
+-- Reference data shared by every organization: readable by anyone, written
+-- only by the backend's service role.
+ALTER TABLE fx_rates ENABLE ROW LEVEL SECURITY;
+CREATE POLICY fx_rates_public_read ON fx_rates FOR SELECT USING (true);
+
+-- Rate for base→quote effective on a day: the latest stored rate on or
+-- before that day, taken directly, inverted, or crossed through USD.
+-- Returns NULL when no usable rate is stored.
+CREATE OR REPLACE FUNCTION fx_rate_on(p_base text, p_quote text, p_on date)
+RETURNS numeric
+LANGUAGE plpgsql
+STABLE
+AS $$
+DECLARE
+  v_base text := upper(trim(p_base));
+  v_quote text := upper(trim(p_quote));
+  v_rate numeric;
+  v_leg numeric;
+BEGIN
+  IF v_base = v_quote THEN
+    RETURN 1;
+  END IF;
+
+  SELECT CASE WHEN f.base_currency = v_base THEN f.rate ELSE 1 / f.rate END
+    INTO v_rate
+    FROM fx_rates f
+   WHERE ((f.base_currency = v_base AND f.quote_currency = v_quote)
+       OR (f.base_currency = v_quote AND f.quote_currency = v_base))
+     AND f.rate_date <= p_on
+   ORDER BY f.rate_date DESC, (f.base_currency = v_base) DESC
+   LIMIT 1;
+  IF v_rate IS NOT NULL OR v_base = 'USD' OR v_quote = 'USD' THEN
+    RETURN v_rate;
+  END IF;
+
+  v_leg := fx_rate_on(v_base, 'USD', p_on);
+  IF v_leg IS NULL THEN
+    RETURN NULL;
+  END IF;
+  v_rate := fx_rate_on('USD', v_quote, p_on);
+  RETURN v_leg * v_rate;
+END;
+$$;
+
 CREATE TABLE expenses (
   id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
   organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
@@ -726,11 +1024,12 @@ CREATE TABLE expenses (
   vendor_name text,
   expense_date date NOT NULL,
   amount numeric(12, 2) NOT NULL CHECK (amount >= 0),
-  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency IN ('PYG', 'USD')),
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
   fx_rate_to_pyg numeric(14, 6),
   payment_method payment_method NOT NULL DEFAULT 'bank_transfer',
   invoice_number text,
   invoice_ruc text,
+  invoice_timbrado text,
   receipt_url text,
   notes text,
   approval_status text NOT NULL DEFAULT 'pending',
@@ -754,15 +1053,22 @@ CREATE TABLE owner_statements (
   unit_id uuid REFERENCES units(id) ON DELETE SET NULL,
   period_start date NOT NULL,
   period_end date NOT NULL,
-  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency IN ('PYG', 'USD')),
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
   gross_revenue numeric(12, 2) NOT NULL DEFAULT 0,
   lease_collections numeric(12, 2) NOT NULL DEFAULT 0,
   service_fees numeric(12, 2) NOT NULL DEFAULT 0,
   collection_fees numeric(12, 2) NOT NULL DEFAULT 0,
+  management_fees numeric(12, 2) NOT NULL DEFAULT 0,
   platform_fees numeric(12, 2) NOT NULL DEFAULT 0,
   taxes_collected numeric(12, 2) NOT NULL DEFAULT 0,
   operating_expenses numeric(12, 2) NOT NULL DEFAULT 0,
   net_payout numeric(12, 2) NOT NULL DEFAULT 0,
+  -- Owner balance: the deficit brought in from the previous statement, what
+  -- the reserve covered or was topped up with, and the deficit carried out.
+  previous_balance numeric(12, 2) NOT NULL DEFAULT 0,
+  reserve_draw numeric(12, 2) NOT NULL DEFAULT 0,
+  reserve_top_up numeric(12, 2) NOT NULL DEFAULT 0,
+  balance_carried_forward numeric(12, 2) NOT NULL DEFAULT 0,
   status statement_status NOT NULL DEFAULT 'draft',
   approval_status text NOT NULL DEFAULT 'none',
   approval_requested_at timestamptz,
@@ -949,6 +1255,7 @@ CREATE TABLE leases (
   tenant_full_name text NOT NULL,
   tenant_email citext,
   tenant_phone_e164 text,
+  tenant_ruc text,
   lease_status lease_status NOT NULL DEFAULT 'draft',
   renewal_status text
     CHECK (renewal_status IS NULL OR renewal_status IN (
@@ -960,7 +1267,7 @@ CREATE TABLE leases (
   renewal_notes text,
   starts_on date NOT NULL,
   ends_on date,
-  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency IN ('PYG', 'USD')),
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
   monthly_rent numeric(12, 2) NOT NULL DEFAULT 0 CHECK (monthly_rent >= 0),
   service_fee_flat numeric(12, 2) NOT NULL DEFAULT 0 CHECK (service_fee_flat >= 0),
   security_deposit numeric(12, 2) NOT NULL DEFAULT 0 CHECK (security_deposit >= 0),
@@ -1005,6 +1312,31 @@ CREATE INDEX idx_leases_org_renewal_ends
   ON leases(organization_id, renewal_status, ends_on)
   WHERE renewal_status IS NOT NULL;
 
+-- Late fees: per-lease policy, or the organization default when lease_id is NULL.
+CREATE TABLE late_fee_policies (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  lease_id uuid REFERENCES leases(id) ON DELETE CASCADE,
+  fee_type text NOT NULL CHECK (fee_type IN ('fixed', 'percentage', 'daily_interest')),
+  amount numeric(12, 4) NOT NULL CHECK (amount > 0),
+  grace_days integer NOT NULL DEFAULT 0 CHECK (grace_days BETWEEN 0 AND 90),
+  max_fee_amount numeric(12, 2) CHECK (max_fee_amount IS NULL OR max_fee_amount > 0),
+  max_fee_percent numeric(6, 2) CHECK (max_fee_percent IS NULL OR max_fee_percent > 0),
+  is_active boolean NOT NULL DEFAULT true,
+  source text NOT NULL DEFAULT 'manual' CHECK (source IN ('manual', 'lease_abstraction')),
+  notes text,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX uq_late_fee_policies_lease
+  ON late_fee_policies(lease_id)
+  WHERE lease_id IS NOT NULL;
+CREATE UNIQUE INDEX uq_late_fee_policies_org_default
+  ON late_fee_policies(organization_id)
+  WHERE lease_id IS NULL;
+
 CREATE TABLE lease_charges (
   id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
   organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
@@ -1013,12 +1345,27 @@ CREATE TABLE lease_charges (
   charge_type fee_line_type NOT NULL,
   description text,
   amount numeric(12, 2) NOT NULL DEFAULT 0 CHECK (amount >= 0),
-  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency IN ('PYG', 'USD')),
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
   status collection_status NOT NULL DEFAULT 'scheduled',
+  -- Late-fee charges: the overdue collection they penalize and the policy
+  -- that priced them. source_collection_id's FK is added after collection_records.
+  source_collection_id uuid,
+  late_fee_policy_id uuid REFERENCES late_fee_policies(id) ON DELETE SET NULL,
+  accrued_through date,
+  original_amount numeric(12, 2),
+  adjusted_at timestamptz,
+  adjusted_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  adjustment_reason text,
+  waived_at timestamptz,
+  waived_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  waive_reason text,
   created_at timestamptz NOT NULL DEFAULT now(),
   updated_at timestamptz NOT NULL DEFAULT now()
 );
 
+CREATE UNIQUE INDEX uq_lease_charges_source_collection
+  ON lease_charges(source_collection_id)
+  WHERE source_collection_id IS NOT NULL;
 CREATE INDEX idx_lease_charges_org_date
   ON lease_charges(organization_id, charge_date, status);
 CREATE INDEX idx_lease_charges_lease
@@ -1031,13 +1378,19 @@ CREATE TABLE collection_records (
   lease_charge_id uuid REFERENCES lease_charges(id) ON DELETE SET NULL,
   due_date date NOT NULL,
   amount numeric(12, 2) NOT NULL DEFAULT 0 CHECK (amount >= 0),
-  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency IN ('PYG', 'USD')),
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
   status collection_status NOT NULL DEFAULT 'scheduled',
   payment_method payment_method,
   payment_reference text,
   scheduled_at timestamptz NOT NULL DEFAULT now(),
   paid_at timestamptz,
   notes text,
+  receipt_pdf_url text,
+  -- CDC and QR link of the collection's SIFEN electronic invoice.
+  sifen_cdc text,
+  sifen_qr_url text,
+  -- Running total of payment_allocations against this record.
+  amount_paid numeric(12, 2) NOT NULL DEFAULT 0 CHECK (amount_paid >= 0),
   created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
   created_at timestamptz NOT NULL DEFAULT now(),
   updated_at timestamptz NOT NULL DEFAULT now()
@@ -1048,6 +1401,406 @@ CREATE INDEX idx_collection_records_org_status_due
 CREATE INDEX idx_collection_records_lease
   ON collection_records(lease_id, due_date);
 
+ALTER TABLE lease_charges
+  ADD CONSTRAINT lease_charges_source_collection_id_fkey
+  FOREIGN KEY (source_collection_id) REFERENCES collection_records(id) ON DELETE CASCADE;
+
+-- Payments received against a lease, allocated oldest-first across its
+-- collection records. unallocated_amount is credit carried forward.
+CREATE TABLE lease_payments (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  lease_id uuid NOT NULL REFERENCES leases(id) ON DELETE CASCADE,
+  source text NOT NULL DEFAULT 'manual'
+    CHECK (source IN ('manual', 'stripe', 'mercado_pago', 'bank_import')),
+  payment_method payment_method,
+  amount numeric(12, 2) NOT NULL CHECK (amount > 0),
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
+  unallocated_amount numeric(12, 2) NOT NULL DEFAULT 0 CHECK (unallocated_amount >= 0),
+  received_at timestamptz NOT NULL DEFAULT now(),
+  reference text,
+  external_id text,
+  bank_transaction_id uuid,
+  notes text,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX uq_lease_payments_external_id
+  ON lease_payments(organization_id, external_id)
+  WHERE external_id IS NOT NULL;
+CREATE INDEX idx_lease_payments_lease
+  ON lease_payments(lease_id, received_at);
+CREATE INDEX idx_lease_payments_credit
+  ON lease_payments(lease_id)
+  WHERE unallocated_amount > 0;
+
+CREATE TABLE payment_allocations (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  payment_id uuid NOT NULL REFERENCES lease_payments(id) ON DELETE CASCADE,
+  collection_record_id uuid NOT NULL REFERENCES collection_records(id) ON DELETE CASCADE,
+  amount numeric(12, 2) NOT NULL CHECK (amount > 0),
+  created_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_payment_allocations_payment
+  ON payment_allocations(payment_id);
+CREATE INDEX idx_payment_allocations_collection
+  ON payment_allocations(collection_record_id);
+
+-- Double-entry general ledger. The chart of accounts is seeded per
+-- organization by the backend; journal entries are immutable and corrected
+-- by reversal. source_key makes automatic postings idempotent.
+CREATE TABLE gl_accounts (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  code text NOT NULL,
+  name text NOT NULL,
+  account_type text NOT NULL
+    CHECK (account_type IN ('asset', 'liability', 'equity', 'revenue', 'expense')),
+  system_key text,
+  is_active boolean NOT NULL DEFAULT true,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (organization_id, code)
+);
+
+CREATE UNIQUE INDEX uq_gl_accounts_system_key
+  ON gl_accounts(organization_id, system_key)
+  WHERE system_key IS NOT NULL;
+
+CREATE TABLE gl_journal_entries (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  entry_date date NOT NULL,
+  description text NOT NULL,
+  source_type text NOT NULL DEFAULT 'manual',
+  source_id uuid,
+  source_key text,
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
+  reversal_of uuid REFERENCES gl_journal_entries(id) ON DELETE SET NULL,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX uq_gl_journal_entries_source_key
+  ON gl_journal_entries(organization_id, source_key)
+  WHERE source_key IS NOT NULL;
+CREATE UNIQUE INDEX uq_gl_journal_entries_reversal_of
+  ON gl_journal_entries(reversal_of)
+  WHERE reversal_of IS NOT NULL;
+CREATE INDEX idx_gl_journal_entries_org_date
+  ON gl_journal_entries(organization_id, entry_date);
+CREATE INDEX idx_gl_journal_entries_source
+  ON gl_journal_entries(source_type, source_id);
+
+CREATE TABLE gl_journal_lines (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  entry_id uuid NOT NULL REFERENCES gl_journal_entries(id) ON DELETE CASCADE,
+  account_id uuid NOT NULL REFERENCES gl_accounts(id) ON DELETE RESTRICT,
+  debit numeric(14, 2) NOT NULL DEFAULT 0 CHECK (debit >= 0),
+  credit numeric(14, 2) NOT NULL DEFAULT 0 CHECK (credit >= 0),
+  property_id uuid REFERENCES properties(id) ON DELETE SET NULL,
+  lease_id uuid REFERENCES leases(id) ON DELETE SET NULL,
+  memo text,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CHECK ((debit > 0) <> (credit > 0))
+);
+
+CREATE INDEX idx_gl_journal_lines_entry ON gl_journal_lines(entry_id);
+CREATE INDEX idx_gl_journal_lines_account ON gl_journal_lines(account_id);
+CREATE INDEX idx_gl_journal_lines_property
+  ON gl_journal_lines(property_id)
+  WHERE property_id IS NOT NULL;
+
+-- Debits must equal credits per entry, checked at commit.
+CREATE OR REPLACE FUNCTION gl_assert_entry_balanced()
+RETURNS trigger
+LANGUAGE plpgsql
+AS $$
+DECLARE
+  v_entry_id uuid;
+  v_debit numeric;
+  v_credit numeric;
+BEGIN
+  v_entry_id := CASE WHEN TG_OP = 'DELETE' THEN OLD.entry_id ELSE NEW.entry_id END;
+
+  SELECT COALESCE(SUM(debit), 0), COALESCE(SUM(credit), 0)
+    INTO v_debit, v_credit
+    FROM gl_journal_lines
+   WHERE entry_id = v_entry_id;
+
+  IF v_debit <> v_credit THEN
+    RAISE EXCEPTION 'Journal entry % is unbalanced (debits %, credits %)',
+      v_entry_id, v_debit, v_credit;
+  END IF;
+  RETURN NULL;
+END;
+$$;
+
+CREATE CONSTRAINT TRIGGER trg_gl_journal_lines_balanced
+  AFTER INSERT OR UPDATE OR DELETE ON gl_journal_lines
+  DEFERRABLE INITIALLY DEFERRED
+  FOR EACH ROW EXECUTE FUNCTION gl_assert_entry_balanced();
+
+-- SIFEN (e-Kuatia) electronic invoicing settings per organization.
+CREATE TABLE sifen_settings (
+  organization_id uuid PRIMARY KEY REFERENCES organizations(id) ON DELETE CASCADE,
+  -- 'mock' never leaves the server; 'test' and 'production' reach SET.
+  environment text NOT NULL DEFAULT 'mock'
+    CHECK (environment IN ('mock', 'test', 'production')),
+  establishment text NOT NULL DEFAULT '001' CHECK (establishment ~ '^[0-9]{3}$'),
+  expedition_point text NOT NULL DEFAULT '001' CHECK (expedition_point ~ '^[0-9]{3}$'),
+  next_document_number integer NOT NULL DEFAULT 1
+    CHECK (next_document_number BETWEEN 1 AND 9999999),
+  timbrado_valid_from date,
+  -- 1 = persona física, 2 = persona jurídica.
+  taxpayer_type smallint NOT NULL DEFAULT 2 CHECK (taxpayer_type IN (1, 2)),
+  fiscal_address text,
+  house_number text NOT NULL DEFAULT '0',
+  department_code smallint,
+  department_name text,
+  city_code integer,
+  city_name text,
+  phone text,
+  email text,
+  activity_code text,
+  activity_description text,
+  csc_id text,
+  csc text,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+-- Signing certificates. The private key is encrypted by the backend
+-- (SIFEN_KEY_ENCRYPTION_KEY) and never returned by the API; the table has no
+-- RLS policy, so only the backend's service role can read it.
+CREATE TABLE sifen_certificates (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  subject text NOT NULL,
+  issuer text,
+  serial_number text,
+  valid_from timestamptz,
+  valid_until timestamptz,
+  certificate_pem text NOT NULL,
+  private_key_encrypted text NOT NULL,
+  is_active boolean NOT NULL DEFAULT true,
+  uploaded_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX uq_sifen_certificates_active
+  ON sifen_certificates(organization_id)
+  WHERE is_active;
+
+-- One electronic invoice per collection or owner statement. A rejected
+-- document is rebuilt in place and keeps its number.
+CREATE TABLE electronic_documents (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  collection_record_id uuid REFERENCES collection_records(id) ON DELETE SET NULL,
+  owner_statement_id uuid REFERENCES owner_statements(id) ON DELETE SET NULL,
+  -- iTiDE: 1 = factura electrónica.
+  document_type smallint NOT NULL DEFAULT 1,
+  establishment text NOT NULL,
+  expedition_point text NOT NULL,
+  document_number integer NOT NULL,
+  cdc text NOT NULL CHECK (cdc ~ '^[0-9]{44}$'),
+  issued_at timestamp NOT NULL,
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
+  total_amount numeric(14, 2) NOT NULL DEFAULT 0,
+  total_iva numeric(14, 2) NOT NULL DEFAULT 0,
+  receiver_name text NOT NULL,
+  receiver_tax_id text,
+  signed_xml text NOT NULL,
+  digest_value text NOT NULL,
+  qr_url text NOT NULL,
+  environment text NOT NULL,
+  status text NOT NULL DEFAULT 'signed'
+    CHECK (status IN ('signed', 'approved', 'rejected')),
+  submitted_at timestamptz,
+  response_code text,
+  response_message text,
+  protocol_number text,
+  response jsonb,
+  certificate_id uuid REFERENCES sifen_certificates(id) ON DELETE SET NULL,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (cdc),
+  UNIQUE (organization_id, establishment, expedition_point, document_number)
+);
+
+CREATE UNIQUE INDEX uq_electronic_documents_collection
+  ON electronic_documents(collection_record_id)
+  WHERE collection_record_id IS NOT NULL;
+CREATE UNIQUE INDEX uq_electronic_documents_statement
+  ON electronic_documents(owner_statement_id)
+  WHERE owner_statement_id IS NOT NULL;
+CREATE INDEX idx_electronic_documents_org_status
+  ON electronic_documents(organization_id, status, issued_at);
+
+-- Fixed-width bulk-transfer layouts of the banks an organization pays
+-- owners from. Each record is a JSON array of field specs.
+CREATE TABLE payout_bank_layouts (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  name text NOT NULL,
+  bank_name text,
+  file_extension text NOT NULL DEFAULT 'txt' CHECK (file_extension ~ '^[a-z0-9]{1,5}$'),
+  line_ending text NOT NULL DEFAULT 'crlf' CHECK (line_ending IN ('crlf', 'lf')),
+  header_fields jsonb NOT NULL DEFAULT '[]'::jsonb
+    CHECK (jsonb_typeof(header_fields) = 'array'),
+  detail_fields jsonb NOT NULL CHECK (jsonb_typeof(detail_fields) = 'array'),
+  trailer_fields jsonb NOT NULL DEFAULT '[]'::jsonb
+    CHECK (jsonb_typeof(trailer_fields) = 'array'),
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (organization_id, name)
+);
+
+-- A batch of owner payouts sent to the bank together.
+CREATE TABLE owner_payout_batches (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  reference text NOT NULL,
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
+  status text NOT NULL DEFAULT 'scheduled'
+    CHECK (status IN ('scheduled', 'sent', 'confirmed', 'failed')),
+  scheduled_for date NOT NULL DEFAULT current_date,
+  transfer_count integer NOT NULL DEFAULT 0,
+  total_amount numeric(14, 2) NOT NULL DEFAULT 0,
+  total_management_fees numeric(14, 2) NOT NULL DEFAULT 0,
+  total_reserves numeric(14, 2) NOT NULL DEFAULT 0,
+  notes text,
+  sent_at timestamptz,
+  confirmed_at timestamptz,
+  failed_at timestamptz,
+  failure_reason text,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (organization_id, reference)
+);
+
+CREATE INDEX idx_owner_payout_batches_org_status
+  ON owner_payout_batches(organization_id, status, scheduled_for);
+
+-- One statement paid by a batch. Statements sharing an owner account are
+-- one transfer (transfer_sequence) in the bank file.
+CREATE TABLE owner_payout_items (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  batch_id uuid NOT NULL REFERENCES owner_payout_batches(id) ON DELETE CASCADE,
+  owner_statement_id uuid NOT NULL REFERENCES owner_statements(id) ON DELETE CASCADE,
+  transfer_sequence integer NOT NULL,
+  payee_name text NOT NULL,
+  payee_tax_id text,
+  bank_name text,
+  bank_account_number text NOT NULL,
+  bank_account_type text,
+  net_payout numeric(12, 2) NOT NULL,
+  management_fees numeric(12, 2) NOT NULL DEFAULT 0,
+  reserve_amount numeric(12, 2) NOT NULL DEFAULT 0,
+  amount numeric(12, 2) NOT NULL CHECK (amount > 0),
+  status text NOT NULL DEFAULT 'scheduled'
+    CHECK (status IN ('scheduled', 'sent', 'confirmed', 'failed')),
+  failure_reason text,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_owner_payout_items_batch
+  ON owner_payout_items(batch_id, transfer_sequence);
+-- A statement is in at most one batch that has not failed.
+CREATE UNIQUE INDEX uq_owner_payout_items_statement_active
+  ON owner_payout_items(owner_statement_id)
+  WHERE status <> 'failed';
+
+-- Money an owner is asked to pay in (a capital call), usually to cover a
+-- statement deficit. Paid contributions are credited to the reserve.
+CREATE TABLE owner_contributions (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  property_id uuid NOT NULL REFERENCES properties(id) ON DELETE CASCADE,
+  owner_statement_id uuid REFERENCES owner_statements(id) ON DELETE SET NULL,
+  reference_code text NOT NULL UNIQUE DEFAULT encode(gen_random_bytes(8), 'hex'),
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
+  amount numeric(12, 2) NOT NULL CHECK (amount > 0),
+  reason text,
+  due_date date,
+  status text NOT NULL DEFAULT 'requested'
+    CHECK (status IN ('requested', 'paid', 'cancelled')),
+  provider text CHECK (provider IS NULL OR provider IN ('stripe', 'mercado_pago')),
+  checkout_url text,
+  provider_session_id text,
+  paid_amount numeric(12, 2),
+  paid_at timestamptz,
+  payment_reference text,
+  cancelled_at timestamptz,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_owner_contributions_org_status
+  ON owner_contributions(organization_id, status, due_date);
+
+-- Movements of a property's owner reserve. The balance is the sum of
+-- amounts: statement top-ups and contributions add, draws subtract.
+CREATE TABLE owner_reserve_entries (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  property_id uuid NOT NULL REFERENCES properties(id) ON DELETE CASCADE,
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
+  entry_type text NOT NULL
+    CHECK (entry_type IN ('top_up', 'draw', 'contribution', 'adjustment')),
+  amount numeric(12, 2) NOT NULL CHECK (amount <> 0),
+  owner_statement_id uuid REFERENCES owner_statements(id) ON DELETE CASCADE,
+  owner_contribution_id uuid REFERENCES owner_contributions(id) ON DELETE SET NULL,
+  notes text,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_owner_reserve_entries_property
+  ON owner_reserve_entries(property_id, currency, created_at);
+CREATE UNIQUE INDEX uq_owner_reserve_entries_statement
+  ON owner_reserve_entries(owner_statement_id, entry_type)
+  WHERE owner_statement_id IS NOT NULL;
+CREATE UNIQUE INDEX uq_owner_reserve_entries_contribution
+  ON owner_reserve_entries(owner_contribution_id)
+  WHERE owner_contribution_id IS NOT NULL;
+
+-- Fee terms of an owner's management contract. A property schedule wins
+-- over the organization default (property_id NULL); see
+-- services/management_fees.rs for the rule types.
+CREATE TABLE management_fee_schedules (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  property_id uuid REFERENCES properties(id) ON DELETE CASCADE,
+  name text NOT NULL,
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
+  effective_from date NOT NULL,
+  effective_to date,
+  rules jsonb NOT NULL DEFAULT '[]'::jsonb CHECK (jsonb_typeof(rules) = 'array'),
+  is_active boolean NOT NULL DEFAULT true,
+  notes text,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  CHECK (effective_to IS NULL OR effective_to >= effective_from)
+);
+
+CREATE INDEX idx_management_fee_schedules_scope
+  ON management_fee_schedules(organization_id, property_id, effective_from DESC);
+
 -- ---------- Messaging ----------
 
 CREATE TABLE message_templates (
@@ -1609,6 +2362,71 @@ CREATE INDEX idx_maintenance_requests_org_status
 CREATE INDEX idx_maintenance_requests_lease ON maintenance_requests(lease_id);
 CREATE INDEX idx_maintenance_requests_property ON maintenance_requests(property_id);
 
+-- ---------- Lease security deposits ----------
+
+-- One deposit per long-term lease: received at move-in, held in trust with
+-- optional simple interest (annual rate), settled at move-out against
+-- itemized deductions. Inspection report ids are not foreign keys because
+-- inspection_reports is created by the vision AI migrations.
+CREATE TABLE lease_deposits (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  lease_id uuid NOT NULL UNIQUE REFERENCES leases(id) ON DELETE CASCADE,
+  property_id uuid REFERENCES properties(id) ON DELETE SET NULL,
+  currency char(3) NOT NULL DEFAULT 'PYG' CHECK (currency ~ '^[A-Z]{3}$'),
+  amount_required numeric(12, 2) NOT NULL DEFAULT 0 CHECK (amount_required >= 0),
+  amount_received numeric(12, 2) NOT NULL DEFAULT 0 CHECK (amount_received >= 0),
+  received_at timestamptz,
+  lease_payment_id uuid REFERENCES lease_payments(id) ON DELETE SET NULL,
+  receipt_reference text,
+  interest_rate numeric(7, 4) NOT NULL DEFAULT 0
+    CHECK (interest_rate >= 0 AND interest_rate <= 1),
+  interest_accrued numeric(12, 2) NOT NULL DEFAULT 0 CHECK (interest_accrued >= 0),
+  interest_accrued_through date,
+  status text NOT NULL DEFAULT 'pending'
+    CHECK (status IN ('pending', 'held', 'move_out', 'settled')),
+  move_out_inspection_id uuid,
+  baseline_inspection_id uuid,
+  inspection_comparison jsonb,
+  deductions_total numeric(12, 2) NOT NULL DEFAULT 0,
+  refund_amount numeric(12, 2) NOT NULL DEFAULT 0,
+  balance_due numeric(12, 2) NOT NULL DEFAULT 0,
+  refund_method text
+    CHECK (refund_method IS NULL OR refund_method IN ('bank_transfer', 'stripe', 'mercado_pago')),
+  refund_reference text,
+  settled_at timestamptz,
+  letter_pdf_url text,
+  notes text,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_lease_deposits_org_status
+  ON lease_deposits(organization_id, status, created_at DESC);
+
+CREATE TABLE lease_deposit_deductions (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  deposit_id uuid NOT NULL REFERENCES lease_deposits(id) ON DELETE CASCADE,
+  category text NOT NULL
+    CHECK (category IN (
+      'damage', 'cleaning', 'unpaid_rent', 'unpaid_utilities', 'missing_items', 'other'
+    )),
+  description text NOT NULL,
+  amount numeric(12, 2) NOT NULL CHECK (amount > 0),
+  room text,
+  maintenance_request_id uuid REFERENCES maintenance_requests(id) ON DELETE SET NULL,
+  inspection_report_id uuid,
+  photo_urls jsonb NOT NULL DEFAULT '[]'::jsonb,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_lease_deposit_deductions_deposit
+  ON lease_deposit_deductions(deposit_id, created_at);
+
 -- ---------- Payment instructions ----------
 
 CREATE TABLE payment_instructions (
@@ -2072,6 +2890,56 @@ CREATE POLICY ml_models_org_member_all ON ml_models FOR ALL
   USING (is_org_member(organization_id))
   WITH CHECK (is_org_member(organization_id));
 
+-- ---------- Background scheduler ----------
+
+CREATE TABLE scheduler_leases (
+  job_name             text PRIMARY KEY,
+  holder_id            text NOT NULL,
+  acquired_at          timestamptz NOT NULL DEFAULT now(),
+  heartbeat_at         timestamptz NOT NULL DEFAULT now(),
+  expires_at           timestamptz NOT NULL,
+  last_run_key         text,
+  last_run_started_at  timestamptz,
+  last_run_finished_at timestamptz,
+  last_run_status      text
+                         CHECK (last_run_status IS NULL OR last_run_status IN ('running', 'succeeded', 'failed')),
+  updated_at           timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_scheduler_leases_holder
+  ON scheduler_leases(holder_id, expires_at DESC);
+
+CREATE TRIGGER trg_scheduler_leases_updated_at
+  BEFORE UPDATE ON scheduler_leases
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TABLE scheduler_runs (
+  id                    uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  job_name              text NOT NULL,
+  organization_id       uuid REFERENCES organizations(id) ON DELETE CASCADE,
+  trigger_source        text NOT NULL DEFAULT 'schedule'
+                          CHECK (trigger_source IN ('schedule', 'manual')),
+  requested_by_user_id  uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  instance_id           text,
+  status                text NOT NULL DEFAULT 'running'
+                          CHECK (status IN ('running', 'succeeded', 'failed')),
+  result                jsonb NOT NULL DEFAULT '{}'::jsonb,
+  error                 text,
+  started_at            timestamptz NOT NULL DEFAULT now(),
+  finished_at           timestamptz,
+  duration_ms           integer
+);
+
+CREATE INDEX idx_scheduler_runs_job_started
+  ON scheduler_runs(job_name, started_at DESC);
+
+CREATE INDEX idx_scheduler_runs_status_started
+  ON scheduler_runs(status, started_at DESC);
+
+CREATE INDEX idx_scheduler_runs_org_started
+  ON scheduler_runs(organization_id, started_at DESC)
+  WHERE organization_id IS NOT NULL;
+
 -- ---------- SaaS subscriptions ----------
 
 CREATE TYPE subscription_status AS ENUM ('trialing', 'active', 'past_due', 'cancelled');
@@ -2149,6 +3017,118 @@ CREATE UNIQUE INDEX idx_integration_events_provider_external
   WHERE external_event_id IS NOT NULL;
 
 CREATE INDEX idx_integration_events_status ON integration_events(status, received_at);
+CREATE INDEX idx_integration_events_org_type
+  ON integration_events(organization_id, event_type, received_at DESC);
+
+-- Per-organization secrets for signed calls to /integrations/webhooks/{provider}.
+-- After a rotation the previous secret keeps verifying until it expires.
+CREATE TABLE webhook_signing_secrets (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  provider text NOT NULL,
+  secret text NOT NULL,
+  previous_secret text,
+  previous_secret_expires_at timestamptz,
+  tolerance_seconds integer NOT NULL DEFAULT 300
+    CHECK (tolerance_seconds BETWEEN 30 AND 3600),
+  is_active boolean NOT NULL DEFAULT true,
+  last_used_at timestamptz,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (organization_id, provider)
+);
+
+-- Verified inbound webhook deliveries, for replay protection by event id.
+CREATE TABLE webhook_receipts (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  provider text NOT NULL,
+  event_id text NOT NULL,
+  organization_id uuid REFERENCES organizations(id) ON DELETE CASCADE,
+  received_at timestamptz NOT NULL DEFAULT now(),
+  UNIQUE (provider, event_id)
+);
+
+CREATE INDEX idx_webhook_receipts_received_at ON webhook_receipts(received_at);
+
+-- Outbound webhooks: external systems subscribed to an org's domain events.
+-- event_types holds trigger names, `prefix*` patterns or `*` (empty = all).
+CREATE TABLE webhook_subscriptions (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  url text NOT NULL,
+  description text,
+  event_types jsonb NOT NULL DEFAULT '[]'::jsonb,
+  secret text NOT NULL,
+  is_active boolean NOT NULL DEFAULT true,
+  consecutive_failures integer NOT NULL DEFAULT 0,
+  disabled_at timestamptz,
+  disabled_reason text,
+  last_delivery_at timestamptz,
+  last_success_at timestamptz,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_webhook_subscriptions_org_active
+  ON webhook_subscriptions(organization_id, is_active);
+
+CREATE TABLE webhook_deliveries (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  subscription_id uuid NOT NULL REFERENCES webhook_subscriptions(id) ON DELETE CASCADE,
+  event_id uuid NOT NULL,
+  event_type text NOT NULL,
+  payload jsonb NOT NULL,
+  status text NOT NULL DEFAULT 'pending'
+    CHECK (status IN ('pending', 'sending', 'succeeded', 'failed', 'cancelled')),
+  attempt_count integer NOT NULL DEFAULT 0,
+  next_attempt_at timestamptz NOT NULL DEFAULT now(),
+  last_attempt_at timestamptz,
+  response_status integer,
+  response_body text,
+  error_message text,
+  delivered_at timestamptz,
+  replay_of_delivery_id uuid REFERENCES webhook_deliveries(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_webhook_deliveries_due
+  ON webhook_deliveries(status, next_attempt_at);
+CREATE INDEX idx_webhook_deliveries_subscription
+  ON webhook_deliveries(subscription_id, created_at DESC);
+
+-- Organization API keys: scoped tokens for integrations. Each key acts as its
+-- own service user; only SHA-256 hashes of tokens are stored.
+CREATE TABLE organization_api_keys (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  organization_id uuid NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+  name text NOT NULL,
+  key_prefix text NOT NULL,
+  token_hash text NOT NULL UNIQUE,
+  previous_token_hash text,
+  previous_token_expires_at timestamptz,
+  scopes text[] NOT NULL
+    CHECK (
+      cardinality(scopes) > 0
+      AND scopes <@ ARRAY['read_only', 'finance', 'operations', 'agents']::text[]
+    ),
+  service_user_id uuid NOT NULL UNIQUE REFERENCES app_users(id) ON DELETE CASCADE,
+  expires_at timestamptz,
+  last_used_at timestamptz,
+  revoked_at timestamptz,
+  created_by_user_id uuid REFERENCES app_users(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_organization_api_keys_org
+  ON organization_api_keys(organization_id, created_at DESC);
+CREATE INDEX idx_organization_api_keys_previous_token_hash
+  ON organization_api_keys(previous_token_hash)
+  WHERE previous_token_hash IS NOT NULL;
 
 CREATE TABLE audit_logs (
   id bigserial PRIMARY KEY,
@@ -2204,6 +3184,10 @@ CREATE TRIGGER trg_organization_members_updated_at
   BEFORE UPDATE ON organization_members
   FOR EACH ROW EXECUTE FUNCTION set_updated_at();
 
+CREATE TRIGGER trg_organization_roles_updated_at
+  BEFORE UPDATE ON organization_roles
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
 CREATE TRIGGER trg_organization_invites_updated_at
   BEFORE UPDATE ON organization_invites
   FOR EACH ROW EXECUTE FUNCTION set_updated_at();
@@ -2212,6 +3196,10 @@ CREATE TRIGGER trg_properties_updated_at
   BEFORE UPDATE ON properties
   FOR EACH ROW EXECUTE FUNCTION set_updated_at();
 
+CREATE TRIGGER trg_property_groups_updated_at
+  BEFORE UPDATE ON property_groups
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
 CREATE TRIGGER trg_units_updated_at
   BEFORE UPDATE ON units
   FOR EACH ROW EXECUTE FUNCTION set_updated_at();
@@ -2284,6 +3272,10 @@ CREATE TRIGGER trg_leases_updated_at
   BEFORE UPDATE ON leases
   FOR EACH ROW EXECUTE FUNCTION set_updated_at();
 
+CREATE TRIGGER trg_late_fee_policies_updated_at
+  BEFORE UPDATE ON late_fee_policies
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
 CREATE TRIGGER trg_lease_charges_updated_at
   BEFORE UPDATE ON lease_charges
   FOR EACH ROW EXECUTE FUNCTION set_updated_at();
@@ -2292,6 +3284,54 @@ CREATE TRIGGER trg_collection_records_updated_at
   BEFORE UPDATE ON collection_records
   FOR EACH ROW EXECUTE FUNCTION set_updated_at();
 
+CREATE TRIGGER trg_lease_payments_updated_at
+  BEFORE UPDATE ON lease_payments
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER trg_lease_deposits_updated_at
+  BEFORE UPDATE ON lease_deposits
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER trg_lease_deposit_deductions_updated_at
+  BEFORE UPDATE ON lease_deposit_deductions
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER trg_gl_accounts_updated_at
+  BEFORE UPDATE ON gl_accounts
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER trg_sifen_settings_updated_at
+  BEFORE UPDATE ON sifen_settings
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER trg_sifen_certificates_updated_at
+  BEFORE UPDATE ON sifen_certificates
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER trg_electronic_documents_updated_at
+  BEFORE UPDATE ON electronic_documents
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER trg_payout_bank_layouts_updated_at
+  BEFORE UPDATE ON payout_bank_layouts
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER trg_owner_payout_batches_updated_at
+  BEFORE UPDATE ON owner_payout_batches
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER trg_owner_payout_items_updated_at
+  BEFORE UPDATE ON owner_payout_items
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER trg_owner_contributions_updated_at
+  BEFORE UPDATE ON owner_contributions
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER trg_management_fee_schedules_updated_at
+  BEFORE UPDATE ON management_fee_schedules
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
 CREATE TRIGGER trg_message_templates_updated_at
   BEFORE UPDATE ON message_templates
   FOR EACH ROW EXECUTE FUNCTION set_updated_at();
@@ -2328,6 +3368,22 @@ CREATE TRIGGER trg_integration_events_updated_at
   BEFORE UPDATE ON integration_events
   FOR EACH ROW EXECUTE FUNCTION set_updated_at();
 
+CREATE TRIGGER trg_webhook_signing_secrets_updated_at
+  BEFORE UPDATE ON webhook_signing_secrets
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER trg_webhook_subscriptions_updated_at
+  BEFORE UPDATE ON webhook_subscriptions
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER trg_webhook_deliveries_updated_at
+  BEFORE UPDATE ON webhook_deliveries
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER trg_organization_api_keys_updated_at
+  BEFORE UPDATE ON organization_api_keys
+  FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
 -- ---------- Optional RLS policies (Supabase-friendly) ----------
 -- If you are on Neon and enforcing tenancy in application code, keep RLS disabled
 -- or adapt auth_user_id() to your session variable model.
@@ -2390,6 +3446,9 @@ CREATE POLICY organization_invites_owner_admin_all
 
 -- Uniform member policies for organization-scoped tables.
 ALTER TABLE properties ENABLE ROW LEVEL SECURITY;
+ALTER TABLE property_groups ENABLE ROW LEVEL SECURITY;
+ALTER TABLE property_group_members ENABLE ROW LEVEL SECURITY;
+ALTER TABLE organization_roles ENABLE ROW LEVEL SECURITY;
 ALTER TABLE units ENABLE ROW LEVEL SECURITY;
 ALTER TABLE property_floors ENABLE ROW LEVEL SECURITY;
 ALTER TABLE unit_spaces ENABLE ROW LEVEL SECURITY;
@@ -2409,8 +3468,30 @@ ALTER TABLE listing_fee_lines ENABLE ROW LEVEL SECURITY;
 ALTER TABLE application_submissions ENABLE ROW LEVEL SECURITY;
 ALTER TABLE application_events ENABLE ROW LEVEL SECURITY;
 ALTER TABLE leases ENABLE ROW LEVEL SECURITY;
+ALTER TABLE late_fee_policies ENABLE ROW LEVEL SECURITY;
 ALTER TABLE lease_charges ENABLE ROW LEVEL SECURITY;
 ALTER TABLE collection_records ENABLE ROW LEVEL SECURITY;
+ALTER TABLE lease_payments ENABLE ROW LEVEL SECURITY;
+ALTER TABLE payment_allocations ENABLE ROW LEVEL SECURITY;
+ALTER TABLE lease_deposits ENABLE ROW LEVEL SECURITY;
+ALTER TABLE lease_deposit_deductions ENABLE ROW LEVEL SECURITY;
+ALTER TABLE webhook_signing_secrets ENABLE ROW LEVEL SECURITY;
+ALTER TABLE webhook_receipts ENABLE ROW LEVEL SECURITY;
+ALTER TABLE webhook_subscriptions ENABLE ROW LEVEL SECURITY;
+ALTER TABLE webhook_deliveries ENABLE ROW LEVEL SECURITY;
+ALTER TABLE organization_api_keys ENABLE ROW LEVEL SECURITY;
+ALTER TABLE gl_accounts ENABLE ROW LEVEL SECURITY;
+ALTER TABLE gl_journal_entries ENABLE ROW LEVEL SECURITY;
+ALTER TABLE gl_journal_lines ENABLE ROW LEVEL SECURITY;
+ALTER TABLE sifen_settings ENABLE ROW LEVEL SECURITY;
+ALTER TABLE sifen_certificates ENABLE ROW LEVEL SECURITY;
+ALTER TABLE electronic_documents ENABLE ROW LEVEL SECURITY;
+ALTER TABLE payout_bank_layouts ENABLE ROW LEVEL SECURITY;
+ALTER TABLE owner_payout_batches ENABLE ROW LEVEL SECURITY;
+ALTER TABLE owner_payout_items ENABLE ROW LEVEL SECURITY;
+ALTER TABLE owner_contributions ENABLE ROW LEVEL SECURITY;
+ALTER TABLE owner_reserve_entries ENABLE ROW LEVEL SECURITY;
+ALTER TABLE management_fee_schedules ENABLE ROW LEVEL SECURITY;
 ALTER TABLE message_templates ENABLE ROW LEVEL SECURITY;
 ALTER TABLE message_logs ENABLE ROW LEVEL SECURITY;
 ALTER TABLE communication_sequences ENABLE ROW LEVEL SECURITY;
@@ -2443,6 +3524,21 @@ CREATE POLICY properties_org_member_all
   USING (is_org_member(organization_id))
   WITH CHECK (is_org_member(organization_id));
 
+CREATE POLICY property_groups_org_member_all
+  ON property_groups FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY property_group_members_org_member_all
+  ON property_group_members FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY organization_roles_org_member_all
+  ON organization_roles FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
 CREATE POLICY units_org_member_all
   ON units FOR ALL
   USING (is_org_member(organization_id))
@@ -2538,6 +3634,11 @@ CREATE POLICY leases_org_member_all
   USING (is_org_member(organization_id))
   WITH CHECK (is_org_member(organization_id));
 
+CREATE POLICY late_fee_policies_org_member_all
+  ON late_fee_policies FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
 CREATE POLICY lease_charges_org_member_all
   ON lease_charges FOR ALL
   USING (is_org_member(organization_id))
@@ -2548,6 +3649,106 @@ CREATE POLICY collection_records_org_member_all
   USING (is_org_member(organization_id))
   WITH CHECK (is_org_member(organization_id));
 
+CREATE POLICY lease_payments_org_member_all
+  ON lease_payments FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY lease_deposits_org_member_all
+  ON lease_deposits FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY lease_deposit_deductions_org_member_all
+  ON lease_deposit_deductions FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY webhook_signing_secrets_org_member_all
+  ON webhook_signing_secrets FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY webhook_receipts_org_member_all
+  ON webhook_receipts FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY webhook_subscriptions_org_member_all
+  ON webhook_subscriptions FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY webhook_deliveries_org_member_all
+  ON webhook_deliveries FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY organization_api_keys_org_member_all
+  ON organization_api_keys FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY payment_allocations_org_member_all
+  ON payment_allocations FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY gl_accounts_org_member_all
+  ON gl_accounts FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY gl_journal_entries_org_member_all
+  ON gl_journal_entries FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY gl_journal_lines_org_member_all
+  ON gl_journal_lines FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY sifen_settings_org_member_all
+  ON sifen_settings FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY electronic_documents_org_member_all
+  ON electronic_documents FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY payout_bank_layouts_org_member_all
+  ON payout_bank_layouts FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY owner_payout_batches_org_member_all
+  ON owner_payout_batches FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY owner_payout_items_org_member_all
+  ON owner_payout_items FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY owner_contributions_org_member_all
+  ON owner_contributions FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY owner_reserve_entries_org_member_all
+  ON owner_reserve_entries FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
+CREATE POLICY management_fee_schedules_org_member_all
+  ON management_fee_schedules FOR ALL
+  USING (is_org_member(organization_id))
+  WITH CHECK (is_org_member(organization_id));
+
 CREATE POLICY message_templates_org_member_all
   ON message_templates FOR ALL
   USING (is_org_member(organization_id))